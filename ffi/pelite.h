@@ -0,0 +1,74 @@
+/* PeLite C bindings, see readme.md */
+
+#ifndef PELITE_H
+#define PELITE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PeliteFile PeliteFile;
+
+typedef struct PeliteSection {
+	char name[9];
+	uint32_t virtual_address;
+	uint32_t virtual_size;
+	uint32_t pointer_to_raw_data;
+	uint32_t size_of_raw_data;
+	uint32_t characteristics;
+} PeliteSection;
+
+typedef struct PeliteExport {
+	/* NULL if exported by ordinal only */
+	const char *name;
+	uint16_t ordinal;
+	/* Zero if forwarded */
+	uint32_t rva;
+	/* NULL unless forwarded */
+	const char *forward;
+} PeliteExport;
+
+typedef struct PeliteImport {
+	const char *dll_name;
+	/* NULL if imported by ordinal */
+	const char *name;
+	uint16_t hint;
+	uint16_t ordinal;
+	uint32_t iat_rva;
+} PeliteImport;
+
+typedef struct PeliteResource {
+	/* Path of the resource, eg. "/#16/#1/#1033" */
+	const char *path;
+	const uint8_t *data;
+	size_t len;
+} PeliteResource;
+
+const char *pelite_last_error(void);
+
+PeliteFile *pelite_open(const char *path);
+PeliteFile *pelite_from_bytes(const uint8_t *data, size_t len);
+void pelite_close(PeliteFile *file);
+/* -1 if file is NULL */
+int pelite_is_64bit(const PeliteFile *file);
+
+PeliteSection *pelite_sections(const PeliteFile *file, size_t *len);
+void pelite_sections_free(PeliteSection *sections, size_t len);
+
+PeliteExport *pelite_exports(const PeliteFile *file, size_t *len);
+void pelite_exports_free(PeliteExport *exports, size_t len);
+
+PeliteImport *pelite_imports(const PeliteFile *file, size_t *len);
+void pelite_imports_free(PeliteImport *imports, size_t len);
+
+PeliteResource *pelite_resources(const PeliteFile *file, size_t *len);
+void pelite_resources_free(PeliteResource *resources, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
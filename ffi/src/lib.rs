@@ -0,0 +1,451 @@
+/*!
+C bindings for pelite.
+
+See [`pelite.h`](../pelite.h) for the declarations and the readme for the conventions.
+*/
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::{fmt, ptr, slice};
+
+use pelite::resources::{Directory, Entry};
+use pelite::{Error, Wrap};
+
+//----------------------------------------------------------------
+
+thread_local! {
+	static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+fn set_error<E: fmt::Display>(err: E) {
+	let msg = CString::new(err.to_string()).unwrap_or_default();
+	LAST_ERROR.with(|last_error| *last_error.borrow_mut() = msg);
+}
+
+/// Returns the last error on the calling thread.
+///
+/// The string is valid until the next failing call on the calling thread.
+#[no_mangle]
+pub extern "C" fn pelite_last_error() -> *const c_char {
+	LAST_ERROR.with(|last_error| last_error.borrow().as_ptr())
+}
+
+// Copies the bytes into an owned C string, pelite strings never contain nul bytes
+fn c_string<S: AsRef<[u8]>>(s: S) -> *const c_char {
+	CString::new(s.as_ref()).unwrap_or_default().into_raw()
+}
+unsafe fn free_c_string(s: *const c_char) {
+	if !s.is_null() {
+		drop(CString::from_raw(s as *mut c_char));
+	}
+}
+
+// Hands out an array with its length
+unsafe fn into_array<T>(vec: Vec<T>, len: *mut usize) -> *mut T {
+	let boxed = vec.into_boxed_slice();
+	if !len.is_null() {
+		*len = boxed.len();
+	}
+	Box::into_raw(boxed) as *mut T
+}
+unsafe fn from_array<T>(array: *mut T, len: usize) -> Option<Box<[T]>> {
+	if array.is_null() {
+		return None;
+	}
+	Some(Box::from_raw(slice::from_raw_parts_mut(array, len)))
+}
+
+//----------------------------------------------------------------
+
+/// Parsed PE file.
+pub struct PeliteFile {
+	image: Box<[u8]>,
+}
+impl PeliteFile {
+	fn new(image: Box<[u8]>) -> *mut PeliteFile {
+		match pelite::PeFile::from_bytes(&image) {
+			Ok(_) => Box::into_raw(Box::new(PeliteFile { image })),
+			Err(err) => {
+				set_error(err);
+				ptr::null_mut()
+			},
+		}
+	}
+	fn pe(&self) -> pelite::PeFile<'_> {
+		// Checked by the constructor
+		pelite::PeFile::from_bytes(&self.image).unwrap()
+	}
+}
+
+/// Reads and parses the file at the given path.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_open(path: *const c_char) -> *mut PeliteFile {
+	if path.is_null() {
+		set_error(Error::Null);
+		return ptr::null_mut();
+	}
+	let path = match CStr::from_ptr(path).to_str() {
+		Ok(path) => path,
+		Err(err) => {
+			set_error(err);
+			return ptr::null_mut();
+		},
+	};
+	match std::fs::read(path) {
+		Ok(image) => PeliteFile::new(image.into_boxed_slice()),
+		Err(err) => {
+			set_error(err);
+			ptr::null_mut()
+		},
+	}
+}
+
+/// Parses a copy of the given bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_from_bytes(data: *const u8, len: usize) -> *mut PeliteFile {
+	if data.is_null() {
+		set_error(Error::Null);
+		return ptr::null_mut();
+	}
+	PeliteFile::new(slice::from_raw_parts(data, len).into())
+}
+
+/// Closes the file.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_close(file: *mut PeliteFile) {
+	if !file.is_null() {
+		drop(Box::from_raw(file));
+	}
+}
+
+// Null files are rejected like the null arguments of the constructors
+macro_rules! try_file {
+	($file:expr, $fail:expr) => {
+		match $file.as_ref() {
+			Some(file) => file,
+			None => {
+				set_error(Error::Null);
+				return $fail;
+			},
+		}
+	};
+}
+
+/// Returns nonzero if the file is a PE32+ image, -1 if the file is null.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_is_64bit(file: *const PeliteFile) -> c_int {
+	let file = try_file!(file, -1);
+	match file.pe() {
+		Wrap::T32(_) => 0,
+		Wrap::T64(_) => 1,
+	}
+}
+
+// Directories which are not present are returned as empty arrays
+macro_rules! try_dir {
+	($e:expr, $len:expr) => {
+		match $e {
+			Ok(value) => value,
+			Err(Error::Null) => return into_array(Vec::new(), $len),
+			Err(err) => {
+				set_error(err);
+				return ptr::null_mut();
+			},
+		}
+	};
+}
+
+//----------------------------------------------------------------
+
+/// Section header.
+#[repr(C)]
+pub struct PeliteSection {
+	pub name: [u8; 9],
+	pub virtual_address: u32,
+	pub virtual_size: u32,
+	pub pointer_to_raw_data: u32,
+	pub size_of_raw_data: u32,
+	pub characteristics: u32,
+}
+
+/// Gets the section headers.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_sections(file: *const PeliteFile, len: *mut usize) -> *mut PeliteSection {
+	let file = try_file!(file, ptr::null_mut());
+	let sections = file.pe().section_headers().image().iter().map(|section| {
+		let mut name = [0u8; 9];
+		name[..8].copy_from_slice(&section.Name);
+		PeliteSection {
+			name,
+			virtual_address: section.VirtualAddress,
+			virtual_size: section.VirtualSize,
+			pointer_to_raw_data: section.PointerToRawData,
+			size_of_raw_data: section.SizeOfRawData,
+			characteristics: section.Characteristics,
+		}
+	}).collect();
+	into_array(sections, len)
+}
+
+/// Frees the section headers.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_sections_free(sections: *mut PeliteSection, len: usize) {
+	drop(from_array(sections, len));
+}
+
+//----------------------------------------------------------------
+
+/// Exported function.
+#[repr(C)]
+pub struct PeliteExport {
+	pub name: *const c_char,
+	pub ordinal: u16,
+	pub rva: u32,
+	pub forward: *const c_char,
+}
+
+/// Gets the exported functions in ordinal order.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_exports(file: *const PeliteFile, len: *mut usize) -> *mut PeliteExport {
+	let pe = try_file!(file, ptr::null_mut()).pe();
+	let exports = try_dir!(pe.exports(), len);
+	let by = try_dir!(exports.by(), len);
+	let mut names = vec![None; by.functions().len()];
+	for (name, index) in by.iter_name_indices() {
+		if let (Ok(name), Some(slot)) = (name, names.get_mut(index)) {
+			*slot = Some(name);
+		}
+	}
+	let mut result = Vec::with_capacity(names.len());
+	for (index, export) in by.iter().enumerate() {
+		let (rva, forward) = match export {
+			Ok(pelite::pe64::exports::Export::Symbol(&rva)) => (rva, ptr::null()),
+			Ok(pelite::pe64::exports::Export::Forward(forward)) => (0, c_string(forward)),
+			// Unused slots in the functions table
+			_ => continue,
+		};
+		result.push(PeliteExport {
+			name: names[index].map_or(ptr::null(), c_string),
+			ordinal: by.ordinal_base().wrapping_add(index as u16),
+			rva,
+			forward,
+		});
+	}
+	into_array(result, len)
+}
+
+/// Frees the exported functions.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_exports_free(exports: *mut PeliteExport, len: usize) {
+	for export in from_array(exports, len).iter().flat_map(|exports| exports.iter()) {
+		free_c_string(export.name);
+		free_c_string(export.forward);
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Imported function.
+#[repr(C)]
+pub struct PeliteImport {
+	pub dll_name: *const c_char,
+	pub name: *const c_char,
+	pub hint: u16,
+	pub ordinal: u16,
+	pub iat_rva: u32,
+}
+
+/// Gets the imported functions.
+///
+/// Descriptors whose dll name or import name table is corrupt are skipped.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_imports(file: *const PeliteFile, len: *mut usize) -> *mut PeliteImport {
+	let pe = try_file!(file, ptr::null_mut()).pe();
+	let thunk_size = match pe {
+		Wrap::T32(_) => 4,
+		Wrap::T64(_) => 8,
+	};
+	let imports = try_dir!(pe.imports(), len);
+	let mut result = Vec::new();
+	for desc in imports.iter() {
+		let (dll_name, int) = match (desc.dll_name(), desc.int()) {
+			(Ok(dll_name), Ok(int)) => (dll_name, int),
+			_ => continue,
+		};
+		for (index, import) in int.enumerate() {
+			let (name, hint, ordinal) = match import {
+				Ok(pelite::pe64::imports::Import::ByName { hint, name }) => (c_string(name), hint as u16, 0),
+				Ok(pelite::pe64::imports::Import::ByOrdinal { ord }) => (ptr::null(), 0, ord),
+				Err(_) => continue,
+			};
+			result.push(PeliteImport {
+				dll_name: c_string(dll_name),
+				name,
+				hint,
+				ordinal,
+				iat_rva: desc.image().FirstThunk.wrapping_add((index * thunk_size) as u32),
+			});
+		}
+	}
+	into_array(result, len)
+}
+
+/// Frees the imported functions.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_imports_free(imports: *mut PeliteImport, len: usize) {
+	for import in from_array(imports, len).iter().flat_map(|imports| imports.iter()) {
+		free_c_string(import.dll_name);
+		free_c_string(import.name);
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Resource data.
+#[repr(C)]
+pub struct PeliteResource {
+	pub path: *const c_char,
+	pub data: *const u8,
+	pub len: usize,
+}
+
+fn walk_resources(dir: Directory<'_>, path: &mut String, result: &mut Vec<PeliteResource>, budget: &mut u32) {
+	for entry in dir.entries() {
+		// Corrupt resources may reference the same directory many times
+		if *budget == 0 {
+			return;
+		}
+		*budget -= 1;
+		let name = match entry.name() {
+			Ok(name) => name,
+			Err(_) => continue,
+		};
+		let len = path.len();
+		path.push('/');
+		path.push_str(&name.to_string());
+		match entry.entry() {
+			Ok(Entry::Directory(dir)) => walk_resources(dir, path, result, budget),
+			Ok(Entry::DataEntry(data)) => {
+				if let Ok(bytes) = data.bytes() {
+					result.push(PeliteResource { path: c_string(&path), data: bytes.as_ptr(), len: bytes.len() });
+				}
+			},
+			Err(_) => (),
+		}
+		path.truncate(len);
+	}
+}
+
+/// Gets the resource data entries, the data points into the file.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_resources(file: *const PeliteFile, len: *mut usize) -> *mut PeliteResource {
+	let resources = try_dir!(try_file!(file, ptr::null_mut()).pe().resources(), len);
+	let root = try_dir!(resources.root(), len);
+	let mut result = Vec::new();
+	walk_resources(root, &mut String::new(), &mut result, &mut 0x10000);
+	into_array(result, len)
+}
+
+/// Frees the resource data entries.
+#[no_mangle]
+pub unsafe extern "C" fn pelite_resources_free(resources: *mut PeliteResource, len: usize) {
+	for resource in from_array(resources, len).iter().flat_map(|resources| resources.iter()) {
+		free_c_string(resource.path);
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn header() {
+		// Every exported function must be declared in the header
+		let header = include_str!("../pelite.h");
+		let source = include_str!("lib.rs");
+		let mut count = 0;
+		for line in source.lines().filter(|line| line.starts_with("pub ") && line.contains("extern \"C\" fn ")) {
+			let name = line.split("fn ").nth(1).unwrap().split('(').next().unwrap();
+			assert!(header.contains(&format!(" {}(", name)) || header.contains(&format!("*{}(", name)), "{} is not declared in pelite.h", name);
+			count += 1;
+		}
+		assert_eq!(header.matches(");").count(), count);
+	}
+
+	#[test]
+	fn demo() {
+		unsafe {
+			assert!(pelite_open(b"does not exist\0".as_ptr() as *const c_char).is_null());
+			assert!(!CStr::from_ptr(pelite_last_error()).to_bytes().is_empty());
+
+			let file = pelite_open(b"../demo/Demo64.dll\0".as_ptr() as *const c_char);
+			assert!(!file.is_null());
+			assert_eq!(pelite_is_64bit(file), 1);
+
+			let mut len = 0;
+			let sections = pelite_sections(file, &mut len);
+			assert_eq!(&slice::from_raw_parts(sections, len)[0].name[..], b".text\0\0\0\0");
+			pelite_sections_free(sections, len);
+
+			let exports = pelite_exports(file, &mut len);
+			let names: Vec<_> = slice::from_raw_parts(exports, len).iter().filter(|export| !export.name.is_null()).collect();
+			assert!(!names.is_empty());
+			pelite_exports_free(exports, len);
+
+			let imports = pelite_imports(file, &mut len);
+			assert!(slice::from_raw_parts(imports, len).iter().any(|import| CStr::from_ptr(import.dll_name).to_bytes().eq_ignore_ascii_case(b"kernel32.dll")));
+			pelite_imports_free(imports, len);
+
+			let resources = pelite_resources(file, &mut len);
+			assert!(!resources.is_null());
+			pelite_resources_free(resources, len);
+
+			pelite_close(file);
+		}
+	}
+
+	#[test]
+	fn null_file() {
+		unsafe {
+			let mut len = 0;
+			assert_eq!(pelite_is_64bit(ptr::null()), -1);
+			assert!(pelite_sections(ptr::null(), &mut len).is_null());
+			assert!(pelite_exports(ptr::null(), &mut len).is_null());
+			assert!(pelite_imports(ptr::null(), &mut len).is_null());
+			assert!(pelite_resources(ptr::null(), &mut len).is_null());
+		}
+	}
+
+	#[test]
+	fn corrupt_import_descriptor() {
+		use pelite::pe64::Pe;
+
+		let mut image = std::fs::read("../demo/Demo64.dll").unwrap();
+		// Point the name of the first import descriptor out of bounds
+		let (offset, dll_name) = {
+			let file = pelite::pe64::PeFile::from_bytes(&image).unwrap();
+			let imports = file.imports().unwrap();
+			let desc = imports.iter().next().unwrap();
+			let offset = desc.image() as *const _ as usize - image.as_ptr() as usize;
+			(offset, CString::new(desc.dll_name().unwrap().as_ref()).unwrap())
+		};
+		image[offset + 12..offset + 16].copy_from_slice(&0xfffffff0u32.to_le_bytes());
+
+		unsafe {
+			let file = pelite_from_bytes(image.as_ptr(), image.len());
+			assert!(!file.is_null());
+			let mut len = 0;
+			let imports = pelite_imports(file, &mut len);
+			assert!(!imports.is_null());
+			let dll_names: Vec<_> = slice::from_raw_parts(imports, len).iter().map(|import| CStr::from_ptr(import.dll_name)).collect();
+			assert!(!dll_names.is_empty());
+			assert!(!dll_names.contains(&dll_name.as_c_str()));
+			pelite_imports_free(imports, len);
+			pelite_close(file);
+		}
+	}
+}
@@ -0,0 +1,391 @@
+/*!
+Python bindings for pelite.
+
+See the readme for an example.
+*/
+
+// Names follow pefile
+#![allow(non_snake_case)]
+
+use std::borrow::Cow;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+use pelite::pattern as pat;
+use pelite::resources::{Directory, Entry};
+use pelite::{pe32, pe64, Error, Wrap};
+
+create_exception!(pelite, PEFormatError, PyException);
+
+fn format_error<E: ToString>(err: E) -> PyErr {
+	PEFormatError::new_err(err.to_string())
+}
+
+// Directories which are not present are returned as None
+fn optional<T>(result: pelite::Result<T>) -> PyResult<Option<T>> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(Error::Null) => Ok(None),
+		Err(err) => Err(format_error(err)),
+	}
+}
+
+// Parses the image as a file or as a view, the image was validated by the constructor
+macro_rules! with_pe {
+	($self:expr, |$pe:ident| $e:expr) => {
+		if $self.mapped {
+			let $pe = pelite::PeView::from_bytes(&$self.image).unwrap();
+			$e
+		}
+		else {
+			let $pe = pelite::PeFile::from_bytes(&$self.image).unwrap();
+			$e
+		}
+	};
+}
+
+//----------------------------------------------------------------
+
+/// Section header.
+#[pyclass(module = "pelite", frozen)]
+pub struct SectionStructure {
+	name: Vec<u8>,
+	#[pyo3(get)]
+	VirtualAddress: u32,
+	#[pyo3(get)]
+	Misc_VirtualSize: u32,
+	#[pyo3(get)]
+	SizeOfRawData: u32,
+	#[pyo3(get)]
+	PointerToRawData: u32,
+	#[pyo3(get)]
+	Characteristics: u32,
+}
+#[pymethods]
+impl SectionStructure {
+	/// Name of the section, padded with nul bytes.
+	#[getter]
+	fn Name(&self) -> Cow<'_, [u8]> {
+		Cow::Borrowed(&self.name)
+	}
+}
+
+/// Exported symbol.
+#[pyclass(module = "pelite", frozen)]
+pub struct ExportData {
+	name: Option<Vec<u8>>,
+	#[pyo3(get)]
+	ordinal: u16,
+	#[pyo3(get)]
+	address: Option<u32>,
+	forwarder: Option<Vec<u8>>,
+}
+#[pymethods]
+impl ExportData {
+	/// Name of the export, None if exported by ordinal only.
+	#[getter]
+	fn name(&self) -> Option<Cow<'_, [u8]>> {
+		self.name.as_deref().map(Cow::Borrowed)
+	}
+	/// Forwarded name as `DllName.ExportName`, None if not forwarded.
+	#[getter]
+	fn forwarder(&self) -> Option<Cow<'_, [u8]>> {
+		self.forwarder.as_deref().map(Cow::Borrowed)
+	}
+}
+
+/// Export directory.
+#[pyclass(module = "pelite", frozen)]
+pub struct ExportDirData {
+	name: Option<Vec<u8>>,
+	#[pyo3(get)]
+	base: u16,
+	#[pyo3(get)]
+	symbols: Vec<Py<ExportData>>,
+}
+#[pymethods]
+impl ExportDirData {
+	/// Name of the dll.
+	#[getter]
+	fn name(&self) -> Option<Cow<'_, [u8]>> {
+		self.name.as_deref().map(Cow::Borrowed)
+	}
+}
+
+/// Imported symbol.
+#[pyclass(module = "pelite", frozen)]
+pub struct ImportData {
+	name: Option<Vec<u8>>,
+	#[pyo3(get)]
+	hint: Option<usize>,
+	#[pyo3(get)]
+	ordinal: Option<u16>,
+	#[pyo3(get)]
+	address: u32,
+}
+#[pymethods]
+impl ImportData {
+	/// Name of the import, None if imported by ordinal.
+	#[getter]
+	fn name(&self) -> Option<Cow<'_, [u8]>> {
+		self.name.as_deref().map(Cow::Borrowed)
+	}
+}
+
+/// Import descriptor.
+#[pyclass(module = "pelite", frozen)]
+pub struct ImportDescData {
+	dll: Vec<u8>,
+	#[pyo3(get)]
+	imports: Vec<Py<ImportData>>,
+}
+#[pymethods]
+impl ImportDescData {
+	/// Name of the dll.
+	#[getter]
+	fn dll(&self) -> Cow<'_, [u8]> {
+		Cow::Borrowed(&self.dll)
+	}
+}
+
+//----------------------------------------------------------------
+
+fn exports<'a, P32: pe32::Pe<'a>, P64: pe64::Pe<'a>>(py: Python<'_>, pe: Wrap<P32, P64>) -> PyResult<Option<ExportDirData>> {
+	let exports = match optional(pe.exports())? {
+		Some(exports) => exports,
+		None => return Ok(None),
+	};
+	let by = exports.by().map_err(format_error)?;
+	let mut names = vec![None; by.functions().len()];
+	for (name, index) in by.iter_name_indices() {
+		if let (Ok(name), Some(slot)) = (name, names.get_mut(index)) {
+			*slot = Some(name.to_vec());
+		}
+	}
+	let mut symbols = Vec::new();
+	for (index, export) in by.iter().enumerate() {
+		// Skip unused slots in the functions table
+		let export = match export {
+			Ok(export) => export,
+			Err(_) => continue,
+		};
+		let data = ExportData {
+			name: names[index].take(),
+			ordinal: by.ordinal_base().wrapping_add(index as u16),
+			address: export.symbol(),
+			forwarder: export.forward().map(|forward| forward.to_vec()),
+		};
+		symbols.push(Py::new(py, data)?);
+	}
+	let name = exports.dll_name().ok().map(|name| name.to_vec());
+	Ok(Some(ExportDirData { name, base: by.ordinal_base(), symbols }))
+}
+
+fn imports<'a, P32: pe32::Pe<'a>, P64: pe64::Pe<'a>>(py: Python<'_>, pe: Wrap<P32, P64>) -> PyResult<Vec<ImportDescData>> {
+	let thunk_size = match pe {
+		Wrap::T32(_) => 4,
+		Wrap::T64(_) => 8,
+	};
+	let mut result = Vec::new();
+	let imports = match optional(pe.imports())? {
+		Some(imports) => imports,
+		None => return Ok(result),
+	};
+	for desc in imports.iter() {
+		let dll = desc.dll_name().map_err(format_error)?.to_vec();
+		let first_thunk = desc.image().FirstThunk;
+		let mut imports = Vec::new();
+		for (index, import) in desc.int().map_err(format_error)?.enumerate() {
+			let address = first_thunk.wrapping_add((index * thunk_size) as u32);
+			let data = match import.map_err(format_error)? {
+				pe64::imports::Import::ByName { hint, name } => ImportData { name: Some(name.to_vec()), hint: Some(hint), ordinal: None, address },
+				pe64::imports::Import::ByOrdinal { ord } => ImportData { name: None, hint: None, ordinal: Some(ord), address },
+			};
+			imports.push(Py::new(py, data)?);
+		}
+		result.push(ImportDescData { dll, imports });
+	}
+	Ok(result)
+}
+
+// Corrupt resources may reference the same directory many times, limit the number of visited entries
+fn walk_resources<'a>(dir: Directory<'a>, path: &mut String, result: &mut Vec<(String, &'a [u8])>, budget: &mut u32) {
+	for entry in dir.entries() {
+		if *budget == 0 {
+			return;
+		}
+		*budget -= 1;
+		let name = match entry.name() {
+			Ok(name) => name,
+			Err(_) => continue,
+		};
+		let len = path.len();
+		path.push('/');
+		path.push_str(&name.to_string());
+		match entry.entry() {
+			Ok(Entry::Directory(dir)) => walk_resources(dir, path, result, budget),
+			Ok(Entry::DataEntry(data)) => {
+				if let Ok(bytes) = data.bytes() {
+					result.push((path.clone(), bytes));
+				}
+			},
+			Err(_) => (),
+		}
+		path.truncate(len);
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Portable Executable file.
+///
+/// Reads the file at the given path or parses a copy of the given data.
+/// Pass `mapped=True` if the image was dumped from memory.
+#[pyclass(module = "pelite", frozen)]
+pub struct PE {
+	image: Vec<u8>,
+	mapped: bool,
+}
+
+#[pymethods]
+impl PE {
+	#[new]
+	#[pyo3(signature = (name = None, data = None, mapped = false))]
+	fn new(name: Option<&str>, data: Option<Vec<u8>>, mapped: bool) -> PyResult<PE> {
+		let image = match (name, data) {
+			(Some(name), None) => std::fs::read(name)?,
+			(None, Some(data)) => data,
+			_ => return Err(PEFormatError::new_err("expected either name or data")),
+		};
+		let result = if mapped { pelite::PeView::from_bytes(&image).map(drop) } else { pelite::PeFile::from_bytes(&image).map(drop) };
+		result.map_err(format_error)?;
+		Ok(PE { image, mapped })
+	}
+	/// True if the image is PE32+.
+	#[getter]
+	fn is_64bit(&self) -> bool {
+		with_pe!(self, |pe| matches!(pe, Wrap::T64(_)))
+	}
+	/// True if the image was parsed as mapped into memory.
+	#[getter]
+	fn is_mapped(&self) -> bool {
+		self.mapped
+	}
+	/// Preferred image base address.
+	#[getter]
+	fn image_base(&self) -> u64 {
+		with_pe!(self, |pe| match pe.optional_header() {
+			Wrap::T32(optional_header) => optional_header.ImageBase as u64,
+			Wrap::T64(optional_header) => optional_header.ImageBase,
+		})
+	}
+	/// Rva of the entry point.
+	#[getter]
+	fn entry_point(&self) -> u32 {
+		with_pe!(self, |pe| match pe.optional_header() {
+			Wrap::T32(optional_header) => optional_header.AddressOfEntryPoint,
+			Wrap::T64(optional_header) => optional_header.AddressOfEntryPoint,
+		})
+	}
+	/// Machine field of the file header.
+	#[getter]
+	fn machine(&self) -> u16 {
+		with_pe!(self, |pe| pe.file_header().Machine)
+	}
+	/// Section headers.
+	#[getter]
+	fn sections(&self) -> Vec<SectionStructure> {
+		with_pe!(self, |pe| pe.section_headers().image().iter().map(|section| SectionStructure {
+			name: section.Name.to_vec(),
+			VirtualAddress: section.VirtualAddress,
+			Misc_VirtualSize: section.VirtualSize,
+			SizeOfRawData: section.SizeOfRawData,
+			PointerToRawData: section.PointerToRawData,
+			Characteristics: section.Characteristics,
+		}).collect())
+	}
+	/// Export directory, None if the image has no exports.
+	#[getter]
+	fn DIRECTORY_ENTRY_EXPORT(&self, py: Python<'_>) -> PyResult<Option<ExportDirData>> {
+		with_pe!(self, |pe| exports(py, pe))
+	}
+	/// Import descriptors.
+	#[getter]
+	fn DIRECTORY_ENTRY_IMPORT(&self, py: Python<'_>) -> PyResult<Vec<ImportDescData>> {
+		with_pe!(self, |pe| imports(py, pe))
+	}
+	/// Lists the resource data entries as `(path, data)` tuples.
+	fn resources(&self) -> PyResult<Vec<(String, Cow<'_, [u8]>)>> {
+		with_pe!(self, |pe| {
+			let mut result = Vec::new();
+			if let Some(resources) = optional(pe.resources())? {
+				let root = resources.root().map_err(format_error)?;
+				walk_resources(root, &mut String::new(), &mut result, &mut 0x10000);
+			}
+			Ok(result.into_iter().map(|(path, bytes)| (path, Cow::Borrowed(bytes))).collect())
+		})
+	}
+	/// Reads the bytes at the rva, up to the end of its section if no length is given.
+	#[pyo3(signature = (rva = 0, length = None))]
+	fn get_data(&self, rva: u32, length: Option<usize>) -> PyResult<Cow<'_, [u8]>> {
+		with_pe!(self, |pe| {
+			let bytes = match length {
+				Some(length) => pe.slice(rva, length, 1).map(|bytes| &bytes[..length]),
+				None => pe.slice_bytes(rva),
+			};
+			bytes.map(Cow::Borrowed).map_err(format_error)
+		})
+	}
+	/// Converts the rva to a file offset.
+	fn get_offset_from_rva(&self, rva: u32) -> PyResult<usize> {
+		with_pe!(self, |pe| match pe {
+			Wrap::T32(pe) => pe32::Pe::rva_to_file_offset(pe, rva),
+			Wrap::T64(pe) => pe64::Pe::rva_to_file_offset(pe, rva),
+		}).map_err(format_error)
+	}
+	/// Converts the file offset to an rva.
+	fn get_rva_from_offset(&self, offset: usize) -> PyResult<u32> {
+		with_pe!(self, |pe| match pe {
+			Wrap::T32(pe) => pe32::Pe::file_offset_to_rva(pe, offset),
+			Wrap::T64(pe) => pe64::Pe::file_offset_to_rva(pe, offset),
+		}).map_err(format_error)
+	}
+	/// Scans for the pattern and returns the save array of every match.
+	///
+	/// Scans the code sections unless a range of rvas is given.
+	#[pyo3(signature = (pattern, start = None, end = None))]
+	fn scan(&self, pattern: &str, start: Option<u32>, end: Option<u32>) -> PyResult<Vec<Vec<u32>>> {
+		let pattern = pat::parse(pattern).map_err(format_error)?;
+		let save_len = pat::save_len(&pattern);
+		with_pe!(self, |pe| {
+			let scanner = pe.scanner();
+			let mut matches = match (start, end) {
+				(Some(start), Some(end)) => scanner.matches(&pattern, start..end),
+				_ => scanner.matches_code(&pattern),
+			};
+			let mut result = Vec::new();
+			let mut save = vec![0; save_len];
+			while matches.next(&mut save) {
+				result.push(save.clone());
+			}
+			Ok(result)
+		})
+	}
+}
+
+//----------------------------------------------------------------
+
+#[pymodule]
+#[pyo3(name = "pelite")]
+fn pelite_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add("PEFormatError", m.py().get_type::<PEFormatError>())?;
+	m.add_class::<PE>()?;
+	m.add_class::<SectionStructure>()?;
+	m.add_class::<ExportDirData>()?;
+	m.add_class::<ExportData>()?;
+	m.add_class::<ImportDescData>()?;
+	m.add_class::<ImportData>()?;
+	Ok(())
+}
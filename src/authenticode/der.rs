@@ -0,0 +1,98 @@
+// Minimal DER decoder, only what the Authenticode structures need.
+//
+// Multi-byte tags and the BER indefinite length form are rejected.
+
+use crate::{Error, Result};
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+
+// Context specific tags
+pub(crate) const fn explicit(n: u8) -> u8 {
+	0xA0 | n
+}
+
+/// Decoded tag, length and value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Tlv<'a> {
+	pub tag: u8,
+	/// The contents without the tag and length.
+	pub value: &'a [u8],
+	/// The whole encoding including the tag and length.
+	pub raw: &'a [u8],
+}
+impl<'a> Tlv<'a> {
+	pub fn reader(&self) -> Reader<'a> {
+		Reader::new(self.value)
+	}
+}
+
+/// Reads consecutive encodings.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Reader<'a> {
+	bytes: &'a [u8],
+}
+impl<'a> Reader<'a> {
+	pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+		Reader { bytes }
+	}
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+	pub fn peek(&self) -> Option<u8> {
+		self.bytes.first().cloned()
+	}
+	pub fn read(&mut self) -> Result<Tlv<'a>> {
+		let bytes = self.bytes;
+		let (&tag, rest) = bytes.split_first().ok_or(Error::Bounds)?;
+		if tag & 0x1F == 0x1F {
+			return Err(Error::Invalid);
+		}
+		let (&len, rest) = rest.split_first().ok_or(Error::Bounds)?;
+		let (len, rest) = match len {
+			0..=0x7F => (len as usize, rest),
+			0x81..=0x84 => {
+				let n = (len & 0x7F) as usize;
+				let len_bytes = rest.get(..n).ok_or(Error::Bounds)?;
+				(len_bytes.iter().fold(0usize, |acc, &byte| acc << 8 | byte as usize), &rest[n..])
+			},
+			_ => return Err(Error::Invalid),
+		};
+		let value = rest.get(..len).ok_or(Error::Bounds)?;
+		let header = bytes.len() - rest.len();
+		self.bytes = &rest[len..];
+		Ok(Tlv { tag, value, raw: &bytes[..header + len] })
+	}
+	pub fn expect(&mut self, tag: u8) -> Result<Tlv<'a>> {
+		let tlv = self.read()?;
+		if tlv.tag != tag {
+			return Err(Error::Invalid);
+		}
+		Ok(tlv)
+	}
+	pub fn optional(&mut self, tag: u8) -> Result<Option<Tlv<'a>>> {
+		if self.peek() == Some(tag) { self.read().map(Some) } else { Ok(None) }
+	}
+	// AlgorithmIdentifier, ignoring the parameters
+	pub fn algorithm(&mut self) -> Result<&'a [u8]> {
+		Ok(self.expect(SEQUENCE)?.reader().expect(OID)?.value)
+	}
+}
+impl<'a> Iterator for Reader<'a> {
+	type Item = Result<Tlv<'a>>;
+	fn next(&mut self) -> Option<Result<Tlv<'a>>> {
+		if self.is_empty() {
+			return None;
+		}
+		let tlv = self.read();
+		if tlv.is_err() {
+			self.bytes = &[];
+		}
+		Some(tlv)
+	}
+}
@@ -0,0 +1,760 @@
+/*!
+Authenticode signatures.
+
+The [security directory](../security/index.html) of a signed image contains a PKCS#7 `SignedData` structure.
+Its content is the `SpcIndirectDataContent` holding the Authenticode digest of the image, signed by the publisher's certificate.
+
+This module decodes the structures needed to inspect a signature: the signed content, the embedded certificates and the signer.
+No cryptography is performed here, see the [verify](verify/index.html) module behind the `verify` feature for that.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let signed_data = file.security()?.signed_data()?;
+	let (digest_algorithm, digest) = signed_data.indirect_data()?;
+	println!("{} digest: {:02x?}", digest_algorithm, digest);
+	if let Some(certificate) = signed_data.signer_certificate() {
+		println!("signed by {}", certificate.subject());
+	}
+	if let Ok(timestamp) = signed_data.timestamp() {
+		println!("timestamped at {}", timestamp.signing_time);
+	}
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::borrow::Cow;
+use std::{char, fmt};
+
+use crate::timestamp::UtcDateTime;
+use crate::{Error, Result};
+
+mod der;
+use self::der::{Reader, Tlv};
+
+#[cfg(feature = "verify")]
+pub mod verify;
+
+//----------------------------------------------------------------
+
+/// Object identifier.
+///
+/// Holds the encoded bytes of the identifier, formats as the familiar dotted notation.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Oid<'a>(&'a [u8]);
+impl<'a> Oid<'a> {
+	/// Wraps the encoded bytes of an object identifier.
+	pub const fn new(bytes: &'a [u8]) -> Oid<'a> {
+		Oid(bytes)
+	}
+	/// Returns the encoded bytes.
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.0
+	}
+}
+impl<'a> fmt::Display for Oid<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut value = 0u64;
+		let mut first = true;
+		for &byte in self.0 {
+			value = value << 7 | (byte & 0x7F) as u64;
+			if byte & 0x80 == 0 {
+				if first {
+					let arc = if value < 80 { value / 40 } else { 2 };
+					write!(f, "{}.{}", arc, value - arc * 40)?;
+					first = false;
+				}
+				else {
+					write!(f, ".{}", value)?;
+				}
+				value = 0;
+			}
+		}
+		Ok(())
+	}
+}
+impl<'a> fmt::Debug for Oid<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Oid({})", self)
+	}
+}
+
+/// Well known object identifiers.
+pub mod oid {
+	use super::Oid;
+
+	/// `1.3.14.3.2.26`
+	pub const SHA1: Oid<'static> = Oid::new(&[0x2B, 0x0E, 0x03, 0x02, 0x1A]);
+	/// `2.16.840.1.101.3.4.2.1`
+	pub const SHA256: Oid<'static> = Oid::new(&[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]);
+	/// `2.16.840.1.101.3.4.2.2`
+	pub const SHA384: Oid<'static> = Oid::new(&[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02]);
+	/// `2.16.840.1.101.3.4.2.3`
+	pub const SHA512: Oid<'static> = Oid::new(&[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03]);
+	/// `1.2.840.113549.1.1.1`
+	pub const RSA_ENCRYPTION: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01]);
+	/// `1.2.840.113549.1.1.5`
+	pub const SHA1_WITH_RSA_ENCRYPTION: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x05]);
+	/// `1.2.840.113549.1.1.11`
+	pub const SHA256_WITH_RSA_ENCRYPTION: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B]);
+	/// `1.2.840.113549.1.1.12`
+	pub const SHA384_WITH_RSA_ENCRYPTION: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0C]);
+	/// `1.2.840.113549.1.1.13`
+	pub const SHA512_WITH_RSA_ENCRYPTION: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0D]);
+	/// `1.2.840.10045.2.1`
+	pub const EC_PUBLIC_KEY: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01]);
+	/// `1.2.840.10045.3.1.7`
+	pub const PRIME256V1: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07]);
+	/// `1.3.132.0.34`
+	pub const SECP384R1: Oid<'static> = Oid::new(&[0x2B, 0x81, 0x04, 0x00, 0x22]);
+	/// `1.2.840.10045.4.3.2`
+	pub const ECDSA_WITH_SHA256: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02]);
+	/// `1.2.840.10045.4.3.3`
+	pub const ECDSA_WITH_SHA384: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03]);
+	/// `1.2.840.113549.1.7.2`
+	pub const SIGNED_DATA: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02]);
+	/// `1.2.840.113549.1.9.3`
+	pub const CONTENT_TYPE: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x03]);
+	/// `1.2.840.113549.1.9.4`
+	pub const MESSAGE_DIGEST: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04]);
+	/// `1.2.840.113549.1.9.5`
+	pub const SIGNING_TIME: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x05]);
+	/// `1.2.840.113549.1.9.6`
+	pub const COUNTER_SIGNATURE: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x06]);
+	/// `1.2.840.113549.1.9.16.1.4`
+	pub const TST_INFO: Oid<'static> = Oid::new(&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x10, 0x01, 0x04]);
+	/// `1.3.6.1.4.1.311.2.1.4`
+	pub const SPC_INDIRECT_DATA: Oid<'static> = Oid::new(&[0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x02, 0x01, 0x04]);
+	/// `1.3.6.1.4.1.311.3.3.1`
+	pub const SPC_RFC3161_TIMESTAMP: Oid<'static> = Oid::new(&[0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x03, 0x03, 0x01]);
+	/// `2.5.29.15`
+	pub const KEY_USAGE: Oid<'static> = Oid::new(&[0x55, 0x1D, 0x0F]);
+	/// `2.5.29.19`
+	pub const BASIC_CONSTRAINTS: Oid<'static> = Oid::new(&[0x55, 0x1D, 0x13]);
+	/// `2.5.4.3`
+	pub const COMMON_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x03]);
+	/// `2.5.4.6`
+	pub const COUNTRY_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x06]);
+	/// `2.5.4.7`
+	pub const LOCALITY_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x07]);
+	/// `2.5.4.8`
+	pub const STATE_OR_PROVINCE_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x08]);
+	/// `2.5.4.10`
+	pub const ORGANIZATION_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x0A]);
+	/// `2.5.4.11`
+	pub const ORGANIZATIONAL_UNIT_NAME: Oid<'static> = Oid::new(&[0x55, 0x04, 0x0B]);
+}
+
+//----------------------------------------------------------------
+
+/// Distinguished name of a certificate issuer or subject.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Name<'a> {
+	raw: &'a [u8],
+}
+impl<'a> Name<'a> {
+	/// Returns the encoded name.
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.raw
+	}
+	/// Returns the attributes of the name in order, attributes which cannot be decoded are skipped.
+	pub fn attributes(&self) -> Vec<(Oid<'a>, Cow<'a, str>)> {
+		let mut attributes = Vec::new();
+		let rdns = match Reader::new(self.raw).expect(der::SEQUENCE) {
+			Ok(rdns) => rdns,
+			Err(_) => return attributes,
+		};
+		for rdn in rdns.reader().flatten() {
+			for attribute in rdn.reader().flatten() {
+				let mut reader = attribute.reader();
+				if let (Ok(oid), Ok(value)) = (reader.expect(der::OID), reader.read()) {
+					if let Some(value) = decode_string(value) {
+						attributes.push((Oid(oid.value), value));
+					}
+				}
+			}
+		}
+		attributes
+	}
+	/// Finds the first attribute with the object identifier.
+	pub fn attribute(&self, oid: Oid<'_>) -> Option<Cow<'a, str>> {
+		self.attributes().into_iter().find(|&(it, _)| it == oid).map(|(_, value)| value)
+	}
+	/// Gets the common name.
+	pub fn common_name(&self) -> Option<Cow<'a, str>> {
+		self.attribute(oid::COMMON_NAME)
+	}
+}
+impl<'a> fmt::Display for Name<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (index, (oid, value)) in self.attributes().iter().enumerate() {
+			if index != 0 {
+				f.write_str(", ")?;
+			}
+			match *oid {
+				oid::COMMON_NAME => f.write_str("CN")?,
+				oid::COUNTRY_NAME => f.write_str("C")?,
+				oid::LOCALITY_NAME => f.write_str("L")?,
+				oid::STATE_OR_PROVINCE_NAME => f.write_str("ST")?,
+				oid::ORGANIZATION_NAME => f.write_str("O")?,
+				oid::ORGANIZATIONAL_UNIT_NAME => f.write_str("OU")?,
+				oid => write!(f, "{}", oid)?,
+			}
+			write!(f, "={}", value)?;
+		}
+		Ok(())
+	}
+}
+impl<'a> fmt::Debug for Name<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Name({:?})", self.to_string())
+	}
+}
+
+// Decodes the directory string types
+fn decode_string(tlv: Tlv<'_>) -> Option<Cow<'_, str>> {
+	match tlv.tag {
+		// UTF8String, PrintableString, IA5String
+		0x0C | 0x13 | 0x16 => std::str::from_utf8(tlv.value).ok().map(Cow::Borrowed),
+		// TeletexString is treated as latin-1
+		0x14 => Some(tlv.value.iter().map(|&byte| byte as char).collect::<String>().into()),
+		// BMPString
+		0x1E => {
+			let words = tlv.value.chunks_exact(2).map(|word| u16::from_be_bytes([word[0], word[1]]));
+			Some(char::decode_utf16(words).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect::<String>().into())
+		},
+		_ => None,
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Key usage bits of a certificate.
+pub mod key_usage {
+	/// The key signs data other than certificates and CRLs, eg. the signed attributes of an Authenticode signature.
+	pub const DIGITAL_SIGNATURE: u16 = 1 << 0;
+	/// The key signs certificates.
+	pub const KEY_CERT_SIGN: u16 = 1 << 5;
+	/// The key signs certificate revocation lists.
+	pub const CRL_SIGN: u16 = 1 << 6;
+}
+
+/// X.509 certificate.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Certificate<'a> {
+	raw: &'a [u8],
+	tbs: &'a [u8],
+	serial_number: &'a [u8],
+	issuer: Name<'a>,
+	not_before: UtcDateTime,
+	not_after: UtcDateTime,
+	subject: Name<'a>,
+	public_key: &'a [u8],
+	extensions: Option<Tlv<'a>>,
+	signature_algorithm: Oid<'a>,
+	signature: &'a [u8],
+}
+impl<'a> Certificate<'a> {
+	/// Decodes the certificate, trailing bytes are ignored.
+	pub fn parse(bytes: &'a [u8]) -> Result<Certificate<'a>> {
+		Certificate::from_tlv(Reader::new(bytes).expect(der::SEQUENCE)?)
+	}
+	fn from_tlv(certificate: Tlv<'a>) -> Result<Certificate<'a>> {
+		let mut reader = certificate.reader();
+		let tbs = reader.expect(der::SEQUENCE)?;
+		let signature_algorithm = Oid(reader.algorithm()?);
+		let signature = bit_string(reader.expect(der::BIT_STRING)?)?;
+
+		let mut fields = tbs.reader();
+		fields.optional(der::explicit(0))?;
+		let serial_number = fields.expect(der::INTEGER)?.value;
+		fields.algorithm()?;
+		let issuer = Name { raw: fields.expect(der::SEQUENCE)?.raw };
+		let mut validity = fields.expect(der::SEQUENCE)?.reader();
+		let not_before = decode_time(validity.read()?)?;
+		let not_after = decode_time(validity.read()?)?;
+		let subject = Name { raw: fields.expect(der::SEQUENCE)?.raw };
+		let public_key = fields.expect(der::SEQUENCE)?.raw;
+		// The unique identifiers are implicitly tagged bit strings
+		fields.optional(0x81)?;
+		fields.optional(0x82)?;
+		let extensions = match fields.optional(der::explicit(3))? {
+			Some(extensions) => Some(extensions.reader().expect(der::SEQUENCE)?),
+			None => None,
+		};
+
+		Ok(Certificate {
+			raw: certificate.raw,
+			tbs: tbs.raw,
+			serial_number,
+			issuer,
+			not_before,
+			not_after,
+			subject,
+			public_key,
+			extensions,
+			signature_algorithm,
+			signature,
+		})
+	}
+	/// Returns the encoded certificate.
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.raw
+	}
+	/// Returns the encoded `TBSCertificate`, the part of the certificate signed by its issuer.
+	pub fn tbs_certificate(&self) -> &'a [u8] {
+		self.tbs
+	}
+	/// Returns the big-endian serial number.
+	pub fn serial_number(&self) -> &'a [u8] {
+		self.serial_number
+	}
+	/// Gets the name of the issuer.
+	pub fn issuer(&self) -> Name<'a> {
+		self.issuer
+	}
+	/// Gets the start of the validity period.
+	pub fn not_before(&self) -> UtcDateTime {
+		self.not_before
+	}
+	/// Gets the end of the validity period.
+	pub fn not_after(&self) -> UtcDateTime {
+		self.not_after
+	}
+	/// Returns true if the time falls within the validity period.
+	pub fn is_valid_at(&self, time: UtcDateTime) -> bool {
+		self.not_before <= time && time <= self.not_after
+	}
+	/// Gets the name of the subject.
+	pub fn subject(&self) -> Name<'a> {
+		self.subject
+	}
+	/// Returns the encoded `SubjectPublicKeyInfo`.
+	pub fn public_key(&self) -> &'a [u8] {
+		self.public_key
+	}
+	/// Finds the encoded value of the extension, without the wrapping octet string.
+	pub fn extension(&self, oid: Oid<'_>) -> Option<&'a [u8]> {
+		self.extensions?.reader().flatten().find_map(|extension| {
+			let mut reader = extension.reader();
+			if reader.expect(der::OID).ok()?.value != oid.0 {
+				return None;
+			}
+			reader.optional(der::BOOLEAN).ok()?;
+			Some(reader.expect(der::OCTET_STRING).ok()?.value)
+		})
+	}
+	/// Returns true if the basic constraints extension marks the subject as a certificate authority.
+	pub fn is_ca(&self) -> bool {
+		let basic_constraints = match self.extension(oid::BASIC_CONSTRAINTS) {
+			Some(value) => value,
+			None => return false,
+		};
+		match Reader::new(basic_constraints).expect(der::SEQUENCE) {
+			Ok(sequence) => matches!(sequence.reader().optional(der::BOOLEAN), Ok(Some(ca)) if ca.value == [0xFF]),
+			Err(_) => false,
+		}
+	}
+	/// Gets the [key usage](key_usage/index.html) bits.
+	///
+	/// Returns `None` if the certificate does not restrict the usage of its key.
+	pub fn key_usage(&self) -> Option<u16> {
+		let value = self.extension(oid::KEY_USAGE)?;
+		let bits = Reader::new(value).expect(der::BIT_STRING).ok()?.value.get(1..)?;
+		// The named bits are numbered starting at the most significant bit of the first byte
+		let bits = bits.iter().take(2).enumerate().fold(0u16, |acc, (i, &byte)| acc | (byte.reverse_bits() as u16) << (i * 8));
+		Some(bits)
+	}
+	/// Gets the algorithm the issuer signed the certificate with.
+	pub fn signature_algorithm(&self) -> Oid<'a> {
+		self.signature_algorithm
+	}
+	/// Returns the signature of the issuer.
+	pub fn signature(&self) -> &'a [u8] {
+		self.signature
+	}
+	/// Returns true if the certificate is issued by its own subject.
+	pub fn is_self_issued(&self) -> bool {
+		self.issuer == self.subject
+	}
+}
+impl<'a> fmt::Debug for Certificate<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Certificate")
+			.field("serial_number", &format_args!("{:02x?}", self.serial_number))
+			.field("issuer", &self.issuer)
+			.field("not_before", &self.not_before)
+			.field("not_after", &self.not_after)
+			.field("subject", &self.subject)
+			.field("signature_algorithm", &self.signature_algorithm)
+			.finish()
+	}
+}
+
+// Only whole bytes are used for keys and signatures
+fn bit_string(tlv: Tlv<'_>) -> Result<&'_ [u8]> {
+	match tlv.value.split_first() {
+		Some((0, bits)) => Ok(bits),
+		_ => Err(Error::Invalid),
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Signer of the `SignedData`.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct SignerInfo<'a> {
+	issuer: Name<'a>,
+	serial_number: &'a [u8],
+	digest_algorithm: Oid<'a>,
+	authenticated_attributes: Option<Tlv<'a>>,
+	signature_algorithm: Oid<'a>,
+	signature: &'a [u8],
+	unauthenticated_attributes: Option<Tlv<'a>>,
+}
+impl<'a> SignerInfo<'a> {
+	fn from_tlv(signer_info: Tlv<'a>) -> Result<SignerInfo<'a>> {
+		let mut reader = signer_info.reader();
+		reader.expect(der::INTEGER)?;
+		// Signers identified by their subject key identifier are not supported
+		let mut sid = reader.expect(der::SEQUENCE)?.reader();
+		let issuer = Name { raw: sid.expect(der::SEQUENCE)?.raw };
+		let serial_number = sid.expect(der::INTEGER)?.value;
+		let digest_algorithm = Oid(reader.algorithm()?);
+		let authenticated_attributes = reader.optional(der::explicit(0))?;
+		let signature_algorithm = Oid(reader.algorithm()?);
+		let signature = reader.expect(der::OCTET_STRING)?.value;
+		let unauthenticated_attributes = reader.optional(der::explicit(1))?;
+		Ok(SignerInfo {
+			issuer,
+			serial_number,
+			digest_algorithm,
+			authenticated_attributes,
+			signature_algorithm,
+			signature,
+			unauthenticated_attributes,
+		})
+	}
+	/// Gets the name of the issuer of the signer's certificate.
+	pub fn issuer(&self) -> Name<'a> {
+		self.issuer
+	}
+	/// Returns the big-endian serial number of the signer's certificate.
+	pub fn serial_number(&self) -> &'a [u8] {
+		self.serial_number
+	}
+	/// Gets the algorithm used to digest the content and the authenticated attributes.
+	pub fn digest_algorithm(&self) -> Oid<'a> {
+		self.digest_algorithm
+	}
+	/// Gets the algorithm used to sign the digest.
+	pub fn signature_algorithm(&self) -> Oid<'a> {
+		self.signature_algorithm
+	}
+	/// Returns the signature.
+	pub fn signature(&self) -> &'a [u8] {
+		self.signature
+	}
+	/// Returns the authenticated attributes encoded as they are signed.
+	///
+	/// The attributes are stored with an implicit context specific tag, the signature covers them with the `SET OF` tag.
+	pub fn signed_attributes(&self) -> Option<Vec<u8>> {
+		let raw = self.authenticated_attributes?.raw;
+		let mut signed = raw.to_vec();
+		signed[0] = der::SET;
+		Some(signed)
+	}
+	/// Finds the encoded first value of the authenticated attribute.
+	pub fn authenticated_attribute(&self, oid: Oid<'_>) -> Option<&'a [u8]> {
+		find_attribute(self.authenticated_attributes?, oid)
+	}
+	/// Finds the encoded first value of the unauthenticated attribute.
+	pub fn unauthenticated_attribute(&self, oid: Oid<'_>) -> Option<&'a [u8]> {
+		find_attribute(self.unauthenticated_attributes?, oid)
+	}
+	/// Returns the digest of the content from the authenticated attributes.
+	pub fn message_digest(&self) -> Option<&'a [u8]> {
+		let value = self.authenticated_attribute(oid::MESSAGE_DIGEST)?;
+		Reader::new(value).expect(der::OCTET_STRING).ok().map(|tlv| tlv.value)
+	}
+	/// Finds the signer's certificate by its issuer and serial number.
+	pub fn find_certificate<'c>(&self, certificates: &'c [Certificate<'a>]) -> Option<&'c Certificate<'a>> {
+		certificates.iter().find(|certificate| certificate.issuer == self.issuer && certificate.serial_number == self.serial_number)
+	}
+}
+impl<'a> fmt::Debug for SignerInfo<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("SignerInfo")
+			.field("issuer", &self.issuer)
+			.field("serial_number", &format_args!("{:02x?}", self.serial_number))
+			.field("digest_algorithm", &self.digest_algorithm)
+			.field("signature_algorithm", &self.signature_algorithm)
+			.finish()
+	}
+}
+
+fn find_attribute<'a>(attributes: Tlv<'a>, oid: Oid<'_>) -> Option<&'a [u8]> {
+	attributes.reader().flatten().find_map(|attribute| {
+		let mut reader = attribute.reader();
+		if reader.expect(der::OID).ok()?.value != oid.0 {
+			return None;
+		}
+		Some(reader.expect(der::SET).ok()?.reader().read().ok()?.raw)
+	})
+}
+
+//----------------------------------------------------------------
+
+/// PKCS#7 `SignedData`.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct SignedData<'a> {
+	content_type: Oid<'a>,
+	content: Tlv<'a>,
+	certificates: Vec<Certificate<'a>>,
+	signer_infos: Vec<SignerInfo<'a>>,
+}
+impl<'a> SignedData<'a> {
+	/// Decodes the `ContentInfo` wrapping the `SignedData`, trailing bytes are ignored.
+	///
+	/// Certificates which cannot be decoded are skipped.
+	pub fn parse(bytes: &'a [u8]) -> Result<SignedData<'a>> {
+		let mut content_info = Reader::new(bytes).expect(der::SEQUENCE)?.reader();
+		if content_info.expect(der::OID)?.value != oid::SIGNED_DATA.0 {
+			return Err(Error::Invalid);
+		}
+		let mut signed_data = content_info.expect(der::explicit(0))?.reader().expect(der::SEQUENCE)?.reader();
+		signed_data.expect(der::INTEGER)?;
+		signed_data.expect(der::SET)?;
+
+		let mut encap_content_info = signed_data.expect(der::SEQUENCE)?.reader();
+		let content_type = Oid(encap_content_info.expect(der::OID)?.value);
+		let content = encap_content_info.expect(der::explicit(0))?.reader().read()?;
+
+		let certificates = match signed_data.optional(der::explicit(0))? {
+			Some(certificates) => certificates.reader()
+				.filter_map(|certificate| Certificate::from_tlv(certificate.ok()?).ok())
+				.collect(),
+			None => Vec::new(),
+		};
+		signed_data.optional(der::explicit(1))?;
+		let signer_infos = signed_data.expect(der::SET)?.reader()
+			.map(|signer_info| SignerInfo::from_tlv(signer_info?))
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(SignedData { content_type, content, certificates, signer_infos })
+	}
+	/// Gets the type of the signed content.
+	pub fn content_type(&self) -> Oid<'a> {
+		self.content_type
+	}
+	/// Returns the signed content bytes.
+	///
+	/// These are the bytes digested in the message digest authenticated attribute, the contents without their tag and length.
+	pub fn content(&self) -> &'a [u8] {
+		self.content.value
+	}
+	/// Returns the embedded certificates.
+	pub fn certificates(&self) -> &[Certificate<'a>] {
+		&self.certificates
+	}
+	/// Returns the signers.
+	pub fn signer_infos(&self) -> &[SignerInfo<'a>] {
+		&self.signer_infos
+	}
+	/// Gets the signer, Authenticode signatures have exactly one signer.
+	pub fn signer_info(&self) -> Option<&SignerInfo<'a>> {
+		self.signer_infos.first()
+	}
+	/// Finds the certificate of the signer.
+	pub fn signer_certificate(&self) -> Option<&Certificate<'a>> {
+		self.signer_info()?.find_certificate(&self.certificates)
+	}
+	/// Gets the digest algorithm and the Authenticode digest of the image from the `SpcIndirectDataContent`.
+	///
+	/// Returns [`Err(Invalid)`](../enum.Error.html#variant.Invalid) if the content is not an Authenticode `SpcIndirectDataContent`.
+	pub fn indirect_data(&self) -> Result<(Oid<'a>, &'a [u8])> {
+		if self.content_type != oid::SPC_INDIRECT_DATA || self.content.tag != der::SEQUENCE {
+			return Err(Error::Invalid);
+		}
+		let mut reader = self.content.reader();
+		reader.expect(der::SEQUENCE)?;
+		let mut digest_info = reader.expect(der::SEQUENCE)?.reader();
+		let algorithm = Oid(digest_info.algorithm()?);
+		let digest = digest_info.expect(der::OCTET_STRING)?.value;
+		Ok((algorithm, digest))
+	}
+	/// Gets the timestamp counter-signature of the signer.
+	///
+	/// RFC 3161 timestamps are preferred over legacy Authenticode counter-signatures if both are present.
+	/// The counter-signature is decoded but not verified.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the signature is not timestamped.
+	pub fn timestamp(&self) -> Result<Timestamp<'a>> {
+		let signer_info = self.signer_info().ok_or(Error::Invalid)?;
+		if let Some(token) = signer_info.unauthenticated_attribute(oid::SPC_RFC3161_TIMESTAMP) {
+			let token = SignedData::parse(token)?;
+			if token.content_type != oid::TST_INFO {
+				return Err(Error::Invalid);
+			}
+			// TSTInfo: version, policy, messageImprint, serialNumber, genTime, ...
+			let mut tst_info = Reader::new(token.content()).expect(der::SEQUENCE)?.reader();
+			tst_info.expect(der::INTEGER)?;
+			tst_info.expect(der::OID)?;
+			let mut message_imprint = tst_info.expect(der::SEQUENCE)?.reader();
+			let imprint = (Oid(message_imprint.algorithm()?), message_imprint.expect(der::OCTET_STRING)?.value);
+			tst_info.expect(der::INTEGER)?;
+			let signing_time = decode_time(tst_info.read()?)?;
+			let signer_info = *token.signer_info().ok_or(Error::Invalid)?;
+			let certificate = token.signer_certificate().cloned();
+			Ok(Timestamp {
+				kind: TimestampKind::Rfc3161,
+				signing_time,
+				signer_info,
+				certificate,
+				content: token.content(),
+				imprint: Some(imprint),
+				certificates: token.certificates,
+			})
+		}
+		else if let Some(counter_signature) = signer_info.unauthenticated_attribute(oid::COUNTER_SIGNATURE) {
+			let counter_signer = SignerInfo::from_tlv(Reader::new(counter_signature).expect(der::SEQUENCE)?)?;
+			let signing_time = counter_signer.authenticated_attribute(oid::SIGNING_TIME).ok_or(Error::Invalid)?;
+			let signing_time = decode_time(Reader::new(signing_time).read()?)?;
+			let certificate = counter_signer.find_certificate(&self.certificates).cloned();
+			Ok(Timestamp {
+				kind: TimestampKind::Authenticode,
+				signing_time,
+				signer_info: counter_signer,
+				certificate,
+				content: signer_info.signature(),
+				imprint: None,
+				certificates: Vec::new(),
+			})
+		}
+		else {
+			Err(Error::Null)
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Kind of timestamp counter-signature.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TimestampKind {
+	/// Legacy Authenticode counter-signature, a `SignerInfo` signing the signature of the signer.
+	Authenticode,
+	/// RFC 3161 timestamp token, a `SignedData` of a `TSTInfo` signed by the timestamping authority.
+	Rfc3161,
+}
+
+/// Timestamp counter-signature.
+///
+/// Created with the method [`SignedData::timestamp`](struct.SignedData.html#method.timestamp).
+#[derive(Clone, Debug)]
+pub struct Timestamp<'a> {
+	/// Kind of counter-signature.
+	pub kind: TimestampKind,
+	/// When the timestamping authority counter-signed the signature.
+	pub signing_time: UtcDateTime,
+	/// The counter-signer.
+	pub signer_info: SignerInfo<'a>,
+	/// Certificate of the timestamping authority if it is embedded.
+	pub certificate: Option<Certificate<'a>>,
+	/// The content digested in the message digest of the counter-signer.
+	///
+	/// This is the encoded `TSTInfo` of RFC 3161 tokens and the signature of the signer for legacy counter-signatures.
+	pub content: &'a [u8],
+	/// The digest algorithm and the digest of the signature of the signer from the `TSTInfo`.
+	///
+	/// Legacy counter-signatures digest the signature directly and have no separate imprint.
+	pub imprint: Option<(Oid<'a>, &'a [u8])>,
+	/// The certificates embedded in the RFC 3161 token.
+	///
+	/// Legacy counter-signatures embed their certificates alongside those of the signer.
+	pub certificates: Vec<Certificate<'a>>,
+}
+
+// Decodes UTCTime and GeneralizedTime, fractional seconds are dropped
+fn decode_time(tlv: Tlv<'_>) -> Result<UtcDateTime> {
+	let digits = |bytes: &[u8]| -> Result<u16> {
+		bytes.iter().try_fold(0u16, |acc, &byte| match byte {
+			b'0'..=b'9' => Ok(acc * 10 + (byte - b'0') as u16),
+			_ => Err(Error::Invalid),
+		})
+	};
+	let (year, rest) = match tlv.tag {
+		0x17 if tlv.value.len() >= 2 => {
+			let year = digits(&tlv.value[..2])?;
+			(if year < 50 { 2000 + year } else { 1900 + year }, &tlv.value[2..])
+		},
+		0x18 if tlv.value.len() >= 4 => (digits(&tlv.value[..4])?, &tlv.value[4..]),
+		_ => return Err(Error::Invalid),
+	};
+	if rest.len() < 11 || rest.last() != Some(&b'Z') {
+		return Err(Error::Invalid);
+	}
+	let time = UtcDateTime {
+		year,
+		month: digits(&rest[0..2])? as u8,
+		day: digits(&rest[2..4])? as u8,
+		hour: digits(&rest[4..6])? as u8,
+		minute: digits(&rest[6..8])? as u8,
+		second: digits(&rest[8..10])? as u8,
+	};
+	if !(1..=12).contains(&time.month) || !(1..=31).contains(&time.day) || time.hour > 23 || time.minute > 59 || time.second > 60 {
+		return Err(Error::Invalid);
+	}
+	Ok(time)
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn oid() {
+		assert_eq!(oid::SHA256.to_string(), "2.16.840.1.101.3.4.2.1");
+		assert_eq!(oid::SPC_INDIRECT_DATA.to_string(), "1.3.6.1.4.1.311.2.1.4");
+		assert_eq!(oid::COMMON_NAME.to_string(), "2.5.4.3");
+		assert_eq!(oid::SHA256_WITH_RSA_ENCRYPTION.to_string(), "1.2.840.113549.1.1.11");
+		assert_eq!(oid::PRIME256V1.to_string(), "1.2.840.10045.3.1.7");
+		assert_eq!(oid::SECP384R1.to_string(), "1.3.132.0.34");
+		assert_eq!(oid::ECDSA_WITH_SHA384.to_string(), "1.2.840.10045.4.3.3");
+	}
+
+	#[test]
+	fn name() {
+		// SEQUENCE { SET { SEQUENCE { CN, UTF8String "Casper" } }, SET { SEQUENCE { O, BMPString "Ab" } } }
+		let raw = [
+			0x30, 0x20,
+			0x31, 0x0F, 0x30, 0x0D, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x06, b'C', b'a', b's', b'p', b'e', b'r',
+			0x31, 0x0D, 0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x0A, 0x1E, 0x04, 0x00, b'A', 0x00, b'b',
+		];
+		let name = Name { raw: &raw };
+		assert_eq!(name.common_name().as_deref(), Some("Casper"));
+		assert_eq!(name.to_string(), "CN=Casper, O=Ab");
+		assert_eq!(Name { raw: &raw[..4] }.attributes(), []);
+	}
+
+	#[test]
+	fn time() {
+		let time = |tag: u8, value: &[u8]| decode_time(Tlv { tag, value, raw: &[] }).map(|time| time.to_string());
+		assert_eq!(time(0x17, b"200905105405Z"), Ok(String::from("2020-09-05 10:54:05 UTC")));
+		assert_eq!(time(0x17, b"991231235959Z"), Ok(String::from("1999-12-31 23:59:59 UTC")));
+		assert_eq!(time(0x18, b"20200905105405.123Z"), Ok(String::from("2020-09-05 10:54:05 UTC")));
+		assert_eq!(time(0x18, b"20201305105405Z"), Err(Error::Invalid));
+		assert_eq!(time(0x17, b"200905105405"), Err(Error::Invalid));
+	}
+}
@@ -0,0 +1,390 @@
+/*!
+Authenticode signature verification.
+
+Verifying a signature takes these steps:
+
+* The Authenticode digest of the image must match the digest in the signed `SpcIndirectDataContent`.
+* The message digest attribute must match the digest of the signed content and
+  the signature of the signer over the authenticated attributes must check out against the public key of the signer's certificate.
+* The signer's certificate must chain up to one of the trust anchors through the embedded certificates, each certificate signed by the next.
+  The issuers must be certificate authorities whose key usage allows signing certificates, the key usage of the signer must allow digital signatures.
+  Every certificate in the chain must be valid at the time of signing.
+* A timestamp counter-signature is verified the same way against the same trust anchors and provides the time of signing.
+  Without a trusted timestamp the time of signing is the time passed by the caller, usually the current time.
+  The outcome of verifying the timestamp is reported separately from the verdict of the signature.
+
+The [`Ring`](struct.Ring.html) verifier provides the SHA-1 and SHA-2 hash functions and the RSA and ECDSA signature algorithms with the `ring` crate.
+Other cryptography crates are plugged in by implementing [`Verifier`](trait.Verifier.html).
+The trust anchors are trusted as is, the extended key usages and revocation status of the certificates are not checked.
+
+Available with the `verify` feature.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use std::time::{SystemTime, UNIX_EPOCH};
+use pelite::pe64::{Pe, PeFile};
+use pelite::authenticode::verify::{Ring, Verdict};
+use pelite::timestamp::UtcDateTime;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, root_certificate: &[u8]) -> pelite::Result<()> {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+	let verification = file.security()?.verify(&Ring, &[root_certificate], UtcDateTime::from_unix_time(now as u32));
+	match verification.verdict {
+		Verdict::Trusted => println!("signed by {}", verification.chain[0].subject()),
+		verdict => println!("not trusted: {:?}", verdict),
+	}
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+use ring::{digest, signature};
+
+use crate::security::Security;
+use crate::timestamp::UtcDateTime;
+use crate::{Error, Result};
+
+use super::der::{self, Reader};
+use super::{bit_string, key_usage, oid, Certificate, Oid, SignerInfo, Timestamp};
+
+//----------------------------------------------------------------
+
+/// Cryptographic primitives used to verify signatures.
+pub trait Verifier {
+	/// Hashes the concatenation of the message parts with the digest algorithm.
+	///
+	/// Returns `None` if the algorithm is not supported.
+	fn digest(&self, algorithm: Oid<'_>, message: &[&[u8]]) -> Option<Vec<u8>>;
+	/// Verifies the signature of the message with the public key.
+	///
+	/// The public key is the encoded `SubjectPublicKeyInfo` of the signing certificate.
+	/// Signers often only name the key algorithm, eg. `rsaEncryption`, the digest algorithm is then given separately.
+	/// Certificates name a combined algorithm, eg. `sha256WithRSAEncryption`, without a separate digest algorithm.
+	///
+	/// Returns false if the signature does not check out or the algorithm is not supported.
+	fn verify_signature(&self, algorithm: Oid<'_>, digest_algorithm: Option<Oid<'_>>, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Verifier backed by the `ring` crate.
+///
+/// Supports the SHA-1, SHA-256, SHA-384 and SHA-512 digests, RSA PKCS#1 v1.5 signatures and ECDSA signatures on the P-256 and P-384 curves.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Ring;
+impl Verifier for Ring {
+	fn digest(&self, algorithm: Oid<'_>, message: &[&[u8]]) -> Option<Vec<u8>> {
+		let algorithm = match algorithm {
+			oid::SHA1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+			oid::SHA256 => &digest::SHA256,
+			oid::SHA384 => &digest::SHA384,
+			oid::SHA512 => &digest::SHA512,
+			_ => return None,
+		};
+		let mut context = digest::Context::new(algorithm);
+		for part in message {
+			context.update(part);
+		}
+		Some(context.finish().as_ref().to_vec())
+	}
+	fn verify_signature(&self, algorithm: Oid<'_>, digest_algorithm: Option<Oid<'_>>, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+		match signature_algorithm(algorithm, digest_algorithm, public_key) {
+			Some((algorithm, public_key)) => signature::UnparsedPublicKey::new(algorithm, public_key).verify(message, signature).is_ok(),
+			None => false,
+		}
+	}
+}
+
+// Picks the signature algorithm for the key and returns the key bits of the SubjectPublicKeyInfo
+fn signature_algorithm<'a>(algorithm: Oid<'_>, digest_algorithm: Option<Oid<'_>>, public_key: &'a [u8]) -> Option<(&'static dyn signature::VerificationAlgorithm, &'a [u8])> {
+	let mut spki = Reader::new(public_key).expect(der::SEQUENCE).ok()?.reader();
+	let mut key_algorithm = spki.expect(der::SEQUENCE).ok()?.reader();
+	let key_type = Oid::new(key_algorithm.expect(der::OID).ok()?.value);
+	let key = bit_string(spki.expect(der::BIT_STRING).ok()?).ok()?;
+
+	// Certificates name a combined algorithm, signers name the key type with a separate digest algorithm
+	let (scheme, digest) = match algorithm {
+		oid::SHA1_WITH_RSA_ENCRYPTION => (oid::RSA_ENCRYPTION, oid::SHA1),
+		oid::SHA256_WITH_RSA_ENCRYPTION => (oid::RSA_ENCRYPTION, oid::SHA256),
+		oid::SHA384_WITH_RSA_ENCRYPTION => (oid::RSA_ENCRYPTION, oid::SHA384),
+		oid::SHA512_WITH_RSA_ENCRYPTION => (oid::RSA_ENCRYPTION, oid::SHA512),
+		oid::ECDSA_WITH_SHA256 => (oid::EC_PUBLIC_KEY, oid::SHA256),
+		oid::ECDSA_WITH_SHA384 => (oid::EC_PUBLIC_KEY, oid::SHA384),
+		algorithm => (algorithm, digest_algorithm?),
+	};
+	if scheme != key_type {
+		return None;
+	}
+	let algorithm: &'static dyn signature::VerificationAlgorithm = match (scheme, digest) {
+		(oid::RSA_ENCRYPTION, oid::SHA1) => &signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+		(oid::RSA_ENCRYPTION, oid::SHA256) => &signature::RSA_PKCS1_2048_8192_SHA256,
+		(oid::RSA_ENCRYPTION, oid::SHA384) => &signature::RSA_PKCS1_2048_8192_SHA384,
+		(oid::RSA_ENCRYPTION, oid::SHA512) => &signature::RSA_PKCS1_2048_8192_SHA512,
+		(oid::EC_PUBLIC_KEY, _) => {
+			// The curve is named by the parameters of the key algorithm
+			let curve = Oid::new(key_algorithm.expect(der::OID).ok()?.value);
+			match (curve, digest) {
+				(oid::PRIME256V1, oid::SHA256) => &signature::ECDSA_P256_SHA256_ASN1,
+				(oid::PRIME256V1, oid::SHA384) => &signature::ECDSA_P256_SHA384_ASN1,
+				(oid::SECP384R1, oid::SHA256) => &signature::ECDSA_P384_SHA256_ASN1,
+				(oid::SECP384R1, oid::SHA384) => &signature::ECDSA_P384_SHA384_ASN1,
+				_ => return None,
+			}
+		},
+		_ => return None,
+	};
+	Some((algorithm, key))
+}
+
+//----------------------------------------------------------------
+
+/// Outcome of the verification.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Verdict {
+	/// The signature is valid and the signer's certificate chains up to a trust anchor.
+	Trusted,
+	/// The signature is valid but the signer's certificate does not chain up to any trust anchor.
+	Untrusted,
+	/// The image or the signed content does not match its digest, the image was modified after signing.
+	DigestMismatch,
+	/// The signature of the signer or one of the certificates in the chain does not check out.
+	BadSignature,
+	/// A certificate in the chain is not valid at the time of signing.
+	Expired,
+	/// A certificate in the chain is not allowed to sign, the issuer is not a certificate authority or its key usage does not allow the signature.
+	InvalidUsage,
+	/// The signer's certificate is not embedded in the signature.
+	MissingCertificate,
+	/// A digest algorithm is not supported by the verifier.
+	Unsupported,
+	/// The signature cannot be decoded.
+	Malformed(Error),
+}
+
+/// Result of the verification.
+#[derive(Clone, Debug)]
+pub struct Verification<'a> {
+	/// The outcome of the verification.
+	pub verdict: Verdict,
+	/// The certificate chain starting with the signer's certificate, as far as it was followed.
+	pub chain: Vec<Certificate<'a>>,
+	/// Index of the trust anchor the chain ends at.
+	pub anchor: Option<usize>,
+	/// The time of the verified timestamp counter-signature the certificates were checked at.
+	pub timestamp: Option<UtcDateTime>,
+	/// The outcome of verifying the timestamp counter-signature, `None` if the signature is not timestamped.
+	///
+	/// The certificates are checked at the given time instead if the timestamp is not trusted.
+	pub timestamp_verdict: Option<Verdict>,
+}
+impl<'a> Verification<'a> {
+	/// Returns true if the signature is valid and trusted.
+	pub fn is_trusted(&self) -> bool {
+		self.verdict == Verdict::Trusted
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Verifies the Authenticode signature against the trust anchors.
+///
+/// The trust anchors are encoded certificates, anchors which cannot be decoded are ignored.
+/// The certificates are checked at the time of the timestamp if the signature has a trusted timestamp, at the given time otherwise.
+pub fn verify<'a, V: Verifier + ?Sized>(security: &Security<'a>, verifier: &V, trust_anchors: &[&[u8]], time: UtcDateTime) -> Verification<'a> {
+	let mut verification = Verification { verdict: Verdict::Untrusted, chain: Vec::new(), anchor: None, timestamp: None, timestamp_verdict: None };
+	verification.verdict = match check(security, verifier, trust_anchors, time, &mut verification) {
+		Ok(verdict) => verdict,
+		Err(err) => Verdict::Malformed(err),
+	};
+	verification
+}
+
+fn check<'a, V: Verifier + ?Sized>(security: &Security<'a>, verifier: &V, trust_anchors: &[&[u8]], time: UtcDateTime, verification: &mut Verification<'a>) -> Result<Verdict> {
+	let signed_data = security.signed_data()?;
+
+	// The image digest
+	let (digest_algorithm, digest) = signed_data.indirect_data()?;
+	let mut message = Vec::new();
+	security.authenticode_digest(|bytes| message.push(bytes));
+	if let Some(verdict) = check_digest(verifier, digest_algorithm, &message, digest) {
+		return Ok(verdict);
+	}
+
+	// The signer's signature over the signed content
+	let signer = signed_data.signer_info().ok_or(Error::Invalid)?;
+	let certificate = match signer.find_certificate(signed_data.certificates()) {
+		Some(certificate) => *certificate,
+		None => return Ok(Verdict::MissingCertificate),
+	};
+	if let Some(verdict) = check_signer(verifier, signer, &certificate, signed_data.content())? {
+		return Ok(verdict);
+	}
+
+	// The time of signing from the timestamp counter-signature
+	let anchors: Vec<_> = trust_anchors.iter().map(|anchor| Certificate::parse(anchor).ok()).collect();
+	verification.timestamp_verdict = match signed_data.timestamp() {
+		Ok(timestamp) => {
+			let verdict = match check_timestamp(verifier, signer, &timestamp, signed_data.certificates(), &anchors) {
+				Ok(verdict) => verdict,
+				Err(err) => Verdict::Malformed(err),
+			};
+			if verdict == Verdict::Trusted {
+				verification.timestamp = Some(timestamp.signing_time);
+			}
+			Some(verdict)
+		},
+		Err(Error::Null) => None,
+		Err(err) => Some(Verdict::Malformed(err)),
+	};
+	let time = verification.timestamp.unwrap_or(time);
+
+	// The certificate chain
+	let (verdict, anchor) = check_chain(verifier, certificate, signed_data.certificates(), &anchors, time, &mut verification.chain);
+	verification.anchor = anchor;
+	Ok(verdict)
+}
+
+// Returns the verdict if the digest of the message doesn't match
+fn check_digest<V: Verifier + ?Sized>(verifier: &V, algorithm: Oid<'_>, message: &[&[u8]], digest: &[u8]) -> Option<Verdict> {
+	match verifier.digest(algorithm, message) {
+		Some(actual) if actual == digest => None,
+		Some(_) => Some(Verdict::DigestMismatch),
+		None => Some(Verdict::Unsupported),
+	}
+}
+
+// Returns the verdict if the message digest or the signature of the signer over its authenticated attributes doesn't check out
+fn check_signer<V: Verifier + ?Sized>(verifier: &V, signer: &SignerInfo<'_>, certificate: &Certificate<'_>, content: &[u8]) -> Result<Option<Verdict>> {
+	let message_digest = signer.message_digest().ok_or(Error::Invalid)?;
+	if let Some(verdict) = check_digest(verifier, signer.digest_algorithm(), &[content], message_digest) {
+		return Ok(Some(verdict));
+	}
+	let signed_attributes = signer.signed_attributes().ok_or(Error::Invalid)?;
+	if !verifier.verify_signature(signer.signature_algorithm(), Some(signer.digest_algorithm()), certificate.public_key(), &signed_attributes, signer.signature()) {
+		return Ok(Some(Verdict::BadSignature));
+	}
+	if certificate.key_usage().map_or(false, |bits| bits & key_usage::DIGITAL_SIGNATURE == 0) {
+		return Ok(Some(Verdict::InvalidUsage));
+	}
+	Ok(None)
+}
+
+fn check_timestamp<V: Verifier + ?Sized>(verifier: &V, signer: &SignerInfo<'_>, timestamp: &Timestamp<'_>, certificates: &[Certificate<'_>], anchors: &[Option<Certificate<'_>>]) -> Result<Verdict> {
+	// The timestamp must counter-sign this signature
+	if let Some((algorithm, digest)) = timestamp.imprint {
+		if let Some(verdict) = check_digest(verifier, algorithm, &[signer.signature()], digest) {
+			return Ok(verdict);
+		}
+	}
+	let certificate = match timestamp.certificate {
+		Some(certificate) => certificate,
+		None => return Ok(Verdict::MissingCertificate),
+	};
+	if let Some(verdict) = check_signer(verifier, &timestamp.signer_info, &certificate, timestamp.content)? {
+		return Ok(verdict);
+	}
+	let certificates: Vec<_> = timestamp.certificates.iter().chain(certificates).cloned().collect();
+	let (verdict, _) = check_chain(verifier, certificate, &certificates, anchors, timestamp.signing_time, &mut Vec::new());
+	Ok(verdict)
+}
+
+// Follows the issuers of the certificate up to a trust anchor
+fn check_chain<'a, V: Verifier + ?Sized>(verifier: &V, certificate: Certificate<'a>, certificates: &[Certificate<'a>], anchors: &[Option<Certificate<'_>>], time: UtcDateTime, chain: &mut Vec<Certificate<'a>>) -> (Verdict, Option<usize>) {
+	let mut explored = Vec::new();
+	search_chain(verifier, certificate, certificates, anchors, time, chain, &mut explored)
+}
+
+// Tries every issuer with a matching subject, renewed and cross-signed intermediates share their subject
+// Certificates which failed to chain up are not explored again, the chain ends with the last path tried
+fn search_chain<'a, V: Verifier + ?Sized>(verifier: &V, certificate: Certificate<'a>, certificates: &[Certificate<'a>], anchors: &[Option<Certificate<'_>>], time: UtcDateTime, chain: &mut Vec<Certificate<'a>>, explored: &mut Vec<Certificate<'a>>) -> (Verdict, Option<usize>) {
+	let is_signed_by = |certificate: &Certificate<'_>, issuer: &Certificate<'_>| {
+		certificate.issuer() == issuer.subject() &&
+		verifier.verify_signature(certificate.signature_algorithm(), None, issuer.public_key(), certificate.tbs_certificate(), certificate.signature())
+	};
+	chain.push(certificate);
+	explored.push(certificate);
+	if !certificate.is_valid_at(time) {
+		return (Verdict::Expired, None);
+	}
+	let anchor = anchors.iter().position(|anchor| match anchor {
+		Some(anchor) => anchor.as_bytes() == certificate.as_bytes() || is_signed_by(&certificate, anchor),
+		None => false,
+	});
+	if anchor.is_some() {
+		return (Verdict::Trusted, anchor);
+	}
+	if certificate.is_self_issued() {
+		return (Verdict::Untrusted, None);
+	}
+	let len = chain.len();
+	let mut verdict = Verdict::Untrusted;
+	for issuer in certificates.iter().filter(|issuer| issuer.subject() == certificate.issuer()) {
+		if explored.contains(issuer) {
+			continue;
+		}
+		if !is_signed_by(&certificate, issuer) {
+			verdict = Verdict::BadSignature;
+			continue;
+		}
+		if !issuer.is_ca() || issuer.key_usage().map_or(false, |bits| bits & key_usage::KEY_CERT_SIGN == 0) {
+			verdict = Verdict::InvalidUsage;
+			continue;
+		}
+		chain.truncate(len);
+		match search_chain(verifier, *issuer, certificates, anchors, time, chain, explored) {
+			(Verdict::Trusted, anchor) => return (Verdict::Trusted, anchor),
+			(issuer_verdict, _) => verdict = issuer_verdict,
+		}
+	}
+	(verdict, None)
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ring::rand::SystemRandom;
+	use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+	fn der(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+		let value = parts.concat();
+		let mut encoded = vec![tag, value.len() as u8];
+		encoded.extend_from_slice(&value);
+		encoded
+	}
+
+	#[test]
+	fn ring_digest() {
+		let digest = Ring.digest(oid::SHA256, &[b"a", b"bc"]).unwrap();
+		assert_eq!(digest, [
+			0xBA, 0x78, 0x16, 0xBF, 0x8F, 0x01, 0xCF, 0xEA, 0x41, 0x41, 0x40, 0xDE, 0x5D, 0xAE, 0x22, 0x23,
+			0xB0, 0x03, 0x61, 0xA3, 0x96, 0x17, 0x7A, 0x9C, 0xB4, 0x10, 0xFF, 0x61, 0xF2, 0x00, 0x15, 0xAD,
+		]);
+		assert_eq!(Ring.digest(oid::RSA_ENCRYPTION, &[b"abc"]), None);
+	}
+
+	#[test]
+	fn ring_ecdsa() {
+		let rng = SystemRandom::new();
+		let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+		let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap();
+		let public_key = der(0x30, &[
+			&der(0x30, &[&der(0x06, &[oid::EC_PUBLIC_KEY.as_bytes()]), &der(0x06, &[oid::PRIME256V1.as_bytes()])]),
+			&der(0x03, &[&[0], key_pair.public_key().as_ref()]),
+		]);
+		let signature = key_pair.sign(&rng, b"message").unwrap();
+		let signature = signature.as_ref();
+
+		// Certificates name the combined algorithm, signers name the key type
+		assert!(Ring.verify_signature(oid::ECDSA_WITH_SHA256, None, &public_key, b"message", signature));
+		assert!(Ring.verify_signature(oid::EC_PUBLIC_KEY, Some(oid::SHA256), &public_key, b"message", signature));
+		assert!(!Ring.verify_signature(oid::ECDSA_WITH_SHA256, None, &public_key, b"massage", signature));
+		assert!(!Ring.verify_signature(oid::ECDSA_WITH_SHA384, None, &public_key, b"message", signature));
+		assert!(!Ring.verify_signature(oid::SHA256_WITH_RSA_ENCRYPTION, None, &public_key, b"message", signature));
+		assert!(!Ring.verify_signature(oid::EC_PUBLIC_KEY, None, &public_key, b"message", signature));
+	}
+}
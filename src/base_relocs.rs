@@ -20,6 +20,11 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 	// Iterate over the rva which need relocation
 	// Padding relocations of type absolute are skipped
 	base_relocs.for_each(|rva, ty| {});
+	for (rva, ty) in base_relocs.iter() {}
+
+	// Query the relocations within an address range
+	let relocated = base_relocs.contains(0x1000);
+	for (rva, ty) in base_relocs.range(0x1000..0x2000) {}
 
 	// Iterate over the relocation blocks
 	for block in base_relocs.iter_blocks() {}
@@ -29,11 +34,12 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 ```
  */
 
+use std::collections::BTreeMap;
 use std::prelude::v1::*;
-use std::{cmp, fmt, iter, mem, slice};
+use std::{cmp, fmt, iter, mem, ops, slice};
 
-use crate::image::{IMAGE_BASE_RELOCATION, IMAGE_REL_BASED_ABSOLUTE};
-use crate::util::{extend_in_place, AlignTo};
+use crate::image::*;
+use crate::util::{extend_in_place, AlignTo, Le};
 use crate::{Error, Result};
 
 /// Base Relocations Directory.
@@ -67,6 +73,26 @@ impl<'a> BaseRelocs<'a> {
 	pub fn iter_blocks(&self) -> IterBlocks<'a> {
 		IterBlocks { data: self.relocs }
 	}
+	/// Iterates over the rvas and types of the base relocations.
+	///
+	/// Padding relocations of type absolute are skipped.
+	pub fn iter(&self) -> impl 'a + Clone + Iterator<Item = (u32, RelocType)> {
+		self.iter_blocks().flat_map(|block| block.iter())
+	}
+	/// Returns true if the rva is relocated.
+	pub fn contains(&self, rva: u32) -> bool {
+		self.range(rva..rva.saturating_add(1)).next().is_some()
+	}
+	/// Iterates over the rvas and types of the base relocations within the range.
+	///
+	/// Only the blocks whose page overlaps the range are inspected.
+	pub fn range(&self, range: ops::Range<u32>) -> impl 'a + Clone + Iterator<Item = (u32, RelocType)> {
+		let ops::Range { start, end } = range;
+		self.iter_blocks()
+			.filter(move |block| block.image.VirtualAddress.le() < end && block.image.VirtualAddress.le().saturating_add(0x1000) > start)
+			.flat_map(|block| block.iter())
+			.filter(move |&(rva, _)| start <= rva && rva < end)
+	}
 	/// Iterates over the base relocations with internal iteration.
 	pub fn for_each<F: FnMut(u32, u8)>(&self, mut f: F) {
 		self.fold((), |(), rva, ty| f(rva, ty))
@@ -108,7 +134,7 @@ impl<'a> IterBlocks<'a> {
 				let image_p = self.data.as_ptr() as *const IMAGE_BASE_RELOCATION;
 				let image = &*image_p;
 				// Calculate the number of words following the base relocation carefully
-				let len = cmp::min(image.SizeOfBlock as usize, self.data.len()).saturating_sub(mem::size_of::<IMAGE_BASE_RELOCATION>()) / 2;
+				let len = cmp::min(image.SizeOfBlock.le() as usize, self.data.len()).saturating_sub(mem::size_of::<IMAGE_BASE_RELOCATION>()) / 2;
 				let words = slice::from_raw_parts(image_p.offset(1) as *const u16, len);
 				Block { image, words }
 			})
@@ -122,13 +148,14 @@ impl<'a> Iterator for IterBlocks<'a> {
 	type Item = Block<'a>;
 	fn next(&mut self) -> Option<Block<'a>> {
 		if let Some(block) = self.peek() {
-			let block_size = block.image.SizeOfBlock;
+			let block_size = block.image.SizeOfBlock.le();
 			// Avoid infinite loop by skipping at least the image base relocation header
 			let block_size = cmp::max(block_size, mem::size_of::<IMAGE_BASE_RELOCATION>() as u32);
 			// Ensure that the data pointer remains dword aligned $1
-			let block_size = block_size.align_to(4);
+			// Clamp to the data size first so the alignment cannot wrap around to zero
+			let block_size = cmp::min(block_size as usize, self.data.len()).align_to(4);
 			// Clamp the length to the data size
-			let block_size = cmp::min(block_size as usize, self.data.len());
+			let block_size = cmp::min(block_size, self.data.len());
 			self.data = &self.data[block_size..];
 			Some(block)
 		}
@@ -158,19 +185,25 @@ impl<'a> Block<'a> {
 	}
 	/// Gets the final Rva of a type-offset word.
 	pub fn rva_of(&self, word: &u16) -> u32 {
-		let offset = (word & 0x0fff) as u32;
-		self.image.VirtualAddress.wrapping_add(offset)
+		let offset = ((*word).le() & 0x0fff) as u32;
+		self.image.VirtualAddress.le().wrapping_add(offset)
 	}
 	/// Gets the type of a type-offset word.
 	pub fn type_of(&self, word: &u16) -> u8 {
-		(word >> 12) as u8
+		((*word).le() >> 12) as u8
+	}
+	/// Iterates over the rvas and types of the relocations in this block.
+	///
+	/// Padding relocations of type absolute are skipped and the parameter of high adjust relocations is consumed.
+	pub fn iter(&self) -> Iter<'a> {
+		Iter { block: *self, words: self.words.iter() }
 	}
 }
 #[rustfmt::skip]
 impl<'a> fmt::Debug for Block<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.debug_struct("Block")
-			.field("virtual_address", &format_args!("{:#x?}", self.image.VirtualAddress))
+			.field("virtual_address", &format_args!("{:#x?}", self.image.VirtualAddress.le()))
 			.field("words.len", &self.words().len())
 			.finish()
 	}
@@ -178,6 +211,95 @@ impl<'a> fmt::Debug for Block<'a> {
 
 //----------------------------------------------------------------
 
+/// Iterator over the relocations in a block.
+#[derive(Clone)]
+pub struct Iter<'a> {
+	block: Block<'a>,
+	words: slice::Iter<'a, u16>,
+}
+impl<'a> Iterator for Iter<'a> {
+	type Item = (u32, RelocType);
+	fn next(&mut self) -> Option<(u32, RelocType)> {
+		loop {
+			let word = self.words.next()?;
+			let ty = RelocType::from(self.block.type_of(word));
+			match ty {
+				RelocType::Absolute => continue,
+				// The next word holds the low half of the adjustment, not a type-offset
+				RelocType::HighAdj => {
+					self.words.next();
+				},
+				_ => (),
+			}
+			return Some((self.block.rva_of(word), ty));
+		}
+	}
+}
+impl<'a> iter::FusedIterator for Iter<'a> {}
+
+//----------------------------------------------------------------
+
+/// Base relocation type.
+///
+/// See the `IMAGE_REL_BASED_*` constants.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RelocType {
+	/// Padding, no relocation is applied.
+	Absolute,
+	/// Adds the high 16 bits of the delta to the 16-bit field.
+	High,
+	/// Adds the low 16 bits of the delta to the 16-bit field.
+	Low,
+	/// Adds the delta to the 32-bit field.
+	HighLow,
+	/// Adds the high 16 bits of the delta to the 16-bit field, adjusted by the low half stored in the next entry.
+	HighAdj,
+	/// Adds the delta to the 64-bit field.
+	Dir64,
+	/// Machine specific or reserved relocation type.
+	Other(u8),
+}
+impl RelocType {
+	/// Returns the number of bytes patched by the relocation, zero if unknown.
+	pub fn size_of(self) -> usize {
+		match self {
+			RelocType::Absolute => 0,
+			RelocType::High | RelocType::Low | RelocType::HighAdj => 2,
+			RelocType::HighLow => 4,
+			RelocType::Dir64 => 8,
+			RelocType::Other(_) => 0,
+		}
+	}
+}
+impl From<u8> for RelocType {
+	fn from(ty: u8) -> RelocType {
+		match ty {
+			IMAGE_REL_BASED_ABSOLUTE => RelocType::Absolute,
+			IMAGE_REL_BASED_HIGH => RelocType::High,
+			IMAGE_REL_BASED_LOW => RelocType::Low,
+			IMAGE_REL_BASED_HIGHLOW => RelocType::HighLow,
+			IMAGE_REL_BASED_HIGHADJ => RelocType::HighAdj,
+			IMAGE_REL_BASED_DIR64 => RelocType::Dir64,
+			ty => RelocType::Other(ty),
+		}
+	}
+}
+impl From<RelocType> for u8 {
+	fn from(ty: RelocType) -> u8 {
+		match ty {
+			RelocType::Absolute => IMAGE_REL_BASED_ABSOLUTE,
+			RelocType::High => IMAGE_REL_BASED_HIGH,
+			RelocType::Low => IMAGE_REL_BASED_LOW,
+			RelocType::HighLow => IMAGE_REL_BASED_HIGHLOW,
+			RelocType::HighAdj => IMAGE_REL_BASED_HIGHADJ,
+			RelocType::Dir64 => IMAGE_REL_BASED_DIR64,
+			RelocType::Other(ty) => ty,
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
 /*
 	"base_relocs": {
 		"rvas": [1000, 1002, 1018, 2048, 2498],
@@ -223,11 +345,10 @@ pub fn build(mut rvas: &[u32], mut types: &[u8]) -> Vec<u8> {
 	while rvas.len() > 0 {
 		// Given RVA range for the relocation block
 		let start = rvas[0] & !0x0fff;
-		let end = start + 0x0fff;
 
 		// Figure the number of rvas to fit in this block
 		let mut n = 0;
-		while n < rvas.len() && rvas[n] >= start && rvas[n] < end {
+		while n < rvas.len() && rvas[n] >= start && rvas[n] - start < 0x1000 {
 			n += 1;
 		}
 
@@ -238,14 +359,14 @@ pub fn build(mut rvas: &[u32], mut types: &[u8]) -> Vec<u8> {
 			extend_in_place(&mut result, size, |bytes| {
 				// Encode the relocation block header
 				let block_ptr = bytes.as_mut_ptr() as *mut IMAGE_BASE_RELOCATION;
-				(*block_ptr).VirtualAddress = start;
-				(*block_ptr).SizeOfBlock = size as u32;
+				(*block_ptr).VirtualAddress = start.le();
+				(*block_ptr).SizeOfBlock = (size as u32).le();
 				// Encode the type and offsets
 				let words = slice::from_raw_parts_mut(block_ptr.offset(1) as *mut u16, n.align_to(2));
 				for i in 0..n {
 					let rva = *rvas.get_unchecked(i);
 					let ty = *types.get_unchecked(i);
-					words[i] = encode_type_offset(start, rva, ty);
+					words[i] = encode_type_offset(start, rva, ty).le();
 				}
 				// Add alignment padding
 				if n < words.len() {
@@ -260,6 +381,91 @@ pub fn build(mut rvas: &[u32], mut types: &[u8]) -> Vec<u8> {
 	result
 }
 
+//----------------------------------------------------------------
+
+/// Base relocations builder.
+///
+/// Collects the relocations sorted by rva, at most one per rva, and emits the base relocations directory.
+/// Use it to update the relocations when adding or overwriting code.
+///
+/// ```
+/// use pelite::base_relocs::{BaseRelocs, Builder, RelocType};
+///
+/// let mut builder = Builder::new();
+/// builder.insert(0x2008, RelocType::Dir64);
+/// builder.insert(0x1000, RelocType::Dir64);
+/// builder.insert(0x1ff8, RelocType::Dir64);
+///
+/// // One block for every page, each 4-byte aligned with absolute padding
+/// let image = builder.build();
+/// assert_eq!(image.len(), 0xc + 0xc);
+///
+/// let base_relocs = BaseRelocs::parse(&image).unwrap();
+/// let relocs: Vec<_> = base_relocs.iter().map(|(rva, _)| rva).collect();
+/// assert_eq!(relocs, [0x1000, 0x1ff8, 0x2008]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Builder {
+	relocs: BTreeMap<u32, RelocType>,
+}
+impl Builder {
+	/// Creates an empty builder.
+	pub fn new() -> Builder {
+		Builder::default()
+	}
+	/// Creates a builder with the relocations of an existing directory.
+	pub fn from_base_relocs(base_relocs: &BaseRelocs<'_>) -> Builder {
+		Builder { relocs: base_relocs.iter().collect() }
+	}
+	/// Returns the number of relocations.
+	pub fn len(&self) -> usize {
+		self.relocs.len()
+	}
+	/// Returns true if there are no relocations.
+	pub fn is_empty(&self) -> bool {
+		self.relocs.is_empty()
+	}
+	/// Inserts a relocation, returns the type of the relocation it replaced.
+	///
+	/// Absolute relocations are padding and high adjust relocations require a parameter, both are ignored.
+	pub fn insert(&mut self, rva: u32, ty: RelocType) -> Option<RelocType> {
+		match ty {
+			RelocType::Absolute | RelocType::HighAdj => None,
+			_ => self.relocs.insert(rva, ty),
+		}
+	}
+	/// Removes the relocation at the rva.
+	pub fn remove(&mut self, rva: u32) -> Option<RelocType> {
+		self.relocs.remove(&rva)
+	}
+	/// Removes the relocations within the range of rvas, eg. when overwriting code.
+	pub fn remove_range(&mut self, range: ops::Range<u32>) {
+		let rvas: Vec<u32> = self.relocs.range(range).map(|(&rva, _)| rva).collect();
+		for rva in rvas {
+			self.relocs.remove(&rva);
+		}
+	}
+	/// Iterates over the relocations sorted by rva.
+	pub fn iter(&self) -> impl '_ + Clone + Iterator<Item = (u32, RelocType)> {
+		self.relocs.iter().map(|(&rva, &ty)| (rva, ty))
+	}
+	/// Emits the base relocations directory.
+	///
+	/// Every block covers a page, its size is a multiple of 4 with an absolute relocation as padding.
+	pub fn build(&self) -> Vec<u8> {
+		let rvas: Vec<u32> = self.relocs.keys().cloned().collect();
+		let types: Vec<u8> = self.relocs.values().map(|&ty| ty.into()).collect();
+		build(&rvas, &types)
+	}
+}
+impl Extend<(u32, RelocType)> for Builder {
+	fn extend<I: IntoIterator<Item = (u32, RelocType)>>(&mut self, iter: I) {
+		for (rva, ty) in iter {
+			self.insert(rva, ty);
+		}
+	}
+}
+
 #[cfg(windows)]
 #[test]
 fn test_build_self() {
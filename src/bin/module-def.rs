@@ -19,6 +19,13 @@ Also works for 32-bit binaries using the 32-bit VC build tools and commands.
 ```bat
 vcvarsall x86
 lib /def:"demo\Demo.DEF" /out:"demo\Demo.LIB" /machine:x86
+```
+
+Alternatively scaffold Rust FFI declarations or a C header from the exports.
+
+```bat
+cargo run --bin module-def -- --rust "demo\Demo64.dll" > "demo\demo64.rs"
+cargo run --bin module-def -- --c "demo\Demo64.dll" > "demo\Demo64.h"
 ```
  */
 
@@ -34,16 +41,36 @@ To create an import library run the following command afterwards:
 
 Usage:
     module-def "MODULE.DLL" > "MODULE.DEF"
+    module-def --rust "MODULE.DLL" > "MODULE.rs"
+    module-def --c "MODULE.DLL" > "MODULE.h"
 "#;
 
+#[derive(Copy, Clone)]
+enum Output {
+	Def,
+	Rust,
+	C,
+}
+
 fn main() {
-	let mut args = env::args_os();
-	if let (Some(_), Some(dll), None) = (args.next(), args.next(), args.next()) {
-		match pelite::FileMap::open(&dll) {
+	let mut args: Vec<_> = env::args_os().skip(1).collect();
+	let output = match args.first().and_then(|arg| arg.to_str()) {
+		Some("--rust") => Output::Rust,
+		Some("--c") => Output::C,
+		_ => Output::Def,
+	};
+	if let Output::Rust | Output::C = output {
+		args.remove(0);
+	}
+	if let [dll] = &args[..] {
+		match pelite::FileMap::open(dll) {
 			Ok(map) => {
 				// Try PE32 and PE32+
 				let result = match pelite::PeFile::from_bytes(&map) {
-					Ok(pe) => lib(pe),
+					Ok(pe) => match output {
+						Output::Def => lib(pe),
+						Output::Rust | Output::C => stubs(pe, output),
+					},
 					Err(err) => Err(err),
 				};
 				// Display errors
@@ -73,3 +100,18 @@ fn lib(pe: pelite::PeFile) -> pelite::Result<()> {
 
 	Ok(())
 }
+
+fn stubs(pe: pelite::PeFile, output: Output) -> pelite::Result<()> {
+	let text = match pe {
+		pelite::Wrap::T32(pe) => {
+			let stubs = pelite::pe32::stubs::Stubs::new(pe)?;
+			if let Output::Rust = output { stubs.to_rust() } else { stubs.to_c_header() }
+		},
+		pelite::Wrap::T64(pe) => {
+			let stubs = pelite::pe64::stubs::Stubs::new(pe)?;
+			if let Output::Rust = output { stubs.to_rust() } else { stubs.to_c_header() }
+		},
+	};
+	print!("{}", text);
+	Ok(())
+}
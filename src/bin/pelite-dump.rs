@@ -0,0 +1,215 @@
+/*!
+Dumps the contents of PE files as text or JSON.
+
+Requires the `cli` feature:
+
+```text
+cargo install pelite --features cli
+pelite-dump demo/Demo64.dll --exports --imports
+pelite-dump demo/Demo64.dll --json > Demo64.json
+```
+
+Exercises most of the library, the source doubles as a reference for its API.
+ */
+
+use std::fmt;
+use std::path::PathBuf;
+use std::{env, process};
+
+use pelite::resources::version_info::VersionInfo;
+use pelite::resources::{FindError, Resources};
+use pelite::{FileMap, PeFile, Wrap};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+//----------------------------------------------------------------
+
+const HELP_TEXT: &str = "\
+NAME:
+  pelite-dump - Dump the contents of PE files.
+
+SYNOPSIS:
+  pelite-dump FILEPATH [--json] [SECTION]...
+
+DESCRIPTION:
+  Prints the selected sections of the file, or all of them if none are selected.
+
+OPTIONS:
+  --json
+      Print a single JSON object with a key for every section.
+
+SECTIONS:
+  --headers       The DOS and NT headers.
+  --sections      The section headers.
+  --exports       The export directory.
+  --imports       The import directory.
+  --resources     The resource directory.
+  --version       The version info resource.
+  --debug         The debug directory.
+  --security      The certificate table.
+  --rich          The rich structure.
+";
+
+const SEPARATOR: &str = "----------------------------------------------------------------\n";
+
+#[derive(Default)]
+struct Options {
+	path: PathBuf,
+	json: bool,
+	headers: bool,
+	sections: bool,
+	exports: bool,
+	imports: bool,
+	resources: bool,
+	version: bool,
+	debug: bool,
+	security: bool,
+	rich: bool,
+}
+
+impl Options {
+	fn parse() -> Options {
+		let mut options = Options::default();
+		let mut path = None;
+		for arg in env::args_os().skip(1) {
+			match arg.to_str() {
+				Some("-h") | Some("--help") => {
+					print!("{}", HELP_TEXT);
+					process::exit(0);
+				},
+				Some("--json") => options.json = true,
+				Some("--headers") => options.headers = true,
+				Some("--sections") => options.sections = true,
+				Some("--exports") => options.exports = true,
+				Some("--imports") => options.imports = true,
+				Some("--resources") => options.resources = true,
+				Some("--version") => options.version = true,
+				Some("--debug") => options.debug = true,
+				Some("--security") => options.security = true,
+				Some("--rich") => options.rich = true,
+				Some(arg) if arg.starts_with('-') => abort(&format!("unknown option {}", arg)),
+				_ if path.is_none() => path = Some(PathBuf::from(arg)),
+				_ => abort("more than one input file given"),
+			}
+		}
+		options.path = path.unwrap_or_else(|| {
+			print!("{}", HELP_TEXT);
+			process::exit(0);
+		});
+		let Options { headers, sections, exports, imports, resources, version, debug, security, rich, .. } = options;
+		if !(headers || sections || exports || imports || resources || version || debug || security || rich) {
+			options = Options { headers: true, sections: true, exports: true, imports: true, resources: true, version: true, debug: true, security: true, rich: true, ..options };
+		}
+		options
+	}
+}
+
+fn abort(message: &str) -> ! {
+	eprintln!("pelite-dump: {}.", message);
+	process::exit(1);
+}
+
+//----------------------------------------------------------------
+
+/// Collects the sections as text or JSON.
+struct Output {
+	json: Option<Map<String, Value>>,
+}
+
+impl Output {
+	/// Adds a section, directories which are not present are `None`.
+	fn section<T: Serialize + fmt::Debug>(&mut self, name: &str, value: Result<Option<T>, String>) {
+		match (&mut self.json, value) {
+			(Some(json), Ok(value)) => {
+				let value = serde_json::to_value(&value).unwrap_or_else(|err| Value::String(err.to_string()));
+				json.insert(name.to_string(), value);
+			},
+			(Some(json), Err(err)) => {
+				let mut error = Map::new();
+				error.insert("error".to_string(), Value::String(err));
+				json.insert(name.to_string(), Value::Object(error));
+			},
+			(None, Ok(Some(value))) => print!("{}{}:\n{:#?}\n", SEPARATOR, name, value),
+			(None, Ok(None)) => print!("{}{}: not present\n", SEPARATOR, name),
+			(None, Err(err)) => print!("{}{}: {}\n", SEPARATOR, name, err),
+		}
+	}
+	fn finish(self) {
+		if let Some(json) = self.json {
+			match serde_json::to_string_pretty(&json) {
+				Ok(json) => println!("{}", json),
+				Err(err) => abort(&err.to_string()),
+			}
+		}
+	}
+}
+
+fn optional<T>(result: pelite::Result<T>) -> Result<Option<T>, String> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(pelite::Error::Null) => Ok(None),
+		Err(err) => Err(err.to_string()),
+	}
+}
+
+fn version_info<'a>(resources: pelite::Result<Resources<'a>>) -> Result<Option<VersionInfo<'a>>, String> {
+	match optional(resources)? {
+		Some(resources) => match resources.version_info() {
+			Ok(version_info) => Ok(Some(version_info)),
+			Err(FindError::NotFound) => Ok(None),
+			Err(err) => Err(err.to_string()),
+		},
+		None => Ok(None),
+	}
+}
+
+// The directories of both formats share their api but not their types
+macro_rules! dump {
+	($name:ident, $pe:ident) => {
+		fn $name(options: &Options, file: pelite::$pe::PeFile<'_>, output: &mut Output) {
+			use pelite::$pe::Pe;
+			if options.headers {
+				output.section("dos_header", Ok(Some(file.dos_header())));
+				output.section("nt_headers", Ok(Some(file.nt_headers())));
+			}
+			if options.sections {
+				output.section("sections", Ok(Some(file.section_headers())));
+			}
+			if options.exports {
+				output.section("exports", optional(file.exports()));
+			}
+			if options.imports {
+				output.section("imports", optional(file.imports()));
+			}
+			if options.resources {
+				output.section("resources", optional(file.resources()));
+			}
+			if options.version {
+				output.section("version", version_info(file.resources()));
+			}
+			if options.debug {
+				output.section("debug", optional(file.debug()));
+			}
+			if options.security {
+				output.section("security", optional(file.security()));
+			}
+			if options.rich {
+				output.section("rich_structure", optional(file.rich_structure()));
+			}
+		}
+	};
+}
+dump!(dump_pe32, pe32);
+dump!(dump_pe64, pe64);
+
+fn main() {
+	let options = Options::parse();
+	let map = FileMap::open(&options.path).unwrap_or_else(|err| abort(&err.to_string()));
+	let mut output = Output { json: if options.json { Some(Map::new()) } else { None } };
+	match PeFile::from_bytes(&map) {
+		Ok(Wrap::T32(file)) => dump_pe32(&options, file, &mut output),
+		Ok(Wrap::T64(file)) => dump_pe64(&options, file, &mut output),
+		Err(err) => abort(&err.to_string()),
+	}
+	output.finish();
+}
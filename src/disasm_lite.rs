@@ -0,0 +1,403 @@
+/*!
+Minimal x86 and x86-64 instruction length decoder.
+
+Decodes just enough of the instruction encoding to find the instruction boundaries and their relative operands, there are no semantics.
+Following jumps, finding cross references and placing hooks only need to know where the instructions start and what they are relative to.
+
+The opcode maps are table driven with the legacy instructions invalid in 64-bit mode decoded separately.
+Legacy, REX, VEX and EVEX prefixes are supported, XOP prefixes are not.
+
+# Examples
+
+```
+use pelite::disasm_lite::{decode, iter, Mode, RelKind};
+
+// call rel32; mov rax, [rip+0x10]; ret
+let code = [0xE8, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00, 0xC3];
+
+let call = decode(&code, Mode::X64).unwrap();
+assert_eq!(call.len, 5);
+assert_eq!(call.rel.unwrap().kind, RelKind::Branch);
+assert_eq!(call.target(0x1000), Some(0x1015));
+
+let lens: Vec<usize> = iter(&code, Mode::X64).map(|(_, insn)| insn.len).collect();
+assert_eq!(lens, [5, 7, 1]);
+```
+*/
+
+use std::iter;
+
+//----------------------------------------------------------------
+
+/// Processor mode.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Mode {
+	/// 32-bit protected mode.
+	X86,
+	/// 64-bit long mode.
+	X64,
+}
+
+/// Kind of relative operand.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RelKind {
+	/// Relative branch, eg. `jmp`, `call` and `jcc`.
+	Branch,
+	/// Rip relative memory operand, only in 64-bit mode.
+	Memory,
+}
+
+/// Relative operand.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Rel {
+	/// Offset of the displacement in the instruction.
+	pub offset: usize,
+	/// Size of the displacement in bytes.
+	pub size: usize,
+	/// The sign extended displacement.
+	pub disp: i32,
+	/// Kind of relative operand.
+	pub kind: RelKind,
+}
+
+/// Decoded instruction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Insn {
+	/// Length of the instruction in bytes.
+	pub len: usize,
+	/// The relative operand if any.
+	pub rel: Option<Rel>,
+}
+impl Insn {
+	/// Returns the target of the relative operand given the address of the instruction.
+	///
+	/// The displacement is relative to the end of the instruction.
+	pub fn target(&self, address: u64) -> Option<u64> {
+		let rel = self.rel?;
+		Some(address.wrapping_add(self.len as u64).wrapping_add(rel.disp as i64 as u64))
+	}
+}
+
+//----------------------------------------------------------------
+
+// Flags describing the operands following the opcode
+const N: u8 = 0x00; // No operands
+const M: u8 = 0x01; // ModRM byte
+const I8: u8 = 0x02; // 8-bit immediate
+const I16: u8 = 0x04; // 16-bit immediate
+const IZ: u8 = 0x08; // 16 or 32-bit immediate depending on the operand size
+const R8: u8 = 0x10; // 8-bit relative branch
+const RZ: u8 = 0x20; // 32-bit relative branch
+const S: u8 = 0x40; // Prefixes, escapes and opcodes with special operands
+const X: u8 = 0x80; // Invalid in 64-bit mode, see the legacy table
+
+#[rustfmt::skip]
+static ONE_BYTE: [u8; 256] = [
+	//  0     1     2     3     4     5     6     7     8     9     A     B     C     D     E     F
+	    M,    M,    M,    M,   I8,   IZ,    X,    X,    M,    M,    M,    M,   I8,   IZ,    X,    S, // 0
+	    M,    M,    M,    M,   I8,   IZ,    X,    X,    M,    M,    M,    M,   I8,   IZ,    X,    X, // 1
+	    M,    M,    M,    M,   I8,   IZ,    S,    X,    M,    M,    M,    M,   I8,   IZ,    S,    X, // 2
+	    M,    M,    M,    M,   I8,   IZ,    S,    X,    M,    M,    M,    M,   I8,   IZ,    S,    X, // 3
+	    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S,    S, // 4
+	    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    N, // 5
+	    X,    X,    S,    M,    S,    S,    S,    S,   IZ, M|IZ,   I8, M|I8,    N,    N,    N,    N, // 6
+	   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8,   R8, // 7
+	 M|I8, M|IZ,    X, M|I8,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 8
+	    N,    N,    N,    N,    N,    N,    N,    N,    N,    N,    X,    N,    N,    N,    N,    N, // 9
+	    S,    S,    S,    S,    N,    N,    N,    N,   I8,   IZ,    N,    N,    N,    N,    N,    N, // A
+	   I8,   I8,   I8,   I8,   I8,   I8,   I8,   I8,    S,    S,    S,    S,    S,    S,    S,    S, // B
+	 M|I8, M|I8,  I16,    N,    S,    S, M|I8, M|IZ,I16|I8,   N,  I16,    N,    N,   I8,    X,    N, // C
+	    M,    M,    M,    M,    X,    X,    X,    N,    M,    M,    M,    M,    M,    M,    M,    M, // D
+	   R8,   R8,   R8,   R8,   I8,   I8,   I8,   I8,   RZ,   RZ,    X,   R8,    N,    N,    N,    N, // E
+	    S,    N,    S,    S,    N,    N,    S,    S,    N,    N,    N,    N,    N,    N,    M,    M, // F
+];
+
+#[rustfmt::skip]
+static TWO_BYTE: [u8; 256] = [
+	//  0     1     2     3     4     5     6     7     8     9     A     B     C     D     E     F
+	    M,    M,    M,    M,    X,    N,    N,    N,    N,    N,    X,    N,    X,    M,    N, M|I8, // 0
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 1
+	    M,    M,    M,    M,    X,    X,    X,    X,    M,    M,    M,    M,    M,    M,    M,    M, // 2
+	    N,    N,    N,    N,    N,    N,    X,    N,    S,    X,    S,    X,    X,    X,    X,    X, // 3
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 4
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 5
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 6
+	 M|I8, M|I8, M|I8, M|I8,    M,    M,    M,    N,    M,    M,    X,    X,    M,    M,    M,    M, // 7
+	   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ,   RZ, // 8
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // 9
+	    N,    N,    N,    M, M|I8,    M,    X,    X,    N,    N,    N,    M, M|I8,    M,    M,    M, // A
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, M|I8,    M,    M,    M,    M,    M, // B
+	    M,    M, M|I8,    M, M|I8, M|I8, M|I8,    M,    N,    N,    N,    N,    N,    N,    N,    N, // C
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // D
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // E
+	    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M,    M, // F
+];
+
+// Operands of the opcodes marked invalid in the one byte table when decoded in 32-bit mode
+fn legacy(opcode: u8) -> Option<u8> {
+	match opcode {
+		0x06 | 0x07 | 0x0E | 0x16 | 0x17 | 0x1E | 0x1F | 0x27 | 0x2F | 0x37 | 0x3F | 0x60 | 0x61 | 0xCE | 0xD6 => Some(N),
+		0x82 => Some(M | I8),
+		0xD4 | 0xD5 => Some(I8),
+		_ => None,
+	}
+}
+
+// The architectural limit on the length of an instruction
+const MAX_LEN: usize = 15;
+
+/// Decodes the instruction at the start of the bytes.
+///
+/// Returns `None` if the instruction is invalid or truncated.
+pub fn decode(bytes: &[u8], mode: Mode) -> Option<Insn> {
+	let bytes = &bytes[..bytes.len().min(MAX_LEN)];
+	let x64 = mode == Mode::X64;
+	let mut i = 0;
+	let mut operand_size = false;
+	let mut address_size = false;
+	let mut rex_w = false;
+
+	// Legacy prefixes followed by an optional REX prefix
+	loop {
+		match *bytes.get(i)? {
+			0x66 => operand_size = true,
+			0x67 => address_size = true,
+			0x26 | 0x2E | 0x36 | 0x3E | 0x64 | 0x65 | 0xF0 | 0xF2 | 0xF3 => (),
+			_ => break,
+		}
+		i += 1;
+	}
+	if let (true, 0x40..=0x4F) = (x64, *bytes.get(i)?) {
+		rex_w = bytes[i] & 0x08 != 0;
+		i += 1;
+	}
+	// Addresses are 16-bit in 32-bit mode with the address size prefix
+	let address16 = !x64 && address_size;
+
+	let opcode = *bytes.get(i)?;
+	i += 1;
+	let flags = match opcode {
+		// Two and three byte opcodes
+		0x0F => {
+			let opcode = *bytes.get(i)?;
+			i += 1;
+			match opcode {
+				0x38 => { i += 1; M },
+				0x3A => { i += 1; M | I8 },
+				_ => TWO_BYTE[opcode as usize],
+			}
+		},
+		// In 32-bit mode these are the VEX and EVEX prefixes only if the next byte would be a register ModRM byte
+		0xC4 | 0xC5 | 0x62 if !x64 && *bytes.get(i)? < 0xC0 => M,
+		// VEX and EVEX prefixes with an implied or explicit opcode map
+		0xC4 | 0xC5 | 0x62 => {
+			let map = match opcode {
+				0xC5 => 1,
+				0xC4 => *bytes.get(i)? & 0x1F,
+				_ => *bytes.get(i)? & 0x07,
+			};
+			i += match opcode { 0xC5 => 1, 0xC4 => 2, _ => 3 };
+			let opcode = *bytes.get(i)?;
+			i += 1;
+			match map {
+				1 => TWO_BYTE[opcode as usize] & !(R8 | RZ),
+				2 | 5 | 6 => M,
+				3 => M | I8,
+				_ => return None,
+			}
+		},
+		// Inc and dec in 32-bit mode
+		0x40..=0x4F if !x64 => N,
+		// Moffs with the size of an address
+		0xA0..=0xA3 => {
+			i += match (x64, address_size) { (true, false) => 8, (false, true) => 2, _ => 4 };
+			N
+		},
+		// Mov with a 64-bit immediate
+		0xB8..=0xBF if rex_w => { i += 8; N },
+		0xB8..=0xBF => IZ,
+		// Test has an immediate, the other group 3 instructions don't
+		0xF6 | 0xF7 => {
+			let reg = (*bytes.get(i)? >> 3) & 7;
+			let imm = if opcode == 0xF6 { I8 } else { IZ };
+			if reg < 2 { M | imm } else { M }
+		},
+		// Far call and jmp with an immediate segment and offset
+		0x9A | 0xEA if !x64 => { i += if operand_size { 4 } else { 6 }; N },
+		_ => match ONE_BYTE[opcode as usize] {
+			X if !x64 => legacy(opcode)?,
+			flags => flags,
+		},
+	};
+	if flags & (S | X) != 0 {
+		return None;
+	}
+
+	let mut rel = None;
+	if flags & M != 0 {
+		let modrm = *bytes.get(i)?;
+		i += 1;
+		let md = modrm >> 6;
+		let rm = modrm & 7;
+		if address16 {
+			i += match (md, rm) {
+				(0, 6) | (2, _) => 2,
+				(1, _) => 1,
+				_ => 0,
+			};
+		}
+		else if md != 3 {
+			if rm == 4 {
+				let sib = *bytes.get(i)?;
+				i += 1;
+				if md == 0 && sib & 7 == 5 {
+					i += 4;
+				}
+			}
+			if (md, rm) == (0, 5) && x64 {
+				rel = Some((i, 4, RelKind::Memory));
+			}
+			i += match (md, rm) {
+				(0, 5) => 4,
+				(1, _) => 1,
+				(2, _) => 4,
+				_ => 0,
+			};
+		}
+	}
+	if flags & R8 != 0 {
+		rel = Some((i, 1, RelKind::Branch));
+		i += 1;
+	}
+	if flags & RZ != 0 {
+		let size = if operand_size && !x64 { 2 } else { 4 };
+		rel = Some((i, size, RelKind::Branch));
+		i += size;
+	}
+	if flags & I8 != 0 {
+		i += 1;
+	}
+	if flags & I16 != 0 {
+		i += 2;
+	}
+	if flags & IZ != 0 {
+		i += if operand_size && !rex_w { 2 } else { 4 };
+	}
+
+	if i > bytes.len() {
+		return None;
+	}
+	let rel = rel.map(|(offset, size, kind)| {
+		let disp = match size {
+			1 => bytes[offset] as i8 as i32,
+			2 => i16::from_le_bytes([bytes[offset], bytes[offset + 1]]) as i32,
+			_ => i32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]),
+		};
+		Rel { offset, size, disp, kind }
+	});
+	Some(Insn { len: i, rel })
+}
+
+/// Iterates over the instructions in the bytes.
+///
+/// Yields the offset of every instruction, stops at the end of the bytes or the first instruction which cannot be decoded.
+pub fn iter(bytes: &[u8], mode: Mode) -> Iter<'_> {
+	Iter { bytes, mode, offset: 0 }
+}
+
+/// Iterator over the instructions, see [`iter`](fn.iter.html).
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+	bytes: &'a [u8],
+	mode: Mode,
+	offset: usize,
+}
+impl<'a> Iter<'a> {
+	/// Returns the offset of the next instruction.
+	///
+	/// When the iterator is exhausted this is where decoding stopped.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+}
+impl<'a> Iterator for Iter<'a> {
+	type Item = (usize, Insn);
+	fn next(&mut self) -> Option<(usize, Insn)> {
+		let insn = decode(&self.bytes[self.offset..], self.mode)?;
+		let offset = self.offset;
+		self.offset += insn.len;
+		Some((offset, insn))
+	}
+}
+impl<'a> iter::FusedIterator for Iter<'a> {}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lens(bytes: &[u8], mode: Mode) -> Vec<usize> {
+		iter(bytes, mode).map(|(_, insn)| insn.len).collect()
+	}
+
+	#[test]
+	fn x64() {
+		#[rustfmt::skip]
+		let code = [
+			0x48, 0x89, 0x5C, 0x24, 0x08,                   // mov [rsp+8], rbx
+			0x48, 0x83, 0xEC, 0x20,                         // sub rsp, 0x20
+			0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00,       // mov rax, [rip+0x10]
+			0x66, 0xC7, 0x00, 0x34, 0x12,                   // mov word [rax], 0x1234
+			0x48, 0xB8, 1, 2, 3, 4, 5, 6, 7, 8,             // mov rax, imm64
+			0xF7, 0xC1, 0x01, 0x00, 0x00, 0x00,             // test ecx, 1
+			0xF7, 0xD8,                                     // neg eax
+			0xC5, 0xF8, 0x77,                               // vzeroupper
+			0xC4, 0xE3, 0x79, 0x0F, 0xC1, 0x08,             // vpalignr xmm0, xmm0, xmm1, 8
+			0x0F, 0x1F, 0x44, 0x00, 0x00,                   // nop [rax+rax]
+			0x0F, 0x84, 0xF0, 0xFF, 0xFF, 0xFF,             // je rel32
+			0x75, 0xFE,                                     // jne rel8
+			0xC3,                                           // ret
+		];
+		assert_eq!(lens(&code, Mode::X64), [5, 4, 7, 5, 10, 6, 2, 3, 6, 5, 6, 2, 1]);
+
+		let insn = decode(&code[9..], Mode::X64).unwrap();
+		assert_eq!(insn.rel, Some(Rel { offset: 3, size: 4, disp: 0x10, kind: RelKind::Memory }));
+		assert_eq!(insn.target(0x1000), Some(0x1017));
+		let insn = decode(&code[53..], Mode::X64).unwrap();
+		assert_eq!(insn.rel, Some(Rel { offset: 2, size: 4, disp: -16, kind: RelKind::Branch }));
+		assert_eq!(insn.target(0x1000), Some(0x0FF6));
+		let insn = decode(&code[59..], Mode::X64).unwrap();
+		assert_eq!(insn.target(0x1000), Some(0x1000));
+
+		// Legacy opcodes, truncated and overlong instructions
+		assert_eq!(decode(&[0x06], Mode::X64), None);
+		assert_eq!(decode(&[0xE8, 0x00, 0x00], Mode::X64), None);
+		assert_eq!(decode(&[0x66; 16], Mode::X64), None);
+	}
+
+	#[test]
+	fn x86() {
+		#[rustfmt::skip]
+		let code = [
+			0x55,                                           // push ebp
+			0x8B, 0xEC,                                     // mov ebp, esp
+			0x40,                                           // inc eax
+			0xA1, 0x00, 0x30, 0x40, 0x00,                   // mov eax, [0x403000]
+			0x67, 0x8B, 0x46, 0x08,                         // mov eax, [bp+8]
+			0x60,                                           // pushad
+			0x9A, 1, 2, 3, 4, 5, 6,                         // call far
+			0xC5, 0x06,                                     // lds eax, [esi]
+			0xC5, 0xF8, 0x77,                               // vzeroupper
+			0x66, 0xE8, 0x10, 0x00,                         // call rel16
+			0x8B, 0x05, 0x00, 0x30, 0x40, 0x00,             // mov eax, [0x403000]
+			0xC2, 0x08, 0x00,                               // ret 8
+		];
+		assert_eq!(lens(&code, Mode::X86), [1, 2, 1, 5, 4, 1, 7, 2, 3, 4, 6, 3]);
+
+		let insn = decode(&code[26..], Mode::X86).unwrap();
+		assert_eq!(insn.rel, Some(Rel { offset: 2, size: 2, disp: 0x10, kind: RelKind::Branch }));
+		// Absolute addresses are not relative
+		assert_eq!(decode(&code[30..], Mode::X86).unwrap().rel, None);
+	}
+}
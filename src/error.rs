@@ -24,6 +24,8 @@ pub enum Error {
 	/// Sections can be shorter than stored on disk, the remaining bytes will default to zeroes when loaded by the system.
 	/// Since these zeroes would just be a waste of space, they are not present in the binaries on disk.
 	/// This error happens when attempting to get a reference to such zero filled data.
+	///
+	/// Also happens when referencing pages the `PeReader` has not loaded yet.
 	ZeroFill,
 	/// Data is not available.
 	///
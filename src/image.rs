@@ -17,6 +17,7 @@ Sources:
 
 use std::{fmt, mem};
 
+use crate::stringify::{DriverSubtype, FileFlags, FileOS, FileType, FontSubtype};
 use crate::Pod;
 
 #[cfg(windows)]
@@ -93,9 +94,46 @@ pub struct IMAGE_DOS_HEADER {
 
 //----------------------------------------------------------------
 
-pub const IMAGE_FILE_MACHINE_I386: u16  = 0x014c;
-pub const IMAGE_FILE_MACHINE_IA64: u16  = 0x0200;
-pub const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+pub const IMAGE_FILE_MACHINE_UNKNOWN: u16     = 0x0000;
+pub const IMAGE_FILE_MACHINE_TARGET_HOST: u16 = 0x0001;
+pub const IMAGE_FILE_MACHINE_I386: u16        = 0x014c;
+pub const IMAGE_FILE_MACHINE_R3000: u16       = 0x0162;
+pub const IMAGE_FILE_MACHINE_R4000: u16       = 0x0166;
+pub const IMAGE_FILE_MACHINE_R10000: u16      = 0x0168;
+pub const IMAGE_FILE_MACHINE_WCEMIPSV2: u16   = 0x0169;
+pub const IMAGE_FILE_MACHINE_ALPHA: u16       = 0x0184;
+pub const IMAGE_FILE_MACHINE_SH3: u16         = 0x01a2;
+pub const IMAGE_FILE_MACHINE_SH3DSP: u16      = 0x01a3;
+pub const IMAGE_FILE_MACHINE_SH3E: u16        = 0x01a4;
+pub const IMAGE_FILE_MACHINE_SH4: u16         = 0x01a6;
+pub const IMAGE_FILE_MACHINE_SH5: u16         = 0x01a8;
+pub const IMAGE_FILE_MACHINE_ARM: u16         = 0x01c0;
+pub const IMAGE_FILE_MACHINE_THUMB: u16       = 0x01c2;
+pub const IMAGE_FILE_MACHINE_ARMNT: u16       = 0x01c4;
+pub const IMAGE_FILE_MACHINE_AM33: u16        = 0x01d3;
+pub const IMAGE_FILE_MACHINE_POWERPC: u16     = 0x01f0;
+pub const IMAGE_FILE_MACHINE_POWERPCFP: u16   = 0x01f1;
+pub const IMAGE_FILE_MACHINE_IA64: u16        = 0x0200;
+pub const IMAGE_FILE_MACHINE_MIPS16: u16      = 0x0266;
+pub const IMAGE_FILE_MACHINE_ALPHA64: u16     = 0x0284;
+pub const IMAGE_FILE_MACHINE_AXP64: u16       = IMAGE_FILE_MACHINE_ALPHA64;
+pub const IMAGE_FILE_MACHINE_MIPSFPU: u16     = 0x0366;
+pub const IMAGE_FILE_MACHINE_MIPSFPU16: u16   = 0x0466;
+pub const IMAGE_FILE_MACHINE_TRICORE: u16     = 0x0520;
+pub const IMAGE_FILE_MACHINE_CEF: u16         = 0x0cef;
+pub const IMAGE_FILE_MACHINE_EBC: u16         = 0x0ebc;
+pub const IMAGE_FILE_MACHINE_CHPE_X86: u16    = 0x3a64;
+pub const IMAGE_FILE_MACHINE_RISCV32: u16     = 0x5032;
+pub const IMAGE_FILE_MACHINE_RISCV64: u16     = 0x5064;
+pub const IMAGE_FILE_MACHINE_RISCV128: u16    = 0x5128;
+pub const IMAGE_FILE_MACHINE_LOONGARCH32: u16 = 0x6232;
+pub const IMAGE_FILE_MACHINE_LOONGARCH64: u16 = 0x6264;
+pub const IMAGE_FILE_MACHINE_AMD64: u16       = 0x8664;
+pub const IMAGE_FILE_MACHINE_M32R: u16        = 0x9041;
+pub const IMAGE_FILE_MACHINE_ARM64EC: u16     = 0xa641;
+pub const IMAGE_FILE_MACHINE_ARM64X: u16      = 0xa64e;
+pub const IMAGE_FILE_MACHINE_ARM64: u16       = 0xaa64;
+pub const IMAGE_FILE_MACHINE_CEE: u16         = 0xc0ee;
 
 pub const IMAGE_FILE_RELOCS_STRIPPED: u16         = 0x0001;
 pub const IMAGE_FILE_EXECUTABLE_IMAGE: u16        = 0x0002;
@@ -127,6 +165,99 @@ pub struct IMAGE_FILE_HEADER {
 	pub Characteristics: u16,
 }
 
+//----------------------------------------------------------------
+// COFF symbol table, pointed to by PointerToSymbolTable
+// https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#coff-symbol-table
+
+pub const IMAGE_SIZEOF_SYMBOL: usize = 18;
+
+pub const IMAGE_SYM_UNDEFINED: i16 = 0;
+pub const IMAGE_SYM_ABSOLUTE: i16  = -1;
+pub const IMAGE_SYM_DEBUG: i16     = -2;
+
+pub const IMAGE_SYM_DTYPE_NULL: u16     = 0;
+pub const IMAGE_SYM_DTYPE_POINTER: u16  = 1;
+pub const IMAGE_SYM_DTYPE_FUNCTION: u16 = 2;
+pub const IMAGE_SYM_DTYPE_ARRAY: u16    = 3;
+
+pub const IMAGE_SYM_CLASS_NULL: u8          = 0;
+pub const IMAGE_SYM_CLASS_AUTOMATIC: u8     = 1;
+pub const IMAGE_SYM_CLASS_EXTERNAL: u8      = 2;
+pub const IMAGE_SYM_CLASS_STATIC: u8        = 3;
+pub const IMAGE_SYM_CLASS_LABEL: u8         = 6;
+pub const IMAGE_SYM_CLASS_FUNCTION: u8      = 101;
+pub const IMAGE_SYM_CLASS_FILE: u8          = 103;
+pub const IMAGE_SYM_CLASS_SECTION: u8       = 104;
+pub const IMAGE_SYM_CLASS_WEAK_EXTERNAL: u8 = 105;
+
+//----------------------------------------------------------------
+// COFF relocation types of object files
+// https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#type-indicators
+
+pub const IMAGE_REL_I386_ABSOLUTE: u16 = 0x0000;
+pub const IMAGE_REL_I386_DIR16: u16    = 0x0001;
+pub const IMAGE_REL_I386_REL16: u16    = 0x0002;
+pub const IMAGE_REL_I386_DIR32: u16    = 0x0006;
+pub const IMAGE_REL_I386_DIR32NB: u16  = 0x0007;
+pub const IMAGE_REL_I386_SEG12: u16    = 0x0009;
+pub const IMAGE_REL_I386_SECTION: u16  = 0x000A;
+pub const IMAGE_REL_I386_SECREL: u16   = 0x000B;
+pub const IMAGE_REL_I386_TOKEN: u16    = 0x000C;
+pub const IMAGE_REL_I386_SECREL7: u16  = 0x000D;
+pub const IMAGE_REL_I386_REL32: u16    = 0x0014;
+
+pub const IMAGE_REL_AMD64_ABSOLUTE: u16 = 0x0000;
+pub const IMAGE_REL_AMD64_ADDR64: u16   = 0x0001;
+pub const IMAGE_REL_AMD64_ADDR32: u16   = 0x0002;
+pub const IMAGE_REL_AMD64_ADDR32NB: u16 = 0x0003;
+pub const IMAGE_REL_AMD64_REL32: u16    = 0x0004;
+pub const IMAGE_REL_AMD64_REL32_1: u16  = 0x0005;
+pub const IMAGE_REL_AMD64_REL32_2: u16  = 0x0006;
+pub const IMAGE_REL_AMD64_REL32_3: u16  = 0x0007;
+pub const IMAGE_REL_AMD64_REL32_4: u16  = 0x0008;
+pub const IMAGE_REL_AMD64_REL32_5: u16  = 0x0009;
+pub const IMAGE_REL_AMD64_SECTION: u16  = 0x000A;
+pub const IMAGE_REL_AMD64_SECREL: u16   = 0x000B;
+pub const IMAGE_REL_AMD64_SECREL7: u16  = 0x000C;
+pub const IMAGE_REL_AMD64_TOKEN: u16    = 0x000D;
+pub const IMAGE_REL_AMD64_SREL32: u16   = 0x000E;
+pub const IMAGE_REL_AMD64_PAIR: u16     = 0x000F;
+pub const IMAGE_REL_AMD64_SSPAN32: u16  = 0x0010;
+
+pub const IMAGE_REL_ARM_ABSOLUTE: u16       = 0x0000;
+pub const IMAGE_REL_ARM_ADDR32: u16         = 0x0001;
+pub const IMAGE_REL_ARM_ADDR32NB: u16       = 0x0002;
+pub const IMAGE_REL_ARM_BRANCH24: u16       = 0x0003;
+pub const IMAGE_REL_ARM_BRANCH11: u16       = 0x0004;
+pub const IMAGE_REL_ARM_REL32: u16          = 0x000A;
+pub const IMAGE_REL_ARM_SECTION: u16        = 0x000E;
+pub const IMAGE_REL_ARM_SECREL: u16         = 0x000F;
+pub const IMAGE_REL_ARM_MOV32: u16          = 0x0010;
+pub const IMAGE_REL_THUMB_MOV32: u16        = 0x0011;
+pub const IMAGE_REL_THUMB_BRANCH20: u16     = 0x0012;
+pub const IMAGE_REL_THUMB_BRANCH24: u16     = 0x0014;
+pub const IMAGE_REL_THUMB_BLX23: u16        = 0x0015;
+pub const IMAGE_REL_ARM_PAIR: u16           = 0x0016;
+
+pub const IMAGE_REL_ARM64_ABSOLUTE: u16       = 0x0000;
+pub const IMAGE_REL_ARM64_ADDR32: u16         = 0x0001;
+pub const IMAGE_REL_ARM64_ADDR32NB: u16       = 0x0002;
+pub const IMAGE_REL_ARM64_BRANCH26: u16       = 0x0003;
+pub const IMAGE_REL_ARM64_PAGEBASE_REL21: u16 = 0x0004;
+pub const IMAGE_REL_ARM64_REL21: u16          = 0x0005;
+pub const IMAGE_REL_ARM64_PAGEOFFSET_12A: u16 = 0x0006;
+pub const IMAGE_REL_ARM64_PAGEOFFSET_12L: u16 = 0x0007;
+pub const IMAGE_REL_ARM64_SECREL: u16         = 0x0008;
+pub const IMAGE_REL_ARM64_SECREL_LOW12A: u16  = 0x0009;
+pub const IMAGE_REL_ARM64_SECREL_HIGH12A: u16 = 0x000A;
+pub const IMAGE_REL_ARM64_SECREL_LOW12L: u16  = 0x000B;
+pub const IMAGE_REL_ARM64_TOKEN: u16          = 0x000C;
+pub const IMAGE_REL_ARM64_SECTION: u16        = 0x000D;
+pub const IMAGE_REL_ARM64_ADDR64: u16         = 0x000E;
+pub const IMAGE_REL_ARM64_BRANCH19: u16       = 0x000F;
+pub const IMAGE_REL_ARM64_BRANCH14: u16       = 0x0010;
+pub const IMAGE_REL_ARM64_REL32: u16          = 0x0011;
+
 //----------------------------------------------------------------
 
 #[derive(Copy, Clone, Debug)]
@@ -183,6 +314,76 @@ impl<T: fmt::Display> fmt::Display for IMAGE_VERSION<T> {
 
 //----------------------------------------------------------------
 
+/// Pointer sized fields whose width differs between PE32 and PE32+.
+///
+/// Implemented by the [`pe32::Va`](../pe32/type.Va.html) and [`pe64::Va`](../pe64/type.Va.html) types,
+/// code generic over the virtual address type only needs to be written once for both formats.
+///
+/// ```
+/// use pelite::image::VirtualAddress;
+///
+/// // Decodes an entry of the import name table of either format
+/// fn import<V: VirtualAddress>(thunk: V) -> Result<u16, u32> {
+/// 	if thunk.is_ordinal() { Ok(thunk.to_u64() as u16) } else { Err(thunk.to_u64() as u32) }
+/// }
+///
+/// assert_eq!(import::<pelite::pe32::Va>(0x80000017), Ok(23));
+/// assert_eq!(import::<pelite::pe64::Va>(0x80000017), Err(0x80000017));
+/// ```
+///
+/// The trait is sealed, the image structures generic over it are only `Pod` without padding for `u32` and `u64`.
+pub trait VirtualAddress: sealed::Sealed + Copy + Default + Eq + Ord + ::std::hash::Hash + fmt::Debug + fmt::LowerHex + fmt::UpperHex + Pod + 'static {
+	/// Width of the field in bits, 32 for PE32 and 64 for PE32+.
+	const BITNESS: u32;
+	/// Magic of the optional header of the format.
+	const OPTIONAL_HDR_MAGIC: u16;
+	/// Flag marking an import by ordinal in the import name table.
+	const ORDINAL_FLAG: Self;
+	/// Zero extends the 32-bit value.
+	fn from_u32(value: u32) -> Self;
+	/// Converts the 64-bit value, `None` if it doesn't fit.
+	fn from_u64(value: u64) -> Option<Self>;
+	/// Zero extends the value to 64-bit.
+	fn to_u64(self) -> u64;
+	/// Returns true if the import name table entry imports by ordinal.
+	fn is_ordinal(self) -> bool;
+	/// Gets the rva of the virtual address given the image base.
+	///
+	/// Returns `None` if the virtual address is below the image base or the rva does not fit in 32-bit.
+	fn to_rva(self, image_base: Self) -> Option<u32>;
+	/// Gets the virtual address of the rva given the image base, `None` on overflow.
+	fn from_rva(rva: u32, image_base: Self) -> Option<Self>;
+}
+mod sealed {
+	pub trait Sealed {}
+	impl Sealed for u32 {}
+	impl Sealed for u64 {}
+}
+impl VirtualAddress for u32 {
+	const BITNESS: u32 = 32;
+	const OPTIONAL_HDR_MAGIC: u16 = IMAGE_NT_OPTIONAL_HDR32_MAGIC;
+	const ORDINAL_FLAG: u32 = IMAGE_ORDINAL_FLAG32;
+	fn from_u32(value: u32) -> u32 { value }
+	fn from_u64(value: u64) -> Option<u32> { if value >> 32 == 0 { Some(value as u32) } else { None } }
+	fn to_u64(self) -> u64 { self as u64 }
+	fn is_ordinal(self) -> bool { self & IMAGE_ORDINAL_FLAG32 != 0 }
+	fn to_rva(self, image_base: u32) -> Option<u32> { self.checked_sub(image_base) }
+	fn from_rva(rva: u32, image_base: u32) -> Option<u32> { image_base.checked_add(rva) }
+}
+impl VirtualAddress for u64 {
+	const BITNESS: u32 = 64;
+	const OPTIONAL_HDR_MAGIC: u16 = IMAGE_NT_OPTIONAL_HDR64_MAGIC;
+	const ORDINAL_FLAG: u64 = IMAGE_ORDINAL_FLAG64;
+	fn from_u32(value: u32) -> u64 { value as u64 }
+	fn from_u64(value: u64) -> Option<u64> { Some(value) }
+	fn to_u64(self) -> u64 { self }
+	fn is_ordinal(self) -> bool { self & IMAGE_ORDINAL_FLAG64 != 0 }
+	fn to_rva(self, image_base: u64) -> Option<u32> { let rva = self.checked_sub(image_base)?; if rva >> 32 == 0 { Some(rva as u32) } else { None } }
+	fn from_rva(rva: u32, image_base: u64) -> Option<u64> { image_base.checked_add(rva as u64) }
+}
+
+//----------------------------------------------------------------
+
 pub const IMAGE_NT_OPTIONAL_HDR32_MAGIC: u16 = 0x10b;
 pub const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20b;
 pub const IMAGE_ROM_OPTIONAL_HDR_MAGIC: u16  = 0x107;
@@ -201,6 +402,7 @@ pub const IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER: u16       = 12;
 pub const IMAGE_SUBSYSTEM_EFI_ROM: u16                  = 13;
 pub const IMAGE_SUBSYSTEM_XBOX: u16                     = 14;
 pub const IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION: u16 = 16;
+pub const IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG: u16        = 17;
 
 pub(crate) const IMAGE_DLLCHARACTERISTICS_0: u16              = 0x0001;
 pub(crate) const IMAGE_DLLCHARACTERISTICS_1: u16              = 0x0002;
@@ -350,6 +552,7 @@ pub const IMAGE_SCN_ALIGN_1024BYTES: u32        = 0x00B00000;
 pub const IMAGE_SCN_ALIGN_2048BYTES: u32        = 0x00C00000;
 pub const IMAGE_SCN_ALIGN_4096BYTES: u32        = 0x00D00000;
 pub const IMAGE_SCN_ALIGN_8192BYTES: u32        = 0x00E00000;
+pub const IMAGE_SCN_ALIGN_MASK: u32             = 0x00F00000;
 pub const IMAGE_SCN_LNK_NRELOC_OVFL: u32        = 0x01000000;
 pub const IMAGE_SCN_MEM_DISCARDABLE: u32        = 0x02000000;
 pub const IMAGE_SCN_MEM_NOT_CACHED: u32         = 0x04000000;
@@ -418,6 +621,54 @@ pub const IMAGE_ORDINAL_FLAG64: u64 = 0x8000000000000000;
 
 //----------------------------------------------------------------
 
+pub const IMAGE_DELAYLOAD_RVA_BASED: u32 = 0x00000001;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct IMAGE_DELAYLOAD_DESCRIPTOR {
+	pub Attributes: u32,
+	pub DllNameRVA: u32,
+	pub ModuleHandleRVA: u32,
+	pub ImportAddressTableRVA: u32,
+	pub ImportNameTableRVA: u32,
+	pub BoundImportAddressTableRVA: u32,
+	pub UnloadInformationTableRVA: u32,
+	pub TimeDateStamp: u32,
+}
+impl IMAGE_DELAYLOAD_DESCRIPTOR {
+	pub fn is_null(&self) -> bool {
+		self.DllNameRVA == 0
+	}
+}
+
+//----------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct IMAGE_BOUND_IMPORT_DESCRIPTOR {
+	pub TimeDateStamp: u32,
+	pub OffsetModuleName: u16,
+	pub NumberOfModuleForwarderRefs: u16,
+}
+impl IMAGE_BOUND_IMPORT_DESCRIPTOR {
+	pub fn is_null(&self) -> bool {
+		self.TimeDateStamp == 0 && self.OffsetModuleName == 0
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct IMAGE_BOUND_FORWARDER_REF {
+	pub TimeDateStamp: u32,
+	pub OffsetModuleName: u16,
+	pub Reserved: u16,
+}
+
+//----------------------------------------------------------------
+
 pub const RT_CURSOR: u16       = 1;
 pub const RT_BITMAP: u16       = 2;
 pub const RT_ICON: u16         = 3;
@@ -461,6 +712,26 @@ pub struct IMAGE_RESOURCE_DIRECTORY_ENTRY {
 	// High bit set means this is offset points to an IMAGE_RESOURCE_DIRECTORY otherwise an IMAGE_RESOURCE_DATA_ENTRY
 	pub Offset: u32,
 }
+impl IMAGE_RESOURCE_DIRECTORY_ENTRY {
+	/// Returns the offset of the name string in the resource section if the entry is named.
+	pub const fn name_offset(&self) -> Option<u32> {
+		let name = u32::from_le(self.Name);
+		if name & 0x80000000 != 0 { Some(name & !0x80000000) } else { None }
+	}
+	/// Returns the id if the entry is not named.
+	pub const fn id(&self) -> Option<u32> {
+		let name = u32::from_le(self.Name);
+		if name & 0x80000000 != 0 { None } else { Some(name) }
+	}
+	/// Returns true if the entry points to an `IMAGE_RESOURCE_DIRECTORY`.
+	pub const fn is_dir(&self) -> bool {
+		u32::from_le(self.Offset) & 0x80000000 != 0
+	}
+	/// Returns the offset of the directory or data entry in the resource section.
+	pub const fn offset(&self) -> u32 {
+		u32::from_le(self.Offset) & !0x80000000
+	}
+}
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
@@ -500,6 +771,17 @@ impl fmt::Display for VS_VERSION {
 		write!(f, "{}.{}.{}.{}", self.Major, self.Minor, self.Patch, self.Build)
 	}
 }
+impl VS_VERSION {
+	/// Returns the version as `(major, minor, patch, build)` in the order it is displayed.
+	pub const fn to_tuple(self) -> (u16, u16, u16, u16) {
+		(u16::from_le(self.Major), u16::from_le(self.Minor), u16::from_le(self.Patch), u16::from_le(self.Build))
+	}
+}
+impl From<VS_VERSION> for (u16, u16, u16, u16) {
+	fn from(version: VS_VERSION) -> (u16, u16, u16, u16) {
+		version.to_tuple()
+	}
+}
 
 #[cfg(feature = "serde")]
 fn ser_fixed_file_info_struc_version<S: serde::Serializer>(&version: &u32, serializer: S) -> std::result::Result<S::Ok, S::Error> {
@@ -527,6 +809,92 @@ pub struct VS_FIXEDFILEINFO {
 	pub dwFileDateMS: u32,
 	pub dwFileDateLS: u32,
 }
+impl VS_FIXEDFILEINFO {
+	/// Gets the file version as `(major, minor, patch, build)`, see [`VS_VERSION`](struct.VS_VERSION.html) to display it.
+	pub const fn file_version(&self) -> (u16, u16, u16, u16) {
+		self.dwFileVersion.to_tuple()
+	}
+	/// Gets the product version as `(major, minor, patch, build)`, see [`VS_VERSION`](struct.VS_VERSION.html) to display it.
+	pub const fn product_version(&self) -> (u16, u16, u16, u16) {
+		self.dwProductVersion.to_tuple()
+	}
+	/// Gets the typed file flags, only the flags in `dwFileFlagsMask` are valid.
+	pub const fn file_flags(&self) -> FileFlags {
+		FileFlags(u32::from_le(self.dwFileFlags) & u32::from_le(self.dwFileFlagsMask))
+	}
+	/// Gets the typed operating system the file was designed for.
+	pub const fn file_os(&self) -> FileOS {
+		FileOS(u32::from_le(self.dwFileOS))
+	}
+	/// Gets the typed general type of the file.
+	pub const fn file_type(&self) -> FileType {
+		FileType(u32::from_le(self.dwFileType))
+	}
+	/// Gets the typed subtype of a driver.
+	///
+	/// Returns `None` if the file is not a driver.
+	pub const fn driver_subtype(&self) -> Option<DriverSubtype> {
+		if u32::from_le(self.dwFileType) == VFT_DRV { Some(DriverSubtype(u32::from_le(self.dwFileSubtype))) } else { None }
+	}
+	/// Gets the typed subtype of a font.
+	///
+	/// Returns `None` if the file is not a font.
+	pub const fn font_subtype(&self) -> Option<FontSubtype> {
+		if u32::from_le(self.dwFileType) == VFT_FONT { Some(FontSubtype(u32::from_le(self.dwFileSubtype))) } else { None }
+	}
+}
+
+pub const VS_FF_DEBUG: u32        = 0x00000001;
+pub const VS_FF_PRERELEASE: u32   = 0x00000002;
+pub const VS_FF_PATCHED: u32      = 0x00000004;
+pub const VS_FF_PRIVATEBUILD: u32 = 0x00000008;
+pub const VS_FF_INFOINFERRED: u32 = 0x00000010;
+pub const VS_FF_SPECIALBUILD: u32 = 0x00000020;
+
+pub const VOS_UNKNOWN: u32 = 0x00000000;
+pub const VOS_DOS: u32     = 0x00010000;
+pub const VOS_OS216: u32   = 0x00020000;
+pub const VOS_OS232: u32   = 0x00030000;
+pub const VOS_NT: u32      = 0x00040000;
+pub const VOS_WINCE: u32   = 0x00050000;
+
+pub const VOS__BASE: u32      = 0x00000000;
+pub const VOS__WINDOWS16: u32 = 0x00000001;
+pub const VOS__PM16: u32      = 0x00000002;
+pub const VOS__PM32: u32      = 0x00000003;
+pub const VOS__WINDOWS32: u32 = 0x00000004;
+
+pub const VOS_DOS_WINDOWS16: u32 = 0x00010001;
+pub const VOS_DOS_WINDOWS32: u32 = 0x00010004;
+pub const VOS_OS216_PM16: u32    = 0x00020002;
+pub const VOS_OS232_PM32: u32    = 0x00030003;
+pub const VOS_NT_WINDOWS32: u32  = 0x00040004;
+
+pub const VFT_UNKNOWN: u32    = 0x00000000;
+pub const VFT_APP: u32        = 0x00000001;
+pub const VFT_DLL: u32        = 0x00000002;
+pub const VFT_DRV: u32        = 0x00000003;
+pub const VFT_FONT: u32       = 0x00000004;
+pub const VFT_VXD: u32        = 0x00000005;
+pub const VFT_STATIC_LIB: u32 = 0x00000007;
+
+pub const VFT2_UNKNOWN: u32               = 0x00000000;
+pub const VFT2_DRV_PRINTER: u32           = 0x00000001;
+pub const VFT2_DRV_KEYBOARD: u32          = 0x00000002;
+pub const VFT2_DRV_LANGUAGE: u32          = 0x00000003;
+pub const VFT2_DRV_DISPLAY: u32           = 0x00000004;
+pub const VFT2_DRV_MOUSE: u32             = 0x00000005;
+pub const VFT2_DRV_NETWORK: u32           = 0x00000006;
+pub const VFT2_DRV_SYSTEM: u32            = 0x00000007;
+pub const VFT2_DRV_INSTALLABLE: u32       = 0x00000008;
+pub const VFT2_DRV_SOUND: u32             = 0x00000009;
+pub const VFT2_DRV_COMM: u32              = 0x0000000A;
+pub const VFT2_DRV_INPUTMETHOD: u32       = 0x0000000B;
+pub const VFT2_DRV_VERSIONED_PRINTER: u32 = 0x0000000C;
+
+pub const VFT2_FONT_RASTER: u32   = 0x00000001;
+pub const VFT2_FONT_VECTOR: u32   = 0x00000002;
+pub const VFT2_FONT_TRUETYPE: u32 = 0x00000003;
 
 //----------------------------------------------------------------
 // Sourced from <Windows.h>
@@ -551,6 +919,13 @@ pub const IMAGE_REL_BASED_MIPSJMPADDR16: u8 = 9;
 pub const IMAGE_REL_BASED_ARM_MOV32: u8 = 5;
 pub const IMAGE_REL_BASED_THUMB_MOV32: u8 = 7;
 
+pub const IMAGE_REL_BASED_RISCV_HIGH20: u8 = 5;
+pub const IMAGE_REL_BASED_RISCV_LOW12I: u8 = 7;
+pub const IMAGE_REL_BASED_RISCV_LOW12S: u8 = 8;
+
+pub const IMAGE_REL_BASED_LOONGARCH32_MARK_LA: u8 = 8;
+pub const IMAGE_REL_BASED_LOONGARCH64_MARK_LA: u8 = 8;
+
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 #[repr(C)]
@@ -560,56 +935,36 @@ pub struct IMAGE_BASE_RELOCATION {
 }
 
 //----------------------------------------------------------------
+// Structures with pointer sized fields are defined once, generic over the width of the fields.
+// See the VirtualAddress trait, the 32-bit and 64-bit structures are aliases of the generic structures.
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 #[repr(C)]
-pub struct IMAGE_LOAD_CONFIG_DIRECTORY32 {
+pub struct IMAGE_LOAD_CONFIG_DIRECTORY_T<V> {
 	pub Size: u32,
 	pub TimeDateStamp: u32,
 	pub Version: IMAGE_VERSION<u16>,
 	pub GlobalFlagsClear: u32,
 	pub GlobalFlagsSet: u32,
 	pub CriticalSectionDefaultTimeout: u32,
-	pub DeCommitFreeBlockThreshold: u32,
-	pub DeCommitTotalFreeThreshold: u32,
-	pub LockPrefixTable: u32,
-	pub MaximumAllocationSize: u32,
-	pub VirtualMemoryThreshold: u32,
-	pub ProcessAffinityMask: u32,
+	pub DeCommitFreeBlockThreshold: V,
+	pub DeCommitTotalFreeThreshold: V,
+	pub LockPrefixTable: V,
+	pub MaximumAllocationSize: V,
+	pub VirtualMemoryThreshold: V,
+	pub ProcessAffinityMask: V,
 	pub ProcessHeapFlags: u32,
 	pub CSDVersion: u16,
 	pub DependentLoadFlags: u16,
-	pub EditList: u32,
-	pub SecurityCookie: u32,
-	pub SEHandlerTable: u32,
-	pub SEHandlerCount: u32,
+	pub EditList: V,
+	pub SecurityCookie: V,
+	pub SEHandlerTable: V,
+	pub SEHandlerCount: V,
 }
 
-#[derive(Copy, Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
-#[repr(C)]
-pub struct IMAGE_LOAD_CONFIG_DIRECTORY64 {
-	pub Size: u32,
-	pub TimeDateStamp: u32,
-	pub Version: IMAGE_VERSION<u16>,
-	pub GlobalFlagsClear: u32,
-	pub GlobalFlagsSet: u32,
-	pub CriticalSectionDefaultTimeout: u32,
-	pub DeCommitFreeBlockThreshold: u64,
-	pub DeCommitTotalFreeThreshold: u64,
-	pub LockPrefixTable: u64,
-	pub MaximumAllocationSize: u64,
-	pub VirtualMemoryThreshold: u64,
-	pub ProcessAffinityMask: u64,
-	pub ProcessHeapFlags: u32,
-	pub CSDVersion: u16,
-	pub DependentLoadFlags: u16,
-	pub EditList: u64,
-	pub SecurityCookie: u64,
-	pub SEHandlerTable: u64,
-	pub SEHandlerCount: u64,
-}
+pub type IMAGE_LOAD_CONFIG_DIRECTORY32 = IMAGE_LOAD_CONFIG_DIRECTORY_T<u32>;
+pub type IMAGE_LOAD_CONFIG_DIRECTORY64 = IMAGE_LOAD_CONFIG_DIRECTORY_T<u64>;
 
 //----------------------------------------------------------------
 // Control flow guard bits of the LoadConfig
@@ -671,88 +1026,65 @@ pub const IMAGE_GUARD_CF_LONGJUMP_TABLE_PRESENT: u32          = 0x00010000;
 pub const IMAGE_GUARD_RF_INSTRUMENTED: u32                    = 0x00020000;
 pub const IMAGE_GUARD_RF_ENABLE: u32                          = 0x00040000;
 pub const IMAGE_GUARD_RF_STRICT: u32                          = 0x00080000;
+pub const IMAGE_GUARD_RETPOLINE_PRESENT: u32                  = 0x00100000;
+pub const IMAGE_GUARD_EH_CONTINUATION_TABLE_PRESENT: u32      = 0x00400000;
+pub const IMAGE_GUARD_XFG_ENABLED: u32                        = 0x00800000;
+pub const IMAGE_GUARD_CASTGUARD_PRESENT: u32                  = 0x01000000;
+pub const IMAGE_GUARD_MEMCPY_PRESENT: u32                     = 0x02000000;
 pub const IMAGE_GUARD_CF_FUNCTION_TABLE_SIZE_MASK: u32        = 0xF0000000;
 pub const IMAGE_GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT: u32       = 28;
 
-#[derive(Copy, Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
-#[repr(C)]
-pub struct IMAGE_GUARDCF32 {
-	pub GuardCFCheckFunctionPointer: u32,
-	pub GuardCFDispatchFunctionPointer: u32,
-	pub GuardCFFunctionTable: u32,
-	pub GuardCFFunctionCount: u32,
-	pub GuardFlags: u32,
-	pub CodeIntegrity: IMAGE_LOAD_CONFIG_CODE_INTEGRITY,
-	pub GuardAddressTakenIatEntryTable: u32,
-	pub GuardAddressTakenIatEntryCount: u32,
-	pub GuardLongJumpTargetTable: u32,
-	pub GuardLongJumpTargetCount: u32,
-	pub DynamicValueRelocTable: u32,
-	pub HybridMetadataPointer: u32,
-	pub GuardRFFailureRoutine: u32,
-	pub GuardRFFailureRoutineFunctionPointer: u32,
-	pub DynamicValueRelocTableOffset: u32,
-	pub DynamicValueRelocTableSection: u16,
-	pub Reserved2: u16,
-	pub GuardRFVerifyStackPointerFunctionPointer: u32,
-	pub HotPatchTableOffset: u32,
-	pub Reserved3: u32,
-	pub EnclaveConfigurationPointer: u32,
-}
+pub const IMAGE_GUARD_FLAG_FID_SUPPRESSED: u8       = 0x01;
+pub const IMAGE_GUARD_FLAG_EXPORT_SUPPRESSED: u8    = 0x02;
+pub const IMAGE_GUARD_FLAG_FID_LANGEXCPTHANDLER: u8 = 0x04;
+pub const IMAGE_GUARD_FLAG_FID_XFG: u8              = 0x08;
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 #[repr(C)]
-pub struct IMAGE_GUARDCF64 {
-	pub GuardCFCheckFunctionPointer: u64,
-	pub GuardCFDispatchFunctionPointer: u64,
-	pub GuardCFFunctionTable: u64,
-	pub GuardCFFunctionCount: u64,
+pub struct IMAGE_GUARDCF_T<V> {
+	pub GuardCFCheckFunctionPointer: V,
+	pub GuardCFDispatchFunctionPointer: V,
+	pub GuardCFFunctionTable: V,
+	pub GuardCFFunctionCount: V,
 	pub GuardFlags: u32,
 	pub CodeIntegrity: IMAGE_LOAD_CONFIG_CODE_INTEGRITY,
-	pub GuardAddressTakenIatEntryTable: u64,
-	pub GuardAddressTakenIatEntryCount: u64,
-	pub GuardLongJumpTargetTable: u64,
-	pub GuardLongJumpTargetCount: u64,
-	pub DynamicValueRelocTable: u64,
-	pub HybridMetadataPointer: u64,
-	pub GuardRFFailureRoutine: u64,
-	pub GuardRFFailureRoutineFunctionPointer: u64,
+	pub GuardAddressTakenIatEntryTable: V,
+	pub GuardAddressTakenIatEntryCount: V,
+	pub GuardLongJumpTargetTable: V,
+	pub GuardLongJumpTargetCount: V,
+	pub DynamicValueRelocTable: V,
+	pub HybridMetadataPointer: V,
+	pub GuardRFFailureRoutine: V,
+	pub GuardRFFailureRoutineFunctionPointer: V,
 	pub DynamicValueRelocTableOffset: u32,
 	pub DynamicValueRelocTableSection: u16,
 	pub Reserved2: u16,
-	pub GuardRFVerifyStackPointerFunctionPointer: u64,
+	pub GuardRFVerifyStackPointerFunctionPointer: V,
 	pub HotPatchTableOffset: u32,
 	pub Reserved3: u32,
-	pub EnclaveConfigurationPointer: u64,
+	pub EnclaveConfigurationPointer: V,
 }
 
+pub type IMAGE_GUARDCF32 = IMAGE_GUARDCF_T<u32>;
+pub type IMAGE_GUARDCF64 = IMAGE_GUARDCF_T<u64>;
+
 //----------------------------------------------------------------
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 #[repr(C)]
-pub struct IMAGE_TLS_DIRECTORY32 {
-	pub StartAddressOfRawData: u32,
-	pub EndAddressOfRawData: u32,
-	pub AddressOfIndex: u32,
-	pub AddressOfCallBacks: u32,
+pub struct IMAGE_TLS_DIRECTORY_T<V> {
+	pub StartAddressOfRawData: V,
+	pub EndAddressOfRawData: V,
+	pub AddressOfIndex: V,
+	pub AddressOfCallBacks: V,
 	pub SizeOfZeroFill: u32,
 	pub Characteristics: u32,
 }
 
-#[derive(Copy, Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
-#[repr(C)]
-pub struct IMAGE_TLS_DIRECTORY64 {
-	pub StartAddressOfRawData: u64,
-	pub EndAddressOfRawData: u64,
-	pub AddressOfIndex: u64,
-	pub AddressOfCallBacks: u64,
-	pub SizeOfZeroFill: u32,
-	pub Characteristics: u32,
-}
+pub type IMAGE_TLS_DIRECTORY32 = IMAGE_TLS_DIRECTORY_T<u32>;
+pub type IMAGE_TLS_DIRECTORY64 = IMAGE_TLS_DIRECTORY_T<u64>;
 
 //----------------------------------------------------------------
 
@@ -825,6 +1157,20 @@ pub struct RUNTIME_FUNCTION {
 	pub UnwindData: u32,
 }
 
+// The UnwindData points to another runtime function instead of unwind info
+pub const RUNTIME_FUNCTION_INDIRECT: u32 = 0x1;
+
+/// Function table entry of ARM and ARM64 images.
+///
+/// The low two bits of `UnwindData` select between an rva of the `.xdata` record and packed unwind data.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct IMAGE_ARM_RUNTIME_FUNCTION_ENTRY {
+	pub BeginAddress: u32,
+	pub UnwindData: u32,
+}
+
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 #[repr(C)]
@@ -874,6 +1220,19 @@ pub const IMAGE_DEBUG_TYPE_POGO: u32          = 13;
 pub const IMAGE_DEBUG_TYPE_ILTCG: u32         = 14;
 pub const IMAGE_DEBUG_TYPE_MPX: u32           = 15;
 pub const IMAGE_DEBUG_TYPE_REPRO: u32         = 16;
+pub const IMAGE_DEBUG_TYPE_EMBEDDED_PORTABLE_PDB: u32 = 17;
+pub const IMAGE_DEBUG_TYPE_SPGO: u32          = 18;
+pub const IMAGE_DEBUG_TYPE_PDBCHECKSUM: u32   = 19;
+pub const IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS: u32 = 20;
+
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT: u32                                  = 0x01;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT_STRICT_MODE: u32                      = 0x02;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE: u32  = 0x04;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_DYNAMIC_APIS_ALLOW_IN_PROC: u32              = 0x08;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_RESERVED_1: u32                              = 0x10;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_RESERVED_2: u32                              = 0x20;
+pub const IMAGE_DLLCHARACTERISTICS_EX_FORWARD_CFI_COMPAT: u32                          = 0x40;
+pub const IMAGE_DLLCHARACTERISTICS_EX_HOTPATCH_COMPATIBLE: u32                         = 0x80;
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize))]
@@ -963,6 +1322,111 @@ pub struct IMAGE_DEBUG_VC_FEATURE {
 	pub GuardN: u32,  // guardN
 }
 
+//----------------------------------------------------------------
+// CLR header and ReadyToRun
+// https://docs.microsoft.com/en-us/dotnet/standard/metadata-and-self-describing-components
+// https://github.com/dotnet/runtime/blob/main/docs/design/coreclr/botr/readytorun-format.md
+
+pub const COMIMAGE_FLAGS_ILONLY: u32            = 0x00000001;
+pub const COMIMAGE_FLAGS_32BITREQUIRED: u32     = 0x00000002;
+pub const COMIMAGE_FLAGS_IL_LIBRARY: u32        = 0x00000004;
+pub const COMIMAGE_FLAGS_STRONGNAMESIGNED: u32  = 0x00000008;
+pub const COMIMAGE_FLAGS_NATIVE_ENTRYPOINT: u32 = 0x00000010;
+pub const COMIMAGE_FLAGS_TRACKDEBUGDATA: u32    = 0x00010000;
+pub const COMIMAGE_FLAGS_32BITPREFERRED: u32    = 0x00020000;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct IMAGE_COR20_HEADER {
+	pub cb: u32,
+	pub MajorRuntimeVersion: u16,
+	pub MinorRuntimeVersion: u16,
+	pub MetaData: IMAGE_DATA_DIRECTORY,
+	pub Flags: u32,
+	pub EntryPointToken: u32, // Or EntryPointRVA if COMIMAGE_FLAGS_NATIVE_ENTRYPOINT
+	pub Resources: IMAGE_DATA_DIRECTORY,
+	pub StrongNameSignature: IMAGE_DATA_DIRECTORY,
+	pub CodeManagerTable: IMAGE_DATA_DIRECTORY,
+	pub VTableFixups: IMAGE_DATA_DIRECTORY,
+	pub ExportAddressTableJumps: IMAGE_DATA_DIRECTORY,
+	pub ManagedNativeHeader: IMAGE_DATA_DIRECTORY,
+}
+
+pub const READYTORUN_SIGNATURE: u32 = 0x00525452; // 'RTR'
+
+pub const READYTORUN_FLAG_PLATFORM_NEUTRAL_SOURCE: u32    = 0x00000001;
+pub const READYTORUN_FLAG_SKIP_TYPE_VALIDATION: u32       = 0x00000002;
+pub const READYTORUN_FLAG_PARTIAL: u32                    = 0x00000004;
+pub const READYTORUN_FLAG_NONSHARED_PINVOKE_STUBS: u32    = 0x00000008;
+pub const READYTORUN_FLAG_EMBEDDED_MSIL: u32              = 0x00000010;
+pub const READYTORUN_FLAG_COMPONENT: u32                  = 0x00000020;
+pub const READYTORUN_FLAG_MULTIMODULE_VERSION_BUBBLE: u32 = 0x00000040;
+pub const READYTORUN_FLAG_UNRELATED_R2R_CODE: u32         = 0x00000080;
+
+pub const READYTORUN_SECTION_COMPILER_IDENTIFIER: u32              = 100;
+pub const READYTORUN_SECTION_IMPORT_SECTIONS: u32                  = 101;
+pub const READYTORUN_SECTION_RUNTIME_FUNCTIONS: u32                = 102;
+pub const READYTORUN_SECTION_METHODDEF_ENTRYPOINTS: u32            = 103;
+pub const READYTORUN_SECTION_EXCEPTION_INFO: u32                   = 104;
+pub const READYTORUN_SECTION_DEBUG_INFO: u32                       = 105;
+pub const READYTORUN_SECTION_DELAYLOAD_METHODCALL_THUNKS: u32      = 106;
+pub const READYTORUN_SECTION_AVAILABLE_TYPES: u32                  = 108;
+pub const READYTORUN_SECTION_INSTANCE_METHOD_ENTRYPOINTS: u32      = 109;
+pub const READYTORUN_SECTION_INLINING_INFO: u32                    = 110;
+pub const READYTORUN_SECTION_PROFILEDATA_INFO: u32                 = 111;
+pub const READYTORUN_SECTION_MANIFEST_METADATA: u32                = 112;
+pub const READYTORUN_SECTION_ATTRIBUTEPRESENCE: u32                = 113;
+pub const READYTORUN_SECTION_INLINING_INFO2: u32                   = 114;
+pub const READYTORUN_SECTION_COMPONENT_ASSEMBLIES: u32             = 115;
+pub const READYTORUN_SECTION_OWNER_COMPOSITE_EXECUTABLE: u32       = 116;
+pub const READYTORUN_SECTION_PGO_INSTRUMENTATION_DATA: u32         = 117;
+pub const READYTORUN_SECTION_MANIFEST_ASSEMBLY_MVIDS: u32          = 118;
+pub const READYTORUN_SECTION_CROSS_MODULE_INLINE_INFO: u32         = 119;
+pub const READYTORUN_SECTION_HOT_COLD_MAP: u32                     = 120;
+pub const READYTORUN_SECTION_METHOD_IS_GENERIC_MAP: u32            = 121;
+pub const READYTORUN_SECTION_ENCLOSING_TYPE_MAP: u32               = 122;
+pub const READYTORUN_SECTION_TYPE_GENERIC_INFO_MAP: u32            = 123;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct READYTORUN_HEADER {
+	pub Signature: u32,
+	pub MajorVersion: u16,
+	pub MinorVersion: u16,
+	pub Flags: u32,
+	pub NumberOfSections: u32,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct READYTORUN_SECTION {
+	pub Type: u32,
+	pub Section: IMAGE_DATA_DIRECTORY,
+}
+
+pub const READYTORUN_IMPORT_SECTION_FLAGS_EAGER: u16     = 0x0001;
+pub const READYTORUN_IMPORT_SECTION_FLAGS_PCODE: u16     = 0x0004;
+
+pub const READYTORUN_IMPORT_SECTION_TYPE_UNKNOWN: u8       = 0;
+pub const READYTORUN_IMPORT_SECTION_TYPE_STUB_DISPATCH: u8 = 2;
+pub const READYTORUN_IMPORT_SECTION_TYPE_STRING_HANDLE: u8 = 3;
+pub const READYTORUN_IMPORT_SECTION_TYPE_ILBODYFIXUPS: u8  = 7;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+#[repr(C)]
+pub struct READYTORUN_IMPORT_SECTION {
+	pub Section: IMAGE_DATA_DIRECTORY,
+	pub Flags: u16,
+	pub Type: u8,
+	pub EntrySize: u8,
+	pub Signatures: u32,
+	pub AuxiliaryData: u32,
+}
+
 //----------------------------------------------------------------
 
 unsafe impl Pod for IMAGE_DOS_HEADER {}
@@ -976,26 +1440,27 @@ unsafe impl Pod for IMAGE_NT_HEADERS64 {}
 unsafe impl Pod for IMAGE_SECTION_HEADER {}
 unsafe impl Pod for IMAGE_EXPORT_DIRECTORY {}
 unsafe impl Pod for IMAGE_IMPORT_DESCRIPTOR {}
+unsafe impl Pod for IMAGE_DELAYLOAD_DESCRIPTOR {}
+unsafe impl Pod for IMAGE_BOUND_IMPORT_DESCRIPTOR {}
+unsafe impl Pod for IMAGE_BOUND_FORWARDER_REF {}
 unsafe impl Pod for IMAGE_RESOURCE_DIRECTORY {}
 unsafe impl Pod for IMAGE_RESOURCE_DIRECTORY_ENTRY {}
 unsafe impl Pod for IMAGE_RESOURCE_DATA_ENTRY {}
 unsafe impl Pod for VS_VERSION {}
 unsafe impl Pod for VS_FIXEDFILEINFO {}
 unsafe impl Pod for IMAGE_BASE_RELOCATION {}
-unsafe impl Pod for IMAGE_LOAD_CONFIG_DIRECTORY32 {}
-unsafe impl Pod for IMAGE_LOAD_CONFIG_DIRECTORY64 {}
+unsafe impl<V: VirtualAddress> Pod for IMAGE_LOAD_CONFIG_DIRECTORY_T<V> {}
 unsafe impl Pod for IMAGE_LOAD_CONFIG_CODE_INTEGRITY {}
 unsafe impl Pod for IMAGE_DYNAMIC_RELOCATION_TABLE {}
 unsafe impl Pod for IMAGE_DYNAMIC_RELOCATION32 {}
 unsafe impl Pod for IMAGE_DYNAMIC_RELOCATION64 {}
-unsafe impl Pod for IMAGE_GUARDCF32 {}
-unsafe impl Pod for IMAGE_GUARDCF64 {}
-unsafe impl Pod for IMAGE_TLS_DIRECTORY32 {}
-unsafe impl Pod for IMAGE_TLS_DIRECTORY64 {}
+unsafe impl<V: VirtualAddress> Pod for IMAGE_GUARDCF_T<V> {}
+unsafe impl<V: VirtualAddress> Pod for IMAGE_TLS_DIRECTORY_T<V> {}
 unsafe impl Pod for WIN_CERTIFICATE {}
 unsafe impl Pod for UNWIND_CODE {}
 unsafe impl Pod for UNWIND_INFO {}
 unsafe impl Pod for RUNTIME_FUNCTION {}
+unsafe impl Pod for IMAGE_ARM_RUNTIME_FUNCTION_ENTRY {}
 unsafe impl Pod for SCOPE_RECORD {}
 unsafe impl Pod for SCOPE_TABLE {}
 unsafe impl Pod for GUID {}
@@ -1005,6 +1470,10 @@ unsafe impl Pod for IMAGE_DEBUG_CV_INFO_PDB70 {}
 unsafe impl Pod for IMAGE_DEBUG_MISC {}
 unsafe impl Pod for FPO_DATA {}
 unsafe impl Pod for IMAGE_DEBUG_VC_FEATURE {}
+unsafe impl Pod for IMAGE_COR20_HEADER {}
+unsafe impl Pod for READYTORUN_HEADER {}
+unsafe impl Pod for READYTORUN_SECTION {}
+unsafe impl Pod for READYTORUN_IMPORT_SECTION {}
 
 //----------------------------------------------------------------
 
@@ -1018,6 +1487,7 @@ const _: [(); 136] = [(); mem::size_of::<IMAGE_NT_HEADERS64>()]; // Unsized
 const _: [(); 40] = [(); mem::size_of::<IMAGE_SECTION_HEADER>()];
 const _: [(); 40] = [(); mem::size_of::<IMAGE_EXPORT_DIRECTORY>()];
 const _: [(); 20] = [(); mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>()];
+const _: [(); 32] = [(); mem::size_of::<IMAGE_DELAYLOAD_DESCRIPTOR>()];
 const _: [(); 16] = [(); mem::size_of::<IMAGE_RESOURCE_DIRECTORY>()];
 const _: [(); 8] = [(); mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>()];
 const _: [(); 16] = [(); mem::size_of::<IMAGE_RESOURCE_DATA_ENTRY>()];
@@ -1035,6 +1505,7 @@ const _: [(); 40] = [(); mem::size_of::<IMAGE_TLS_DIRECTORY64>()];
 const _: [(); 2] = [(); mem::size_of::<UNWIND_CODE>()];
 const _: [(); 4] = [(); mem::size_of::<UNWIND_INFO>()]; // Unsized
 const _: [(); 12] = [(); mem::size_of::<RUNTIME_FUNCTION>()];
+const _: [(); 8] = [(); mem::size_of::<IMAGE_ARM_RUNTIME_FUNCTION_ENTRY>()];
 const _: [(); 4] = [(); mem::size_of::<SCOPE_TABLE>()]; // Unsized
 const _: [(); 16] = [(); mem::size_of::<SCOPE_RECORD>()];
 const _: [(); 16] = [(); mem::size_of::<GUID>()];
@@ -1042,3 +1513,7 @@ const _: [(); 28] = [(); mem::size_of::<IMAGE_DEBUG_DIRECTORY>()];
 const _: [(); 16] = [(); mem::size_of::<IMAGE_DEBUG_CV_INFO_PDB20>()]; // Unsized
 const _: [(); 24] = [(); mem::size_of::<IMAGE_DEBUG_CV_INFO_PDB70>()]; // Unsized
 const _: [(); 12] = [(); mem::size_of::<IMAGE_DEBUG_MISC>()]; // Unsized
+const _: [(); 72] = [(); mem::size_of::<IMAGE_COR20_HEADER>()];
+const _: [(); 16] = [(); mem::size_of::<READYTORUN_HEADER>()];
+const _: [(); 12] = [(); mem::size_of::<READYTORUN_SECTION>()];
+const _: [(); 20] = [(); mem::size_of::<READYTORUN_IMPORT_SECTION>()];
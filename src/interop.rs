@@ -0,0 +1,69 @@
+/*!
+Conversions from and to the PE representations of the `object` and `goblin` crates.
+
+Enable the `object` or `goblin` features to use these.
+They allow pipelines handling many executable formats to standardize on one of those readers while still reaching for pelite's directory-level detail on PE images.
+
+Both crates parse the file on disk layout, only [`PeFile`](../pe64/struct.PeFile.html) is supported.
+
+# Examples
+
+```
+# #[cfg(feature = "object")] {
+use pelite::pe64::{Pe, PeFile};
+
+# #[allow(dead_code)]
+fn example(file: &object::read::pe::PeFile64<'_>) -> pelite::Result<()> {
+	// The object file keeps a reference to the bytes it was parsed from
+	let file = PeFile::from_object(file)?;
+	let exports = file.exports()?;
+	println!("{}", exports.dll_name()?);
+	Ok(())
+}
+# }
+```
+*/
+
+use crate::*;
+
+macro_rules! impl_interop {
+	($pe:ident, $object:ident, $is_64:expr) => {
+		#[cfg(feature = "object")]
+		impl<'a> $pe::PeFile<'a> {
+			/// Constructs from the bytes the `object` crate's file was parsed from.
+			pub fn from_object(file: &object::read::pe::$object<'a, &'a [u8]>) -> Result<$pe::PeFile<'a>> {
+				$pe::PeFile::from_bytes(file.data())
+			}
+			/// Parses the image with the `object` crate.
+			pub fn to_object(&self) -> object::Result<object::read::pe::$object<'a, &'a [u8]>> {
+				use $pe::PeObject;
+				object::read::pe::$object::parse(self.image())
+			}
+		}
+
+		#[cfg(feature = "goblin")]
+		impl<'a> $pe::PeFile<'a> {
+			/// Constructs from the bytes the `goblin` crate's PE was parsed from.
+			///
+			/// The goblin PE does not keep a reference to its bytes, they must be passed separately.
+			///
+			/// # Errors
+			///
+			/// * [`PeMagic`](../../enum.Error.html#variant.PeMagic): The goblin PE is not of this format.
+			pub fn from_goblin(pe: &goblin::pe::PE<'_>, bytes: &'a [u8]) -> Result<$pe::PeFile<'a>> {
+				if pe.is_64 != $is_64 {
+					return Err(Error::PeMagic);
+				}
+				$pe::PeFile::from_bytes(bytes)
+			}
+			/// Parses the image with the `goblin` crate.
+			pub fn to_goblin(&self) -> goblin::error::Result<goblin::pe::PE<'a>> {
+				use $pe::PeObject;
+				goblin::pe::PE::parse(self.image())
+			}
+		}
+	};
+}
+
+impl_interop!(pe32, PeFile32, false);
+impl_interop!(pe64, PeFile64, true);
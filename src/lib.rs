@@ -10,6 +10,8 @@ Use it if you want to work with modules in your own process.
 Evidently this is only available on Windows targets.
 
 Due to small but incompatible differences the two formats are not unified.
+
+Malformed images are expected: the `Pe` accessors and the directory parsers never panic on arbitrary input, corruption is reported as an [`Error`](enum.Error.html) instead.
 */
 
 #![recursion_limit = "128"]
@@ -29,12 +31,18 @@ pub mod pattern;
 
 pub use pelite_macros::pattern;
 
+pub mod disasm_lite;
+
 mod error;
 pub use self::error::{Error, Result};
 
-#[cfg(feature = "mmap")]
+mod options;
+pub use self::options::{ParseOptions, Violation, Violations};
+
+// Memory mapping is not available on targets without an OS such as wasm32-unknown-unknown
+#[cfg(all(feature = "mmap", any(unix, windows)))]
 mod mmap;
-#[cfg(feature = "mmap")]
+#[cfg(all(feature = "mmap", any(unix, windows)))]
 pub use self::mmap::*;
 
 pub mod pe32;
@@ -47,6 +55,57 @@ mod pir;
 #[cfg(feature = "unstable")]
 pub use self::pir::Pir;
 
+/// Plain old data trait and its derive macro.
+///
+/// Implement `Pod` for custom structures to read them from the image with the `derva` family of methods.
+/// The derive macro (enabled by the default `derive_pod` feature) checks that the type is `#[repr(C)]` or `#[repr(transparent)]`,
+/// that every field implements `Pod` and that the struct has no padding.
+///
+/// ```
+/// use pelite::Pod;
+/// use pelite::pe64::{Pe, PeFile, Rva};
+///
+/// #[derive(Copy, Clone, Pod)]
+/// #[repr(C)]
+/// struct Header {
+/// 	magic: [u8; 4],
+/// 	size: u32,
+/// 	data: Rva,
+/// }
+///
+/// # #[allow(dead_code)]
+/// fn example(file: PeFile<'_>, rva: Rva) -> pelite::Result<()> {
+/// 	let header: &Header = file.derva(rva)?;
+/// 	let data = file.derva_slice::<u8>(header.data, header.size as usize)?;
+/// 	Ok(())
+/// }
+/// ```
+///
+/// Fixed-size arrays of any length are `Pod`, read tables directly instead of passing magic lengths to `derva_slice`:
+///
+/// ```
+/// use pelite::pe64::{Pe, PeFile, Rva};
+///
+/// # #[allow(dead_code)]
+/// fn example(file: PeFile<'_>, rva: Rva) -> pelite::Result<()> {
+/// 	let table: &[u32; 64] = file.derva(rva)?;
+/// 	let guid = file.derva_copy::<[u8; 16]>(rva)?;
+/// 	Ok(())
+/// }
+/// ```
+///
+/// Tuples are not `Pod`, their layout is unspecified. Use a `#[repr(C)]` struct instead.
+///
+/// Structures with padding or fields which aren't `Pod` are rejected at compiletime:
+///
+/// ```compile_fail
+/// #[derive(Copy, Clone, pelite::Pod)]
+/// #[repr(C)]
+/// struct Padded {
+/// 	byte: u8,
+/// 	dword: u32,
+/// }
+/// ```
 pub use dataview::Pod;
 
 /// Defaults to the current platform if it is available.
@@ -56,13 +115,20 @@ pub use self::pe32 as pe;
 #[cfg(all(windows, target_pointer_width = "64"))]
 pub use self::pe64 as pe;
 
+pub mod authenticode;
 pub mod base_relocs;
+#[cfg(any(feature = "object", feature = "goblin"))]
+pub mod interop;
+pub mod map_file;
+pub mod metadata;
+pub mod ordinals;
 
 #[cfg(any(feature = "std", feature = "resources_nostd"))]
 pub mod resources;
 pub mod rich_structure;
 pub mod security;
 pub mod strings;
+pub mod timestamp;
 
 // FIXME! Causes STATUS_STACK_BUFFER_OVERRUN in CI on nightly x86_64-pc-windows-msvc
 // #[cfg(test)]
@@ -0,0 +1,310 @@
+/*!
+MSVC linker map files.
+
+The linker writes a map file with `/MAP` listing the sections, the public and static symbols and the entry point.
+Map files are a cheap source of symbols when the PDB is unavailable, some SDKs ship them alongside their binaries.
+
+Parsing is lenient, lines which aren't understood are skipped.
+The symbols are addressed by section number and offset, correlate them with the section headers of the image to get their rvas.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile, Rva};
+use pelite::map_file::MapFile;
+use pelite::util::Le;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, text: &str, rva: Rva) {
+	let map_file = MapFile::parse(text);
+	// Check the map file belongs to the image
+	if map_file.time_date_stamp != Some(file.file_header().TimeDateStamp.le()) {
+		println!("stale map file!");
+	}
+	let symbols = map_file.correlate(file.section_headers().image());
+	if let Some((symbol, offset)) = symbols.lookup(rva) {
+		println!("{:#x} is {}+{:#x} from {}", rva, symbol.name, offset, symbol.object);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+
+use crate::image::IMAGE_SECTION_HEADER;
+use crate::util::Le;
+
+//----------------------------------------------------------------
+
+/// Section contribution, a group of the same name and class in one of the sections.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Group<'a> {
+	/// Section number, starting at 1.
+	pub section: u16,
+	/// Offset of the group in the section.
+	pub offset: u32,
+	/// Length of the group in bytes.
+	pub length: u32,
+	/// Name of the group, eg. `.text$mn`.
+	pub name: &'a str,
+	/// Class of the group, `CODE` or `DATA`.
+	pub class: &'a str,
+}
+
+/// Public or static symbol.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Symbol<'a> {
+	/// Section number, starting at 1. Absolute symbols are in section 0.
+	pub section: u16,
+	/// Offset of the symbol in the section.
+	pub offset: u32,
+	/// Decorated name of the symbol.
+	pub name: &'a str,
+	/// Virtual address of the symbol at the preferred load address.
+	pub va: u64,
+	/// The symbol is a function.
+	pub function: bool,
+	/// The symbol is an inline function.
+	pub inline: bool,
+	/// The symbol is static, listed in the static symbols.
+	pub is_static: bool,
+	/// The object file, prefixed by its library if any, eg. `libcmt:exe_main.obj`.
+	pub object: &'a str,
+}
+impl<'a> Symbol<'a> {
+	/// Returns the rva of the symbol given the section headers of the image.
+	///
+	/// Returns `None` for absolute symbols and symbols in sections the image does not have.
+	pub fn rva(&self, section_headers: &[IMAGE_SECTION_HEADER]) -> Option<u32> {
+		let section = section_headers.get((self.section as usize).checked_sub(1)?)?;
+		section.VirtualAddress.le().checked_add(self.offset)
+	}
+}
+
+/// Parsed map file.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MapFile<'a> {
+	/// Name of the module.
+	pub module: &'a str,
+	/// The timestamp of the image.
+	pub time_date_stamp: Option<u32>,
+	/// The preferred load address of the image.
+	pub load_address: Option<u64>,
+	/// Section contributions in order of the map file.
+	pub groups: Vec<Group<'a>>,
+	/// The public symbols followed by the static symbols in order of the map file.
+	pub symbols: Vec<Symbol<'a>>,
+	/// Section number and offset of the entry point.
+	pub entry_point: Option<(u16, u32)>,
+}
+
+impl<'a> MapFile<'a> {
+	/// Parses the map file text.
+	pub fn parse(text: &'a str) -> MapFile<'a> {
+		let mut map_file = MapFile::default();
+		let mut is_static = false;
+		for line in text.lines().map(str::trim) {
+			if line.is_empty() {
+				continue;
+			}
+			if map_file.module.is_empty() {
+				map_file.module = line;
+			}
+			else if let Some(rest) = line.strip_prefix("Timestamp is ") {
+				map_file.time_date_stamp = rest.split_whitespace().next().and_then(|ts| u32::from_str_radix(ts, 16).ok());
+			}
+			else if let Some(rest) = line.strip_prefix("Preferred load address is ") {
+				map_file.load_address = u64::from_str_radix(rest.trim(), 16).ok();
+			}
+			else if let Some(rest) = line.strip_prefix("entry point at") {
+				map_file.entry_point = parse_address(rest.trim());
+			}
+			else if line == "Static symbols" {
+				is_static = true;
+			}
+			else if let Some(group) = parse_group(line) {
+				map_file.groups.push(group);
+			}
+			else if let Some(symbol) = parse_symbol(line, is_static) {
+				map_file.symbols.push(symbol);
+			}
+		}
+		map_file
+	}
+	/// Correlates the symbols with the section headers of the image.
+	pub fn correlate(&self, section_headers: &[IMAGE_SECTION_HEADER]) -> Symbols<'_, 'a> {
+		let mut symbols: Vec<_> = self.symbols.iter().filter_map(|symbol| Some((symbol.rva(section_headers)?, symbol))).collect();
+		symbols.sort_by_key(|&(rva, symbol)| (rva, symbol.is_static));
+		let sections = section_headers.iter()
+			.map(|it| it.le())
+			.map(|it| (it.VirtualAddress, it.VirtualAddress.saturating_add(cmp::max(it.VirtualSize, it.SizeOfRawData))))
+			.collect();
+		Symbols { symbols, sections }
+	}
+}
+
+// Parses the section number and offset, eg. `0001:00000010`
+fn parse_address(s: &str) -> Option<(u16, u32)> {
+	let (section, offset) = s.split_once(':')?;
+	if section.len() != 4 || offset.len() != 8 {
+		return None;
+	}
+	Some((u16::from_str_radix(section, 16).ok()?, u32::from_str_radix(offset, 16).ok()?))
+}
+
+// Parses a section contribution, eg. `0001:00000000 00000f2aH .text$mn CODE`
+fn parse_group(line: &str) -> Option<Group<'_>> {
+	let mut tokens = line.split_whitespace();
+	let (section, offset) = parse_address(tokens.next()?)?;
+	let length = u32::from_str_radix(tokens.next()?.strip_suffix('H')?, 16).ok()?;
+	let name = tokens.next()?;
+	let class = tokens.next()?;
+	Some(Group { section, offset, length, name, class })
+}
+
+// Parses a symbol, eg. `0001:00000010 main 0000000140001010 f i main.obj`
+fn parse_symbol(line: &str, is_static: bool) -> Option<Symbol<'_>> {
+	let mut tokens = line.split_whitespace();
+	let (section, offset) = parse_address(tokens.next()?)?;
+	let name = tokens.next()?;
+	let va = u64::from_str_radix(tokens.next()?, 16).ok()?;
+	let mut symbol = Symbol { section, offset, name, va, function: false, inline: false, is_static, object: "" };
+	for token in tokens {
+		match token {
+			"f" => symbol.function = true,
+			"i" => symbol.inline = true,
+			object => symbol.object = object,
+		}
+	}
+	Some(symbol)
+}
+
+//----------------------------------------------------------------
+
+/// Symbols sorted by rva.
+///
+/// Created with the method [`correlate`](struct.MapFile.html#method.correlate).
+#[derive(Clone, Debug)]
+pub struct Symbols<'m, 'a> {
+	symbols: Vec<(u32, &'m Symbol<'a>)>,
+	sections: Vec<(u32, u32)>,
+}
+impl<'m, 'a> Symbols<'m, 'a> {
+	/// Returns the symbols and their rvas sorted by rva, public symbols before static symbols at the same rva.
+	pub fn as_slice(&self) -> &[(u32, &'m Symbol<'a>)] {
+		&self.symbols
+	}
+	/// Finds the symbol with the rva.
+	pub fn find(&self, rva: u32) -> Option<&'m Symbol<'a>> {
+		let index = self.symbols.partition_point(|&(it, _)| it < rva);
+		self.symbols.get(index).filter(|&&(it, _)| it == rva).map(|&(_, symbol)| symbol)
+	}
+	/// Finds the closest symbol at or before the rva in the same section and the offset of the rva from the symbol.
+	///
+	/// Without symbol sizes this is only a best guess for rvas far past the symbol.
+	pub fn lookup(&self, rva: u32) -> Option<(&'m Symbol<'a>, u32)> {
+		let end = self.symbols.partition_point(|&(it, _)| it <= rva);
+		let &(symbol_rva, _) = self.symbols[..end].last()?;
+		// Prefer the public symbol of aliases
+		let start = self.symbols[..end].partition_point(|&(it, _)| it < symbol_rva);
+		let symbol = self.symbols[start].1;
+		let &(_, section_end) = self.sections.get(symbol.section as usize - 1)?;
+		if rva >= section_end {
+			return None;
+		}
+		Some((symbol, rva - symbol_rva))
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEXT: &str = "
+ Demo
+
+ Timestamp is 5e2f8b2a (Mon Jan 27 19:24:58 2020)
+
+ Preferred load address is 0000000140000000
+
+ Start         Length     Name                   Class
+ 0001:00000000 00000f2aH .text$mn                CODE
+ 0002:00000000 00000120H .idata$5                DATA
+
+  Address         Publics by Value              Rva+Base               Lib:Object
+
+ 0000:00000000       __guard_flags              0000000000000000     <absolute>
+ 0001:00000000       main                       0000000140001000 f   main.obj
+ 0001:00000010       ?inl@@YAXXZ                0000000140001010 f i main.obj
+ 0002:00000008       __imp_Sleep                0000000140002008     kernel32:KERNEL32.dll
+
+ entry point at        0001:00000000
+
+ Static symbols
+
+ 0001:00000010       helper                     0000000140001010 f   main.obj
+";
+
+	fn section(virtual_address: u32, virtual_size: u32) -> IMAGE_SECTION_HEADER {
+		IMAGE_SECTION_HEADER {
+			Name: [0; 8],
+			VirtualSize: virtual_size,
+			VirtualAddress: virtual_address,
+			SizeOfRawData: 0,
+			PointerToRawData: 0,
+			PointerToRelocations: 0,
+			PointerToLinenumbers: 0,
+			NumberOfRelocations: 0,
+			NumberOfLinenumbers: 0,
+			Characteristics: 0,
+		}
+	}
+
+	#[test]
+	fn parse() {
+		let map_file = MapFile::parse(TEXT);
+		assert_eq!(map_file.module, "Demo");
+		assert_eq!(map_file.time_date_stamp, Some(0x5e2f8b2a));
+		assert_eq!(map_file.load_address, Some(0x140000000));
+		assert_eq!(map_file.entry_point, Some((1, 0)));
+		assert_eq!(map_file.groups.len(), 2);
+		assert_eq!(map_file.groups[0], Group { section: 1, offset: 0, length: 0xf2a, name: ".text$mn", class: "CODE" });
+		assert_eq!(map_file.symbols.len(), 5);
+		assert_eq!(map_file.symbols[2], Symbol {
+			section: 1,
+			offset: 0x10,
+			name: "?inl@@YAXXZ",
+			va: 0x140001010,
+			function: true,
+			inline: true,
+			is_static: false,
+			object: "main.obj",
+		});
+		assert_eq!(map_file.symbols[3].object, "kernel32:KERNEL32.dll");
+		assert!(!map_file.symbols[3].function);
+		assert!(map_file.symbols[4].is_static);
+	}
+
+	#[test]
+	fn correlate() {
+		let map_file = MapFile::parse(TEXT);
+		let section_headers = [section(0x1000, 0xf2a), section(0x2000, 0x120)];
+		let symbols = map_file.correlate(&section_headers);
+
+		// Absolute symbols have no rva
+		assert_eq!(symbols.as_slice().len(), 4);
+		assert_eq!(symbols.find(0x1010).unwrap().name, "?inl@@YAXXZ");
+		assert_eq!(symbols.find(0x1011), None);
+		let (symbol, offset) = symbols.lookup(0x1020).unwrap();
+		assert_eq!((symbol.name, offset), ("?inl@@YAXXZ", 0x10));
+		assert_eq!(symbols.lookup(0x2008).unwrap().0.name, "__imp_Sleep");
+		assert_eq!(symbols.lookup(0x2200), None);
+		assert_eq!(symbols.lookup(0xfff), None);
+	}
+}
@@ -0,0 +1,596 @@
+/*!
+CLI metadata.
+
+The metadata of managed assemblies is referenced by the [CLR header](../pe64/clr/index.html) and consists of a set of streams.
+The `#~` stream contains the metadata tables, their string columns are offsets in the `#Strings` heap.
+
+Only the parts needed to enumerate the manifest resources are decoded, the other tables are available as raw rows.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::metadata::{Implementation, Metadata};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let clr = file.clr()?;
+	let metadata = Metadata::new(clr.metadata()?)?;
+	println!("runtime {}", metadata.version());
+
+	for resource in metadata.tables()?.manifest_resources() {
+		let resource = resource?;
+		if resource.implementation == Implementation::Embedded {
+			let data = clr.manifest_resource_data(resource.offset)?;
+			println!("{}: {} bytes", resource.name, data.len());
+		}
+	}
+
+	Ok(())
+}
+```
+*/
+
+// References:
+//
+// * ECMA-335 6th edition, Partition II, §22 Metadata logical format: tables and §24 Metadata physical layout
+
+use std::{fmt, str};
+
+use crate::util::CStr;
+use crate::{Error, Result};
+
+//----------------------------------------------------------------
+
+const METADATA_SIGNATURE: u32 = 0x424A5342; // "BSJB"
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16> {
+	let bytes = bytes.get(offset..offset.wrapping_add(2)).ok_or(Error::Bounds)?;
+	Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+	let bytes = bytes.get(offset..offset.wrapping_add(4)).ok_or(Error::Bounds)?;
+	Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+// Blobs are prefixed with their length in a compressed unsigned integer
+fn read_blob(heap: &[u8], index: u32) -> Result<&[u8]> {
+	let bytes = heap.get(index as usize..).ok_or(Error::Bounds)?;
+	let (len, prefix) = match *bytes.first().ok_or(Error::Bounds)? {
+		byte @ 0x00..=0x7f => (byte as usize, 1usize),
+		byte @ 0x80..=0xbf => ((byte as usize & 0x3f) << 8 | *bytes.get(1).ok_or(Error::Bounds)? as usize, 2),
+		0xc0..=0xdf => (read_u32(bytes, 0)?.swap_bytes() as usize & 0x1fff_ffff, 4),
+		_ => return Err(Error::Invalid),
+	};
+	bytes.get(prefix..prefix.saturating_add(len)).ok_or(Error::Bounds)
+}
+
+/// Metadata root.
+#[derive(Copy, Clone)]
+pub struct Metadata<'a> {
+	bytes: &'a [u8],
+	version: &'a str,
+	streams: &'a [u8],
+	stream_count: u16,
+}
+impl<'a> Metadata<'a> {
+	/// Parses the metadata root.
+	///
+	/// # Errors
+	///
+	/// * [`BadMagic`](../enum.Error.html#variant.BadMagic): The signature is not `BSJB`.
+	///
+	/// * [`Bounds`](../enum.Error.html#variant.Bounds): The metadata root is truncated.
+	///
+	/// * [`Encoding`](../enum.Error.html#variant.Encoding): The version string is not valid UTF-8.
+	pub fn new(bytes: &'a [u8]) -> Result<Metadata<'a>> {
+		if read_u32(bytes, 0)? != METADATA_SIGNATURE {
+			return Err(Error::BadMagic);
+		}
+		let length = read_u32(bytes, 12)? as usize;
+		let version = bytes.get(16..16usize.saturating_add(length)).ok_or(Error::Bounds)?;
+		let version = &version[..version.iter().position(|&byte| byte == 0).unwrap_or(version.len())];
+		let version = str::from_utf8(version).map_err(|_| Error::Encoding)?;
+		let offset = 16 + length;
+		let stream_count = read_u16(bytes, offset + 2)?;
+		let streams = bytes.get(offset + 4..).ok_or(Error::Bounds)?;
+		Ok(Metadata { bytes, version, streams, stream_count })
+	}
+	/// Returns the metadata bytes.
+	pub fn bytes(&self) -> &'a [u8] {
+		self.bytes
+	}
+	/// Returns the runtime version the assembly was built against, eg. `v4.0.30319`.
+	pub fn version(&self) -> &'a str {
+		self.version
+	}
+	/// Iterates over the stream headers.
+	pub fn streams(&self) -> Streams<'a> {
+		Streams { bytes: self.bytes, headers: self.streams, count: self.stream_count }
+	}
+	/// Gets a stream by name.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if there is no such stream.
+	pub fn stream(&self, name: &str) -> Result<&'a [u8]> {
+		for stream in self.streams() {
+			let stream = stream?;
+			if stream.name == name {
+				return Ok(stream.data);
+			}
+		}
+		Err(Error::Null)
+	}
+	/// Gets a string from the `#Strings` heap.
+	pub fn string(&self, index: u32) -> Result<&'a CStr> {
+		let heap = self.stream("#Strings")?;
+		let bytes = heap.get(index as usize..).ok_or(Error::Bounds)?;
+		CStr::from_bytes(bytes).ok_or(Error::Bounds)
+	}
+	/// Gets a blob from the `#Blob` heap.
+	pub fn blob(&self, index: u32) -> Result<&'a [u8]> {
+		read_blob(self.stream("#Blob")?, index)
+	}
+	/// Parses the metadata tables from the `#~` stream, or the uncompressed `#-` stream.
+	pub fn tables(&self) -> Result<Tables<'a>> {
+		let stream = match self.stream("#~") {
+			Err(Error::Null) => self.stream("#-")?,
+			stream => stream?,
+		};
+		let heap = |name| match self.stream(name) {
+			Err(Error::Null) => Ok(&[][..]),
+			heap => heap,
+		};
+		Tables::new(stream, heap("#Strings")?, heap("#Blob")?)
+	}
+}
+impl<'a> fmt::Debug for Metadata<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Metadata")
+			.field("version", &self.version)
+			.field("streams", &self.streams())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Metadata stream.
+#[derive(Copy, Clone, Debug)]
+pub struct Stream<'a> {
+	/// Name of the stream, eg. `#Strings`.
+	pub name: &'a str,
+	/// Offset of the stream from the start of the metadata root.
+	pub offset: u32,
+	/// Contents of the stream.
+	pub data: &'a [u8],
+}
+
+/// Iterator over the metadata streams.
+#[derive(Clone)]
+pub struct Streams<'a> {
+	bytes: &'a [u8],
+	headers: &'a [u8],
+	count: u16,
+}
+impl<'a> Iterator for Streams<'a> {
+	type Item = Result<Stream<'a>>;
+	fn next(&mut self) -> Option<Result<Stream<'a>>> {
+		if self.count == 0 {
+			return None;
+		}
+		self.count -= 1;
+		let result = (|| {
+			let offset = read_u32(self.headers, 0)?;
+			let size = read_u32(self.headers, 4)?;
+			let name = self.headers.get(8..).ok_or(Error::Bounds)?;
+			let len = name.iter().take(32).position(|&byte| byte == 0).ok_or(Error::Bounds)?;
+			// The name is padded to a multiple of four bytes including its nul terminator
+			let padded = (len + 4) & !3;
+			let name = str::from_utf8(&name[..len]).map_err(|_| Error::Encoding)?;
+			let data = self.bytes.get(offset as usize..(offset as usize).saturating_add(size as usize)).ok_or(Error::Bounds)?;
+			self.headers = self.headers.get(8 + padded..).unwrap_or(&[]);
+			Ok(Stream { name, offset, data })
+		})();
+		if result.is_err() {
+			self.count = 0;
+		}
+		Some(result)
+	}
+}
+impl<'a> fmt::Debug for Streams<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.clone().filter_map(|stream| stream.ok().map(|stream| stream.name))).finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Metadata table identifiers.
+pub mod table {
+	pub const MODULE: usize = 0x00;
+	pub const TYPE_REF: usize = 0x01;
+	pub const TYPE_DEF: usize = 0x02;
+	pub const FIELD_PTR: usize = 0x03;
+	pub const FIELD: usize = 0x04;
+	pub const METHOD_PTR: usize = 0x05;
+	pub const METHOD_DEF: usize = 0x06;
+	pub const PARAM_PTR: usize = 0x07;
+	pub const PARAM: usize = 0x08;
+	pub const INTERFACE_IMPL: usize = 0x09;
+	pub const MEMBER_REF: usize = 0x0A;
+	pub const CONSTANT: usize = 0x0B;
+	pub const CUSTOM_ATTRIBUTE: usize = 0x0C;
+	pub const FIELD_MARSHAL: usize = 0x0D;
+	pub const DECL_SECURITY: usize = 0x0E;
+	pub const CLASS_LAYOUT: usize = 0x0F;
+	pub const FIELD_LAYOUT: usize = 0x10;
+	pub const STAND_ALONE_SIG: usize = 0x11;
+	pub const EVENT_MAP: usize = 0x12;
+	pub const EVENT_PTR: usize = 0x13;
+	pub const EVENT: usize = 0x14;
+	pub const PROPERTY_MAP: usize = 0x15;
+	pub const PROPERTY_PTR: usize = 0x16;
+	pub const PROPERTY: usize = 0x17;
+	pub const METHOD_SEMANTICS: usize = 0x18;
+	pub const METHOD_IMPL: usize = 0x19;
+	pub const MODULE_REF: usize = 0x1A;
+	pub const TYPE_SPEC: usize = 0x1B;
+	pub const IMPL_MAP: usize = 0x1C;
+	pub const FIELD_RVA: usize = 0x1D;
+	pub const ENC_LOG: usize = 0x1E;
+	pub const ENC_MAP: usize = 0x1F;
+	pub const ASSEMBLY: usize = 0x20;
+	pub const ASSEMBLY_PROCESSOR: usize = 0x21;
+	pub const ASSEMBLY_OS: usize = 0x22;
+	pub const ASSEMBLY_REF: usize = 0x23;
+	pub const ASSEMBLY_REF_PROCESSOR: usize = 0x24;
+	pub const ASSEMBLY_REF_OS: usize = 0x25;
+	pub const FILE: usize = 0x26;
+	pub const EXPORTED_TYPE: usize = 0x27;
+	pub const MANIFEST_RESOURCE: usize = 0x28;
+	pub const NESTED_CLASS: usize = 0x29;
+	pub const GENERIC_PARAM: usize = 0x2A;
+	pub const METHOD_SPEC: usize = 0x2B;
+	pub const GENERIC_PARAM_CONSTRAINT: usize = 0x2C;
+}
+
+// Column types of the table schemas
+#[derive(Copy, Clone)]
+enum Col {
+	U16,
+	U32,
+	Str,
+	Guid,
+	Blob,
+	Table(usize),
+	Coded(&'static [usize]),
+}
+
+// Coded index tables, `!0` marks unused tags
+const TYPE_DEF_OR_REF: &[usize] = &[table::TYPE_DEF, table::TYPE_REF, table::TYPE_SPEC];
+const HAS_CONSTANT: &[usize] = &[table::FIELD, table::PARAM, table::PROPERTY];
+const HAS_CUSTOM_ATTRIBUTE: &[usize] = &[
+	table::METHOD_DEF, table::FIELD, table::TYPE_REF, table::TYPE_DEF, table::PARAM, table::INTERFACE_IMPL, table::MEMBER_REF,
+	table::MODULE, table::DECL_SECURITY, table::PROPERTY, table::EVENT, table::STAND_ALONE_SIG, table::MODULE_REF, table::TYPE_SPEC,
+	table::ASSEMBLY, table::ASSEMBLY_REF, table::FILE, table::EXPORTED_TYPE, table::MANIFEST_RESOURCE, table::GENERIC_PARAM,
+	table::GENERIC_PARAM_CONSTRAINT, table::METHOD_SPEC,
+];
+const HAS_FIELD_MARSHAL: &[usize] = &[table::FIELD, table::PARAM];
+const HAS_DECL_SECURITY: &[usize] = &[table::TYPE_DEF, table::METHOD_DEF, table::ASSEMBLY];
+const MEMBER_REF_PARENT: &[usize] = &[table::TYPE_DEF, table::TYPE_REF, table::MODULE_REF, table::METHOD_DEF, table::TYPE_SPEC];
+const HAS_SEMANTICS: &[usize] = &[table::EVENT, table::PROPERTY];
+const METHOD_DEF_OR_REF: &[usize] = &[table::METHOD_DEF, table::MEMBER_REF];
+const MEMBER_FORWARDED: &[usize] = &[table::FIELD, table::METHOD_DEF];
+const IMPLEMENTATION: &[usize] = &[table::FILE, table::ASSEMBLY_REF, table::EXPORTED_TYPE];
+const CUSTOM_ATTRIBUTE_TYPE: &[usize] = &[!0, !0, table::METHOD_DEF, table::MEMBER_REF, !0];
+const RESOLUTION_SCOPE: &[usize] = &[table::MODULE, table::MODULE_REF, table::ASSEMBLY_REF, table::TYPE_REF];
+const TYPE_OR_METHOD_DEF: &[usize] = &[table::TYPE_DEF, table::METHOD_DEF];
+
+use self::Col::*;
+static SCHEMA: [&[Col]; 0x2D] = [
+	/*Module*/ &[U16, Str, Guid, Guid, Guid],
+	/*TypeRef*/ &[Coded(RESOLUTION_SCOPE), Str, Str],
+	/*TypeDef*/ &[U32, Str, Str, Coded(TYPE_DEF_OR_REF), Table(table::FIELD), Table(table::METHOD_DEF)],
+	/*FieldPtr*/ &[Table(table::FIELD)],
+	/*Field*/ &[U16, Str, Blob],
+	/*MethodPtr*/ &[Table(table::METHOD_DEF)],
+	/*MethodDef*/ &[U32, U16, U16, Str, Blob, Table(table::PARAM)],
+	/*ParamPtr*/ &[Table(table::PARAM)],
+	/*Param*/ &[U16, U16, Str],
+	/*InterfaceImpl*/ &[Table(table::TYPE_DEF), Coded(TYPE_DEF_OR_REF)],
+	/*MemberRef*/ &[Coded(MEMBER_REF_PARENT), Str, Blob],
+	/*Constant*/ &[U16, Coded(HAS_CONSTANT), Blob],
+	/*CustomAttribute*/ &[Coded(HAS_CUSTOM_ATTRIBUTE), Coded(CUSTOM_ATTRIBUTE_TYPE), Blob],
+	/*FieldMarshal*/ &[Coded(HAS_FIELD_MARSHAL), Blob],
+	/*DeclSecurity*/ &[U16, Coded(HAS_DECL_SECURITY), Blob],
+	/*ClassLayout*/ &[U16, U32, Table(table::TYPE_DEF)],
+	/*FieldLayout*/ &[U32, Table(table::FIELD)],
+	/*StandAloneSig*/ &[Blob],
+	/*EventMap*/ &[Table(table::TYPE_DEF), Table(table::EVENT)],
+	/*EventPtr*/ &[Table(table::EVENT)],
+	/*Event*/ &[U16, Str, Coded(TYPE_DEF_OR_REF)],
+	/*PropertyMap*/ &[Table(table::TYPE_DEF), Table(table::PROPERTY)],
+	/*PropertyPtr*/ &[Table(table::PROPERTY)],
+	/*Property*/ &[U16, Str, Blob],
+	/*MethodSemantics*/ &[U16, Table(table::METHOD_DEF), Coded(HAS_SEMANTICS)],
+	/*MethodImpl*/ &[Table(table::TYPE_DEF), Coded(METHOD_DEF_OR_REF), Coded(METHOD_DEF_OR_REF)],
+	/*ModuleRef*/ &[Str],
+	/*TypeSpec*/ &[Blob],
+	/*ImplMap*/ &[U16, Coded(MEMBER_FORWARDED), Str, Table(table::MODULE_REF)],
+	/*FieldRVA*/ &[U32, Table(table::FIELD)],
+	/*EncLog*/ &[U32, U32],
+	/*EncMap*/ &[U32],
+	/*Assembly*/ &[U32, U16, U16, U16, U16, U32, Blob, Str, Str],
+	/*AssemblyProcessor*/ &[U32],
+	/*AssemblyOS*/ &[U32, U32, U32],
+	/*AssemblyRef*/ &[U16, U16, U16, U16, U32, Blob, Str, Str, Blob],
+	/*AssemblyRefProcessor*/ &[U32, Table(table::ASSEMBLY_REF)],
+	/*AssemblyRefOS*/ &[U32, U32, U32, Table(table::ASSEMBLY_REF)],
+	/*File*/ &[U32, Str, Blob],
+	/*ExportedType*/ &[U32, U32, Str, Str, Coded(IMPLEMENTATION)],
+	/*ManifestResource*/ &[U32, U32, Str, Coded(IMPLEMENTATION)],
+	/*NestedClass*/ &[Table(table::TYPE_DEF), Table(table::TYPE_DEF)],
+	/*GenericParam*/ &[U16, U16, Coded(TYPE_OR_METHOD_DEF), Str],
+	/*MethodSpec*/ &[Coded(METHOD_DEF_OR_REF), Blob],
+	/*GenericParamConstraint*/ &[Table(table::GENERIC_PARAM), Coded(TYPE_DEF_OR_REF)],
+];
+
+/// Metadata tables.
+#[derive(Copy, Clone)]
+pub struct Tables<'a> {
+	strings: &'a [u8],
+	blobs: &'a [u8],
+	heap_sizes: u8,
+	rows: [u32; 64],
+	// Offset of each table from the start of the stream, `usize::MAX` after an unknown table
+	offsets: [usize; 64],
+	stream: &'a [u8],
+}
+impl<'a> Tables<'a> {
+	fn new(stream: &'a [u8], strings: &'a [u8], blobs: &'a [u8]) -> Result<Tables<'a>> {
+		let heap_sizes = *stream.get(6).ok_or(Error::Bounds)?;
+		let valid = read_u32(stream, 8)? as u64 | (read_u32(stream, 12)? as u64) << 32;
+		let mut rows = [0u32; 64];
+		let mut offset = 24;
+		for (index, rows) in rows.iter_mut().enumerate() {
+			if valid & (1u64 << index) != 0 {
+				*rows = read_u32(stream, offset)?;
+				offset += 4;
+			}
+		}
+		// Uncompressed streams may have an extra dword after the row counts
+		if heap_sizes & 0x40 != 0 {
+			offset += 4;
+		}
+		let mut tables = Tables { strings, blobs, heap_sizes, rows, offsets: [usize::MAX; 64], stream };
+		for (index, &count) in rows.iter().enumerate() {
+			if offset > stream.len() {
+				break;
+			}
+			tables.offsets[index] = offset;
+			if count == 0 {
+				continue;
+			}
+			// The layout of unknown tables is not known, the tables which follow it cannot be located
+			let row_size = match tables.row_size(index) {
+				Some(row_size) => row_size,
+				None => break,
+			};
+			offset = offset.saturating_add((count as usize).saturating_mul(row_size));
+		}
+		Ok(tables)
+	}
+	/// Returns the number of rows in the table.
+	pub fn rows(&self, table: usize) -> u32 {
+		self.rows.get(table).cloned().unwrap_or(0)
+	}
+	/// Returns the size of a row in bytes, `None` if the table is unknown.
+	pub fn row_size(&self, table: usize) -> Option<usize> {
+		let schema = SCHEMA.get(table)?;
+		Some(schema.iter().map(|&col| self.col_size(col)).sum())
+	}
+	/// Gets the raw bytes of a row, the index is one based as in metadata tokens.
+	pub fn row(&self, table: usize, index: u32) -> Result<&'a [u8]> {
+		if index == 0 || index > self.rows(table) {
+			return Err(Error::Bounds);
+		}
+		let row_size = self.row_size(table).ok_or(Error::Invalid)?;
+		let offset = self.offsets.get(table).cloned().filter(|&offset| offset != usize::MAX).ok_or(Error::Invalid)?;
+		let start = offset.saturating_add((index as usize - 1).saturating_mul(row_size));
+		self.stream.get(start..start.saturating_add(row_size)).ok_or(Error::Bounds)
+	}
+	/// Gets the public key of the assembly from the Assembly table.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the assembly has no public key.
+	pub fn public_key(&self) -> Result<&'a [u8]> {
+		let row = self.row(table::ASSEMBLY, 1)?;
+		let public_key = read_blob(self.blobs, self.read_col(row, table::ASSEMBLY, 6))?;
+		if public_key.is_empty() {
+			return Err(Error::Null);
+		}
+		Ok(public_key)
+	}
+	/// Iterates over the ManifestResource table.
+	pub fn manifest_resources(&self) -> ManifestResources<'a> {
+		ManifestResources { tables: *self, index: 1 }
+	}
+	fn col_size(&self, col: Col) -> usize {
+		match col {
+			U16 => 2,
+			U32 => 4,
+			Str => if self.heap_sizes & 0x01 != 0 { 4 } else { 2 },
+			Guid => if self.heap_sizes & 0x02 != 0 { 4 } else { 2 },
+			Blob => if self.heap_sizes & 0x04 != 0 { 4 } else { 2 },
+			Table(table) => if self.rows[table] < 0x10000 { 2 } else { 4 },
+			Coded(tables) => {
+				let tag_bits = coded_tag_bits(tables);
+				let max_rows = tables.iter().filter(|&&table| table != !0).map(|&table| self.rows[table]).max().unwrap_or(0);
+				if max_rows < 1 << (16 - tag_bits) { 2 } else { 4 }
+			},
+		}
+	}
+	fn read_col(&self, row: &[u8], table: usize, col: usize) -> u32 {
+		let schema = SCHEMA[table];
+		let offset: usize = schema[..col].iter().map(|&col| self.col_size(col)).sum();
+		match self.col_size(schema[col]) {
+			2 => read_u16(row, offset).map(|value| value as u32),
+			_ => read_u32(row, offset),
+		}.unwrap_or(0)
+	}
+	fn string(&self, index: u32) -> Result<&'a CStr> {
+		let bytes = self.strings.get(index as usize..).ok_or(Error::Bounds)?;
+		CStr::from_bytes(bytes).ok_or(Error::Bounds)
+	}
+}
+impl<'a> fmt::Debug for Tables<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut map = f.debug_map();
+		for (index, &rows) in self.rows.iter().enumerate() {
+			if rows != 0 {
+				map.entry(&format_args!("{:#04x}", index), &rows);
+			}
+		}
+		map.finish()
+	}
+}
+
+fn coded_tag_bits(tables: &[usize]) -> u32 {
+	(tables.len() as u32).next_power_of_two().trailing_zeros()
+}
+
+//----------------------------------------------------------------
+
+/// Location of a manifest resource.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Implementation {
+	/// Embedded in the CLR resources directory of this image.
+	Embedded,
+	/// Stored in another file of the assembly, the row index in the File table.
+	File(u32),
+	/// Stored in another assembly, the row index in the AssemblyRef table.
+	AssemblyRef(u32),
+	/// Invalid coded index.
+	Invalid(u32),
+}
+
+/// Manifest resource.
+#[derive(Copy, Clone, Debug)]
+pub struct ManifestResource<'a> {
+	/// Offset of the resource in the CLR resources directory if embedded.
+	pub offset: u32,
+	/// Visibility flags, `1` for public and `2` for private.
+	pub flags: u32,
+	/// Name of the resource, eg. `App.Properties.Resources.resources`.
+	pub name: &'a CStr,
+	/// Location of the resource.
+	pub implementation: Implementation,
+}
+
+/// Iterator over the ManifestResource table.
+#[derive(Clone)]
+pub struct ManifestResources<'a> {
+	tables: Tables<'a>,
+	index: u32,
+}
+impl<'a> Iterator for ManifestResources<'a> {
+	type Item = Result<ManifestResource<'a>>;
+	fn next(&mut self) -> Option<Result<ManifestResource<'a>>> {
+		const TABLE: usize = table::MANIFEST_RESOURCE;
+		if self.index > self.tables.rows(TABLE) {
+			return None;
+		}
+		let index = self.index;
+		self.index += 1;
+		let row = match self.tables.row(TABLE, index) {
+			Ok(row) => row,
+			Err(err) => {
+				self.index = u32::MAX;
+				return Some(Err(err));
+			},
+		};
+		let tables = &self.tables;
+		let coded = tables.read_col(row, TABLE, 3);
+		let implementation = match (coded & 3, coded >> 2) {
+			(_, 0) => Implementation::Embedded,
+			(0, index) => Implementation::File(index),
+			(1, index) => Implementation::AssemblyRef(index),
+			_ => Implementation::Invalid(coded),
+		};
+		Some(tables.string(tables.read_col(row, TABLE, 2)).map(|name| ManifestResource {
+			offset: tables.read_col(row, TABLE, 0),
+			flags: tables.read_col(row, TABLE, 1),
+			name,
+			implementation,
+		}))
+	}
+}
+impl<'a> fmt::Debug for ManifestResources<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.clone()).finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn words(bytes: &mut Vec<u8>, words: &[u32]) {
+		for word in words {
+			bytes.extend_from_slice(&word.to_le_bytes());
+		}
+	}
+
+	#[test]
+	fn tables() {
+		let strings = b"\0App.resources\0Other.resources\0\0";
+		let blobs = b"\0\x03key\x80\x01";
+		let mut tables = Vec::new();
+		let valid_hi = 1 << (table::ASSEMBLY - 32) | 1 << (table::MANIFEST_RESOURCE - 32);
+		words(&mut tables, &[0, 0x0000_0002, 1 << table::MODULE, valid_hi, 0, 0, 1, 1, 2]);
+		tables.extend_from_slice(&[0, 0, 0x0f, 0, 1, 0, 0, 0, 0, 0]);
+		words(&mut tables, &[0x8004, 1, 0, 0]);
+		tables.extend_from_slice(&[1, 0, 1, 0, 0, 0]);
+		words(&mut tables, &[0, 1]);
+		tables.extend_from_slice(&[1, 0, 0, 0]);
+		words(&mut tables, &[0, 2]);
+		tables.extend_from_slice(&[15, 0, 1 << 2 | 1, 0, 0, 0]);
+
+		let mut bytes = Vec::new();
+		words(&mut bytes, &[METADATA_SIGNATURE, 0x0001_0001, 0, 12]);
+		bytes.extend_from_slice(b"v4.0.30319\0\0");
+		words(&mut bytes, &[3 << 16]);
+		words(&mut bytes, &[80, tables.len() as u32]);
+		bytes.extend_from_slice(b"#~\0\0");
+		words(&mut bytes, &[80 + tables.len() as u32, strings.len() as u32]);
+		bytes.extend_from_slice(b"#Strings\0\0\0\0");
+		words(&mut bytes, &[80 + tables.len() as u32 + strings.len() as u32, blobs.len() as u32]);
+		bytes.extend_from_slice(b"#Blob\0\0\0");
+		assert_eq!(bytes.len(), 80);
+		bytes.extend_from_slice(&tables);
+		bytes.extend_from_slice(strings);
+		bytes.extend_from_slice(blobs);
+
+		let metadata = Metadata::new(&bytes).unwrap();
+		assert_eq!(metadata.version(), "v4.0.30319");
+		assert_eq!(metadata.streams().count(), 3);
+		assert_eq!(metadata.stream("#US").map(|_| ()), Err(Error::Null));
+		assert_eq!(metadata.string(1).unwrap(), "App.resources");
+		assert_eq!(metadata.blob(1), Ok(&b"key"[..]));
+		assert_eq!(metadata.blob(5), Err(Error::Bounds));
+
+		let tables = metadata.tables().unwrap();
+		assert_eq!((tables.rows(table::MODULE), tables.rows(table::MANIFEST_RESOURCE)), (1, 2));
+		assert_eq!(tables.row_size(table::MANIFEST_RESOURCE), Some(12));
+		assert_eq!(tables.row(table::MODULE, 1).unwrap(), &[0, 0, 0x0f, 0, 1, 0, 0, 0, 0, 0]);
+		assert_eq!(tables.public_key(), Ok(&b"key"[..]));
+		let resources: Vec<_> = tables.manifest_resources().map(|resource| resource.unwrap()).collect();
+		assert_eq!(resources.len(), 2);
+		assert_eq!((resources[0].name.to_str(), resources[0].flags, resources[0].implementation), (Ok("App.resources"), 1, Implementation::Embedded));
+		assert_eq!((resources[1].name.to_str(), resources[1].implementation), (Ok("Other.resources"), Implementation::AssemblyRef(1)));
+
+		assert_eq!(Metadata::new(&bytes[4..]).map(|_| ()), Err(Error::BadMagic));
+		assert!(Metadata::new(&bytes[..60]).unwrap().streams().any(|stream| stream.is_err()));
+	}
+}
@@ -0,0 +1,157 @@
+/*!
+Parse options.
+*/
+
+use std::fmt;
+
+/// Configurable limits protecting against hostile images.
+///
+/// Exceeding any of these limits results in an [`Insanity`](enum.Error.html#variant.Insanity) error.
+///
+/// The defaults match the behavior of the regular constructors.
+///
+/// # Examples
+///
+/// ```
+/// use pelite::ParseOptions;
+/// use pelite::pe64::PeFile;
+///
+/// # #[allow(dead_code)]
+/// fn example(bytes: &[u8]) -> pelite::Result<PeFile<'_>> {
+/// 	let options = ParseOptions {
+/// 		max_string_len: 0x1000,
+/// 		max_import_descriptors: 0x100,
+/// 		..ParseOptions::DEFAULT
+/// 	};
+/// 	PeFile::from_bytes_with_options(bytes, options)
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ParseOptions {
+	/// Maximum `NumberOfSections` in the file header.
+	///
+	/// Defaults to `96`, the limit historically imposed by the Windows loader.
+	pub max_sections: u16,
+	/// Maximum length in bytes of nul-terminated strings read with `derva_c_str` and `deref_c_str`, not including the nul terminator.
+	///
+	/// Defaults to unlimited, strings are always bounded by the section they are contained in.
+	pub max_string_len: usize,
+	/// Maximum nesting depth of the resource directory when walking the resources filesystem.
+	///
+	/// Defaults to `32`, regular images have a depth of `3`.
+	pub max_resource_depth: u32,
+	/// Maximum number of import descriptors in the import directory.
+	///
+	/// Defaults to unlimited, the descriptors are always bounded by the section they are contained in.
+	pub max_import_descriptors: usize,
+	/// Maximum `SizeOfImage` of images read on demand by the `PeReader`, which allocates a zeroed buffer this large up front.
+	///
+	/// Defaults to unlimited, the `SizeOfImage` is at most 4 GiB and only the pages which are loaded are typically committed.
+	pub max_image_size: usize,
+	/// Tolerate header violations the Windows loader accepts, recording them as [`Violations`](struct.Violations.html) instead.
+	///
+	/// Defaults to `false`, see `PeFile::from_bytes_lax`.
+	pub lenient: bool,
+}
+
+impl ParseOptions {
+	/// The default parse options.
+	pub const DEFAULT: ParseOptions = ParseOptions {
+		max_sections: 96,
+		max_string_len: usize::MAX,
+		max_resource_depth: 32,
+		max_import_descriptors: usize::MAX,
+		max_image_size: usize::MAX,
+		lenient: false,
+	};
+	/// The lenient parse options.
+	pub const LENIENT: ParseOptions = ParseOptions { lenient: true, ..ParseOptions::DEFAULT };
+}
+
+impl Default for ParseOptions {
+	#[inline]
+	fn default() -> ParseOptions {
+		ParseOptions::DEFAULT
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Header violation tolerated by the lenient constructors.
+///
+/// The strict constructors reject the image for the violations documented as such, the others are only ever recorded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Violation {
+	/// `e_lfanew` exceeds the reasonable limit of 16 MiB, rejected by the strict constructors.
+	LfanewLimit,
+	/// `e_lfanew` is not aligned to 8 bytes as required by the PE specification.
+	///
+	/// The headers are accessed in place, PE32+ headers need 8 byte alignment and are rejected with `Misaligned` regardless
+	/// unless the `unsafe_alignment` feature is enabled.
+	LfanewAlignment,
+	/// `SizeOfHeaders` exceeds the size of the image, rejected by the strict constructors.
+	HeadersBounds,
+	/// `SizeOfHeaders` exceeds `SizeOfImage`, rejected by the strict constructors.
+	HeadersExceedImage,
+	/// `NumberOfSections` exceeds [`max_sections`](struct.ParseOptions.html#structfield.max_sections), rejected by the strict constructors.
+	TooManySections,
+	/// `FileAlignment` is not a power of two between 512 and 64K while not equal to `SectionAlignment`.
+	FileAlignment,
+	/// `SectionAlignment` is not a power of two or is less than `FileAlignment`.
+	SectionAlignment,
+}
+impl Violation {
+	/// All the violations.
+	pub const ALL: [Violation; 7] = [
+		Violation::LfanewLimit,
+		Violation::LfanewAlignment,
+		Violation::HeadersBounds,
+		Violation::HeadersExceedImage,
+		Violation::TooManySections,
+		Violation::FileAlignment,
+		Violation::SectionAlignment,
+	];
+	/// Returns true if the strict constructors reject images with this violation.
+	pub const fn is_fatal(self) -> bool {
+		matches!(self, Violation::LfanewLimit | Violation::HeadersBounds | Violation::HeadersExceedImage | Violation::TooManySections)
+	}
+	#[inline]
+	const fn bit(self) -> u32 {
+		1 << self as u32
+	}
+}
+
+/// Set of header violations.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Violations(u32);
+impl Violations {
+	/// Returns the empty set.
+	#[inline]
+	pub const fn empty() -> Violations {
+		Violations(0)
+	}
+	/// Returns true if no violations were recorded.
+	#[inline]
+	pub const fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+	/// Returns true if the violation was recorded.
+	#[inline]
+	pub const fn contains(self, violation: Violation) -> bool {
+		self.0 & violation.bit() != 0
+	}
+	/// Records the violation.
+	#[inline]
+	pub fn insert(&mut self, violation: Violation) {
+		self.0 |= violation.bit();
+	}
+	/// Iterates over the recorded violations.
+	pub fn iter(self) -> impl Clone + Iterator<Item = Violation> {
+		Violation::ALL.iter().cloned().filter(move |&violation| self.contains(violation))
+	}
+}
+impl fmt::Debug for Violations {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_set().entries(self.iter()).finish()
+	}
+}
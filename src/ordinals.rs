@@ -0,0 +1,209 @@
+/*!
+Names of symbols imported by ordinal.
+
+Some dlls are commonly imported by ordinal only, eg. `ws2_32.dll` and `oleaut32.dll`, MFC dlls export almost nothing by name.
+The import directory then only knows the ordinal and reports become hard to read without the exports of the dll at hand.
+
+Known ordinal to name tables are registered with [`OrdinalNames`](struct.OrdinalNames.html) to name these imports.
+A small table of well known ordinals is built in with the `ordinal_names` feature.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::imports::Import;
+use pelite::ordinals::{OrdinalNames, OrdinalTable};
+
+// Ordinals of a private dll
+static MYLIB: OrdinalTable<'static> = OrdinalTable {
+	dll_name: "mylib.dll",
+	names: &[(1, "MyInit"), (2, "MyExit")],
+};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let mut ordinal_names = OrdinalNames::new();
+	ordinal_names.add(&MYLIB);
+
+	for desc in file.imports()? {
+		let dll_name = desc.dll_name()?;
+		for import in desc.int()? {
+			if let Ok(Import::ByOrdinal { ord }) = import {
+				match ordinal_names.lookup(dll_name, ord) {
+					Some(name) => println!("{}!{} (#{})", dll_name, name, ord),
+					None => println!("{}!#{}", dll_name, ord),
+				}
+			}
+		}
+	}
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+//----------------------------------------------------------------
+
+/// Ordinal to name table of a dll.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OrdinalTable<'a> {
+	/// Name of the dll, the `.dll` extension is optional.
+	pub dll_name: &'a str,
+	/// Ordinals and their names.
+	pub names: &'a [(u16, &'a str)],
+}
+impl<'a> OrdinalTable<'a> {
+	/// Returns true if the table belongs to the dll, ignoring ASCII case and the `.dll` extension.
+	pub fn is_dll<S: AsRef<[u8]> + ?Sized>(&self, dll_name: &S) -> bool {
+		strip_dll(self.dll_name.as_bytes()).eq_ignore_ascii_case(strip_dll(dll_name.as_ref()))
+	}
+	/// Finds the name of the ordinal.
+	pub fn lookup(&self, ordinal: u16) -> Option<&'a str> {
+		self.names.iter().find(|&&(it, _)| it == ordinal).map(|&(_, name)| name)
+	}
+}
+
+fn strip_dll(name: &[u8]) -> &[u8] {
+	if name.len() >= 4 && name[name.len() - 4..].eq_ignore_ascii_case(b".dll") {
+		&name[..name.len() - 4]
+	}
+	else {
+		name
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Collection of ordinal to name tables.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default)]
+pub struct OrdinalNames<'a> {
+	tables: Vec<&'a OrdinalTable<'a>>,
+}
+impl<'a> OrdinalNames<'a> {
+	/// Creates an empty collection.
+	pub fn new() -> OrdinalNames<'a> {
+		OrdinalNames { tables: Vec::new() }
+	}
+	/// Creates a collection of the built in tables.
+	#[cfg(feature = "ordinal_names")]
+	pub fn builtin() -> OrdinalNames<'static> {
+		OrdinalNames { tables: BUILTIN.to_vec() }
+	}
+	/// Adds a table, tables added later take precedence over earlier tables for the same dll.
+	pub fn add(&mut self, table: &'a OrdinalTable<'a>) -> &mut OrdinalNames<'a> {
+		self.tables.push(table);
+		self
+	}
+	/// Returns the tables in order they were added.
+	pub fn tables(&self) -> &[&'a OrdinalTable<'a>] {
+		&self.tables
+	}
+	/// Finds the name of the ordinal exported by the dll.
+	pub fn lookup<S: AsRef<[u8]> + ?Sized>(&self, dll_name: &S, ordinal: u16) -> Option<&'a str> {
+		let dll_name = dll_name.as_ref();
+		self.tables.iter().rev()
+			.filter(|table| table.is_dll(dll_name))
+			.find_map(|table| table.lookup(ordinal))
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Built in ordinal tables.
+#[cfg(feature = "ordinal_names")]
+pub static BUILTIN: [&OrdinalTable<'static>; 3] = [&WS2_32, &WSOCK32, &OLEAUT32];
+
+/// Ordinals of `ws2_32.dll`.
+#[cfg(feature = "ordinal_names")]
+#[rustfmt::skip]
+pub static WS2_32: OrdinalTable<'static> = OrdinalTable {
+	dll_name: "ws2_32.dll",
+	names: &[
+		(1, "accept"), (2, "bind"), (3, "closesocket"), (4, "connect"), (5, "getpeername"), (6, "getsockname"),
+		(7, "getsockopt"), (8, "htonl"), (9, "htons"), (10, "ioctlsocket"), (11, "inet_addr"), (12, "inet_ntoa"),
+		(13, "listen"), (14, "ntohl"), (15, "ntohs"), (16, "recv"), (17, "recvfrom"), (18, "select"), (19, "send"),
+		(20, "sendto"), (21, "setsockopt"), (22, "shutdown"), (23, "socket"),
+		(51, "gethostbyaddr"), (52, "gethostbyname"), (53, "getprotobyname"), (54, "getprotobynumber"),
+		(55, "getservbyname"), (56, "getservbyport"), (57, "gethostname"),
+		(101, "WSAAsyncSelect"), (111, "WSAGetLastError"), (112, "WSASetLastError"),
+		(115, "WSAStartup"), (116, "WSACleanup"), (151, "__WSAFDIsSet"),
+	],
+};
+
+/// Ordinals of `wsock32.dll`.
+#[cfg(feature = "ordinal_names")]
+#[rustfmt::skip]
+pub static WSOCK32: OrdinalTable<'static> = OrdinalTable {
+	dll_name: "wsock32.dll",
+	names: &[
+		(1, "accept"), (2, "bind"), (3, "closesocket"), (4, "connect"), (5, "getpeername"), (6, "getsockname"),
+		(7, "getsockopt"), (8, "htonl"), (9, "htons"), (10, "ioctlsocket"), (11, "inet_addr"), (12, "inet_ntoa"),
+		(13, "listen"), (14, "ntohl"), (15, "ntohs"), (16, "recv"), (17, "recvfrom"), (18, "select"), (19, "send"),
+		(20, "sendto"), (21, "setsockopt"), (22, "shutdown"), (23, "socket"),
+		(51, "gethostbyaddr"), (52, "gethostbyname"), (53, "getprotobyname"), (54, "getprotobynumber"),
+		(55, "getservbyname"), (56, "getservbyport"), (57, "gethostname"),
+		(101, "WSAAsyncSelect"), (111, "WSAGetLastError"), (112, "WSASetLastError"),
+		(115, "WSAStartup"), (116, "WSACleanup"), (151, "__WSAFDIsSet"),
+	],
+};
+
+/// Ordinals of `oleaut32.dll`.
+#[cfg(feature = "ordinal_names")]
+#[rustfmt::skip]
+pub static OLEAUT32: OrdinalTable<'static> = OrdinalTable {
+	dll_name: "oleaut32.dll",
+	names: &[
+		(2, "SysAllocString"), (3, "SysReAllocString"), (4, "SysAllocStringLen"), (5, "SysReAllocStringLen"),
+		(6, "SysFreeString"), (7, "SysStringLen"), (8, "VariantInit"), (9, "VariantClear"), (10, "VariantCopy"),
+		(11, "VariantCopyInd"), (12, "VariantChangeType"),
+		(15, "SafeArrayCreate"), (16, "SafeArrayDestroy"), (17, "SafeArrayGetDim"), (18, "SafeArrayGetElemsize"),
+		(19, "SafeArrayGetUBound"), (20, "SafeArrayGetLBound"), (21, "SafeArrayLock"), (22, "SafeArrayUnlock"),
+		(23, "SafeArrayAccessData"), (24, "SafeArrayUnaccessData"), (25, "SafeArrayGetElement"),
+		(26, "SafeArrayPutElement"), (27, "SafeArrayCopy"),
+		(147, "VariantChangeTypeEx"), (149, "SysStringByteLen"), (150, "SysAllocStringByteLen"),
+		(161, "LoadTypeLib"), (162, "LoadRegTypeLib"), (163, "RegisterTypeLib"), (183, "LoadTypeLibEx"),
+	],
+};
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	static MYLIB: OrdinalTable<'static> = OrdinalTable {
+		dll_name: "MyLib",
+		names: &[(1, "MyInit"), (2, "MyExit")],
+	};
+	static MYLIB_V2: OrdinalTable<'static> = OrdinalTable {
+		dll_name: "mylib.dll",
+		names: &[(2, "MyExit2")],
+	};
+
+	#[test]
+	fn lookup() {
+		let mut ordinal_names = OrdinalNames::new();
+		assert_eq!(ordinal_names.lookup("mylib.dll", 1), None);
+		ordinal_names.add(&MYLIB).add(&MYLIB_V2);
+		assert_eq!(ordinal_names.lookup("MYLIB.DLL", 1), Some("MyInit"));
+		assert_eq!(ordinal_names.lookup("mylib", 2), Some("MyExit2"));
+		assert_eq!(ordinal_names.lookup("mylib.dll", 3), None);
+		assert_eq!(ordinal_names.lookup("mylib.exe", 1), None);
+		assert_eq!(ordinal_names.lookup(".dll", 1), None);
+	}
+
+	#[cfg(feature = "ordinal_names")]
+	#[test]
+	fn builtin() {
+		let ordinal_names = OrdinalNames::builtin();
+		assert_eq!(ordinal_names.lookup("WS2_32.dll", 115), Some("WSAStartup"));
+		assert_eq!(ordinal_names.lookup("oleaut32.dll", 6), Some("SysFreeString"));
+		for table in ordinal_names.tables() {
+			assert!(table.names.windows(2).all(|w| w[0].0 < w[1].0), "{} is not sorted", table.dll_name);
+		}
+	}
+}
@@ -32,6 +32,7 @@ Import the [`Pe`](trait.Pe.html) trait to continue from here.
 use std::path::Path;
 use pelite::{FileMap, Result};
 use pelite::pe32::{Pe, PeFile};
+use pelite::util::Le;
 
 fn file_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 	let path = path.as_ref();
@@ -39,7 +40,7 @@ fn file_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 		let file = PeFile::from_bytes(&map)?;
 
 		// Access the file contents through the Pe trait
-		let image_base = file.optional_header().ImageBase;
+		let image_base = file.optional_header().ImageBase.le();
 		println!("The preferred load address of {:?} is {}.", path, image_base);
 
 		// See the respective modules to access other parts of the PE file.
@@ -65,6 +66,7 @@ If you don't know which to choose, go with [`PeFile`](struct.PeFile.html).
 use std::path::Path;
 use pelite::{ImageMap, Result};
 use pelite::pe32::{Pe, PeView};
+use pelite::util::Le;
 
 fn image_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 	let path = path.as_ref();
@@ -72,7 +74,7 @@ fn image_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 		let view = PeView::from_bytes(&image)?;
 
 		// Access the image contents through the Pe trait
-		let image_size = view.optional_header().SizeOfImage;
+		let image_size = view.optional_header().SizeOfImage.le();
 		println!("The size of image in memory of {:?} is {}", path, image_size);
 
 		// See the respective modules to access other parts of the PE image.
@@ -101,12 +103,13 @@ This is mostly safe, but be even more cautious when using it to read from writab
 use std::path::Path;
 use pelite::Result;
 use pelite::pe::{Pe, PeView};
+use pelite::util::Le;
 
 fn image_base() {
 	let view = unsafe { PeView::new() };
 
 	// Access the image contents through the Pe trait
-	let image_size = view.optional_header().SizeOfImage;
+	let image_size = view.optional_header().SizeOfImage.le();
 	println!("The size of our image is {}", image_size);
 
 	// See the respective modules to access other parts of the PE image.
@@ -122,10 +125,22 @@ pub mod image;
 
 // I love Rust <3
 
+#[path = "../pe64/addr.rs"]
+pub mod addr;
+#[path = "../pe64/analysis/mod.rs"]
+pub mod analysis;
 #[path = "../pe64/base_relocs.rs"]
 pub(crate) mod base_relocs;
+#[path = "../pe64/bound_imports.rs"]
+pub mod bound_imports;
+#[path = "../pe64/clr.rs"]
+pub mod clr;
+#[path = "../pe64/buf.rs"]
+mod buf;
 #[path = "../pe64/debug.rs"]
 pub mod debug;
+#[path = "../pe64/delay_imports.rs"]
+pub mod delay_imports;
 #[path = "../pe64/exception.rs"]
 pub mod exception;
 #[path = "../pe64/exports.rs"]
@@ -136,12 +151,19 @@ mod file;
 pub mod headers;
 #[path = "../pe64/imports.rs"]
 pub mod imports;
+#[path = "../pe64/index.rs"]
+pub mod index;
 #[path = "../pe64/load_config.rs"]
 pub mod load_config;
 #[path = "../pe64/pe.rs"]
 mod pe;
+#[path = "../pe64/pe_headers.rs"]
+mod pe_headers;
 #[path = "../pe64/ptr.rs"]
 mod ptr;
+#[cfg(feature = "std")]
+#[path = "../pe64/reader.rs"]
+pub mod reader;
 #[path = "../pe64/resources.rs"]
 pub mod resources;
 #[path = "../pe64/rich_structure.rs"]
@@ -150,17 +172,27 @@ pub(crate) mod rich_structure;
 pub mod scanner;
 #[path = "../pe64/security.rs"]
 pub(crate) mod security;
+#[path = "../pe64/stubs.rs"]
+pub mod stubs;
+#[path = "../pe64/symbols.rs"]
+pub mod symbols;
 #[path = "../pe64/tls.rs"]
 pub mod tls;
+#[path = "../pe64/validate.rs"]
+pub mod validate;
 #[path = "../pe64/view.rs"]
 mod view;
 
 pub mod msvc;
 
+pub use self::buf::{PeFileBuf, PeViewBuf};
 pub use self::file::PeFile;
+pub use self::pe_headers::PeHeaders;
 pub use self::image::{Rva, Va};
 pub use self::pe::{Align, Pe, PeObject};
 pub use self::ptr::Ptr;
+#[cfg(feature = "std")]
+pub use self::reader::{PeReader, ReadError};
 pub use self::view::PeView;
 
 #[cfg(feature = "unstable")]
@@ -0,0 +1,286 @@
+/*!
+Checked address arithmetic.
+
+The [`Rva`](../type.Rva.html) and [`Va`](../type.Va.html) type aliases are bare integers, address math on them silently wraps around on corrupt input.
+The newtypes in this module only allow checked arithmetic and explicit conversions.
+
+Convert from and to the raw integers with `From` or the `new` and `get` methods.
+
+# Examples
+
+```
+use pelite::pe64::addr::{Rva, Va};
+
+let image_base = Va::new(0x1_4000_0000);
+let rva = Rva::new(0x1000);
+
+// Offsetting and aligning returns None on overflow
+assert_eq!(rva.checked_add(0x234), Some(Rva::new(0x1234)));
+assert_eq!(Rva::new(0xffff_f000).checked_add(0x1000), None);
+assert_eq!(Rva::new(0x1234).align_up(0x1000), Some(Rva::new(0x2000)));
+
+// Converting between rvas and vas requires the image base
+let va = rva.to_va(image_base).unwrap();
+assert_eq!(va, Va::new(0x1_4000_1000));
+assert_eq!(va.to_rva(image_base), Some(rva));
+
+// Use the raw integers with the rest of the library
+let raw: pelite::pe64::Rva = rva.into();
+assert_eq!(raw, 0x1000);
+```
+*/
+
+use std::fmt;
+
+use crate::Pod;
+
+use super::image;
+
+//----------------------------------------------------------------
+
+/// Checked relative virtual address.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct Rva(image::Rva);
+
+impl Rva {
+	/// Null rva constant.
+	pub const NULL: Rva = Rva(0);
+	/// Invalid rva constant, see [`BADRVA`](../image/constant.BADRVA.html).
+	pub const BAD: Rva = Rva(image::BADRVA);
+	/// Wraps the raw rva.
+	#[inline]
+	pub const fn new(rva: image::Rva) -> Rva {
+		Rva(rva)
+	}
+	/// Returns the raw rva.
+	#[inline]
+	pub const fn get(self) -> image::Rva {
+		self.0
+	}
+	/// Returns true if the rva is null.
+	#[inline]
+	pub const fn is_null(self) -> bool {
+		self.0 == 0
+	}
+	/// Adds a byte offset, returns `None` on overflow.
+	#[inline]
+	pub fn checked_add(self, offset: u32) -> Option<Rva> {
+		self.0.checked_add(offset).map(Rva)
+	}
+	/// Subtracts a byte offset, returns `None` on underflow.
+	#[inline]
+	pub fn checked_sub(self, offset: u32) -> Option<Rva> {
+		self.0.checked_sub(offset).map(Rva)
+	}
+	/// Returns the byte offset from the base to this rva, `None` if the base is above this rva.
+	#[inline]
+	pub fn offset_from(self, base: Rva) -> Option<u32> {
+		self.0.checked_sub(base.0)
+	}
+	/// Rounds up to a multiple of the alignment, returns `None` on overflow or if the alignment is not a power of two.
+	#[inline]
+	pub fn align_up(self, align: u32) -> Option<Rva> {
+		let mask = align.wrapping_sub(1);
+		if align.is_power_of_two() { self.0.checked_add(mask).map(|rva| Rva(rva & !mask)) } else { None }
+	}
+	/// Rounds down to a multiple of the alignment, returns `None` if the alignment is not a power of two.
+	#[inline]
+	pub fn align_down(self, align: u32) -> Option<Rva> {
+		if align.is_power_of_two() { Some(Rva(self.0 & !(align - 1))) } else { None }
+	}
+	/// Returns true if the rva is a multiple of the alignment.
+	///
+	/// Alignments which are not a power of two are never satisfied.
+	#[inline]
+	pub fn is_aligned(self, align: u32) -> bool {
+		align.is_power_of_two() && self.0 & (align - 1) == 0
+	}
+	/// Converts to virtual address given the image base, returns `None` on overflow.
+	#[inline]
+	pub fn to_va(self, image_base: Va) -> Option<Va> {
+		image_base.0.checked_add(self.0 as image::Va).map(Va)
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Checked virtual address.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct Va(image::Va);
+
+impl Va {
+	/// Null va constant.
+	pub const NULL: Va = Va(0);
+	/// Invalid va constant, see [`BADVA`](../image/constant.BADVA.html).
+	pub const BAD: Va = Va(image::BADVA);
+	/// Wraps the raw va.
+	#[inline]
+	pub const fn new(va: image::Va) -> Va {
+		Va(va)
+	}
+	/// Returns the raw va.
+	#[inline]
+	pub const fn get(self) -> image::Va {
+		self.0
+	}
+	/// Returns true if the va is null.
+	#[inline]
+	pub const fn is_null(self) -> bool {
+		self.0 == 0
+	}
+	/// Adds a byte offset, returns `None` on overflow.
+	#[inline]
+	pub fn checked_add(self, offset: image::Va) -> Option<Va> {
+		self.0.checked_add(offset).map(Va)
+	}
+	/// Subtracts a byte offset, returns `None` on underflow.
+	#[inline]
+	pub fn checked_sub(self, offset: image::Va) -> Option<Va> {
+		self.0.checked_sub(offset).map(Va)
+	}
+	/// Returns the byte offset from the base to this va, `None` if the base is above this va.
+	#[inline]
+	pub fn offset_from(self, base: Va) -> Option<image::Va> {
+		self.0.checked_sub(base.0)
+	}
+	/// Rounds up to a multiple of the alignment, returns `None` on overflow or if the alignment is not a power of two.
+	#[inline]
+	pub fn align_up(self, align: image::Va) -> Option<Va> {
+		let mask = align.wrapping_sub(1);
+		if align.is_power_of_two() { self.0.checked_add(mask).map(|va| Va(va & !mask)) } else { None }
+	}
+	/// Rounds down to a multiple of the alignment, returns `None` if the alignment is not a power of two.
+	#[inline]
+	pub fn align_down(self, align: image::Va) -> Option<Va> {
+		if align.is_power_of_two() { Some(Va(self.0 & !(align - 1))) } else { None }
+	}
+	/// Returns true if the va is a multiple of the alignment.
+	///
+	/// Alignments which are not a power of two are never satisfied.
+	#[inline]
+	pub fn is_aligned(self, align: image::Va) -> bool {
+		align.is_power_of_two() && self.0 & (align - 1) == 0
+	}
+	/// Converts to relative virtual address given the image base.
+	///
+	/// Returns `None` if the va is below the image base or too far above it to fit an rva.
+	#[inline]
+	pub fn to_rva(self, image_base: Va) -> Option<Rva> {
+		let offset = self.0.checked_sub(image_base.0)?;
+		if offset <= image::Rva::MAX as image::Va { Some(Rva(offset as image::Rva)) } else { None }
+	}
+}
+
+//----------------------------------------------------------------
+
+unsafe impl Pod for Rva {}
+unsafe impl Pod for Va {}
+
+impl From<image::Rva> for Rva {
+	#[inline]
+	fn from(rva: image::Rva) -> Rva {
+		Rva(rva)
+	}
+}
+impl From<Rva> for image::Rva {
+	#[inline]
+	fn from(rva: Rva) -> image::Rva {
+		rva.0
+	}
+}
+impl From<image::Va> for Va {
+	#[inline]
+	fn from(va: image::Va) -> Va {
+		Va(va)
+	}
+}
+impl From<Va> for image::Va {
+	#[inline]
+	fn from(va: Va) -> image::Va {
+		va.0
+	}
+}
+impl<T: ?Sized> From<super::Ptr<T>> for Va {
+	#[inline]
+	fn from(ptr: super::Ptr<T>) -> Va {
+		Va(ptr.into_raw())
+	}
+}
+impl<T: ?Sized> From<Va> for super::Ptr<T> {
+	#[inline]
+	fn from(va: Va) -> super::Ptr<T> {
+		super::Ptr::from(va.0)
+	}
+}
+
+impl fmt::Debug for Rva {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Rva({:#x})", self.0)
+	}
+}
+impl fmt::Display for Rva {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:#x}", self.0)
+	}
+}
+impl fmt::LowerHex for Rva {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::LowerHex::fmt(&self.0, f)
+	}
+}
+impl fmt::UpperHex for Rva {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::UpperHex::fmt(&self.0, f)
+	}
+}
+impl fmt::Debug for Va {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Va({:#x})", self.0)
+	}
+}
+impl fmt::Display for Va {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:#x}", self.0)
+	}
+}
+impl fmt::LowerHex for Va {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::LowerHex::fmt(&self.0, f)
+	}
+}
+impl fmt::UpperHex for Va {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::UpperHex::fmt(&self.0, f)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn checked() {
+		assert_eq!(Rva::new(0x1000).checked_sub(0x1001), None);
+		assert_eq!(Rva::new(0x1000).offset_from(Rva::new(0x800)), Some(0x800));
+		assert_eq!(Rva::new(0x1000).offset_from(Rva::new(0x1800)), None);
+		assert_eq!(Rva::new(0xffff_f001).align_up(0x1000), None);
+		assert_eq!(Rva::new(0x1234).align_up(3), None);
+		assert_eq!(Rva::new(0x1234).align_down(0x1000), Some(Rva::new(0x1000)));
+		assert!(Rva::new(0x2000).is_aligned(0x1000));
+		assert!(!Rva::new(0x2000).is_aligned(0));
+
+		assert_eq!(Va::BAD.checked_add(1), None);
+		assert_eq!(Va::BAD.align_up(0x10), None);
+		assert_eq!(Va::new(0x1000).to_rva(Va::new(0x2000)), None);
+		assert_eq!(Rva::new(0x10).to_va(Va::new(0x1000)).and_then(|va| va.to_rva(Va::new(0x1000))), Some(Rva::new(0x10)));
+		assert_eq!(format!("{:?} {}", Rva::new(0x1000), Va::new(0x2000)), "Rva(0x1000) 0x2000");
+	}
+}
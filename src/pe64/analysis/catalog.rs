@@ -0,0 +1,132 @@
+/*!
+Catalog signing.
+
+Many operating system components carry no signature of their own, they are signed through a catalog file instead.
+A catalog (`.cat`) is a signed list of hashes installed in the system's catalog store, Windows looks up the hash of an image there when it has no embedded signature.
+Catalogs refer to PE files by their Authenticode hash and to other files by their flat file hash, older catalogs use SHA-1 and newer catalogs SHA-256.
+
+Reporting such images as unsigned is a common false positive.
+The image is checked for hints it may be catalog signed, only the catalog store of the system can tell for sure.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::catalog::{catalog_hashes, catalog_signing, Signing};
+use pelite::util::Fnv1a;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let signing = catalog_signing(file);
+	if signing.status == Signing::MaybeCatalogSigned {
+		// Look up the hashes in the catalog store, use SHA-1 or SHA-256 instead
+		let hashes = catalog_hashes::<_, Fnv1a>(file)?;
+		println!("may be catalog signed: {:?}, authentihash {:x}", signing.evidence, hashes.authenticode);
+	}
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+use crate::util::{Digest, Le};
+use crate::{Error, Result};
+
+use super::super::image::*;
+use super::super::{Align, Pe};
+use super::hashes::authenticode_hash;
+
+//----------------------------------------------------------------
+
+/// Hashes looked up in the catalog files.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CatalogHashes<T> {
+	/// Hash of the whole file.
+	pub flat: T,
+	/// The Authenticode hash, PE files are listed by this hash.
+	pub authenticode: T,
+}
+
+/// Computes the hashes a catalog file lists the image by.
+///
+/// Returns [`Err(Unmapped)`](../../../enum.Error.html#variant.Unmapped) if the image is not file aligned.
+pub fn catalog_hashes<'a, P: Pe<'a>, D: Digest + Default>(pe: P) -> Result<CatalogHashes<D::Output>> {
+	if pe.align() != Align::File {
+		return Err(Error::Unmapped);
+	}
+	let mut flat = D::default();
+	flat.update(pe.image());
+	Ok(CatalogHashes {
+		flat: flat.finish(),
+		authenticode: authenticode_hash::<P, D>(pe)?,
+	})
+}
+
+//----------------------------------------------------------------
+
+/// Signing status.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Signing {
+	/// The image has an embedded signature.
+	Embedded,
+	/// The image has no embedded signature but looks like it may be signed by a catalog file.
+	MaybeCatalogSigned,
+	/// The image has no embedded signature and nothing hints at a catalog signature.
+	Unsigned,
+}
+
+/// Hint the image may be catalog signed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Evidence {
+	/// The image requires a signature to load with `IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY`.
+	ForceIntegrity,
+	/// The timestamp is a reproducible build hash as used by operating system components.
+	ReproBuild,
+	/// The version info names Microsoft as the company.
+	MicrosoftVersionInfo,
+}
+
+/// Catalog signing report.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct CatalogSigning {
+	/// The signing status.
+	pub status: Signing,
+	/// Hints the image may be catalog signed, also collected for images with an embedded signature.
+	pub evidence: Vec<Evidence>,
+}
+
+/// Checks whether the image may be catalog signed.
+///
+/// The certificate table data directory entry is trusted to tell whether the image has an embedded signature, this also works for mapped images.
+pub fn catalog_signing<'a, P: Pe<'a>>(pe: P) -> CatalogSigning {
+	let mut evidence = Vec::new();
+	if pe.optional_header().DllCharacteristics.le() & IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY != 0 {
+		evidence.push(Evidence::ForceIntegrity);
+	}
+	if pe.headers().time_date_stamp().is_repro() {
+		evidence.push(Evidence::ReproBuild);
+	}
+	#[cfg(any(feature = "std", feature = "resources_nostd"))]
+	{
+		let company_name = pe.resources().ok().and_then(|resources| resources.version_info().ok()).and_then(|version_info| version_info.company_name());
+		if company_name.map_or(false, |company_name| company_name.starts_with("Microsoft")) {
+			evidence.push(Evidence::MicrosoftVersionInfo);
+		}
+	}
+
+	let embedded = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY)
+		.map_or(false, |datadir| datadir.VirtualAddress.le() != 0 && datadir.Size.le() != 0);
+	let status = match (embedded, evidence.is_empty()) {
+		(true, _) => Signing::Embedded,
+		(false, false) => Signing::MaybeCatalogSigned,
+		(false, true) => Signing::Unsigned,
+	};
+	CatalogSigning { status, evidence }
+}
@@ -0,0 +1,100 @@
+/*!
+Code cave finder.
+
+Code caves are unused regions of an image where code can be injected without moving anything around.
+
+Two kinds of caves are reported:
+
+* Runs of padding bytes, `0x00` or `0xCC`, inside the initialized data of executable sections.
+  Compilers pad between functions with these bytes.
+* Slack space between the end of a section and the start of the next section.
+  This space is reserved in the address space but is not backed by the file, the section headers must be extended to use it.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::caves::caves;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	for cave in caves(file, 64) {
+		let section = &file.section_headers().image()[cave.section];
+		println!("{:?} cave at {:#x} of {} bytes in {:?}", cave.kind, cave.rva, cave.size, section.Name);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::{Pe, Rva};
+
+/// Kind of code cave.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum CaveKind {
+	/// Run of the given padding byte in an executable section.
+	Padding(u8),
+	/// Address space between the end of the section and the next section.
+	Slack,
+}
+
+/// Code cave.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Cave {
+	/// Start of the cave.
+	pub rva: Rva,
+	/// Size of the cave in bytes.
+	pub size: u32,
+	/// Index of the section containing the cave in the section headers.
+	pub section: usize,
+	/// Kind of the cave.
+	pub kind: CaveKind,
+}
+
+/// Finds the code caves of at least the given size.
+///
+/// The caves are returned in order of the section headers, the padding runs of a section before its slack space.
+pub fn caves<'a, P: Pe<'a>>(pe: P, min_size: u32) -> Vec<Cave> {
+	let mut caves = Vec::new();
+	let min_size = cmp::max(min_size, 1);
+	let size_of_image = pe.optional_header().SizeOfImage.le();
+	let section_headers = pe.section_headers().image();
+
+	for (index, section) in section_headers.iter().enumerate() {
+		let section = section.le();
+
+		// Padding runs in the initialized data of executable sections
+		if section.Characteristics & IMAGE_SCN_MEM_EXECUTE != 0 {
+			if let Ok(bytes) = pe.get_section_bytes(&section) {
+				let virtual_size = if section.VirtualSize != 0 { section.VirtualSize } else { section.SizeOfRawData };
+				let len = cmp::min(bytes.len(), cmp::min(virtual_size, section.SizeOfRawData) as usize);
+				let mut i = 0;
+				while i < len {
+					let byte = bytes[i];
+					let run = bytes[i..len].iter().take_while(|&&b| b == byte).count();
+					if (byte == 0x00 || byte == 0xCC) && run as u32 >= min_size {
+						caves.push(Cave { rva: section.VirtualAddress.wrapping_add(i as u32), size: run as u32, section: index, kind: CaveKind::Padding(byte) });
+					}
+					i += run;
+				}
+			}
+		}
+
+		// Slack space up to the next section or the end of the image
+		let end_of_data = section.VirtualAddress.saturating_add(cmp::max(section.VirtualSize, section.SizeOfRawData));
+		let next = section_headers.get(index + 1).map_or(size_of_image, |next| next.VirtualAddress.le());
+		if next > end_of_data && next - end_of_data >= min_size {
+			caves.push(Cave { rva: end_of_data, size: next - end_of_data, section: index, kind: CaveKind::Slack });
+		}
+	}
+
+	caves
+}
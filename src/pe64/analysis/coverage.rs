@@ -0,0 +1,125 @@
+/*!
+Coverage attribution.
+
+Attributes the addresses hit by a trace, eg. the basic blocks recorded by DynamoRIO or decoded from Intel PT, to the sections and functions of the image.
+The functions come from the exception directory, chained function records count towards the function owning them.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile, Rva};
+use pelite::pe64::analysis::coverage::Coverage;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, hits: &[Rva]) {
+	let coverage = Coverage::from_rvas(file, hits);
+	for function in &coverage.functions {
+		println!("{:#x}..{:#x}: {} hits at {} addresses", function.begin, function.end, function.hits, function.unique);
+	}
+	println!("{} hits outside of any function", coverage.unattributed);
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::collections::BTreeMap;
+
+use crate::util::Le;
+
+use super::super::{Pe, Rva};
+
+/// Coverage of a function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct FunctionCoverage {
+	/// Start of the function.
+	pub begin: Rva,
+	/// End of the function.
+	pub end: Rva,
+	/// Number of hits in the function.
+	pub hits: u32,
+	/// Number of distinct addresses hit in the function.
+	pub unique: u32,
+}
+
+/// Coverage of the image.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Coverage {
+	/// Number of hits per section, in order of the section headers.
+	pub sections: Vec<u32>,
+	/// The functions with at least one hit sorted by their start.
+	pub functions: Vec<FunctionCoverage>,
+	/// Number of hits in the image which aren't in any function.
+	///
+	/// Leaf functions have no function record, their hits are counted here.
+	pub unattributed: u32,
+	/// Number of hits outside of the image.
+	pub outside: u32,
+}
+
+impl Coverage {
+	/// Attributes the hit rvas.
+	///
+	/// The rvas don't need to be sorted and may repeat, every occurrence counts as a hit.
+	pub fn from_rvas<'a, P: Pe<'a>>(pe: P, hits: &[Rva]) -> Coverage {
+		let mut hits = hits.to_vec();
+		hits.sort_unstable();
+		let mut coverage = Coverage {
+			sections: vec![0; pe.section_headers().image().len()],
+			..Coverage::default()
+		};
+		let size_of_image = pe.optional_header().SizeOfImage.le();
+		let exception = pe.exception().ok();
+
+		let mut functions = BTreeMap::new();
+		let mut prev = None;
+		for &rva in &hits {
+			if rva >= size_of_image {
+				coverage.outside += 1;
+				continue;
+			}
+			if let Some(index) = pe.section_headers().iter().position(|section| section.virtual_range().contains(&rva)) {
+				coverage.sections[index] += 1;
+			}
+			let function = match exception.and_then(|exception| exception.lookup(rva).ok()) {
+				Some(function) => function.image().le(),
+				None => {
+					coverage.unattributed += 1;
+					continue;
+				},
+			};
+			let unique = prev != Some(rva);
+			prev = Some(rva);
+			let it = functions.entry(function.BeginAddress).or_insert(FunctionCoverage {
+				begin: function.BeginAddress,
+				end: function.EndAddress,
+				hits: 0,
+				unique: 0,
+			});
+			it.hits += 1;
+			it.unique += unique as u32;
+		}
+
+		coverage.functions = functions.into_values().collect();
+		coverage
+	}
+	/// Attributes the hit file offsets.
+	///
+	/// File offsets which aren't mapped in the image count as outside of the image.
+	pub fn from_file_offsets<'a, P: Pe<'a>>(pe: P, hits: &[usize]) -> Coverage {
+		let mut hits = hits.to_vec();
+		hits.sort_unstable();
+		let rvas = pe.file_offsets_to_rvas(&hits);
+		let mut coverage = Coverage::from_rvas(pe, &rvas.iter().filter_map(|rva| rva.ok()).collect::<Vec<_>>());
+		coverage.outside += rvas.iter().filter(|rva| rva.is_err()).count() as u32;
+		coverage
+	}
+	/// Returns the total number of hits.
+	pub fn hits(&self) -> u32 {
+		self.functions.iter().map(|function| function.hits).sum::<u32>() + self.unattributed + self.outside
+	}
+}
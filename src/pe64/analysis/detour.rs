@@ -0,0 +1,88 @@
+/*!
+Safe detour points.
+
+Detouring a function overwrites its first few bytes with a jump to the hook, the overwritten instructions are relocated to a trampoline.
+The detour must end on an instruction boundary or the original code continues in the middle of an instruction.
+
+The function boundaries and prolog length come from the exception directory's unwind info, the instruction boundaries from a minimal x86-64 length decoder.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile, Rva};
+use pelite::pe64::analysis::detour::detour_point;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, function: Rva) -> pelite::Result<()> {
+	// Room for a 5 byte relative jump
+	let detour = detour_point(file, function, 5)?;
+	println!("relocate {} bytes, continue at {:#x}", detour.len, detour.rva);
+	if !detour.in_prolog() {
+		println!("the detour extends past the prolog");
+	}
+	Ok(())
+}
+```
+*/
+
+use crate::disasm_lite::{decode, Mode};
+use crate::util::Le;
+use crate::{Error, Result};
+
+use super::super::{Pe, Rva};
+
+/// Suggested detour point.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct DetourPoint {
+	/// Start of the function.
+	pub function: Rva,
+	/// End of the function.
+	pub end: Rva,
+	/// Length of the function's prolog in bytes.
+	pub prolog_len: usize,
+	/// The instruction boundary where execution continues after the relocated instructions.
+	pub rva: Rva,
+	/// Length of the instructions overwritten by the detour.
+	pub len: usize,
+}
+impl DetourPoint {
+	/// Returns true if the overwritten instructions are all part of the prolog.
+	///
+	/// Prolog instructions set up the stack frame and rarely reference code or data, they are the safest to relocate.
+	/// Large stack allocations call `__chkstk` in the prolog which must be fixed up when relocated.
+	pub fn in_prolog(&self) -> bool {
+		self.len <= self.prolog_len
+	}
+}
+
+/// Suggests the first instruction boundary at or after `min_len` bytes into the function containing the address.
+///
+/// Returns [`Err(Null)`](../../../enum.Error.html#variant.Null) if no function contains the address,
+/// [`Err(Bounds)`](../../../enum.Error.html#variant.Bounds) if the function is shorter than `min_len` bytes
+/// and [`Err(Invalid)`](../../../enum.Error.html#variant.Invalid) if its instructions cannot be decoded.
+pub fn detour_point<'a, P: Pe<'a>>(pe: P, rva: Rva, min_len: usize) -> Result<DetourPoint> {
+	let function = pe.exception()?.lookup(rva)?;
+	let prolog_len = function.unwind_info()?.size_of_prolog();
+	let bytes = function.bytes()?;
+
+	let mut len = 0;
+	while len < min_len {
+		if len >= bytes.len() {
+			return Err(Error::Bounds);
+		}
+		len += decode(&bytes[len..], Mode::X64).ok_or(Error::Invalid)?.len;
+	}
+
+	let image = function.image().le();
+	Ok(DetourPoint {
+		function: image.BeginAddress,
+		end: image.EndAddress,
+		prolog_len,
+		rva: image.BeginAddress + len as u32,
+		len,
+	})
+}
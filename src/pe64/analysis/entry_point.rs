@@ -0,0 +1,121 @@
+/*!
+Entry point sanity.
+
+Compilers place the entry point somewhere in the middle of the code section.
+Packers and protectors often point it elsewhere: into the last section where their stub is appended, at the very start of a section, into a writable section or even into data.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::entry_point::{entry_point, Location};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let entry_point = entry_point(file);
+	if let Location::Section(section) = entry_point.location {
+		println!("entry point in {:?}", file.section_headers().image()[section].Name);
+	}
+	if entry_point.is_suspicious() {
+		println!("suspicious entry point: {:?}", entry_point);
+	}
+}
+```
+*/
+
+use std::cmp;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::Pe;
+
+/// Location of the entry point.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Location {
+	/// The image has no entry point, eg. a resource only DLL.
+	None,
+	/// The entry point is in the headers, tiny images have their code there.
+	Headers,
+	/// The entry point is in the section with the given index in the section headers.
+	Section(usize),
+	/// The entry point is outside of the headers and every section.
+	OutOfBounds,
+}
+
+/// Entry point classification.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct EntryPoint {
+	/// The `AddressOfEntryPoint`.
+	pub rva: Rva,
+	/// Location of the entry point.
+	pub location: Location,
+	/// The section is executable.
+	pub executable: bool,
+	/// The section is writable.
+	pub writable: bool,
+	/// The section is the last of multiple sections.
+	pub last_section: bool,
+	/// The entry point is the first byte of the section.
+	pub section_start: bool,
+	/// The entry point points into the import address table.
+	pub in_iat: bool,
+}
+impl EntryPoint {
+	/// Returns true if the entry point is not where a compiler would put it.
+	///
+	/// That is outside of the headers and sections, in a section which isn't executable or is writable, in the last section, at the start of a section or in the IAT.
+	pub fn is_suspicious(&self) -> bool {
+		match self.location {
+			Location::None | Location::Headers => self.in_iat,
+			Location::Section(_) => !self.executable || self.writable || self.last_section || self.section_start || self.in_iat,
+			Location::OutOfBounds => true,
+		}
+	}
+}
+
+/// Classifies the entry point.
+pub fn entry_point<'a, P: Pe<'a>>(pe: P) -> EntryPoint {
+	let optional_header = pe.optional_header();
+	let rva = optional_header.AddressOfEntryPoint.le();
+	let mut entry_point = EntryPoint {
+		rva,
+		location: Location::None,
+		executable: false,
+		writable: false,
+		last_section: false,
+		section_start: false,
+		in_iat: false,
+	};
+	if rva == 0 {
+		return entry_point;
+	}
+
+	let in_iat = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_IAT).map(|datadir| datadir.le());
+	entry_point.in_iat = in_iat.map_or(false, |datadir| datadir.VirtualAddress != 0 && rva.wrapping_sub(datadir.VirtualAddress) < datadir.Size);
+
+	let section_headers = pe.section_headers().image();
+	let section = section_headers.iter().position(|section| {
+		let section = section.le();
+		let size = cmp::max(section.VirtualSize, section.SizeOfRawData);
+		rva.wrapping_sub(section.VirtualAddress) < size
+	});
+	match section {
+		Some(index) => {
+			let section = section_headers[index].le();
+			entry_point.location = Location::Section(index);
+			entry_point.executable = section.Characteristics & IMAGE_SCN_MEM_EXECUTE != 0;
+			entry_point.writable = section.Characteristics & IMAGE_SCN_MEM_WRITE != 0;
+			entry_point.last_section = section_headers.len() > 1 && index == section_headers.len() - 1;
+			entry_point.section_start = rva == section.VirtualAddress;
+		},
+		None if rva < optional_header.SizeOfHeaders.le() => entry_point.location = Location::Headers,
+		None => entry_point.location = Location::OutOfBounds,
+	}
+	entry_point
+}
@@ -0,0 +1,154 @@
+/*!
+Memory footprint of the mapped image.
+
+The loader maps the headers and every section rounded up to the section alignment.
+The footprint totals these mapped bytes by page protection and reports the slack between the virtual size of a section and its aligned size.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::footprint::footprint;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let footprint = footprint(file);
+	println!("{} pages: {} RX, {} RW, {} RO bytes", footprint.pages(), footprint.read_execute, footprint.read_write, footprint.read_only);
+	for section in &footprint.sections {
+		println!("{:?}: {:?} with {} bytes of slack", file.section_headers().image()[section.section].Name, section.protection, section.slack());
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::Pe;
+
+/// Size of a page.
+pub const PAGE_SIZE: u32 = 0x1000;
+
+/// Page protection of a mapped section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Protection {
+	/// `PAGE_READONLY`.
+	ReadOnly,
+	/// `PAGE_WRITECOPY`, or `PAGE_READWRITE` for shared sections.
+	ReadWrite,
+	/// `PAGE_EXECUTE_READ`.
+	ReadExecute,
+	/// `PAGE_EXECUTE_READWRITE`.
+	ReadWriteExecute,
+}
+impl Protection {
+	/// Gets the protection of the section characteristics.
+	///
+	/// Sections without `IMAGE_SCN_MEM_READ` are mapped readable regardless.
+	pub fn from_characteristics(characteristics: u32) -> Protection {
+		match (characteristics & IMAGE_SCN_MEM_EXECUTE != 0, characteristics & IMAGE_SCN_MEM_WRITE != 0) {
+			(false, false) => Protection::ReadOnly,
+			(false, true) => Protection::ReadWrite,
+			(true, false) => Protection::ReadExecute,
+			(true, true) => Protection::ReadWriteExecute,
+		}
+	}
+}
+
+/// Memory footprint of a section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct SectionFootprint {
+	/// Index of the section in the section headers.
+	pub section: usize,
+	/// Virtual size of the section, the size of its raw data if the virtual size is zero.
+	pub virtual_size: u32,
+	/// Virtual size rounded up to the section alignment.
+	pub aligned_size: u64,
+	/// Page protection of the section.
+	pub protection: Protection,
+}
+impl SectionFootprint {
+	/// Returns the number of bytes between the virtual size and the aligned size.
+	pub fn slack(&self) -> u64 {
+		self.aligned_size - self.virtual_size as u64
+	}
+	/// Returns the number of pages mapped for the section.
+	pub fn pages(&self) -> u64 {
+		(self.aligned_size + (PAGE_SIZE - 1) as u64) / PAGE_SIZE as u64
+	}
+}
+
+/// Memory footprint of the mapped image.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Footprint {
+	/// Size of the headers rounded up to the section alignment.
+	pub headers: u64,
+	/// Footprint of every section in order of the section headers.
+	pub sections: Vec<SectionFootprint>,
+	/// Bytes mapped read-only, including the headers.
+	pub read_only: u64,
+	/// Bytes mapped read-write.
+	pub read_write: u64,
+	/// Bytes mapped read-execute.
+	pub read_execute: u64,
+	/// Bytes mapped read-write-execute.
+	pub read_write_execute: u64,
+	/// The `SizeOfImage` recorded in the optional header.
+	pub size_of_image: u32,
+}
+impl Footprint {
+	/// Returns the total number of mapped bytes.
+	///
+	/// Differs from the `SizeOfImage` if the sections leave gaps or the header is inconsistent.
+	pub fn virtual_size(&self) -> u64 {
+		self.read_only + self.read_write + self.read_execute + self.read_write_execute
+	}
+	/// Returns the total number of mapped pages.
+	pub fn pages(&self) -> u64 {
+		(self.virtual_size() + (PAGE_SIZE - 1) as u64) / PAGE_SIZE as u64
+	}
+	/// Returns the total slack of the sections.
+	pub fn slack(&self) -> u64 {
+		self.sections.iter().map(SectionFootprint::slack).sum()
+	}
+}
+
+/// Computes the memory footprint of the image from its headers.
+///
+/// Section alignments which aren't a power of two are replaced by the page size.
+pub fn footprint<'a, P: Pe<'a>>(pe: P) -> Footprint {
+	let optional_header = pe.optional_header();
+	let alignment = optional_header.SectionAlignment.le();
+	let alignment = if alignment.is_power_of_two() { alignment as u64 } else { PAGE_SIZE as u64 };
+	let align = |size: u32| (size as u64 + (alignment - 1)) & !(alignment - 1);
+
+	let headers = align(optional_header.SizeOfHeaders.le());
+	let mut footprint = Footprint {
+		headers,
+		read_only: headers,
+		size_of_image: optional_header.SizeOfImage.le(),
+		..Footprint::default()
+	};
+	for (index, section) in pe.section_headers().image().iter().enumerate() {
+		let section = section.le();
+		let virtual_size = if section.VirtualSize != 0 { section.VirtualSize } else { section.SizeOfRawData };
+		let protection = Protection::from_characteristics(section.Characteristics);
+		let aligned_size = align(virtual_size);
+		*match protection {
+			Protection::ReadOnly => &mut footprint.read_only,
+			Protection::ReadWrite => &mut footprint.read_write,
+			Protection::ReadExecute => &mut footprint.read_execute,
+			Protection::ReadWriteExecute => &mut footprint.read_write_execute,
+		} += aligned_size;
+		footprint.sections.push(SectionFootprint { section: index, virtual_size, aligned_size, protection });
+	}
+	footprint
+}
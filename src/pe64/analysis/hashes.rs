@@ -0,0 +1,258 @@
+/*!
+Section and image hashes.
+
+Hashes of the raw data of every section cluster similar images: a patched build changes some sections while others stay the same.
+The image hash is a lightweight variant of the Authenticode hash, it covers the headers and the sections but not the overlay.
+The Authenticode hash itself covers the whole file except for the signature, it is the hash signatures and catalog files refer to the image by.
+
+Function hashes diff two builds of the same module: the functions are taken from the exception directory and
+the operands which change whenever the code moves, base relocated addresses and rip relative memory operands referencing the IAT, are masked.
+
+The hash function is pluggable, implement [`Digest`](../../../util/trait.Digest.html) for the hash function of your choice.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::hashes::function_hashes;
+use pelite::util::Fnv1a;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	for section_hash in file.section_hashes::<Fnv1a>() {
+		let section = &file.section_headers().as_slice()[section_hash.section];
+		println!("{:?}: {:x?}", section.name(), section_hash.hash);
+	}
+	let image_hash = file.image_hash::<Fnv1a>()?;
+	for function_hash in function_hashes::<_, Fnv1a>(file)? {
+		println!("{:#x}: {:x?}", function_hash.rva, function_hash.hash);
+	}
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::ops::Range;
+use std::{cmp, mem};
+
+use crate::disasm_lite::{self, Mode, RelKind};
+use crate::util::{Digest, Le};
+use crate::{Error, Result};
+
+use super::super::image::*;
+use super::super::{Align, Pe, Rva};
+
+/// Hash of a section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SectionHash<T> {
+	/// Index of the section in the section headers.
+	pub section: usize,
+	/// Hash of the raw data of the section.
+	///
+	/// The section bytes are those returned by [`get_section_bytes`](../../trait.Pe.html#method.get_section_bytes),
+	/// a file hashes the raw data including its file alignment padding while a mapped image hashes the virtual size.
+	pub hash: Result<T>,
+}
+
+/// Hashes the raw data of every section.
+///
+/// Sections without raw data hash the empty input.
+pub fn section_hashes<'a, P: Pe<'a>, D: Digest + Default>(pe: P) -> Vec<SectionHash<D::Output>> {
+	pe.section_headers()
+		.iter()
+		.enumerate()
+		.map(|(section, section_header)| {
+			let bytes = match pe.get_section_bytes(section_header) {
+				Err(Error::Null) => Ok(&[][..]),
+				bytes => bytes,
+			};
+			let hash = bytes.map(|bytes| {
+				let mut digest = D::default();
+				digest.update(bytes);
+				digest.finish()
+			});
+			SectionHash { section, hash }
+		})
+		.collect()
+}
+
+/// Hashes the headers and the raw data of the sections, skipping the overlay.
+///
+/// Like the Authenticode hash the `CheckSum` field and the certificate table data directory entry are skipped and the sections are hashed in file order.
+/// Unlike the Authenticode hash the overlay is never hashed, appending data or a signature does not change the hash.
+///
+/// Returns [`Err(Unmapped)`](../../../enum.Error.html#variant.Unmapped) if the image is not file aligned.
+pub fn image_hash<'a, P: Pe<'a>, D: Digest + Default>(pe: P) -> Result<D::Output> {
+	if pe.align() != Align::File {
+		return Err(Error::Unmapped);
+	}
+	let image = pe.image();
+	let base = image.as_ptr() as usize;
+	let headers = image.get(..pe.optional_header().SizeOfHeaders.le() as usize).ok_or(Error::Bounds)?;
+	let mut digest = D::default();
+
+	// CheckSum is at the same offset in the 32 and 64 bit optional headers
+	let checksum = pe.optional_header() as *const _ as usize - base + 64;
+	let mut excluded = vec![(checksum, 4)];
+	if let Some(datadir) = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY) {
+		excluded.push((datadir as *const _ as usize - base, mem::size_of::<IMAGE_DATA_DIRECTORY>()));
+	}
+	let mut pos = 0;
+	for (start, len) in excluded {
+		if start >= headers.len() {
+			break;
+		}
+		digest.update(&headers[pos..start]);
+		pos = usize::min(start + len, headers.len());
+	}
+	digest.update(&headers[pos..]);
+
+	let mut sections: Vec<_> = pe.section_headers().iter().filter(|section| section.SizeOfRawData.le() != 0).collect();
+	sections.sort_by_key(|section| section.PointerToRawData.le());
+	for section in sections {
+		let start = section.PointerToRawData.le() as usize;
+		let end = start.checked_add(section.SizeOfRawData.le() as usize).ok_or(Error::Overflow)?;
+		digest.update(image.get(start..end).ok_or(Error::Bounds)?);
+	}
+	Ok(digest.finish())
+}
+
+/// Hashes the file like the Authenticode hash.
+///
+/// The whole file is hashed in order, skipping the `CheckSum` field, the certificate table data directory entry and the certificate table if the image is signed.
+/// Signing the image or replacing its signature does not change the hash, hash with the digest algorithm of the signature or catalog.
+///
+/// Returns [`Err(Unmapped)`](../../../enum.Error.html#variant.Unmapped) if the image is not file aligned or an error if the certificate table is corrupt.
+pub fn authenticode_hash<'a, P: Pe<'a>, D: Digest + Default>(pe: P) -> Result<D::Output> {
+	if pe.align() != Align::File {
+		return Err(Error::Unmapped);
+	}
+	let mut digest = D::default();
+	let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY);
+	if datadir.map_or(false, |datadir| datadir.VirtualAddress.le() != 0) {
+		pe.security()?.authenticode_digest(|bytes| digest.update(bytes));
+	}
+	else {
+		let image = pe.image();
+		let base = image.as_ptr() as usize;
+		// CheckSum is at the same offset in the 32 and 64 bit optional headers
+		let checksum = pe.optional_header() as *const _ as usize - base + 64;
+		digest.update(&image[..checksum]);
+		match datadir {
+			Some(datadir) => {
+				let datadir = datadir as *const _ as usize - base;
+				digest.update(&image[checksum + 4..datadir]);
+				digest.update(&image[datadir + mem::size_of::<IMAGE_DATA_DIRECTORY>()..]);
+			},
+			None => digest.update(&image[checksum + 4..]),
+		}
+	}
+	Ok(digest.finish())
+}
+
+/// Hash of a function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FunctionHash<T> {
+	/// Index of the function in the exception directory.
+	pub function: usize,
+	/// Start of the function.
+	pub rva: Rva,
+	/// Size of the function in bytes.
+	pub size: u32,
+	/// Hash of the function bytes with the relocated and IAT operands masked.
+	pub hash: Result<T>,
+}
+
+/// Hashes the bytes of every function in the exception directory.
+///
+/// Bytes patched by base relocations and the displacements of rip relative memory operands targeting the IAT are hashed as zero.
+/// The displacements are found by decoding the function instruction by instruction, the function's code after an instruction which cannot be decoded is hashed as is.
+/// Only the code of the function itself is hashed, functions calling each other at different addresses still hash differently.
+///
+/// Functions with the same hash in two builds are very likely unchanged, compare the hashes by their exported or symbol names.
+///
+/// Returns an error if the image has no exception directory.
+pub fn function_hashes<'a, P: Pe<'a>, D: Digest + Default>(pe: P) -> Result<Vec<FunctionHash<D::Output>>> {
+	let exception = pe.exception()?;
+	let base_relocs = pe.base_relocs().ok();
+	let iat = match pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_IAT).map(|datadir| datadir.le()) {
+		Some(datadir) => datadir.VirtualAddress..datadir.VirtualAddress.saturating_add(datadir.Size),
+		None => 0..0,
+	};
+	let mut buf = Vec::new();
+	let hashes = exception
+		.functions()
+		.enumerate()
+		.map(|(function, func)| {
+			let image = func.image().le();
+			let hash = func.bytes().map(|bytes| {
+				buf.clear();
+				buf.extend_from_slice(bytes);
+				if let Some(base_relocs) = &base_relocs {
+					for (rva, ty) in base_relocs.range(image.BeginAddress..image.EndAddress) {
+						let start = (rva - image.BeginAddress) as usize;
+						let end = cmp::min(start + ty.size_of(), buf.len());
+						buf[start..end].iter_mut().for_each(|byte| *byte = 0);
+					}
+				}
+				// Absolute references to the IAT are relocated, only 64-bit code references it rip relative
+				if mem::size_of::<Va>() == 8 {
+					mask_iat_refs(&mut buf, image.BeginAddress, &iat);
+				}
+				let mut digest = D::default();
+				digest.update(&buf);
+				digest.finish()
+			});
+			FunctionHash { function, rva: image.BeginAddress, size: image.EndAddress.wrapping_sub(image.BeginAddress), hash }
+		})
+		.collect();
+	Ok(hashes)
+}
+
+// Masks the displacement of rip relative memory operands targeting the IAT
+//
+// The code is walked instruction by instruction, masking stops at the first instruction which cannot be decoded
+fn mask_iat_refs(code: &mut [u8], rva: Rva, iat: &Range<Rva>) {
+	let iat = iat.start as u64..iat.end as u64;
+	let refs: Vec<_> = disasm_lite::iter(code, Mode::X64)
+		.filter_map(|(offset, insn)| {
+			let rel = insn.rel.filter(|rel| rel.kind == RelKind::Memory)?;
+			let target = insn.target(rva as u64 + offset as u64)?;
+			if iat.contains(&target) {
+				Some(offset + rel.offset..offset + rel.offset + rel.size)
+			}
+			else {
+				None
+			}
+		})
+		.collect();
+	for range in refs {
+		code[range].iter_mut().for_each(|byte| *byte = 0);
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn iat_refs() {
+		let mut code = [
+			0x48, 0xB8, 0xFF, 0x15, 0xF8, 0x0F, 0x00, 0x00, 0x00, 0x00, // mov rax, 0xff815ff
+			0xFF, 0x15, 0xF8, 0x0F, 0x00, 0x00, // call [rip+0xff8]
+			0x48, 0x8B, 0x05, 0xF9, 0x0F, 0x00, 0x00, // mov rax, [rip+0xff9]
+			0xFF, 0x25, 0xE3, 0x1F, 0x00, 0x00, // jmp [rip+0x1fe3]
+		];
+		mask_iat_refs(&mut code, 0x1000, &(0x2000..0x2100));
+		assert_eq!(code, [
+			0x48, 0xB8, 0xFF, 0x15, 0xF8, 0x0F, 0x00, 0x00, 0x00, 0x00,
+			0xFF, 0x15, 0x00, 0x00, 0x00, 0x00,
+			0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00,
+			0xFF, 0x25, 0xE3, 0x1F, 0x00, 0x00,
+		]);
+	}
+}
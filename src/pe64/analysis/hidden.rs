@@ -0,0 +1,105 @@
+/*!
+Hidden data between sections.
+
+The raw data of the sections is normally laid out back to back in the file, right after the headers.
+Any file space between the end of the section table and the raw data of the first section, or between the raw data of two sections, is never mapped by the loader.
+Droppers and steganographic tools hide their payload there where it doesn't disturb the image.
+
+Only the file regions containing non-zero bytes are reported, the bound import directory which the linker places after the section table is not considered hidden.
+The overlay after the last section is covered by the [overlay](../overlay/index.html) analysis instead.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::hidden::hidden_data;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	for hidden in hidden_data(file) {
+		println!("{} bytes hidden at file offset {:#x}", hidden.data.end - hidden.data.start, hidden.data.start);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+use std::ops::Range;
+
+use crate::util::Le;
+use crate::Align;
+
+use super::super::image::*;
+use super::super::Pe;
+
+/// Non-zero data found in the file space between the headers and sections.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct HiddenData {
+	/// Index of the section in the section headers the gap follows, `None` for the gap after the section table.
+	pub after: Option<usize>,
+	/// File range of the whole gap.
+	pub gap: Range<u32>,
+	/// File range from the first to the last non-zero byte in the gap.
+	pub data: Range<u32>,
+}
+impl HiddenData {
+	/// Returns the hidden bytes.
+	pub fn bytes<'a, P: Pe<'a>>(&self, pe: P) -> &'a [u8] {
+		&pe.image()[self.data.start as usize..self.data.end as usize]
+	}
+}
+
+/// Finds non-zero data in the file space not belonging to the headers or any section.
+///
+/// Returns nothing if the image is mapped in memory, the gaps are not available.
+pub fn hidden_data<'a, P: Pe<'a>>(pe: P) -> Vec<HiddenData> {
+	let mut hidden = Vec::new();
+	if pe.align() != Align::File {
+		return hidden;
+	}
+	let image = pe.image();
+	let section_headers = pe.section_headers().image();
+	let headers_end = (section_headers.as_ptr_range().end as usize - image.as_ptr() as usize) as u32;
+
+	// The bound import directory is located in the headers
+	let bound_imports = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT)
+		.map(|datadir| datadir.le())
+		.map(|datadir| datadir.VirtualAddress..datadir.VirtualAddress.saturating_add(datadir.Size))
+		.unwrap_or(0..0);
+
+	// Raw data of the sections in file order
+	let mut sections: Vec<_> = section_headers.iter().enumerate()
+		.map(|(index, section)| (index, section.le()))
+		.filter(|(_, section)| section.SizeOfRawData != 0)
+		.map(|(index, section)| (index, section.PointerToRawData, section.PointerToRawData.saturating_add(section.SizeOfRawData)))
+		.collect();
+	sections.sort_by_key(|&(_, start, _)| start);
+
+	let mut after = None;
+	let mut end = headers_end;
+	for (index, start, section_end) in sections {
+		if start > end {
+			let gap = end..start;
+			if let Some(data) = non_zero(image, gap.clone(), &bound_imports) {
+				hidden.push(HiddenData { after, gap, data });
+			}
+		}
+		if section_end >= end {
+			after = Some(index);
+			end = section_end;
+		}
+	}
+	hidden
+}
+
+// Finds the range from the first to the last non-zero byte, ignoring the excluded range
+fn non_zero(image: &[u8], gap: Range<u32>, exclude: &Range<u32>) -> Option<Range<u32>> {
+	let end = cmp::min(gap.end as usize, image.len()) as u32;
+	let is_data = |&offset: &u32| image[offset as usize] != 0 && !exclude.contains(&offset);
+	let first = (gap.start..end).find(is_data)?;
+	let last = (first..end).rev().find(is_data)?;
+	Some(first..last + 1)
+}
@@ -0,0 +1,253 @@
+/*!
+Import reconstruction for dumped images.
+
+Images dumped from a running process have their import address table filled in with the addresses of the imported functions, and packed images often have their import directory destroyed.
+Given the exports of the modules loaded in that process, the resolved pointers in the import address table are matched back to the imported symbols and a new import directory is generated.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeView, Va};
+use pelite::pe64::analysis::imprec::{ExportMap, Imports};
+
+# #[allow(dead_code)]
+fn example(dump: PeView<'_>, kernel32: PeView<'_>, kernel32_base: Va) -> pelite::Result<Vec<u8>> {
+	// Describe the modules loaded in the dumped process
+	let mut export_map = ExportMap::new();
+	export_map.add_module("KERNEL32.dll", kernel32_base, kernel32)?;
+
+	// Find the import address table and resolve its entries
+	let iat = export_map.locate_iat(dump).ok_or(pelite::Error::Null)?;
+	let imports = Imports::resolve(dump, iat, &export_map)?;
+
+	// Generate the import directory at the rva where it will be appended to the image
+	let bytes = imports.build(0x10000);
+	Ok(bytes)
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::collections::BTreeMap;
+use std::{cmp, mem};
+use std::ops::Range;
+
+use crate::util::{AlignTo, Le};
+use crate::{Error, Result};
+
+use super::super::image::*;
+use super::super::{Pe, Rva, Va};
+
+//----------------------------------------------------------------
+
+/// Imported symbol.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Symbol {
+	/// Imported by name.
+	Name(String),
+	/// Imported by ordinal.
+	Ordinal(Ordinal),
+}
+
+/// Exports of the modules loaded in the dumped process.
+///
+/// Maps the absolute addresses of the exported symbols to the module and symbol.
+#[derive(Clone, Debug, Default)]
+pub struct ExportMap {
+	modules: Vec<String>,
+	symbols: BTreeMap<Va, (usize, Symbol)>,
+}
+
+impl ExportMap {
+	/// Creates an empty export map.
+	pub fn new() -> ExportMap {
+		ExportMap::default()
+	}
+	/// Adds an exported symbol at its absolute address.
+	///
+	/// If multiple symbols are exported at the same address, the first one added is kept.
+	pub fn add(&mut self, module: &str, va: Va, symbol: Symbol) {
+		let index = match self.modules.iter().position(|name| name.eq_ignore_ascii_case(module)) {
+			Some(index) => index,
+			None => {
+				self.modules.push(module.to_string());
+				self.modules.len() - 1
+			},
+		};
+		self.symbols.entry(va).or_insert((index, symbol));
+	}
+	/// Adds the exports of a module loaded at the given base address.
+	///
+	/// Named exports are preferred over their ordinal, forwarded exports are skipped as their address is that of the forwarded to module.
+	pub fn add_module<'a, P: Pe<'a>>(&mut self, module: &str, base: Va, pe: P) -> Result<()> {
+		let by = pe.exports()?.by()?;
+		for (name, export) in by.iter_names() {
+			if let (Ok(name), Ok(export)) = (name, export) {
+				if let (Ok(name), Some(rva)) = (name.to_str(), export.symbol()) {
+					self.add(module, base.wrapping_add(rva as Va), Symbol::Name(name.to_string()));
+				}
+			}
+		}
+		for (index, export) in by.iter().enumerate() {
+			if let Some(rva) = export.ok().and_then(|export| export.symbol()).filter(|&rva| rva != 0) {
+				let ordinal = by.ordinal_base().wrapping_add(index as Ordinal);
+				self.add(module, base.wrapping_add(rva as Va), Symbol::Ordinal(ordinal));
+			}
+		}
+		Ok(())
+	}
+	/// Looks up the module name and symbol exported at the absolute address.
+	pub fn get(&self, va: Va) -> Option<(&str, &Symbol)> {
+		self.symbols.get(&va).map(|&(index, ref symbol)| (&*self.modules[index], symbol))
+	}
+	/// Locates the import address table in the dumped image.
+	///
+	/// Returns the largest pointer aligned range of the image where every entry is either null or resolves to an exported symbol.
+	pub fn locate_iat<'a, P: Pe<'a>>(&self, pe: P) -> Option<Range<Rva>> {
+		const SIZE_OF_VA: usize = mem::size_of::<Va>();
+		let mut best: Option<Range<Rva>> = None;
+		for section in pe.section_headers() {
+			let bytes = match pe.get_section_bytes(section) {
+				Ok(bytes) => bytes,
+				Err(_) => continue,
+			};
+			let len = cmp::min(bytes.len(), section.VirtualSize.le() as usize);
+			let mut run: Option<Range<Rva>> = None;
+			for (i, entry) in bytes[..len].chunks_exact(SIZE_OF_VA).enumerate() {
+				let rva = section.VirtualAddress.le().wrapping_add((i * SIZE_OF_VA) as Rva);
+				let mut va = [0u8; SIZE_OF_VA];
+				va.copy_from_slice(entry);
+				match Va::from_le_bytes(va) {
+					// Null separators between the modules continue the run
+					0 if run.is_some() => (),
+					va if self.get(va).is_some() => {
+						let start = run.as_ref().map_or(rva, |run| run.start);
+						run = Some(start..rva.wrapping_add(SIZE_OF_VA as Rva));
+					},
+					_ => best = longest(best, run.take()),
+				}
+			}
+			best = longest(best, run);
+		}
+		best
+	}
+}
+
+fn longest(a: Option<Range<Rva>>, b: Option<Range<Rva>>) -> Option<Range<Rva>> {
+	match (a, b) {
+		(Some(a), Some(b)) => Some(if b.end.wrapping_sub(b.start) > a.end.wrapping_sub(a.start) { b } else { a }),
+		(a, b) => a.or(b),
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Reconstructed import descriptor.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Descriptor {
+	/// Name of the imported module.
+	pub dll_name: String,
+	/// Rva of the first entry of the import address table for this module.
+	pub first_thunk: Rva,
+	/// The imported symbols in the order of the import address table entries.
+	pub symbols: Vec<Symbol>,
+}
+
+/// Reconstructed imports.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Imports {
+	/// The import descriptors.
+	pub descriptors: Vec<Descriptor>,
+	/// Rvas of the import address table entries which did not resolve to an exported symbol and their value.
+	pub unresolved: Vec<(Rva, Va)>,
+}
+
+impl Imports {
+	/// Resolves the entries of the import address table of the dumped image.
+	///
+	/// A new descriptor starts after every null entry and every time the module of the resolved symbol changes.
+	/// Unresolved entries also end the current descriptor and are recorded.
+	pub fn resolve<'a, P: Pe<'a>>(pe: P, iat: Range<Rva>, export_map: &ExportMap) -> Result<Imports> {
+		let entries = pe.derva_slice::<Va>(iat.start, (iat.end.saturating_sub(iat.start) as usize) / mem::size_of::<Va>())?;
+		let mut imports = Imports::default();
+		let mut current: Option<Descriptor> = None;
+		for (i, &va) in entries.iter().enumerate() {
+			let rva = iat.start.wrapping_add((i * mem::size_of::<Va>()) as Rva);
+			let resolved = if va != 0 { export_map.get(va) } else { None };
+			match (resolved, &mut current) {
+				(Some((dll_name, symbol)), Some(desc)) if desc.dll_name == dll_name => {
+					desc.symbols.push(symbol.clone());
+				},
+				(Some((dll_name, symbol)), _) => {
+					imports.descriptors.extend(current.take());
+					current = Some(Descriptor { dll_name: dll_name.to_string(), first_thunk: rva, symbols: vec![symbol.clone()] });
+				},
+				(None, _) => {
+					imports.descriptors.extend(current.take());
+					if va != 0 {
+						imports.unresolved.push((rva, va));
+					}
+				},
+			}
+		}
+		imports.descriptors.extend(current);
+		if imports.descriptors.is_empty() {
+			return Err(Error::Null);
+		}
+		Ok(imports)
+	}
+
+	/// Builds the import directory to be placed at the given rva.
+	///
+	/// The import descriptors refer to the existing import address table entries in the image.
+	/// The generated directory contains the descriptors, the import name tables, the hint/name entries and the dll names.
+	///
+	/// Point the import data directory at the given rva with a size of `(descriptors.len() + 1) * 20` bytes.
+	pub fn build(&self, rva: Rva) -> Vec<u8> {
+		const SIZE_OF_VA: usize = mem::size_of::<Va>();
+		let descriptors_size = (self.descriptors.len() + 1) * mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>();
+		let thunks_size: usize = self.descriptors.iter().map(|desc| (desc.symbols.len() + 1) * SIZE_OF_VA).sum();
+
+		let mut bytes = vec![0u8; descriptors_size.align_to(SIZE_OF_VA) + thunks_size];
+		let mut thunks_offset = descriptors_size.align_to(SIZE_OF_VA);
+		for (index, desc) in self.descriptors.iter().enumerate() {
+			// Import name table
+			let int_rva = rva.wrapping_add(thunks_offset as Rva);
+			for symbol in &desc.symbols {
+				let thunk = match symbol {
+					Symbol::Name(name) => {
+						let hint_name_rva = rva.wrapping_add(bytes.len() as Rva);
+						bytes.extend_from_slice(&[0, 0]);
+						bytes.extend_from_slice(name.as_bytes());
+						bytes.push(0);
+						bytes.resize(bytes.len().align_to(2), 0);
+						hint_name_rva as Va
+					},
+					&Symbol::Ordinal(ordinal) => IMAGE_ORDINAL_FLAG | ordinal as Va,
+				};
+				bytes[thunks_offset..thunks_offset + SIZE_OF_VA].copy_from_slice(&thunk.to_le_bytes());
+				thunks_offset += SIZE_OF_VA;
+			}
+			thunks_offset += SIZE_OF_VA;
+
+			// Dll name
+			let name_rva = rva.wrapping_add(bytes.len() as Rva);
+			bytes.extend_from_slice(desc.dll_name.as_bytes());
+			bytes.push(0);
+			bytes.resize(bytes.len().align_to(2), 0);
+
+			let offset = index * mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>();
+			let fields = [int_rva, 0, 0, name_rva, desc.first_thunk];
+			for (i, field) in fields.iter().enumerate() {
+				bytes[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&field.to_le_bytes());
+			}
+		}
+		bytes
+	}
+}
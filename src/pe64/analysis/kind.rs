@@ -0,0 +1,132 @@
+/*!
+Image classification.
+
+Buckets images by what loads them: the kernel, the EFI firmware, the CLR or the regular Windows loader.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::kind::{image_kind, ImageKind};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	match image_kind(file) {
+		ImageKind::Driver => println!("kernel driver"),
+		ImageKind::DotNet => println!("managed assembly"),
+		kind => println!("{}", kind),
+	}
+}
+```
+*/
+
+use std::fmt;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::Pe;
+
+/// Kind of image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum ImageKind {
+	/// EFI application, driver or option ROM.
+	Efi,
+	/// Kernel mode driver, including kernel mode dlls.
+	Driver,
+	/// Native application running before the Win32 subsystem, eg. `smss.exe`.
+	Native,
+	/// Managed .NET assembly.
+	DotNet,
+	/// Dynamic link library.
+	Dll,
+	/// Executable.
+	Exe,
+}
+impl ImageKind {
+	/// Returns the kind as a short lowercase name.
+	pub fn to_str(self) -> &'static str {
+		match self {
+			ImageKind::Efi => "efi",
+			ImageKind::Driver => "driver",
+			ImageKind::Native => "native",
+			ImageKind::DotNet => "dotnet",
+			ImageKind::Dll => "dll",
+			ImageKind::Exe => "exe",
+		}
+	}
+}
+impl fmt::Display for ImageKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.to_str())
+	}
+}
+
+/// Modules only kernel mode images import from.
+const KERNEL_MODULES: [&[u8]; 9] = [
+	b"ntoskrnl.exe", b"ntkrnlpa.exe", b"ntkrnlmp.exe", b"ntkrpamp.exe", b"hal.dll", b"fltmgr.sys", b"ndis.sys", b"wdfldr.sys", b"cng.sys",
+];
+
+/// Classifies the image.
+///
+/// The predicates are checked in the order of the variants, eg. a managed dll is classified as `DotNet`.
+pub fn image_kind<'a, P: Pe<'a>>(pe: P) -> ImageKind {
+	if is_efi(pe) {
+		ImageKind::Efi
+	}
+	else if is_driver(pe) {
+		ImageKind::Driver
+	}
+	else if pe.optional_header().Subsystem.le() == IMAGE_SUBSYSTEM_NATIVE {
+		ImageKind::Native
+	}
+	else if is_dotnet(pe) {
+		ImageKind::DotNet
+	}
+	else if is_dll(pe) {
+		ImageKind::Dll
+	}
+	else {
+		ImageKind::Exe
+	}
+}
+
+/// Returns true if the image is a kernel mode driver.
+///
+/// Drivers use the native subsystem and are either marked as WDM drivers or import from the kernel or one of its core modules.
+pub fn is_driver<'a, P: Pe<'a>>(pe: P) -> bool {
+	let optional_header = pe.optional_header();
+	if optional_header.Subsystem.le() != IMAGE_SUBSYSTEM_NATIVE {
+		return false;
+	}
+	if optional_header.DllCharacteristics.le() & IMAGE_DLLCHARACTERISTICS_WDM_DRIVER != 0 {
+		return true;
+	}
+	let imports = match pe.imports() {
+		Ok(imports) => imports,
+		Err(_) => return false,
+	};
+	imports.iter().filter_map(|desc| desc.dll_name().ok()).any(|dll_name| {
+		KERNEL_MODULES.iter().any(|module| dll_name.as_ref().eq_ignore_ascii_case(module))
+	})
+}
+
+/// Returns true if the image is a dll.
+pub fn is_dll<'a, P: Pe<'a>>(pe: P) -> bool {
+	pe.file_header().Characteristics.le() & IMAGE_FILE_DLL != 0
+}
+
+/// Returns true if the image is a managed .NET assembly, including mixed mode assemblies.
+pub fn is_dotnet<'a, P: Pe<'a>>(pe: P) -> bool {
+	pe.clr().is_ok()
+}
+
+/// Returns true if the image is loaded by EFI firmware.
+pub fn is_efi<'a, P: Pe<'a>>(pe: P) -> bool {
+	matches!(
+		pe.optional_header().Subsystem.le(),
+		IMAGE_SUBSYSTEM_EFI_APPLICATION | IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER | IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER | IMAGE_SUBSYSTEM_EFI_ROM
+	)
+}
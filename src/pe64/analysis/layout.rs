@@ -0,0 +1,161 @@
+/*!
+Image layout map.
+
+Describes the whole virtual address space of the image as an ordered list of regions: the headers, every section, every data directory and the gaps between them.
+The overlay is appended to the file and isn't part of the address space, its file range is reported separately.
+
+Sections which overlap each other or the headers and data directories which overlap each other are flagged, a data directory is expected to be nested within a section.
+
+This is the data model behind those colorful PE layout visualizations.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let layout = file.layout();
+	for region in &layout.regions {
+		println!("{:#010x}..{:#010x} {:?}", region.rva, region.end(), region.kind);
+	}
+	for &(first, second) in &layout.overlaps {
+		println!("{:?} overlaps {:?}", layout.regions[first].kind, layout.regions[second].kind);
+	}
+	if let Some(overlay) = &layout.overlay {
+		println!("overlay at file offset {:#x}..{:#x}", overlay.start, overlay.end);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+use std::ops::Range;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::{Pe, Rva};
+use super::overlay::overlay;
+
+/// Kind of region.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum RegionKind {
+	/// The headers up to `SizeOfHeaders`.
+	Headers,
+	/// Section with the given index in the section headers.
+	Section(usize),
+	/// Data directory with the given index.
+	Directory(usize),
+	/// Address space not covered by the headers or any section.
+	Gap,
+}
+impl RegionKind {
+	// Directories are nested within the headers and sections
+	fn is_directory(self) -> bool {
+		matches!(self, RegionKind::Directory(_))
+	}
+}
+
+/// Region of the virtual address space.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Region {
+	/// Start of the region.
+	pub rva: Rva,
+	/// Size of the region in bytes.
+	pub size: u32,
+	/// Kind of the region.
+	pub kind: RegionKind,
+}
+impl Region {
+	/// Returns the rva one past the end of the region.
+	pub fn end(&self) -> Rva {
+		self.rva.saturating_add(self.size)
+	}
+	/// Returns true if the region contains the rva.
+	pub fn contains(&self, rva: Rva) -> bool {
+		rva >= self.rva && rva < self.end()
+	}
+	fn overlaps(&self, other: &Region) -> bool {
+		cmp::max(self.rva, other.rva) < cmp::min(self.end(), other.end())
+	}
+}
+
+/// Image layout map.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Layout {
+	/// Regions ordered by rva, the headers and sections before the data directories starting at the same rva.
+	pub regions: Vec<Region>,
+	/// Indices of every pair of overlapping regions.
+	pub overlaps: Vec<(usize, usize)>,
+	/// File range of the overlay, only available for file images.
+	pub overlay: Option<Range<usize>>,
+}
+impl Layout {
+	/// Returns true if no regions overlap.
+	pub fn is_clean(&self) -> bool {
+		self.overlaps.is_empty()
+	}
+	/// Iterates over the regions containing the rva.
+	pub fn regions_at(&self, rva: Rva) -> impl '_ + Iterator<Item = &'_ Region> {
+		self.regions.iter().filter(move |region| region.contains(rva))
+	}
+}
+
+/// Maps the layout of the image.
+///
+/// The extent of a section is the larger of its virtual size and its size of raw data.
+/// The security directory is a file offset and is reported as part of the overlay instead.
+pub fn layout<'a, P: Pe<'a>>(pe: P) -> Layout {
+	let optional_header = pe.optional_header();
+	let size_of_image = optional_header.SizeOfImage.le();
+
+	let mut regions = vec![Region { rva: 0, size: optional_header.SizeOfHeaders.le(), kind: RegionKind::Headers }];
+	for (index, section) in pe.section_headers().image().iter().enumerate() {
+		let section = section.le();
+		let size = cmp::max(section.VirtualSize, section.SizeOfRawData);
+		regions.push(Region { rva: section.VirtualAddress, size, kind: RegionKind::Section(index) });
+	}
+
+	// Fill the gaps between the headers and sections up to the end of the image
+	let mut covered: Vec<_> = regions.iter().map(|region| (region.rva, region.end())).collect();
+	covered.sort_unstable();
+	let mut next = 0;
+	for (start, end) in covered {
+		if start > next {
+			regions.push(Region { rva: next, size: cmp::min(start, size_of_image).saturating_sub(next), kind: RegionKind::Gap });
+		}
+		next = cmp::max(next, end);
+	}
+	if size_of_image > next {
+		regions.push(Region { rva: next, size: size_of_image - next, kind: RegionKind::Gap });
+	}
+	regions.retain(|region| region.size != 0 || region.kind != RegionKind::Gap);
+
+	for (index, datadir) in pe.data_directory().iter().enumerate() {
+		let datadir = datadir.le();
+		if index != IMAGE_DIRECTORY_ENTRY_SECURITY && datadir.VirtualAddress != 0 && datadir.Size != 0 {
+			regions.push(Region { rva: datadir.VirtualAddress, size: datadir.Size, kind: RegionKind::Directory(index) });
+		}
+	}
+	regions.sort_by_key(|region| (region.rva, region.kind.is_directory(), cmp::Reverse(region.size)));
+
+	let mut overlaps = Vec::new();
+	for (second, region) in regions.iter().enumerate() {
+		for (first, other) in regions[..second].iter().enumerate() {
+			if region.kind.is_directory() == other.kind.is_directory() && region.overlaps(other) {
+				overlaps.push((first, second));
+			}
+		}
+	}
+
+	let overlay = overlay(pe).map(|overlay| overlay.offset()..overlay.offset() + overlay.bytes().len());
+	Layout { regions, overlaps, overlay }
+}
@@ -0,0 +1,306 @@
+/*!
+Security mitigations report.
+
+Inspects the dll characteristics, the load config directory, the debug directory and the imports to report which exploit mitigations an image opts into.
+
+The stack buffer overrun detection also reports where its [security cookie](struct.SecurityCookie.html) lives and whether it looks initialized by the entry point.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::mitigations::{Mitigations, Status};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let mitigations = Mitigations::new(file);
+
+	// Print the status of every mitigation
+	print!("{}", mitigations);
+
+	// Check individual mitigations
+	if mitigations.cfg != Status::Enabled {
+		println!("control flow guard is not enabled!");
+	}
+}
+```
+*/
+
+use std::{cmp, fmt};
+
+use crate::util::Le;
+use crate::Error;
+
+use super::super::image::*;
+use super::super::imports::Import;
+use super::super::{Pe, Rva};
+
+//----------------------------------------------------------------
+
+/// Status of a mitigation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Status {
+	/// The image opts into the mitigation.
+	Enabled,
+	/// The image does not opt into the mitigation.
+	Disabled,
+	/// The mitigation does not apply to this kind of image.
+	NotApplicable,
+	/// The directories needed to tell are corrupt.
+	Unknown,
+}
+impl Status {
+	fn from_bool(enabled: bool) -> Status {
+		if enabled { Status::Enabled } else { Status::Disabled }
+	}
+}
+impl fmt::Display for Status {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Status::Enabled => "enabled",
+			Status::Disabled => "disabled",
+			Status::NotApplicable => "n/a",
+			Status::Unknown => "unknown",
+		})
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Security cookie used by stack buffer overrun detection.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct SecurityCookie {
+	/// Address of the security cookie from the load config.
+	pub rva: Rva,
+	/// The cookie resides in a writable section.
+	///
+	/// The cookie is randomized at startup, it cannot be initialized if its section is read-only.
+	pub writable: bool,
+	/// The cookie is initialized near the entry point.
+	///
+	/// This is a heuristic: the entry point or one of the functions it calls right away references the cookie the way `__security_init_cookie` does.
+	pub initialized: bool,
+}
+
+//----------------------------------------------------------------
+
+/// Security mitigations report.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Mitigations {
+	/// Address space layout randomization.
+	///
+	/// Requires `IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE` and base relocations, the image cannot be rebased without them.
+	pub aslr: Status,
+	/// High entropy 64-bit address space layout randomization.
+	///
+	/// Requires `IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA` on top of ASLR. Not applicable to PE32 images.
+	pub high_entropy_va: Status,
+	/// Data execution prevention, `IMAGE_DLLCHARACTERISTICS_NX_COMPAT`.
+	pub dep: Status,
+	/// Control flow guard.
+	///
+	/// Requires `IMAGE_DLLCHARACTERISTICS_GUARD_CF` and the instrumented guard flag in the load config.
+	pub cfg: Status,
+	/// Extended flow guard, the typed successor of control flow guard.
+	pub xfg: Status,
+	/// CET shadow stack compatibility from the extended dll characteristics in the debug directory.
+	///
+	/// Not applicable to PE32 images.
+	pub cet_shadow_stack: Status,
+	/// Safe structured exception handlers.
+	///
+	/// Requires the exception handler table in the load config.
+	/// Not applicable to PE32+ images which use table based exception handling, or images with `IMAGE_DLLCHARACTERISTICS_NO_SEH`.
+	pub safe_seh: Status,
+	/// Stack buffer overrun detection (`/GS`).
+	///
+	/// This is a heuristic: the image is assumed to be compiled with `/GS` if the load config has a security cookie or it imports the stack cookie helpers.
+	pub gs: Status,
+	/// The security cookie from the load config.
+	///
+	/// Is `None` if the load config has no security cookie or its address is invalid.
+	pub security_cookie: Option<SecurityCookie>,
+	/// Return flow guard.
+	pub rfg: Status,
+}
+
+impl Mitigations {
+	/// Inspects the image for its security mitigations.
+	///
+	/// Corrupt directories do not fail the report, the affected mitigations are reported as [`Unknown`](enum.Status.html#variant.Unknown) instead.
+	pub fn new<'a, P: Pe<'a>>(pe: P) -> Mitigations {
+		let dll_chars = pe.optional_header().DllCharacteristics.le();
+		let has_dll_chars = |flag: u16| dll_chars & flag != 0;
+
+		// Images without relocations are loaded at their preferred base
+		let relocs_stripped = pe.file_header().Characteristics.le() & IMAGE_FILE_RELOCS_STRIPPED != 0;
+		let has_relocs = match pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_BASERELOC).map(|datadir| datadir.le()) {
+			Some(datadir) => datadir.VirtualAddress != 0 && datadir.Size != 0,
+			None => false,
+		};
+		let aslr = Status::from_bool(has_dll_chars(IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE) && has_relocs && !relocs_stripped);
+
+		let high_entropy_va = branch! {
+			pe32 { Status::NotApplicable }
+			pe64 { Status::from_bool(aslr == Status::Enabled && has_dll_chars(IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA)) }
+		};
+
+		let dep = Status::from_bool(has_dll_chars(IMAGE_DLLCHARACTERISTICS_NX_COMPAT));
+
+		let load_config = pe.load_config();
+		let guard_flags = match &load_config {
+			Ok(load_config) => Some(load_config.guard_flags().unwrap_or(0)),
+			Err(Error::Null) => Some(0),
+			Err(_) => None,
+		};
+		let guard_status = |enabled: fn(u32) -> bool| guard_flags.map_or(Status::Unknown, |flags| Status::from_bool(enabled(flags)));
+
+		let cfg = if has_dll_chars(IMAGE_DLLCHARACTERISTICS_GUARD_CF) {
+			guard_status(|flags| flags & IMAGE_GUARD_CF_INSTRUMENTED != 0)
+		}
+		else {
+			Status::Disabled
+		};
+		let xfg = match cfg {
+			Status::Enabled => guard_status(|flags| flags & IMAGE_GUARD_XFG_ENABLED != 0),
+			status => status,
+		};
+		let rfg = guard_status(|flags| flags & IMAGE_GUARD_RF_INSTRUMENTED != 0 && flags & (IMAGE_GUARD_RF_ENABLE | IMAGE_GUARD_RF_STRICT) != 0);
+
+		let cet_shadow_stack = branch! {
+			pe32 { Status::NotApplicable }
+			pe64 { cet_compat(pe) }
+		};
+
+		let safe_seh = branch! {
+			pe32 {
+				match &load_config {
+					_ if has_dll_chars(IMAGE_DLLCHARACTERISTICS_NO_SEH) => Status::NotApplicable,
+					Ok(load_config) => {
+						let image = load_config.image();
+						Status::from_bool(image.Size.le() as usize >= std::mem::size_of::<IMAGE_LOAD_CONFIG_DIRECTORY>() && image.SEHandlerTable.le() != 0)
+					},
+					Err(Error::Null) => Status::Disabled,
+					Err(_) => Status::Unknown,
+				}
+			}
+			pe64 { Status::NotApplicable }
+		};
+
+		let gs = match &load_config {
+			Ok(load_config) if load_config.security_cookie().is_ok() => Status::Enabled,
+			_ if imports_gs_helpers(pe) => Status::Enabled,
+			Ok(_) | Err(Error::Null) => Status::Disabled,
+			Err(_) => Status::Unknown,
+		};
+
+		let security_cookie = match &load_config {
+			Ok(load_config) => security_cookie(pe, load_config.image().SecurityCookie.le()),
+			Err(_) => None,
+		};
+
+		Mitigations { aslr, high_entropy_va, dep, cfg, xfg, cet_shadow_stack, safe_seh, gs, security_cookie, rfg }
+	}
+}
+
+branch! {
+	pe32 {}
+	pe64 {
+		fn cet_compat<'a, P: Pe<'a>>(pe: P) -> Status {
+			let debug = match pe.debug() {
+				Ok(debug) => debug,
+				Err(Error::Null) => return Status::Disabled,
+				Err(_) => return Status::Unknown,
+			};
+			for dir in debug {
+				if dir.image().Type.le() != IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS {
+					continue;
+				}
+				return match dir.data() {
+					Some(&[a, b, c, d, ..]) => Status::from_bool(u32::from_le_bytes([a, b, c, d]) & IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT != 0),
+					_ => Status::Unknown,
+				};
+			}
+			Status::Disabled
+		}
+	}
+}
+
+// The stack cookie helpers are imported from the CRT when it is linked dynamically, eg. by MinGW
+fn imports_gs_helpers<'a, P: Pe<'a>>(pe: P) -> bool {
+	const HELPERS: [&str; 3] = ["__security_init_cookie", "__security_check_cookie", "__report_gsfailure"];
+	let imports = match pe.imports() {
+		Ok(imports) => imports,
+		Err(_) => return false,
+	};
+	imports.iter().filter_map(|desc| desc.int().ok()).flatten().any(|import| match import {
+		Ok(Import::ByName { name, .. }) => HELPERS.iter().any(|&helper| name == helper),
+		_ => false,
+	})
+}
+
+// Locates the cookie and looks for its initialization in the entry point and the functions it calls first
+fn security_cookie<'a, P: Pe<'a>>(pe: P, va: Va) -> Option<SecurityCookie> {
+	// How far into the entry point to look for calls and how far into those functions to look for the cookie
+	const ENTRY_WINDOW: usize = 0x40;
+	const INIT_WINDOW: usize = 0x60;
+
+	if va == 0 {
+		return None;
+	}
+	let rva = pe.va_to_rva(va).ok()?;
+	let writable = pe.section_headers().iter()
+		.find(|section| section.virtual_range().contains(&rva))
+		.map_or(false, |section| section.Characteristics.le() & IMAGE_SCN_MEM_WRITE != 0);
+
+	let code = |rva: Rva, window: usize| pe.slice_bytes(rva).map(|bytes| &bytes[..cmp::min(bytes.len(), window)]).unwrap_or(&[]);
+	let is_pe32 = Va::BITNESS == 32;
+	let references = |func: Rva| code(func, INIT_WINDOW).windows(4).enumerate().any(|(i, window)| {
+		let value = u32::from_le_bytes([window[0], window[1], window[2], window[3]]);
+		if is_pe32 {
+			// Absolute address of the cookie
+			value as Va == va
+		}
+		else {
+			// Rip relative address of the cookie, assumes the displacement ends the instruction
+			func.wrapping_add(i as u32 + 4).wrapping_add(value) == rva
+		}
+	});
+
+	let entry_point = pe.optional_header().AddressOfEntryPoint.le();
+	let initialized = entry_point != 0 && (references(entry_point) || {
+		let bytes = code(entry_point, ENTRY_WINDOW);
+		(0..bytes.len()).any(|i| match bytes[i..] {
+			// call rel32 or jmp rel32
+			[0xE8, a, b, c, d, ..] | [0xE9, a, b, c, d, ..] => references(entry_point.wrapping_add(i as u32 + 5).wrapping_add(u32::from_le_bytes([a, b, c, d]))),
+			_ => false,
+		})
+	});
+
+	Some(SecurityCookie { rva, writable, initialized })
+}
+
+impl fmt::Display for Mitigations {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "ASLR:             {}", self.aslr)?;
+		writeln!(f, "High entropy VA:  {}", self.high_entropy_va)?;
+		writeln!(f, "DEP:              {}", self.dep)?;
+		writeln!(f, "CFG:              {}", self.cfg)?;
+		writeln!(f, "XFG:              {}", self.xfg)?;
+		writeln!(f, "CET shadow stack: {}", self.cet_shadow_stack)?;
+		writeln!(f, "SafeSEH:          {}", self.safe_seh)?;
+		writeln!(f, "GS:               {}", self.gs)?;
+		if let Some(cookie) = &self.security_cookie {
+			writeln!(f, "GS cookie:        {:#x}{}{}", cookie.rva,
+				if cookie.writable { "" } else { " (read-only)" },
+				if cookie.initialized { "" } else { " (uninitialized)" })?;
+		}
+		writeln!(f, "RFG:              {}", self.rfg)
+	}
+}
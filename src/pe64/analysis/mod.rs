@@ -0,0 +1,47 @@
+/*!
+Image analysis.
+
+Heuristics and reports built on top of the parsed directories.
+*/
+
+pub mod catalog;
+pub mod caves;
+pub mod coverage;
+branch! {
+	pe32 {}
+	pe64 { pub mod detour; }
+}
+pub mod entry_point;
+pub mod footprint;
+pub mod hashes;
+pub mod hidden;
+#[cfg(feature = "std")]
+pub mod imprec;
+pub mod kind;
+pub mod layout;
+pub mod mitigations;
+#[cfg(feature = "std")]
+pub mod oep;
+pub mod overlay;
+#[cfg(feature = "std")]
+pub mod packer;
+pub mod rebase;
+pub mod slack;
+pub mod toolchain;
+pub mod what_is;
+
+/// Returns true if the needle occurs in the haystack.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+	let first = match needle.first() {
+		Some(&first) => first,
+		None => return true,
+	};
+	let mut rest = haystack;
+	while let Some(i) = rest.iter().position(|&byte| byte == first) {
+		if rest[i..].starts_with(needle) {
+			return true;
+		}
+		rest = &rest[i + 1..];
+	}
+	false
+}
@@ -0,0 +1,181 @@
+/*!
+Original entry point heuristics.
+
+Packed images start execution in the packer's stub which unpacks the original code and then transfers control to the original entry point.
+For images dumped from memory after unpacking, these heuristics suggest where the original entry point is:
+
+* Known C runtime entry point code sequences.
+* Tail jumps from the entry point section into another executable section, the way packer stubs hand over control.
+* Stub epilogues restoring the registers right before such a jump.
+* The jump leaving a high entropy section for a lower entropy section.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeView};
+use pelite::pe64::analysis::oep::find_oep;
+
+# #[allow(dead_code)]
+fn example(dump: PeView<'_>) {
+	for candidate in find_oep(dump).iter().take(3) {
+		println!("{:#x} (score {}): {:?}", candidate.rva, candidate.score, candidate.reasons);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+
+use crate::pattern as pat;
+use crate::util::{shannon_entropy, Le};
+
+use super::super::image::*;
+use super::super::{Pe, Rva};
+
+/// Reason for suggesting an original entry point candidate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Reason {
+	/// The candidate is the entry point in the optional header.
+	EntryPoint,
+	/// The candidate matches a known C runtime entry point signature.
+	CrtSignature(&'static str),
+	/// A jump at the given rva in the entry point section targets the candidate in another executable section.
+	TailJump(Rva),
+	/// The tail jump at the given rva follows a stub epilogue restoring the registers.
+	StubEpilogue(Rva),
+	/// The tail jump at the given rva leaves a section with higher entropy than the section of the candidate.
+	EntropyDrop(Rva),
+}
+impl Reason {
+	/// Returns the score contributed by the reason.
+	pub fn score(&self) -> u32 {
+		match self {
+			Reason::EntryPoint => 10,
+			Reason::CrtSignature(_) => 40,
+			Reason::TailJump(_) => 30,
+			Reason::StubEpilogue(_) => 20,
+			Reason::EntropyDrop(_) => 10,
+		}
+	}
+}
+
+/// Original entry point candidate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Candidate {
+	/// Address of the candidate.
+	pub rva: Rva,
+	/// The sum of the scores of the reasons.
+	pub score: u32,
+	/// The reasons for suggesting the candidate.
+	pub reasons: Vec<Reason>,
+}
+
+//----------------------------------------------------------------
+
+branch! {
+	pe32 {
+		const CRT_SIGNATURES: [(&str, &str); 5] = [
+			("MSVC mainCRTStartup", "E8 ? ? ? ? E9 ? ? ? ? 8B FF 55 8B EC"),
+			("MSVC DllMainCRTStartup", "55 8B EC 83 7D 0C 01 75 05 E8 ? ? ? ? FF 75 10 FF 75 0C FF 75 08 E8"),
+			("MSVC DllMainCRTStartup", "8B FF 55 8B EC 83 7D 0C 01 75 05 E8"),
+			("MinGW mainCRTStartup", "83 EC 1C C7 04 24 ( 01 | 02 ) 00 00 00 FF 15"),
+			("Delphi entry point", "55 8B EC 83 C4 ? B8 ? ? ? ? E8"),
+		];
+	}
+	pe64 {
+		const CRT_SIGNATURES: [(&str, &str); 3] = [
+			("MSVC mainCRTStartup", "48 83 EC 28 E8 ? ? ? ? 48 83 C4 28 E9"),
+			("MSVC DllMainCRTStartup", "48 89 5C 24 08 48 89 74 24 10 57 48 83 EC 20 49 8B F8 8B DA 48 8B F1 83 FA 01 75 ? E8"),
+			("MinGW mainCRTStartup", "48 83 EC 28 48 8B 05 ? ? ? ? C7 00 ( 00 | 01 ) 00 00 00 E8"),
+		];
+	}
+}
+
+/// Suggests original entry point candidates.
+///
+/// Returns the candidates ranked by score, highest first.
+pub fn find_oep<'a, P: Pe<'a>>(pe: P) -> Vec<Candidate> {
+	let mut reasons = Vec::new();
+	let section_headers: Vec<IMAGE_SECTION_HEADER> = pe.section_headers().image().iter().map(|section| section.le()).collect();
+	let is_code = |section: &IMAGE_SECTION_HEADER| section.Characteristics & (IMAGE_SCN_MEM_EXECUTE | IMAGE_SCN_CNT_CODE) != 0;
+	let section_of = |rva: Rva| section_headers.iter().position(|section| {
+		rva.wrapping_sub(section.VirtualAddress) < cmp::max(section.VirtualSize, section.SizeOfRawData)
+	});
+
+	let entry_point = pe.optional_header().AddressOfEntryPoint.le();
+	if entry_point != 0 {
+		reasons.push((entry_point, Reason::EntryPoint));
+	}
+
+	// Known C runtime entry point code
+	let scanner = pe.scanner();
+	let mut save = [0; 4];
+	for &(name, pattern) in &CRT_SIGNATURES {
+		if let Ok(pattern) = pat::parse(pattern) {
+			let mut matches = scanner.matches_code(&pattern);
+			while matches.next(&mut save) {
+				reasons.push((save[0], Reason::CrtSignature(name)));
+			}
+		}
+	}
+
+	// Tail jumps from the stub in the entry point section into another executable section
+	if let Some(stub_index) = section_of(entry_point) {
+		let stub = &section_headers[stub_index];
+		let bytes = pe.get_section_bytes(stub).unwrap_or(&[]);
+		let virtual_size = if stub.VirtualSize != 0 { stub.VirtualSize } else { stub.SizeOfRawData };
+		let bytes = &bytes[..cmp::min(bytes.len(), virtual_size as usize)];
+		let entropy_of = |section: &IMAGE_SECTION_HEADER| {
+			let bytes = pe.get_section_bytes(section).unwrap_or(&[]);
+			shannon_entropy(&bytes[..cmp::min(bytes.len(), section.SizeOfRawData as usize)])
+		};
+		let stub_entropy = entropy_of(stub);
+		let is_pe32 = Va::BITNESS == 32;
+		for i in 0..bytes.len() {
+			let from = stub.VirtualAddress.wrapping_add(i as u32);
+			let target = match bytes[i..] {
+				// jmp rel32
+				[0xE9, a, b, c, d, ..] => from.wrapping_add(5).wrapping_add(u32::from_le_bytes([a, b, c, d])),
+				// push imm32; ret
+				[0x68, a, b, c, d, 0xC3, ..] if is_pe32 => {
+					match pe.va_to_rva(u32::from_le_bytes([a, b, c, d]) as _) {
+						Ok(target) => target,
+						Err(_) => continue,
+					}
+				},
+				_ => continue,
+			};
+			let target_index = match section_of(target) {
+				Some(index) if index != stub_index && is_code(&section_headers[index]) => index,
+				_ => continue,
+			};
+			reasons.push((target, Reason::TailJump(from)));
+			// popad restores the registers saved by the stub
+			if is_pe32 && bytes[i.saturating_sub(16)..i].contains(&0x61) {
+				reasons.push((target, Reason::StubEpilogue(from)));
+			}
+			if stub_entropy > entropy_of(&section_headers[target_index]) {
+				reasons.push((target, Reason::EntropyDrop(from)));
+			}
+		}
+	}
+
+	// Merge the reasons per candidate and rank them
+	reasons.sort_by_key(|&(rva, _)| rva);
+	let mut candidates: Vec<Candidate> = Vec::new();
+	for (rva, reason) in reasons {
+		match candidates.last_mut() {
+			Some(candidate) if candidate.rva == rva => {
+				candidate.score += reason.score();
+				candidate.reasons.push(reason);
+			},
+			_ => candidates.push(Candidate { rva, score: reason.score(), reasons: vec![reason] }),
+		}
+	}
+	candidates.sort_by_key(|candidate| cmp::Reverse(candidate.score));
+	candidates
+}
@@ -0,0 +1,153 @@
+/*!
+Overlay content sniffing.
+
+The overlay is the data appended to a PE file after the end of its last section, it is not mapped when the image is loaded.
+Authenticode signatures, self-extracting archives and installers store their payload there.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile, PeObject};
+use pelite::pe64::analysis::overlay::{overlay, OverlayKind};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	if let Some(overlay) = overlay(file) {
+		match overlay.kind() {
+			OverlayKind::Nsis { offset } | OverlayKind::Zip { offset } => {
+				// Extract the payload starting at the file offset
+				let payload = &file.image()[offset..];
+			},
+			kind => println!("overlay at {:#x}: {:?}", overlay.offset(), kind),
+		}
+	}
+}
+```
+*/
+
+use std::cmp;
+use std::ops::Range;
+
+use crate::util::Le;
+
+use super::super::image::*;
+use super::super::{Align, Pe};
+use super::contains;
+
+/// Overlay content classification.
+///
+/// The offsets are file offsets of the start of the embedded payload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum OverlayKind {
+	/// Only zero padding, eg. from page aligned memory mapping.
+	Padding,
+	/// Only the Authenticode certificate table and zero padding.
+	Authenticode,
+	/// ZIP archive, eg. a self-extracting archive.
+	Zip { offset: usize },
+	/// Microsoft cabinet archive.
+	Cab { offset: usize },
+	/// 7-Zip archive.
+	SevenZip { offset: usize },
+	/// Nullsoft Scriptable Install System installer data, the offset of its first header.
+	Nsis { offset: usize },
+	/// Inno Setup installer data.
+	InnoSetup { offset: usize },
+	/// Unrecognized data.
+	Unknown,
+}
+
+/// Overlay of a PE file.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Debug)]
+pub struct Overlay<'a> {
+	offset: usize,
+	bytes: &'a [u8],
+	certificates: (usize, usize),
+}
+
+/// Gets the overlay of a PE file.
+///
+/// Returns `None` if there is no data after the end of the last section, or if the image is mapped in memory and its overlay is not available.
+pub fn overlay<'a, P: Pe<'a>>(pe: P) -> Option<Overlay<'a>> {
+	if pe.align() != Align::File {
+		return None;
+	}
+	let mut offset = pe.optional_header().SizeOfHeaders.le() as usize;
+	for section in pe.section_headers() {
+		if section.SizeOfRawData.le() != 0 {
+			offset = cmp::max(offset, (section.PointerToRawData.le() as usize).saturating_add(section.SizeOfRawData.le() as usize));
+		}
+	}
+	let bytes = pe.image().get(offset..).filter(|bytes| !bytes.is_empty())?;
+	// The certificate table is not mapped and its address is a file offset
+	let certificates = match pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY).map(|datadir| datadir.le()) {
+		Some(datadir) if datadir.VirtualAddress != 0 && datadir.VirtualAddress as usize >= offset => {
+			let start = datadir.VirtualAddress as usize - offset;
+			(start, start.saturating_add(datadir.Size as usize))
+		},
+		_ => (0, 0),
+	};
+	Some(Overlay { offset, bytes, certificates })
+}
+
+impl<'a> Overlay<'a> {
+	/// Returns the file offset of the overlay.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+	/// Returns the overlay bytes, including any certificate table.
+	pub fn bytes(&self) -> &'a [u8] {
+		self.bytes
+	}
+	/// Returns the file range of the certificate table if it is located in the overlay.
+	pub fn certificates(&self) -> Option<Range<usize>> {
+		let (start, end) = self.certificates;
+		if start < end { Some(self.offset + start..self.offset + end) } else { None }
+	}
+	/// Returns the length of the overlay data other than the certificate table and trailing zero padding.
+	pub fn data_len(&self) -> usize {
+		let (start, end) = self.certificates;
+		self.bytes.iter().enumerate().rposition(|(i, &byte)| byte != 0 && !(i >= start && i < end)).map_or(0, |last| last + 1)
+	}
+	/// Classifies the overlay content.
+	pub fn kind(&self) -> OverlayKind {
+		let (start, end) = self.certificates;
+		// Installers place their data before the certificate table
+		let before = &self.bytes[..cmp::min(start, self.bytes.len())];
+		let (data_offset, data) = if start >= end || before.iter().any(|&byte| byte != 0) {
+			(self.offset, if start < end { before } else { self.bytes })
+		}
+		else {
+			(self.offset.saturating_add(end), self.bytes.get(end..).unwrap_or(&[]))
+		};
+
+		if data.iter().all(|&byte| byte == 0) {
+			return if start < end { OverlayKind::Authenticode } else { OverlayKind::Padding };
+		}
+		if data.starts_with(b"PK\x03\x04") {
+			return OverlayKind::Zip { offset: data_offset };
+		}
+		if data.starts_with(b"MSCF\0\0\0\0") {
+			return OverlayKind::Cab { offset: data_offset };
+		}
+		if data.starts_with(b"7z\xBC\xAF\x27\x1C") {
+			return OverlayKind::SevenZip { offset: data_offset };
+		}
+		// The NSIS first header is searched for at 512 byte aligned file offsets
+		let mut nsis = (data_offset + 0x1ff) & !0x1ff;
+		while let Some(header) = data.get(nsis - data_offset + 4..nsis - data_offset + 20) {
+			if header == b"\xEF\xBE\xAD\xDENullsoftInst" {
+				return OverlayKind::Nsis { offset: nsis };
+			}
+			nsis += 0x200;
+		}
+		if data.starts_with(b"idska32\x1A") || data.starts_with(b"zlb\x1A") || contains(&data[..cmp::min(data.len(), 0x1000)], b"Inno Setup Setup Data (") {
+			return OverlayKind::InnoSetup { offset: data_offset };
+		}
+		OverlayKind::Unknown
+	}
+}
@@ -0,0 +1,244 @@
+/*!
+Packer detection.
+
+Checks the section names, section entropy, entry point location, import table size and overlay presence against a built-in set of packer signatures.
+Images which look packed but match no signature are reported as an [`Unknown`](enum.Packer.html#variant.Unknown) packer.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::packer::detect_packer;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	for packer_match in detect_packer(file) {
+		println!("{} ({}% confidence)", packer_match.packer, packer_match.confidence);
+		for indicator in &packer_match.indicators {
+			println!("  {:?}", indicator);
+		}
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, fmt};
+
+use crate::util::{shannon_entropy, Le};
+
+use super::super::headers::SectionHeader;
+use super::super::image::*;
+use super::super::Pe;
+use super::contains;
+use super::entry_point::{entry_point, Location};
+
+//----------------------------------------------------------------
+
+/// Known packers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Packer {
+	Upx,
+	Mpress,
+	Themida,
+	VMProtect,
+	ASPack,
+	PECompact,
+	Enigma,
+	NsPack,
+	Petite,
+	/// The image looks packed but matches no signature.
+	Unknown,
+}
+impl fmt::Display for Packer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Packer::Upx => "UPX",
+			Packer::Mpress => "MPRESS",
+			Packer::Themida => "Themida",
+			Packer::VMProtect => "VMProtect",
+			Packer::ASPack => "ASPack",
+			Packer::PECompact => "PECompact",
+			Packer::Enigma => "Enigma",
+			Packer::NsPack => "NsPack",
+			Packer::Petite => "Petite",
+			Packer::Unknown => "unknown packer",
+		})
+	}
+}
+
+/// Indicators of a packed image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Indicator<'a> {
+	/// A section name from the packer's signature.
+	SectionName(&'a str),
+	/// A marker string from the packer's signature found in the headers or the entry point section.
+	Marker(&'static str),
+	/// The raw data of the section has an entropy in bits per byte above `7.0`, typical of compressed or encrypted data.
+	HighEntropy(&'a str, f64),
+	/// The executable section has no raw data, it is filled in at runtime.
+	UninitializedCode(&'a str),
+	/// The entry point is in a section which isn't executable.
+	NonExecutableEntryPoint(&'a str),
+	/// The entry point is in a writable section.
+	WritableEntryPoint(&'a str),
+	/// The entry point is in the last of multiple sections.
+	EntryPointInLastSection(&'a str),
+	/// The image imports very few functions, the packer's stub resolves the rest at runtime.
+	FewImports(usize),
+	/// Data is appended after the end of the image, excluding the certificate table.
+	Overlay(usize),
+}
+impl<'a> Indicator<'a> {
+	/// Returns the weight of the indicator towards the confidence score.
+	pub fn weight(&self) -> u32 {
+		match self {
+			Indicator::SectionName(_) => 50,
+			Indicator::Marker(_) => 40,
+			_ => 10,
+		}
+	}
+}
+
+/// Packer detection match.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct PackerMatch<'a> {
+	/// The detected packer.
+	pub packer: Packer,
+	/// Confidence score between `0` and `100`.
+	pub confidence: u32,
+	/// The indicators supporting the match.
+	pub indicators: Vec<Indicator<'a>>,
+}
+
+//----------------------------------------------------------------
+
+struct Signature {
+	packer: Packer,
+	section_names: &'static [&'static str],
+	markers: &'static [&'static str],
+}
+
+const SIGNATURES: [Signature; 9] = [
+	Signature { packer: Packer::Upx, section_names: &["UPX0", "UPX1", "UPX2"], markers: &["UPX!", "$Info: This file is packed with the UPX"] },
+	Signature { packer: Packer::Mpress, section_names: &[".MPRESS1", ".MPRESS2"], markers: &[] },
+	Signature { packer: Packer::Themida, section_names: &[".themida", ".winlice"], markers: &[] },
+	Signature { packer: Packer::VMProtect, section_names: &[".vmp0", ".vmp1", ".vmp2"], markers: &[] },
+	Signature { packer: Packer::ASPack, section_names: &[".aspack", ".adata"], markers: &[] },
+	Signature { packer: Packer::PECompact, section_names: &["PEC2", "PEC2TO", "PEC2MO", "pec1", "pec2"], markers: &[] },
+	Signature { packer: Packer::Enigma, section_names: &[".enigma1", ".enigma2"], markers: &[] },
+	Signature { packer: Packer::NsPack, section_names: &[".nsp0", ".nsp1", ".nsp2"], markers: &[] },
+	Signature { packer: Packer::Petite, section_names: &[".petite"], markers: &[] },
+];
+
+const HIGH_ENTROPY: f64 = 7.0;
+const FEW_IMPORTS: usize = 10;
+
+/// Detects packers.
+///
+/// Returns the matches sorted by confidence, most confident first.
+/// Packers matching their signature are reported with their generic indicators included.
+/// If no signature matches but at least three generic indicators are found, an [`Unknown`](enum.Packer.html#variant.Unknown) packer is reported.
+pub fn detect_packer<'a, P: Pe<'a>>(pe: P) -> Vec<PackerMatch<'a>> {
+	let generic = generic_indicators(pe);
+
+	// Markers are expected in the headers or in the packer's stub at the entry point
+	let headers = pe.image().get(..pe.optional_header().SizeOfHeaders.le() as usize).unwrap_or(&[]);
+	let entry_section = entry_section(pe).and_then(|section| pe.get_section_bytes(section).ok()).unwrap_or(&[]);
+
+	let mut matches = Vec::new();
+	for signature in &SIGNATURES {
+		let mut indicators = Vec::new();
+		for section in pe.section_headers() {
+			if let Ok(name) = section.name() {
+				if signature.section_names.contains(&name) {
+					indicators.push(Indicator::SectionName(name));
+				}
+			}
+		}
+		for &marker in signature.markers {
+			if contains(headers, marker.as_bytes()) || contains(entry_section, marker.as_bytes()) {
+				indicators.push(Indicator::Marker(marker));
+			}
+		}
+		if !indicators.is_empty() {
+			indicators.extend_from_slice(&generic);
+			matches.push(packer_match(signature.packer, indicators));
+		}
+	}
+	if matches.is_empty() && generic.len() >= 3 {
+		matches.push(packer_match(Packer::Unknown, generic));
+	}
+	matches.sort_by_key(|packer_match| cmp::Reverse(packer_match.confidence));
+	matches
+}
+
+fn packer_match(packer: Packer, indicators: Vec<Indicator<'_>>) -> PackerMatch<'_> {
+	let confidence = cmp::min(100, indicators.iter().map(Indicator::weight).sum());
+	PackerMatch { packer, confidence, indicators }
+}
+
+fn entry_section<'a, P: Pe<'a>>(pe: P) -> Option<&'a SectionHeader> {
+	match entry_point(pe).location {
+		Location::Section(index) => pe.section_headers().iter().nth(index),
+		_ => None,
+	}
+}
+
+fn generic_indicators<'a, P: Pe<'a>>(pe: P) -> Vec<Indicator<'a>> {
+	let mut indicators = Vec::new();
+
+	let section_headers = pe.section_headers();
+	for section in section_headers {
+		let name = section.name().unwrap_or("");
+		let characteristics = section.Characteristics.le();
+		if characteristics & IMAGE_SCN_MEM_EXECUTE != 0 && section.SizeOfRawData.le() == 0 && section.VirtualSize.le() != 0 {
+			indicators.push(Indicator::UninitializedCode(name));
+		}
+		if let Ok(bytes) = pe.get_section_bytes(section) {
+			let bytes = &bytes[..cmp::min(bytes.len(), section.SizeOfRawData.le() as usize)];
+			if bytes.len() >= 0x200 {
+				let entropy = shannon_entropy(bytes);
+				if entropy > HIGH_ENTROPY {
+					indicators.push(Indicator::HighEntropy(name, entropy));
+				}
+			}
+		}
+	}
+
+	let entry_point = entry_point(pe);
+	if let Some(section) = entry_section(pe) {
+		let name = section.name().unwrap_or("");
+		if !entry_point.executable {
+			indicators.push(Indicator::NonExecutableEntryPoint(name));
+		}
+		if entry_point.writable {
+			indicators.push(Indicator::WritableEntryPoint(name));
+		}
+		if entry_point.last_section {
+			indicators.push(Indicator::EntryPointInLastSection(name));
+		}
+	}
+
+	if let Ok(imports) = pe.imports() {
+		let count = imports.iter().filter_map(|desc| desc.int().ok()).map(|int| int.count()).sum();
+		if count < FEW_IMPORTS {
+			indicators.push(Indicator::FewImports(count));
+		}
+	}
+
+	if let Some(overlay) = super::overlay::overlay(pe) {
+		let data_len = overlay.data_len();
+		if data_len != 0 {
+			indicators.push(Indicator::Overlay(data_len));
+		}
+	}
+
+	indicators
+}
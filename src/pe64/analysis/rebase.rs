@@ -0,0 +1,137 @@
+/*!
+Preferred base conflicts.
+
+Every image prefers to be loaded at its `ImageBase`.
+When that range is already occupied the loader relocates the image, which costs load time and the pages touched by the relocations can no longer be shared between processes.
+
+Given the images loaded into a process in load order, reports which preferred ranges overlap and which images are forced to relocate.
+Like the classic `REBASE.EXE` it suggests a layout which packs the images next to each other.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::PeFile;
+use pelite::pe64::analysis::rebase::{Range, Report};
+
+# #[allow(dead_code)]
+fn example(app: PeFile<'_>, plugin: PeFile<'_>) {
+	let report = Report::new(vec![Range::new("app.exe", app), Range::new("plugin.dll", plugin)]);
+	for conflict in &report.conflicts {
+		println!("{} overlaps {}", report.ranges[conflict.second].name, report.ranges[conflict.first].name);
+	}
+	for (range, base) in report.ranges.iter().zip(report.suggest(0x1_8000_0000)) {
+		println!("rebase {} to {:#x}", range.name, base);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+use crate::util::Le;
+
+use super::super::{Pe, Va};
+
+/// Allocation granularity of the address space, images are rebased to multiples of this value.
+pub const ALLOCATION_GRANULARITY: Va = 0x10000;
+
+/// Preferred address range of an image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Range<'n> {
+	/// Name of the image.
+	pub name: &'n str,
+	/// Preferred base address.
+	pub image_base: Va,
+	/// Size of the image.
+	pub size_of_image: u32,
+}
+impl<'n> Range<'n> {
+	/// Gets the preferred range of the image.
+	pub fn new<'a, P: Pe<'a>>(name: &'n str, pe: P) -> Range<'n> {
+		let optional_header = pe.optional_header();
+		Range {
+			name,
+			image_base: optional_header.ImageBase.le(),
+			size_of_image: optional_header.SizeOfImage.le(),
+		}
+	}
+	/// Returns the address one past the end of the range.
+	pub fn end(&self) -> Va {
+		self.image_base.saturating_add(self.size_of_image as Va)
+	}
+	/// Returns the number of bytes overlapping the other range.
+	pub fn overlap(&self, other: &Range<'_>) -> Va {
+		let start = Va::max(self.image_base, other.image_base);
+		let end = Va::min(self.end(), other.end());
+		end.saturating_sub(start)
+	}
+}
+
+/// Overlapping preferred ranges.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Conflict {
+	/// Index of the image loaded first.
+	pub first: usize,
+	/// Index of the image loaded second.
+	pub second: usize,
+	/// Number of overlapping bytes.
+	pub overlap: Va,
+}
+
+/// Preferred base conflict report.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Report<'n> {
+	/// Preferred ranges of the images in load order.
+	pub ranges: Vec<Range<'n>>,
+	/// Every pair of overlapping ranges.
+	pub conflicts: Vec<Conflict>,
+	/// Indices of the images which cannot be loaded at their preferred base.
+	///
+	/// An image is relocated if it overlaps an image loaded before it which was itself loaded at its preferred base.
+	pub relocated: Vec<usize>,
+}
+impl<'n> Report<'n> {
+	/// Analyzes the preferred ranges of the images given in load order.
+	pub fn new(ranges: Vec<Range<'n>>) -> Report<'n> {
+		let mut conflicts = Vec::new();
+		let mut relocated = Vec::new();
+		for (second, range) in ranges.iter().enumerate() {
+			let mut forced = false;
+			for (first, other) in ranges[..second].iter().enumerate() {
+				let overlap = range.overlap(other);
+				if overlap != 0 {
+					conflicts.push(Conflict { first, second, overlap });
+					forced |= relocated.binary_search(&first).is_err();
+				}
+			}
+			if forced {
+				relocated.push(second);
+			}
+		}
+		Report { ranges, conflicts, relocated }
+	}
+	/// Returns true if no image is forced to relocate.
+	pub fn is_clean(&self) -> bool {
+		self.relocated.is_empty()
+	}
+	/// Suggests a base address for every image so none of them overlap.
+	///
+	/// The images are packed in load order starting at the given base, every image aligned to the allocation granularity.
+	pub fn suggest(&self, base: Va) -> Vec<Va> {
+		let mask = ALLOCATION_GRANULARITY - 1;
+		let mut next = base.saturating_add(mask) & !mask;
+		self.ranges.iter()
+			.map(|range| {
+				let image_base = next;
+				next = next.saturating_add((range.size_of_image as Va).saturating_add(mask) & !mask);
+				image_base
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,99 @@
+/*!
+Section slack.
+
+Sections have two kinds of slack which are easily confused:
+
+* Physical slack is the raw data in the file past the virtual size of the section, up to its `SizeOfRawData`.
+  The linker zero pads the raw data to the file alignment, anything else in there is data hidden in the file.
+  The loader only maps the physical slack which fits in the virtual slack, the rest is never seen at runtime.
+* Virtual slack is the address space past the virtual size of the section, up to the section alignment.
+  It is mapped with the protection of the section but isn't backed by any data in the file.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::slack::slack;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	for slack in slack(file) {
+		let section = &file.section_headers().image()[slack.section];
+		if let Some(bytes) = slack.physical_bytes(file) {
+			if bytes.iter().any(|&byte| byte != 0) {
+				println!("{:?} hides data at file offset {:#x}", section.Name, slack.physical_slack.start);
+			}
+		}
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::cmp;
+use std::ops::Range;
+
+use crate::util::Le;
+use crate::Align;
+
+use super::super::{Pe, Rva};
+use super::footprint::PAGE_SIZE;
+
+/// Slack of a section.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct SectionSlack {
+	/// Index of the section in the section headers.
+	pub section: usize,
+	/// File range of the raw data past the virtual size of the section.
+	pub physical_slack: Range<u32>,
+	/// Address range past the virtual size of the section up to the section alignment.
+	pub virtual_slack: Range<Rva>,
+}
+impl SectionSlack {
+	/// Returns the number of bytes of physical slack.
+	pub fn physical_len(&self) -> u32 {
+		self.physical_slack.end - self.physical_slack.start
+	}
+	/// Returns the number of bytes of virtual slack.
+	pub fn virtual_len(&self) -> u32 {
+		self.virtual_slack.end - self.virtual_slack.start
+	}
+	/// Returns the bytes of the physical slack.
+	///
+	/// Returns `None` if the image is mapped in memory or the raw data is out of bounds.
+	pub fn physical_bytes<'a, P: Pe<'a>>(&self, pe: P) -> Option<&'a [u8]> {
+		if pe.align() != Align::File {
+			return None;
+		}
+		pe.image().get(self.physical_slack.start as usize..self.physical_slack.end as usize)
+	}
+}
+
+/// Computes the physical and virtual slack of every section in order of the section headers.
+///
+/// Sections with a zero virtual size are mapped with the size of their raw data and have no physical slack.
+/// Section alignments which aren't a power of two are replaced by the page size.
+pub fn slack<'a, P: Pe<'a>>(pe: P) -> Vec<SectionSlack> {
+	let alignment = pe.optional_header().SectionAlignment.le();
+	let alignment = if alignment.is_power_of_two() { alignment as u64 } else { PAGE_SIZE as u64 };
+
+	pe.section_headers().image().iter().enumerate().map(|(section, header)| {
+		let header = header.le();
+		let virtual_size = if header.VirtualSize != 0 { header.VirtualSize } else { header.SizeOfRawData };
+
+		let physical_start = header.PointerToRawData.saturating_add(cmp::min(virtual_size, header.SizeOfRawData));
+		let physical_end = header.PointerToRawData.saturating_add(header.SizeOfRawData);
+
+		let virtual_start = header.VirtualAddress.saturating_add(virtual_size);
+		let aligned_size = (virtual_size as u64 + (alignment - 1)) & !(alignment - 1);
+		let virtual_end = cmp::min(header.VirtualAddress as u64 + aligned_size, u32::MAX as u64) as u32;
+
+		SectionSlack {
+			section,
+			physical_slack: physical_start..physical_end,
+			virtual_slack: virtual_start..cmp::max(virtual_start, virtual_end),
+		}
+	}).collect()
+}
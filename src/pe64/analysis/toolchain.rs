@@ -0,0 +1,242 @@
+/*!
+Compiler and linker identification.
+
+Combines the Rich header, the linker version, the section names, the imported dlls and marker strings in the image contents into a guess of the toolchain which produced the image.
+Every piece of evidence votes for a compiler, the compiler with the most votes wins.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::analysis::toolchain::detect_toolchain;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let toolchain = detect_toolchain(file);
+
+	if let Some(compiler) = toolchain.compiler {
+		println!("compiled with {} ({})", compiler, toolchain.version.unwrap_or("unknown version"));
+	}
+	for (compiler, evidence) in &toolchain.evidence {
+		println!("  {}: {:?}", compiler, evidence);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, fmt};
+
+use crate::util::{CStr, Le};
+
+use super::super::Pe;
+use super::contains;
+
+//----------------------------------------------------------------
+
+/// Compiler toolchains.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Compiler {
+	/// Microsoft Visual C++.
+	Msvc,
+	/// GCC from the MinGW project.
+	MinGW,
+	/// The Rust compiler.
+	Rust,
+	/// The Go compiler.
+	Go,
+	/// Borland and Embarcadero Delphi.
+	Delphi,
+}
+impl Compiler {
+	const ALL: [Compiler; 5] = [Compiler::Msvc, Compiler::MinGW, Compiler::Rust, Compiler::Go, Compiler::Delphi];
+}
+impl fmt::Display for Compiler {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Compiler::Msvc => "MSVC",
+			Compiler::MinGW => "MinGW",
+			Compiler::Rust => "Rust",
+			Compiler::Go => "Go",
+			Compiler::Delphi => "Delphi",
+		})
+	}
+}
+
+/// Evidence for a compiler.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum Evidence<'a> {
+	/// The Rich header, only emitted by the Microsoft linker.
+	RichHeader,
+	/// The linker version from the optional header.
+	LinkerVersion(u8, u8),
+	/// A section name characteristic of the toolchain.
+	SectionName(&'a str),
+	/// An imported dll characteristic of the toolchain.
+	Import(&'a CStr),
+	/// A marker string found in the image contents.
+	Marker(&'static str),
+}
+impl<'a> Evidence<'a> {
+	/// Returns the weight of the vote.
+	pub fn weight(&self) -> u32 {
+		match self {
+			Evidence::RichHeader => 2,
+			Evidence::LinkerVersion(..) => 1,
+			Evidence::SectionName(_) => 2,
+			Evidence::Import(_) => 2,
+			Evidence::Marker(_) => 4,
+		}
+	}
+}
+
+/// Toolchain identification result.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Toolchain<'a> {
+	/// The probable compiler, `None` if no evidence was found.
+	pub compiler: Option<Compiler>,
+	/// The Visual Studio release derived from the linker version, only for MSVC.
+	pub version: Option<&'static str>,
+	/// All the evidence found and the compiler it votes for.
+	pub evidence: Vec<(Compiler, Evidence<'a>)>,
+}
+impl<'a> Toolchain<'a> {
+	/// Returns the total weight of the votes for the compiler.
+	pub fn score(&self, compiler: Compiler) -> u32 {
+		self.evidence.iter().filter(|&&(c, _)| c == compiler).map(|(_, evidence)| evidence.weight()).sum()
+	}
+}
+
+//----------------------------------------------------------------
+
+// Section names which are characteristic of a toolchain
+const SECTION_NAMES: [(&str, Compiler); 7] = [
+	(".CRT", Compiler::MinGW),
+	(".eh_fram", Compiler::MinGW),
+	(".symtab", Compiler::Go),
+	(".itext", Compiler::Delphi),
+	("CODE", Compiler::Delphi),
+	("DATA", Compiler::Delphi),
+	("BSS", Compiler::Delphi),
+];
+
+// Imported dlls which are characteristic of a toolchain, compared case insensitively
+const IMPORTS: [(&str, Compiler); 9] = [
+	("libgcc_s_dw2-1.dll", Compiler::MinGW),
+	("libgcc_s_seh-1.dll", Compiler::MinGW),
+	("libstdc++-6.dll", Compiler::MinGW),
+	("libwinpthread-1.dll", Compiler::MinGW),
+	("vcruntime140.dll", Compiler::Msvc),
+	("msvcp140.dll", Compiler::Msvc),
+	("msvcr120.dll", Compiler::Msvc),
+	("msvcr100.dll", Compiler::Msvc),
+	("borlndmm.dll", Compiler::Delphi),
+];
+
+// Strings embedded by the runtime libraries
+const MARKERS: [(&str, Compiler); 5] = [
+	("/rustc/", Compiler::Rust),
+	("\u{ff} Go buildinf:", Compiler::Go),
+	(" Go build ID: \"", Compiler::Go),
+	("Mingw-w64 runtime failure:", Compiler::MinGW),
+	("SOFTWARE\\Borland\\Delphi\\RTL", Compiler::Delphi),
+];
+
+/// Guesses the compiler toolchain which produced the image.
+///
+/// This is a heuristic, the evidence is recorded to allow reviewing the result.
+pub fn detect_toolchain<'a, P: Pe<'a>>(pe: P) -> Toolchain<'a> {
+	let mut evidence = Vec::new();
+
+	if pe.rich_structure().is_ok() {
+		evidence.push((Compiler::Msvc, Evidence::RichHeader));
+	}
+
+	let linker_version = pe.optional_header().LinkerVersion.le();
+	let (major, minor) = (linker_version.Major, linker_version.Minor);
+	let linker_compiler = match (major, minor) {
+		(2, 25) => Some(Compiler::Delphi),
+		(2, _) => Some(Compiler::MinGW),
+		(3, 0) => Some(Compiler::Go),
+		_ if msvc_version(major, minor).is_some() => Some(Compiler::Msvc),
+		_ => None,
+	};
+	if let Some(compiler) = linker_compiler {
+		evidence.push((compiler, Evidence::LinkerVersion(major, minor)));
+	}
+
+	for section in pe.section_headers() {
+		if let Ok(name) = section.name() {
+			if let Some(&(_, compiler)) = SECTION_NAMES.iter().find(|&&(known, _)| known == name) {
+				evidence.push((compiler, Evidence::SectionName(name)));
+			}
+			// Long section names are resolved through the COFF string table which only GNU ld emits
+			else if name.starts_with('/') && name.len() > 1 && name[1..].bytes().all(|byte| byte.is_ascii_digit()) {
+				evidence.push((Compiler::MinGW, Evidence::SectionName(name)));
+			}
+		}
+	}
+
+	if let Ok(imports) = pe.imports() {
+		for desc in imports {
+			if let Ok(dll_name) = desc.dll_name() {
+				if let Some(&(_, compiler)) = IMPORTS.iter().find(|&&(known, _)| dll_name.eq_ignore_ascii_case(known.as_bytes())) {
+					evidence.push((compiler, Evidence::Import(dll_name)));
+				}
+			}
+		}
+	}
+
+	// Only the initialized data of the sections can contain the markers
+	let mut markers_found = [false; MARKERS.len()];
+	for section in pe.section_headers() {
+		if let Ok(bytes) = pe.get_section_bytes(section) {
+			let bytes = &bytes[..cmp::min(bytes.len(), section.SizeOfRawData.le() as usize)];
+			for (found, &(marker, _)) in markers_found.iter_mut().zip(MARKERS.iter()) {
+				*found = *found || contains(bytes, marker.as_bytes());
+			}
+		}
+	}
+	for (_, &(marker, compiler)) in markers_found.iter().zip(MARKERS.iter()).filter(|&(&found, _)| found) {
+		evidence.push((compiler, Evidence::Marker(marker)));
+	}
+
+	let mut toolchain = Toolchain { compiler: None, version: None, evidence };
+	let mut best_score = 0;
+	for &compiler in &Compiler::ALL {
+		let score = toolchain.score(compiler);
+		if score > best_score {
+			best_score = score;
+			toolchain.compiler = Some(compiler);
+		}
+	}
+	if toolchain.compiler == Some(Compiler::Msvc) {
+		toolchain.version = msvc_version(major, minor);
+	}
+	toolchain
+}
+
+/// Maps the linker version to the Visual Studio release.
+fn msvc_version(major: u8, minor: u8) -> Option<&'static str> {
+	match (major, minor) {
+		(6, 0) => Some("Visual C++ 6.0"),
+		(7, 0) => Some("Visual Studio .NET 2002"),
+		(7, 10) => Some("Visual Studio .NET 2003"),
+		(8, 0) => Some("Visual Studio 2005"),
+		(9, 0) => Some("Visual Studio 2008"),
+		(10, 0) => Some("Visual Studio 2010"),
+		(11, 0) => Some("Visual Studio 2012"),
+		(12, 0) => Some("Visual Studio 2013"),
+		(14, 0) => Some("Visual Studio 2015"),
+		(14, 10...19) => Some("Visual Studio 2017"),
+		(14, 20...29) => Some("Visual Studio 2019"),
+		(14, 30...49) => Some("Visual Studio 2022"),
+		_ => None,
+	}
+}
@@ -0,0 +1,242 @@
+/*!
+Rva ownership.
+
+Tells what an rva belongs to by consulting the headers and the parsed directories: the header field, the section, the data directories and the specific import, export, runtime function or resource it falls in.
+Useful to triage the matches of a pattern scan.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let what_is = file.what_is(0x1010);
+	if let Some(section) = what_is.section {
+		println!("in {:?}", file.section_headers().image()[section].Name);
+	}
+	for item in &what_is.items {
+		println!("  {:?}", item);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, mem};
+
+use crate::util::{CStr, Le};
+
+use super::super::exports::RvaExport;
+use super::super::image::*;
+use super::super::imports::{import_from_va, Import};
+use super::super::Pe;
+
+/// Structure an rva belongs to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Item<'a> {
+	/// Field of the DOS, file or optional header, eg. `"OptionalHeader.AddressOfEntryPoint"`.
+	HeaderField(&'static str),
+	/// The DOS stub and Rich header between the DOS header and the NT headers.
+	DosStub,
+	/// Data directory entry with the given index in the optional header.
+	DataDirectoryEntry(usize),
+	/// Section header with the given index.
+	SectionHeader(usize),
+	/// Import descriptor of the DLL.
+	ImportDescriptor(&'a CStr),
+	/// Name of the DLL imported from.
+	ImportDllName(&'a CStr),
+	/// Import address table entry.
+	ImportAddress { dll_name: &'a CStr, import: Import<'a> },
+	/// Import name table entry.
+	ImportLookup { dll_name: &'a CStr, import: Import<'a> },
+	/// Hint and name of an import.
+	ImportName { dll_name: &'a CStr, import: Import<'a> },
+	/// Export address table entry of the export with the given ordinal.
+	ExportAddress(Ordinal),
+	/// Name table entry, name index or name of an export.
+	ExportName(&'a CStr),
+	/// The closest code or data export at or before the rva, see [`RvaOrder::owner`](../../exports/struct.RvaOrder.html#method.owner).
+	Export(RvaExport<'a>),
+	/// Runtime function with the given index in the exception directory.
+	RuntimeFunction(usize),
+	/// Data of the resource with the given path.
+	Resource(String),
+}
+
+/// What an rva belongs to.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhatIs<'a> {
+	/// The rva in question.
+	pub rva: Rva,
+	/// Index of the section containing the rva.
+	pub section: Option<usize>,
+	/// Indices of the data directories containing the rva.
+	pub directories: Vec<usize>,
+	/// Structures containing the rva, from the outermost to the innermost.
+	pub items: Vec<Item<'a>>,
+}
+
+/// Finds what the rva belongs to.
+///
+/// Directories which fail to parse are skipped.
+pub fn what_is<'a, P: Pe<'a>>(pe: P, rva: Rva) -> WhatIs<'a> {
+	let section = pe.section_headers().image().iter().position(|section| {
+		let section = section.le();
+		within(rva, section.VirtualAddress, cmp::max(section.VirtualSize, section.SizeOfRawData) as usize)
+	});
+	let directories = pe.data_directory().iter().enumerate()
+		.filter(|&(index, datadir)| {
+			let datadir = datadir.le();
+			index != IMAGE_DIRECTORY_ENTRY_SECURITY && datadir.VirtualAddress != 0 && within(rva, datadir.VirtualAddress, datadir.Size as usize)
+		})
+		.map(|(index, _)| index)
+		.collect();
+	let mut items = Vec::new();
+	if rva < pe.optional_header().SizeOfHeaders.le() {
+		items.extend(header_item(pe, rva));
+	}
+	imports(pe, rva, &mut items);
+	exports(pe, rva, &mut items);
+	if let Ok(exception) = pe.exception() {
+		if let Ok(index) = exception.index_of(rva) {
+			items.push(Item::RuntimeFunction(index));
+		}
+	}
+	#[cfg(any(feature = "std", feature = "resources_nostd"))]
+	{
+		if let Some(path) = pe.resources().ok().and_then(|resources| resources.data_path(rva)) {
+			items.push(Item::Resource(path));
+		}
+	}
+	WhatIs { rva, section, directories, items }
+}
+
+fn within(rva: Rva, start: Rva, size: usize) -> bool {
+	rva >= start && ((rva - start) as usize) < size
+}
+
+//----------------------------------------------------------------
+
+fn header_item<'a, P: Pe<'a>>(pe: P, rva: Rva) -> Option<Item<'a>> {
+	let image = pe.image();
+	let rva = rva as usize;
+	let contains = |ptr: *const u8, size: usize| {
+		let start = (ptr as usize).wrapping_sub(image.as_ptr() as usize);
+		rva >= start && rva - start < size
+	};
+	macro_rules! fields {
+		($header:expr, $prefix:literal, $($field:ident)*) => {
+			$(if contains(&$header.$field as *const _ as *const u8, mem::size_of_val(&$header.$field)) {
+				return Some(Item::HeaderField(concat!($prefix, stringify!($field))));
+			})*
+		};
+	}
+
+	let dos_header = pe.dos_header();
+	fields!(dos_header, "",
+		e_magic e_cblp e_cp e_crlc e_cparhdr e_minalloc e_maxalloc e_ss e_sp e_csum e_ip e_cs e_lfarlc e_ovno e_res e_oemid e_oeminfo e_res2 e_lfanew);
+	let nt_headers = pe.nt_headers();
+	if rva < (nt_headers as *const _ as usize).wrapping_sub(image.as_ptr() as usize) {
+		return Some(Item::DosStub);
+	}
+	fields!(nt_headers, "", Signature);
+	fields!(nt_headers.FileHeader, "FileHeader.",
+		Machine NumberOfSections TimeDateStamp PointerToSymbolTable NumberOfSymbols SizeOfOptionalHeader Characteristics);
+	fields!(nt_headers.OptionalHeader, "OptionalHeader.",
+		Magic LinkerVersion SizeOfCode SizeOfInitializedData SizeOfUninitializedData AddressOfEntryPoint BaseOfCode ImageBase
+		SectionAlignment FileAlignment OperatingSystemVersion ImageVersion SubsystemVersion Win32VersionValue SizeOfImage SizeOfHeaders CheckSum
+		Subsystem DllCharacteristics SizeOfStackReserve SizeOfStackCommit SizeOfHeapReserve SizeOfHeapCommit LoaderFlags NumberOfRvaAndSizes);
+	let data_directory = pe.data_directory();
+	if let Some(index) = (0..data_directory.len()).find(|&index| contains(&data_directory[index] as *const _ as *const u8, mem::size_of::<IMAGE_DATA_DIRECTORY>())) {
+		return Some(Item::DataDirectoryEntry(index));
+	}
+	let section_headers = pe.section_headers().image();
+	if let Some(index) = (0..section_headers.len()).find(|&index| contains(&section_headers[index] as *const _ as *const u8, mem::size_of::<IMAGE_SECTION_HEADER>())) {
+		return Some(Item::SectionHeader(index));
+	}
+	None
+}
+
+fn imports<'a, P: Pe<'a>>(pe: P, rva: Rva, items: &mut Vec<Item<'a>>) {
+	let imports = match pe.imports() {
+		Ok(imports) => imports,
+		Err(_) => return,
+	};
+	let datadir = pe.data_directory()[IMAGE_DIRECTORY_ENTRY_IMPORT].le();
+	for (index, desc) in imports.iter().enumerate() {
+		let dll_name = match desc.dll_name() {
+			Ok(dll_name) => dll_name,
+			Err(_) => continue,
+		};
+		let image = desc.image().le();
+		let desc_rva = datadir.VirtualAddress.wrapping_add((index * mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>()) as Rva);
+		if within(rva, desc_rva, mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>()) {
+			items.push(Item::ImportDescriptor(dll_name));
+		}
+		if within(rva, image.Name, dll_name.len() + 1) {
+			items.push(Item::ImportDllName(dll_name));
+		}
+		let iat = desc.iat().map(|iat| iat.as_slice()).unwrap_or(&[]);
+		let int = pe.derva_slice_s::<Va>(image.OriginalFirstThunk, 0).unwrap_or(iat);
+		// Without an import name table the unbound import address table names the imports
+		let import = |index: usize| int.get(index).and_then(|va| import_from_va(pe, va).ok());
+		if within(rva, image.FirstThunk, mem::size_of_val(iat)) {
+			if let Some(import) = import((rva - image.FirstThunk) as usize / mem::size_of::<Va>()) {
+				items.push(Item::ImportAddress { dll_name, import });
+			}
+		}
+		if image.OriginalFirstThunk != 0 && within(rva, image.OriginalFirstThunk, mem::size_of_val(int)) {
+			if let Some(import) = import((rva - image.OriginalFirstThunk) as usize / mem::size_of::<Va>()) {
+				items.push(Item::ImportLookup { dll_name, import });
+			}
+		}
+		for (&va, import) in int.iter().zip(0..).filter_map(|(va, index)| Some((va, import(index)?))) {
+			if let Import::ByName { name, .. } = import {
+				let va = va.le();
+				if va & IMAGE_ORDINAL_FLAG == 0 && within(rva, va as Rva, 2 + name.len() + 1) {
+					items.push(Item::ImportName { dll_name, import });
+				}
+			}
+		}
+	}
+}
+
+fn exports<'a, P: Pe<'a>>(pe: P, rva: Rva, items: &mut Vec<Item<'a>>) {
+	let exports = match pe.exports() {
+		Ok(exports) => exports,
+		Err(_) => return,
+	};
+	let by = match exports.by() {
+		Ok(by) => by,
+		Err(_) => return,
+	};
+	let image = exports.image().le();
+	if within(rva, image.AddressOfFunctions, mem::size_of_val(by.functions())) {
+		let index = (rva - image.AddressOfFunctions) as usize / mem::size_of::<Rva>();
+		items.push(Item::ExportAddress(by.ordinal_base().wrapping_add(index as Ordinal)));
+	}
+	for (hint, &name_rva) in by.names().iter().enumerate() {
+		let name_rva = name_rva.le();
+		let name = match pe.derva_c_str(name_rva) {
+			Ok(name) => name,
+			Err(_) => continue,
+		};
+		if within(rva, image.AddressOfNames.wrapping_add((hint * 4) as Rva), 4)
+			|| within(rva, image.AddressOfNameOrdinals.wrapping_add((hint * 2) as Rva), 2)
+			|| within(rva, name_rva, name.len() + 1)
+		{
+			items.push(Item::ExportName(name));
+		}
+	}
+	let datadir = pe.data_directory()[IMAGE_DIRECTORY_ENTRY_EXPORT].le();
+	if !within(rva, datadir.VirtualAddress, datadir.Size as usize) {
+		if let Some(export) = by.rva_order().owner(rva) {
+			items.push(Item::Export(*export));
+		}
+	}
+}
@@ -1,4 +1,5 @@
 use crate::base_relocs::BaseRelocs;
+use crate::util::Le;
 use crate::{Error, Result};
 
 use super::image::*;
@@ -6,8 +7,8 @@ use super::Pe;
 
 pub(crate) fn try_from<'a, P: Pe<'a>>(pe: P) -> Result<BaseRelocs<'a>> {
 	let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_BASERELOC).ok_or(Error::Bounds)?;
-	let relocs = pe.slice(datadir.VirtualAddress, datadir.Size as usize, 4)?; // $1
-	let relocs = unsafe { relocs.get_unchecked(..datadir.Size as usize) };
+	let relocs = pe.slice(datadir.VirtualAddress.le(), datadir.Size.le() as usize, 4)?; // $1
+	let relocs = unsafe { relocs.get_unchecked(..datadir.Size.le() as usize) };
 	Ok(unsafe { BaseRelocs::new(relocs) })
 }
 
@@ -25,10 +26,14 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 			.map(move |word| block.rva_of(word))
 	});
 
-	base_relocs.for_each(|rva, _| {
+	let mut iter = base_relocs.iter();
+	base_relocs.for_each(|rva, ty| {
 		assert_eq!(baseline.next(), Some(rva));
+		assert_eq!(iter.next(), Some((rva, ty.into())));
+		assert!(base_relocs.contains(rva));
 	});
 	assert_eq!(baseline.next(), None);
+	assert_eq!(iter.next(), None);
 
 	Ok(())
 }
@@ -0,0 +1,236 @@
+/*!
+Bound Import Directory.
+
+Binding resolves the imports ahead of time: the IAT in the file already contains the addresses of the imported symbols.
+The bound import directory records the timestamps of the modules the image was bound against.
+The loader only trusts the bound IAT if the timestamp of the loaded module matches, stale bindings are resolved again at load time.
+
+Modules which forward exports to other modules record the timestamps of those modules as forwarder references.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	// Access the bound import directory
+	let bound_imports = file.bound_imports()?;
+
+	// Iterate over the modules the image was bound against
+	for desc in bound_imports.iter() {
+		println!("{} bound at {:#x}", desc.dll_name()?, desc.time_date_stamp());
+	}
+
+	// Compare against the timestamps of the modules which will be loaded
+	let stale = bound_imports.stale(|dll_name| if dll_name == "KERNEL32.dll" { Some(0x5e8c_5c1a) } else { None });
+	for binding in stale {
+		println!("{} is stale", binding.dll_name);
+	}
+
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, fmt, mem, slice};
+
+use crate::util::{AlignTo, CStr, Le};
+use crate::{Error, Result};
+
+use super::image::*;
+use super::pe::c_str_bounded;
+use super::Pe;
+
+//----------------------------------------------------------------
+
+/// Bound Import Directory.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct BoundImports<'a, P> {
+	pe: P,
+	datadir: IMAGE_DATA_DIRECTORY,
+	bytes: &'a [u8],
+	image: &'a [IMAGE_BOUND_IMPORT_DESCRIPTOR],
+}
+impl<'a, P: Pe<'a>> BoundImports<'a, P> {
+	pub(crate) fn try_from(pe: P) -> Result<BoundImports<'a, P>> {
+		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT).ok_or(Error::Bounds)?.le();
+		// The directory is usually stored in the headers which aren't part of any section
+		// Rvas in the headers are equal to their file offset regardless of alignment
+		let size_of_headers = pe.optional_header().SizeOfHeaders.le();
+		let bytes = if datadir.VirtualAddress != 0 && datadir.VirtualAddress < size_of_headers {
+			let end = cmp::min(size_of_headers as usize, pe.image().len());
+			pe.image().get(datadir.VirtualAddress as usize..end).ok_or(Error::Bounds)?
+		}
+		else {
+			pe.slice_bytes(datadir.VirtualAddress)?
+		};
+		if !cfg!(feature = "unsafe_alignment") && !bytes.as_ptr().aligned_to(4) {
+			return Err(Error::Misaligned);
+		}
+		// Forwarder references have the same size as the descriptors, the whole directory is an array
+		let len = datadir.Size as usize / mem::size_of::<IMAGE_BOUND_IMPORT_DESCRIPTOR>();
+		if len * mem::size_of::<IMAGE_BOUND_IMPORT_DESCRIPTOR>() > bytes.len() {
+			return Err(Error::Bounds);
+		}
+		// Size and alignment are checked above and the descriptors are Pod
+		let image = unsafe { slice::from_raw_parts(bytes.as_ptr() as *const IMAGE_BOUND_IMPORT_DESCRIPTOR, len) };
+		Ok(BoundImports { pe, datadir, bytes, image })
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying bound import directory image array.
+	///
+	/// Contains the descriptors interleaved with their forwarder references.
+	pub fn image(&self) -> &'a [IMAGE_BOUND_IMPORT_DESCRIPTOR] {
+		self.image
+	}
+	/// Iterator over the bound import descriptors.
+	pub fn iter(&self) -> Iter<'a, P> {
+		Iter { bound_imports: *self, image: self.image }
+	}
+	/// Finds the bindings whose timestamp does not match the actual module.
+	///
+	/// The callback is given the name of every bound module and forwarder reference and returns its actual timestamp.
+	/// Modules for which the callback returns `None` are not checked.
+	pub fn stale<F: FnMut(&CStr) -> Option<u32>>(&self, mut timestamps: F) -> Vec<StaleBinding<'a>> {
+		let mut stale = Vec::new();
+		for desc in self.iter() {
+			let dll_name = match desc.dll_name() {
+				Ok(dll_name) => dll_name,
+				Err(_) => continue,
+			};
+			let mut check = |dll_name: &'a CStr, bound: u32, forwarder: Option<&'a CStr>| {
+				if let Some(actual) = timestamps(dll_name) {
+					if actual != bound {
+						stale.push(StaleBinding { dll_name, bound, actual, forwarder });
+					}
+				}
+			};
+			check(dll_name, desc.time_date_stamp(), None);
+			for fwd in desc.forwarder_refs() {
+				if let Ok(name) = self.name(fwd.OffsetModuleName.le()) {
+					check(name, fwd.TimeDateStamp.le(), Some(dll_name));
+				}
+			}
+		}
+		stale
+	}
+	/// Returns the bytes of the directory as sized by its data directory.
+	pub(crate) fn dir_bytes(&self) -> &'a [u8] {
+		&self.bytes[..cmp::min(self.datadir.Size as usize, self.bytes.len())]
+	}
+	fn name(&self, offset: u16) -> Result<&'a CStr> {
+		let bytes = self.bytes.get(offset as usize..).ok_or(Error::Bounds)?;
+		c_str_bounded(bytes, self.pe.options().max_string_len)
+	}
+}
+impl<'a, P: Pe<'a>> IntoIterator for BoundImports<'a, P> {
+	type Item = Desc<'a, P>;
+	type IntoIter = Iter<'a, P>;
+	fn into_iter(self) -> Iter<'a, P> {
+		self.iter()
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for BoundImports<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list()
+			.entries(self.iter())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Iterator over the bound import descriptors.
+///
+/// Stops at the null terminator or when a descriptor has more forwarder references than there are entries left.
+#[derive(Clone)]
+pub struct Iter<'a, P> {
+	bound_imports: BoundImports<'a, P>,
+	image: &'a [IMAGE_BOUND_IMPORT_DESCRIPTOR],
+}
+impl<'a, P: Pe<'a>> Iterator for Iter<'a, P> {
+	type Item = Desc<'a, P>;
+	fn next(&mut self) -> Option<Desc<'a, P>> {
+		let (image, rest) = self.image.split_first().filter(|(image, _)| !image.is_null())?;
+		let len = image.NumberOfModuleForwarderRefs.le() as usize;
+		if len > rest.len() {
+			self.image = &[];
+			return None;
+		}
+		let (forwarder_refs, rest) = rest.split_at(len);
+		self.image = rest;
+		// Same size and alignment, both are Pod
+		let forwarder_refs = unsafe { &*(forwarder_refs as *const [IMAGE_BOUND_IMPORT_DESCRIPTOR] as *const [IMAGE_BOUND_FORWARDER_REF]) };
+		Some(Desc { bound_imports: self.bound_imports, image, forwarder_refs })
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Bound import descriptor.
+#[derive(Copy, Clone)]
+pub struct Desc<'a, P> {
+	bound_imports: BoundImports<'a, P>,
+	image: &'a IMAGE_BOUND_IMPORT_DESCRIPTOR,
+	forwarder_refs: &'a [IMAGE_BOUND_FORWARDER_REF],
+}
+impl<'a, P: Pe<'a>> Desc<'a, P> {
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.bound_imports.pe
+	}
+	/// Returns the underlying bound import descriptor image.
+	pub fn image(&self) -> &'a IMAGE_BOUND_IMPORT_DESCRIPTOR {
+		self.image
+	}
+	/// Gets the name of the bound module.
+	pub fn dll_name(&self) -> Result<&'a CStr> {
+		self.bound_imports.name(self.image.OffsetModuleName.le())
+	}
+	/// Gets the timestamp of the module the image was bound against.
+	pub fn time_date_stamp(&self) -> u32 {
+		self.image.TimeDateStamp.le()
+	}
+	/// Gets the forwarder references.
+	pub fn forwarder_refs(&self) -> &'a [IMAGE_BOUND_FORWARDER_REF] {
+		self.forwarder_refs
+	}
+	/// Iterates over the names and timestamps of the forwarder references.
+	pub fn forwarders<'s>(&'s self) -> impl 's + Clone + Iterator<Item = (Result<&'a CStr>, u32)> {
+		self.forwarder_refs.iter().map(move |fwd| (self.bound_imports.name(fwd.OffsetModuleName.le()), fwd.TimeDateStamp.le()))
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for Desc<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Desc")
+			.field("dll_name", &format_args!("{:?}", self.dll_name()))
+			.field("time_date_stamp", &self.time_date_stamp())
+			.field("forwarder_refs.len", &self.forwarder_refs.len())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Binding whose timestamp does not match the actual module.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StaleBinding<'a> {
+	/// Name of the bound module.
+	pub dll_name: &'a CStr,
+	/// Timestamp recorded when the image was bound.
+	pub bound: u32,
+	/// Timestamp of the actual module.
+	pub actual: u32,
+	/// Name of the bound module forwarding to this module if this is a forwarder reference.
+	pub forwarder: Option<&'a CStr>,
+}
@@ -0,0 +1,312 @@
+/*!
+Owned PE buffers.
+
+The [`PeFile`](../struct.PeFile.html) and [`PeView`](../struct.PeView.html) types borrow their image from somewhere else.
+The buffer types here own their image so they can be freely returned from functions or stored in collections.
+
+References to the buffers implement [`Pe`](../trait.Pe.html) directly.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFileBuf};
+
+# #[allow(dead_code)]
+fn load(bytes: Vec<u8>) -> pelite::Result<PeFileBuf> {
+	let buf = PeFileBuf::from_vec(bytes)?;
+
+	// Access the file through the Pe trait
+	let exports = (&buf).exports()?;
+
+	// Or borrow it as a regular PeFile
+	let file = buf.as_file();
+
+	Ok(buf)
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, mem};
+
+use crate::util::Le;
+use crate::{Error, ParseOptions, Result, Violations};
+
+use super::image::*;
+use super::pe::validate_headers;
+use super::{Align, Pe, PeFile, PeObject, PeView};
+
+//----------------------------------------------------------------
+
+/// Owned unmapped PE file.
+#[derive(Clone)]
+pub struct PeFileBuf {
+	image: Vec<u8>,
+}
+
+impl PeFileBuf {
+	/// Takes ownership of the file contents.
+	///
+	/// # Errors
+	///
+	/// See [`PeFile::from_bytes`](../struct.PeFile.html#method.from_bytes) for a list of errors.
+	pub fn from_vec(image: Vec<u8>) -> Result<PeFileBuf> {
+		let _ = validate_headers(&image, &ParseOptions::DEFAULT)?;
+		Ok(PeFileBuf { image })
+	}
+	/// Borrows the buffer as a file view.
+	#[inline]
+	pub fn as_file(&self) -> PeFile<'_> {
+		self.into()
+	}
+	/// Converts the file to section alignment.
+	pub fn to_view(&self) -> Result<PeViewBuf> {
+		PeViewBuf::from_vec(self.as_file().to_view())
+	}
+	/// Removes the import bindings.
+	///
+	/// Restores the IAT of bound import descriptors from their import name table and clears the bound import directory.
+	/// Descriptors without an import name table cannot be restored and are left as is.
+	pub fn unbind_imports(&mut self) -> Result<()> {
+		let edits = unbind_imports(self.as_file())?;
+		edits.apply(&mut self.image)
+	}
+	/// Sets a data directory entry.
+	///
+	/// If the entry is past the end of the data directory, the data directory is grown and the section headers are moved to make room.
+	/// This requires free space, that is zero bytes, between the end of the section headers and the end of the headers.
+	///
+	/// The directory itself is not validated, it is up to the caller to place it in the image.
+	///
+	/// # Errors
+	///
+	/// * [`Bounds`](../../enum.Error.html#variant.Bounds):
+	///   The index is out of range or there is not enough free space in the headers to grow the data directory.
+	///
+	/// If the edited headers no longer validate the image is left unchanged and the validation error is returned.
+	pub fn set_data_directory(&mut self, index: usize, datadir: IMAGE_DATA_DIRECTORY) -> Result<()> {
+		let edits = set_data_directory(self.as_file(), index, datadir)?;
+		edits.apply(&mut self.image)
+	}
+	/// Returns the underlying image.
+	#[inline]
+	pub fn into_vec(self) -> Vec<u8> {
+		self.image
+	}
+}
+
+impl<'a> From<&'a PeFileBuf> for PeFile<'a> {
+	#[inline]
+	fn from(buf: &'a PeFileBuf) -> PeFile<'a> {
+		// The headers were validated when the buffer was constructed and are kept valid by its edits
+		unsafe { PeFile::from_validated(&buf.image, ParseOptions::DEFAULT, Violations::empty()) }
+	}
+}
+
+unsafe impl<'a> Pe<'a> for &'a PeFileBuf {}
+
+unsafe impl<'a> PeObject<'a> for &'a PeFileBuf {
+	fn image(&self) -> &'a [u8] {
+		&self.image
+	}
+	fn align(&self) -> Align {
+		Align::File
+	}
+
+	fn image_base(&self) -> super::Va {
+		self.optional_header().ImageBase.le()
+	}
+
+	#[cfg(feature = "serde")]
+	fn serde_name(&self) -> &'static str {
+		"PeFile"
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Owned mapped PE image.
+#[derive(Clone)]
+pub struct PeViewBuf {
+	image: Vec<u8>,
+}
+
+impl PeViewBuf {
+	/// Takes ownership of the image contents.
+	///
+	/// # Errors
+	///
+	/// See [`PeView::from_bytes`](../struct.PeView.html#method.from_bytes) for a list of errors.
+	pub fn from_vec(image: Vec<u8>) -> Result<PeViewBuf> {
+		let _ = validate_headers(&image, &ParseOptions::DEFAULT)?;
+		Ok(PeViewBuf { image })
+	}
+	/// Borrows the buffer as an image view.
+	#[inline]
+	pub fn as_view(&self) -> PeView<'_> {
+		self.into()
+	}
+	/// Removes the import bindings.
+	///
+	/// See [`PeFileBuf::unbind_imports`](struct.PeFileBuf.html#method.unbind_imports) for more information.
+	pub fn unbind_imports(&mut self) -> Result<()> {
+		let edits = unbind_imports(self.as_view())?;
+		edits.apply(&mut self.image)
+	}
+	/// Sets a data directory entry.
+	///
+	/// See [`PeFileBuf::set_data_directory`](struct.PeFileBuf.html#method.set_data_directory) for more information.
+	pub fn set_data_directory(&mut self, index: usize, datadir: IMAGE_DATA_DIRECTORY) -> Result<()> {
+		let edits = set_data_directory(self.as_view(), index, datadir)?;
+		edits.apply(&mut self.image)
+	}
+	/// Returns the underlying image.
+	#[inline]
+	pub fn into_vec(self) -> Vec<u8> {
+		self.image
+	}
+}
+
+impl<'a> From<&'a PeViewBuf> for PeView<'a> {
+	#[inline]
+	fn from(buf: &'a PeViewBuf) -> PeView<'a> {
+		// The headers were validated when the buffer was constructed and are kept valid by its edits
+		unsafe { PeView::from_validated(&buf.image, ParseOptions::DEFAULT, Violations::empty()) }
+	}
+}
+
+unsafe impl<'a> Pe<'a> for &'a PeViewBuf {}
+
+unsafe impl<'a> PeObject<'a> for &'a PeViewBuf {
+	fn image(&self) -> &'a [u8] {
+		&self.image
+	}
+	fn align(&self) -> Align {
+		Align::Section
+	}
+
+	fn image_base(&self) -> super::Va {
+		self.optional_header().ImageBase.le()
+	}
+
+	#[cfg(feature = "serde")]
+	fn serde_name(&self) -> &'static str {
+		"PeView"
+	}
+}
+
+//----------------------------------------------------------------
+
+// Edits are collected while the image is borrowed and applied afterwards
+#[derive(Default)]
+struct Edits {
+	copies: Vec<(usize, usize, usize)>,
+	zeros: Vec<(usize, usize)>,
+	writes: Vec<(usize, Vec<u8>)>,
+}
+impl Edits {
+	// The buffers hand out views without validating their headers again
+	// Edits which leave the headers invalid are undone and fail with the validation error
+	fn apply(&self, image: &mut [u8]) -> Result<()> {
+		let ranges = self.copies.iter().map(|&(_, dest, len)| (dest, len))
+			.chain(self.zeros.iter().copied())
+			.chain(self.writes.iter().map(|(offset, bytes)| (*offset, bytes.len())));
+		let saved: Vec<_> = ranges.map(|(offset, len)| (offset, image[offset..offset + len].to_vec())).collect();
+
+		for &(src, dest, len) in &self.copies {
+			image.copy_within(src..src + len, dest);
+		}
+		for &(offset, len) in &self.zeros {
+			for byte in &mut image[offset..offset + len] {
+				*byte = 0;
+			}
+		}
+		for (offset, bytes) in &self.writes {
+			image[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+		}
+
+		if let Err(err) = validate_headers(image, &ParseOptions::DEFAULT) {
+			for (offset, bytes) in saved.iter().rev() {
+				image[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+			}
+			return Err(err);
+		}
+		Ok(())
+	}
+}
+
+fn unbind_imports<'a, P: Pe<'a>>(pe: P) -> Result<Edits> {
+	let base = pe.image().as_ptr() as usize;
+	let offset = |ptr: *const u8| ptr as usize - base;
+	let mut edits = Edits::default();
+
+	match pe.imports() {
+		Ok(imports) => {
+			for desc in imports {
+				let image = desc.image();
+				if image.TimeDateStamp == 0 || image.OriginalFirstThunk == 0 {
+					continue;
+				}
+				let int = pe.derva_slice_f::<Va, _>(image.OriginalFirstThunk.le(), |&va| va == 0)?;
+				let iat = pe.derva_slice::<Va>(image.FirstThunk.le(), int.len())?;
+				edits.copies.push((offset(int.as_ptr() as *const u8), offset(iat.as_ptr() as *const u8), mem::size_of_val(int)));
+				// Clears the TimeDateStamp and ForwarderChain fields
+				edits.zeros.push((offset(&image.TimeDateStamp as *const u32 as *const u8), 8));
+			}
+		},
+		Err(Error::Null) => (),
+		Err(err) => return Err(err),
+	}
+
+	if let Some(datadir) = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT) {
+		if let Ok(bound_imports) = pe.bound_imports() {
+			let bytes = bound_imports.dir_bytes();
+			edits.zeros.push((offset(bytes.as_ptr()), bytes.len()));
+		}
+		edits.zeros.push((offset(datadir as *const IMAGE_DATA_DIRECTORY as *const u8), mem::size_of::<IMAGE_DATA_DIRECTORY>()));
+	}
+	Ok(edits)
+}
+
+fn set_data_directory<'a, P: Pe<'a>>(pe: P, index: usize, datadir: IMAGE_DATA_DIRECTORY) -> Result<Edits> {
+	if index >= IMAGE_NUMBEROF_DIRECTORY_ENTRIES {
+		return Err(Error::Bounds);
+	}
+	let base = pe.image().as_ptr() as usize;
+	let offset = |ptr: *const u8| ptr as usize - base;
+	let mut edits = Edits::default();
+
+	let file_header = pe.file_header();
+	let optional_header = pe.optional_header();
+	let data_directory = pe.data_directory();
+	let len = data_directory.len();
+	if index >= len {
+		let new_len = index + 1;
+		let size_of_optional_header = mem::size_of::<IMAGE_OPTIONAL_HEADER>() + new_len * mem::size_of::<IMAGE_DATA_DIRECTORY>();
+		if size_of_optional_header > file_header.SizeOfOptionalHeader.le() as usize {
+			// Move the section headers into the free space following them
+			let shift = size_of_optional_header - file_header.SizeOfOptionalHeader.le() as usize;
+			let section_headers = pe.section_headers().as_slice();
+			let sections = offset(section_headers.as_ptr() as *const u8);
+			let sections_len = mem::size_of_val(section_headers);
+			let headers_end = cmp::min(optional_header.SizeOfHeaders.le() as usize, pe.image().len());
+			let free = pe.image().get(sections + sections_len..sections + sections_len + shift).ok_or(Error::Bounds)?;
+			if sections + sections_len + shift > headers_end || free.iter().any(|&byte| byte != 0) {
+				return Err(Error::Bounds);
+			}
+			edits.copies.push((sections, sections + shift, sections_len));
+			edits.writes.push((offset(&file_header.SizeOfOptionalHeader as *const u16 as *const u8), (size_of_optional_header as u16).to_le_bytes().to_vec()));
+		}
+		// Clear the new entries, they may overlap the old section headers
+		let start = offset(data_directory.as_ptr() as *const u8) + mem::size_of_val(data_directory);
+		edits.zeros.push((start, (new_len - len) * mem::size_of::<IMAGE_DATA_DIRECTORY>()));
+		edits.writes.push((offset(&optional_header.NumberOfRvaAndSizes as *const u32 as *const u8), (new_len as u32).to_le_bytes().to_vec()));
+	}
+
+	let mut bytes = datadir.VirtualAddress.to_le_bytes().to_vec();
+	bytes.extend_from_slice(&datadir.Size.to_le_bytes());
+	let entry = offset(optional_header.DataDirectory.as_ptr() as *const u8) + index * mem::size_of::<IMAGE_DATA_DIRECTORY>();
+	edits.writes.push((entry, bytes));
+	Ok(edits)
+}
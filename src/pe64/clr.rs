@@ -0,0 +1,291 @@
+/*!
+CLR header and ReadyToRun.
+
+Managed assemblies point their COM descriptor data directory at the CLR header which describes the metadata, managed resources and strong name signature.
+Assemblies compiled ahead of time with ReadyToRun (crossgen) additionally point the CLR header's `ManagedNativeHeader` at the ReadyToRun header.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::image::READYTORUN_SECTION_RUNTIME_FUNCTIONS;
+use pelite::util::Le;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	// Access the CLR header
+	let clr = file.clr()?;
+	println!("runtime v{}.{}", clr.image().MajorRuntimeVersion.le(), clr.image().MinorRuntimeVersion.le());
+
+	// Access the ReadyToRun header of precompiled assemblies
+	if let Ok(r2r) = clr.ready_to_run() {
+		println!("compiled by {}", r2r.compiler_identifier()?);
+		let runtime_functions = r2r.section(READYTORUN_SECTION_RUNTIME_FUNCTIONS)?;
+		for section in r2r.import_sections()? {
+			println!("import section at {:#x}", section.Section.VirtualAddress.le());
+		}
+	}
+
+	Ok(())
+}
+```
+*/
+
+use std::{cmp, fmt, mem};
+
+use crate::util::{CStr, Le};
+use crate::{Error, Result};
+
+use super::image::*;
+use super::{Align, Pe};
+
+//----------------------------------------------------------------
+
+/// CLR header.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct Clr<'a, P> {
+	pe: P,
+	image: &'a IMAGE_COR20_HEADER,
+}
+impl<'a, P: Pe<'a>> Clr<'a, P> {
+	pub(crate) fn try_from(pe: P) -> Result<Clr<'a, P>> {
+		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR).ok_or(Error::Bounds)?;
+		let image: &IMAGE_COR20_HEADER = pe.derva(datadir.VirtualAddress.le())?;
+		if (image.cb.le() as usize) < mem::size_of::<IMAGE_COR20_HEADER>() {
+			return Err(Error::Invalid);
+		}
+		Ok(Clr { pe, image })
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying CLR header image.
+	pub fn image(&self) -> &'a IMAGE_COR20_HEADER {
+		self.image
+	}
+	/// Gets the `COMIMAGE_FLAGS_*` flags.
+	pub fn flags(&self) -> u32 {
+		self.image.Flags.le()
+	}
+	/// Returns true if the image contains only IL code.
+	pub fn is_il_only(&self) -> bool {
+		self.flags() & COMIMAGE_FLAGS_ILONLY != 0
+	}
+	/// Gets the metadata root.
+	pub fn metadata(&self) -> Result<&'a [u8]> {
+		self.directory(&self.image.MetaData)
+	}
+	/// Gets the managed resources.
+	pub fn resources(&self) -> Result<&'a [u8]> {
+		self.directory(&self.image.Resources)
+	}
+	/// Gets the data of an embedded manifest resource.
+	///
+	/// The offset is relative to the managed resources, see the ManifestResource table in the [metadata](../../metadata/index.html).
+	/// The resources are stored with their length in a `u32` prefix.
+	pub fn manifest_resource_data(&self, offset: u32) -> Result<&'a [u8]> {
+		let resources = self.resources()?;
+		let offset = offset as usize;
+		let len = resources.get(offset..offset.wrapping_add(4)).ok_or(Error::Bounds)?;
+		let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+		resources.get(offset + 4..(offset + 4).saturating_add(len)).ok_or(Error::Bounds)
+	}
+	/// Gets the strong name signature.
+	pub fn strong_name_signature(&self) -> Result<&'a [u8]> {
+		self.directory(&self.image.StrongNameSignature)
+	}
+	/// Returns true if the assembly is strong name signed.
+	pub fn is_strong_name_signed(&self) -> bool {
+		self.flags() & COMIMAGE_FLAGS_STRONGNAMESIGNED != 0
+	}
+	/// Feeds the bytes covered by the strong name signature to the given hash function.
+	///
+	/// The strong name digest covers the headers and the raw data of the sections in the order of the section headers,
+	/// skipping the `CheckSum` field, the certificate table data directory entry and the strong name signature blob itself.
+	/// The Authenticode signature is not part of any section and thus never hashed.
+	///
+	/// Hash the bytes with the algorithm of the assembly, usually SHA-1, and verify the [signature](#method.strong_name_signature) against the assembly's public key.
+	/// The signature is stored in little endian byte order, most RSA implementations expect it reversed.
+	///
+	/// Returns [`Err(Unmapped)`](../../enum.Error.html#variant.Unmapped) if the image is not file aligned.
+	pub fn strong_name_digest<F: FnMut(&'a [u8])>(&self, mut update: F) -> Result<()> {
+		if self.pe.align() != Align::File {
+			return Err(Error::Unmapped);
+		}
+		let image = self.pe.image();
+		let base = image.as_ptr() as usize;
+		// CheckSum is at the same offset in the 32 and 64 bit optional headers
+		let checksum = self.pe.optional_header() as *const _ as usize - base + 64;
+		let data_directory = self.pe.data_directory();
+		let security = match data_directory.get(IMAGE_DIRECTORY_ENTRY_SECURITY) {
+			Some(datadir) => (datadir as *const _ as usize - base, 8),
+			None => (0, 0),
+		};
+		let signature = self.image.StrongNameSignature.le();
+		let signature = match signature.VirtualAddress {
+			0 => (0, 0),
+			rva => (self.pe.rva_to_file_offset(rva)?, signature.Size as usize),
+		};
+		let mut excluded = [(checksum, 4), security, signature];
+		excluded.sort_unstable();
+
+		let mut hash = |start: usize, len: usize| -> Result<()> {
+			let end = start.checked_add(len).ok_or(Error::Overflow)?;
+			if end > image.len() {
+				return Err(Error::Bounds);
+			}
+			let mut pos = start;
+			for &(skip_start, skip_len) in &excluded {
+				let skip_end = skip_start.saturating_add(skip_len);
+				if skip_len == 0 || skip_end <= pos || skip_start >= end {
+					continue;
+				}
+				if skip_start > pos {
+					update(&image[pos..skip_start]);
+				}
+				pos = cmp::max(pos, skip_end);
+			}
+			if pos < end {
+				update(&image[pos..end]);
+			}
+			Ok(())
+		};
+		hash(0, self.pe.optional_header().SizeOfHeaders.le() as usize)?;
+		for section in self.pe.section_headers() {
+			let section = section.le();
+			if section.SizeOfRawData != 0 {
+				hash(section.PointerToRawData as usize, section.SizeOfRawData as usize)?;
+			}
+		}
+		Ok(())
+	}
+	/// Gets the managed native header.
+	pub fn managed_native_header(&self) -> Result<&'a [u8]> {
+		self.directory(&self.image.ManagedNativeHeader)
+	}
+	/// Gets the ReadyToRun header.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the image is not precompiled
+	/// and [`Err(BadMagic)`](../../enum.Error.html#variant.BadMagic) if the managed native header is not a ReadyToRun header.
+	pub fn ready_to_run(&self) -> Result<ReadyToRun<'a, P>> {
+		ReadyToRun::try_from(self.pe, self.image.ManagedNativeHeader.VirtualAddress.le())
+	}
+	fn directory(&self, datadir: &IMAGE_DATA_DIRECTORY) -> Result<&'a [u8]> {
+		let datadir = datadir.le();
+		if datadir.VirtualAddress == 0 {
+			return Err(Error::Null);
+		}
+		self.pe.derva_slice(datadir.VirtualAddress, datadir.Size as usize)
+	}
+}
+impl<'a, P: Pe<'a>> fmt::Debug for Clr<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Clr")
+			.field("runtime_version", &format_args!("{}.{}", self.image.MajorRuntimeVersion.le(), self.image.MinorRuntimeVersion.le()))
+			.field("flags", &format_args!("{:#x}", self.flags()))
+			.field("metadata.len", &format_args!("{:?}", self.metadata().map(|metadata| metadata.len())))
+			.field("ready_to_run", &self.ready_to_run().ok())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// ReadyToRun header.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct ReadyToRun<'a, P> {
+	pe: P,
+	image: &'a READYTORUN_HEADER,
+	sections: &'a [READYTORUN_SECTION],
+}
+impl<'a, P: Pe<'a>> ReadyToRun<'a, P> {
+	fn try_from(pe: P, rva: u32) -> Result<ReadyToRun<'a, P>> {
+		let image: &READYTORUN_HEADER = pe.derva(rva)?;
+		if image.Signature.le() != READYTORUN_SIGNATURE {
+			return Err(Error::BadMagic);
+		}
+		let sections_rva = rva.checked_add(mem::size_of::<READYTORUN_HEADER>() as u32).ok_or(Error::Overflow)?;
+		let sections = pe.derva_slice(sections_rva, image.NumberOfSections.le() as usize)?;
+		Ok(ReadyToRun { pe, image, sections })
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying ReadyToRun header image.
+	pub fn image(&self) -> &'a READYTORUN_HEADER {
+		self.image
+	}
+	/// Gets the `READYTORUN_FLAG_*` flags.
+	pub fn flags(&self) -> u32 {
+		self.image.Flags.le()
+	}
+	/// Returns the section descriptors.
+	pub fn sections(&self) -> &'a [READYTORUN_SECTION] {
+		self.sections
+	}
+	/// Gets the data of the section with the given `READYTORUN_SECTION_*` type.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if there is no such section.
+	pub fn section(&self, ty: u32) -> Result<&'a [u8]> {
+		let section = self.sections.iter().find(|section| section.Type.le() == ty).ok_or(Error::Null)?;
+		let datadir = section.Section.le();
+		self.pe.derva_slice(datadir.VirtualAddress, datadir.Size as usize)
+	}
+	/// Gets the identifier of the compiler which generated the native code.
+	pub fn compiler_identifier(&self) -> Result<&'a CStr> {
+		let bytes = self.section(READYTORUN_SECTION_COMPILER_IDENTIFIER)?;
+		CStr::from_bytes(bytes).ok_or(Error::Bounds)
+	}
+	/// Gets the import sections.
+	pub fn import_sections(&self) -> Result<&'a [READYTORUN_IMPORT_SECTION]> {
+		self.table(READYTORUN_SECTION_IMPORT_SECTIONS)
+	}
+	/// Gets the runtime function table of the precompiled methods.
+	///
+	/// Only x64 images use the `RUNTIME_FUNCTION` layout, returns [`Err(Invalid)`](../../enum.Error.html#variant.Invalid) for other machines.
+	/// Use [`section`](#method.section) to access the raw table instead.
+	pub fn runtime_functions(&self) -> Result<&'a [RUNTIME_FUNCTION]> {
+		if self.pe.file_header().Machine.le() != IMAGE_FILE_MACHINE_AMD64 {
+			return Err(Error::Invalid);
+		}
+		self.table(READYTORUN_SECTION_RUNTIME_FUNCTIONS)
+	}
+	fn table<T: crate::Pod>(&self, ty: u32) -> Result<&'a [T]> {
+		let section = self.sections.iter().find(|section| section.Type.le() == ty).ok_or(Error::Null)?;
+		let datadir = section.Section.le();
+		self.pe.derva_slice(datadir.VirtualAddress, datadir.Size as usize / mem::size_of::<T>())
+	}
+}
+impl<'a, P: Pe<'a>> fmt::Debug for ReadyToRun<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ReadyToRun")
+			.field("version", &format_args!("{}.{}", self.image.MajorVersion.le(), self.image.MinorVersion.le()))
+			.field("flags", &format_args!("{:#x}", self.flags()))
+			.field("sections.len", &self.sections.len())
+			.field("compiler_identifier", &self.compiler_identifier().ok())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
+	let clr = pe.clr()?;
+	let _ = format!("{:?}", clr);
+	let _metadata = clr.metadata();
+	let _resources = clr.resources();
+	if let Ok(r2r) = clr.ready_to_run() {
+		let _ = format!("{:?}", r2r);
+		let _import_sections = r2r.import_sections();
+		let _runtime_functions = r2r.runtime_functions();
+	}
+	Ok(())
+}
@@ -25,7 +25,7 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 use std::{fmt, iter, mem, slice};
 
 use crate::util::AlignTo;
-use crate::util::CStr;
+use crate::util::{CStr, Le};
 use crate::{Error, Result};
 
 use super::{image::*, Align, Pe};
@@ -44,13 +44,13 @@ impl<'a, P: Pe<'a>> Debug<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<Debug<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_DEBUG).ok_or(Error::Bounds)?;
 		let (len, rem) = (
-			datadir.Size as usize / mem::size_of::<IMAGE_DEBUG_DIRECTORY>(),
-			datadir.Size as usize % mem::size_of::<IMAGE_DEBUG_DIRECTORY>(),
+			datadir.Size.le() as usize / mem::size_of::<IMAGE_DEBUG_DIRECTORY>(),
+			datadir.Size.le() as usize % mem::size_of::<IMAGE_DEBUG_DIRECTORY>(),
 		);
 		if rem != 0 {
 			return Err(Error::Invalid);
 		}
-		let image = pe.derva_slice(datadir.VirtualAddress, len)?;
+		let image = pe.derva_slice(datadir.VirtualAddress.le(), len)?;
 		Ok(Debug { pe, image })
 	}
 	/// Gets the PE instance.
@@ -139,16 +139,16 @@ impl<'a, P: Pe<'a>> Dir<'a, P> {
 	/// Gets the raw data of this debug directory entry.
 	pub fn data(&self) -> Option<&'a [u8]> {
 		let image = self.pe.image();
-		let size = self.image.SizeOfData as usize;
+		let size = self.image.SizeOfData.le() as usize;
 		let offset = match self.pe.align() {
-			Align::File => self.image.PointerToRawData,
-			Align::Section => self.image.AddressOfRawData,
+			Align::File => self.image.PointerToRawData.le(),
+			Align::Section => self.image.AddressOfRawData.le(),
 		} as usize;
 		image.get(offset..offset.wrapping_add(size))
 	}
 	/// Interprets the directory entry.
 	pub fn entry(&self) -> Result<Entry<'a>> {
-		match self.image.Type {
+		match self.image.Type.le() {
 			IMAGE_DEBUG_TYPE_CODEVIEW => Ok(Entry::CodeView(code_view(&self)?)),
 			IMAGE_DEBUG_TYPE_MISC => Ok(Entry::Dbg(dbg(&self)?)),
 			IMAGE_DEBUG_TYPE_POGO => Ok(Entry::Pgo(pgo(&self)?)),
@@ -159,9 +159,9 @@ impl<'a, P: Pe<'a>> Dir<'a, P> {
 impl<'a, P: Pe<'a>> fmt::Debug for Dir<'a, P> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.debug_struct("Dir")
-			.field("type", &crate::stringify::DebugType(self.image.Type).to_str().ok_or(self.image.Type))
-			.field("time_date_stamp", &self.image.TimeDateStamp)
-			.field("version", &self.image.Version)
+			.field("type", &crate::stringify::DebugType(self.image.Type.le()).to_str().ok_or(self.image.Type.le()))
+			.field("time_date_stamp", &self.image.TimeDateStamp.le())
+			.field("version", &self.image.Version.le())
 			.field("entry", &self.entry())
 			.finish()
 	}
@@ -246,6 +246,7 @@ fn pgo<'a, P: Pe<'a>>(dir: &Dir<'a, P>) -> Result<Pgo<'a>> {
 #[cfg(feature = "serde")]
 mod serde {
 	use crate::util::serde_helper::*;
+	use crate::util::Le;
 
 	use super::{Debug, Dir, Pe};
 
@@ -259,13 +260,13 @@ mod serde {
 			let is_human_readable = serializer.is_human_readable();
 			let mut state = serializer.serialize_struct("Dir", 4)?;
 			if is_human_readable {
-				state.serialize_field("type", &crate::stringify::DebugType(self.image.Type).to_str())?;
+				state.serialize_field("type", &crate::stringify::DebugType(self.image.Type.le()).to_str())?;
 			}
 			else {
-				state.serialize_field("type", &self.image.Type)?;
+				state.serialize_field("type", &self.image.Type.le())?;
 			}
-			state.serialize_field("time_date_stamp", &self.image.TimeDateStamp)?;
-			state.serialize_field("version", &self.image.Version)?;
+			state.serialize_field("time_date_stamp", &self.image.TimeDateStamp.le())?;
+			state.serialize_field("version", &self.image.Version.le())?;
 			state.serialize_field("entry", &self.entry().ok())?;
 			state.end()
 		}
@@ -0,0 +1,286 @@
+/*!
+Delay Import Directory.
+
+Delay loaded modules aren't loaded until one of their imports is called for the first time.
+Until then their IAT entries point at small stubs within the image which load the module, resolve the import and overwrite the IAT entry with its address.
+
+Inspecting the delay IAT of a mapped image, eg. the image of a live process, tells which imports have been resolved so far.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeView};
+use pelite::pe64::delay_imports::Binding;
+
+# #[allow(dead_code)]
+fn example(view: PeView<'_>) -> pelite::Result<()> {
+	// Access the delay import directory
+	let delay_imports = view.delay_imports()?;
+
+	// Iterate over the delay loaded modules
+	for desc in delay_imports {
+		println!("{} loaded: {}", desc.dll_name()?, desc.module_handle()? != 0);
+
+		// Inspect the current state of its imports
+		for binding in desc.bindings()? {
+			match binding.target {
+				Binding::Stub(rva) => println!("  {:?} not yet resolved, stub at {:#x}", binding.import, rva),
+				Binding::Resolved(va) => println!("  {:?} resolved to {:#x}", binding.import, va),
+			}
+		}
+	}
+
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{fmt, iter, mem, slice};
+
+use crate::util::{CStr, Le};
+use crate::{Error, Result};
+
+use super::image::*;
+use super::imports::{import_from_va, Import};
+use super::Pe;
+
+//----------------------------------------------------------------
+
+/// Delay Import Directory.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct DelayImports<'a, P> {
+	pe: P,
+	image: &'a [IMAGE_DELAYLOAD_DESCRIPTOR],
+}
+impl<'a, P: Pe<'a>> DelayImports<'a, P> {
+	pub(crate) fn try_from(pe: P) -> Result<DelayImports<'a, P>> {
+		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT).ok_or(Error::Bounds)?.le();
+		let image = pe.derva_slice_f(datadir.VirtualAddress, |image: &IMAGE_DELAYLOAD_DESCRIPTOR| image.is_null())?;
+		if image.len() > pe.options().max_import_descriptors {
+			return Err(Error::Insanity);
+		}
+		Ok(DelayImports { pe, image })
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying delay import directory image array.
+	pub fn image(&self) -> &'a [IMAGE_DELAYLOAD_DESCRIPTOR] {
+		self.image
+	}
+	/// Iterator over the delay import descriptors.
+	pub fn iter(&self) -> Iter<'a, P> {
+		Iter { pe: self.pe, iter: self.image.iter() }
+	}
+	/// Finds the import bound to the delay IAT entry at the given rva.
+	///
+	/// Descriptors which cannot be read are skipped.
+	pub fn find_iat(&self, rva: Rva) -> Option<DelayBinding<'a>> {
+		self.iter().find_map(|desc| {
+			let iat = desc.iat_rva().ok()?;
+			let index = rva.checked_sub(iat)? as usize;
+			if index % mem::size_of::<Va>() != 0 {
+				return None;
+			}
+			desc.bindings().ok()?.into_iter().nth(index / mem::size_of::<Va>())
+		})
+	}
+	/// Validates every delay import descriptor.
+	///
+	/// Reads the DLL names, the module handles, the delay import address tables and every entry of the import name tables.
+	pub fn validate(&self) -> Result<()> {
+		for desc in self.iter() {
+			desc.dll_name()?;
+			desc.module_handle()?;
+			desc.iat()?;
+			for import in desc.int()? {
+				import?;
+			}
+		}
+		Ok(())
+	}
+}
+impl<'a, P: Pe<'a>> IntoIterator for DelayImports<'a, P> {
+	type Item = Desc<'a, P>;
+	type IntoIter = Iter<'a, P>;
+	fn into_iter(self) -> Iter<'a, P> {
+		self.iter()
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for DelayImports<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list()
+			.entries(self.iter())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Iterator over the delay import descriptors.
+#[derive(Clone)]
+pub struct Iter<'a, P> {
+	pe: P,
+	iter: slice::Iter<'a, IMAGE_DELAYLOAD_DESCRIPTOR>,
+}
+impl<'a, P: Pe<'a>> Iter<'a, P> {
+	pub fn image(&self) -> &'a [IMAGE_DELAYLOAD_DESCRIPTOR] {
+		self.iter.as_slice()
+	}
+}
+impl<'a, P: Pe<'a>> Iterator for Iter<'a, P> {
+	type Item = Desc<'a, P>;
+	fn next(&mut self) -> Option<Desc<'a, P>> {
+		self.iter.next().map(|image| Desc { pe: self.pe, image })
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+impl<'a, P: Pe<'a>> DoubleEndedIterator for Iter<'a, P> {
+	fn next_back(&mut self) -> Option<Desc<'a, P>> {
+		self.iter.next_back().map(|image| Desc { pe: self.pe, image })
+	}
+}
+impl<'a, P: Pe<'a>> ExactSizeIterator for Iter<'a, P> {}
+
+//----------------------------------------------------------------
+
+/// Delay import descriptor.
+#[derive(Copy, Clone)]
+pub struct Desc<'a, P> {
+	pe: P,
+	image: &'a IMAGE_DELAYLOAD_DESCRIPTOR,
+}
+impl<'a, P: Pe<'a>> Desc<'a, P> {
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying delay import descriptor image.
+	pub fn image(&self) -> &'a IMAGE_DELAYLOAD_DESCRIPTOR {
+		self.image
+	}
+	// Old linkers store virtual addresses instead of rvas in the descriptor
+	fn rva(&self, field: u32) -> Result<Rva> {
+		let field = field.le();
+		if self.image.Attributes.le() & IMAGE_DELAYLOAD_RVA_BASED != 0 {
+			Ok(field)
+		}
+		else {
+			self.pe.va_to_rva(Va::from(field))
+		}
+	}
+	/// Gets the name of the DLL imported from.
+	pub fn dll_name(&self) -> Result<&'a CStr> {
+		self.pe.derva_c_str(self.rva(self.image.DllNameRVA.le())?)
+	}
+	/// Gets the module handle stored by the delay load helper, zero if the module hasn't been loaded yet.
+	pub fn module_handle(&self) -> Result<Va> {
+		self.pe.derva_copy(self.rva(self.image.ModuleHandleRVA.le())?).map(|handle: Va| handle.le())
+	}
+	fn iat_rva(&self) -> Result<Rva> {
+		self.rva(self.image.ImportAddressTableRVA.le())
+	}
+	/// Gets the delay import address table.
+	///
+	/// Its length is given by the import name table as resolved entries are never null.
+	pub fn iat(&self) -> Result<&'a [Va]> {
+		let len = self.pe.derva_slice_s::<Va>(self.rva(self.image.ImportNameTableRVA.le())?, 0)?.len();
+		self.pe.derva_slice(self.iat_rva()?, len)
+	}
+	/// Gets the import name table.
+	pub fn int(&self) -> Result<iter::Map<slice::Iter<'a, Va>, impl Clone + FnMut(&'a Va) -> Result<Import<'a>>>> {
+		let slice = self.pe.derva_slice_s(self.rva(self.image.ImportNameTableRVA.le())?, 0)?;
+		let pe = self.pe;
+		Ok(slice.iter().map(move |va| import_from_va(pe, va)))
+	}
+	/// Resolves the current delay IAT entries back to their imports.
+	///
+	/// Entries pointing within the image are the stubs which haven't been called yet, any other value is the resolved address.
+	pub fn bindings(&self) -> Result<Vec<DelayBinding<'a>>> {
+		let dll_name = self.dll_name()?;
+		let iat_rva = self.iat_rva()?;
+		let iat = self.iat()?;
+		let int = self.int()?;
+		let bindings = Iterator::zip(iat.iter().map(|&va| va.le()), int)
+			.enumerate()
+			.map(|(index, (va, import))| DelayBinding {
+				dll_name,
+				import,
+				iat: iat_rva.wrapping_add((index * mem::size_of::<Va>()) as Rva),
+				target: match self.pe.va_to_rva(va) {
+					Ok(rva) => Binding::Stub(rva),
+					Err(_) => Binding::Resolved(va),
+				},
+			})
+			.collect();
+		Ok(bindings)
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for Desc<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Desc")
+			.field("dll_name", &format_args!("{:?}", self.dll_name()))
+			.field("module_handle", &format_args!("{:?}", self.module_handle()))
+			.field("iat.len", &format_args!("{:?}", self.iat().map(<[_]>::len)))
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Current target of a delay IAT entry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Binding {
+	/// Not yet resolved, points at the delay load stub at this rva.
+	Stub(Rva),
+	/// Resolved to this address outside the image.
+	Resolved(Va),
+}
+impl Binding {
+	/// Returns true if the import has been resolved.
+	pub fn is_resolved(&self) -> bool {
+		matches!(self, Binding::Resolved(_))
+	}
+}
+
+/// Delay imported symbol and the current state of its IAT entry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelayBinding<'a> {
+	/// Name of the DLL imported from.
+	pub dll_name: &'a CStr,
+	/// Imported symbol.
+	pub import: Result<Import<'a>>,
+	/// Rva of the IAT entry.
+	pub iat: Rva,
+	/// Current target of the IAT entry.
+	pub target: Binding,
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
+	let delay_imports = pe.delay_imports()?;
+	let _ = format!("{:?}", delay_imports);
+
+	for desc in delay_imports {
+		let _ = format!("{:?}", desc);
+		let _dll_name = desc.dll_name();
+		if let Ok(bindings) = desc.bindings() {
+			for binding in bindings {
+				assert_eq!(delay_imports.find_iat(binding.iat).map(|found| found.iat), Some(binding.iat));
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -1,14 +1,17 @@
 /*!
 Exception Directory.
+
+ARM and ARM64 images store their function table in a different format, see [`ArmException`](struct.ArmException.html).
 */
 
 use std::cmp::Ordering;
 use std::{fmt, iter, mem, slice};
 
+use crate::util::Le;
 use crate::{Error, Result};
 
 use super::image::*;
-use super::Pe;
+use super::{Pe, Rva};
 
 //----------------------------------------------------------------
 
@@ -22,7 +25,10 @@ pub struct Exception<'a, P> {
 }
 impl<'a, P: Pe<'a>> Exception<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<Exception<'a, P>> {
-		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_EXCEPTION).ok_or(Error::Bounds)?;
+		if is_arm(pe.file_header().Machine.le()) {
+			return Err(Error::Invalid);
+		}
+		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_EXCEPTION).ok_or(Error::Bounds)?.le();
 		let len = datadir.Size as usize / mem::size_of::<RUNTIME_FUNCTION>();
 		let rem = datadir.Size as usize % mem::size_of::<RUNTIME_FUNCTION>();
 		if rem != 0 {
@@ -46,6 +52,7 @@ impl<'a, P: Pe<'a>> Exception<'a, P> {
 	pub fn check_sorted(&self) -> bool {
 		#[rustfmt::skip]
 		fn check_sorted(window: &[RUNTIME_FUNCTION]) -> bool {
+			let window = [window[0].le(), window[1].le()];
 			return
 				window[0].BeginAddress <= window[0].EndAddress &&
 				window[0].EndAddress <= window[1].BeginAddress &&
@@ -53,6 +60,18 @@ impl<'a, P: Pe<'a>> Exception<'a, P> {
 		}
 		self.image.windows(2).all(check_sorted)
 	}
+	/// Validates the function table.
+	///
+	/// Returns [`Err(Invalid)`](../../enum.Error.html#variant.Invalid) if the function table isn't sorted, otherwise reads the unwind info of every function.
+	pub fn validate(&self) -> Result<()> {
+		if !self.check_sorted() {
+			return Err(Error::Invalid);
+		}
+		for function in self.functions() {
+			function.unwind_info()?;
+		}
+		Ok(())
+	}
 	/// Gets an iterator over the function records.
 	pub fn functions(&self) -> iter::Map<slice::Iter<'a, RUNTIME_FUNCTION>, impl Clone + FnMut(&'a RUNTIME_FUNCTION) -> Function<'a, P>> {
 		let pe = self.pe;
@@ -61,6 +80,7 @@ impl<'a, P: Pe<'a>> Exception<'a, P> {
 	/// Finds the index of the function for the given program counter.
 	pub fn index_of(&self, pc: Rva) -> std::result::Result<usize, usize> {
 		self.image.binary_search_by(|rf| {
+			let rf = rf.le();
 			if pc < rf.BeginAddress {
 				Ordering::Less
 			}
@@ -83,6 +103,39 @@ impl<'a, P: Pe<'a>> Exception<'a, P> {
 			})
 			.ok()
 	}
+	/// Looks up the function owning the given address the way `RtlLookupFunctionEntry` does.
+	///
+	/// Function records whose unwind data is indirect point to the primary function record, and so does chained unwind info.
+	/// These are followed to the primary function record describing the owning function's begin and end address and its unwind info.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if no function record contains the address
+	/// and [`Err(Invalid)`](../../enum.Error.html#variant.Invalid) if the indirections are nested too deep.
+	pub fn lookup(&self, rva: Rva) -> Result<Function<'a, P>> {
+		// Limits the indirections to guard against records referencing each other
+		const MAX_INDIRECTIONS: usize = 32;
+		let index = self.image.partition_point(|rf| rf.EndAddress.le() <= rva);
+		let mut image = match self.image.get(index) {
+			Some(image) if image.BeginAddress.le() <= rva => image,
+			_ => return Err(Error::Null),
+		};
+		for _ in 0..MAX_INDIRECTIONS {
+			let unwind_data = image.UnwindData.le();
+			if unwind_data & RUNTIME_FUNCTION_INDIRECT != 0 {
+				image = self.pe.derva(unwind_data & !RUNTIME_FUNCTION_INDIRECT)?;
+				continue;
+			}
+			let function = Function { pe: self.pe, image };
+			let unwind_info = function.unwind_info()?;
+			if unwind_info.flags() & UNW_FLAG_CHAININFO == 0 {
+				return Ok(function);
+			}
+			// The chained function record follows the unwind codes, whose count is rounded up to keep it aligned
+			let count_of_codes = (unwind_info.image().CountOfCodes as u32 + 1) & !1;
+			let offset = (mem::size_of::<UNWIND_INFO>() + mem::size_of::<UNWIND_CODE>() * count_of_codes as usize) as u32;
+			image = self.pe.derva(unwind_data.wrapping_add(offset))?;
+		}
+		Err(Error::Invalid)
+	}
 }
 #[rustfmt::skip]
 impl<'a, P: Pe<'a>> fmt::Debug for Exception<'a, P> {
@@ -112,19 +165,20 @@ impl<'a, P: Pe<'a>> Function<'a, P> {
 	}
 	/// Gets the function bytes.
 	pub fn bytes(&self) -> Result<&'a [u8]> {
-		let len = if self.image.BeginAddress > self.image.EndAddress {
+		let image = self.image.le();
+		let len = if image.BeginAddress > image.EndAddress {
 			return Err(Error::Overflow);
 		}
 		else {
-			(self.image.EndAddress - self.image.BeginAddress) as usize
+			(image.EndAddress - image.BeginAddress) as usize
 		};
-		self.pe.derva_slice(self.image.BeginAddress, len)
+		self.pe.derva_slice(image.BeginAddress, len)
 	}
 	/// Gets the unwind info.
 	pub fn unwind_info(&self) -> Result<UnwindInfo<'a, P>> {
 		// Read as many bytes as we can for interpretation
 		let bytes = self.pe.slice(
-			self.image.UnwindData,
+			self.image.UnwindData.le(),
 			mem::size_of::<UNWIND_INFO>(),
 			if cfg!(feature = "unsafe_alignment") { 1 } else { mem::align_of::<UNWIND_INFO>() },
 		)?;
@@ -137,6 +191,10 @@ impl<'a, P: Pe<'a>> Function<'a, P> {
 		// Ok
 		Ok(UnwindInfo { pe: self.pe, image })
 	}
+	/// Summarizes the stack frame set up by the prolog from the unwind info.
+	pub fn frame(&self) -> Result<Frame> {
+		self.unwind_info()?.frame()
+	}
 }
 #[rustfmt::skip]
 impl<'a, P: Pe<'a>> fmt::Debug for Function<'a, P> {
@@ -183,6 +241,37 @@ impl<'a, P: Pe<'a>> UnwindInfo<'a, P> {
 		let len = self.image.CountOfCodes as usize;
 		unsafe { slice::from_raw_parts(self.image.UnwindCode.as_ptr(), len) }
 	}
+	/// Decodes the unwind codes.
+	///
+	/// Yields the offset in the prolog at the end of the instruction together with the operation, in the reverse order of the prolog instructions.
+	pub fn unwind_ops(&self) -> UnwindOps<'a> {
+		UnwindOps { codes: self.unwind_codes() }
+	}
+	/// Summarizes the stack frame set up by the prolog.
+	///
+	/// Chained unwind info only describes the part of the prolog in its own unwind codes.
+	pub fn frame(&self) -> Result<Frame> {
+		let mut frame = Frame {
+			prolog_len: self.size_of_prolog(),
+			frame_register: None,
+			stack_alloc: 0,
+			pushed: 0,
+			saved: 0,
+			saved_xmm: 0,
+			machine_frame: false,
+		};
+		for op in self.unwind_ops() {
+			match op?.1 {
+				UnwindOp::PushNonvol(reg) => frame.pushed |= 1 << reg,
+				UnwindOp::Alloc(size) => frame.stack_alloc = frame.stack_alloc.wrapping_add(size),
+				UnwindOp::SetFpreg => frame.frame_register = Some((self.frame_register(), self.frame_offset() as u32 * 16)),
+				UnwindOp::SaveNonvol(reg, _) => frame.saved |= 1 << reg,
+				UnwindOp::SaveXmm128(reg, _) => frame.saved_xmm |= 1 << reg,
+				UnwindOp::PushMachframe(_) => frame.machine_frame = true,
+			}
+		}
+		Ok(frame)
+	}
 }
 impl<'a, P: Pe<'a>> fmt::Debug for UnwindInfo<'a, P> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -199,6 +288,433 @@ impl<'a, P: Pe<'a>> fmt::Debug for UnwindInfo<'a, P> {
 
 //----------------------------------------------------------------
 
+/// Decoded unwind operation.
+///
+/// Registers are numbered in the order of the general purpose registers, `rax` is 0 and `r15` is 15.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub enum UnwindOp {
+	/// Pushes the nonvolatile register.
+	PushNonvol(u8),
+	/// Allocates the number of bytes on the stack.
+	Alloc(u32),
+	/// Establishes the frame register from the unwind info.
+	SetFpreg,
+	/// Saves the nonvolatile register at the offset from the stack pointer.
+	SaveNonvol(u8, u32),
+	/// Saves the xmm register at the offset from the stack pointer.
+	SaveXmm128(u8, u32),
+	/// Pushes a machine frame, with an error code if true.
+	PushMachframe(bool),
+}
+
+/// Iterator over the decoded unwind codes.
+///
+/// Yields [`Err(Invalid)`](../../enum.Error.html#variant.Invalid) for unknown operations and [`Err(Bounds)`](../../enum.Error.html#variant.Bounds) for truncated ones after which iteration stops.
+#[derive(Clone, Debug)]
+pub struct UnwindOps<'a> {
+	codes: &'a [UNWIND_CODE],
+}
+impl<'a> UnwindOps<'a> {
+	fn slot(&self, index: usize) -> Option<u32> {
+		self.codes.get(index).map(|code| u16::from_le_bytes([code.CodeOffset, code.UnwindOpInfo]) as u32)
+	}
+	// Decodes the operation and the number of slots it takes up
+	fn decode(&self) -> Result<(u8, UnwindOp, usize)> {
+		let code = &self.codes[0];
+		let op = code.UnwindOpInfo & 0xf;
+		let info = code.UnwindOpInfo >> 4;
+		let slot = |index| self.slot(index).ok_or(Error::Bounds);
+		let (op, slots) = match op {
+			UWOP_PUSH_NONVOL => (UnwindOp::PushNonvol(info), 1),
+			UWOP_ALLOC_LARGE if info == 0 => (UnwindOp::Alloc(slot(1)? * 8), 2),
+			UWOP_ALLOC_LARGE if info == 1 => (UnwindOp::Alloc(slot(1)? | slot(2)? << 16), 3),
+			UWOP_ALLOC_SMALL => (UnwindOp::Alloc(info as u32 * 8 + 8), 1),
+			UWOP_SET_FPREG => (UnwindOp::SetFpreg, 1),
+			UWOP_SAVE_NONVOL => (UnwindOp::SaveNonvol(info, slot(1)? * 8), 2),
+			UWOP_SAVE_NONVOL_FAR => (UnwindOp::SaveNonvol(info, slot(1)? | slot(2)? << 16), 3),
+			UWOP_SAVE_XMM128 => (UnwindOp::SaveXmm128(info, slot(1)? * 16), 2),
+			UWOP_SAVE_XMM128_FAR => (UnwindOp::SaveXmm128(info, slot(1)? | slot(2)? << 16), 3),
+			UWOP_PUSH_MACHFRAME if info <= 1 => (UnwindOp::PushMachframe(info != 0), 1),
+			_ => return Err(Error::Invalid),
+		};
+		Ok((code.CodeOffset, op, slots))
+	}
+}
+impl<'a> Iterator for UnwindOps<'a> {
+	type Item = Result<(u8, UnwindOp)>;
+	fn next(&mut self) -> Option<Result<(u8, UnwindOp)>> {
+		if self.codes.is_empty() {
+			return None;
+		}
+		match self.decode() {
+			Ok((offset, op, slots)) => {
+				self.codes = &self.codes[slots..];
+				Some(Ok((offset, op)))
+			},
+			Err(err) => {
+				self.codes = &[];
+				Some(Err(err))
+			},
+		}
+	}
+}
+impl<'a> iter::FusedIterator for UnwindOps<'a> {}
+
+/// Stack frame summary of a function's prolog.
+///
+/// Hooking frameworks use this to place detours after the prolog or to walk the stack.
+/// Register sets are bitmasks of the register numbers as in [`UnwindOp`](enum.UnwindOp.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Frame {
+	/// Length of the prolog in bytes.
+	pub prolog_len: usize,
+	/// The frame register and its offset from the stack pointer if the prolog establishes one.
+	pub frame_register: Option<(u8, u32)>,
+	/// Fixed stack allocation in bytes, not including the pushed registers.
+	pub stack_alloc: u32,
+	/// Nonvolatile registers pushed on the stack.
+	pub pushed: u16,
+	/// Nonvolatile registers saved in the stack allocation.
+	pub saved: u16,
+	/// Xmm registers saved in the stack allocation.
+	pub saved_xmm: u16,
+	/// The prolog pushes a machine frame, as in interrupt and exception handlers.
+	pub machine_frame: bool,
+}
+impl Frame {
+	/// Returns the size of the stack frame in bytes, the fixed stack allocation plus the pushed registers.
+	///
+	/// Does not include the machine frame or the return address.
+	pub fn size(&self) -> u32 {
+		self.stack_alloc.wrapping_add(self.pushed.count_ones() * 8)
+	}
+}
+
+//----------------------------------------------------------------
+
+fn is_arm(machine: u16) -> bool {
+	machine == IMAGE_FILE_MACHINE_ARM || machine == IMAGE_FILE_MACHINE_ARMNT || machine == IMAGE_FILE_MACHINE_ARM64
+}
+
+/// Exception Directory of ARM and ARM64 images.
+///
+/// The function table entries only record the start of the function,
+/// its length is found in the packed unwind data or in the `.xdata` record.
+#[derive(Copy, Clone)]
+pub struct ArmException<'a, P> {
+	pe: P,
+	image: &'a [IMAGE_ARM_RUNTIME_FUNCTION_ENTRY],
+	arm64: bool,
+}
+impl<'a, P: Pe<'a>> ArmException<'a, P> {
+	pub(crate) fn try_from(pe: P) -> Result<ArmException<'a, P>> {
+		let machine = pe.file_header().Machine.le();
+		if !is_arm(machine) {
+			return Err(Error::Invalid);
+		}
+		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_EXCEPTION).ok_or(Error::Bounds)?.le();
+		let len = datadir.Size as usize / mem::size_of::<IMAGE_ARM_RUNTIME_FUNCTION_ENTRY>();
+		let rem = datadir.Size as usize % mem::size_of::<IMAGE_ARM_RUNTIME_FUNCTION_ENTRY>();
+		if rem != 0 {
+			return Err(Error::Invalid);
+		}
+		let image = pe.derva_slice(datadir.VirtualAddress, len)?;
+		Ok(ArmException { pe, image, arm64: machine == IMAGE_FILE_MACHINE_ARM64 })
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the functions slice.
+	pub fn image(&self) -> &'a [IMAGE_ARM_RUNTIME_FUNCTION_ENTRY] {
+		self.image
+	}
+	/// Returns true for ARM64 images, false for ARM Thumb-2 images.
+	pub fn is_arm64(&self) -> bool {
+		self.arm64
+	}
+	/// Checks if the function table is sorted.
+	///
+	/// Only the begin addresses are compared, the function lengths require reading the unwind data.
+	pub fn check_sorted(&self) -> bool {
+		self.image.windows(2).all(|window| window[0].BeginAddress.le() & !1 < window[1].BeginAddress.le() & !1)
+	}
+	/// Gets an iterator over the function records.
+	pub fn functions(&self) -> iter::Map<slice::Iter<'a, IMAGE_ARM_RUNTIME_FUNCTION_ENTRY>, impl Clone + FnMut(&'a IMAGE_ARM_RUNTIME_FUNCTION_ENTRY) -> ArmFunction<'a, P>> {
+		let (pe, arm64) = (self.pe, self.arm64);
+		self.image.iter().map(move |image| ArmFunction { pe, image, arm64 })
+	}
+	/// Finds the index of the function for the given program counter.
+	///
+	/// If the program counter falls between functions or the length of the preceding function cannot be read,
+	/// returns the index where a function containing it would be inserted.
+	pub fn index_of(&self, pc: Rva) -> std::result::Result<usize, usize> {
+		let index = self.image.partition_point(|rf| rf.BeginAddress.le() & !1 <= pc);
+		if index == 0 {
+			return Err(0);
+		}
+		let function = ArmFunction { pe: self.pe, image: &self.image[index - 1], arm64: self.arm64 };
+		match function.end_address() {
+			Ok(end) if pc < end => Ok(index - 1),
+			_ => Err(index),
+		}
+	}
+	/// Finds the function for the given 'program counter' address.
+	///
+	/// The function records are sorted by their address allowing binary search for the record.
+	pub fn lookup_function_entry(&self, pc: Rva) -> Option<ArmFunction<'a, P>> {
+		self.index_of(pc)
+			.map(|index| ArmFunction {
+				pe: self.pe,
+				image: &self.image[index],
+				arm64: self.arm64,
+			})
+			.ok()
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for ArmException<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ArmException")
+			.field("arm64", &self.arm64)
+			.field("functions.len", &self.image.len())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// ARM or ARM64 runtime function.
+#[derive(Copy, Clone)]
+pub struct ArmFunction<'a, P> {
+	pe: P,
+	image: &'a IMAGE_ARM_RUNTIME_FUNCTION_ENTRY,
+	arm64: bool,
+}
+impl<'a, P: Pe<'a>> ArmFunction<'a, P> {
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the underlying runtime function image.
+	pub fn image(&self) -> &'a IMAGE_ARM_RUNTIME_FUNCTION_ENTRY {
+		self.image
+	}
+	/// Gets the rva of the function, the Thumb bit of ARM functions is cleared.
+	pub fn begin_address(&self) -> Rva {
+		self.image.BeginAddress.le() & !1
+	}
+	/// Gets the length of the function in bytes.
+	pub fn function_length(&self) -> Result<u32> {
+		Ok(self.unwind_data()?.function_length())
+	}
+	/// Gets the rva one past the end of the function.
+	pub fn end_address(&self) -> Result<Rva> {
+		self.begin_address().checked_add(self.function_length()?).ok_or(Error::Overflow)
+	}
+	/// Gets the function bytes.
+	pub fn bytes(&self) -> Result<&'a [u8]> {
+		let len = self.function_length()? as usize;
+		self.pe.derva_slice(self.begin_address(), len)
+	}
+	/// Gets the unwind data.
+	///
+	/// Returns [`Err(Invalid)`](../enum.Error.html#variant.Invalid) for the reserved packing flag.
+	pub fn unwind_data(&self) -> Result<ArmUnwindData<'a>> {
+		let unwind_data = self.image.UnwindData.le();
+		match unwind_data & 3 {
+			0 => ArmXdata::try_from(self.pe, unwind_data, self.arm64).map(ArmUnwindData::Xdata),
+			3 => Err(Error::Invalid),
+			_ => Ok(ArmUnwindData::Packed(ArmPackedUnwind { value: unwind_data, arm64: self.arm64 })),
+		}
+	}
+}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for ArmFunction<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ArmFunction")
+			.field("begin_address", &format_args!("{:#x}", self.begin_address()))
+			.field("function_length", &self.function_length())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// ARM or ARM64 unwind data.
+#[derive(Copy, Clone, Debug)]
+pub enum ArmUnwindData<'a> {
+	/// Unwind data packed in the function table entry.
+	Packed(ArmPackedUnwind),
+	/// Unwind data in an `.xdata` record.
+	Xdata(ArmXdata<'a>),
+}
+impl<'a> ArmUnwindData<'a> {
+	/// Gets the length of the function in bytes.
+	pub fn function_length(&self) -> u32 {
+		match self {
+			ArmUnwindData::Packed(packed) => packed.function_length(),
+			ArmUnwindData::Xdata(xdata) => xdata.function_length(),
+		}
+	}
+}
+
+/// Packed unwind data.
+///
+/// The layout of the fields following the function length differs between ARM and ARM64,
+/// see the Microsoft documentation on ARM exception handling.
+#[derive(Copy, Clone)]
+pub struct ArmPackedUnwind {
+	value: u32,
+	arm64: bool,
+}
+impl ArmPackedUnwind {
+	/// Returns the raw packed unwind data.
+	pub fn value(&self) -> u32 {
+		self.value
+	}
+	/// Gets the packing flag.
+	///
+	/// `1` describes a function with a canonical prolog and epilog, `2` a fragment without a prolog.
+	pub fn flag(&self) -> u8 {
+		(self.value & 3) as u8
+	}
+	/// Gets the length of the function in bytes.
+	pub fn function_length(&self) -> u32 {
+		((self.value >> 2) & 0x7ff) * if self.arm64 { 4 } else { 2 }
+	}
+}
+#[rustfmt::skip]
+impl fmt::Debug for ArmPackedUnwind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ArmPackedUnwind")
+			.field("value", &format_args!("{:#x}", self.value))
+			.field("flag", &self.flag())
+			.field("function_length", &self.function_length())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Unwind data in an `.xdata` record.
+#[derive(Copy, Clone)]
+pub struct ArmXdata<'a> {
+	header: u32,
+	epilog_count: u32,
+	code_words: u32,
+	epilog_scopes: &'a [u32],
+	unwind_codes: &'a [u8],
+	exception_handler: Option<Rva>,
+	arm64: bool,
+}
+impl<'a> ArmXdata<'a> {
+	fn try_from<P: Pe<'a>>(pe: P, rva: Rva, arm64: bool) -> Result<ArmXdata<'a>> {
+		let header = pe.derva_copy::<u32>(rva)?.le();
+		let (mut epilog_count, mut code_words) = if arm64 { ((header >> 22) & 0x1f, header >> 27) } else { ((header >> 23) & 0x1f, header >> 28) };
+		// Both zero indicates an extension word with the larger counts
+		let mut len = 1;
+		if epilog_count == 0 && code_words == 0 {
+			let extension = pe.derva_copy::<u32>(rva.checked_add(4).ok_or(Error::Overflow)?)?.le();
+			epilog_count = extension & 0xffff;
+			code_words = (extension >> 16) & 0xff;
+			len = 2;
+		}
+		// A single epilog is described by the header without any epilog scopes
+		let scopes = if header & (1 << 21) != 0 { 0 } else { epilog_count as usize };
+		let handler = (header >> 20) as usize & 1;
+		let words = pe.derva_slice::<u32>(rva, len + scopes + code_words as usize + handler)?;
+		let epilog_scopes = &words[len..len + scopes];
+		let codes_rva = rva.checked_add(((len + scopes) * 4) as u32).ok_or(Error::Overflow)?;
+		let unwind_codes = pe.derva_slice::<u8>(codes_rva, code_words as usize * 4)?;
+		let exception_handler = if handler != 0 { words.last().map(|&rva| rva.le()) } else { None };
+		Ok(ArmXdata { header, epilog_count, code_words, epilog_scopes, unwind_codes, exception_handler, arm64 })
+	}
+	/// Returns the raw header word.
+	pub fn header(&self) -> u32 {
+		self.header
+	}
+	/// Gets the length of the function in bytes.
+	pub fn function_length(&self) -> u32 {
+		(self.header & 0x3ffff) * if self.arm64 { 4 } else { 2 }
+	}
+	/// Gets the version of the record, only version `0` is defined.
+	pub fn version(&self) -> u8 {
+		((self.header >> 18) & 3) as u8
+	}
+	/// Returns true if the record is followed by an exception handler.
+	pub fn has_exception_data(&self) -> bool {
+		self.header & (1 << 20) != 0
+	}
+	/// Returns true if the function has a single epilog described without epilog scopes.
+	///
+	/// The [`epilog_count`](#method.epilog_count) is then the index of the first unwind code of the epilog.
+	pub fn single_epilog(&self) -> bool {
+		self.header & (1 << 21) != 0
+	}
+	/// Returns true if the ARM function is a fragment without a prolog, always false for ARM64.
+	pub fn is_fragment(&self) -> bool {
+		!self.arm64 && self.header & (1 << 22) != 0
+	}
+	/// Gets the number of epilog scopes.
+	pub fn epilog_count(&self) -> u32 {
+		self.epilog_count
+	}
+	/// Gets the number of 32-bit words holding the unwind codes.
+	pub fn code_words(&self) -> u32 {
+		self.code_words
+	}
+	/// Iterates over the epilog scopes.
+	pub fn epilog_scopes(&self) -> impl 'a + Clone + Iterator<Item = ArmEpilogScope> {
+		let arm64 = self.arm64;
+		self.epilog_scopes.iter().map(move |&scope| {
+			let scope = scope.le();
+			if arm64 {
+				ArmEpilogScope { start_offset: (scope & 0x3ffff) * 4, start_index: scope >> 22, condition: 0xe }
+			}
+			else {
+				ArmEpilogScope { start_offset: (scope & 0x3ffff) * 2, start_index: scope >> 24, condition: ((scope >> 20) & 0xf) as u8 }
+			}
+		})
+	}
+	/// Returns the unwind code bytes.
+	pub fn unwind_codes(&self) -> &'a [u8] {
+		self.unwind_codes
+	}
+	/// Gets the rva of the exception handler if present.
+	pub fn exception_handler(&self) -> Option<Rva> {
+		self.exception_handler
+	}
+}
+#[rustfmt::skip]
+impl<'a> fmt::Debug for ArmXdata<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ArmXdata")
+			.field("function_length", &self.function_length())
+			.field("version", &self.version())
+			.field("single_epilog", &self.single_epilog())
+			.field("epilog_count", &self.epilog_count)
+			.field("code_words", &self.code_words)
+			.field("exception_handler", &self.exception_handler)
+			.finish()
+	}
+}
+
+/// Epilog scope of an `.xdata` record.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ArmEpilogScope {
+	/// Offset of the epilog in bytes relative to the start of the function.
+	pub start_offset: u32,
+	/// Index of the first unwind code byte describing the epilog.
+	pub start_index: u32,
+	/// Condition under which the ARM epilog executes, always `0xe` for ARM64.
+	pub condition: u8,
+}
+
+//----------------------------------------------------------------
+
 #[cfg(test)]
 pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 	let exception = pe.exception()?;
@@ -211,7 +727,7 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 		let _bytes = function.bytes();
 
 		if sorted {
-			for pc in function.image().BeginAddress..function.image().EndAddress {
+			for pc in function.image().BeginAddress.le()..function.image().EndAddress.le() {
 				assert_eq!(exception.index_of(pc), Ok(index));
 			}
 		}
@@ -227,5 +743,17 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 		}
 	}
 
+	if let Ok(exception) = pe.arm_exception() {
+		let _ = format!("{:?}", exception);
+		let _ = exception.check_sorted();
+		for function in exception.functions() {
+			let _ = format!("{:?}", function);
+			let _ = function.bytes();
+			if let Ok(ArmUnwindData::Xdata(xdata)) = function.unwind_data() {
+				for _ in xdata.epilog_scopes() {}
+			}
+		}
+	}
+
 	Ok(())
 }
@@ -53,9 +53,11 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 ```
 */
 
-use std::{fmt, ops};
+use std::collections::BTreeMap;
+use std::prelude::v1::*;
+use std::{fmt, mem, ops};
 
-use crate::util::CStr;
+use crate::util::{CStr, Le};
 use crate::{Error, Result};
 
 use super::image::*;
@@ -80,7 +82,7 @@ pub struct Exports<'a, P> {
 impl<'a, P: Pe<'a>> Exports<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<Exports<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_EXPORT).ok_or(Error::Bounds)?;
-		let image = pe.derva(datadir.VirtualAddress)?;
+		let image = pe.derva(datadir.VirtualAddress.le())?;
 		Ok(Exports { pe, datadir, image })
 	}
 	/// Gets the PE instance.
@@ -93,15 +95,15 @@ impl<'a, P: Pe<'a>> Exports<'a, P> {
 	}
 	/// Gets the export directory's name for this library.
 	pub fn dll_name(&self) -> Result<&'a CStr> {
-		self.pe.derva_c_str(self.image.Name)
+		self.pe.derva_c_str(self.image.Name.le())
 	}
 	/// Gets the ordinal base for the exported functions.
 	pub fn ordinal_base(&self) -> Ordinal {
-		self.image.Base as Ordinal
+		self.image.Base.le() as Ordinal
 	}
 	/// Gets the export address table.
 	pub fn functions(&self) -> Result<&'a [Rva]> {
-		self.pe.derva_slice(self.image.AddressOfFunctions, self.image.NumberOfFunctions as usize)
+		self.pe.derva_slice(self.image.AddressOfFunctions.le(), self.image.NumberOfFunctions.le() as usize)
 	}
 	/// Gets the name address table.
 	///
@@ -109,13 +111,13 @@ impl<'a, P: Pe<'a>> Exports<'a, P> {
 	///
 	/// The names are sorted allowing binary search lookup.
 	pub fn names(&self) -> Result<&'a [Rva]> {
-		self.pe.derva_slice(self.image.AddressOfNames, self.image.NumberOfNames as usize)
+		self.pe.derva_slice(self.image.AddressOfNames.le(), self.image.NumberOfNames.le() as usize)
 	}
 	/// Gets the name index table.
 	///
 	/// The values are indices (not ordinals!) into the export address table matching name with the same index in the name address table.
 	pub fn name_indices(&self) -> Result<&'a [u16]> {
-		self.pe.derva_slice(self.image.AddressOfNameOrdinals, self.image.NumberOfNames as usize)
+		self.pe.derva_slice(self.image.AddressOfNameOrdinals.le(), self.image.NumberOfNames.le() as usize)
 	}
 	/// Query the exports.
 	///
@@ -143,16 +145,68 @@ impl<'a, P: Pe<'a>> Exports<'a, P> {
 			name_indices,
 		})
 	}
+	/// Validates the tables referenced by the export directory.
+	///
+	/// Reads the DLL name and every exported name and checks the name indices against the export address table.
+	/// The export directory is otherwise validated on demand as its tables are accessed.
+	pub fn validate(&self) -> Result<()> {
+		self.dll_name()?;
+		let by = self.by()?;
+		if by.name_indices().iter().any(|&index| index.le() as usize >= by.functions().len()) {
+			return Err(Error::Bounds);
+		}
+		for &name in by.names() {
+			self.pe.derva_c_str(name.le())?;
+		}
+		Ok(())
+	}
+	/// Builds a name index for repeated name lookups.
+	///
+	/// See [`NameIndex`](struct.NameIndex.html) for more information.
+	pub fn name_index(&self) -> Result<NameIndex<'a, P>> {
+		self.by().map(|by| by.name_index())
+	}
+	/// Sorts the exports by rva.
+	///
+	/// See [`RvaOrder`](struct.RvaOrder.html) for more information.
+	pub fn rva_order(&self) -> Result<RvaOrder<'a, P>> {
+		self.by().map(|by| by.rva_order())
+	}
+	/// Compares the exports with those of another version of the module.
+	///
+	/// Named exports are matched by name, exports without a name by ordinal.
+	/// Exports whose ordinal or forwarder changed are reported as changed, changes in rva are expected between versions and ignored.
+	///
+	/// See [`ExportsDiff`](struct.ExportsDiff.html) for more information.
+	pub fn diff<Q: Pe<'a>>(&self, other: &Exports<'a, Q>) -> Result<ExportsDiff<'a>> {
+		let old = self.by()?.diff_entries();
+		let mut new = other.by()?.diff_entries();
+		let mut diff = ExportsDiff::default();
+		for (key, old) in old {
+			match new.remove(&key) {
+				Some(new) => {
+					if old.ordinal != new.ordinal || old.export.forward() != new.export.forward() {
+						diff.changed.push((old, new));
+					}
+				},
+				None => diff.removed.push(old),
+			}
+		}
+		diff.added.extend(new.into_values());
+		Ok(diff)
+	}
 	fn is_forwarded(&self, rva: Rva) -> bool {
 		// An export is forward if its rva points within data directory bounds
-		rva >= self.datadir.VirtualAddress && rva < self.datadir.VirtualAddress + self.datadir.Size
+		let datadir = self.datadir.le();
+		rva >= datadir.VirtualAddress && rva - datadir.VirtualAddress < datadir.Size
 	}
 	pub(crate) fn symbol_from_rva(&self, rva: &'a Rva) -> Result<Export<'a>> {
-		if *rva == 0 {
+		let value = (*rva).le();
+		if value == 0 {
 			Err(Error::Null)
 		}
-		else if self.is_forwarded(*rva) {
-			let fwd = self.pe.derva_c_str(*rva)?;
+		else if self.is_forwarded(value) {
+			let fwd = self.pe.derva_c_str(value)?;
 			Ok(Export::Forward(fwd))
 		}
 		else {
@@ -223,7 +277,7 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 	}
 	/// Looks up an `Export` by its ordinal.
 	pub fn ordinal(&self, ordinal: Ordinal) -> Result<Export<'a>> {
-		let base = self.exp.image.Base;
+		let base = self.exp.image.Base.le();
 		if (ordinal as u32) < base {
 			Err(Error::Bounds)
 		}
@@ -262,7 +316,7 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 		let mut upper_bound = self.names.len();
 		while lower_bound != upper_bound {
 			let i = lower_bound + (upper_bound - lower_bound) / 2;
-			let name_rva = self.names[i];
+			let name_rva = self.names[i].le();
 			let name_it = self.exp.pe.derva_c_str(name_rva)?.as_ref();
 			use std::cmp::Ordering::*;
 			match name.cmp(name_it) {
@@ -270,7 +324,7 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 				Greater => lower_bound = i + 1,
 				Equal => {
 					let &index = self.name_indices.get(i).ok_or(Error::Bounds)?;
-					return self.index(index as usize);
+					return self.index(index.le() as usize);
 				},
 			};
 		}
@@ -278,6 +332,8 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 		Err(Error::Null)
 	}
 	/// Looks up an `Export` by its import.
+	///
+	/// Imports by name are resolved like the loader does, see [`hint_name`](#method.hint_name).
 	pub fn import(&self, import: Import) -> Result<Export<'a>> {
 		match import {
 			Import::ByName { hint, name } => self.hint_name_(hint, name),
@@ -292,29 +348,27 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 	/// Looks up an export by its hint.
 	pub fn hint(&self, hint: usize) -> Result<Export<'a>> {
 		let &index = self.name_indices.get(hint).ok_or(Error::Bounds)?;
-		self.index(index as usize)
+		self.index(index.le() as usize)
 	}
 	/// Looks up an export by its hint and falls back to the name if the hint is incorrect.
+	///
+	/// This matches the loader: the name table slot given by the hint is checked first, on a mismatch the name is binary searched.
+	/// Imports bound against the same version of the module resolve without a search.
 	pub fn hint_name<S: AsRef<[u8]> + ?Sized>(&self, hint: usize, name: &S) -> Result<Export<'a>> {
 		self.hint_name_(hint, name.as_ref())
 	}
 	fn hint_name_(&self, hint: usize, name: &[u8]) -> Result<Export<'a>> {
-		// Try the hint first
-		if let Ok(export) = self.hint(hint) {
-			// Double check that this is the correct export
-			if let Ok(export_name) = self.name_of_hint(hint) {
-				if export_name == name {
-					return Ok(export);
-				}
-			}
+		// Try the hint first, only resolve the export if it names the right one
+		match self.name_of_hint(hint) {
+			Ok(export_name) if export_name == name => self.hint(hint),
+			// Otherwise fallback to the name
+			_ => self.name(name),
 		}
-		// Otherwise fallback to the name
-		self.name(name)
 	}
 	/// Looks up the name for a hint.
 	pub fn name_of_hint(&self, hint: usize) -> Result<&'a CStr> {
 		let &name_rva = self.names.get(hint).ok_or(Error::Bounds)?;
-		self.exp.pe.derva_c_str(name_rva)
+		self.exp.pe.derva_c_str(name_rva.le())
 	}
 	/// Given an index in the functions array, gets the named export.
 	///
@@ -324,20 +378,60 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 	/// See [`iter_names`](#method.iter_names) to iterate over the exported names in linear time.
 	pub fn name_lookup(&self, index: usize) -> Result<Import<'a>> {
 		// Lookup the name index, accidentally quadratic :)
-		match self.name_indices.iter().position(|&i| i as usize == index) {
+		match self.name_indices.iter().position(|&i| i.le() as usize == index) {
 			Some(hint) => {
 				// Lookup the name
-				let name_rva = self.names[hint];
+				let name_rva = self.names[hint].le();
 				let name = self.exp.pe.derva_c_str(name_rva)?;
 				Ok(Import::ByName { hint, name })
 			},
 			None => {
 				// Name not found
-				let ord = (index as u32 + self.exp.image.Base) as Ordinal;
+				let ord = (index as u32).wrapping_add(self.exp.image.Base.le()) as Ordinal;
 				Ok(Import::ByOrdinal { ord })
 			},
 		}
 	}
+	/// Builds a name index for repeated name lookups.
+	///
+	/// See [`NameIndex`](struct.NameIndex.html) for more information.
+	pub fn name_index(&self) -> NameIndex<'a, P> {
+		NameIndex::new(*self)
+	}
+	/// Sorts the exports by rva.
+	///
+	/// See [`RvaOrder`](struct.RvaOrder.html) for more information.
+	pub fn rva_order(&self) -> RvaOrder<'a, P> {
+		RvaOrder::new(*self)
+	}
+	/// Classifies the export as code, data or forwarder.
+	///
+	/// Whether a symbol is code or data is a heuristic based on the characteristics of the section it points into.
+	/// Data exports are global variables, importers access them through a pointer in their IAT instead of calling them.
+	pub fn kind(&self, export: Export<'a>) -> ExportKind<'a> {
+		match export {
+			Export::Symbol(&rva) => {
+				let is_code = self.pe.section_headers().by_rva(rva.le())
+					.map_or(false, |section| section.Characteristics.le() & (IMAGE_SCN_MEM_EXECUTE | IMAGE_SCN_CNT_CODE) != 0);
+				if is_code { ExportKind::Code } else { ExportKind::Data }
+			},
+			Export::Forward(fwd) => ExportKind::Forwarder(fwd),
+		}
+	}
+	/// Finds the ordinals of the exports suppressed from the control flow guard valid call targets.
+	///
+	/// See [`LoadConfig::suppressed_exports`](../load_config/struct.LoadConfig.html#method.suppressed_exports) for more information.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the image has no export suppression info.
+	pub fn cfg_suppressed(&self) -> Result<Vec<Ordinal>> {
+		let suppressed = self.pe().load_config()?.suppressed_exports()?;
+		let ordinals = self.functions.iter()
+			.enumerate()
+			.filter(|&(_, rva)| suppressed.binary_search(&(*rva).le()).is_ok())
+			.map(|(index, _)| self.ordinal_base().wrapping_add(index as Ordinal))
+			.collect();
+		Ok(ordinals)
+	}
 	/// Iterate over exported functions.
 	///
 	/// Not every exported function has a name, some are exported by ordinal.
@@ -352,15 +446,35 @@ impl<'a, P: Pe<'a>> By<'a, P> {
 	}
 	/// Iterate over functions exported by name, returning their name and index in the functions table.
 	pub fn iter_name_indices<'s>(&'s self) -> impl 's + Clone + Iterator<Item = (Result<&'a CStr>, usize)> {
-		(0..self.names().len() as u32).map(move |hint| (self.name_of_hint(hint as usize), self.name_indices[hint as usize] as usize))
+		(0..self.names().len() as u32).map(move |hint| (self.name_of_hint(hint as usize), self.name_indices[hint as usize].le() as usize))
+	}
+	// Keys the exports by name, or by ordinal if they have no name
+	fn diff_entries(&self) -> BTreeMap<(Option<&'a CStr>, Ordinal), DiffEntry<'a>> {
+		let mut entries = BTreeMap::new();
+		let mut named = vec![false; self.functions.len()];
+		for (name, index) in self.iter_name_indices() {
+			if let (Ok(name), Ok(export)) = (name, self.index(index)) {
+				let ordinal = self.ordinal_base().wrapping_add(index as Ordinal);
+				// Duplicate names resolve to the first, see `name_linear`
+				entries.entry((Some(name), 0)).or_insert(DiffEntry { name: Some(name), ordinal, export });
+				named[index] = true;
+			}
+		}
+		for (index, export) in self.iter().enumerate() {
+			if let (false, Ok(export)) = (named[index], export) {
+				let ordinal = self.ordinal_base().wrapping_add(index as Ordinal);
+				entries.insert((None, ordinal), DiffEntry { name: None, ordinal, export });
+			}
+		}
+		entries
 	}
 }
 impl<'a, P: Pe<'a>> fmt::Debug for By<'a, P> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.debug_struct("Exports")
 			.field("dll_name", &format_args!("{:?}", self.dll_name()))
-			.field("time_date_stamp", &self.image.TimeDateStamp)
-			.field("version", &self.image.Version)
+			.field("time_date_stamp", &self.image.TimeDateStamp.le())
+			.field("version", &self.image.Version.le())
 			.field("ordinal_base", &self.ordinal_base())
 			.field("functions.len", &self.functions().len())
 			.field("names.len", &self.names.len())
@@ -370,6 +484,361 @@ impl<'a, P: Pe<'a>> fmt::Debug for By<'a, P> {
 
 //----------------------------------------------------------------
 
+/// Export directory name index.
+///
+/// Every name lookup with [`By::name`](struct.By.html#method.name) reads the names from the image and requires the name table to be sorted.
+/// The name index reads every name once, ignoring corrupt entries, and sorts them regardless of the order in the image.
+/// Use it when looking up many names in the same module.
+#[derive(Clone)]
+pub struct NameIndex<'a, P> {
+	by: By<'a, P>,
+	names: Vec<(&'a [u8], usize)>,
+}
+impl<'a, P: Pe<'a>> NameIndex<'a, P> {
+	fn new(by: By<'a, P>) -> NameIndex<'a, P> {
+		let mut names: Vec<_> = (0..by.names.len())
+			.filter_map(|hint| by.name_of_hint(hint).ok().map(|name| (name.as_ref(), hint)))
+			.collect();
+		// Stable sort and dedup keep the first hint for duplicate names, see `By::name_linear`
+		names.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+		names.dedup_by(|next, prev| next.0 == prev.0);
+		NameIndex { by, names }
+	}
+	/// Gets the export directory symbol lookup.
+	pub fn by(&self) -> By<'a, P> {
+		self.by
+	}
+	/// Returns the number of valid names in the index.
+	pub fn len(&self) -> usize {
+		self.names.len()
+	}
+	/// Returns true if there are no named exports.
+	pub fn is_empty(&self) -> bool {
+		self.names.is_empty()
+	}
+	/// Looks up the hint for a name.
+	pub fn hint<S: AsRef<[u8]> + ?Sized>(&self, name: &S) -> Option<usize> {
+		let name = name.as_ref();
+		let i = self.names.binary_search_by(|&(name_it, _)| name_it.cmp(name)).ok()?;
+		Some(self.names[i].1)
+	}
+	/// Looks up an `Export` by its name.
+	pub fn name<S: AsRef<[u8]> + ?Sized>(&self, name: &S) -> Result<Export<'a>> {
+		match self.hint(name) {
+			Some(hint) => self.by.hint(hint),
+			None => Err(Error::Null),
+		}
+	}
+	/// Looks up an `Export` by its hint and falls back to the index if the hint is incorrect.
+	///
+	/// Unlike [`By::hint_name`](struct.By.html#method.hint_name) the fallback works even if the name table isn't sorted.
+	pub fn hint_name<S: AsRef<[u8]> + ?Sized>(&self, hint: usize, name: &S) -> Result<Export<'a>> {
+		let name = name.as_ref();
+		match self.by.name_of_hint(hint) {
+			Ok(export_name) if export_name == name => self.by.hint(hint),
+			_ => self.name(name),
+		}
+	}
+	/// Looks up an `Export` by its import.
+	pub fn import(&self, import: Import) -> Result<Export<'a>> {
+		match import {
+			Import::ByName { hint, name } => self.hint_name(hint, name),
+			Import::ByOrdinal { ord } => self.by.ordinal(ord),
+		}
+	}
+}
+impl<'a, P: Pe<'a>> fmt::Debug for NameIndex<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("NameIndex")
+			.field("dll_name", &format_args!("{:?}", self.by.dll_name()))
+			.field("names.len", &self.names.len())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Classification of an export.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExportKind<'a> {
+	/// The export points into an executable section.
+	Code,
+	/// The export points into a section which isn't executable or outside of any section.
+	Data,
+	/// The export is forwarded to another dll.
+	Forwarder(&'a CStr),
+}
+
+/// Export sorted by [`RvaOrder`](struct.RvaOrder.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RvaExport<'a> {
+	/// Rva of the export, for forwarders this is the rva of the forwarder string.
+	pub rva: Rva,
+	/// Ordinal of the export.
+	pub ordinal: Ordinal,
+	/// Name of the export, `None` if exported by ordinal only.
+	///
+	/// Exports with more than one name use the first name in the name table.
+	pub name: Option<&'a CStr>,
+	/// Classification of the export.
+	pub kind: ExportKind<'a>,
+}
+
+/// Exports sorted by rva.
+///
+/// The export address table is ordered by ordinal and the name table by name, neither helps to find the export containing an address.
+/// The exports are read once, ignoring null and corrupt entries, and classified as code, data or forwarder.
+/// Whether an export is code or data is a heuristic based on the characteristics of the section it points into.
+#[derive(Clone)]
+pub struct RvaOrder<'a, P> {
+	by: By<'a, P>,
+	exports: Vec<RvaExport<'a>>,
+}
+impl<'a, P: Pe<'a>> RvaOrder<'a, P> {
+	fn new(by: By<'a, P>) -> RvaOrder<'a, P> {
+		let mut names = vec![None; by.functions.len()];
+		for (name, index) in by.iter_name_indices() {
+			if let (Ok(name), Some(slot @ None)) = (name, names.get_mut(index)) {
+				*slot = Some(name);
+			}
+		}
+		let mut exports: Vec<_> = by.iter().zip(names).enumerate()
+			.filter_map(|(index, (export, name))| {
+				let export = export.ok()?;
+				let rva = by.functions[index];
+				let kind = by.kind(export);
+				let ordinal = by.ordinal_base().wrapping_add(index as Ordinal);
+				Some(RvaExport { rva, ordinal, name, kind })
+			})
+			.collect();
+		exports.sort_by_key(|export| (export.rva, export.ordinal));
+		RvaOrder { by, exports }
+	}
+	/// Gets the export directory symbol lookup.
+	pub fn by(&self) -> By<'a, P> {
+		self.by
+	}
+	/// Returns the exports sorted by rva, aliases are sorted by ordinal.
+	pub fn as_slice(&self) -> &[RvaExport<'a>] {
+		&self.exports
+	}
+	/// Iterates over the exports sorted by rva.
+	pub fn iter(&self) -> std::slice::Iter<'_, RvaExport<'a>> {
+		self.exports.iter()
+	}
+	/// Iterates over the code exports sorted by rva.
+	pub fn code<'s>(&'s self) -> impl 's + Clone + Iterator<Item = &'s RvaExport<'a>> {
+		self.exports.iter().filter(|export| export.kind == ExportKind::Code)
+	}
+	/// Iterates over the data exports, the exported global variables, sorted by rva.
+	pub fn data<'s>(&'s self) -> impl 's + Clone + Iterator<Item = &'s RvaExport<'a>> {
+		self.exports.iter().filter(|export| export.kind == ExportKind::Data)
+	}
+	/// Finds the export which owns the rva.
+	///
+	/// This is the closest code or data export at or before the rva in the same section, aliases prefer the lowest ordinal.
+	/// Without symbols the size of an export is unknown, the owner is only a best guess for rvas far past the export.
+	pub fn owner(&self, rva: Rva) -> Option<&RvaExport<'a>> {
+		let section_headers = self.by.pe.section_headers();
+		let section = section_headers.by_rva(rva)?;
+		let end = self.exports.partition_point(|export| export.rva <= rva);
+		let last = self.exports[..end].iter().rposition(|export| !matches!(export.kind, ExportKind::Forwarder(_)))?;
+		let owner_rva = self.exports[last].rva;
+		if owner_rva < section.VirtualAddress.le() {
+			return None;
+		}
+		let first = self.exports[..last].partition_point(|export| export.rva < owner_rva);
+		Some(&self.exports[first])
+	}
+}
+impl<'a, P: Pe<'a>> fmt::Debug for RvaOrder<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("RvaOrder")
+			.field("dll_name", &format_args!("{:?}", self.by.dll_name()))
+			.field("exports.len", &self.exports.len())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Exported symbol compared by [`Exports::diff`](struct.Exports.html#method.diff).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DiffEntry<'a> {
+	/// Name of the export, `None` if exported by ordinal only.
+	pub name: Option<&'a CStr>,
+	/// Ordinal of the export.
+	pub ordinal: Ordinal,
+	/// The exported symbol or forwarder.
+	pub export: Export<'a>,
+}
+
+/// Differences between the exports of two versions of a module.
+///
+/// Useful to monitor the ABI compatibility of third party libraries.
+/// Removed exports break programs importing them, changed ordinals break programs importing them by ordinal.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportsDiff<'a> {
+	/// Exports only present in the new version.
+	pub added: Vec<DiffEntry<'a>>,
+	/// Exports only present in the old version.
+	///
+	/// Both lists are sorted, exports without a name by ordinal come first followed by the named exports by name.
+	pub removed: Vec<DiffEntry<'a>>,
+	/// Pairs of old and new exports whose ordinal changed or which are forwarded differently.
+	pub changed: Vec<(DiffEntry<'a>, DiffEntry<'a>)>,
+}
+impl<'a> ExportsDiff<'a> {
+	/// Returns true if the exports are the same.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+	/// Returns true if no exports were removed or changed.
+	pub fn is_compatible(&self) -> bool {
+		self.removed.is_empty() && self.changed.is_empty()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Export target of the [`Builder`](struct.Builder.html).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Target {
+	/// Rva of the exported symbol.
+	Symbol(Rva),
+	/// Forwarded to another dll, formatted as `"DllName.ExportName"` or `"DllName.#Ordinal"`.
+	Forward(String),
+}
+
+/// Export directory builder.
+///
+/// Collects the exports by ordinal and their names and serializes a complete export directory at a chosen rva.
+/// The names are sorted as the loader expects for its binary search, forwarder strings are placed within the directory.
+///
+/// ```
+/// use pelite::pe64::exports::{Builder, Target};
+///
+/// let mut builder = Builder::new("stub.dll");
+/// let init = builder.push("Init", Target::Symbol(0x1000));
+/// builder.push("Alloc", Target::Forward(String::from("NTDLL.RtlAllocateHeap")));
+/// builder.insert(10, None, Target::Symbol(0x1040));
+/// assert_eq!(init, 1);
+///
+/// // Place the bytes in the image at the rva and point the export data directory at them
+/// let bytes = builder.build(0x5000);
+/// assert_eq!(&bytes[0x10..0x14], &1u32.to_le_bytes()); // Base
+/// assert_eq!(&bytes[0x14..0x18], &10u32.to_le_bytes()); // NumberOfFunctions
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Builder {
+	dll_name: String,
+	time_date_stamp: u32,
+	functions: BTreeMap<Ordinal, Target>,
+	names: BTreeMap<String, Ordinal>,
+}
+impl Builder {
+	/// Creates an empty builder.
+	pub fn new(dll_name: &str) -> Builder {
+		Builder { dll_name: String::from(dll_name), ..Builder::default() }
+	}
+	/// Creates a builder with the exports of an existing directory.
+	///
+	/// Returns [`Err(Encoding)`](../../enum.Error.html#variant.Encoding) if any of the names or forwarders is not valid UTF-8.
+	pub fn from_exports<'a, P: Pe<'a>>(exports: &Exports<'a, P>) -> Result<Builder> {
+		let by = exports.by()?;
+		let mut builder = Builder::new(exports.dll_name()?.to_str()?);
+		builder.time_date_stamp = exports.image().TimeDateStamp.le();
+		for index in 0..by.functions().len() {
+			let ordinal = by.ordinal_base().wrapping_add(index as Ordinal);
+			match by.index(index) {
+				Ok(Export::Symbol(&rva)) => builder.insert(ordinal, None, Target::Symbol(rva)),
+				Ok(Export::Forward(name)) => builder.insert(ordinal, None, Target::Forward(String::from(name.to_str()?))),
+				Err(Error::Null) => continue,
+				Err(err) => return Err(err),
+			};
+		}
+		for (name, index) in by.iter_name_indices() {
+			let ordinal = by.ordinal_base().wrapping_add(index as Ordinal);
+			builder.names.insert(String::from(name?.to_str()?), ordinal);
+		}
+		Ok(builder)
+	}
+	/// Sets the time date stamp of the export directory.
+	pub fn set_time_date_stamp(&mut self, time_date_stamp: u32) {
+		self.time_date_stamp = time_date_stamp;
+	}
+	/// Returns the number of exports.
+	pub fn len(&self) -> usize {
+		self.functions.len()
+	}
+	/// Returns true if there are no exports.
+	pub fn is_empty(&self) -> bool {
+		self.functions.is_empty()
+	}
+	/// Inserts an export with an optional name, returns the target it replaced.
+	///
+	/// An export may be given multiple names by inserting it again with another name.
+	pub fn insert(&mut self, ordinal: Ordinal, name: Option<&str>, target: Target) -> Option<Target> {
+		if let Some(name) = name {
+			self.names.insert(String::from(name), ordinal);
+		}
+		self.functions.insert(ordinal, target)
+	}
+	/// Adds a named export with the ordinal following the highest ordinal so far, returns its ordinal.
+	pub fn push(&mut self, name: &str, target: Target) -> Ordinal {
+		let ordinal = self.functions.keys().next_back().map_or(1, |&ordinal| ordinal.wrapping_add(1));
+		self.insert(ordinal, Some(name), target);
+		ordinal
+	}
+	/// Serializes the export directory to be placed in the image at the given rva.
+	///
+	/// The export data directory must cover the returned bytes for the loader to recognize the forwarders.
+	/// The ordinal base is the lowest ordinal, gaps in the ordinals are left as null entries.
+	pub fn build(&self, rva: Rva) -> Vec<u8> {
+		let base = self.functions.keys().next().copied().unwrap_or(1);
+		let count = self.functions.keys().next_back().map_or(0, |&last| (last - base) as usize + 1);
+		let functions_offset = mem::size_of::<IMAGE_EXPORT_DIRECTORY>();
+		let names_offset = functions_offset + count * 4;
+		let name_indices_offset = names_offset + self.names.len() * 4;
+		let mut bytes = vec![0u8; name_indices_offset + self.names.len() * 2];
+		fn write(bytes: &mut [u8], offset: usize, value: u32) {
+			bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+		}
+		fn push_str(bytes: &mut Vec<u8>, rva: Rva, string: &str) -> Rva {
+			let string_rva = rva.wrapping_add(bytes.len() as Rva);
+			bytes.extend_from_slice(string.as_bytes());
+			bytes.push(0);
+			string_rva
+		}
+
+		let name_rva = push_str(&mut bytes, rva, &self.dll_name);
+		for (&ordinal, target) in &self.functions {
+			let value = match target {
+				&Target::Symbol(rva) => rva,
+				Target::Forward(forward) => push_str(&mut bytes, rva, forward),
+			};
+			write(&mut bytes, functions_offset + (ordinal - base) as usize * 4, value);
+		}
+		for (hint, (name, &ordinal)) in self.names.iter().enumerate() {
+			let name_rva = push_str(&mut bytes, rva, name);
+			write(&mut bytes, names_offset + hint * 4, name_rva);
+			let offset = name_indices_offset + hint * 2;
+			bytes[offset..offset + 2].copy_from_slice(&(ordinal.wrapping_sub(base)).to_le_bytes());
+		}
+
+		let fields = [
+			0, self.time_date_stamp, 0, name_rva, base as u32, count as u32, self.names.len() as u32,
+			rva.wrapping_add(functions_offset as Rva), rva.wrapping_add(names_offset as Rva), rva.wrapping_add(name_indices_offset as Rva),
+		];
+		for (i, &field) in fields.iter().enumerate() {
+			write(&mut bytes, i * 4, field);
+		}
+		bytes
+	}
+}
+
+//----------------------------------------------------------------
+
 /// Convenient way to get an exported address.
 pub trait GetProcAddress<'a, T>: Pe<'a> {
 	/// Convenient method to get an exported function.
@@ -421,6 +890,7 @@ impl<'b, 'a, P: Pe<'a>, S: AsRef<[u8]> + ?Sized> GetProcAddress<'a, &'b S> for P
 #[cfg(feature = "serde")]
 mod serde {
 	use crate::util::serde_helper::*;
+	use crate::util::Le;
 
 	use super::{By, Exports, Pe};
 
@@ -433,8 +903,8 @@ mod serde {
 		fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
 			let mut state = serializer.serialize_struct("Exports", 6)?;
 			state.serialize_field("dll_name", &self.dll_name().ok())?;
-			state.serialize_field("time_date_stamp", &self.image.TimeDateStamp)?;
-			state.serialize_field("version", &self.image.Version)?;
+			state.serialize_field("time_date_stamp", &self.image.TimeDateStamp.le())?;
+			state.serialize_field("version", &self.image.Version.le())?;
 			state.serialize_field("ordinal_base", &self.ordinal_base())?;
 			state.serialize_field("functions", &self.functions())?;
 			let names = self
@@ -468,6 +938,7 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 		}
 	}
 
+	let name_index = by.name_index();
 	for (hint, (name, export)) in by.iter_names().enumerate() {
 		// println!("hint:{:?} name:{:?} export:{:?}", hint, name, export);
 
@@ -489,6 +960,10 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 			// Lookup the export by its name and hint
 			assert_eq!(export, by.hint_name(hint, name));
 			assert_eq!(export, by.import(Import::ByName { hint, name }));
+			if unique {
+				assert_eq!(export, name_index.hint_name(hint, name));
+				assert_eq!(export, name_index.import(Import::ByName { hint: 0, name }));
+			}
 			if sorted && unique {
 				assert_eq!(export, by.hint_name(0, name));
 				assert_eq!(export, by.import(Import::ByName { hint: 0, name }));
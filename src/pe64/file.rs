@@ -4,15 +4,20 @@ PE file.
 
 use std::prelude::v1::*;
 
-use crate::Result;
+use std::cmp;
 
-use super::pe::validate_headers;
+use crate::util::Le;
+use crate::{ParseOptions, Result, Violations};
+
+use super::pe::{is_low_alignment, loader_section, validate_headers_lax};
 use super::{Align, Pe, PeObject};
 
 /// View into an unmapped PE file.
 #[derive(Copy, Clone)]
 pub struct PeFile<'a> {
 	image: &'a [u8],
+	options: ParseOptions,
+	violations: Violations,
 }
 
 impl<'a> PeFile<'a> {
@@ -35,36 +40,72 @@ impl<'a> PeFile<'a> {
 	/// * [`Insanity`](../enum.Error.html#variant.Insanity):
 	///   Reasonable limits on `e_lfanew`, `SizeOfHeaders` or `NumberOfSections` are exceeded.
 	pub fn from_bytes<T: AsRef<[u8]> + ?Sized>(image: &'a T) -> Result<PeFile<'a>> {
+		PeFile::from_bytes_with_options(image, ParseOptions::DEFAULT)
+	}
+	/// Constructs a file view from a byte slice with custom parse options.
+	///
+	/// See [`from_bytes`](#method.from_bytes) for a list of errors.
+	pub fn from_bytes_with_options<T: AsRef<[u8]> + ?Sized>(image: &'a T, options: ParseOptions) -> Result<PeFile<'a>> {
 		let image = image.as_ref();
-		let _ = validate_headers(image)?;
-		Ok(PeFile { image })
+		let (_, violations) = validate_headers_lax(image, &options)?;
+		Ok(PeFile { image, options, violations })
+	}
+	/// Constructs a file view from a byte slice, tolerating header violations the Windows loader accepts.
+	///
+	/// Packed and malformed files often violate the limits checked by [`from_bytes`](#method.from_bytes) while loading just fine.
+	/// The violations are recorded, inspect them with [`violations`](#method.violations).
+	///
+	/// The errors which remain guard against out of bounds access of the headers, see [`from_bytes`](#method.from_bytes).
+	pub fn from_bytes_lax<T: AsRef<[u8]> + ?Sized>(image: &'a T) -> Result<PeFile<'a>> {
+		PeFile::from_bytes_with_options(image, ParseOptions::LENIENT)
+	}
+	// Constructs a file view of an image whose headers were already validated with the options
+	pub(crate) unsafe fn from_validated(image: &'a [u8], options: ParseOptions, violations: Violations) -> PeFile<'a> {
+		PeFile { image, options, violations }
+	}
+	/// Returns the header violations tolerated by the lenient constructor.
+	///
+	/// Always empty unless the file was parsed with lenient options.
+	pub fn violations(&self) -> Violations {
+		self.violations
 	}
 	/// Converts the file to section alignment.
 	pub fn to_view(self) -> Vec<u8> {
 		let (sizeof_headers, sizeof_image) = {
 			let optional_header = self.optional_header();
-			(optional_header.SizeOfHeaders, optional_header.SizeOfImage)
+			(optional_header.SizeOfHeaders.le(), optional_header.SizeOfImage.le())
 		};
 
 		// Zero fill the underlying image
 		let mut vec = vec![0u8; sizeof_image as usize];
 
-		// Start by copying the headers
+		// Low alignment images are mapped as is
 		let image = self.image();
+		if is_low_alignment(self.optional_header()) {
+			let len = cmp::min(vec.len(), image.len());
+			vec[..len].copy_from_slice(&image[..len]);
+			return vec;
+		}
+
+		// Start by copying the headers
+		// Lenient parsing allows the headers to exceed the image
+		let sizeof_headers = cmp::min(sizeof_headers as usize, cmp::min(vec.len(), image.len()));
 		unsafe {
-			// Validated by constructor
-			let dest_headers = vec.get_unchecked_mut(..sizeof_headers as usize);
-			let src_headers = image.get_unchecked(..sizeof_headers as usize);
+			let dest_headers = vec.get_unchecked_mut(..sizeof_headers);
+			let src_headers = image.get_unchecked(..sizeof_headers);
 			dest_headers.copy_from_slice(src_headers);
 		}
 
 		// Copy the section file data
 		for section in self.section_headers() {
+			let section = loader_section(section, self.optional_header(), self.options.lenient);
 			let dest = vec.get_mut(section.VirtualAddress as usize..u32::wrapping_add(section.VirtualAddress, section.VirtualSize) as usize);
 			let src = image.get(section.PointerToRawData as usize..u32::wrapping_add(section.PointerToRawData, section.SizeOfRawData) as usize);
 			// Skip invalid sections...
 			if let (Some(dest), Some(src)) = (dest, src) {
-				dest.copy_from_slice(src);
+				// Only the smaller of the virtual size and the size of raw data is present in both
+				let len = cmp::min(dest.len(), src.len());
+				dest[..len].copy_from_slice(&src[..len]);
 			}
 		}
 
@@ -85,7 +126,10 @@ unsafe impl<'a> PeObject<'a> for PeFile<'a> {
 	}
 
 	fn image_base(&self) -> super::Va {
-		self.optional_header().ImageBase
+		self.optional_header().ImageBase.le()
+	}
+	fn options(&self) -> ParseOptions {
+		self.options
 	}
 
 	#[cfg(feature = "serde")]
@@ -2,9 +2,14 @@
 PE headers.
  */
 
+use std::cmp;
 use std::ops::Range;
 use std::slice;
 
+use crate::stringify::{DllChars, FileChars, Machine, Subsystem};
+use crate::timestamp::TimeDateStamp;
+use crate::util::Le;
+
 use super::image::*;
 use super::Pe;
 
@@ -26,14 +31,17 @@ impl<'a, P: Pe<'a>> Headers<P> {
 	}
 	/// Gets the PE headers as a byte slice.
 	pub fn image(&self) -> &'a [u8] {
-		unsafe { self.pe.image().get_unchecked(..self.pe.optional_header().SizeOfHeaders as usize) }
+		let image = self.pe.image();
+		// Lenient parsing allows the headers to exceed the image
+		let size_of_headers = cmp::min(self.pe.optional_header().SizeOfHeaders.le() as usize, image.len());
+		unsafe { image.get_unchecked(..size_of_headers) }
 	}
 	/// Calculates the optional header's CheckSum.
 	pub fn check_sum(&self) -> u32 {
 		let image = self.pe.image();
 		#[rustfmt::skip]
 		let check_sum_position = (
-			self.pe.dos_header().e_lfanew as usize +
+			self.pe.dos_header().e_lfanew.le() as usize +
 			dataview::offset_of!(IMAGE_NT_HEADERS.OptionalHeader) +
 			dataview::offset_of!(IMAGE_OPTIONAL_HEADER.CheckSum)) / 4;
 		let dwords = unsafe { slice::from_raw_parts(image.as_ptr() as *const u32, image.len() / 4) };
@@ -56,15 +64,113 @@ impl<'a, P: Pe<'a>> Headers<P> {
 
 		check_sum as u32
 	}
+	/// Gets the typed machine from the file header.
+	pub fn machine(&self) -> Machine {
+		Machine(self.pe.file_header().Machine.le())
+	}
+	/// Gets the typed characteristics from the file header.
+	pub fn file_characteristics(&self) -> FileChars {
+		FileChars(self.pe.file_header().Characteristics.le())
+	}
+	/// Decodes the file header's TimeDateStamp.
+	///
+	/// Reproducible builds are detected by the presence of an `IMAGE_DEBUG_TYPE_REPRO` debug directory entry.
+	pub fn time_date_stamp(&self) -> TimeDateStamp {
+		let repro = match self.pe.debug() {
+			Ok(debug) => debug.image().iter().any(|dir| dir.Type.le() == IMAGE_DEBUG_TYPE_REPRO),
+			Err(_) => false,
+		};
+		TimeDateStamp::new(self.pe.file_header().TimeDateStamp.le(), repro)
+	}
+	/// Gets the typed subsystem from the optional header.
+	pub fn subsystem(&self) -> Subsystem {
+		Subsystem(self.pe.optional_header().Subsystem.le())
+	}
+	/// Gets the typed dll characteristics from the optional header.
+	pub fn dll_characteristics(&self) -> DllChars {
+		DllChars(self.pe.optional_header().DllCharacteristics.le())
+	}
 	/// Gets the code range from the optional header.
 	pub fn code_range(&self) -> Range<Rva> {
 		let optional_header = self.pe.optional_header();
-		optional_header.BaseOfCode..u32::wrapping_add(optional_header.BaseOfCode, optional_header.SizeOfCode)
+		optional_header.BaseOfCode.le()..u32::wrapping_add(optional_header.BaseOfCode.le(), optional_header.SizeOfCode.le())
 	}
 	/// Gets the full image range excluding the PE headers.
 	pub fn image_range(&self) -> Range<Rva> {
 		let optional_header = self.pe.optional_header();
-		optional_header.SizeOfHeaders..optional_header.SizeOfImage
+		optional_header.SizeOfHeaders.le()..optional_header.SizeOfImage.le()
+	}
+	/// Summarizes the headers.
+	pub fn summary(&self) -> Summary {
+		let file_header = self.pe.file_header();
+		let optional_header = self.pe.optional_header();
+		let directories = self.pe.data_directory().iter()
+			.enumerate()
+			.filter(|(_, datadir)| datadir.VirtualAddress.le() != 0)
+			.fold(0u16, |bits, (index, _)| bits | 1 << index);
+		Summary {
+			machine: file_header.Machine.le(),
+			bitness: Va::BITNESS,
+			characteristics: file_header.Characteristics.le(),
+			subsystem: optional_header.Subsystem.le(),
+			dll_characteristics: optional_header.DllCharacteristics.le(),
+			entry_point: optional_header.AddressOfEntryPoint.le(),
+			image_base: optional_header.ImageBase.le().to_u64(),
+			size_of_image: optional_header.SizeOfImage.le(),
+			size_of_headers: optional_header.SizeOfHeaders.le(),
+			size_of_code: optional_header.SizeOfCode.le(),
+			time_date_stamp: file_header.TimeDateStamp.le(),
+			check_sum: optional_header.CheckSum.le(),
+			number_of_sections: file_header.NumberOfSections.le(),
+			directories,
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Compact summary of the PE headers.
+///
+/// Created with the method [`Headers::summary`](struct.Headers.html#method.summary).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Summary {
+	/// The `IMAGE_FILE_MACHINE_*` machine type.
+	pub machine: u16,
+	/// Either 32 for PE32 or 64 for PE32+ images.
+	pub bitness: u32,
+	/// The `IMAGE_FILE_*` file characteristics.
+	pub characteristics: u16,
+	/// The `IMAGE_SUBSYSTEM_*` subsystem.
+	pub subsystem: u16,
+	/// The `IMAGE_DLLCHARACTERISTICS_*` dll characteristics.
+	pub dll_characteristics: u16,
+	/// Rva of the entry point, zero if there is none.
+	pub entry_point: Rva,
+	/// The preferred load address.
+	pub image_base: u64,
+	pub size_of_image: u32,
+	pub size_of_headers: u32,
+	pub size_of_code: u32,
+	pub time_date_stamp: u32,
+	/// The checksum as stored in the optional header, see [`Headers::check_sum`](struct.Headers.html#method.check_sum) to calculate it.
+	pub check_sum: u32,
+	pub number_of_sections: u16,
+	/// Bitmap of the present data directories, bit `n` is set if data directory entry `n` has a non-zero address.
+	pub directories: u16,
+}
+impl Summary {
+	/// Returns true if the data directory entry is present.
+	pub fn has_directory(&self, index: usize) -> bool {
+		index < 16 && self.directories & (1 << index) != 0
+	}
+	/// Gets the typed machine.
+	pub fn machine(&self) -> Machine {
+		Machine(self.machine)
+	}
+	/// Gets the typed subsystem.
+	pub fn subsystem(&self) -> Subsystem {
+		Subsystem(self.subsystem)
 	}
 }
 
@@ -95,6 +201,7 @@ impl<'a, P: Pe<'a>> Headers<P> {
 mod serde {
 	use crate::stringify;
 	use crate::util::serde_helper::*;
+	use crate::util::Le;
 
 	use super::{Headers, Pe};
 
@@ -121,28 +228,30 @@ mod serde {
 			state.serialize_field("NtHeaders.Signature", "PE")?;
 
 			let file_header = self.pe.file_header();
-			state.serialize_field("FileHeader.Machine", &stringify::Machine(file_header.Machine).to_str())?;
-			state.serialize_field("FileHeader.Characteristics", &SerdeIter(stringify::FileChars(file_header.Characteristics).to_strs()))?;
+			state.serialize_field("FileHeader.Machine", &stringify::Machine(file_header.Machine.le()).to_str())?;
+			state.serialize_field("FileHeader.Characteristics", &SerdeIter(stringify::FileChars(file_header.Characteristics.le()).to_strs()))?;
 
 			let optional_header = self.pe.optional_header();
-			state.serialize_field("OptionalHeader.Magic", &stringify::OptionalMagic(optional_header.Magic).to_str())?;
+			state.serialize_field("OptionalHeader.Magic", &stringify::OptionalMagic(optional_header.Magic.le()).to_str())?;
 			state.serialize_field("OptionalHeader.CheckSum", &Headers { pe: self.pe }.check_sum())?;
-			state.serialize_field("OptionalHeader.Subsystem", &stringify::Subsystem(optional_header.Subsystem).to_str())?;
-			state.serialize_field("OptionalHeader.DllCharacteristics", &SerdeIter(stringify::DllChars(optional_header.DllCharacteristics).to_strs()))?;
+			state.serialize_field("OptionalHeader.Subsystem", &stringify::Subsystem(optional_header.Subsystem.le()).to_str())?;
+			state.serialize_field("OptionalHeader.DllCharacteristics", &SerdeIter(stringify::DllChars(optional_header.DllCharacteristics.le()).to_strs()))?;
 
 			let data_directory_names = (0..self.pe.data_directory().len()).map(stringify::DirectoryEntry).map(stringify::DirectoryEntry::to_str);
 			state.serialize_field("DataDirectory.Names", &SerdeIter(data_directory_names))?;
 
 			let data_directory_sects = self.pe.data_directory().iter().map(|dd| {
+				let dd = dd.le();
 				self.pe
 					.section_headers()
 					.iter()
-					.position(|&sect| dd.VirtualAddress >= sect.VirtualAddress && dd.VirtualAddress < sect.VirtualAddress + sect.VirtualSize)
+					.map(|sect| sect.le())
+					.position(|sect| dd.VirtualAddress >= sect.VirtualAddress && dd.VirtualAddress - sect.VirtualAddress < sect.VirtualSize)
 			});
 			state.serialize_field("DataDirectory.Sections", &SerdeIter(data_directory_sects))?;
 
 			let sections_chars = self.pe.section_headers().iter().map(|sect| {
-				let section_chars = sect.Characteristics;
+				let section_chars = sect.Characteristics.le();
 				SerdeIter(stringify::SectionChars(section_chars).to_strs())
 			});
 			state.serialize_field("SectionHeaders.Characteristics", &SerdeIter(sections_chars))?;
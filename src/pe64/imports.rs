@@ -41,13 +41,15 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 ```
 */
 
+use std::prelude::v1::*;
+use std::ops::Range;
 use std::{fmt, iter, mem, slice};
 
-use crate::util::CStr;
+use crate::util::{AlignTo, CStr, Le};
 use crate::{Error, Result};
 
 use super::image::*;
-use super::Pe;
+use super::{Align, Pe};
 
 //----------------------------------------------------------------
 
@@ -57,13 +59,14 @@ pub use crate::wrap::imports::Import;
 //
 // These aren't actually virtual addresses.
 // This function will decode them to get the import.
-fn import_from_va<'a, P: Pe<'a>>(pe: P, &va: &'a Va) -> Result<Import<'a>> {
-	if va & IMAGE_ORDINAL_FLAG == 0 {
+pub(crate) fn import_from_va<'a, P: Pe<'a>>(pe: P, &va: &'a Va) -> Result<Import<'a>> {
+	let va = va.le();
+	if !va.is_ordinal() {
 		// TODO! Validate that this really is an Rva in PE32+?
 		let rva = va as Rva;
-		let hint = pe.derva::<u16>(rva)?;
+		let hint = pe.derva_copy::<u16>(rva)?.le();
 		let name = pe.derva_c_str(rva + 2)?;
-		Ok(Import::ByName { hint: *hint as usize, name })
+		Ok(Import::ByName { hint: hint as usize, name })
 	}
 	else {
 		Ok(Import::ByOrdinal { ord: va as Ordinal })
@@ -83,7 +86,10 @@ pub struct Imports<'a, P> {
 impl<'a, P: Pe<'a>> Imports<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<Imports<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_IMPORT).ok_or(Error::Bounds)?;
-		let image = pe.derva_slice_f(datadir.VirtualAddress, |image: &IMAGE_IMPORT_DESCRIPTOR| image.is_null())?;
+		let image = pe.derva_slice_f(datadir.VirtualAddress.le(), |image: &IMAGE_IMPORT_DESCRIPTOR| image.is_null())?;
+		if image.len() > pe.options().max_import_descriptors {
+			return Err(Error::Insanity);
+		}
 		Ok(Imports { pe, image })
 	}
 	/// Gets the PE instance.
@@ -98,6 +104,22 @@ impl<'a, P: Pe<'a>> Imports<'a, P> {
 	pub fn iter(&self) -> Iter<'a, P> {
 		Iter { pe: self.pe, iter: self.image.iter() }
 	}
+	/// Validates every import descriptor.
+	///
+	/// Reads the DLL names, the import address tables and every entry of the import name tables.
+	/// Descriptors without an import name table are accepted, older linkers only emit the import address table.
+	pub fn validate(&self) -> Result<()> {
+		for desc in self.iter() {
+			desc.dll_name()?;
+			let _ = desc.iat()?;
+			if desc.image.OriginalFirstThunk.le() != 0 {
+				for import in desc.int()? {
+					import?;
+				}
+			}
+		}
+		Ok(())
+	}
 }
 impl<'a, P: Pe<'a>> IntoIterator for Imports<'a, P> {
 	type Item = Desc<'a, P>;
@@ -129,7 +151,7 @@ impl<'a, P: Pe<'a>> IAT<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<IAT<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_IAT).ok_or(Error::Bounds)?;
 		// Ignore datadir.Size not being a multiple of sizeof(Va), not that big of a deal...
-		let image = pe.derva_slice(datadir.VirtualAddress, datadir.Size as usize / mem::size_of::<Va>())?;
+		let image = pe.derva_slice(datadir.VirtualAddress.le(), datadir.Size.le() as usize / mem::size_of::<Va>())?;
 		Ok(IAT { pe, image })
 	}
 	/// Gets the PE instance.
@@ -211,7 +233,7 @@ impl<'a, P: Pe<'a>> Desc<'a, P> {
 	}
 	/// Gets the name of the DLL imported from.
 	pub fn dll_name(&self) -> Result<&'a CStr> {
-		self.pe.derva_c_str(self.image.Name)
+		self.pe.derva_c_str(self.image.Name.le())
 	}
 	/// Gets the import address table.
 	///
@@ -220,15 +242,99 @@ impl<'a, P: Pe<'a>> Desc<'a, P> {
 	/// Otherwise these contain references to the imported functions.
 	/// See [`import_from_va`](struct.Desc.html#import_from_va) to get their names.
 	pub fn iat(&self) -> Result<slice::Iter<'a, Va>> {
-		let slice = self.pe.derva_slice_s(self.image.FirstThunk, 0)?;
+		let slice = self.pe.derva_slice_s(self.image.FirstThunk.le(), 0)?;
 		Ok(slice.iter())
 	}
 	/// Gets the import name table.
 	pub fn int(&self) -> Result<iter::Map<slice::Iter<'a, Va>, impl Clone + FnMut(&'a Va) -> Result<Import<'a>>>> {
-		let slice = self.pe.derva_slice_s(self.image.OriginalFirstThunk, 0)?;
+		let slice = self.pe.derva_slice_s(self.image.OriginalFirstThunk.le(), 0)?;
 		let pe = self.pe;
 		Ok(slice.iter().map(move |va| import_from_va(pe, va)))
 	}
+	/// Gets the imported symbols from the import name table, falling back to the import address table.
+	///
+	/// Packers often clear or corrupt the import name table and only keep the import address table.
+	/// Before the imports are resolved the import address table is a copy of the import name table,
+	/// this is only assumed for file aligned images as the import address table of a loaded image contains the resolved addresses.
+	///
+	/// The error of the import name table is returned if the fallback fails as well.
+	pub fn int_or_iat(&self) -> Result<Thunks<'a, P>> {
+		let pe = self.pe;
+		let is_valid = |slice: &'a [Va]| slice.iter().try_for_each(|va| import_from_va(pe, va).map(|_| ()));
+		let int = match self.image.OriginalFirstThunk.le() {
+			0 => Err(Error::Null),
+			rva => pe.derva_slice_s::<Va>(rva, 0),
+		};
+		let (source, slice) = match int.and_then(|slice| is_valid(slice).map(|_| slice)) {
+			Ok(slice) => (ThunkSource::Int, slice),
+			Err(err) => {
+				if pe.align() != Align::File {
+					return Err(err);
+				}
+				let slice = pe.derva_slice_s::<Va>(self.image.FirstThunk.le(), 0).map_err(|_| err)?;
+				is_valid(slice).map_err(|_| err)?;
+				(ThunkSource::Iat, slice)
+			},
+		};
+		Ok(Thunks { pe, source, iter: slice.iter() })
+	}
+}
+/// Table the imported symbols of a descriptor are read from.
+///
+/// See [`Desc::int_or_iat`](struct.Desc.html#method.int_or_iat) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ThunkSource {
+	/// The import name table.
+	Int,
+	/// The import address table, the import name table is null or corrupt.
+	Iat,
+}
+
+/// Imported symbols of a descriptor.
+///
+/// See [`Desc::int_or_iat`](struct.Desc.html#method.int_or_iat) for more information.
+#[derive(Clone)]
+pub struct Thunks<'a, P> {
+	pe: P,
+	source: ThunkSource,
+	iter: slice::Iter<'a, Va>,
+}
+impl<'a, P: Pe<'a>> Thunks<'a, P> {
+	/// Returns the table the symbols are read from.
+	pub fn source(&self) -> ThunkSource {
+		self.source
+	}
+	/// Returns the remaining thunks.
+	pub fn image(&self) -> &'a [Va] {
+		self.iter.as_slice()
+	}
+}
+impl<'a, P: Pe<'a>> Iterator for Thunks<'a, P> {
+	type Item = Result<Import<'a>>;
+	fn next(&mut self) -> Option<Result<Import<'a>>> {
+		let pe = self.pe;
+		self.iter.next().map(|va| import_from_va(pe, va))
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+impl<'a, P: Pe<'a>> DoubleEndedIterator for Thunks<'a, P> {
+	fn next_back(&mut self) -> Option<Result<Import<'a>>> {
+		let pe = self.pe;
+		self.iter.next_back().map(|va| import_from_va(pe, va))
+	}
+}
+impl<'a, P: Pe<'a>> ExactSizeIterator for Thunks<'a, P> {}
+impl<'a, P: Pe<'a>> iter::FusedIterator for Thunks<'a, P> {}
+#[rustfmt::skip]
+impl<'a, P: Pe<'a>> fmt::Debug for Thunks<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Thunks")
+			.field("source", &self.source)
+			.field("len", &self.iter.len())
+			.finish()
+	}
 }
 #[rustfmt::skip]
 impl<'a, P: Pe<'a>> fmt::Debug for Desc<'a, P> {
@@ -243,6 +349,165 @@ impl<'a, P: Pe<'a>> fmt::Debug for Desc<'a, P> {
 
 //----------------------------------------------------------------
 
+/// Imported symbol of the [`Builder`](struct.Builder.html).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Symbol {
+	/// Imported by name, the hint is the index of the name in the export name table of the DLL or zero if unknown.
+	Name { hint: u16, name: String },
+	/// Imported by ordinal.
+	Ordinal(Ordinal),
+}
+
+/// Import directory builder.
+///
+/// Collects the imported symbols by DLL and serializes the import descriptors, the import address table, the import name tables,
+/// the hint/name entries and the DLL names at a chosen rva, without requiring an image to edit.
+///
+/// The bytes are laid out in that order, the import address table is a single contiguous array with the symbols of every DLL followed by a null entry.
+///
+/// ```
+/// use pelite::pe64::imports::{Builder, Symbol};
+///
+/// let mut builder = Builder::new();
+/// builder.add("KERNEL32.dll", Symbol::Name { hint: 0, name: String::from("LoadLibraryA") });
+/// builder.add("KERNEL32.dll", Symbol::Name { hint: 0, name: String::from("GetProcAddress") });
+/// builder.add("WS2_32.dll", Symbol::Ordinal(23));
+///
+/// // Point the import and IAT data directories at these rvas
+/// let rva = 0x5000;
+/// let bytes = builder.build(rva);
+/// assert_eq!(builder.descriptors_size(), 3 * 20);
+/// assert_eq!(builder.iat(rva), 0x5040..0x5068);
+/// assert_eq!(builder.thunk(rva, "WS2_32.dll", 0), Some(0x5058));
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Builder {
+	descriptors: Vec<(String, Vec<Symbol>)>,
+}
+impl Builder {
+	/// Creates an empty builder.
+	pub fn new() -> Builder {
+		Builder::default()
+	}
+	/// Creates a builder with the imports of an existing directory.
+	///
+	/// Descriptors without an import name table take their symbols from the unbound import address table.
+	/// Returns [`Err(Encoding)`](../../enum.Error.html#variant.Encoding) if any of the names is not valid UTF-8.
+	pub fn from_imports<'a, P: Pe<'a>>(imports: &Imports<'a, P>) -> Result<Builder> {
+		let mut builder = Builder::new();
+		for desc in imports.iter() {
+			let dll_name = desc.dll_name()?.to_str()?;
+			let thunks = match desc.image.OriginalFirstThunk.le() {
+				0 => desc.iat()?.as_slice(),
+				rva => desc.pe.derva_slice_s(rva, 0)?,
+			};
+			let symbols = thunks.iter()
+				.map(|va| match import_from_va(desc.pe, va)? {
+					Import::ByName { hint, name } => Ok(Symbol::Name { hint: hint as u16, name: String::from(name.to_str()?) }),
+					Import::ByOrdinal { ord } => Ok(Symbol::Ordinal(ord)),
+				})
+				.collect::<Result<Vec<_>>>()?;
+			builder.descriptors.push((String::from(dll_name), symbols));
+		}
+		Ok(builder)
+	}
+	/// Returns the number of import descriptors.
+	pub fn len(&self) -> usize {
+		self.descriptors.len()
+	}
+	/// Returns true if there are no import descriptors.
+	pub fn is_empty(&self) -> bool {
+		self.descriptors.is_empty()
+	}
+	/// Adds a symbol imported from the DLL.
+	///
+	/// The DLL names are compared ignoring ASCII case, the symbols of a DLL are imported in the order they are added.
+	pub fn add(&mut self, dll_name: &str, symbol: Symbol) {
+		match self.descriptors.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(dll_name)) {
+			Some((_, symbols)) => symbols.push(symbol),
+			None => self.descriptors.push((String::from(dll_name), vec![symbol])),
+		}
+	}
+	/// Iterates over the DLL names and their symbols.
+	pub fn iter(&self) -> impl '_ + Clone + Iterator<Item = (&'_ str, &'_ [Symbol])> {
+		self.descriptors.iter().map(|(dll_name, symbols)| (&**dll_name, &**symbols))
+	}
+	/// Returns the size of the import descriptors including the null terminator, the size of the import data directory.
+	pub fn descriptors_size(&self) -> usize {
+		(self.descriptors.len() + 1) * mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>()
+	}
+	/// Returns the range of the import address table when built at the given rva, the range of the IAT data directory.
+	pub fn iat(&self, rva: Rva) -> Range<Rva> {
+		let start = rva.wrapping_add(self.descriptors_size().align_to(mem::size_of::<Va>()) as Rva);
+		start..start.wrapping_add(self.thunks_size() as Rva)
+	}
+	/// Returns the rva of the import address table entry of a symbol when built at the given rva.
+	///
+	/// The loader writes the address of the symbol there, code calls the symbol indirectly through it.
+	pub fn thunk(&self, rva: Rva, dll_name: &str, index: usize) -> Option<Rva> {
+		let mut offset = 0;
+		for (name, symbols) in &self.descriptors {
+			if name.eq_ignore_ascii_case(dll_name) {
+				return if index < symbols.len() { Some(self.iat(rva).start.wrapping_add(((offset + index) * mem::size_of::<Va>()) as Rva)) } else { None };
+			}
+			offset += symbols.len() + 1;
+		}
+		None
+	}
+	fn thunks_size(&self) -> usize {
+		self.descriptors.iter().map(|(_, symbols)| (symbols.len() + 1) * mem::size_of::<Va>()).sum()
+	}
+	/// Serializes the import directory to be placed in the image at the given rva.
+	///
+	/// Point the import data directory at the rva with the [`descriptors_size`](#method.descriptors_size)
+	/// and the IAT data directory at the [`iat`](#method.iat).
+	pub fn build(&self, rva: Rva) -> Vec<u8> {
+		const SIZE_OF_VA: usize = mem::size_of::<Va>();
+		let iat_offset = self.descriptors_size().align_to(SIZE_OF_VA);
+		let int_offset = iat_offset + self.thunks_size();
+		let mut bytes = vec![0u8; int_offset + self.thunks_size()];
+
+		let mut thunk_offset = 0;
+		for (index, (dll_name, symbols)) in self.descriptors.iter().enumerate() {
+			let first_thunk = rva.wrapping_add((iat_offset + thunk_offset) as Rva);
+			let original_first_thunk = rva.wrapping_add((int_offset + thunk_offset) as Rva);
+			for symbol in symbols {
+				let thunk = match symbol {
+					Symbol::Name { hint, name } => {
+						let hint_name_rva = rva.wrapping_add(bytes.len() as Rva);
+						bytes.extend_from_slice(&hint.to_le_bytes());
+						bytes.extend_from_slice(name.as_bytes());
+						bytes.push(0);
+						bytes.resize(bytes.len().align_to(2), 0);
+						hint_name_rva as Va
+					},
+					&Symbol::Ordinal(ordinal) => IMAGE_ORDINAL_FLAG | ordinal as Va,
+				};
+				// The unbound import address table is a copy of the import name table
+				for &offset in &[iat_offset, int_offset] {
+					bytes[offset + thunk_offset..offset + thunk_offset + SIZE_OF_VA].copy_from_slice(&thunk.to_le_bytes());
+				}
+				thunk_offset += SIZE_OF_VA;
+			}
+			thunk_offset += SIZE_OF_VA;
+
+			let name_rva = rva.wrapping_add(bytes.len() as Rva);
+			bytes.extend_from_slice(dll_name.as_bytes());
+			bytes.push(0);
+			bytes.resize(bytes.len().align_to(2), 0);
+
+			let offset = index * mem::size_of::<IMAGE_IMPORT_DESCRIPTOR>();
+			let fields = [original_first_thunk, 0, 0, name_rva, first_thunk];
+			for (i, field) in fields.iter().enumerate() {
+				bytes[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&field.to_le_bytes());
+			}
+		}
+		bytes
+	}
+}
+
+//----------------------------------------------------------------
+
 /*
 	imports: [
 		{
@@ -0,0 +1,243 @@
+/*!
+Cached section lookup.
+
+Every address translation on a [`PeFile`](../struct.PeFile.html) scans the section headers to find the section containing the address.
+For tight loops, such as applying relocations or resolving many pointers, build a [`SectionIndex`](struct.SectionIndex.html) once and wrap the PE with [`Indexed`](struct.Indexed.html).
+It implements [`Pe`](../trait.Pe.html) and finds sections with a binary search instead.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::index::{Indexed, SectionIndex};
+use pelite::util::Le;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let index = SectionIndex::new(file);
+	let file = Indexed::new(file, &index);
+
+	// Use the indexed PE like any other PE
+	let entry = file.rva_to_file_offset(file.optional_header().AddressOfEntryPoint.le())?;
+
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+
+use std::ops::Range;
+
+use crate::util::{AlignTo, Le};
+use crate::{Error, ParseOptions, Result};
+
+use super::image::*;
+use super::pe::*;
+use super::{Align, Pe, PeObject};
+
+//----------------------------------------------------------------
+
+/// Precomputed sorted section ranges.
+///
+/// Produces the same results as the linear scan in the `Pe` trait, including for overlapping sections where the first section header wins.
+/// If the sections overlap the index falls back to a linear scan of the ranges.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default)]
+pub struct SectionIndex {
+	virtual_ranges: Vec<(Range<Rva>, usize)>,
+	file_ranges: Vec<(Range<usize>, usize)>,
+	virtual_overlaps: bool,
+	file_overlaps: bool,
+}
+
+impl SectionIndex {
+	/// Builds the section index for the PE image.
+	pub fn new<'a, P: Pe<'a>>(pe: P) -> SectionIndex {
+		let (optional_header, lenient) = (pe.optional_header(), pe.options().lenient);
+		let section_headers: Vec<_> = pe.section_headers().image().iter().map(|it| loader_section(it, optional_header, lenient)).collect();
+		let virtual_ranges = section_headers.iter().map(section_rva_range).enumerate().map(|(i, range)| (range, i));
+		let file_ranges = section_headers.iter().map(section_file_range).enumerate().map(|(i, range)| (range, i));
+		let (virtual_ranges, virtual_overlaps) = sort_ranges(virtual_ranges.collect());
+		let (file_ranges, file_overlaps) = sort_ranges(file_ranges.collect());
+		SectionIndex { virtual_ranges, file_ranges, virtual_overlaps, file_overlaps }
+	}
+	/// Finds the index of the section header containing the rva.
+	pub fn section_of_rva(&self, rva: Rva) -> Option<usize> {
+		find(&self.virtual_ranges, self.virtual_overlaps, rva)
+	}
+	/// Finds the index of the section header containing the file offset.
+	pub fn section_of_file_offset(&self, file_offset: usize) -> Option<usize> {
+		find(&self.file_ranges, self.file_overlaps, file_offset)
+	}
+}
+
+// Removes the empty ranges, sorts them and checks if any of them overlap.
+// When they overlap the ranges are kept in section header order instead.
+fn sort_ranges<T: Copy + Ord>(mut ranges: Vec<(Range<T>, usize)>) -> (Vec<(Range<T>, usize)>, bool) {
+	ranges.retain(|(range, _)| range.start < range.end);
+	let mut sorted = ranges.clone();
+	sorted.sort_by_key(|(range, _)| range.start);
+	let overlaps = sorted.windows(2).any(|pair| pair[0].0.end > pair[1].0.start);
+	if overlaps { (ranges, true) } else { (sorted, false) }
+}
+
+fn find<T: Copy + Ord>(ranges: &[(Range<T>, usize)], overlaps: bool, value: T) -> Option<usize> {
+	if overlaps {
+		ranges.iter().find(|(range, _)| range.start <= value && value < range.end).map(|&(_, i)| i)
+	}
+	else {
+		// Find the last range starting at or before the value
+		let pos = match ranges.binary_search_by(|(range, _)| range.start.cmp(&value)) {
+			Ok(pos) => pos,
+			Err(0) => return None,
+			Err(pos) => pos - 1,
+		};
+		let (range, i) = &ranges[pos];
+		if value < range.end { Some(*i) } else { None }
+	}
+}
+
+//----------------------------------------------------------------
+
+/// PE image with cached section lookup.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct Indexed<'s, P> {
+	pe: P,
+	index: &'s SectionIndex,
+}
+
+impl<'s, 'a, P: Pe<'a>> Indexed<'s, P> {
+	/// Wraps the PE image with its section index.
+	///
+	/// The index must have been built from the same image.
+	pub fn new(pe: P, index: &'s SectionIndex) -> Indexed<'s, P> {
+		Indexed { pe, index }
+	}
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Gets the section index.
+	pub fn index(&self) -> &'s SectionIndex {
+		self.index
+	}
+	fn section_header(&self, index: usize) -> Result<IMAGE_SECTION_HEADER> {
+		// Guard against an index built from a different image
+		let it = self.pe.section_headers().image().get(index).ok_or(Error::Bounds)?;
+		Ok(loader_section(it, self.pe.optional_header(), self.pe.options().lenient))
+	}
+	fn range_file(&self, rva: Rva, min_size_of: usize, align: usize) -> Result<&'a [u8]> {
+		if !usize::wrapping_add(self.pe.image().as_ptr() as usize, rva as usize).aligned_to(align) {
+			return Err(Error::Misaligned);
+		}
+		let bytes = if is_low_alignment(self.pe.optional_header()) {
+			range_file_flat(self.pe.image(), self.pe.optional_header().SizeOfImage.le(), rva, min_size_of)?
+		}
+		else if self.pe.file_header().NumberOfSections.le() == 0 {
+			range_file_flat(self.pe.image(), self.pe.optional_header().SizeOfHeaders.le(), rva, min_size_of)?
+		}
+		else {
+			let index = self.index.section_of_rva(rva).ok_or(Error::Bounds)?;
+			range_file_section(self.pe.image(), &self.section_header(index)?, rva, min_size_of)?
+		};
+		check_aligned(bytes, align)
+	}
+}
+
+unsafe impl<'s, 'a, P: Pe<'a>> PeObject<'a> for Indexed<'s, P> {
+	fn image(&self) -> &'a [u8] {
+		self.pe.image()
+	}
+	fn align(&self) -> Align {
+		self.pe.align()
+	}
+	fn image_base(&self) -> Va {
+		self.pe.image_base()
+	}
+	fn options(&self) -> ParseOptions {
+		self.pe.options()
+	}
+	#[cfg(feature = "serde")]
+	fn serde_name(&self) -> &'static str {
+		self.pe.serde_name()
+	}
+}
+
+unsafe impl<'s, 'a, P: Pe<'a>> Pe<'a> for Indexed<'s, P> {
+	fn rva_to_file_offset(self, rva: Rva) -> Result<usize> {
+		// Consider rva inside headers to be valid
+		if rva < self.optional_header().SizeOfHeaders.le() {
+			return Ok(rva as usize);
+		}
+		if is_low_alignment(self.optional_header()) {
+			return self.pe.rva_to_file_offset(rva);
+		}
+		let index = self.index.section_of_rva(rva).ok_or(Error::Bounds)?;
+		section_rva_to_file_offset(&self.section_header(index)?, rva)
+	}
+	fn file_offset_to_rva(self, file_offset: usize) -> Result<Rva> {
+		// Consider file offset inside headers to be valid
+		if file_offset < self.optional_header().SizeOfHeaders.le() as usize {
+			return Ok(file_offset as Rva);
+		}
+		if is_low_alignment(self.optional_header()) {
+			return self.pe.file_offset_to_rva(file_offset);
+		}
+		let index = self.index.section_of_file_offset(file_offset).ok_or(Error::Bounds)?;
+		section_file_offset_to_rva(&self.section_header(index)?, file_offset)
+	}
+	fn slice(&self, rva: Rva, min_size_of: usize, align: usize) -> Result<&'a [u8]> {
+		match self.pe.align() {
+			Align::File if rva == 0 => Err(Error::Null),
+			Align::File => self.range_file(rva, min_size_of, align),
+			Align::Section => self.pe.slice(rva, min_size_of, align),
+		}
+	}
+	fn read(&self, va: Va, min_size_of: usize, align: usize) -> Result<&'a [u8]> {
+		match self.pe.align() {
+			Align::File => {
+				let image_base = self.pe.image_base();
+				let size_of_image = self.optional_header().SizeOfImage.le();
+				if va == 0 {
+					Err(Error::Null)
+				}
+				else if va < image_base || va - image_base > size_of_image as Va {
+					Err(Error::Bounds)
+				}
+				else {
+					self.range_file((va - image_base) as Rva, min_size_of, align)
+				}
+			},
+			Align::Section => self.pe.read(va, min_size_of, align),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn find_ranges() {
+		let (ranges, overlaps) = sort_ranges(vec![(0x3000..0x4000, 0), (0x1000..0x2000, 1), (0x5000..0x5000, 2)]);
+		assert!(!overlaps);
+		assert_eq!(find(&ranges, overlaps, 0x0fffu32), None);
+		assert_eq!(find(&ranges, overlaps, 0x1000u32), Some(1));
+		assert_eq!(find(&ranges, overlaps, 0x2000u32), None);
+		assert_eq!(find(&ranges, overlaps, 0x3fffu32), Some(0));
+		assert_eq!(find(&ranges, overlaps, 0x5000u32), None);
+
+		// Overlapping ranges prefer the first section header
+		let (ranges, overlaps) = sort_ranges(vec![(0x2000..0x4000, 0), (0x1000..0x3000, 1)]);
+		assert!(overlaps);
+		assert_eq!(find(&ranges, overlaps, 0x2800u32), Some(0));
+		assert_eq!(find(&ranges, overlaps, 0x1800u32), Some(1));
+	}
+}
@@ -16,13 +16,22 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 	let security_cookie = load_config.security_cookie()?;
 	let se_handler_table = load_config.se_handler_table()?;
 
+	// Valid targets of exception handler continuations
+	for entry in load_config.guard_eh_continuation_table()?.iter() {
+		println!("EH continuation at {:#x}", entry.rva);
+	}
+
 	Ok(())
 }
 ```
 */
 
-use std::fmt;
+use std::convert::TryFrom;
+use std::prelude::v1::*;
+use std::{fmt, mem, slice};
 
+use crate::util::Le;
+use crate::Pod;
 use crate::{Error, Result};
 
 use super::image::*;
@@ -39,7 +48,7 @@ pub struct LoadConfig<'a, P> {
 impl<'a, P: Pe<'a>> LoadConfig<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<LoadConfig<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG).ok_or(Error::Bounds)?;
-		let image = pe.derva(datadir.VirtualAddress)?;
+		let image = pe.derva(datadir.VirtualAddress.le())?;
 		Ok(LoadConfig { pe, image })
 	}
 	/// Gets the PE instance.
@@ -52,11 +61,106 @@ impl<'a, P: Pe<'a>> LoadConfig<'a, P> {
 	}
 	/// Gets the default security cookie for the image.
 	pub fn security_cookie(&self) -> Result<&'a u32> {
-		self.pe.deref(self.image.SecurityCookie.into())
+		self.pe.deref(self.image.SecurityCookie.le().into())
 	}
 	/// Gets the structured exception handler table.
 	pub fn se_handler_table(&self) -> Result<&'a [Va]> {
-		self.pe.deref_slice(self.image.SEHandlerTable.into(), self.image.SEHandlerCount as usize)
+		self.pe.deref_slice(self.image.SEHandlerTable.le().into(), self.image.SEHandlerCount.le() as usize)
+	}
+	/// Gets the control flow guard flags.
+	///
+	/// Returns `None` if the load config directory predates control flow guard and is too small to contain the flags.
+	pub fn guard_flags(&self) -> Option<u32> {
+		self.field::<u32>(GUARD_FLAGS).map(|&flags| flags.le())
+	}
+	/// Gets the control flow guard function table.
+	///
+	/// Lists the functions which are valid targets of indirect calls.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the load config directory is too small or the table is not present.
+	pub fn guard_cf_function_table(&self) -> Result<GuardTable<'a>> {
+		let flags = self.guard_flags().ok_or(Error::Null)?;
+		if flags & IMAGE_GUARD_CF_FUNCTION_TABLE_PRESENT == 0 {
+			return Err(Error::Null);
+		}
+		let table = self.field::<Va>(GUARD_FLAGS - 2 * mem::size_of::<Va>()).copied().ok_or(Error::Null)?.le();
+		let count = self.field::<Va>(GUARD_FLAGS - mem::size_of::<Va>()).copied().ok_or(Error::Null)?.le();
+		self.guard_table(table, count, flags)
+	}
+	/// Gets the control flow guard table of address-taken IAT entries.
+	///
+	/// Imports whose address is taken are valid call targets even if the exporting module suppresses them.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the load config directory is too small or the table is not present.
+	pub fn guard_address_taken_iat_entry_table(&self) -> Result<GuardTable<'a>> {
+		let flags = self.guard_flags().ok_or(Error::Null)?;
+		if flags & IMAGE_GUARD_CF_FUNCTION_TABLE_PRESENT == 0 {
+			return Err(Error::Null);
+		}
+		let table = self.field::<Va>(GUARD_ADDRESS_TAKEN_IAT_ENTRY_TABLE).copied().ok_or(Error::Null)?.le();
+		let count = self.field::<Va>(GUARD_ADDRESS_TAKEN_IAT_ENTRY_TABLE + mem::size_of::<Va>()).copied().ok_or(Error::Null)?.le();
+		self.guard_table(table, count, flags)
+	}
+	/// Gets the sorted rvas of the exports suppressed from the valid call targets.
+	///
+	/// Export suppression removes exported functions from the control flow guard bitmap until they are resolved through `GetProcAddress`.
+	/// They are flagged with `IMAGE_GUARD_FLAG_EXPORT_SUPPRESSED` in the guard function table.
+	/// Whether the loader enforces the suppression is given by `IMAGE_GUARD_CF_ENABLE_EXPORT_SUPPRESSION` in the guard flags.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the image has no export suppression info.
+	pub fn suppressed_exports(&self) -> Result<Vec<Rva>> {
+		let flags = self.guard_flags().ok_or(Error::Null)?;
+		if flags & IMAGE_GUARD_CF_EXPORT_SUPPRESSION_INFO_PRESENT == 0 {
+			return Err(Error::Null);
+		}
+		let table = self.guard_cf_function_table()?;
+		let mut rvas: Vec<Rva> = table.iter().filter(|entry| entry.flags() & IMAGE_GUARD_FLAG_EXPORT_SUPPRESSED != 0).map(|entry| entry.rva).collect();
+		rvas.sort_unstable();
+		Ok(rvas)
+	}
+	/// Iterates over the XFG type hashes of the guarded functions.
+	///
+	/// Functions flagged with `IMAGE_GUARD_FLAG_FID_XFG` in the guard function table are preceded by the 8 byte hash of their prototype.
+	/// Indirect calls through the XFG dispatch function check this hash against the hash of the prototype expected by the caller.
+	///
+	/// See [`XfgHashes`](struct.XfgHashes.html) for more information.
+	pub fn xfg_hashes(&self) -> Result<XfgHashes<'a, P>> {
+		let table = self.guard_cf_function_table()?;
+		Ok(XfgHashes { pe: self.pe, iter: table.iter() })
+	}
+	/// Gets the table of valid exception handler continuation targets.
+	///
+	/// Used by CET shadow stacks to validate the targets of exception handlers which return to a different address.
+	///
+	/// Returns [`Err(Null)`](../../enum.Error.html#variant.Null) if the load config directory is too small or the table is not present.
+	pub fn guard_eh_continuation_table(&self) -> Result<GuardTable<'a>> {
+		let flags = self.guard_flags().ok_or(Error::Null)?;
+		if flags & IMAGE_GUARD_EH_CONTINUATION_TABLE_PRESENT == 0 {
+			return Err(Error::Null);
+		}
+		let table = self.field::<Va>(GUARD_EH_CONTINUATION_TABLE).copied().ok_or(Error::Null)?.le();
+		let count = self.field::<Va>(GUARD_EH_CONTINUATION_TABLE + mem::size_of::<Va>()).copied().ok_or(Error::Null)?.le();
+		self.guard_table(table, count, flags)
+	}
+	fn guard_table(&self, table: Va, count: Va, flags: u32) -> Result<GuardTable<'a>> {
+		if table == 0 {
+			return Err(Error::Null);
+		}
+		// The upper bits of the guard flags count the extra bytes of metadata following every rva
+		let stride = mem::size_of::<Rva>() + ((flags & IMAGE_GUARD_CF_FUNCTION_TABLE_SIZE_MASK) >> IMAGE_GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT) as usize;
+		let count = usize::try_from(count).map_err(|_| Error::Overflow)?;
+		let len = count.checked_mul(stride).ok_or(Error::Overflow)?;
+		let bytes = self.pe.deref_slice::<u8>(table.into(), len)?;
+		Ok(GuardTable { bytes, stride })
+	}
+	// Reads a field of the load config directory past the legacy fields if the directory is large enough
+	fn field<T: Pod>(&self, offset: usize) -> Option<&'a T> {
+		if (self.image.Size.le() as usize) < offset + mem::size_of::<T>() {
+			return None;
+		}
+		let datadir = self.pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG)?.le();
+		let rva = datadir.VirtualAddress.checked_add(offset as u32)?;
+		self.pe.derva::<T>(rva).ok()
 	}
 }
 impl<'a, P: Pe<'a>> fmt::Debug for LoadConfig<'a, P> {
@@ -68,6 +172,112 @@ impl<'a, P: Pe<'a>> fmt::Debug for LoadConfig<'a, P> {
 	}
 }
 
+//----------------------------------------------------------------
+
+// Offsets of the fields following the legacy load config directory
+const GUARD_FLAGS: usize = mem::size_of::<IMAGE_LOAD_CONFIG_DIRECTORY>() + 4 * mem::size_of::<Va>();
+const GUARD_ADDRESS_TAKEN_IAT_ENTRY_TABLE: usize = GUARD_FLAGS + 4 + mem::size_of::<IMAGE_LOAD_CONFIG_CODE_INTEGRITY>();
+const GUARD_EH_CONTINUATION_TABLE: usize = GUARD_FLAGS + 4 + mem::size_of::<IMAGE_LOAD_CONFIG_CODE_INTEGRITY>() + 11 * mem::size_of::<Va>() + 16;
+
+/// Control flow guard table.
+///
+/// The entries are rvas followed by a number of bytes of metadata given by the guard flags.
+#[derive(Copy, Clone)]
+pub struct GuardTable<'a> {
+	bytes: &'a [u8],
+	stride: usize,
+}
+impl<'a> GuardTable<'a> {
+	/// Returns the raw bytes of the table.
+	pub fn image(&self) -> &'a [u8] {
+		self.bytes
+	}
+	/// Returns the size of an entry in bytes.
+	pub fn stride(&self) -> usize {
+		self.stride
+	}
+	/// Returns the number of entries.
+	pub fn len(&self) -> usize {
+		self.bytes.len() / self.stride
+	}
+	/// Returns true if the table has no entries.
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+	/// Iterates over the entries.
+	pub fn iter(&self) -> GuardIter<'a> {
+		GuardIter { chunks: self.bytes.chunks_exact(self.stride) }
+	}
+}
+impl<'a> IntoIterator for GuardTable<'a> {
+	type Item = GuardEntry<'a>;
+	type IntoIter = GuardIter<'a>;
+	fn into_iter(self) -> GuardIter<'a> {
+		self.iter()
+	}
+}
+impl<'a> fmt::Debug for GuardTable<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+/// Control flow guard table entry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GuardEntry<'a> {
+	/// The guarded rva.
+	pub rva: Rva,
+	/// Metadata following the rva, eg. the `IMAGE_GUARD_FLAG_*` flags.
+	pub metadata: &'a [u8],
+}
+impl<'a> GuardEntry<'a> {
+	/// Returns the `IMAGE_GUARD_FLAG_*` flags, zero if the table has no metadata.
+	pub fn flags(&self) -> u8 {
+		self.metadata.first().copied().unwrap_or(0)
+	}
+}
+
+/// Iterator over the control flow guard table entries.
+#[derive(Clone)]
+pub struct GuardIter<'a> {
+	chunks: slice::ChunksExact<'a, u8>,
+}
+impl<'a> Iterator for GuardIter<'a> {
+	type Item = GuardEntry<'a>;
+	fn next(&mut self) -> Option<GuardEntry<'a>> {
+		let (rva, metadata) = self.chunks.next()?.split_at(mem::size_of::<Rva>());
+		Some(GuardEntry { rva: Rva::from_le_bytes([rva[0], rva[1], rva[2], rva[3]]), metadata })
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.chunks.size_hint()
+	}
+}
+impl<'a> ExactSizeIterator for GuardIter<'a> {}
+
+//----------------------------------------------------------------
+
+/// Iterator over the XFG type hashes of the guarded functions.
+///
+/// Yields the rva of the guarded function and the hash of its prototype.
+#[derive(Clone)]
+pub struct XfgHashes<'a, P> {
+	pe: P,
+	iter: GuardIter<'a>,
+}
+impl<'a, P: Pe<'a>> Iterator for XfgHashes<'a, P> {
+	type Item = (Rva, Result<u64>);
+	fn next(&mut self) -> Option<(Rva, Result<u64>)> {
+		let entry = self.iter.by_ref().find(|entry| entry.flags() & IMAGE_GUARD_FLAG_FID_XFG != 0)?;
+		let hash = match entry.rva.checked_sub(8) {
+			Some(rva) => self.pe.derva_slice::<u8>(rva, 8).map(|bytes| u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]])),
+			None => Err(Error::Bounds),
+		};
+		Some((entry.rva, hash))
+	}
+}
+
+//----------------------------------------------------------------
+
 #[cfg(feature = "serde")]
 mod serde {
 	use crate::util::serde_helper::*;
@@ -90,5 +300,18 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 	let _ = format!("{:?}", load_config);
 	let _security_cookie = load_config.security_cookie();
 	let _se_handler_table = load_config.se_handler_table();
+	let _guard_flags = load_config.guard_flags();
+	if let Ok(table) = load_config.guard_eh_continuation_table() {
+		for _entry in table.iter() {}
+	}
+	if let Ok(xfg_hashes) = load_config.xfg_hashes() {
+		for _hash in xfg_hashes {}
+	}
+	if let Ok(table) = load_config.guard_address_taken_iat_entry_table() {
+		for _entry in table.iter() {}
+	}
+	if let Ok(rvas) = load_config.suppressed_exports() {
+		assert!(rvas.windows(2).all(|window| window[0] <= window[1]));
+	}
 	Ok(())
 }
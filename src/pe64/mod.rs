@@ -32,6 +32,7 @@ Import the [`Pe`](trait.Pe.html) trait to continue from here.
 use std::path::Path;
 use pelite::{FileMap, Result};
 use pelite::pe64::{Pe, PeFile};
+use pelite::util::Le;
 
 fn file_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 	let path = path.as_ref();
@@ -39,7 +40,7 @@ fn file_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 		let file = PeFile::from_bytes(&map)?;
 
 		// Access the file contents through the Pe trait
-		let image_base = file.optional_header().ImageBase;
+		let image_base = file.optional_header().ImageBase.le();
 		println!("The preferred load address of {:?} is {}.", path, image_base);
 
 		// See the respective modules to access other parts of the PE file.
@@ -65,6 +66,7 @@ If you don't know which to choose, go with [`PeFile`](struct.PeFile.html).
 use std::path::Path;
 use pelite::{ImageMap, Result};
 use pelite::pe64::{Pe, PeView};
+use pelite::util::Le;
 
 fn image_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 	let path = path.as_ref();
@@ -72,7 +74,7 @@ fn image_map<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
 		let view = PeView::from_bytes(&image)?;
 
 		// Access the image contents through the Pe trait
-		let image_size = view.optional_header().SizeOfImage;
+		let image_size = view.optional_header().SizeOfImage.le();
 		println!("The size of image in memory of {:?} is {}", path, image_size);
 
 		// See the respective modules to access other parts of the PE image.
@@ -101,12 +103,13 @@ This is mostly safe, but be even more cautious when using it to read from writab
 use std::path::Path;
 use pelite::Result;
 use pelite::pe::{Pe, PeView};
+use pelite::util::Le;
 
 fn image_base() {
 	let view = unsafe { PeView::new() };
 
 	// Access the image contents through the Pe trait
-	let image_size = view.optional_header().SizeOfImage;
+	let image_size = view.optional_header().SizeOfImage.le();
 	println!("The size of our image is {}", image_size);
 
 	// See the respective modules to access other parts of the PE image.
@@ -120,28 +123,45 @@ mod macros;
 
 pub mod image;
 
+pub mod addr;
+pub mod analysis;
 pub(crate) mod base_relocs;
+pub mod bound_imports;
+pub mod clr;
+mod buf;
 pub mod debug;
+pub mod delay_imports;
 pub mod exception;
 pub mod exports;
 mod file;
 pub mod headers;
 pub mod imports;
+pub mod index;
 pub mod load_config;
 pub mod msvc;
 mod pe;
+mod pe_headers;
 mod ptr;
+#[cfg(feature = "std")]
+pub mod reader;
 pub mod resources;
 pub(crate) mod rich_structure;
 pub mod scanner;
 pub(crate) mod security;
+pub mod stubs;
+pub mod symbols;
 pub mod tls;
+pub mod validate;
 mod view;
 
+pub use self::buf::{PeFileBuf, PeViewBuf};
 pub use self::file::PeFile;
+pub use self::pe_headers::PeHeaders;
 pub use self::image::{Rva, Va};
 pub use self::pe::{Align, Pe, PeObject};
 pub use self::ptr::Ptr;
+#[cfg(feature = "std")]
+pub use self::reader::{PeReader, ReadError};
 pub use self::view::PeView;
 
 #[cfg(feature = "unstable")]
@@ -2,10 +2,11 @@
 Abstract over mapped images and file binaries.
 */
 
-use std::{cmp, mem, ptr, slice};
+use std::prelude::v1::*;
+use std::{cmp, mem, ops, ptr, slice};
 
-use crate::{util::AlignTo, util::CStr, util::FromBytes, Pod};
-use crate::{Error, Result};
+use crate::{util::AlignTo, util::CStr, util::Digest, util::FromBytes, util::Le, util::UnalignedSlice, Pod};
+use crate::{Error, ParseOptions, Result, Violation, Violations};
 
 use super::{image::*, Ptr};
 
@@ -13,6 +14,27 @@ use super::{image::*, Ptr};
 
 pub use crate::wrap::Align;
 
+/// Object safe base of the [`Pe`](trait.Pe.html) trait.
+///
+/// Use `dyn PeObject<'a>` to store different kinds of PE images behind a single type without making everything generic.
+/// The trait object references `&dyn PeObject<'a>` (and its `Send + Sync` variant) implement `Pe<'a>` themselves.
+///
+/// # Examples
+///
+/// ```
+/// use pelite::pe64::{Pe, PeObject, PeFile, PeView};
+///
+/// # #[allow(dead_code)]
+/// fn example<'a>(file: PeFile<'a>, view: PeView<'a>) -> pelite::Result<()> {
+/// 	let images: Vec<Box<dyn PeObject<'a>>> = vec![Box::new(file), Box::new(view)];
+/// 	for image in &images {
+/// 		// Reborrow as a trait object reference to access the `Pe` methods
+/// 		let pe: &dyn PeObject<'a> = &**image;
+/// 		println!("{:?}", pe.exports()?.dll_name()?);
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
 pub unsafe trait PeObject<'a> {
 	/// Returns the image as a byte slice.
 	fn image(&self) -> &'a [u8];
@@ -25,6 +47,13 @@ pub unsafe trait PeObject<'a> {
 	/// For an image on disk, this should be the preferred virtual address. For a memory mapped image, this should be the actual virtual address.
 	fn image_base(&self) -> Va;
 
+	/// Returns the parse options limiting how far the image is trusted.
+	///
+	/// See [`ParseOptions`](../struct.ParseOptions.html) for more information.
+	fn options(&self) -> ParseOptions {
+		ParseOptions::DEFAULT
+	}
+
 	// Give a struct name in Serialize implementation
 	#[cfg(feature = "serde")]
 	#[doc(hidden)]
@@ -51,13 +80,32 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		unsafe { file_header(self.image()) }
 	}
 	/// Returns the optional header.
+	///
+	/// If `SizeOfOptionalHeader` is smaller than the optional header structure the fields past it overlap the section headers,
+	/// see [`optional_header_window`](#method.optional_header_window) for the fields actually present.
 	fn optional_header(self) -> &'a IMAGE_OPTIONAL_HEADER {
 		unsafe { optional_header(self.image()) }
 	}
+	/// Returns the bytes of the optional header as declared by `SizeOfOptionalHeader`, including the data directory.
+	///
+	/// The declared size may be smaller or larger than the optional header structure, both are valid.
+	/// Only the fields which fit in the window are present, larger windows have unused trailing bytes before the section headers.
+	fn optional_header_window(self) -> &'a [u8] {
+		unsafe { optional_header_window(self.image()) }
+	}
 	/// Returns the data directory.
+	///
+	/// Like the Windows loader the number of entries is `NumberOfRvaAndSizes` regardless of `SizeOfOptionalHeader`,
+	/// the entries past the declared optional header overlap the section headers.
 	fn data_directory(self) -> &'a [IMAGE_DATA_DIRECTORY] {
 		unsafe { data_directory(self.image()) }
 	}
+	/// Returns the data directory entries which fit in the declared optional header.
+	///
+	/// See [`optional_header_window`](#method.optional_header_window) for more information.
+	fn declared_data_directory(self) -> &'a [IMAGE_DATA_DIRECTORY] {
+		unsafe { declared_data_directory(self.image()) }
+	}
 	/// Returns the section headers.
 	fn section_headers(self) -> &'a super::headers::SectionHeaders {
 		unsafe { section_headers(self.image()) }
@@ -84,36 +132,17 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	///   The rva falls outside any valid section or the PE headers.
 	fn rva_to_file_offset(self, rva: Rva) -> Result<usize> {
 		// Consider rva inside headers to be valid
-		if rva < self.optional_header().SizeOfHeaders {
+		if rva < self.optional_header().SizeOfHeaders.le() {
 			return Ok(rva as usize);
 		}
-		// This code has been carefully designed to avoid panicking on overflow
+		if is_low_alignment(self.optional_header()) {
+			return flat_rva_to_file_offset(self.image(), self.optional_header().SizeOfImage.le(), rva);
+		}
+		let lenient = self.options().lenient;
 		for it in self.section_headers() {
-			// Compare if rva is contained within the virtual address space of a section
-			// If the calculating the section end address overflows the corrupt section will be skipped
-			#[allow(non_snake_case)]
-			let VirtualEnd = it.VirtualAddress.wrapping_add(cmp::max(it.VirtualSize, it.SizeOfRawData));
-			// $1
-			if it.VirtualAddress <= rva && rva < VirtualEnd {
-				// Check if the raw data reference is sane
-				// $2
-				if let None = it.PointerToRawData.checked_add(it.SizeOfRawData) {
-					return Err(Error::Overflow);
-				}
-				// Calculate the offset in the section. cannot underflow, see $1
-				let section_offset = rva - it.VirtualAddress;
-				// $3
-				return if section_offset < it.SizeOfRawData {
-					// Calculate the final offset in the file. cannot overflow, see $2 and $3
-					Ok((section_offset + it.PointerToRawData) as usize)
-				}
-				// Identify the reason the conversion fails
-				else if section_offset < it.VirtualSize {
-					Err(Error::ZeroFill)
-				}
-				else {
-					Err(Error::Bounds)
-				};
+			let it = &loader_section(it, self.optional_header(), lenient);
+			if section_contains_rva(it, rva) {
+				return section_rva_to_file_offset(it, rva);
 			}
 		}
 		Err(Error::Bounds)
@@ -132,40 +161,49 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	///   The file offset falls outside any valid section or PE headers.
 	fn file_offset_to_rva(self, file_offset: usize) -> Result<Rva> {
 		// Consider rva inside headers to be valid
-		if file_offset < self.optional_header().SizeOfHeaders as usize {
+		if file_offset < self.optional_header().SizeOfHeaders.le() as usize {
 			return Ok(file_offset as Rva);
 		}
-		// This code has been carefully designed to avoid panicking on overflow
+		if is_low_alignment(self.optional_header()) {
+			return flat_file_offset_to_rva(self.image(), self.optional_header().SizeOfImage.le(), file_offset);
+		}
+		let lenient = self.options().lenient;
 		for it in self.section_headers() {
-			// Compare if file_offset is contained within the raw data of a section
-			// If the calculating the section end address overflows the corrupt section will be skipped
-			#[allow(non_snake_case)]
-			let EndOfRawData = it.PointerToRawData.wrapping_add(it.SizeOfRawData);
-			// $1
-			if it.PointerToRawData as usize <= file_offset && file_offset < EndOfRawData as usize {
-				// Check if the virtual reference is sane
-				// $2
-				if let None = it.VirtualAddress.checked_add(it.VirtualSize) {
-					return Err(Error::Overflow);
-				}
-				// Calculate the offset in the section. cannot underflow, see $1
-				let section_offset = file_offset as Rva - it.PointerToRawData;
-				// $3
-				return if section_offset < it.VirtualSize {
-					// Calculate the final virtual address. cannot overflow, see $2 and $3
-					Ok(section_offset + it.VirtualAddress)
-				}
-				// Identify the reason the conversion fails
-				else if section_offset < it.SizeOfRawData {
-					Err(Error::Unmapped)
-				}
-				else {
-					Err(Error::Bounds)
-				};
+			let it = &loader_section(it, self.optional_header(), lenient);
+			if section_contains_file_offset(it, file_offset) {
+				return section_file_offset_to_rva(it, file_offset);
 			}
 		}
 		Err(Error::Bounds)
 	}
+	/// Converts a sorted slice of relative virtual addresses to file offsets.
+	///
+	/// Walks the sections once instead of searching them for every rva, for translating the many addresses of symbol maps or coverage data.
+	/// The results are the same as [`rva_to_file_offset`](#method.rva_to_file_offset) for every rva.
+	/// Unsorted rvas and images with unsorted or overlapping sections are translated correctly, only slower.
+	fn rvas_to_file_offsets(self, rvas: &[Rva]) -> Vec<Result<usize>> {
+		if is_low_alignment(self.optional_header()) {
+			return rvas.iter().map(|&rva| self.rva_to_file_offset(rva)).collect();
+		}
+		let lenient = self.options().lenient;
+		let sections = self.section_headers().iter().map(|it| loader_section(it, self.optional_header(), lenient)).collect();
+		let size_of_headers = self.optional_header().SizeOfHeaders.le();
+		translate_sorted(sections, rvas, size_of_headers, section_rva_range, section_rva_to_file_offset, |rva| self.rva_to_file_offset(rva))
+	}
+	/// Converts a sorted slice of file offsets to relative virtual addresses.
+	///
+	/// Walks the sections once instead of searching them for every file offset, for translating the many addresses of symbol maps or coverage data.
+	/// The results are the same as [`file_offset_to_rva`](#method.file_offset_to_rva) for every file offset.
+	/// Unsorted file offsets and images with unsorted or overlapping sections are translated correctly, only slower.
+	fn file_offsets_to_rvas(self, file_offsets: &[usize]) -> Vec<Result<Rva>> {
+		if is_low_alignment(self.optional_header()) {
+			return file_offsets.iter().map(|&file_offset| self.file_offset_to_rva(file_offset)).collect();
+		}
+		let lenient = self.options().lenient;
+		let sections = self.section_headers().iter().map(|it| loader_section(it, self.optional_header(), lenient)).collect();
+		let size_of_headers = self.optional_header().SizeOfHeaders.le() as usize;
+		translate_sorted(sections, file_offsets, size_of_headers, section_file_range, section_file_offset_to_rva, |file_offset| self.file_offset_to_rva(file_offset))
+	}
 
 	/// Converts from relative virtual address to virtual address.
 	///
@@ -176,16 +214,19 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	///
 	/// * [`Bounds`](../enum.Error.html#variant.Bounds):
 	///   The rva does not fall within the virtual image bounds.
+	///
+	/// * [`Overflow`](../enum.Error.html#variant.Overflow):
+	///   The image base is so large the virtual address overflows.
 	fn rva_to_va(self, rva: Rva) -> Result<Va> {
 		if rva == 0 {
 			Err(Error::Null)
 		}
 		else {
 			let image_base = self.image_base();
-			let size_of_image = self.optional_header().SizeOfImage;
+			let size_of_image = self.optional_header().SizeOfImage.le();
 
 			if rva < size_of_image {
-				Ok(image_base + rva as Va)
+				image_base.checked_add(rva as Va).ok_or(Error::Overflow)
 			}
 			else {
 				Err(Error::Bounds)
@@ -207,7 +248,7 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		}
 		else {
 			let image_base = self.image_base();
-			let size_of_image = self.optional_header().SizeOfImage;
+			let size_of_image = self.optional_header().SizeOfImage.le();
 
 			// Carefully avoid panicking overflow
 			if va < image_base || va - image_base > size_of_image as Va {
@@ -236,7 +277,7 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	fn slice(&self, rva: Rva, min_size_of: usize, align: usize) -> Result<&'a [u8]> {
 		unsafe {
 			match (self.align(), self.image()) {
-				(Align::File, image) => slice_file(image, rva, min_size_of, align),
+				(Align::File, image) => slice_file(image, self.options().lenient, rva, min_size_of, align),
 				(Align::Section, image) => slice_section(image, rva, min_size_of, align),
 			}
 		}
@@ -280,7 +321,7 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	fn read(&self, va: Va, min_size_of: usize, align: usize) -> Result<&'a [u8]> {
 		unsafe {
 			match (self.align(), self.image()) {
-				(Align::File, image) => read_file(image, self.image_base(), va, min_size_of, align),
+				(Align::File, image) => read_file(image, self.options().lenient, self.image_base(), va, min_size_of, align),
 				(Align::Section, image) => read_section(image, self.image_base(), va, min_size_of, align),
 			}
 		}
@@ -299,6 +340,9 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	//----------------------------------------------------------------
 
 	/// Reads an aligned pod `T`.
+	///
+	/// Returns [`Err(Misaligned)`](../enum.Error.html#variant.Misaligned) if the rva isn't aligned for `T`, unless the `unsafe_alignment` feature is enabled.
+	/// Use [`derva_copy`](#method.derva_copy) or [`derva_slice_unaligned`](#method.derva_slice_unaligned) to read packed structures regardless.
 	fn derva<T: Pod>(self, rva: Rva) -> Result<&'a T> {
 		let align = if cfg!(feature = "unsafe_alignment") { 1 } else { mem::align_of::<T>() };
 		let bytes = self.slice(rva, mem::size_of::<T>(), align)?;
@@ -334,6 +378,14 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		// This is safe as per Pod bound, min_size_of and align
 		unsafe { Ok(slice::from_raw_parts(bytes.as_ptr() as *const T, len)) }
 	}
+	/// Reads an array of pod `T` with given length which may not be aligned.
+	///
+	/// The elements are copied out on access, this never fails with [`Misaligned`](../enum.Error.html#variant.Misaligned) regardless of the `unsafe_alignment` feature.
+	fn derva_slice_unaligned<T: Copy + Pod>(self, rva: Rva, len: usize) -> Result<UnalignedSlice<'a, T>> {
+		let min_size_of = mem::size_of::<T>().checked_mul(len).ok_or(Error::Overflow)?;
+		let bytes = self.slice(rva, min_size_of, 1)?;
+		Ok(UnalignedSlice::new(&bytes[..min_size_of]))
+	}
 	/// Reads an array of pod `T`.
 	///
 	/// For every element of the array, starting at the given `rva`, the callable `f` is called with that element.
@@ -372,8 +424,21 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		self.derva_slice_f(rva, |tee| *tee == sentinel)
 	}
 	/// Reads a nul-terminated C string.
+	///
+	/// The length of the string is limited by [`ParseOptions::max_string_len`](../struct.ParseOptions.html#structfield.max_string_len).
 	fn derva_c_str(self, rva: Rva) -> Result<&'a CStr> {
-		self.derva_string(rva)
+		let bytes = self.slice(rva, 0, 1)?;
+		c_str_bounded(bytes, self.options().max_string_len)
+	}
+	/// Reads a nul-terminated C string of at most `max_len` bytes, not including the nul terminator.
+	///
+	/// Caps the scan for the nul terminator, the limit of [`ParseOptions::max_string_len`](../struct.ParseOptions.html#structfield.max_string_len) still applies.
+	///
+	/// Returns [`Err(Insanity)`](../enum.Error.html#variant.Insanity) if no nul terminator is found within the limit
+	/// and [`Err(Encoding)`](../enum.Error.html#variant.Encoding) if the string runs into the end of the section before.
+	fn derva_c_str_bounded(self, rva: Rva, max_len: usize) -> Result<&'a CStr> {
+		let bytes = self.slice(rva, 0, 1)?;
+		c_str_bounded(bytes, cmp::min(max_len, self.options().max_string_len))
 	}
 	/// Reads a string.
 	fn derva_string<T: FromBytes + ?Sized>(self, rva: Rva) -> Result<&'a T> {
@@ -420,6 +485,14 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		// This is safe as per Pod bound, min_size_of and align
 		unsafe { Ok(slice::from_raw_parts(bytes.as_ptr() as *const T, len)) }
 	}
+	/// Reads an array of pod `T` with given length which may not be aligned.
+	///
+	/// The elements are copied out on access, this never fails with [`Misaligned`](../enum.Error.html#variant.Misaligned) regardless of the `unsafe_alignment` feature.
+	fn deref_slice_unaligned<T: Copy + Pod>(self, ptr: Ptr<[T]>, len: usize) -> Result<UnalignedSlice<'a, T>> {
+		let min_size_of = mem::size_of::<T>().checked_mul(len).ok_or(Error::Overflow)?;
+		let bytes = self.read(ptr.into(), min_size_of, 1)?;
+		Ok(UnalignedSlice::new(&bytes[..min_size_of]))
+	}
 	/// Reads an array of pod `T`.
 	///
 	/// For every element of the array, starting at the given `ptr`, the callable `f` is called with that element.
@@ -457,8 +530,18 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		self.deref_slice_f(ptr, |tee| *tee == sentinel)
 	}
 	/// Dereferences the pointer to a nul-terminated C string.
+	///
+	/// The length of the string is limited by [`ParseOptions::max_string_len`](../struct.ParseOptions.html#structfield.max_string_len).
 	fn deref_c_str(self, ptr: Ptr<CStr>) -> Result<&'a CStr> {
-		self.deref_string(ptr)
+		let bytes = self.read(ptr.into(), 0, 1)?;
+		c_str_bounded(bytes, self.options().max_string_len)
+	}
+	/// Dereferences the pointer to a nul-terminated C string of at most `max_len` bytes, not including the nul terminator.
+	///
+	/// See [`derva_c_str_bounded`](#method.derva_c_str_bounded) for more information.
+	fn deref_c_str_bounded(self, ptr: Ptr<CStr>, max_len: usize) -> Result<&'a CStr> {
+		let bytes = self.read(ptr.into(), 0, 1)?;
+		c_str_bounded(bytes, cmp::min(max_len, self.options().max_string_len))
 	}
 	/// Dereferences the pointer to a string.
 	fn deref_string<T: FromBytes + ?Sized>(self, ptr: Ptr<T>) -> Result<&'a T> {
@@ -480,19 +563,41 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	/// See the [exports](exports/index.html) module for more information.
 	///
 	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no exports. Any other error indiciates some form of corruption.
+	///
+	/// Only the export directory itself is read, the tables it references are validated as they are accessed.
 	fn exports(self) -> Result<super::exports::Exports<'a, Self>> {
 		super::exports::Exports::try_from(self)
 	}
 
+	/// Gets the Export Directory and eagerly validates it.
+	///
+	/// See [`Exports::validate`](exports/struct.Exports.html#method.validate) for the checks performed.
+	fn try_exports(self) -> Result<super::exports::Exports<'a, Self>> {
+		let exports = super::exports::Exports::try_from(self)?;
+		exports.validate()?;
+		Ok(exports)
+	}
+
 	/// Gets the Import Directory.
 	///
 	/// See the [imports](imports/index.html) module for more information.
 	///
 	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no imports. Any other error indicates some form of corruption.
+	///
+	/// Only the import descriptors are read, the names and thunks they reference are validated as they are accessed.
 	fn imports(self) -> Result<super::imports::Imports<'a, Self>> {
 		super::imports::Imports::try_from(self)
 	}
 
+	/// Gets the Import Directory and eagerly validates it.
+	///
+	/// See [`Imports::validate`](imports/struct.Imports.html#method.validate) for the checks performed.
+	fn try_imports(self) -> Result<super::imports::Imports<'a, Self>> {
+		let imports = super::imports::Imports::try_from(self)?;
+		imports.validate()?;
+		Ok(imports)
+	}
+
 	/// Gets the Import Address Table.
 	///
 	/// See the [imports](imports/index.html) module for more information.
@@ -502,6 +607,33 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		super::imports::IAT::try_from(self)
 	}
 
+	/// Gets the Bound Import Directory.
+	///
+	/// See the [bound imports](bound_imports/index.html) module for more information.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no bound imports. Any other error indicates some form of corruption.
+	fn bound_imports(self) -> Result<super::bound_imports::BoundImports<'a, Self>> {
+		super::bound_imports::BoundImports::try_from(self)
+	}
+
+	/// Gets the Delay Import Directory.
+	///
+	/// See the [delay imports](delay_imports/index.html) module for more information.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no delay imports. Any other error indicates some form of corruption.
+	fn delay_imports(self) -> Result<super::delay_imports::DelayImports<'a, Self>> {
+		super::delay_imports::DelayImports::try_from(self)
+	}
+
+	/// Gets the Delay Import Directory and eagerly validates it.
+	///
+	/// See [`DelayImports::validate`](delay_imports/struct.DelayImports.html#method.validate) for the checks performed.
+	fn try_delay_imports(self) -> Result<super::delay_imports::DelayImports<'a, Self>> {
+		let delay_imports = super::delay_imports::DelayImports::try_from(self)?;
+		delay_imports.validate()?;
+		Ok(delay_imports)
+	}
+
 	/// Gets the Base Relocations Directory.
 	///
 	/// See the [base relocations](base_relocs/index.html) module for more information.
@@ -543,10 +675,29 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 	/// See the [exception](exception/index.html) module for more information.
 	///
 	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no exception directory. Any other error indicates some form of corruption.
+	/// ARM and ARM64 images return [`Err(Invalid)`](../enum.Error.html#variant.Invalid), see [`arm_exception`](#method.arm_exception) instead.
 	fn exception(self) -> Result<super::exception::Exception<'a, Self>> {
 		super::exception::Exception::try_from(self)
 	}
 
+	/// Gets the Exception Directory and eagerly validates it.
+	///
+	/// See [`Exception::validate`](exception/struct.Exception.html#method.validate) for the checks performed.
+	fn try_exception(self) -> Result<super::exception::Exception<'a, Self>> {
+		let exception = super::exception::Exception::try_from(self)?;
+		exception.validate()?;
+		Ok(exception)
+	}
+
+	/// Gets the Exception Directory of ARM and ARM64 images.
+	///
+	/// See the [exception](exception/index.html) module for more information.
+	///
+	/// Returns [`Err(Invalid)`](../enum.Error.html#variant.Invalid) if the image is not an ARM or ARM64 image.
+	fn arm_exception(self) -> Result<super::exception::ArmException<'a, Self>> {
+		super::exception::ArmException::try_from(self)
+	}
+
 	/// Gets the Debug Directory.
 	///
 	/// See the [debug](debug/index.html) module for more information.
@@ -556,20 +707,75 @@ pub unsafe trait Pe<'a>: PeObject<'a> + Copy {
 		super::debug::Debug::try_from(self)
 	}
 
+	/// Gets the CLR header.
+	///
+	/// See the [clr](clr/index.html) module for more information.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image is not a managed assembly. Any other error indicates some form of corruption.
+	fn clr(self) -> Result<super::clr::Clr<'a, Self>> {
+		super::clr::Clr::try_from(self)
+	}
+
 	/// Gets the Resources.
 	///
 	/// See the [resources](resources/index.html) module for more information.
 	///
 	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the image has no resources. Any other error indicates some form of corruption.
+	///
+	/// In file images the resources may straddle sections whose raw data is laid out back to back, these are accessed as a whole.
+	/// Resources which cannot be accessed from the file as a whole report [`ZeroFill`](../enum.Error.html#variant.ZeroFill) for references past the available bytes.
 	#[cfg(any(feature = "std", feature = "resources_nostd"))]
 	fn resources(self) -> Result<crate::resources::Resources<'a>>
 	where
 		Self: Copy,
 	{
 		let datadir = self.data_directory().get(IMAGE_DIRECTORY_ENTRY_RESOURCE).ok_or(Error::Bounds)?;
-		let bytes = self.slice_bytes(datadir.VirtualAddress)?;
-		let size = cmp::min(datadir.Size as usize, bytes.len());
-		Ok(crate::resources::Resources::new(&bytes[..size], datadir))
+		let bytes = stitch_sections(self, datadir.VirtualAddress.le(), datadir.Size.le() as usize)?;
+		let size = cmp::min(datadir.Size.le() as usize, bytes.len());
+		let resources = crate::resources::Resources::new(&bytes[..size], datadir);
+		Ok(resources.set_max_depth(self.options().max_resource_depth))
+	}
+
+	/// Runs the structural validator.
+	///
+	/// See the [validate](validate/index.html) module for more information.
+	fn validate(self) -> super::validate::Report {
+		super::validate::validate(self)
+	}
+
+	/// Maps the layout of the image.
+	///
+	/// See the [layout](analysis/layout/index.html) module for more information.
+	fn layout(self) -> super::analysis::layout::Layout {
+		super::analysis::layout::layout(self)
+	}
+
+	/// Finds what the rva belongs to.
+	///
+	/// See the [what is](analysis/what_is/index.html) module for more information.
+	fn what_is(self, rva: Rva) -> super::analysis::what_is::WhatIs<'a> {
+		super::analysis::what_is::what_is(self, rva)
+	}
+
+	/// Hashes the raw data of every section.
+	///
+	/// See the [hashes](analysis/hashes/index.html) module for more information.
+	fn section_hashes<D: Digest + Default>(self) -> Vec<super::analysis::hashes::SectionHash<D::Output>> {
+		super::analysis::hashes::section_hashes::<Self, D>(self)
+	}
+
+	/// Hashes the headers and the raw data of the sections, skipping the overlay.
+	///
+	/// See the [hashes](analysis/hashes/index.html) module for more information.
+	fn image_hash<D: Digest + Default>(self) -> Result<D::Output> {
+		super::analysis::hashes::image_hash::<Self, D>(self)
+	}
+
+	/// Hashes the file like the Authenticode hash, skipping the signature.
+	///
+	/// See the [hashes](analysis/hashes/index.html) module for more information.
+	fn authenticode_hash<D: Digest + Default>(self) -> Result<D::Output> {
+		super::analysis::hashes::authenticode_hash::<Self, D>(self)
 	}
 
 	/// Gets Scanner access.
@@ -594,6 +800,9 @@ unsafe impl<'s, 'a> PeObject<'a> for &'s dyn PeObject<'a> {
 	fn image_base(&self) -> Va {
 		PeObject::image_base(*self)
 	}
+	fn options(&self) -> ParseOptions {
+		PeObject::options(*self)
+	}
 
 	#[cfg(feature = "serde")]
 	fn serde_name(&self) -> &'static str {
@@ -603,6 +812,29 @@ unsafe impl<'s, 'a> PeObject<'a> for &'s dyn PeObject<'a> {
 
 unsafe impl<'s, 'a> Pe<'a> for &'s dyn PeObject<'a> {}
 
+unsafe impl<'a> PeObject<'a> for &(dyn PeObject<'a> + Send + Sync) {
+	fn image(&self) -> &'a [u8] {
+		PeObject::image(*self)
+	}
+	fn align(&self) -> Align {
+		PeObject::align(*self)
+	}
+
+	fn image_base(&self) -> Va {
+		PeObject::image_base(*self)
+	}
+	fn options(&self) -> ParseOptions {
+		PeObject::options(*self)
+	}
+
+	#[cfg(feature = "serde")]
+	fn serde_name(&self) -> &'static str {
+		PeObject::serde_name(*self)
+	}
+}
+
+unsafe impl<'a> Pe<'a> for &(dyn PeObject<'a> + Send + Sync) {}
+
 //----------------------------------------------------------------
 
 #[cfg(feature = "serde")]
@@ -626,30 +858,51 @@ pub(crate) fn serialize_pe<'a, P: Pe<'a>, S: serde::Serializer>(pe: P, serialize
 //----------------------------------------------------------------
 // Implementation helpers
 
-unsafe fn dos_header(image: &[u8]) -> &IMAGE_DOS_HEADER {
+pub(crate) fn c_str_bounded(bytes: &[u8], max_len: usize) -> Result<&CStr> {
+	let bounded = bytes.get(..max_len.saturating_add(1)).unwrap_or(bytes);
+	match CStr::from_bytes(bounded) {
+		Some(c_str) => Ok(c_str),
+		// The string was cut short by the limit
+		None if bounded.len() < bytes.len() => Err(Error::Insanity),
+		None => Err(Error::Encoding),
+	}
+}
+
+pub(crate) unsafe fn dos_header(image: &[u8]) -> &IMAGE_DOS_HEADER {
 	&*(image.as_ptr() as *const IMAGE_DOS_HEADER)
 }
 unsafe fn dos_image(image: &[u8]) -> &[u8] {
-	image.get_unchecked(..dos_header(image).e_lfanew as usize)
+	image.get_unchecked(..dos_header(image).e_lfanew.le() as usize)
 }
-unsafe fn nt_headers(image: &[u8]) -> &IMAGE_NT_HEADERS {
-	&*(image.as_ptr().offset(dos_header(image).e_lfanew as isize) as *const IMAGE_NT_HEADERS)
+pub(crate) unsafe fn nt_headers(image: &[u8]) -> &IMAGE_NT_HEADERS {
+	&*(image.as_ptr().offset(dos_header(image).e_lfanew.le() as isize) as *const IMAGE_NT_HEADERS)
 }
-unsafe fn file_header(image: &[u8]) -> &IMAGE_FILE_HEADER {
+pub(crate) unsafe fn file_header(image: &[u8]) -> &IMAGE_FILE_HEADER {
 	&nt_headers(image).FileHeader
 }
 pub(crate) unsafe fn optional_header(image: &[u8]) -> &IMAGE_OPTIONAL_HEADER {
 	&nt_headers(image).OptionalHeader
 }
-unsafe fn data_directory(image: &[u8]) -> &[IMAGE_DATA_DIRECTORY] {
+unsafe fn optional_header_window(image: &[u8]) -> &[u8] {
+	let start = dos_header(image).e_lfanew.le() as usize + (mem::size_of::<IMAGE_NT_HEADERS>() - mem::size_of::<IMAGE_OPTIONAL_HEADER>());
+	let end = cmp::min(start + file_header(image).SizeOfOptionalHeader.le() as usize, image.len());
+	image.get_unchecked(start..end)
+}
+pub(crate) unsafe fn data_directory(image: &[u8]) -> &[IMAGE_DATA_DIRECTORY] {
 	let opt = optional_header(image);
-	let len = cmp::min(opt.NumberOfRvaAndSizes as usize, IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+	let len = cmp::min(opt.NumberOfRvaAndSizes.le() as usize, IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
 	slice::from_raw_parts(opt.DataDirectory.as_ptr(), len)
 }
-unsafe fn section_headers(image: &[u8]) -> &super::headers::SectionHeaders {
+unsafe fn declared_data_directory(image: &[u8]) -> &[IMAGE_DATA_DIRECTORY] {
+	let data_directory = data_directory(image);
+	let size_of_optional_header = file_header(image).SizeOfOptionalHeader.le() as usize;
+	let fits = size_of_optional_header.saturating_sub(mem::size_of::<IMAGE_OPTIONAL_HEADER>()) / mem::size_of::<IMAGE_DATA_DIRECTORY>();
+	&data_directory[..cmp::min(data_directory.len(), fits)]
+}
+pub(crate) unsafe fn section_headers(image: &[u8]) -> &super::headers::SectionHeaders {
 	let nt = nt_headers(image);
-	let data = (&nt.OptionalHeader as *const _ as *const u8).offset(nt.FileHeader.SizeOfOptionalHeader as isize) as *const IMAGE_SECTION_HEADER;
-	let raw = slice::from_raw_parts(data, nt.FileHeader.NumberOfSections as usize);
+	let data = (&nt.OptionalHeader as *const _ as *const u8).offset(nt.FileHeader.SizeOfOptionalHeader.le() as isize) as *const IMAGE_SECTION_HEADER;
+	let raw = slice::from_raw_parts(data, nt.FileHeader.NumberOfSections.le() as usize);
 	super::headers::SectionHeaders::new(raw)
 }
 
@@ -669,7 +922,7 @@ unsafe fn slice_section(image: &[u8], rva: Rva, min_size_of: usize, align_of: us
 	}
 }
 unsafe fn read_section(image: &[u8], image_base: Va, va: Va, min_size_of: usize, align_of: usize) -> Result<&[u8]> {
-	let image_size = optional_header(image).SizeOfImage;
+	let image_size = optional_header(image).SizeOfImage.le();
 
 	if va == 0 {
 		Err(Error::Null)
@@ -691,32 +944,250 @@ unsafe fn read_section(image: &[u8], image_base: Va, va: Va, min_size_of: usize,
 	}
 }
 
-unsafe fn range_file(image: &[u8], rva: Rva, min_size_of: usize) -> Result<&[u8]> {
-	// This code has been carefully designed to avoid panicking on overflow
+unsafe fn range_file(image: &[u8], lenient: bool, rva: Rva, min_size_of: usize) -> Result<&[u8]> {
+	let optional_header = optional_header(image);
+	if is_low_alignment(optional_header) {
+		return range_file_flat(image, optional_header.SizeOfImage.le(), rva, min_size_of);
+	}
+	// Images without sections have all their content in the headers
+	if file_header(image).NumberOfSections.le() == 0 {
+		return range_file_flat(image, optional_header.SizeOfHeaders.le(), rva, min_size_of);
+	}
 	for it in section_headers(image) {
-		// Compare if rva is contained within the virtual address space of a section
-		// If the calculating the section end address overflows the corrupt section will be skipped
-		#[allow(non_snake_case)]
-		let VirtualEnd = it.VirtualAddress.wrapping_add(cmp::max(it.VirtualSize, it.SizeOfRawData));
-		// $1
-		if it.VirtualAddress <= rva && rva < VirtualEnd {
-			// Isolate and range check the pointer and size of raw data
-			// If this fails immediately abort and return an error
-			let section_range = it.PointerToRawData as usize..it.PointerToRawData.wrapping_add(it.SizeOfRawData) as usize;
-			let section_bytes = image.get(section_range).ok_or(Error::Invalid)?;
-			// Calculate the offset in the section requested. cannot underflow, see $1
-			let section_offset = (rva - it.VirtualAddress) as usize;
-			return match section_bytes.get(section_offset..) {
-				Some(bytes) if bytes.len() >= min_size_of => Ok(bytes),
-				// Identify the reason the slice fails. cannot underflow, see $1
-				_ => Err(if min_size_of > (VirtualEnd - rva) as usize { Error::Bounds } else { Error::ZeroFill }),
-			};
+		let it = &loader_section(it, optional_header, lenient);
+		if section_contains_rva(it, rva) {
+			return range_file_section(image, it, rva, min_size_of);
 		}
 	}
 	Err(Error::Bounds)
 }
+
+//----------------------------------------------------------------
+// Section address translation helpers
+//
+// Lenient parsing translates addresses with the section headers as the Windows loader interprets them, see `loader_section`.
+//
+// These have been carefully designed to avoid panicking on overflow.
+
+// Calculates the end of the virtual address space of a section.
+// If the calculation overflows the end wraps around and the corrupt section will never contain any rva.
+#[inline]
+fn section_virtual_end(it: &IMAGE_SECTION_HEADER) -> Rva {
+	it.VirtualAddress.wrapping_add(cmp::max(it.VirtualSize, it.SizeOfRawData))
+}
+// Calculates the end of the raw data of a section.
+// If the calculation overflows the end wraps around and the corrupt section will never contain any file offset.
+#[inline]
+fn section_end_of_raw_data(it: &IMAGE_SECTION_HEADER) -> u32 {
+	it.PointerToRawData.wrapping_add(it.SizeOfRawData)
+}
+// Translates sorted values with a cursor over the sections sorted by the range of values they contain.
+// Values in the headers or outside of the sections and all values of images with unsorted or overlapping sections take the slow path.
+fn translate_sorted<T: Copy + PartialOrd, R>(
+	mut sections: Vec<IMAGE_SECTION_HEADER>, values: &[T], size_of_headers: T,
+	range: fn(&IMAGE_SECTION_HEADER) -> ops::Range<T>,
+	convert: fn(&IMAGE_SECTION_HEADER, T) -> Result<R>,
+	slow: impl Fn(T) -> Result<R>,
+) -> Vec<Result<R>> {
+	// Sections with an empty range never contain any value
+	sections.retain(|it| { let range = range(it); range.start < range.end });
+	if !sections.windows(2).all(|pair| range(&pair[0]).end <= range(&pair[1]).start) {
+		return values.iter().map(|&value| slow(value)).collect();
+	}
+	let mut index = 0;
+	let mut prev = None;
+	values.iter().map(|&value| {
+		// Restart the cursor when the values aren't sorted
+		if prev.map_or(false, |prev| value < prev) {
+			index = 0;
+		}
+		prev = Some(value);
+		while index < sections.len() && range(&sections[index]).end <= value {
+			index += 1;
+		}
+		match sections.get(index) {
+			Some(it) if range(it).start <= value && value >= size_of_headers => convert(it, value),
+			_ => slow(value),
+		}
+	}).collect()
+}
+/// Returns true if the image has a `SectionAlignment` below the page size.
+///
+/// The Windows loader maps these images as is, rvas equal file offsets and the section headers are not used for address translation.
+#[inline]
+pub(crate) fn is_low_alignment(optional_header: &IMAGE_OPTIONAL_HEADER) -> bool {
+	let section_alignment = optional_header.SectionAlignment.le();
+	section_alignment < 0x1000 && section_alignment == optional_header.FileAlignment.le()
+}
+/// Converts an rva to file offset in a low alignment image.
+pub(crate) fn flat_rva_to_file_offset(image: &[u8], size_of_image: u32, rva: Rva) -> Result<usize> {
+	if rva >= size_of_image {
+		Err(Error::Bounds)
+	}
+	else if rva as usize >= image.len() {
+		Err(Error::ZeroFill)
+	}
+	else {
+		Ok(rva as usize)
+	}
+}
+/// Converts a file offset to rva in a low alignment image.
+pub(crate) fn flat_file_offset_to_rva(image: &[u8], size_of_image: u32, file_offset: usize) -> Result<Rva> {
+	if file_offset < size_of_image as usize {
+		Ok(file_offset as Rva)
+	}
+	else if file_offset < image.len() {
+		Err(Error::Unmapped)
+	}
+	else {
+		Err(Error::Bounds)
+	}
+}
+/// Slices the file image of a low alignment image.
+///
+/// Also used for images without sections with the size of headers as the size of image.
+pub(crate) fn range_file_flat(image: &[u8], size_of_image: u32, rva: Rva, min_size_of: usize) -> Result<&[u8]> {
+	// The bytes after the end of the image are not mapped
+	let mapped = &image[..cmp::min(image.len(), size_of_image as usize)];
+	match mapped.get(rva as usize..) {
+		Some(bytes) if bytes.len() >= min_size_of => Ok(bytes),
+		// Identify the reason the slice fails
+		_ => Err(if (rva as u64).saturating_add(min_size_of as u64) > size_of_image as u64 { Error::Bounds } else { Error::ZeroFill }),
+	}
+}
+/// Returns the section header as interpreted by the Windows loader if lenient, otherwise a copy of the section header.
+///
+/// The returned section header is in native byte order.
+///
+/// The loader truncates `PointerToRawData` to a 512 byte boundary and rounds `SizeOfRawData` up to `FileAlignment`, limited by the section's virtual size.
+/// Images with a `SectionAlignment` below the page size are mapped as is and are left alone.
+#[inline]
+pub(crate) fn loader_section(it: &IMAGE_SECTION_HEADER, optional_header: &IMAGE_OPTIONAL_HEADER, lenient: bool) -> IMAGE_SECTION_HEADER {
+	let mut section = it.le();
+	let file_alignment = optional_header.FileAlignment.le();
+	let section_alignment = optional_header.SectionAlignment.le();
+	if lenient && section_alignment >= 0x1000 {
+		section.PointerToRawData &= !0x1ff;
+		if file_alignment.is_power_of_two() && section_alignment.is_power_of_two() {
+			let virtual_size = if section.VirtualSize != 0 { section.VirtualSize } else { section.SizeOfRawData };
+			let size_of_raw_data = (section.SizeOfRawData as u64).align_to(file_alignment as u64);
+			let virtual_size = (virtual_size as u64).align_to(section_alignment as u64);
+			section.SizeOfRawData = cmp::min(cmp::min(size_of_raw_data, virtual_size), u32::MAX as u64) as u32;
+		}
+	}
+	section
+}
+/// Returns the range of rvas a section contains, the range is empty for corrupt sections.
+#[inline]
+pub(crate) fn section_rva_range(it: &IMAGE_SECTION_HEADER) -> ops::Range<Rva> {
+	it.VirtualAddress..section_virtual_end(it)
+}
+/// Returns the range of file offsets a section contains, the range is empty for corrupt sections.
+#[inline]
+pub(crate) fn section_file_range(it: &IMAGE_SECTION_HEADER) -> ops::Range<usize> {
+	it.PointerToRawData as usize..section_end_of_raw_data(it) as usize
+}
+/// Compares if rva is contained within the virtual address space of a section.
+#[inline]
+pub(crate) fn section_contains_rva(it: &IMAGE_SECTION_HEADER, rva: Rva) -> bool {
+	it.VirtualAddress <= rva && rva < section_virtual_end(it)
+}
+/// Compares if file_offset is contained within the raw data of a section.
+#[inline]
+pub(crate) fn section_contains_file_offset(it: &IMAGE_SECTION_HEADER, file_offset: usize) -> bool {
+	it.PointerToRawData as usize <= file_offset && file_offset < section_end_of_raw_data(it) as usize
+}
+/// Converts an rva contained within the section to file offset.
+pub(crate) fn section_rva_to_file_offset(it: &IMAGE_SECTION_HEADER, rva: Rva) -> Result<usize> {
+	debug_assert!(section_contains_rva(it, rva));
+	// Check if the raw data reference is sane
+	// $1
+	if it.PointerToRawData.checked_add(it.SizeOfRawData).is_none() {
+		return Err(Error::Overflow);
+	}
+	// Calculate the offset in the section. cannot underflow as the rva is contained in the section
+	let section_offset = rva - it.VirtualAddress;
+	// $2
+	if section_offset < it.SizeOfRawData {
+		// Calculate the final offset in the file. cannot overflow, see $1 and $2
+		Ok((section_offset + it.PointerToRawData) as usize)
+	}
+	// Identify the reason the conversion fails
+	else if section_offset < it.VirtualSize {
+		Err(Error::ZeroFill)
+	}
+	else {
+		Err(Error::Bounds)
+	}
+}
+/// Converts a file offset contained within the section to rva.
+pub(crate) fn section_file_offset_to_rva(it: &IMAGE_SECTION_HEADER, file_offset: usize) -> Result<Rva> {
+	debug_assert!(section_contains_file_offset(it, file_offset));
+	// Check if the virtual reference is sane
+	// $1
+	if it.VirtualAddress.checked_add(it.VirtualSize).is_none() {
+		return Err(Error::Overflow);
+	}
+	// Calculate the offset in the section. cannot underflow as the file offset is contained in the section
+	let section_offset = file_offset as Rva - it.PointerToRawData;
+	// $2
+	if section_offset < it.VirtualSize {
+		// Calculate the final virtual address. cannot overflow, see $1 and $2
+		Ok(section_offset + it.VirtualAddress)
+	}
+	// Identify the reason the conversion fails
+	else if section_offset < it.SizeOfRawData {
+		Err(Error::Unmapped)
+	}
+	else {
+		Err(Error::Bounds)
+	}
+}
+/// Slices the file image at an rva contained within the section.
+pub(crate) fn range_file_section<'a>(image: &'a [u8], it: &IMAGE_SECTION_HEADER, rva: Rva, min_size_of: usize) -> Result<&'a [u8]> {
+	debug_assert!(section_contains_rva(it, rva));
+	// Isolate and range check the pointer and size of raw data
+	// If this fails immediately abort and return an error
+	let section_bytes = image.get(section_file_range(it)).ok_or(Error::Invalid)?;
+	// Calculate the offset in the section requested. cannot underflow as the rva is contained in the section
+	let section_offset = (rva - it.VirtualAddress) as usize;
+	match section_bytes.get(section_offset..) {
+		Some(bytes) if bytes.len() >= min_size_of => Ok(bytes),
+		// Identify the reason the slice fails. cannot underflow as the rva is contained in the section
+		_ => Err(if min_size_of > (section_virtual_end(it) - rva) as usize { Error::Bounds } else { Error::ZeroFill }),
+	}
+}
+
+/// Checks the alignment of the bytes in the file image.
+///
+/// The raw data of sections isn't necessarily aligned the same way as their rvas.
+#[inline]
+pub(crate) fn check_aligned(bytes: &[u8], align_of: usize) -> Result<&[u8]> {
+	if bytes.as_ptr().aligned_to(align_of) { Ok(bytes) } else { Err(Error::Misaligned) }
+}
+/// Slices the bytes at the rva extending across the sections which follow it in both the virtual and the file layout.
+#[cfg(any(feature = "std", feature = "resources_nostd"))]
+fn stitch_sections<'a, P: Pe<'a>>(pe: P, rva: Rva, size: usize) -> Result<&'a [u8]> {
+	let mut bytes = pe.slice_bytes(rva)?;
+	if pe.align() == Align::File && bytes.len() < size {
+		let start = pe.rva_to_file_offset(rva)?;
+		while bytes.len() < size {
+			let next = match rva.checked_add(bytes.len() as Rva) {
+				Some(next) => next,
+				None => break,
+			};
+			match (pe.rva_to_file_offset(next), pe.slice_bytes(next)) {
+				(Ok(offset), Ok(more)) if offset == start + bytes.len() && !more.is_empty() => {
+					bytes = &pe.image()[start..offset + more.len()];
+				},
+				_ => break,
+			}
+		}
+	}
+	Ok(bytes)
+}
 #[inline(never)]
-unsafe fn slice_file(image: &[u8], rva: Rva, min_size_of: usize, align_of: usize) -> Result<&[u8]> {
+unsafe fn slice_file(image: &[u8], lenient: bool, rva: Rva, min_size_of: usize, align_of: usize) -> Result<&[u8]> {
 	if rva == 0 {
 		Err(Error::Null)
 	}
@@ -724,12 +1195,12 @@ unsafe fn slice_file(image: &[u8], rva: Rva, min_size_of: usize, align_of: usize
 		Err(Error::Misaligned)
 	}
 	else {
-		range_file(image, rva, min_size_of)
+		range_file(image, lenient, rva, min_size_of).and_then(|bytes| check_aligned(bytes, align_of))
 	}
 }
 #[inline(never)]
-unsafe fn read_file(image: &[u8], image_base: Va, va: Va, min_size_of: usize, align_of: usize) -> Result<&[u8]> {
-	let size_of_image = optional_header(image).SizeOfImage;
+unsafe fn read_file(image: &[u8], lenient: bool, image_base: Va, va: Va, min_size_of: usize, align_of: usize) -> Result<&[u8]> {
+	let size_of_image = optional_header(image).SizeOfImage.le();
 
 	if va == 0 {
 		Err(Error::Null)
@@ -743,7 +1214,7 @@ unsafe fn read_file(image: &[u8], image_base: Va, va: Va, min_size_of: usize, al
 			Err(Error::Misaligned)
 		}
 		else {
-			range_file(image, rva, min_size_of)
+			range_file(image, lenient, rva, min_size_of).and_then(|bytes| check_aligned(bytes, align_of))
 		}
 	}
 }
@@ -752,7 +1223,23 @@ unsafe fn read_file(image: &[u8], image_base: Va, va: Va, min_size_of: usize, al
 
 // TODO: This code needs to be audited...
 // The safety of `Pe` relies on it.
-pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
+pub(crate) fn validate_headers(image: &[u8], options: &ParseOptions) -> Result<u32> {
+	validate_headers_lax(image, options).map(|(size_of_image, _)| size_of_image)
+}
+/// Validates the headers, tolerating the violations the Windows loader accepts if the options are lenient.
+pub(crate) fn validate_headers_lax(image: &[u8], options: &ParseOptions) -> Result<(u32, Violations)> {
+	let mut violations = Violations::empty();
+	// Records the violation in lenient mode, fails with the error otherwise
+	let mut violation = |violation: Violation, err: Error| {
+		if options.lenient {
+			violations.insert(violation);
+			Ok(())
+		}
+		else {
+			Err(err)
+		}
+	};
+
 	// Grab the DOS header
 	if mem::size_of::<IMAGE_DOS_HEADER>() > image.len() {
 		return Err(Error::Bounds);
@@ -761,7 +1248,8 @@ pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
 	if !image.as_ptr().aligned_to(4) {
 		return Err(Error::Misaligned);
 	}
-	let dos = unsafe { &*(image.as_ptr() as *const IMAGE_DOS_HEADER) };
+	// The headers are copied in native byte order
+	let dos = unsafe { (*(image.as_ptr() as *const IMAGE_DOS_HEADER)).le() };
 	// Verify the DOS header
 	if dos.e_magic != IMAGE_DOS_SIGNATURE {
 		return Err(Error::BadMagic);
@@ -773,29 +1261,51 @@ pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
 	// Prevent overflow the easy way...
 	// When changing, take care of overflow in later offset calculations!
 	if dos.e_lfanew > 0x01000000 {
-		return Err(Error::Insanity);
+		violation(Violation::LfanewLimit, Error::Insanity)?;
 	}
 
 	// Grab the NT headers
+	// Lenient mode allows any e_lfanew, bound it by the image size before doing further offset calculations
+	if dos.e_lfanew as usize > image.len() {
+		return Err(Error::Bounds);
+	}
 	let nt_end = dos.e_lfanew as usize + mem::size_of::<IMAGE_NT_HEADERS>();
 	if nt_end > image.len() {
 		return Err(Error::Bounds);
 	}
-	let nt = unsafe { &*(image.as_ptr().offset(dos.e_lfanew as isize) as *const IMAGE_NT_HEADERS) };
+	let nt = unsafe { ptr::read_unaligned(image.as_ptr().offset(dos.e_lfanew as isize) as *const IMAGE_NT_HEADERS).le() };
 	// Verify the NT headers
 	if nt.Signature != IMAGE_NT_HEADERS_SIGNATURE || !(nt.OptionalHeader.Magic == IMAGE_NT_OPTIONAL_HDR32_MAGIC || nt.OptionalHeader.Magic == IMAGE_NT_OPTIONAL_HDR64_MAGIC) {
 		return Err(Error::BadMagic);
 	}
 	if nt.OptionalHeader.SizeOfHeaders as usize > image.len() {
-		return Err(Error::Bounds);
+		violation(Violation::HeadersBounds, Error::Bounds)?;
 	}
 	if nt.OptionalHeader.SizeOfHeaders > nt.OptionalHeader.SizeOfImage {
-		return Err(Error::Insanity);
+		violation(Violation::HeadersExceedImage, Error::Insanity)?;
 	}
 	// Give the caller a chance to retry with the correct parser
 	if nt.OptionalHeader.Magic != IMAGE_NT_OPTIONAL_HDR_MAGIC {
 		return Err(Error::PeMagic);
 	}
+	// The NT headers are accessed in place, PE32+ headers need 8 byte alignment
+	if !(cfg!(feature = "unsafe_alignment") || (image.as_ptr() as usize).wrapping_add(dos.e_lfanew as usize).aligned_to(mem::align_of::<IMAGE_NT_HEADERS>())) {
+		return Err(Error::Misaligned);
+	}
+	// Only recorded once the headers are known to be accessible in place
+	if options.lenient && !dos.e_lfanew.aligned_to(8) {
+		violation(Violation::LfanewAlignment, Error::Misaligned)?;
+	}
+
+	// Check the alignments, these are only recorded
+	let file_alignment = nt.OptionalHeader.FileAlignment;
+	let section_alignment = nt.OptionalHeader.SectionAlignment;
+	if options.lenient && (!file_alignment.is_power_of_two() || (file_alignment != section_alignment && !(0x200..=0x10000).contains(&file_alignment))) {
+		violation(Violation::FileAlignment, Error::Invalid)?;
+	}
+	if options.lenient && (!section_alignment.is_power_of_two() || section_alignment < file_alignment) {
+		violation(Violation::SectionAlignment, Error::Invalid)?;
+	}
 
 	// Verify the data directory
 	let num_rva_sizes = cmp::min(nt.OptionalHeader.NumberOfRvaAndSizes as usize, IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
@@ -805,12 +1315,12 @@ pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
 	}
 
 	// Verify the section headers
-	if nt.FileHeader.NumberOfSections > 96 {
-		return Err(Error::Insanity);
+	if nt.FileHeader.NumberOfSections > options.max_sections {
+		violation(Violation::TooManySections, Error::Insanity)?;
 	}
 	// u16 * sizeof(T) casted to usize, cannot reasonably overflow
 	let size_of_sections = nt.FileHeader.NumberOfSections as usize * mem::size_of::<IMAGE_SECTION_HEADER>();
-	// e_lfanew is checked for reasonable values, the others then cannot reasonably cause overflow
+	// e_lfanew is checked to be within the image, the others then cannot reasonably cause overflow
 	#[rustfmt::skip]
 	let start_of_sections = dos.e_lfanew as usize
 		+ (mem::size_of::<IMAGE_NT_HEADERS>() - mem::size_of::<IMAGE_OPTIONAL_HEADER>())
@@ -819,7 +1329,11 @@ pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
 	if size_of_sections + start_of_sections > image.len() {
 		return Err(Error::Bounds);
 	}
-	Ok(nt.OptionalHeader.SizeOfImage)
+	// The section headers are accessed in place
+	if !(cfg!(feature = "unsafe_alignment") || start_of_sections.aligned_to(4)) {
+		return Err(Error::Misaligned);
+	}
+	Ok((nt.OptionalHeader.SizeOfImage, violations))
 }
 
 /// Returns the PE headers as mutable borrows.
@@ -830,12 +1344,12 @@ pub(crate) fn validate_headers(image: &[u8]) -> Result<u32> {
 #[cfg(feature = "unstable")]
 pub unsafe fn headers_mut(image: &mut [u8]) -> (&mut IMAGE_DOS_HEADER, &mut IMAGE_NT_HEADERS, &mut [IMAGE_DATA_DIRECTORY], &mut [IMAGE_SECTION_HEADER]) {
 	let dos = &mut *(image.as_mut_ptr() as *mut IMAGE_DOS_HEADER);
-	let nt = &mut *(image.as_mut_ptr().offset(dos.e_lfanew as isize) as *mut IMAGE_NT_HEADERS);
+	let nt = &mut *(image.as_mut_ptr().offset(dos.e_lfanew.le() as isize) as *mut IMAGE_NT_HEADERS);
 	let dd_ptr = nt.OptionalHeader.DataDirectory.as_mut_ptr();
-	let dd_len = cmp::min(nt.OptionalHeader.NumberOfRvaAndSizes as usize, IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+	let dd_len = cmp::min(nt.OptionalHeader.NumberOfRvaAndSizes.le() as usize, IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
 	let dd = slice::from_raw_parts_mut(dd_ptr, dd_len);
-	let sections_ptr = (&mut nt.OptionalHeader as *mut _ as *mut u8).offset(nt.FileHeader.SizeOfOptionalHeader as isize) as *mut IMAGE_SECTION_HEADER;
-	let sections_len = nt.FileHeader.NumberOfSections as usize;
+	let sections_ptr = (&mut nt.OptionalHeader as *mut _ as *mut u8).offset(nt.FileHeader.SizeOfOptionalHeader.le() as isize) as *mut IMAGE_SECTION_HEADER;
+	let sections_len = nt.FileHeader.NumberOfSections.le() as usize;
 	let sections = slice::from_raw_parts_mut(sections_ptr, sections_len);
 	(dos, nt, dd, sections)
 }
@@ -0,0 +1,156 @@
+/*!
+Headers of truncated files.
+*/
+
+use std::cmp;
+
+use crate::stringify::{DllChars, FileChars, Machine, Subsystem};
+use crate::util::Le;
+use crate::{Error, ParseOptions, Result, Violation, Violations};
+
+use super::image::*;
+use super::pe::{self, validate_headers_lax};
+
+/// Headers of a possibly truncated PE file.
+///
+/// Parses only the DOS, NT and section headers.
+/// This is useful for large-scale triage where only the start of a file is available, eg. the first 4 KiB fetched over the network.
+///
+/// The buffer must contain at least the section headers, which end at [`headers_len`](#method.headers_len).
+/// Everything else, including the rest of `SizeOfHeaders`, may be truncated.
+///
+/// # Examples
+///
+/// ```
+/// use pelite::pe64::PeHeaders;
+///
+/// # #[allow(dead_code)]
+/// fn triage(first_4k: &[u8]) -> pelite::Result<()> {
+/// 	let headers = PeHeaders::from_bytes(first_4k)?;
+/// 	println!("machine: {}", headers.machine());
+/// 	println!("expected file size: {}", headers.file_size());
+/// 	for section in headers.section_headers() {
+/// 		println!("section: {}", section.name().unwrap_or("?"));
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct PeHeaders<'a> {
+	image: &'a [u8],
+	violations: Violations,
+}
+
+impl<'a> PeHeaders<'a> {
+	/// Parses the headers from the start of a PE file.
+	///
+	/// Header violations which the Windows loader accepts are recorded and available through [`violations`](#method.violations).
+	///
+	/// # Errors
+	///
+	/// See [`PeFile::from_bytes`](struct.PeFile.html#method.from_bytes) for a list of errors.
+	/// `Bounds` is only returned when the buffer is too small to fit the section headers.
+	pub fn from_bytes<T: AsRef<[u8]> + ?Sized>(image: &'a T) -> Result<PeHeaders<'a>> {
+		PeHeaders::from_bytes_with_options(image, ParseOptions::LENIENT)
+	}
+	/// Parses the headers from the start of a PE file with custom parse options.
+	pub fn from_bytes_with_options<T: AsRef<[u8]> + ?Sized>(image: &'a T, options: ParseOptions) -> Result<PeHeaders<'a>> {
+		let image = image.as_ref();
+		// Truncation of SizeOfHeaders is expected, validate leniently and apply the strict checks afterwards
+		let (_, lenient_violations) = validate_headers_lax(image, &ParseOptions { lenient: true, ..options })?;
+		let mut violations = Violations::empty();
+		for violation in lenient_violations.iter().filter(|&violation| violation != Violation::HeadersBounds) {
+			if !options.lenient {
+				if violation.is_fatal() {
+					return Err(Error::Insanity);
+				}
+				continue;
+			}
+			violations.insert(violation);
+		}
+		Ok(PeHeaders { image, violations })
+	}
+	/// Returns the bytes the headers were parsed from.
+	pub fn image(&self) -> &'a [u8] {
+		self.image
+	}
+	/// Returns the header violations tolerated by the lenient parser.
+	///
+	/// Truncation of the headers is expected and not recorded as a violation.
+	pub fn violations(&self) -> Violations {
+		self.violations
+	}
+	/// Returns the DOS header.
+	pub fn dos_header(&self) -> &'a IMAGE_DOS_HEADER {
+		unsafe { pe::dos_header(self.image) }
+	}
+	/// Returns the NT headers.
+	pub fn nt_headers(&self) -> &'a IMAGE_NT_HEADERS {
+		unsafe { pe::nt_headers(self.image) }
+	}
+	/// Returns the file header.
+	pub fn file_header(&self) -> &'a IMAGE_FILE_HEADER {
+		unsafe { pe::file_header(self.image) }
+	}
+	/// Returns the optional header.
+	pub fn optional_header(&self) -> &'a IMAGE_OPTIONAL_HEADER {
+		unsafe { pe::optional_header(self.image) }
+	}
+	/// Returns the data directory.
+	pub fn data_directory(&self) -> &'a [IMAGE_DATA_DIRECTORY] {
+		unsafe { pe::data_directory(self.image) }
+	}
+	/// Returns the section headers.
+	pub fn section_headers(&self) -> &'a super::headers::SectionHeaders {
+		unsafe { pe::section_headers(self.image) }
+	}
+	/// Gets the typed machine from the file header.
+	pub fn machine(&self) -> Machine {
+		Machine(self.file_header().Machine.le())
+	}
+	/// Gets the typed characteristics from the file header.
+	pub fn file_characteristics(&self) -> FileChars {
+		FileChars(self.file_header().Characteristics.le())
+	}
+	/// Gets the typed subsystem from the optional header.
+	pub fn subsystem(&self) -> Subsystem {
+		Subsystem(self.optional_header().Subsystem.le())
+	}
+	/// Gets the typed dll characteristics from the optional header.
+	pub fn dll_characteristics(&self) -> DllChars {
+		DllChars(self.optional_header().DllCharacteristics.le())
+	}
+	/// Returns the number of bytes needed to parse the headers, the end of the section headers.
+	pub fn headers_len(&self) -> usize {
+		let section_headers = self.section_headers().image();
+		section_headers.as_ptr() as usize - self.image.as_ptr() as usize + std::mem::size_of_val(section_headers)
+	}
+	/// Calculates the expected size of the complete file.
+	///
+	/// This is the end of the headers, the section raw data or the certificate table, whichever is furthest.
+	/// Any overlay data appended to the file other than the certificate table is not accounted for.
+	pub fn file_size(&self) -> u64 {
+		let mut file_size = self.optional_header().SizeOfHeaders.le() as u64;
+		for section in self.section_headers() {
+			let section = section.le();
+			if section.SizeOfRawData != 0 {
+				file_size = cmp::max(file_size, section.PointerToRawData as u64 + section.SizeOfRawData as u64);
+			}
+		}
+		// The certificate table is not mapped and its address is a file offset
+		if let Some(security) = self.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY).map(|security| security.le()) {
+			if security.VirtualAddress != 0 {
+				file_size = cmp::max(file_size, security.VirtualAddress as u64 + security.Size as u64);
+			}
+		}
+		file_size
+	}
+	/// Returns the size of the image when mapped in memory.
+	pub fn image_size(&self) -> u32 {
+		self.optional_header().SizeOfImage.le()
+	}
+	/// Returns true if the given number of bytes covers the expected size of the complete file.
+	pub fn is_complete(&self, len: u64) -> bool {
+		len >= self.file_size()
+	}
+}
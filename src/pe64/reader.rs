@@ -0,0 +1,340 @@
+/*!
+Parsing images from a reader.
+
+The [`PeFile`](../struct.PeFile.html) and [`PeView`](../struct.PeView.html) types require the whole image in memory.
+Multi-gigabyte binaries or files embedded in disk images are better read on demand.
+
+The reader maps the image page by page into a zero allocated buffer as large as the image, only the pages which are explicitly loaded are read from the source.
+Allocators hand out fresh pages for large zeroed allocations, the memory of the pages which are never loaded is typically never committed.
+Its size can be limited with [`max_image_size`](../../struct.ParseOptions.html#structfield.max_image_size) as `SizeOfImage` comes straight from the untrusted headers.
+
+Parse the directories through the [`view`](struct.PeReader.html#method.view) after loading the pages they reference.
+Referencing data in pages which are not loaded fails with [`ZeroFill`](../../enum.Error.html#variant.ZeroFill) and slices end at the first page which is not loaded,
+only the [`image`](../trait.PeObject.html#tymethod.image) of the view exposes the unloaded pages as zeroes.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use std::fs::File;
+use pelite::image::IMAGE_DIRECTORY_ENTRY_EXPORT;
+use pelite::pe64::{Pe, PeReader};
+
+# #[allow(dead_code)]
+fn example(file: File) -> Result<(), pelite::pe64::ReadError> {
+	let mut reader = PeReader::new(file)?;
+
+	// Load the export directory, the names it references live in the same section
+	reader.load_directory(IMAGE_DIRECTORY_ENTRY_EXPORT)?;
+	let exports = reader.view().exports()?;
+	println!("{:?}", exports.dll_name()?);
+
+	Ok(())
+}
+```
+*/
+
+use std::alloc::{self, Layout};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::{cmp, error, fmt};
+
+use crate::util::Le;
+use crate::{Error, ParseOptions};
+
+use super::image::*;
+use super::{Align, Pe, PeHeaders, PeObject, PeView, Rva};
+
+/// Size of the pages loaded at once.
+pub const PAGE_SIZE: usize = 0x1000;
+
+// Gives up on finding the end of the section headers past this many bytes
+const MAX_HEADERS_LEN: usize = 0x10_0000;
+
+//----------------------------------------------------------------
+
+/// Reader error.
+#[derive(Debug)]
+pub enum ReadError {
+	/// An error happened reading the source.
+	Io(io::Error),
+	/// The image is corrupt.
+	Pe(Error),
+}
+impl From<io::Error> for ReadError {
+	fn from(err: io::Error) -> ReadError {
+		ReadError::Io(err)
+	}
+}
+impl From<Error> for ReadError {
+	fn from(err: Error) -> ReadError {
+		ReadError::Pe(err)
+	}
+}
+impl fmt::Display for ReadError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ReadError::Io(err) => err.fmt(f),
+			ReadError::Pe(err) => err.fmt(f),
+		}
+	}
+}
+impl error::Error for ReadError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			ReadError::Io(err) => Some(err),
+			ReadError::Pe(err) => Some(err),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Image read on demand.
+///
+/// For more information see the [module-level documentation](index.html).
+pub struct PeReader<R> {
+	reader: R,
+	image: Vec<u8>,
+	loaded: Vec<bool>,
+	size_of_headers: usize,
+	options: ParseOptions,
+}
+
+impl<R: Read + Seek> PeReader<R> {
+	/// Reads the headers from the source.
+	///
+	/// # Errors
+	///
+	/// See [`PeView::from_bytes`](../struct.PeView.html#method.from_bytes) for a list of errors.
+	/// Returns `Insanity` if the `SizeOfImage` doesn't cover the headers.
+	pub fn new(reader: R) -> Result<PeReader<R>, ReadError> {
+		PeReader::with_options(reader, ParseOptions::DEFAULT)
+	}
+	/// Reads the headers from the source with custom parse options.
+	///
+	/// # Errors
+	///
+	/// See [`PeView::from_bytes_with_options`](../struct.PeView.html#method.from_bytes_with_options) for a list of errors.
+	/// Returns `Insanity` if the `SizeOfImage` doesn't cover the headers or exceeds the `max_image_size`.
+	/// Returns an `Io` error of kind `OutOfMemory` if the buffer for the image cannot be allocated.
+	pub fn with_options(mut reader: R, options: ParseOptions) -> Result<PeReader<R>, ReadError> {
+		// Read just enough to find the size of the headers
+		let mut headers = vec![0; PAGE_SIZE];
+		let pe_headers = loop {
+			let len = read_at(&mut reader, 0, &mut headers)?;
+			match PeHeaders::from_bytes_with_options(&headers[..len], options) {
+				// The section headers may not fit in the first page
+				Err(Error::Bounds) if len == headers.len() && len < MAX_HEADERS_LEN => headers.resize(len * 2, 0),
+				result => break result?,
+			}
+		};
+		let size_of_headers = pe_headers.optional_header().SizeOfHeaders.le() as usize;
+		let size_of_image = pe_headers.image_size() as usize;
+		if size_of_image < size_of_headers || size_of_image > options.max_image_size {
+			return Err(ReadError::Pe(Error::Insanity));
+		}
+
+		// The headers are mapped as is at the start of the image
+		let mut image = zeroed(size_of_image).ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+		read_at(&mut reader, 0, &mut image[..size_of_headers])?;
+		let _ = PeView::from_bytes_with_options(&image, options)?;
+		let loaded = vec![false; (size_of_image + PAGE_SIZE - 1) / PAGE_SIZE];
+		let mut pe_reader = PeReader { reader, image, loaded, size_of_headers, options };
+		// Sections may share the pages of the headers in images with a small section alignment
+		pe_reader.load(0, size_of_headers)?;
+		Ok(pe_reader)
+	}
+	/// Loads the pages covering the given range.
+	///
+	/// The range is clamped to the size of the image.
+	pub fn load(&mut self, rva: Rva, len: usize) -> Result<(), ReadError> {
+		let start = cmp::min(rva as usize, self.image.len());
+		let end = cmp::min(start.saturating_add(len), self.image.len());
+		for page in start / PAGE_SIZE..(end + PAGE_SIZE - 1) / PAGE_SIZE {
+			if !self.loaded[page] {
+				self.load_page(page)?;
+			}
+		}
+		Ok(())
+	}
+	/// Loads the pages covering a data directory.
+	///
+	/// Data referenced by the directory outside of its own range, eg. the names of the imports, must be loaded separately.
+	///
+	/// Returns [`Err(Null)`](../enum.Error.html#variant.Null) if the data directory is not present.
+	pub fn load_directory(&mut self, index: usize) -> Result<(), ReadError> {
+		let datadir = self.view().data_directory().get(index).ok_or(Error::Bounds)?.le();
+		if datadir.VirtualAddress == 0 {
+			return Err(ReadError::Pe(Error::Null));
+		}
+		self.load(datadir.VirtualAddress, datadir.Size as usize)
+	}
+	/// Loads the pages of a section.
+	pub fn load_section(&mut self, index: usize) -> Result<(), ReadError> {
+		let section = self.view().section_headers().image().get(index).ok_or(Error::Bounds)?.le();
+		self.load(section.VirtualAddress, cmp::max(section.VirtualSize, section.SizeOfRawData) as usize)
+	}
+	/// Returns true if the page containing the rva has been loaded.
+	pub fn is_loaded(&self, rva: Rva) -> bool {
+		self.loaded.get(rva as usize / PAGE_SIZE).copied().unwrap_or(false)
+	}
+	/// Returns the underlying reader.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+	fn load_page(&mut self, page: usize) -> Result<(), ReadError> {
+		let page_start = page * PAGE_SIZE;
+		let page_end = cmp::min(page_start + PAGE_SIZE, self.image.len());
+		// Copy the parts of the sections with raw data overlapping this page
+		let section_headers: Vec<_> = self.view().section_headers().image().iter().map(|section| section.le()).collect();
+		for section in &section_headers {
+			let virtual_size = if section.VirtualSize != 0 { section.VirtualSize } else { section.SizeOfRawData };
+			let section_start = section.VirtualAddress as usize;
+			let section_end = section_start.saturating_add(cmp::min(virtual_size, section.SizeOfRawData) as usize);
+			let start = cmp::max(cmp::max(section_start, page_start), self.size_of_headers);
+			let end = cmp::min(section_end, page_end);
+			if start < end {
+				let offset = section.PointerToRawData as u64 + (start - section_start) as u64;
+				read_at(&mut self.reader, offset, &mut self.image[start..end])?;
+			}
+		}
+		self.loaded[page] = true;
+		Ok(())
+	}
+}
+
+impl<R> PeReader<R> {
+	/// Borrows the loaded pages as an image view.
+	#[inline]
+	pub fn view(&self) -> ReaderView<'_> {
+		// The headers were validated when the reader was constructed and loading pages never writes to them
+		let view = PeView::from_bytes_with_options(&self.image, self.options).unwrap();
+		ReaderView { view, loaded: &self.loaded }
+	}
+}
+
+impl<R> fmt::Debug for PeReader<R> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("PeReader")
+			.field("image.len", &self.image.len())
+			.field("loaded", &self.loaded.iter().filter(|&&loaded| loaded).count())
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
+/// View of the loaded pages.
+///
+/// Behaves like a [`PeView`](../struct.PeView.html) except that referencing data in pages which are not loaded fails with [`Err(ZeroFill)`](../../enum.Error.html#variant.ZeroFill).
+/// Slices end at the first page which is not loaded.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone)]
+pub struct ReaderView<'a> {
+	view: PeView<'a>,
+	loaded: &'a [bool],
+}
+
+impl<'a> ReaderView<'a> {
+	/// Gets the view of the whole image, the pages which are not loaded read as zeroes.
+	pub fn pe(&self) -> PeView<'a> {
+		self.view
+	}
+	// Cuts the bytes off at the first page which is not loaded
+	fn loaded(&self, bytes: &'a [u8], min_size_of: usize) -> crate::Result<&'a [u8]> {
+		if bytes.is_empty() {
+			return Ok(bytes);
+		}
+		let start = bytes.as_ptr() as usize - self.view.image().as_ptr() as usize;
+		let end = match self.loaded[start / PAGE_SIZE..].iter().position(|&loaded| !loaded) {
+			Some(pages) => (start / PAGE_SIZE + pages) * PAGE_SIZE,
+			None => self.view.image().len(),
+		};
+		let len = cmp::min(end.saturating_sub(start), bytes.len());
+		if len == 0 || len < min_size_of {
+			return Err(Error::ZeroFill);
+		}
+		Ok(&bytes[..len])
+	}
+}
+
+unsafe impl<'a> PeObject<'a> for ReaderView<'a> {
+	fn image(&self) -> &'a [u8] {
+		self.view.image()
+	}
+	fn align(&self) -> Align {
+		Align::Section
+	}
+	fn image_base(&self) -> Va {
+		self.view.image_base()
+	}
+	fn options(&self) -> ParseOptions {
+		self.view.options()
+	}
+	#[cfg(feature = "serde")]
+	fn serde_name(&self) -> &'static str {
+		"ReaderView"
+	}
+}
+
+unsafe impl<'a> Pe<'a> for ReaderView<'a> {
+	fn slice(&self, rva: Rva, min_size_of: usize, align: usize) -> crate::Result<&'a [u8]> {
+		self.loaded(self.view.slice(rva, min_size_of, align)?, min_size_of)
+	}
+	fn get_section_bytes(self, section_header: &IMAGE_SECTION_HEADER) -> crate::Result<&'a [u8]> {
+		let bytes = self.view.get_section_bytes(section_header)?;
+		self.loaded(bytes, bytes.len())
+	}
+	fn read(&self, va: Va, min_size_of: usize, align: usize) -> crate::Result<&'a [u8]> {
+		self.loaded(self.view.read(va, min_size_of, align)?, min_size_of)
+	}
+}
+
+impl<'a> fmt::Debug for ReaderView<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ReaderView")
+			.field("image.len", &self.view.image().len())
+			.field("loaded", &self.loaded.iter().filter(|&&loaded| loaded).count())
+			.finish()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for ReaderView<'a> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		super::pe::serialize_pe(*self, serializer)
+	}
+}
+
+//----------------------------------------------------------------
+
+// Allocates the zeroed buffer without touching its pages
+fn zeroed(len: usize) -> Option<Vec<u8>> {
+	if len == 0 {
+		return Some(Vec::new());
+	}
+	let layout = Layout::array::<u8>(len).ok()?;
+	unsafe {
+		let ptr = alloc::alloc_zeroed(layout);
+		if ptr.is_null() {
+			return None;
+		}
+		Some(Vec::from_raw_parts(ptr, len, len))
+	}
+}
+
+// Reads as many bytes as available at the offset, a truncated source leaves the rest zero
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+	reader.seek(SeekFrom::Start(offset))?;
+	let mut len = 0;
+	while len < buf.len() {
+		match reader.read(&mut buf[len..]) {
+			Ok(0) => break,
+			Ok(n) => len += n,
+			Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+			Err(err) => return Err(err),
+		}
+	}
+	Ok(len)
+}
@@ -28,16 +28,23 @@ fn example(file: PeFile<'_>, pat: &[pat::Atom]) {
 	while matches.next(&mut save) {
 		println!("{:x?}", save);
 	}
+
+	// Logs the section, function, export and surrounding bytes of the matches
+	let mut matches = scanner.matches_code(pat);
+	while let Some(context) = matches.next_context(&mut save, 32) {
+		print!("{}", context);
+	}
 }
 ```
 */
 
 use std::ops::Range;
-use std::{cmp, mem, ptr};
+use std::{cmp, fmt, mem, ptr};
 
-use crate::util::AlignTo;
+use crate::util::{AlignTo, Le};
 use crate::{pattern as pat, Pod};
 
+use super::exports::RvaExport;
 use super::{image::*, Align, Pe, Rva};
 
 /// Size of the prefix buffer for search optimization.
@@ -106,6 +113,76 @@ impl<'a, P: Pe<'a>> Scanner<P> {
 	pub fn exec(&self, cursor: Rva, pat: &[pat::Atom], save: &mut [Rva]) -> bool {
 		Exec { pe: self.pe, pat, cursor, pc: 0 }.exec(save)
 	}
+	/// Gathers the context of a match for logging.
+	///
+	/// Looks up the section, the function from the exception directory and the closest export,
+	/// and captures up to `radius` bytes before and after the match within its section.
+	pub fn context(&self, rva: Rva, radius: u32) -> Context<'a> {
+		let pe = self.pe;
+		let section_headers = pe.section_headers();
+		let section = section_headers.iter().position(|section| section.virtual_range().contains(&rva));
+		let function = pe.exception().and_then(|exception| exception.lookup(rva)).ok()
+			.map(|function| function.image().le())
+			.map(|image| image.BeginAddress..image.EndAddress);
+		let export = pe.exports().and_then(|exports| exports.rva_order()).ok()
+			.and_then(|rva_order| rva_order.owner(rva).copied());
+
+		// Clamp the window to the section containing the match
+		let floor = section.map_or(0, |index| section_headers.image()[index].VirtualAddress.le());
+		let window_rva = cmp::max(rva.saturating_sub(radius), floor);
+		let window = pe.slice_bytes(window_rva).unwrap_or(&[]);
+		let window = &window[..cmp::min(window.len(), (rva - window_rva) as usize + radius as usize)];
+
+		Context { rva, section, function, export, window_rva, window }
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Context of a match.
+///
+/// Created with the method [`context`](struct.Scanner.html#method.context), its display implementation logs the context followed by a hexdump of the window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Context<'a> {
+	/// The rva of the match.
+	pub rva: Rva,
+	/// Index of the section containing the match.
+	pub section: Option<usize>,
+	/// Begin and end of the function containing the match from the exception directory.
+	pub function: Option<Range<Rva>>,
+	/// The closest code or data export at or before the match in the same section.
+	pub export: Option<RvaExport<'a>>,
+	/// The rva of the first byte in the window.
+	pub window_rva: Rva,
+	/// The bytes surrounding the match.
+	pub window: &'a [u8],
+}
+impl<'a> fmt::Display for Context<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:#x}", self.rva)?;
+		if let Some(section) = self.section {
+			write!(f, " in section #{}", section)?;
+		}
+		if let Some(function) = &self.function {
+			write!(f, " in function {:#x}+{:#x}", function.start, self.rva - function.start)?;
+		}
+		if let Some(export) = &self.export {
+			match export.name {
+				Some(name) => write!(f, " near {}", name)?,
+				None => write!(f, " near #{}", export.ordinal)?,
+			}
+			write!(f, "+{:#x}", self.rva - export.rva)?;
+		}
+		writeln!(f)?;
+		for (i, line) in self.window.chunks(16).enumerate() {
+			write!(f, "{:08x}:", self.window_rva as usize + i * 16)?;
+			for byte in line {
+				write!(f, " {:02x}", byte)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
 }
 
 //----------------------------------------------------------------
@@ -547,6 +624,20 @@ impl<'a, 'pat, P: Pe<'a>> Matches<'pat, P> {
 		self.range.start += slice.len() as u32;
 		return false;
 	}
+	/// Finds the next match with the given save array and gathers its context.
+	///
+	/// The match is expected in `save[0]`, as patterns parsed from strings save their cursor there.
+	/// See [`Scanner::context`](struct.Scanner.html#method.context) for more information.
+	///
+	/// # Panics
+	///
+	/// Panics if the save array is empty.
+	pub fn next_context(&mut self, save: &mut [Rva], radius: u32) -> Option<Context<'a>> {
+		if !self.next(save) {
+			return None;
+		}
+		Some(self.scanner.context(save[0], radius))
+	}
 	/// Finds the next match with the given save array.
 	pub fn next(&mut self, save: &mut [Rva]) -> bool {
 		// Build the quicksearch buffer
@@ -557,6 +648,7 @@ impl<'a, 'pat, P: Pe<'a>> Matches<'pat, P> {
 		match self.scanner.pe.align() {
 			Align::File => {
 				for section in self.scanner.pe.section_headers() {
+					let section = section.le();
 					// If section overlaps with the scanning range
 					if section.VirtualAddress < self.range.end && u32::wrapping_add(section.VirtualAddress, section.VirtualSize) > self.range.start {
 						// Get the image slice for this section for further processing, skipping corrupt section headers
@@ -584,10 +676,14 @@ impl<'a, 'pat, P: Pe<'a>> Matches<'pat, P> {
 
 		// Clamp the slice to the expected input scan range
 		self.range.start = cmp::max(base, self.range.start);
-		let start = self.range.start - base;
-		let end = cmp::min(base + slice.len() as u32, self.range.end) - base;
+		let start = (self.range.start - base) as usize;
+		let end = cmp::min(self.range.end.saturating_sub(base) as usize, slice.len());
 
-		self.strategy(qsbuf, &slice[start as usize..end as usize], save)
+		// Corrupt section headers may place the range outside the slice
+		match slice.get(start..end) {
+			Some(slice) => self.strategy(qsbuf, slice, save),
+			None => false,
+		}
 	}
 }
 
@@ -1,5 +1,7 @@
+use std::cmp;
+
 use crate::security::Security;
-use crate::util::AlignTo;
+use crate::util::{AlignTo, Le};
 use crate::{Error, Result};
 
 use super::image::*;
@@ -11,7 +13,8 @@ pub(crate) fn try_from<'a, P: Pe<'a>>(pe: P) -> Result<Security<'a>> {
 		return Err(Error::Unmapped);
 	}
 	// Manual alignment and size check
-	let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY).ok_or(Error::Bounds)?;
+	let entry = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_SECURITY).ok_or(Error::Bounds)?;
+	let datadir = entry.le();
 	if datadir.VirtualAddress == 0 {
 		return Err(Error::Null);
 	}
@@ -23,9 +26,17 @@ pub(crate) fn try_from<'a, P: Pe<'a>>(pe: P) -> Result<Security<'a>> {
 	}
 	// Interpret the bytes
 	let start = datadir.VirtualAddress as usize;
-	let end = (datadir.VirtualAddress + datadir.Size) as usize;
+	let end = datadir.VirtualAddress.checked_add(datadir.Size).ok_or(Error::Overflow)? as usize;
 	let image = pe.image().get(start..end).ok_or(Error::Bounds)?;
-	Ok(unsafe { Security::new(image) })
+	// CheckSum is at the same offset in the 32 and 64 bit optional headers
+	let base = pe.image().as_ptr() as usize;
+	let check_sum = pe.optional_header() as *const _ as usize - base + 64;
+	let entry = entry as *const _ as usize - base;
+	// The Authenticode digest skips the certificate table after the headers it covers
+	if start < cmp::max(entry + 8, pe.optional_header().SizeOfHeaders.le() as usize) {
+		return Err(Error::Bounds);
+	}
+	Ok(unsafe { Security::new(image, pe.image(), check_sum, entry) })
 }
 
 #[cfg(test)]
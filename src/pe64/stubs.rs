@@ -0,0 +1,234 @@
+/*!
+FFI stub generation from the export directory.
+
+Scaffolds bindings for undocumented DLLs: every export is declared as an `extern "system"` Rust function or a C function prototype.
+The export directory does not describe signatures, the generated declarations take no arguments and return nothing.
+
+Exported names which are not valid identifiers, such as C++ decorated names, are sanitized and linked by their original name.
+Exports without a name are declared as `Ordinal{N}` and linked by ordinal.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::stubs::Stubs;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) -> pelite::Result<()> {
+	let stubs = Stubs::new(file)?;
+	for stub in stubs.stubs() {
+		println!("{} => {}", stub.ordinal, stub.ident);
+	}
+	// Rust declarations linking with raw-dylib
+	println!("{}", stubs.to_rust());
+	// C header declaring the functions
+	println!("{}", stubs.to_c_header());
+	Ok(())
+}
+```
+*/
+
+use std::fmt::Write;
+use std::prelude::v1::*;
+use std::str;
+
+use crate::util::{CStr, Le};
+use crate::Result;
+
+use super::exports::Export;
+use super::image::{Ordinal, VirtualAddress};
+use super::{Pe, Rva, Va};
+
+//----------------------------------------------------------------
+
+/// Declaration of an exported function.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Stub<'a> {
+	/// Name of the export, `None` if exported by ordinal only.
+	pub name: Option<&'a str>,
+	/// Ordinal of the export.
+	pub ordinal: Ordinal,
+	/// Identifier used in the declaration, unique within the module.
+	pub ident: String,
+	/// Rva of the export, zero if forwarded.
+	pub rva: Rva,
+	/// Forwarded name as `DllName.ExportName`.
+	pub forward: Option<&'a CStr>,
+}
+
+/// Declarations of the exported functions.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct Stubs<'a> {
+	dll_name: &'a str,
+	stubs: Vec<Stub<'a>>,
+}
+
+impl<'a> Stubs<'a> {
+	/// Collects the exported functions in ordinal order.
+	///
+	/// Exports whose name is not valid UTF-8 are skipped.
+	pub fn new<P: Pe<'a>>(pe: P) -> Result<Stubs<'a>> {
+		let exports = pe.exports()?;
+		let dll_name = exports.dll_name()?.to_str().map_err(|_| crate::Error::Encoding)?;
+		let by = exports.by()?;
+		let mut names = vec![None; by.functions().len()];
+		for (name, index) in by.iter_name_indices() {
+			if let (Ok(name), Some(slot)) = (name, names.get_mut(index)) {
+				// Multiple names may refer to the same function, declare the first
+				if slot.is_none() {
+					*slot = Some(name);
+				}
+			}
+		}
+		let mut stubs: Vec<Stub<'a>> = Vec::new();
+		for (index, export) in by.iter().enumerate() {
+			let (rva, forward) = match export {
+				Ok(Export::Symbol(&rva)) => (rva.le(), None),
+				Ok(Export::Forward(forward)) => (0, Some(forward)),
+				// Skip unused slots in the functions table
+				Err(_) => continue,
+			};
+			let ordinal = by.ordinal_base().wrapping_add(index as Ordinal);
+			let name = match names[index] {
+				Some(name) => match name.to_str() {
+					Ok(name) => Some(name),
+					Err(_) => continue,
+				},
+				None => None,
+			};
+			let mut ident = match name {
+				Some(name) => sanitize(name),
+				None => format!("Ordinal{}", ordinal),
+			};
+			if stubs.iter().any(|stub| stub.ident == ident) {
+				let _ = write!(ident, "_{}", ordinal);
+			}
+			stubs.push(Stub { name, ordinal, ident, rva, forward });
+		}
+		Ok(Stubs { dll_name, stubs })
+	}
+	/// Gets the name of the dll.
+	pub fn dll_name(&self) -> &'a str {
+		self.dll_name
+	}
+	/// Gets the library name without the file extension, as passed to the linker.
+	pub fn lib_name(&self) -> &'a str {
+		match self.dll_name.rfind('.') {
+			Some(i) => &self.dll_name[..i],
+			None => self.dll_name,
+		}
+	}
+	/// Gets the declarations.
+	pub fn stubs(&self) -> &[Stub<'a>] {
+		&self.stubs
+	}
+	/// Generates Rust declarations.
+	///
+	/// Links with `raw-dylib` which does not require an import library and supports linking by ordinal.
+	pub fn to_rust(&self) -> String {
+		let mut s = String::new();
+		let _ = writeln!(s, "// Generated from {} by pelite.", self.dll_name);
+		let _ = writeln!(s, "// The signatures are unknown, fill in the parameter and return types.");
+		let _ = writeln!(s);
+		// 32-bit stdcall names are decorated by default but DLLs usually export them undecorated
+		if Va::BITNESS == 32 {
+			let _ = writeln!(s, "#[link(name = {:?}, kind = \"raw-dylib\", import_name_type = \"undecorated\")]", self.lib_name());
+		}
+		else {
+			let _ = writeln!(s, "#[link(name = {:?}, kind = \"raw-dylib\")]", self.lib_name());
+		}
+		let _ = writeln!(s, "extern \"system\" {{");
+		for stub in &self.stubs {
+			match stub.forward {
+				Some(forward) => {
+					let _ = writeln!(s, "\t/// Ordinal {}, forwarded to `{}`.", stub.ordinal, forward);
+				},
+				None => {
+					let _ = writeln!(s, "\t/// Ordinal {}, rva {:#x}.", stub.ordinal, stub.rva);
+				},
+			}
+			match stub.name {
+				Some(name) if name != stub.ident => {
+					let _ = writeln!(s, "\t#[link_name = {:?}]", name);
+				},
+				Some(_) => (),
+				None => {
+					let _ = writeln!(s, "\t#[link_ordinal({})]", stub.ordinal);
+				},
+			}
+			let _ = writeln!(s, "\tpub fn {}();", stub.ident);
+		}
+		let _ = writeln!(s, "}}");
+		s
+	}
+	/// Generates a C header.
+	///
+	/// Exports without a name or whose name is not a valid identifier cannot be declared in C and are listed in comments.
+	pub fn to_c_header(&self) -> String {
+		let mut s = String::new();
+		let _ = writeln!(s, "/* Generated from {} by pelite. */", comment(self.dll_name));
+		let _ = writeln!(s, "/* The signatures are unknown, fill in the parameter and return types. */");
+		let _ = writeln!(s, "#pragma once\n");
+		let _ = writeln!(s, "#ifdef __cplusplus\nextern \"C\" {{\n#endif\n");
+		for stub in &self.stubs {
+			match stub.name {
+				Some(name) if name == stub.ident => {
+					let _ = writeln!(s, "__declspec(dllimport) void __stdcall {}(void);", name);
+				},
+				Some(name) => {
+					let _ = writeln!(s, "/* ordinal {}: {} */", stub.ordinal, comment(name));
+				},
+				None => {
+					let _ = writeln!(s, "/* ordinal {}: exported by ordinal only */", stub.ordinal);
+				},
+			}
+		}
+		let _ = writeln!(s, "\n#ifdef __cplusplus\n}}\n#endif");
+		s
+	}
+}
+
+// Rust keywords which cannot be used as identifiers
+const KEYWORDS: [&str; 51] = [
+	"Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn",
+	"else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+	"match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super",
+	"trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while",
+];
+
+/// Converts the name into a valid Rust and C identifier.
+fn sanitize(name: &str) -> String {
+	let mut ident: String = name.chars().map(|chr| if chr.is_ascii_alphanumeric() || chr == '_' { chr } else { '_' }).collect();
+	if ident.is_empty() || ident.as_bytes()[0].is_ascii_digit() {
+		ident.insert(0, '_');
+	}
+	if KEYWORDS.contains(&&*ident) || ident == "_" {
+		ident.push('_');
+	}
+	ident
+}
+
+/// Escapes the end of comment sequence.
+fn comment(text: &str) -> String {
+	text.replace("*/", "*\\/")
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sanitize_names() {
+		assert_eq!(sanitize("CreateFileW"), "CreateFileW");
+		assert_eq!(sanitize("??0Passwds@@QEAA@PEBD@Z"), "__0Passwds__QEAA_PEBD_Z");
+		assert_eq!(sanitize("3D"), "_3D");
+		assert_eq!(sanitize("type"), "type_");
+		assert_eq!(sanitize(""), "__");
+		assert_eq!(comment("a*/b"), "a*\\/b");
+	}
+}
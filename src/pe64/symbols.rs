@@ -0,0 +1,338 @@
+/*!
+Address symbolization.
+
+Resolves addresses to the nearest preceding symbol plus offset, eg. `kernel32!CreateFileW+0x2a`, and symbol names back to addresses.
+
+Symbols are provided by implementations of the [`SymbolProvider`](trait.SymbolProvider.html) trait:
+
+* [`ExportSymbols`](struct.ExportSymbols.html) from the export directory.
+* [`CoffSymbols`](struct.CoffSymbols.html) from the COFF symbol table, present in eg. MinGW builds.
+* [`MapSymbols`](struct.MapSymbols.html) from externally sourced debug information such as linker map files.
+
+Combine them with [`Providers`](struct.Providers.html) which queries its providers in priority order.
+Other sources such as PDB files can be plugged in by implementing the trait.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile, Rva};
+use pelite::pe64::symbols::{CoffSymbols, ExportSymbols, Providers, SymbolProvider};
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, rva: Rva) -> pelite::Result<()> {
+	let mut providers = Providers::new();
+	if let Ok(coff) = CoffSymbols::new(file) {
+		providers.push(coff);
+	}
+	providers.push(ExportSymbols::new(file)?);
+
+	if let Some(symbol) = providers.symbolize(rva) {
+		println!("{:#x} is {}", rva, symbol);
+	}
+	if let Some(rva) = providers.resolve("DllMain") {
+		println!("DllMain is at {:#x}", rva);
+	}
+
+	Ok(())
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{fmt, str};
+
+use crate::util::Le;
+use crate::{Error, Result};
+
+use super::image::*;
+use super::{Align, Pe, Rva};
+
+//----------------------------------------------------------------
+
+/// Symbol and offset resolved from an address.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Symbol<'s> {
+	/// Name of the module without the file extension.
+	pub module: &'s str,
+	/// Name of the symbol.
+	pub name: &'s str,
+	/// Address of the symbol.
+	pub rva: Rva,
+	/// Offset of the symbolized address from the symbol.
+	pub offset: u32,
+}
+impl<'s> fmt::Display for Symbol<'s> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}!{}", self.module, self.name)?;
+		if self.offset != 0 {
+			write!(f, "+{:#x}", self.offset)?;
+		}
+		Ok(())
+	}
+}
+
+/// Source of symbols.
+pub trait SymbolProvider {
+	/// Resolves the address to the nearest symbol at or before it.
+	///
+	/// Returns `None` if the address is not covered by the provider's symbols.
+	fn symbolize(&self, rva: Rva) -> Option<Symbol<'_>>;
+	/// Resolves the symbol name to its address.
+	fn resolve(&self, name: &str) -> Option<Rva>;
+}
+
+impl<T: SymbolProvider + ?Sized> SymbolProvider for &T {
+	fn symbolize(&self, rva: Rva) -> Option<Symbol<'_>> {
+		T::symbolize(*self, rva)
+	}
+	fn resolve(&self, name: &str) -> Option<Rva> {
+		T::resolve(*self, name)
+	}
+}
+
+//----------------------------------------------------------------
+
+// Symbols sorted by address with an index sorted by name
+#[derive(Clone, Debug)]
+struct Table<'a> {
+	module: &'a str,
+	size_of_image: u32,
+	symbols: Vec<(Rva, &'a str)>,
+	names: Vec<u32>,
+}
+impl<'a> Table<'a> {
+	fn new(module: &'a str, size_of_image: u32, mut symbols: Vec<(Rva, &'a str)>) -> Table<'a> {
+		symbols.sort();
+		let mut names: Vec<u32> = (0..symbols.len() as u32).collect();
+		names.sort_by_key(|&index| symbols[index as usize].1);
+		Table { module, size_of_image, symbols, names }
+	}
+	fn symbolize(&self, rva: Rva) -> Option<Symbol<'_>> {
+		if rva >= self.size_of_image {
+			return None;
+		}
+		// Find the last symbol at or before the address, aliases resolve to the first name in sort order
+		let end = self.symbols.partition_point(|&(symbol_rva, _)| symbol_rva <= rva);
+		let symbol_rva = self.symbols[..end].last()?.0;
+		let index = self.symbols[..end].partition_point(|&(other_rva, _)| other_rva < symbol_rva);
+		let (symbol_rva, name) = self.symbols[index];
+		Some(Symbol { module: self.module, name, rva: symbol_rva, offset: rva - symbol_rva })
+	}
+	fn resolve(&self, name: &str) -> Option<Rva> {
+		let index = self.names.binary_search_by(|&index| self.symbols[index as usize].1.cmp(name)).ok()?;
+		Some(self.symbols[self.names[index] as usize].0)
+	}
+}
+
+// Trims the file extension from a dll name
+fn module_name(dll_name: &str) -> &str {
+	match dll_name.rfind('.') {
+		Some(i) => &dll_name[..i],
+		None => dll_name,
+	}
+}
+
+macro_rules! impl_table {
+	($ty:ident) => {
+		impl<'a> $ty<'a> {
+			/// Overrides the module name.
+			pub fn with_module(mut self, module: &'a str) -> $ty<'a> {
+				self.0.module = module;
+				self
+			}
+			/// Returns the module name.
+			pub fn module(&self) -> &'a str {
+				self.0.module
+			}
+			/// Returns the symbols sorted by address.
+			pub fn symbols(&self) -> &[(Rva, &'a str)] {
+				&self.0.symbols
+			}
+		}
+		impl<'a> SymbolProvider for $ty<'a> {
+			fn symbolize(&self, rva: Rva) -> Option<Symbol<'_>> {
+				self.0.symbolize(rva)
+			}
+			fn resolve(&self, name: &str) -> Option<Rva> {
+				self.0.resolve(name)
+			}
+		}
+	};
+}
+
+//----------------------------------------------------------------
+
+/// Symbols from the export directory.
+///
+/// Forwarded exports and exports without a name are not symbols.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct ExportSymbols<'a>(Table<'a>);
+impl<'a> ExportSymbols<'a> {
+	/// Collects the exported symbols.
+	///
+	/// The module name is taken from the export directory's dll name.
+	pub fn new<P: Pe<'a>>(pe: P) -> Result<ExportSymbols<'a>> {
+		let exports = pe.exports()?;
+		let module = module_name(exports.dll_name()?.to_str().unwrap_or(""));
+		let by = exports.by()?;
+		let mut symbols = Vec::with_capacity(by.names().len());
+		for (name, export) in by.iter_names() {
+			if let (Ok(name), Ok(export)) = (name, export) {
+				if let (Ok(name), Some(rva)) = (name.to_str(), export.symbol()) {
+					symbols.push((rva, name));
+				}
+			}
+		}
+		Ok(ExportSymbols(Table::new(module, pe.optional_header().SizeOfImage.le(), symbols)))
+	}
+}
+impl_table!(ExportSymbols);
+
+//----------------------------------------------------------------
+
+/// Symbols from the COFF symbol table.
+///
+/// The symbol table is referenced by the file header's `PointerToSymbolTable` and is not mapped in memory, it is only available for file aligned images.
+/// Only external symbols and static functions defined in a section are symbols, the module name defaults to the export directory's dll name if any.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct CoffSymbols<'a>(Table<'a>);
+impl<'a> CoffSymbols<'a> {
+	/// Collects the COFF symbols.
+	///
+	/// # Errors
+	///
+	/// * [`Unmapped`](../../enum.Error.html#variant.Unmapped): The image is not file aligned.
+	///
+	/// * [`Null`](../../enum.Error.html#variant.Null): There is no symbol table.
+	///
+	/// * [`Bounds`](../../enum.Error.html#variant.Bounds): The symbol table is out of bounds of the image.
+	pub fn new<P: Pe<'a>>(pe: P) -> Result<CoffSymbols<'a>> {
+		if pe.align() != Align::File {
+			return Err(Error::Unmapped);
+		}
+		let file_header = pe.file_header();
+		let offset = file_header.PointerToSymbolTable.le() as usize;
+		if offset == 0 {
+			return Err(Error::Null);
+		}
+		let image = pe.image();
+		let table = (file_header.NumberOfSymbols.le() as usize).checked_mul(IMAGE_SIZEOF_SYMBOL)
+			.and_then(|len| image.get(offset..offset.checked_add(len)?))
+			.ok_or(Error::Bounds)?;
+		// The string table follows the symbols and starts with its size in bytes, a missing string table is tolerated
+		let strings = &image[offset + table.len()..];
+		let strings = match strings.get(..4) {
+			Some(size) => &strings[..std::cmp::min(u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize, strings.len())],
+			None => &[][..],
+		};
+		let section_headers = pe.section_headers();
+		let mut symbols = Vec::new();
+		let mut records = table.chunks_exact(IMAGE_SIZEOF_SYMBOL);
+		while let Some(record) = records.next() {
+			let value = u32::from_le_bytes([record[8], record[9], record[10], record[11]]);
+			let section_number = i16::from_le_bytes([record[12], record[13]]);
+			let ty = u16::from_le_bytes([record[14], record[15]]);
+			let storage_class = record[16];
+			// Skip the auxiliary records
+			for _ in 0..record[17] {
+				records.next();
+			}
+			let is_function = (ty >> 4) & 3 == IMAGE_SYM_DTYPE_FUNCTION;
+			if !(storage_class == IMAGE_SYM_CLASS_EXTERNAL || storage_class == IMAGE_SYM_CLASS_STATIC && is_function) {
+				continue;
+			}
+			let section = match section_number.checked_sub(1).and_then(|index| section_headers.image().get(index as usize)) {
+				Some(section) => section,
+				None => continue,
+			};
+			let name = if record[..4] == [0; 4] {
+				let offset = u32::from_le_bytes([record[4], record[5], record[6], record[7]]) as usize;
+				let name = strings.get(offset..).unwrap_or(&[]);
+				&name[..name.iter().position(|&byte| byte == 0).unwrap_or(name.len())]
+			}
+			else {
+				&record[..record[..8].iter().position(|&byte| byte == 0).unwrap_or(8)]
+			};
+			if let (Ok(name), Some(rva)) = (str::from_utf8(name), section.VirtualAddress.le().checked_add(value)) {
+				if !name.is_empty() {
+					symbols.push((rva, name));
+				}
+			}
+		}
+		let module = pe.exports().and_then(|exports| exports.dll_name()).ok().and_then(|dll_name| dll_name.to_str().ok()).map_or("", module_name);
+		Ok(CoffSymbols(Table::new(module, pe.optional_header().SizeOfImage.le(), symbols)))
+	}
+}
+impl_table!(CoffSymbols);
+
+//----------------------------------------------------------------
+
+/// Symbols from external debug information.
+///
+/// Provides symbols sourced from outside the image, eg. linker map files or symbol dumps.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct MapSymbols<'a>(Table<'a>);
+impl<'a> MapSymbols<'a> {
+	/// Creates symbols for the image from a list of addresses and names.
+	///
+	/// Symbols outside the image are discarded.
+	pub fn new<P: Pe<'a>>(pe: P, module: &'a str, mut symbols: Vec<(Rva, &'a str)>) -> MapSymbols<'a> {
+		let size_of_image = pe.optional_header().SizeOfImage.le();
+		symbols.retain(|&(rva, _)| rva < size_of_image);
+		MapSymbols(Table::new(module, size_of_image, symbols))
+	}
+}
+impl_table!(MapSymbols);
+
+//----------------------------------------------------------------
+
+/// Queries symbol providers in priority order.
+///
+/// The first provider which resolves the address or name wins, push the most precise providers first.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Default)]
+pub struct Providers<'p> {
+	providers: Vec<Box<dyn SymbolProvider + 'p>>,
+}
+impl<'p> Providers<'p> {
+	/// Creates an empty set of providers.
+	pub fn new() -> Providers<'p> {
+		Providers { providers: Vec::new() }
+	}
+	/// Adds a provider with a lower priority than the providers added before it.
+	///
+	/// Providers are also implemented for references, allowing providers to be shared.
+	pub fn push<S: SymbolProvider + 'p>(&mut self, provider: S) {
+		self.providers.push(Box::new(provider));
+	}
+	/// Returns the number of providers.
+	pub fn len(&self) -> usize {
+		self.providers.len()
+	}
+	/// Returns true if there are no providers.
+	pub fn is_empty(&self) -> bool {
+		self.providers.is_empty()
+	}
+}
+impl<'p> SymbolProvider for Providers<'p> {
+	fn symbolize(&self, rva: Rva) -> Option<Symbol<'_>> {
+		self.providers.iter().find_map(|provider| provider.symbolize(rva))
+	}
+	fn resolve(&self, name: &str) -> Option<Rva> {
+		self.providers.iter().find_map(|provider| provider.resolve(name))
+	}
+}
+impl<'p> fmt::Debug for Providers<'p> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Providers").field("len", &self.providers.len()).finish()
+	}
+}
@@ -15,19 +15,27 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 	// Access the initialized thread local data
 	let raw_data = tls.raw_data()?;
 
-	// Access the TLS slot
+	// Size of the zero initialized data following the raw data
+	let zero_fill_size = tls.zero_fill_size();
+
+	// Access the TLS slot and read the TLS index assigned by the loader
 	let slot = tls.slot()?;
+	let index = tls.index()?;
 
-	// Access the TLS callbacks
-	let callbacks = tls.callbacks()?;
+	// Iterate over the TLS callbacks
+	for rva in tls.callbacks()? {
+		println!("TLS callback at {:#x}", rva?);
+	}
 
 	Ok(())
 }
 ```
 */
 
-use std::fmt;
+use std::convert::TryFrom;
+use std::{fmt, slice};
 
+use crate::util::Le;
 use crate::{Error, Result};
 
 use super::image::*;
@@ -46,7 +54,7 @@ pub struct Tls<'a, P> {
 impl<'a, P: Pe<'a>> Tls<'a, P> {
 	pub(crate) fn try_from(pe: P) -> Result<Tls<'a, P>> {
 		let datadir = pe.data_directory().get(IMAGE_DIRECTORY_ENTRY_TLS).ok_or(Error::Bounds)?;
-		let image = pe.derva(datadir.VirtualAddress)?;
+		let image = pe.derva(datadir.VirtualAddress.le())?;
 		Ok(Tls { pe, image })
 	}
 	/// Gets the PE instance.
@@ -59,26 +67,49 @@ impl<'a, P: Pe<'a>> Tls<'a, P> {
 	}
 	/// Gets the raw TLS initialization data.
 	pub fn raw_data(&self) -> Result<&'a [u8]> {
-		if self.image.StartAddressOfRawData > self.image.EndAddressOfRawData {
+		let image = self.image.le();
+		if image.StartAddressOfRawData > image.EndAddressOfRawData {
 			return Err(Error::Invalid);
 		}
-		// FIXME! truncation warning on 32bit...
-		let len = (self.image.EndAddressOfRawData - self.image.StartAddressOfRawData) as usize;
-		self.pe.deref_slice(self.image.StartAddressOfRawData.into(), len)
+		let len = image.EndAddressOfRawData - image.StartAddressOfRawData;
+		// The raw data of PE32+ images may not fit the address space of 32-bit hosts
+		let len = usize::try_from(len).map_err(|_| Error::Overflow)?;
+		self.pe.deref_slice(image.StartAddressOfRawData.into(), len)
+	}
+	/// Gets the size of the zero initialized data following the raw data.
+	pub fn zero_fill_size(&self) -> u32 {
+		self.image.SizeOfZeroFill.le()
+	}
+	/// Gets the size of the TLS template, the raw data followed by the zero fill.
+	///
+	/// The loader allocates this many bytes for every thread.
+	pub fn template_size(&self) -> Result<usize> {
+		let len = self.raw_data()?.len();
+		len.checked_add(self.zero_fill_size() as usize).ok_or(Error::Overflow)
 	}
 	/// Gets the TLS slot location.
 	pub fn slot(&self) -> Result<&'a u32> {
-		self.pe.deref(self.image.AddressOfIndex.into())
+		self.pe.deref(self.image.AddressOfIndex.le().into())
+	}
+	/// Reads the TLS index.
+	///
+	/// In an image mapped by the Windows loader this is the index assigned to the module's TLS data, in a file it is the initial value.
+	pub fn index(&self) -> Result<u32> {
+		self.slot().map(|&index| index.le())
 	}
-	/// Gets the TLS initialization callbacks.
-	pub fn callbacks(&self) -> Result<&'a [Va]> {
-		self.pe.deref_slice_s(self.image.AddressOfCallBacks.into(), 0)
+	/// Iterator over the TLS initialization callbacks.
+	///
+	/// The callbacks are stored as a null terminated array of virtual addresses, the iterator translates them to rvas.
+	pub fn callbacks(&self) -> Result<Callbacks<'a, P>> {
+		let vas = self.pe.deref_slice_s(self.image.AddressOfCallBacks.le().into(), 0)?;
+		Ok(Callbacks { pe: self.pe, iter: vas.iter() })
 	}
 }
 impl<'a, P: Pe<'a>> fmt::Debug for Tls<'a, P> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.debug_struct("Tls")
 			.field("raw_data.len", &format_args!("{:?}", self.raw_data().map(|raw_data| raw_data.len())))
+			.field("zero_fill_size", &self.zero_fill_size())
 			.field("callbacks.len", &format_args!("{:?}", &self.callbacks().map(|cbs| cbs.len())))
 			.finish()
 	}
@@ -86,6 +117,42 @@ impl<'a, P: Pe<'a>> fmt::Debug for Tls<'a, P> {
 
 //----------------------------------------------------------------
 
+/// Iterator over the TLS initialization callbacks.
+///
+/// Yields the rva of every callback, callbacks whose virtual address isn't part of the image yield an error.
+#[derive(Clone)]
+pub struct Callbacks<'a, P> {
+	pe: P,
+	iter: slice::Iter<'a, Va>,
+}
+impl<'a, P: Pe<'a>> Callbacks<'a, P> {
+	/// Gets the PE instance.
+	pub fn pe(&self) -> P {
+		self.pe
+	}
+	/// Returns the virtual addresses of the remaining callbacks as stored in the image.
+	pub fn as_slice(&self) -> &'a [Va] {
+		self.iter.as_slice()
+	}
+}
+impl<'a, P: Pe<'a>> Iterator for Callbacks<'a, P> {
+	type Item = Result<Rva>;
+	fn next(&mut self) -> Option<Result<Rva>> {
+		self.iter.next().map(|&va| self.pe.va_to_rva(va.le()))
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+impl<'a, P: Pe<'a>> ExactSizeIterator for Callbacks<'a, P> {}
+impl<'a, P: Pe<'a>> fmt::Debug for Callbacks<'a, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.clone()).finish()
+	}
+}
+
+//----------------------------------------------------------------
+
 #[cfg(feature = "serde")]
 mod serde {
 	use crate::util::serde_helper::*;
@@ -103,7 +170,7 @@ mod serde {
 			else {
 				state.serialize_field("raw_data", &self.raw_data().ok())?;
 			}
-			state.serialize_field("callbacks", &self.callbacks().ok())?;
+			state.serialize_field("callbacks", &self.callbacks().ok().map(|cbs| cbs.as_slice()))?;
 			state.end()
 		}
 	}
@@ -117,6 +184,11 @@ pub(crate) fn test<'a, P: Pe<'a>>(pe: P) -> Result<()> {
 	let _ = format!("{:?}", tls);
 	let _raw_data = tls.raw_data();
 	let _slot = tls.slot();
-	let _callbacks = tls.callbacks();
+	let _index = tls.index();
+	let _template_size = tls.template_size();
+	if let Ok(callbacks) = tls.callbacks() {
+		let _ = format!("{:?}", callbacks);
+		for _rva in callbacks {}
+	}
 	Ok(())
 }
@@ -0,0 +1,563 @@
+/*!
+Structural validation.
+
+Where the rest of the library fails on the first error it encounters, the validator runs an extensive set of structural checks and collects every problem it finds as a typed [`Finding`](enum.Finding.html) with a [`Severity`](enum.Severity.html).
+
+The checks cover header consistency, overlapping sections, dangerous section characteristics, data directory bounds, import and export string sanity, tampered import and export tables and base relocation targets.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::pe64::validate::Severity;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	let report = file.validate();
+
+	// Print all the findings
+	print!("{}", report);
+
+	// Filter the findings by severity
+	for finding in report.iter().filter(|finding| finding.severity() >= Severity::Warning) {
+		println!("{:?}", finding);
+	}
+}
+```
+*/
+
+use std::prelude::v1::*;
+use std::{cmp, fmt, slice};
+
+use crate::util::{AlignTo, Le};
+use crate::{Error, ParseOptions, Violation};
+
+use super::image::*;
+use super::analysis::entry_point::{entry_point, Location};
+use super::imports::{import_from_va, Import};
+use super::pe::validate_headers_lax;
+use super::{Align, Pe};
+
+//----------------------------------------------------------------
+
+/// Severity of a finding.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+	/// Unusual but harmless.
+	Info,
+	/// Violates the specification but the Windows loader accepts it.
+	Warning,
+	/// The image is corrupt.
+	Error,
+}
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Severity::Info => "info",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		})
+	}
+}
+
+/// Structural problem found by the validator.
+///
+/// Sections are identified by their index in the section headers, import descriptors by their index in the import directory.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Finding {
+	/// Header violation tolerated by the lenient constructors.
+	Header(Violation),
+	/// `SizeOfImage` does not match the end of the last section rounded up to `SectionAlignment`.
+	SizeOfImage { expected: u32 },
+	/// `SizeOfHeaders` is not a multiple of `FileAlignment`.
+	SizeOfHeaders,
+	/// Data directory entries extend past `SizeOfOptionalHeader` into the section headers.
+	DataDirectoryOverlap { count: usize },
+	/// The entry point is not contained in the headers or a section.
+	EntryPoint,
+	/// The entry point is in a section which isn't executable.
+	EntryPointNotExecutable { section: usize },
+	/// The entry point points into the import address table.
+	EntryPointInIat,
+	/// The section's virtual address is not a multiple of `SectionAlignment`.
+	SectionAlignment { section: usize },
+	/// The section is both writable and executable.
+	SectionWritableExecutable { section: usize },
+	/// The section is both shared between processes and writable.
+	SectionSharedWritable { section: usize },
+	/// The section is executable but named like a data section, eg. `.data` or `.rsrc`.
+	SectionExecutableData { section: usize },
+	/// The section has no raw data but covers at least half of the image, typical of packers unpacking into it.
+	SectionVirtualOnly { section: usize },
+	/// The section's virtual address is lower than the previous section's.
+	SectionOrder { section: usize },
+	/// The virtual address ranges of the sections overlap.
+	///
+	/// Each overlapping section is reported once, together with the overlapped section reaching the furthest.
+	SectionOverlap { first: usize, second: usize },
+	/// The raw data of the sections overlap.
+	///
+	/// Each overlapping section is reported once, together with the overlapped section reaching the furthest.
+	SectionRawOverlap { first: usize, second: usize },
+	/// The raw data of the section is not contained in the file.
+	SectionRawBounds { section: usize },
+	/// The data directory entry does not reference valid data.
+	DirectoryBounds { index: usize, error: Error },
+	/// The import descriptor's DLL name is invalid.
+	ImportDllName { desc: usize, error: Error },
+	/// The import name table entry is invalid.
+	ImportName { desc: usize, index: usize, error: Error },
+	/// The import descriptor has no import name table but its import address table is populated.
+	///
+	/// The names only survive in the import address table until it is bound or the image is loaded.
+	ImportNullInt { desc: usize },
+	/// The DLL name (without index) or the hint and name of the import is not contained in a section.
+	ImportNameOutsideSection { desc: usize, index: Option<usize> },
+	/// The hint of the import is out of order with the names imported before it.
+	///
+	/// The export name table is sorted, the hints of the imports sorted by name must ascend with it.
+	ImportHint { desc: usize, index: usize },
+	/// The import descriptor's DLL name is the same as the descriptor with a lower index, ignoring case.
+	ImportDllDuplicate { desc: usize, first: usize },
+	/// The export directory's DLL name is invalid.
+	ExportDllName(Error),
+	/// The export name is invalid.
+	ExportName { index: usize, error: Error },
+	/// The export name contains non-ASCII bytes.
+	ExportNameNonAscii { index: usize },
+	/// The export name is the same as the name with a lower index.
+	ExportNameDuplicate { index: usize, first: usize },
+	/// The export names are not sorted, lookup by name will fail.
+	ExportNamesUnsorted,
+	/// The export address table has a run of null entries starting at the ordinal.
+	ExportOrdinalGap { ordinal: Ordinal, count: usize },
+	/// The base relocation target is outside the image.
+	RelocTarget { rva: Rva },
+}
+impl Finding {
+	/// Gets the severity of the finding.
+	pub fn severity(&self) -> Severity {
+		match self {
+			Finding::SizeOfHeaders => Severity::Info,
+			Finding::DataDirectoryOverlap { .. } => Severity::Info,
+			Finding::SectionRawOverlap { .. } => Severity::Info,
+			Finding::ExportOrdinalGap { .. } => Severity::Info,
+			Finding::Header(_) => Severity::Warning,
+			Finding::SizeOfImage { .. } => Severity::Warning,
+			Finding::EntryPoint => Severity::Warning,
+			Finding::EntryPointNotExecutable { .. } => Severity::Warning,
+			Finding::EntryPointInIat => Severity::Error,
+			Finding::SectionAlignment { .. } => Severity::Warning,
+			Finding::SectionWritableExecutable { .. } => Severity::Warning,
+			Finding::SectionSharedWritable { .. } => Severity::Warning,
+			Finding::SectionExecutableData { .. } => Severity::Warning,
+			Finding::SectionVirtualOnly { .. } => Severity::Info,
+			Finding::ExportNameNonAscii { .. } => Severity::Warning,
+			Finding::ExportNameDuplicate { .. } => Severity::Warning,
+			Finding::ExportNamesUnsorted => Severity::Warning,
+			Finding::ImportNullInt { .. } => Severity::Warning,
+			Finding::ImportNameOutsideSection { .. } => Severity::Warning,
+			Finding::ImportHint { .. } => Severity::Warning,
+			Finding::ImportDllDuplicate { .. } => Severity::Warning,
+			Finding::SectionOrder { .. } => Severity::Error,
+			Finding::SectionOverlap { .. } => Severity::Error,
+			Finding::SectionRawBounds { .. } => Severity::Error,
+			Finding::DirectoryBounds { .. } => Severity::Error,
+			Finding::ImportDllName { .. } => Severity::Error,
+			Finding::ImportName { .. } => Severity::Error,
+			Finding::ExportDllName(_) => Severity::Error,
+			Finding::ExportName { .. } => Severity::Error,
+			Finding::RelocTarget { .. } => Severity::Error,
+		}
+	}
+}
+impl fmt::Display for Finding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Finding::Header(violation) => write!(f, "header violation: {:?}", violation),
+			Finding::SizeOfImage { expected } => write!(f, "SizeOfImage does not match the sections, expected {:#x}", expected),
+			Finding::SizeOfHeaders => f.write_str("SizeOfHeaders is not a multiple of FileAlignment"),
+			Finding::DataDirectoryOverlap { count } => write!(f, "{} data directory entries past the declared optional header", count),
+			Finding::EntryPoint => f.write_str("entry point outside of the headers and sections"),
+			Finding::EntryPointNotExecutable { section } => write!(f, "entry point in section #{} which is not executable", section),
+			Finding::EntryPointInIat => f.write_str("entry point in the import address table"),
+			Finding::SectionAlignment { section } => write!(f, "section #{} is not aligned to SectionAlignment", section),
+			Finding::SectionWritableExecutable { section } => write!(f, "section #{} is writable and executable", section),
+			Finding::SectionSharedWritable { section } => write!(f, "section #{} is shared and writable", section),
+			Finding::SectionExecutableData { section } => write!(f, "section #{} is executable but named like a data section", section),
+			Finding::SectionVirtualOnly { section } => write!(f, "section #{} has no raw data but covers most of the image", section),
+			Finding::SectionOrder { section } => write!(f, "section #{} is not in ascending virtual address order", section),
+			Finding::SectionOverlap { first, second } => write!(f, "sections #{} and #{} overlap", first, second),
+			Finding::SectionRawOverlap { first, second } => write!(f, "raw data of sections #{} and #{} overlap", first, second),
+			Finding::SectionRawBounds { section } => write!(f, "raw data of section #{} is outside the file", section),
+			Finding::DirectoryBounds { index, error } => write!(f, "data directory #{}: {}", index, error),
+			Finding::ImportDllName { desc, error } => write!(f, "import descriptor #{} dll name: {}", desc, error),
+			Finding::ImportName { desc, index, error } => write!(f, "import descriptor #{} name #{}: {}", desc, index, error),
+			Finding::ImportNullInt { desc } => write!(f, "import descriptor #{} has no import name table", desc),
+			Finding::ImportNameOutsideSection { desc, index: None } => write!(f, "import descriptor #{} dll name outside of the sections", desc),
+			Finding::ImportNameOutsideSection { desc, index: Some(index) } => write!(f, "import descriptor #{} name #{} outside of the sections", desc, index),
+			Finding::ImportHint { desc, index } => write!(f, "import descriptor #{} name #{} has an abnormal hint", desc, index),
+			Finding::ImportDllDuplicate { desc, first } => write!(f, "import descriptor #{} duplicates the dll name of import descriptor #{}", desc, first),
+			Finding::ExportDllName(error) => write!(f, "export dll name: {}", error),
+			Finding::ExportName { index, error } => write!(f, "export name #{}: {}", index, error),
+			Finding::ExportNameNonAscii { index } => write!(f, "export name #{} is not ASCII", index),
+			Finding::ExportNameDuplicate { index, first } => write!(f, "export name #{} duplicates export name #{}", index, first),
+			Finding::ExportNamesUnsorted => f.write_str("export names are not sorted"),
+			Finding::ExportOrdinalGap { ordinal, count } => write!(f, "{} exports missing starting at ordinal {}", count, ordinal),
+			Finding::RelocTarget { rva } => write!(f, "base relocation target {:#x} outside the image", rva),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Validation report.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+	findings: Vec<Finding>,
+}
+impl Report {
+	/// Gets the findings in the order they were found.
+	pub fn findings(&self) -> &[Finding] {
+		&self.findings
+	}
+	/// Iterates over the findings.
+	pub fn iter(&self) -> slice::Iter<'_, Finding> {
+		self.findings.iter()
+	}
+	/// Returns true if nothing was found.
+	pub fn is_empty(&self) -> bool {
+		self.findings.is_empty()
+	}
+	/// Gets the highest severity of the findings.
+	pub fn max_severity(&self) -> Option<Severity> {
+		self.findings.iter().map(Finding::severity).max()
+	}
+	/// Returns true if any of the findings indicate corruption.
+	pub fn has_errors(&self) -> bool {
+		self.max_severity() == Some(Severity::Error)
+	}
+	/// Returns the findings.
+	pub fn into_vec(self) -> Vec<Finding> {
+		self.findings
+	}
+}
+impl<'r> IntoIterator for &'r Report {
+	type Item = &'r Finding;
+	type IntoIter = slice::Iter<'r, Finding>;
+	fn into_iter(self) -> slice::Iter<'r, Finding> {
+		self.iter()
+	}
+}
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for finding in &self.findings {
+			writeln!(f, "{}: {}", finding.severity(), finding)?;
+		}
+		Ok(())
+	}
+}
+
+//----------------------------------------------------------------
+
+pub(crate) fn validate<'a, P: Pe<'a>>(pe: P) -> Report {
+	let mut findings = Vec::new();
+	headers(pe, &mut findings);
+	sections(pe, &mut findings);
+	directories(pe, &mut findings);
+	imports(pe, &mut findings);
+	exports(pe, &mut findings);
+	base_relocs(pe, &mut findings);
+	Report { findings }
+}
+
+// The virtual size as used by the loader
+fn virtual_size(it: &IMAGE_SECTION_HEADER) -> u32 {
+	if it.VirtualSize != 0 { it.VirtualSize } else { it.SizeOfRawData }
+}
+
+// Strings referenced by the import and export directories should be printable and not empty
+fn check_name(name: &[u8]) -> Result<(), Error> {
+	if !name.is_empty() && name.iter().all(|byte| (0x20..0x7f).contains(byte)) {
+		Ok(())
+	}
+	else {
+		Err(Error::Encoding)
+	}
+}
+
+fn headers<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let options = ParseOptions { lenient: true, ..pe.options() };
+	if let Ok((_, violations)) = validate_headers_lax(pe.image(), &options) {
+		findings.extend(violations.iter().map(Finding::Header));
+	}
+
+	let optional_header = pe.optional_header().le();
+	let section_alignment = optional_header.SectionAlignment;
+	let file_alignment = optional_header.FileAlignment;
+
+	if section_alignment.is_power_of_two() {
+		let end = pe.section_headers().iter().map(|it| it.le()).map(|it| it.VirtualAddress as u64 + virtual_size(&it) as u64).max();
+		let end = end.unwrap_or(optional_header.SizeOfHeaders as u64).align_to(section_alignment as u64);
+		if end != optional_header.SizeOfImage as u64 {
+			findings.push(Finding::SizeOfImage { expected: end as u32 });
+		}
+	}
+	if file_alignment.is_power_of_two() && !optional_header.SizeOfHeaders.aligned_to(file_alignment) {
+		findings.push(Finding::SizeOfHeaders);
+	}
+	let count = pe.data_directory().len() - pe.declared_data_directory().len();
+	if count != 0 {
+		findings.push(Finding::DataDirectoryOverlap { count });
+	}
+
+	// DLLs without DllMain have no entry point, tiny images have their code in the headers
+	let entry_point = entry_point(pe);
+	match entry_point.location {
+		Location::Section(section) if !entry_point.executable => findings.push(Finding::EntryPointNotExecutable { section }),
+		Location::OutOfBounds => findings.push(Finding::EntryPoint),
+		_ => (),
+	}
+	if entry_point.in_iat {
+		findings.push(Finding::EntryPointInIat);
+	}
+}
+
+fn sections<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let section_alignment = pe.optional_header().SectionAlignment.le();
+	let sections: Vec<IMAGE_SECTION_HEADER> = pe.section_headers().image().iter().map(|it| it.le()).collect();
+
+	for (section, it) in sections.iter().enumerate() {
+		if section_alignment.is_power_of_two() && !it.VirtualAddress.aligned_to(section_alignment) {
+			findings.push(Finding::SectionAlignment { section });
+		}
+		if section > 0 && it.VirtualAddress < sections[section - 1].VirtualAddress {
+			findings.push(Finding::SectionOrder { section });
+		}
+		if pe.align() == Align::File && it.SizeOfRawData != 0 && it.PointerToRawData as u64 + it.SizeOfRawData as u64 > pe.image().len() as u64 {
+			findings.push(Finding::SectionRawBounds { section });
+		}
+	}
+	characteristics(pe, findings);
+
+	let ranges = sections.iter().map(|it| (it.VirtualAddress, virtual_size(it))).collect();
+	overlapping(ranges, |first, second| findings.push(Finding::SectionOverlap { first, second }));
+	let ranges = sections.iter().map(|it| (it.PointerToRawData, it.SizeOfRawData)).collect();
+	overlapping(ranges, |first, second| findings.push(Finding::SectionRawOverlap { first, second }));
+}
+// Names of the sections the toolchains put data in
+const DATA_SECTION_NAMES: [&[u8]; 10] = [b".data", b".rdata", b".bss", b".idata", b".edata", b".pdata", b".tls", b".rsrc", b".reloc", b".didat"];
+
+fn characteristics<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let size_of_image = pe.optional_header().SizeOfImage.le() as u64;
+	for (section, it) in pe.section_headers().image().iter().enumerate() {
+		let it = it.le();
+		let executable = it.Characteristics & IMAGE_SCN_MEM_EXECUTE != 0;
+		let writable = it.Characteristics & IMAGE_SCN_MEM_WRITE != 0;
+		if executable && writable {
+			findings.push(Finding::SectionWritableExecutable { section });
+		}
+		if writable && it.Characteristics & IMAGE_SCN_MEM_SHARED != 0 {
+			findings.push(Finding::SectionSharedWritable { section });
+		}
+		let name = crate::util::strn(&it.Name);
+		if executable && DATA_SECTION_NAMES.iter().any(|data| name.eq_ignore_ascii_case(data)) {
+			findings.push(Finding::SectionExecutableData { section });
+		}
+		if it.SizeOfRawData == 0 && it.VirtualSize != 0 && it.VirtualSize as u64 * 2 >= size_of_image {
+			findings.push(Finding::SectionVirtualOnly { section });
+		}
+	}
+}
+
+// Sweeps the non-empty ranges sorted by start, the number of sections isn't limited in lenient mode
+// Each range overlapping an earlier range is reported once together with the earlier range reaching the furthest
+fn overlapping<F: FnMut(usize, usize)>(ranges: Vec<(u32, u32)>, mut f: F) {
+	let mut sorted: Vec<_> = ranges.into_iter().enumerate()
+		.filter(|&(_, (_, size))| size != 0)
+		.map(|(index, (start, size))| (start as u64, start as u64 + size as u64, index))
+		.collect();
+	sorted.sort_unstable_by_key(|&(start, _, index)| (start, index));
+	let mut furthest: Option<(u64, usize)> = None;
+	for (start, end, index) in sorted {
+		match furthest {
+			Some((furthest_end, other)) if start < furthest_end => {
+				f(cmp::min(index, other), cmp::max(index, other));
+				if end > furthest_end {
+					furthest = Some((end, index));
+				}
+			},
+			_ => furthest = Some((end, index)),
+		}
+	}
+}
+
+fn directories<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	for (index, datadir) in pe.data_directory().iter().enumerate() {
+		let datadir = datadir.le();
+		if datadir.VirtualAddress == 0 && datadir.Size == 0 {
+			continue;
+		}
+		let result = if index == IMAGE_DIRECTORY_ENTRY_SECURITY {
+			// The security directory is a file offset and not mapped in memory
+			if pe.align() == Align::File && datadir.VirtualAddress as u64 + datadir.Size as u64 > pe.image().len() as u64 {
+				Err(Error::Bounds)
+			}
+			else {
+				Ok(())
+			}
+		}
+		else {
+			pe.slice(datadir.VirtualAddress, datadir.Size as usize, 1).map(|_| ())
+		};
+		if let Err(error) = result {
+			findings.push(Finding::DirectoryBounds { index, error });
+		}
+	}
+}
+
+// Reports the directory error unless the directory bounds check already did or the directory is not present
+fn directory_error<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>, index: usize, error: Error) {
+	if index < pe.data_directory().len() && !findings.iter().any(|finding| matches!(*finding, Finding::DirectoryBounds { index: i, .. } if i == index)) {
+		findings.push(Finding::DirectoryBounds { index, error });
+	}
+}
+
+fn imports<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let imports = match pe.imports() {
+		Ok(imports) => imports,
+		Err(Error::Null) => return,
+		Err(error) => return directory_error(pe, findings, IMAGE_DIRECTORY_ENTRY_IMPORT, error),
+	};
+	let mut dll_names: Vec<(&[u8], usize)> = Vec::new();
+	for (desc, import) in imports.iter().enumerate() {
+		match import.dll_name().and_then(|dll_name| check_name(dll_name.as_ref()).map(|_| dll_name)) {
+			Ok(dll_name) => {
+				// Rebuilt import tables split the imports of a DLL over descriptors naming it differently
+				match dll_names.iter().find(|&&(name, _)| name.eq_ignore_ascii_case(dll_name.as_ref())) {
+					Some(&(_, first)) => findings.push(Finding::ImportDllDuplicate { desc, first }),
+					None => dll_names.push((dll_name.as_ref(), desc)),
+				}
+			},
+			Err(error) => findings.push(Finding::ImportDllName { desc, error }),
+		}
+		let image = import.image().le();
+		if !in_section(pe, image.Name) {
+			findings.push(Finding::ImportNameOutsideSection { desc, index: None });
+		}
+		// Bound imports without an import name table are checked by the directory bounds
+		let int = match pe.derva_slice_s::<Va>(image.OriginalFirstThunk, 0) {
+			Ok(int) => int,
+			Err(_) => {
+				if image.OriginalFirstThunk == 0 && import.iat().map_or(false, |mut iat| iat.next().is_some()) {
+					findings.push(Finding::ImportNullInt { desc });
+				}
+				continue;
+			},
+		};
+		let mut hints = Vec::new();
+		for (index, va) in int.iter().enumerate() {
+			let result = import_from_va(pe, va).and_then(|import| match import {
+				Import::ByName { hint, name } => {
+					if !in_section(pe, (*va).le() as Rva) {
+						findings.push(Finding::ImportNameOutsideSection { desc, index: Some(index) });
+					}
+					// Hints of zero are left for the loader to figure out
+					if hint != 0 {
+						hints.push((name, hint, index));
+					}
+					check_name(name.as_ref())
+				},
+				Import::ByOrdinal { .. } => Ok(()),
+			});
+			if let Err(error) = result {
+				findings.push(Finding::ImportName { desc, index, error });
+			}
+		}
+		hints.sort_unstable();
+		for pair in hints.windows(2) {
+			if pair[0].1 >= pair[1].1 {
+				findings.push(Finding::ImportHint { desc, index: pair[1].2 });
+			}
+		}
+	}
+}
+
+fn in_section<'a, P: Pe<'a>>(pe: P, rva: Rva) -> bool {
+	pe.section_headers().iter().map(|it| it.le()).any(|it| rva >= it.VirtualAddress && rva - it.VirtualAddress < virtual_size(&it))
+}
+
+fn exports<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let by = match pe.exports().and_then(|exports| exports.by()) {
+		Ok(by) => by,
+		Err(Error::Null) => return,
+		Err(error) => return directory_error(pe, findings, IMAGE_DIRECTORY_ENTRY_EXPORT, error),
+	};
+	if let Err(error) = by.dll_name().and_then(|dll_name| check_name(dll_name.as_ref())) {
+		findings.push(Finding::ExportDllName(error));
+	}
+	let mut names_ok = true;
+	let mut names = Vec::new();
+	for (index, &name) in by.names().iter().enumerate() {
+		match pe.derva_c_str(name.le()) {
+			// Obfuscated exports hide behind names the tools don't print
+			Ok(name) if !name.is_empty() && name.as_ref().iter().any(|&byte| byte >= 0x80) => {
+				findings.push(Finding::ExportNameNonAscii { index });
+				names.push((name, index));
+			},
+			Ok(name) => {
+				if let Err(error) = check_name(name.as_ref()) {
+					findings.push(Finding::ExportName { index, error });
+					names_ok = false;
+				}
+				names.push((name, index));
+			},
+			Err(error) => {
+				findings.push(Finding::ExportName { index, error });
+				names_ok = false;
+			},
+		}
+	}
+	names.sort_unstable();
+	for pair in names.windows(2) {
+		if pair[0].0 == pair[1].0 {
+			findings.push(Finding::ExportNameDuplicate { index: pair[1].1, first: pair[0].1 });
+		}
+	}
+	if names_ok && by.check_sorted() == Ok(false) {
+		findings.push(Finding::ExportNamesUnsorted);
+	}
+
+	// Runs of null entries between the exports
+	let functions = by.functions();
+	let mut index = 0;
+	while index < functions.len() {
+		let count = functions[index..].iter().take_while(|&&rva| rva == 0).count();
+		if count != 0 {
+			findings.push(Finding::ExportOrdinalGap { ordinal: by.ordinal_base().wrapping_add(index as Ordinal), count });
+		}
+		index += count + 1;
+	}
+}
+
+fn base_relocs<'a, P: Pe<'a>>(pe: P, findings: &mut Vec<Finding>) {
+	let base_relocs = match pe.base_relocs() {
+		Ok(base_relocs) => base_relocs,
+		// Directory errors are reported by the directory bounds
+		Err(_) => return,
+	};
+	let size_of_image = pe.optional_header().SizeOfImage.le() as u64;
+	base_relocs.for_each(|rva, ty| {
+		let size_of = match ty {
+			IMAGE_REL_BASED_DIR64 => 8,
+			IMAGE_REL_BASED_HIGH | IMAGE_REL_BASED_LOW => 2,
+			_ => 4,
+		};
+		if rva as u64 + size_of > size_of_image {
+			findings.push(Finding::RelocTarget { rva });
+		}
+	});
+}
@@ -6,10 +6,11 @@ use std::prelude::v1::*;
 
 use std::{cmp, slice};
 
-use crate::Result;
+use crate::util::Le;
+use crate::{ParseOptions, Result, Violations};
 
 use super::image::*;
-use super::pe::{optional_header, validate_headers};
+use super::pe::{is_low_alignment, optional_header, validate_headers_lax};
 use super::{Align, Pe, PeObject};
 
 /// View into a mapped PE image.
@@ -17,6 +18,8 @@ use super::{Align, Pe, PeObject};
 pub struct PeView<'a> {
 	image: &'a [u8],
 	base_address: Va,
+	options: ParseOptions,
+	violations: Violations,
 }
 
 current_target! {
@@ -56,18 +59,35 @@ impl<'a> PeView<'a> {
 	///   Reasonable limits on `e_lfanew`, `SizeOfHeaders` or `NumberOfSections` are exceeded.
 	#[inline]
 	pub fn from_bytes<T: AsRef<[u8]> + ?Sized>(image: &'a T) -> Result<PeView<'a>> {
+		PeView::from_bytes_with_options(image, ParseOptions::DEFAULT)
+	}
+	/// Constructs a view from a byte slice with custom parse options.
+	///
+	/// See [`from_bytes`](#method.from_bytes) for a list of errors.
+	pub fn from_bytes_with_options<T: AsRef<[u8]> + ?Sized>(image: &'a T, options: ParseOptions) -> Result<PeView<'a>> {
 		let image = image.as_ref();
-		let _ = validate_headers(image)?;
-		let base_address = unsafe { optional_header(image).ImageBase };
-		Ok(PeView { image, base_address })
+		let (_, violations) = validate_headers_lax(image, &options)?;
+		let base_address = unsafe { optional_header(image).ImageBase.le() };
+		Ok(PeView { image, base_address, options, violations })
+	}
+
+	// Constructs a view of an image whose headers were already validated with the options
+	pub(crate) unsafe fn from_validated(image: &'a [u8], options: ParseOptions, violations: Violations) -> PeView<'a> {
+		let base_address = optional_header(image).ImageBase.le();
+		PeView { image, base_address, options, violations }
 	}
 
 	/// Returns a new `PeView` instance with the provided base address.
 	#[inline]
 	#[must_use]
 	pub fn set_base_address(self, base_address: Va) -> PeView<'a> {
-		let PeView { image, .. } = self;
-		PeView { image, base_address }
+		PeView { base_address, ..self }
+	}
+	/// Returns the header violations tolerated by lenient parse options.
+	///
+	/// Always empty unless the view was constructed with lenient options.
+	pub fn violations(&self) -> Violations {
+		self.violations
 	}
 
 	/// Constructs a new view from module handle.
@@ -82,22 +102,25 @@ impl<'a> PeView<'a> {
 	#[inline]
 	pub unsafe fn module(base: *const u8) -> PeView<'a> {
 		let dos = &*(base as *const IMAGE_DOS_HEADER);
-		let nt = &*(base.offset(dos.e_lfanew as isize) as *const IMAGE_NT_HEADERS);
+		let nt = &*(base.offset(dos.e_lfanew.le() as isize) as *const IMAGE_NT_HEADERS);
 		PeView {
-			image: slice::from_raw_parts(base, nt.OptionalHeader.SizeOfImage as usize),
+			image: slice::from_raw_parts(base, nt.OptionalHeader.SizeOfImage.le() as usize),
 			base_address: base as Va,
+			options: ParseOptions::DEFAULT,
+			violations: Violations::empty(),
 		}
 	}
 	/// Converts the view to file alignment.
 	pub fn to_file(self) -> Vec<u8> {
 		let (sizeof_headers, sizeof_image) = {
 			let optional_header = self.optional_header();
-			(optional_header.SizeOfHeaders, optional_header.SizeOfImage)
+			(optional_header.SizeOfHeaders.le(), optional_header.SizeOfImage.le())
 		};
 
 		// Figure out the size of the file image
 		let mut file_size = sizeof_headers;
 		for section in self.section_headers() {
+			let section = section.le();
 			file_size = cmp::max(file_size, u32::wrapping_add(section.PointerToRawData, section.SizeOfRawData));
 		}
 		// Clamp to the actual image size...
@@ -106,22 +129,33 @@ impl<'a> PeView<'a> {
 		// Zero fill the underlying file
 		let mut vec = vec![0u8; file_size as usize];
 
-		// Start by copying the headers
+		// Low alignment images are mapped as is
 		let image = self.image();
+		if is_low_alignment(self.optional_header()) {
+			let len = cmp::min(vec.len(), image.len());
+			vec[..len].copy_from_slice(&image[..len]);
+			return vec;
+		}
+
+		// Start by copying the headers
+		// Lenient parsing allows the headers to exceed the image
+		let sizeof_headers = cmp::min(sizeof_headers as usize, cmp::min(vec.len(), image.len()));
 		unsafe {
-			// Validated by constructor
-			let dest_headers = vec.get_unchecked_mut(..sizeof_headers as usize);
-			let src_headers = image.get_unchecked(..sizeof_headers as usize);
+			let dest_headers = vec.get_unchecked_mut(..sizeof_headers);
+			let src_headers = image.get_unchecked(..sizeof_headers);
 			dest_headers.copy_from_slice(src_headers);
 		}
 
 		// Copy the section image data
 		for section in self.section_headers() {
+			let section = section.le();
 			let dest = vec.get_mut(section.PointerToRawData as usize..u32::wrapping_add(section.PointerToRawData, section.SizeOfRawData) as usize);
 			let src = image.get(section.VirtualAddress as usize..u32::wrapping_add(section.VirtualAddress, section.VirtualSize) as usize);
 			// Skip invalid sections...
 			if let (Some(dest), Some(src)) = (dest, src) {
-				dest.copy_from_slice(src);
+				// Only the smaller of the virtual size and the size of raw data is present in both
+				let len = cmp::min(dest.len(), src.len());
+				dest[..len].copy_from_slice(&src[..len]);
 			}
 		}
 
@@ -144,6 +178,9 @@ unsafe impl<'a> PeObject<'a> for PeView<'a> {
 	fn image_base(&self) -> Va {
 		self.base_address
 	}
+	fn options(&self) -> ParseOptions {
+		self.options
+	}
 
 	#[cfg(feature = "serde")]
 	fn serde_name(&self) -> &'static str {
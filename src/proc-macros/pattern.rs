@@ -612,6 +612,156 @@ fn parse_helper(pat: &mut &str, result: &mut Vec<Atom>) -> Result<(), PatError>
 
 //----------------------------------------------------------------
 
+/// IDA-style signature parser.
+///
+/// IDA-style signatures are whitespace separated hexadecimal bytes with `?` or `??` as wildcards.
+///
+/// ```text
+/// E8 ? ? ? ? 48 8B 4? ??
+/// ```
+///
+/// A question mark in place of one of the hexadecimal digits matches the other nibble only.
+///
+/// Use this to migrate existing signature collections, everything outside this syntax is an error.
+pub fn parse_ida(pat: &str) -> Result<Pattern, ParsePatError> {
+	let mut result = vec![Atom::Save(0)];
+	for token in pat.split(|chr: char| chr.is_ascii_whitespace()) {
+		let position = token.as_ptr() as usize - pat.as_ptr() as usize;
+		let (byte, mask) = match token.as_bytes() {
+			[] => continue,
+			[b'?'] | [b'?', b'?'] => (0, 0),
+			&[hi, lo] => match (nibble(hi), nibble(lo)) {
+				(Some(hi), Some(lo)) => ((hi.0 << 4) | lo.0, (hi.1 << 4) | lo.1),
+				_ => return Err(ParsePatError { kind: PatError::UnknownChar, position }),
+			},
+			_ => return Err(ParsePatError { kind: PatError::UnpairedHexDigit, position }),
+		};
+		match mask {
+			0 => {
+				// Coalescence skips together
+				if let Some(Atom::Skip(skip)) = result.last_mut() {
+					if *skip != PTR_SKIP && *skip < 255u8 {
+						*skip += 1;
+						continue;
+					}
+				}
+				result.push(Atom::Skip(1));
+			},
+			0xff => result.push(Atom::Byte(byte)),
+			_ => {
+				result.push(Atom::Fuzzy(mask));
+				result.push(Atom::Byte(byte));
+			},
+		}
+	}
+	// Remove redundant skips at the end
+	while let Some(Atom::Skip(_)) = result.last() {
+		result.pop();
+	}
+	Ok(result)
+}
+/// Parses a hexadecimal digit or wildcard into its value and mask.
+fn nibble(chr: u8) -> Option<(u8, u8)> {
+	match chr {
+		b'0'...b'9' => Some((chr - b'0', 0xf)),
+		b'A'...b'F' => Some((chr - b'A' + 10, 0xf)),
+		b'a'...b'f' => Some((chr - b'a' + 10, 0xf)),
+		b'?' => Some((0, 0)),
+		_ => None,
+	}
+}
+
+/// Flattens the pattern into a sequence of bytes and their masks.
+///
+/// Returns `None` if the pattern cannot be expressed as a fixed sequence of masked bytes.
+fn flatten(pat: &[Atom]) -> Option<Vec<(u8, u8)>> {
+	let mut result = Vec::new();
+	let mut mask = 0xff;
+	let mut ext = 0usize;
+	for &atom in pat {
+		match atom {
+			// Bookmarks do not affect what the pattern matches
+			Atom::Save(_) | Atom::Zero(_) | Atom::Nop => (),
+			Atom::Byte(byte) => {
+				result.push((byte & mask, mask));
+				mask = 0xff;
+			},
+			Atom::Fuzzy(fuzzy) => mask = fuzzy,
+			Atom::Rangext(range) => ext = range as usize,
+			Atom::Skip(skip) if skip != PTR_SKIP => {
+				let len = ext * 256 + skip as usize;
+				result.extend((0..len).map(|_| (0, 0)));
+				ext = 0;
+			},
+			// Reading operands matches any value
+			Atom::ReadI8(_) | Atom::ReadU8(_) => result.push((0, 0)),
+			Atom::ReadI16(_) | Atom::ReadU16(_) => result.extend_from_slice(&[(0, 0); 2]),
+			Atom::ReadI32(_) | Atom::ReadU32(_) => result.extend_from_slice(&[(0, 0); 4]),
+			_ => return None,
+		}
+	}
+	Some(result)
+}
+
+/// Formats the pattern as an IDA-style signature.
+///
+/// Returns `None` if the pattern cannot be expressed, eg. it follows jumps, has alternatives, variable length skips or masks other than whole nibbles.
+/// Bookmarks are dropped and read operands become wildcards as they do not affect where the pattern matches.
+///
+/// ```text
+/// E8 u4 48 8B ? ?  =>  E8 ? ? ? ? 48 8B
+/// ```
+pub fn to_ida(pat: &[Atom]) -> Option<String> {
+	const HEX: &[u8; 16] = b"0123456789ABCDEF";
+	let mut result = String::new();
+	for (byte, mask) in flatten(pat)? {
+		if !result.is_empty() {
+			result.push(' ');
+		}
+		match mask {
+			0 => result.push('?'),
+			0xff | 0xf0 | 0x0f => {
+				result.push(if mask & 0xf0 != 0 { HEX[(byte >> 4) as usize] as char } else { '?' });
+				result.push(if mask & 0x0f != 0 { HEX[(byte & 0xf) as usize] as char } else { '?' });
+			},
+			_ => return None,
+		}
+	}
+	Some(result)
+}
+
+/// Formats the pattern as a Ghidra pattern.
+///
+/// This is the syntax of the `data` element in Ghidra's function start pattern files: `0x` prefixed hexadecimal bytes
+/// and eight binary digits where a dot matches any bit. Wildcard bytes are written as `........`.
+///
+/// Returns `None` if the pattern cannot be expressed, eg. it follows jumps, has alternatives or variable length skips.
+/// Bookmarks are dropped and read operands become wildcards as they do not affect where the pattern matches.
+///
+/// ```text
+/// 55 8B ? 4?  =>  0x55 0x8b ........ 0100....
+/// ```
+pub fn to_ghidra(pat: &[Atom]) -> Option<String> {
+	use std::fmt::Write;
+	let mut result = String::new();
+	for (byte, mask) in flatten(pat)? {
+		if !result.is_empty() {
+			result.push(' ');
+		}
+		if mask == 0xff {
+			let _ = write!(result, "0x{:02x}", byte);
+		}
+		else {
+			for bit in (0..8).rev() {
+				result.push(if mask & (1 << bit) == 0 { '.' } else if byte & (1 << bit) != 0 { '1' } else { '0' });
+			}
+		}
+	}
+	Some(result)
+}
+
+//----------------------------------------------------------------
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -663,6 +813,26 @@ mod tests {
 		]));
 	}
 
+	#[rustfmt::skip]
+	#[test]
+	fn ida() {
+		use self::Atom::*;
+
+		assert_eq!(parse_ida("E8 ? ? ?? ?  48 8b 4? ?F ?"), Ok(vec![
+			Save(0), Byte(0xE8), Skip(4), Byte(0x48), Byte(0x8B), Fuzzy(0xF0), Byte(0x40), Fuzzy(0x0F), Byte(0x0F)
+		]));
+		assert_eq!(Err(ParsePatError { kind: PatError::UnpairedHexDigit, position: 3 }), parse_ida("E8 123"));
+		assert_eq!(Err(ParsePatError { kind: PatError::UnknownChar, position: 0 }), parse_ida("XY"));
+
+		let pat = parse_ida("E8 ? ? ? ? 48 8B 4? ?F").unwrap();
+		assert_eq!(to_ida(&pat).as_deref(), Some("E8 ? ? ? ? 48 8B 4? ?F"));
+		assert_eq!(to_ghidra(&pat).as_deref(), Some("0xe8 ........ ........ ........ ........ 0x48 0x8b 0100.... ....1111"));
+		assert_eq!(to_ida(&parse("b8 [300] 50").unwrap()).map(|ida| ida.len()), Some(2 + 300 * 2 + 3));
+		assert_eq!(to_ida(&parse("e8 $ ' 31 c0").unwrap()), None);
+		assert_eq!(to_ida(&parse("b8 [16-20] 50").unwrap()), None);
+		assert_eq!(to_ida(&[Save(0), Fuzzy(0x81), Byte(0x81)]), None);
+	}
+
 	#[test]
 	fn errors() {
 		use self::PatError::*;
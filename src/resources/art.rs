@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt;
 
 use super::{Directory, Entry, Resources};
@@ -63,12 +64,16 @@ impl<'a, 'd> TreeFmt<'a, 'd> {
 		TreeFmt { dir, art, depth: 0, margin: 0 }
 	}
 
-	fn draw<F: fmt::Write>(&self, f: &mut F) -> fmt::Result {
+	fn draw<F: fmt::Write>(&self, f: &mut F, visited: &mut BTreeSet<usize>) -> fmt::Result {
 		// Encode if root in depth
 		let (root, depth) = if self.depth == !0 { (true, 0) } else { (false, self.depth) };
 
 		// Quiet failsafe, unlikely to happen
-		if depth >= 32 {
+		if depth >= 32 || depth >= self.dir.resources().max_depth {
+			return Ok(());
+		}
+		// Corrupt resources may loop back to their ancestors, each directory is drawn only once
+		if !visited.insert(self.dir.image() as *const _ as usize) {
 			return Ok(());
 		}
 
@@ -101,7 +106,7 @@ impl<'a, 'd> TreeFmt<'a, 'd> {
 					depth: depth + 1,
 					margin: self.margin | (tail as u32) << depth,
 				}
-				.draw(f)?;
+				.draw(f, visited)?;
 			}
 		}
 		Ok(())
@@ -112,7 +117,7 @@ impl<'a> fmt::Display for Resources<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.write_str("Resources/\n")?;
 		match self.root() {
-			Ok(root) => TreeFmt::root(&root, TreeArt::Ascii).draw(f),
+			Ok(root) => TreeFmt::root(&root, TreeArt::Ascii).draw(f, &mut BTreeSet::new()),
 			Err(err) => err.fmt(f),
 		}
 	}
@@ -121,6 +126,6 @@ impl<'a> fmt::Display for Resources<'a> {
 impl<'a> fmt::Display for Directory<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.write_str("Directory/\n")?;
-		TreeFmt::dir(self, TreeArt::Ascii).draw(f)
+		TreeFmt::dir(self, TreeArt::Ascii).draw(f, &mut BTreeSet::new())
 	}
 }
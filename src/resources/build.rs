@@ -0,0 +1,375 @@
+/*!
+Rebuilding resource sections.
+
+The [`Builder`](struct.Builder.html) reads an existing resource tree or starts from scratch, resources are inserted, replaced or removed by their path and a new resource section is serialized at a chosen rva.
+
+The default [`Compact`](enum.Layout.html#variant.Compact) layout lays out the tree from scratch the way the resource compiler does.
+When only a single resource must change the rest of the section should stay as it was, eg. to keep comparing equal with the original or to keep data referenced from elsewhere in place.
+The [`Preserve`](enum.Layout.html#variant.Preserve) layout keeps the original ordering, string placement and padding so unmodified parts round trip byte for byte.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::resources::Name;
+use pelite::resources::build::{Builder, Layout};
+use pelite::util::Le;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>, manifest: &[u8]) -> pelite::Result<Vec<u8>> {
+	let resources = file.resources()?;
+	let mut builder = Builder::from_resources(resources)?;
+	builder.set_layout(Layout::Preserve);
+	builder.insert(&[Name::MANIFEST, Name::Id(1), Name::Id(1033)], manifest, 0).ok();
+
+	// Place the bytes in the image at the rva of the resource data directory
+	let rva = file.data_directory()[pelite::image::IMAGE_DIRECTORY_ENTRY_RESOURCE].VirtualAddress.le();
+	Ok(builder.build(rva))
+}
+```
+*/
+
+use std::borrow::Cow;
+use std::prelude::v1::*;
+use std::{cmp, mem};
+
+use crate::image::*;
+use crate::util::Le;
+use crate::Error;
+
+use super::find::FindError;
+use super::{Entry, Name, Resources, RSRC_TYPES};
+
+//----------------------------------------------------------------
+
+/// Layout of the serialized resource section.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Layout {
+	/// Lays out the tree from scratch.
+	///
+	/// Named entries sorted by name are followed by id entries sorted by id.
+	/// The directories are followed by the data entries, the name strings and the 8 byte aligned data.
+	#[default]
+	Compact,
+	/// Preserves the layout of the original resources.
+	///
+	/// As long as no entries are added or removed the original section is reused and only the data entries of modified resources are updated.
+	/// Modified data is written over its original data when it fits and appended to the end of the section otherwise.
+	///
+	/// Adding or removing entries falls back to laying out the tree from scratch in the original entry order.
+	Preserve,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum Key {
+	// Named entries come first
+	Name(Vec<u16>),
+	Id(u32),
+}
+impl Key {
+	fn from_name(name: Name<'_>) -> Key {
+		match name {
+			Name::Id(id) => Key::Id(id),
+			Name::Wide(words) => Key::Name(words.to_vec()),
+			Name::Str(string) => {
+				if let Some(id) = string.strip_prefix('#') {
+					if let Ok(id) = id.parse() {
+						return Key::Id(id);
+					}
+					if let Some(id) = RSRC_TYPES.iter().position(|&it| it == Some(string)) {
+						return Key::Id(id as u32);
+					}
+				}
+				Key::Name(string.encode_utf16().collect())
+			},
+		}
+	}
+	fn to_name(&self) -> Name<'_> {
+		match self {
+			&Key::Id(id) => Name::Id(id),
+			Key::Name(words) => Name::Wide(words),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+enum Node<'a> {
+	Directory(Directory<'a>),
+	Data(Data<'a>),
+}
+
+#[derive(Clone, Debug, Default)]
+struct Directory<'a> {
+	characteristics: u32,
+	time_date_stamp: u32,
+	version: (u16, u16),
+	entries: Vec<(Key, Node<'a>)>,
+}
+
+#[derive(Clone, Debug)]
+struct Data<'a> {
+	bytes: Cow<'a, [u8]>,
+	code_page: u32,
+	// Offset of the data entry, offset of the data and size of the data in the original section
+	original: Option<(u32, u32, u32)>,
+	modified: bool,
+}
+
+//----------------------------------------------------------------
+
+/// Resource section builder.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default)]
+pub struct Builder<'a> {
+	root: Directory<'a>,
+	original: Option<Resources<'a>>,
+	layout: Layout,
+	restructured: bool,
+}
+impl<'a> Builder<'a> {
+	/// Creates an empty builder.
+	pub fn new() -> Builder<'a> {
+		Builder::default()
+	}
+	/// Creates a builder with the resources of an existing resource section.
+	///
+	/// The resource tree must pass the [consistency check](../struct.Resources.html#method.fsck).
+	pub fn from_resources(resources: Resources<'a>) -> Result<Builder<'a>, Error> {
+		let root = read_directory(resources.root()?, 0)?;
+		Ok(Builder { root, original: Some(resources), layout: Layout::Compact, restructured: false })
+	}
+	/// Sets the layout of the serialized resource section.
+	pub fn set_layout(&mut self, layout: Layout) {
+		self.layout = layout;
+	}
+	/// Inserts or replaces the resource data at the path, directories along the path are created as needed.
+	///
+	/// The path is typically the type, name and language of the resource.
+	pub fn insert<T: Into<Cow<'a, [u8]>>>(&mut self, path: &[Name<'_>], bytes: T, code_page: u32) -> Result<(), FindError> {
+		let (last, dirs) = path.split_last().ok_or(FindError::NotFound)?;
+		let mut dir = &mut self.root;
+		for &name in dirs {
+			let index = match find(dir, name) {
+				Some(index) => index,
+				None => {
+					self.restructured = true;
+					push(dir, Key::from_name(name), Node::Directory(Directory::default()))
+				},
+			};
+			dir = match &mut dir.entries[index].1 {
+				Node::Directory(dir) => dir,
+				Node::Data(_) => return Err(FindError::UnDataEntry),
+			};
+		}
+		let bytes = bytes.into();
+		match find(dir, *last) {
+			Some(index) => match &mut dir.entries[index].1 {
+				Node::Data(data) => {
+					data.modified |= data.bytes != bytes || data.code_page != code_page;
+					data.bytes = bytes;
+					data.code_page = code_page;
+				},
+				Node::Directory(_) => return Err(FindError::UnDirectory),
+			},
+			None => {
+				self.restructured = true;
+				push(dir, Key::from_name(*last), Node::Data(Data { bytes, code_page, original: None, modified: true }));
+			},
+		}
+		Ok(())
+	}
+	/// Removes the resource data or directory at the path.
+	pub fn remove(&mut self, path: &[Name<'_>]) -> Result<(), FindError> {
+		let (last, dirs) = path.split_last().ok_or(FindError::NotFound)?;
+		let mut dir = &mut self.root;
+		for &name in dirs {
+			let index = find(dir, name).ok_or(FindError::NotFound)?;
+			dir = match &mut dir.entries[index].1 {
+				Node::Directory(dir) => dir,
+				Node::Data(_) => return Err(FindError::UnDataEntry),
+			};
+		}
+		let index = find(dir, *last).ok_or(FindError::NotFound)?;
+		dir.entries.remove(index);
+		self.restructured = true;
+		Ok(())
+	}
+	/// Serializes the resource section to be placed in the image at the given rva.
+	///
+	/// The resource data directory must point at the returned bytes.
+	pub fn build(&self, rva: u32) -> Vec<u8> {
+		match self.original {
+			Some(original) if self.layout == Layout::Preserve && !self.restructured => self.patch(original, rva),
+			_ => self.lay_out(rva),
+		}
+	}
+
+	// Reuses the original section updating the data entries of the modified resources
+	fn patch(&self, original: Resources<'a>, rva: u32) -> Vec<u8> {
+		let mut bytes = original.section.to_vec();
+		bytes.resize(cmp::max(bytes.len(), original.dir.Size.le() as usize), 0);
+		let mut stack = vec![&self.root];
+		while let Some(dir) = stack.pop() {
+			for (_, node) in &dir.entries {
+				let data = match node {
+					Node::Directory(dir) => {
+						stack.push(dir);
+						continue;
+					},
+					Node::Data(data) => data,
+				};
+				let (entry_offset, mut data_offset, size) = match data.original {
+					Some(original) => original,
+					None => continue,
+				};
+				let entry_offset = entry_offset as usize;
+				if data.modified {
+					let start = data_offset as usize;
+					if data.bytes.len() <= size as usize {
+						bytes[start..start + size as usize].fill(0);
+						bytes[start..start + data.bytes.len()].copy_from_slice(&data.bytes);
+					}
+					else {
+						data_offset = align8(bytes.len()) as u32;
+						bytes.resize(data_offset as usize, 0);
+						bytes.extend_from_slice(&data.bytes);
+					}
+					write(&mut bytes, entry_offset + 4, data.bytes.len() as u32);
+					write(&mut bytes, entry_offset + 8, data.code_page);
+				}
+				// Unlike the rest of the tree the data entries are relative to the image
+				write(&mut bytes, entry_offset, rva.wrapping_add(data_offset));
+			}
+		}
+		bytes
+	}
+
+	// Lays out the directories, data entries, strings and data from scratch
+	fn lay_out(&self, rva: u32) -> Vec<u8> {
+		// Directories and their entries in breadth first order
+		let mut dirs = vec![(&self.root, self.entries(&self.root))];
+		let mut index = 0;
+		while index < dirs.len() {
+			let children: Vec<_> = dirs[index].1.iter().filter_map(|&(_, node)| match node {
+				Node::Directory(dir) => Some(dir),
+				Node::Data(_) => None,
+			}).collect();
+			dirs.extend(children.into_iter().map(|dir| (dir, self.entries(dir))));
+			index += 1;
+		}
+
+		let mut dir_offsets = Vec::with_capacity(dirs.len());
+		let mut offset = 0;
+		for (_, entries) in &dirs {
+			dir_offsets.push(offset);
+			offset += mem::size_of::<IMAGE_RESOURCE_DIRECTORY>() + entries.len() * mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>();
+		}
+		let data_entries_offset = offset;
+		let data_count = dirs.iter().flat_map(|(_, entries)| entries).filter(|(_, node)| matches!(node, Node::Data(_))).count();
+		let strings_offset = data_entries_offset + data_count * mem::size_of::<IMAGE_RESOURCE_DATA_ENTRY>();
+		let mut bytes = vec![0u8; strings_offset];
+
+		let mut next_dir = 1;
+		let mut datas = Vec::with_capacity(data_count);
+		for (&(dir, ref entries), &offset) in dirs.iter().zip(&dir_offsets) {
+			let named = entries.iter().filter(|(key, _)| matches!(key, Key::Name(_))).count();
+			write(&mut bytes, offset, dir.characteristics);
+			write(&mut bytes, offset + 4, dir.time_date_stamp);
+			write(&mut bytes, offset + 8, dir.version.0 as u32 | (dir.version.1 as u32) << 16);
+			write(&mut bytes, offset + 12, named as u32 | ((entries.len() - named) as u32) << 16);
+			for (index, &(key, node)) in entries.iter().enumerate() {
+				let name = match key {
+					&Key::Id(id) => id,
+					Key::Name(words) => {
+						let string_offset = bytes.len() as u32;
+						bytes.extend_from_slice(&(words.len() as u16).to_le_bytes());
+						bytes.extend(words.iter().flat_map(|word| word.to_le_bytes()));
+						string_offset | 0x80000000
+					},
+				};
+				let target = match node {
+					Node::Directory(_) => {
+						next_dir += 1;
+						dir_offsets[next_dir - 1] as u32 | 0x80000000
+					},
+					Node::Data(data) => {
+						datas.push(data);
+						(data_entries_offset + (datas.len() - 1) * mem::size_of::<IMAGE_RESOURCE_DATA_ENTRY>()) as u32
+					},
+				};
+				let entry_offset = offset + mem::size_of::<IMAGE_RESOURCE_DIRECTORY>() + index * mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>();
+				write(&mut bytes, entry_offset, name);
+				write(&mut bytes, entry_offset + 4, target);
+			}
+		}
+
+		for (index, data) in datas.into_iter().enumerate() {
+			let data_offset = align8(bytes.len());
+			bytes.resize(data_offset, 0);
+			bytes.extend_from_slice(&data.bytes);
+			let entry_offset = data_entries_offset + index * mem::size_of::<IMAGE_RESOURCE_DATA_ENTRY>();
+			write(&mut bytes, entry_offset, rva.wrapping_add(data_offset as u32));
+			write(&mut bytes, entry_offset + 4, data.bytes.len() as u32);
+			write(&mut bytes, entry_offset + 8, data.code_page);
+		}
+		bytes
+	}
+	fn entries<'d>(&self, dir: &'d Directory<'a>) -> Vec<&'d (Key, Node<'a>)> {
+		let mut entries: Vec<_> = dir.entries.iter().collect();
+		if self.layout == Layout::Compact {
+			entries.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+		}
+		entries
+	}
+}
+
+fn read_directory<'a>(dir: super::Directory<'a>, depth: u32) -> Result<Directory<'a>, Error> {
+	if depth >= dir.resources.max_depth {
+		return Err(Error::Insanity);
+	}
+	let image = dir.image().le();
+	let mut entries = Vec::new();
+	for entry in dir.entries() {
+		let key = Key::from_name(entry.name()?);
+		let node = match entry.entry()? {
+			Entry::Directory(dir) => Node::Directory(read_directory(dir, depth + 1)?),
+			Entry::DataEntry(data) => {
+				let entry_offset = data.image() as *const _ as usize - dir.resources.section.as_ptr() as usize;
+				let data_offset = data.image().OffsetToData.le().wrapping_sub(dir.resources.dir.VirtualAddress.le());
+				let original = Some((entry_offset as u32, data_offset, data.size() as u32));
+				Node::Data(Data { bytes: Cow::Borrowed(data.bytes()?), code_page: data.code_page(), original, modified: false })
+			},
+		};
+		entries.push((key, node));
+	}
+	Ok(Directory {
+		characteristics: image.Characteristics.le(),
+		time_date_stamp: image.TimeDateStamp.le(),
+		version: (image.Version.le().Major, image.Version.le().Minor),
+		entries,
+	})
+}
+
+fn find(dir: &Directory<'_>, name: Name<'_>) -> Option<usize> {
+	dir.entries.iter().position(|(key, _)| key.to_name() == name)
+}
+
+// New named entries are inserted after the existing named entries, new id entries at the end
+fn push<'a>(dir: &mut Directory<'a>, key: Key, node: Node<'a>) -> usize {
+	let index = match key {
+		Key::Name(_) => dir.entries.iter().position(|(key, _)| matches!(key, Key::Id(_))).unwrap_or(dir.entries.len()),
+		Key::Id(_) => dir.entries.len(),
+	};
+	dir.entries.insert(index, (key, node));
+	index
+}
+
+fn write(bytes: &mut [u8], offset: usize, value: u32) {
+	bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn align8(offset: usize) -> usize {
+	(offset + 7) & !7
+}
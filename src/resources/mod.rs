@@ -2,10 +2,12 @@
 Resources.
 */
 
+use std::collections::BTreeSet;
 use std::prelude::v1::*;
 use std::{char, fmt, iter, mem, slice};
 
 use crate::image::*;
+use crate::util::{Le, WStr};
 use crate::{Error, Pod, Result};
 
 //----------------------------------------------------------------
@@ -15,7 +17,10 @@ pub use self::find::FindError;
 
 mod art;
 
+pub mod build;
 pub mod group;
+pub mod salvage;
+pub mod stats;
 pub mod version_info;
 
 //----------------------------------------------------------------
@@ -25,16 +30,27 @@ pub mod version_info;
 pub struct Resources<'a> {
 	section: &'a [u8],
 	dir: &'a IMAGE_DATA_DIRECTORY,
+	max_depth: u32,
 }
 impl<'a> Resources<'a> {
 	/// Parse the bytes as PE resources.
 	///
 	/// No validation or integrity checking is done ahead of time.
+	///
+	/// If the bytes are shorter than the data directory, references past their end within the data directory return [`ZeroFill`](../enum.Error.html#variant.ZeroFill).
 	pub fn new(section: &'a [u8], dir: &'a IMAGE_DATA_DIRECTORY) -> Resources<'a> {
 		// All offsets _except_ the data entry offsets are relative to the resource directory.
 		// Data entry offsets are relative virtual addresses from the PE image.
 		// Microsoft... Why would you do this?
-		Resources { section, dir }
+		Resources { section, dir, max_depth: 32 }
+	}
+	/// Returns a new `Resources` instance with the provided maximum directory nesting depth.
+	///
+	/// Walking the filesystem deeper than this results in an [`Insanity`](../enum.Error.html#variant.Insanity) error, defaults to `32`.
+	#[inline]
+	#[must_use]
+	pub fn set_max_depth(self, max_depth: u32) -> Resources<'a> {
+		Resources { max_depth, ..self }
 	}
 	/// Gets the root directory.
 	pub fn root(&self) -> Result<Directory<'a>> {
@@ -43,20 +59,29 @@ impl<'a> Resources<'a> {
 	/// Filesystem consistency check.
 	///
 	/// Simply walks the filesystem checking all references are valid.
+	/// Fails on the first error, see [`salvage`](#method.salvage) to get what can be read from a corrupt filesystem.
 	pub fn fsck(&self) -> Result<()> {
 		self.root()?.fsck()
 	}
+	/// Finds the path of the data entry whose data contains the rva.
+	///
+	/// The path is formatted like the paths in the [statistics](stats/index.html), eg. `/#MANIFEST/#2/#1033`.
+	pub fn data_path(&self, rva: u32) -> Option<String> {
+		let mut path = String::new();
+		let mut visited = BTreeSet::new();
+		if self.root().ok()?.data_path(rva, &mut path, &mut visited, 0) { Some(path) } else { None }
+	}
 
 	#[inline]
 	fn slice<T: Pod>(&self, offset: u32) -> Result<&'a T> {
 		let start = offset as usize;
 		let end = mem::size_of::<T>().wrapping_add(start);
 		// Alignment checking
-		if !cfg!(feature = "unsafe_alignment") && start & (mem::align_of::<T>() - 1) != 0 {
+		if !cfg!(feature = "unsafe_alignment") && (self.section.as_ptr() as usize).wrapping_add(start) & (mem::align_of::<T>() - 1) != 0 {
 			return Err(Error::Misaligned);
 		}
 		// Range checking done by the indexing operator
-		let bytes = self.section.get(start..end).ok_or(Error::Bounds)?;
+		let bytes = self.section.get(start..end).ok_or_else(|| self.bounds(end))?;
 		// Safe because size and alignment are checked and T is Pod
 		Ok(unsafe { &*(bytes.as_ptr() as *const T) })
 	}
@@ -67,28 +92,34 @@ impl<'a> Resources<'a> {
 		let size_of = mem::size_of::<T>().checked_mul(len).ok_or(Error::Overflow)?;
 		let end = start.wrapping_add(size_of);
 		// Alignment checking
-		if !cfg!(feature = "unsafe_alignment") && start & (mem::align_of::<T>() - 1) != 0 {
+		if !cfg!(feature = "unsafe_alignment") && (self.section.as_ptr() as usize).wrapping_add(start) & (mem::align_of::<T>() - 1) != 0 {
 			return Err(Error::Misaligned);
 		}
 		// Range checking done by the indexing operator
-		let bytes = self.section.get(start..end).ok_or(Error::Bounds)?;
+		let bytes = self.section.get(start..end).ok_or_else(|| self.bounds(end))?;
 		Ok(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const T, len) })
 	}
 	#[inline]
 	fn slice_ws(&self, offset: u32) -> Result<&'a [u16]> {
 		let offset = offset as usize;
 		// Alignment checking
-		if !cfg!(feature = "unsafe_alignment") && offset & 1 != 0 {
+		if !cfg!(feature = "unsafe_alignment") && (self.section.as_ptr() as usize).wrapping_add(offset) & 1 != 0 {
 			return Err(Error::Misaligned);
 		}
 		// The name is prefixed by its length in words
-		let len = self.section.get(offset..offset + 2).ok_or(Error::Bounds)?;
+		let len = self.section.get(offset..offset + 2).ok_or_else(|| self.bounds(offset + 2))?;
 		let len = unsafe { *(len.as_ptr() as *const u16) } as usize;
 		// Extract the name given its length
-		let name = self.section.get(offset + 2..offset + 2 + len * 2).ok_or(Error::Bounds)?;
+		let name = self.section.get(offset + 2..offset + 2 + len * 2).ok_or_else(|| self.bounds(offset + 2 + len * 2))?;
 		let name = unsafe { slice::from_raw_parts(name.as_ptr() as *const u16, len) };
 		Ok(name)
 	}
+	// The resources of file images may be cut short by the end of the section's raw data
+	// Distinguish references within the data directory which aren't available on disk from references out of bounds
+	#[inline]
+	fn bounds(&self, end: usize) -> Error {
+		if end > self.section.len() && end <= self.dir.Size.le() as usize { Error::ZeroFill } else { Error::Bounds }
+	}
 }
 impl<'a> fmt::Debug for Resources<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -110,10 +141,10 @@ impl<'a> Directory<'a> {
 		// Validate the number of directory entries
 		// This code has been carefully written to avoid panicking on overflow
 		// It also validates the unsafe blocks below cf. size and alignment
-		let entries_size = (image.NumberOfNamedEntries as usize + image.NumberOfIdEntries as usize) * mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>();
+		let entries_size = (image.NumberOfNamedEntries.le() as usize + image.NumberOfIdEntries.le() as usize) * mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>();
 		let entries_offset = offset as usize + mem::size_of::<IMAGE_RESOURCE_DIRECTORY>();
 		if entries_size > resources.section.len() - entries_offset {
-			return Err(Error::Bounds);
+			return Err(resources.bounds(entries_offset + entries_size));
 		}
 		Ok(Directory { resources, image })
 	}
@@ -130,7 +161,7 @@ impl<'a> Directory<'a> {
 		// Validated by constructor
 		let slice = unsafe {
 			let p = (self.image as *const IMAGE_RESOURCE_DIRECTORY).offset(1) as *const IMAGE_RESOURCE_DIRECTORY_ENTRY;
-			let len = self.image.NumberOfNamedEntries as usize + self.image.NumberOfIdEntries as usize;
+			let len = self.image.NumberOfNamedEntries.le() as usize + self.image.NumberOfIdEntries.le() as usize;
 			slice::from_raw_parts(p, len)
 		};
 		let resources = self.resources;
@@ -144,7 +175,7 @@ impl<'a> Directory<'a> {
 		let slice = unsafe {
 			// Named entries come first in the array (see chapter "PE File Resources" in "Peering Inside the PE: A Tour of the Win32 Portable Executable File Format")
 			let p = (self.image as *const IMAGE_RESOURCE_DIRECTORY).offset(1) as *const IMAGE_RESOURCE_DIRECTORY_ENTRY;
-			let len = self.image.NumberOfNamedEntries as usize;
+			let len = self.image.NumberOfNamedEntries.le() as usize;
 			slice::from_raw_parts(p, len)
 		};
 		let resources = self.resources;
@@ -157,8 +188,8 @@ impl<'a> Directory<'a> {
 		// Validated by the constructor
 		let slice = unsafe {
 			// Id entries come last in the array
-			let p = ((self.image as *const IMAGE_RESOURCE_DIRECTORY).offset(1) as *const IMAGE_RESOURCE_DIRECTORY_ENTRY).offset(self.image.NumberOfNamedEntries as isize);
-			let len = self.image.NumberOfIdEntries as usize;
+			let p = ((self.image as *const IMAGE_RESOURCE_DIRECTORY).offset(1) as *const IMAGE_RESOURCE_DIRECTORY_ENTRY).offset(self.image.NumberOfNamedEntries.le() as isize);
+			let len = self.image.NumberOfIdEntries.le() as usize;
 			slice::from_raw_parts(p, len)
 		};
 		let resources = self.resources;
@@ -168,7 +199,39 @@ impl<'a> Directory<'a> {
 	///
 	/// Simply walks the filesystem checking all references are valid.
 	pub fn fsck(&self) -> Result<()> {
-		self.entries().try_for_each(|e| e.fsck())
+		self.fsck_(0)
+	}
+	fn fsck_(&self, depth: u32) -> Result<()> {
+		if depth >= self.resources.max_depth {
+			return Err(Error::Insanity);
+		}
+		self.entries().try_for_each(|e| e.fsck_(depth + 1))
+	}
+	// Corrupt resources may reference the same directory many times, each directory is visited only once
+	fn data_path(&self, rva: u32, path: &mut String, visited: &mut BTreeSet<usize>, depth: u32) -> bool {
+		if depth >= self.resources.max_depth || !visited.insert(self.image as *const _ as usize) {
+			return false;
+		}
+		for entry in self.entries() {
+			let name = match entry.name() {
+				Ok(name) => name,
+				Err(_) => continue,
+			};
+			let len = path.len();
+			path.push('/');
+			let names: &[Option<&str>] = if depth == 0 { &RSRC_TYPES } else { &[] };
+			path.push_str(&name.rename_id(names).to_string());
+			let found = match entry.entry() {
+				Ok(Entry::Directory(dir)) => dir.data_path(rva, path, visited, depth + 1),
+				Ok(Entry::DataEntry(data)) => rva.wrapping_sub(data.image.OffsetToData.le()) < data.image.Size.le(),
+				Err(_) => false,
+			};
+			if found {
+				return true;
+			}
+			path.truncate(len);
+		}
+		false
 	}
 }
 #[rustfmt::skip]
@@ -238,6 +301,19 @@ impl<'a> Name<'a> {
 			&Name::Str(name) => string == name,
 		}
 	}
+	/// Returns the id if the name is an id which fits in a `u16`.
+	///
+	/// Windows APIs cannot address resources with larger ids.
+	pub fn id(&self) -> Option<u16> {
+		match *self {
+			Name::Id(id) if id <= 0xffff => Some(id as u16),
+			_ => None,
+		}
+	}
+	/// Returns true if the name is an id.
+	pub fn is_id(&self) -> bool {
+		matches!(self, Name::Id(_))
+	}
 	fn rename_id(self, names: &[Option<&'a str>]) -> Name<'a> {
 		if let Name::Id(id) = self {
 			if let Some(&Some(name)) = names.get(id as usize) {
@@ -257,6 +333,11 @@ impl<'a> From<&'a [u16]> for Name<'a> {
 		Name::Wide(words)
 	}
 }
+impl<'a> From<&'a WStr> for Name<'a> {
+	fn from(name: &'a WStr) -> Name<'a> {
+		Name::Wide(name)
+	}
+}
 impl<'a> From<&'a str> for Name<'a> {
 	fn from(name: &'a str) -> Name<'a> {
 		Name::Str(name)
@@ -282,6 +363,16 @@ impl PartialEq<str> for Name<'_> {
 		self.eq_string(rhs)
 	}
 }
+impl<'b> PartialEq<&'b str> for Name<'_> {
+	fn eq(&self, rhs: &&'b str) -> bool {
+		self.eq_string(rhs)
+	}
+}
+impl PartialEq<u16> for Name<'_> {
+	fn eq(&self, &rhs: &u16) -> bool {
+		*self == rhs as u32
+	}
+}
 impl PartialEq<u32> for Name<'_> {
 	fn eq(&self, &rhs: &u32) -> bool {
 		match self {
@@ -353,38 +444,34 @@ impl<'a> DirectoryEntry<'a> {
 	}
 	/// Gets the name for this entry.
 	pub fn name(&self) -> Result<Name<'a>> {
-		if self.image.Name & 0x80000000 != 0 {
-			let offset = self.image.Name & !0x80000000;
-			let words = self.resources.slice_ws(offset)?;
-			Ok(Name::Wide(words))
-		}
-		else {
-			// TODO: What if this doesn't fit in u16...
-			Ok(Name::Id(self.image.Name))
+		match self.image.name_offset() {
+			Some(offset) => self.resources.slice_ws(offset).map(Name::Wide),
+			None => Ok(Name::Id(self.image.Name.le())),
 		}
 	}
 	/// Returns if this entry is a directory.
 	pub fn is_dir(&self) -> bool {
-		self.image.Offset & 0x80000000 != 0
+		self.image.is_dir()
 	}
 	/// Returns the directory or data entry for this entry.
 	pub fn entry(&self) -> Result<Entry<'a>> {
 		if self.is_dir() {
-			let offset = self.image.Offset & !0x80000000;
-			Directory::try_from(self.resources, offset).map(Entry::Directory)
+			Directory::try_from(self.resources, self.image.offset()).map(Entry::Directory)
 		}
 		else {
-			let offset = self.image.Offset;
-			DataEntry::try_from(self.resources, offset).map(Entry::DataEntry)
+			DataEntry::try_from(self.resources, self.image.offset()).map(Entry::DataEntry)
 		}
 	}
 	/// Filesystem consistency check.
 	///
 	/// Simply walks the filesystem checking all references are valid.
 	pub fn fsck(&self) -> Result<()> {
+		self.fsck_(0)
+	}
+	fn fsck_(&self, depth: u32) -> Result<()> {
 		self.name()?;
 		match self.entry()? {
-			Entry::Directory(dir) => dir.fsck(),
+			Entry::Directory(dir) => dir.fsck_(depth),
 			Entry::DataEntry(data) => data.fsck(),
 		}
 	}
@@ -421,17 +508,17 @@ impl<'a> DataEntry<'a> {
 	}
 	/// Gets the actual data.
 	pub fn bytes(&self) -> Result<&'a [u8]> {
-		let start = u32::checked_sub(self.image.OffsetToData, self.resources.dir.VirtualAddress).ok_or(Error::Overflow)?;
-		let end = u32::checked_add(start, self.image.Size).ok_or(Error::Overflow)?;
-		self.resources.section.get(start as usize..end as usize).ok_or(Error::Bounds)
+		let start = u32::checked_sub(self.image.OffsetToData.le(), self.resources.dir.VirtualAddress.le()).ok_or(Error::Overflow)?;
+		let end = u32::checked_add(start, self.image.Size.le()).ok_or(Error::Overflow)?;
+		self.resources.section.get(start as usize..end as usize).ok_or_else(|| self.resources.bounds(end as usize))
 	}
 	/// Gets the data size.
 	pub fn size(&self) -> usize {
-		self.image.Size as usize
+		self.image.Size.le() as usize
 	}
 	/// Gets the code page.
 	pub fn code_page(&self) -> u32 {
-		self.image.CodePage
+		self.image.CodePage.le()
 	}
 	/// Filesystem consistency check.
 	///
@@ -445,7 +532,7 @@ impl<'a> DataEntry<'a> {
 impl<'a> fmt::Debug for DataEntry<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.debug_struct("DataEntry")
-			.field("data.len", &self.image.Size)
+			.field("data.len", &self.image.Size.le())
 			.finish()
 	}
 }
@@ -508,6 +595,7 @@ static RSRC_TYPES: [Option<&str>; 25] = [
 #[cfg(feature = "serde")]
 mod serde {
 	use crate::util::serde_helper::*;
+	use crate::util::Le;
 
 	use super::{DataEntry, Directory, DirectoryEntry, Name, Resources};
 
@@ -555,7 +643,7 @@ mod serde {
 	impl<'a> Serialize for DataEntry<'a> {
 		fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
 			let mut state = serializer.serialize_struct("DataEntry", 3)?;
-			state.serialize_field("address", &self.image().OffsetToData)?;
+			state.serialize_field("address", &self.image().OffsetToData.le())?;
 			state.serialize_field("size", &self.size())?;
 			state.serialize_field("code_page", &self.code_page())?;
 			state.end()
@@ -616,3 +704,25 @@ pub(crate) fn test(resources: Resources<'_>) -> Result<()> {
 	}
 	resources.root().map(test_dir)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn name_eq() {
+		let words = [b'I' as u16, b'C' as u16, b'O' as u16, b'N' as u16, 0];
+		let wide = Name::from(WStr::from_words(&words).unwrap());
+		assert_eq!(wide, "ICON");
+		assert_eq!(wide, Name::Str("ICON"));
+		assert!(wide != 3u16 && !wide.is_id() && wide.id().is_none());
+
+		let id = Name::Id(RT_ICON as u32);
+		assert_eq!(id, 3u16);
+		assert_eq!(id, "#3");
+		assert_eq!(id, "#ICON");
+		assert!(id != "ICON" && id != wide);
+		assert_eq!(id.id(), Some(3));
+		assert_eq!(Name::Id(0x10000).id(), None);
+	}
+}
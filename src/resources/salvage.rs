@@ -0,0 +1,133 @@
+/*!
+Salvaging corrupt resource trees.
+
+Malware likes to corrupt a single branch of the resource tree to break the tools inspecting it:
+a directory entry referencing one of its ancestors, an offset pointing out of bounds or an absurdly deep nesting.
+
+Where [`fsck`](../struct.Resources.html#method.fsck) fails on the first error it encounters, salvaging walks the whole tree,
+collects every readable data entry and marks the broken subtrees with what is wrong with them.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::resources::Resources;
+
+# #[allow(dead_code)]
+fn example(resources: Resources<'_>) -> pelite::Result<()> {
+	let salvage = resources.salvage()?;
+	for (path, data) in &salvage.data {
+		println!("{}: {} bytes", path, data.size());
+	}
+	for broken in &salvage.broken {
+		println!("{} is broken: {:?}", broken.path, broken.damage);
+	}
+	Ok(())
+}
+```
+*/
+
+use std::collections::BTreeSet;
+use std::prelude::v1::*;
+
+use crate::{Error, Result};
+
+use super::{DataEntry, Directory, Entry, Resources, RSRC_TYPES};
+
+//----------------------------------------------------------------
+
+/// What is wrong with a broken subtree.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Damage {
+	/// The entry's name, directory, data entry or data cannot be read.
+	Error(Error),
+	/// The directory was already visited, it loops back to one of its ancestors or is shared with another subtree.
+	Revisited,
+	/// The directory is nested deeper than the [maximum depth](../struct.Resources.html#method.set_max_depth).
+	TooDeep,
+}
+
+/// Broken subtree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Broken {
+	/// Path to the broken entry, entries whose name cannot be read are named by their index as in `/#ICON/[2]`.
+	pub path: String,
+	/// What is wrong with it.
+	pub damage: Damage,
+}
+
+/// Salvaged resource tree.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default)]
+pub struct Salvage<'a> {
+	/// Paths and data entries whose data can be read, in directory order.
+	pub data: Vec<(String, DataEntry<'a>)>,
+	/// Broken subtrees in directory order.
+	pub broken: Vec<Broken>,
+}
+impl<'a> Salvage<'a> {
+	/// Returns true if no subtree is broken.
+	pub fn is_intact(&self) -> bool {
+		self.broken.is_empty()
+	}
+}
+
+//----------------------------------------------------------------
+
+impl<'a> Resources<'a> {
+	/// Salvages what can be read from the resource tree.
+	///
+	/// Only errors if the root directory cannot be read, broken subtrees below it are skipped and reported.
+	pub fn salvage(&self) -> Result<Salvage<'a>> {
+		let root = self.root()?;
+		let mut salvager = Salvager {
+			salvage: Salvage::default(),
+			visited: BTreeSet::new(),
+		};
+		salvager.directory(root, &mut String::new(), 0);
+		Ok(salvager.salvage)
+	}
+}
+
+struct Salvager<'a> {
+	salvage: Salvage<'a>,
+	visited: BTreeSet<usize>,
+}
+
+impl<'a> Salvager<'a> {
+	fn broken(&mut self, path: &str, damage: Damage) {
+		self.salvage.broken.push(Broken { path: String::from(path), damage });
+	}
+	fn directory(&mut self, dir: Directory<'a>, path: &mut String, depth: u32) {
+		if depth >= dir.resources.max_depth {
+			return self.broken(path, Damage::TooDeep);
+		}
+		if !self.visited.insert(dir.image as *const _ as usize) {
+			return self.broken(path, Damage::Revisited);
+		}
+		for (index, entry) in dir.entries().enumerate() {
+			let len = path.len();
+			path.push('/');
+			match entry.name() {
+				Ok(name) => {
+					let names: &[Option<&str>] = if depth == 0 { &RSRC_TYPES } else { &[] };
+					path.push_str(&name.rename_id(names).to_string());
+				},
+				Err(err) => {
+					path.push_str(&format!("[{}]", index));
+					self.broken(path, Damage::Error(err));
+				},
+			}
+			match entry.entry() {
+				Ok(Entry::Directory(dir)) => self.directory(dir, path, depth + 1),
+				Ok(Entry::DataEntry(data)) => match data.bytes() {
+					Ok(_) => self.salvage.data.push((path.clone(), data)),
+					Err(err) => self.broken(path, Damage::Error(err)),
+				},
+				Err(err) => self.broken(path, Damage::Error(err)),
+			}
+			path.truncate(len);
+		}
+	}
+}
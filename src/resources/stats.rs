@@ -0,0 +1,233 @@
+/*!
+Resource statistics and duplicate detection.
+
+Walks the whole resource tree and reports where the bytes go: total size by resource type, the languages present,
+data entries with identical contents under different paths and the bytes not referenced by any structure or data.
+
+Useful to slim down installers and to spot resource-stuffed binaries.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::resources::Resources;
+
+# #[allow(dead_code)]
+fn example(resources: Resources<'_>) -> pelite::Result<()> {
+	let stats = resources.stats()?;
+	for ty in &stats.types {
+		println!("{}: {} entries, {} bytes", ty.name, ty.count, ty.size);
+	}
+	for dupe in &stats.duplicates {
+		println!("{} bytes duplicated at {:?}", dupe.size, dupe.paths);
+	}
+	println!("{} bytes wasted on duplicates and {} bytes of padding", stats.duplicate_size(), stats.padding);
+	Ok(())
+}
+```
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::prelude::v1::*;
+use std::{cmp, fmt, mem};
+
+use crate::image::*;
+use crate::util::Le;
+use crate::Result;
+
+use super::{Directory, Entry, Name, Resources, RSRC_TYPES};
+
+//----------------------------------------------------------------
+
+/// Size of a resource type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeStats<'a> {
+	/// Name of the resource type, predefined type ids are renamed as in `#VERSION`.
+	pub name: Name<'a>,
+	/// Number of data entries.
+	pub count: usize,
+	/// Total size of the data entries.
+	pub size: usize,
+}
+
+/// Data entries with the same contents.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Duplicate {
+	/// Size of a single copy.
+	pub size: usize,
+	/// Paths to the data entries, can be passed to [`find_data`](../struct.Resources.html#method.find_data).
+	pub paths: Vec<String>,
+}
+
+/// Resource statistics.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats<'a> {
+	/// Number of data entries.
+	pub data_entries: usize,
+	/// Total size of the data entries.
+	pub data_size: usize,
+	/// Statistics by resource type in directory order.
+	pub types: Vec<TypeStats<'a>>,
+	/// Sorted language ids present in the tree.
+	pub languages: Vec<u32>,
+	/// Data entries with identical contents, largest first.
+	pub duplicates: Vec<Duplicate>,
+	/// Number of bytes in the resource directory not referenced by any structure, name or data.
+	pub padding: usize,
+	/// Number of entries which could not be read.
+	pub invalid: usize,
+}
+
+impl<'a> Stats<'a> {
+	/// Returns the number of bytes which could be saved by deduplicating the data entries.
+	pub fn duplicate_size(&self) -> usize {
+		self.duplicates.iter().map(|dupe| dupe.size * (dupe.paths.len() - 1)).sum()
+	}
+}
+
+impl<'a> fmt::Display for Stats<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "Data entries: {} ({} bytes)", self.data_entries, self.data_size)?;
+		for ty in &self.types {
+			writeln!(f, "  {}: {} ({} bytes)", ty.name, ty.count, ty.size)?;
+		}
+		f.write_str("Languages:")?;
+		for lang in &self.languages {
+			write!(f, " {}", lang)?;
+		}
+		writeln!(f)?;
+		writeln!(f, "Duplicates: {} bytes", self.duplicate_size())?;
+		for dupe in &self.duplicates {
+			writeln!(f, "  {} bytes: {}", dupe.size, dupe.paths.join(", "))?;
+		}
+		writeln!(f, "Padding: {} bytes", self.padding)?;
+		writeln!(f, "Invalid: {}", self.invalid)
+	}
+}
+
+//----------------------------------------------------------------
+
+impl<'a> Resources<'a> {
+	/// Walks the resource tree and collects statistics.
+	///
+	/// Only errors if the root directory cannot be read, corrupt entries below it are counted as invalid.
+	/// Directories referenced more than once are visited only once.
+	pub fn stats(&self) -> Result<Stats<'a>> {
+		let root = self.root()?;
+		let mut walker = Walker {
+			resources: *self,
+			stats: Stats::default(),
+			visited: BTreeSet::new(),
+			ranges: Vec::new(),
+			contents: BTreeMap::new(),
+		};
+		walker.directory(root, &mut String::new(), None, 0);
+		Ok(walker.finish())
+	}
+}
+
+struct Walker<'a> {
+	resources: Resources<'a>,
+	stats: Stats<'a>,
+	visited: BTreeSet<usize>,
+	ranges: Vec<(usize, usize)>,
+	contents: BTreeMap<&'a [u8], Vec<String>>,
+}
+
+impl<'a> Walker<'a> {
+	fn offset<T>(&self, ptr: *const T) -> usize {
+		ptr as usize - self.resources.section.as_ptr() as usize
+	}
+	fn directory(&mut self, dir: Directory<'a>, path: &mut String, ty: Option<usize>, depth: u32) {
+		let start = self.offset(dir.image);
+		if depth >= self.resources.max_depth || !self.visited.insert(start) {
+			self.stats.invalid += 1;
+			return;
+		}
+		let len = dir.image.NumberOfNamedEntries.le() as usize + dir.image.NumberOfIdEntries.le() as usize;
+		let size = mem::size_of::<IMAGE_RESOURCE_DIRECTORY>() + len * mem::size_of::<IMAGE_RESOURCE_DIRECTORY_ENTRY>();
+		self.ranges.push((start, start + size));
+
+		for entry in dir.entries() {
+			let name = match entry.name() {
+				Ok(name) => name,
+				Err(_) => {
+					self.stats.invalid += 1;
+					continue;
+				},
+			};
+			if let Name::Wide(words) = name {
+				let start = self.offset(words.as_ptr()) - 2;
+				self.ranges.push((start, start + 2 + words.len() * 2));
+			}
+			// The first level names the type, the third level the language
+			let ty = match ty {
+				Some(ty) => ty,
+				None => {
+					let name = name.rename_id(&RSRC_TYPES);
+					match self.stats.types.iter().position(|ts| ts.name == name) {
+						Some(index) => index,
+						None => {
+							self.stats.types.push(TypeStats { name, count: 0, size: 0 });
+							self.stats.types.len() - 1
+						},
+					}
+				},
+			};
+			if let (2, Name::Id(lang)) = (depth, name) {
+				if let Err(index) = self.stats.languages.binary_search(&lang) {
+					self.stats.languages.insert(index, lang);
+				}
+			}
+
+			let len = path.len();
+			path.push('/');
+			let names: &[Option<&str>] = if depth == 0 { &RSRC_TYPES } else { &[] };
+			path.push_str(&name.rename_id(names).to_string());
+			match entry.entry() {
+				Ok(Entry::Directory(dir)) => self.directory(dir, path, Some(ty), depth + 1),
+				Ok(Entry::DataEntry(data)) => {
+					let start = self.offset(data.image);
+					self.ranges.push((start, start + mem::size_of::<IMAGE_RESOURCE_DATA_ENTRY>()));
+					match data.bytes() {
+						Ok(bytes) => {
+							let start = self.offset(bytes.as_ptr());
+							self.ranges.push((start, start + bytes.len()));
+							self.stats.data_entries += 1;
+							self.stats.data_size += bytes.len();
+							self.stats.types[ty].count += 1;
+							self.stats.types[ty].size += bytes.len();
+							self.contents.entry(bytes).or_default().push(path.clone());
+						},
+						Err(_) => self.stats.invalid += 1,
+					}
+				},
+				Err(_) => self.stats.invalid += 1,
+			}
+			path.truncate(len);
+		}
+	}
+	fn finish(mut self) -> Stats<'a> {
+		// Count the bytes not covered by any of the referenced ranges
+		self.ranges.sort_unstable();
+		let mut covered = 0;
+		let mut end = 0;
+		for &(start, stop) in &self.ranges {
+			let start = usize::max(start, end);
+			if stop > start {
+				covered += stop - start;
+				end = stop;
+			}
+		}
+		self.stats.padding = self.resources.section.len() - covered;
+
+		self.stats.duplicates = self.contents.into_iter()
+			.filter(|(bytes, paths)| !bytes.is_empty() && paths.len() > 1)
+			.map(|(bytes, paths)| Duplicate { size: bytes.len(), paths })
+			.collect();
+		self.stats.duplicates.sort_by_key(|dupe| cmp::Reverse(dupe.size));
+		self.stats
+	}
+}
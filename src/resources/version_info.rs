@@ -23,6 +23,9 @@ fn example(bin: PeFile<'_>) -> Result<(), pelite::resources::FindError> {
 	// Query some properties
 	let company_name = version_info.value(lang, "CompanyName");
 
+	// Query a property with the language fallback of the Windows shell
+	let file_description = version_info.file_description();
+
 	// Print all the properties for this language
 	version_info.strings(lang, |key, value| {
 		println!("{}: {:?}", key, value);
@@ -50,7 +53,7 @@ use std::{char, cmp, fmt, mem, slice};
 use hashbrown::HashMap;
 
 use crate::image::VS_FIXEDFILEINFO;
-use crate::util::{wstrn, AlignTo, FmtUtf16};
+use crate::util::{wstrn, AlignTo, FmtUtf16, Le};
 use crate::{Error, Pod, Result};
 
 //----------------------------------------------------------------
@@ -152,6 +155,59 @@ impl<'a> VersionInfo<'a> {
 	pub fn strings<F: FnMut(&str, &str)>(self, lang: Language, f: F) {
 		self.visit(&mut QueryStrings { lang, f });
 	}
+	/// Gets a string value by name with language fallback.
+	///
+	/// Looks for the first string table with the key, trying the languages in order:
+	///
+	/// 1. The preferred language ids, eg. an explicitly requested language followed by the user's default UI language.
+	/// 2. US English `0x0409`.
+	/// 3. Language neutral `0x0000`.
+	/// 4. The languages in the translation table followed by any other string table.
+	///
+	/// For every language id the charsets from the translation table are tried before Unicode `0x04B0` and Windows Latin-1 `0x04E4`.
+	pub fn query(self, preferred: &[u16], key: &str) -> Option<String> {
+		let langs = self.translation();
+		let mut tables = QueryTables(Vec::new());
+		self.visit(&mut tables);
+		let tables = tables.0;
+
+		let mut order = Vec::with_capacity(tables.len());
+		for &lang_id in preferred.iter().chain(&[0x0409, 0x0000]) {
+			let charset_ids = langs.iter().map(|lang| lang.charset_id).chain([0x04B0, 0x04E4].iter().cloned());
+			for charset_id in charset_ids {
+				let lang = Language { lang_id, charset_id };
+				if tables.contains(&lang) && !order.contains(&lang) {
+					order.push(lang);
+				}
+			}
+		}
+		for &lang in langs.iter().chain(&tables) {
+			if tables.contains(&lang) && !order.contains(&lang) {
+				order.push(lang);
+			}
+		}
+		order.into_iter().find_map(|lang| self.value(lang, key))
+	}
+	/// Gets the `CompanyName` with language fallback, see [`query`](#method.query).
+	pub fn company_name(self) -> Option<String> {
+		self.query(&[], "CompanyName")
+	}
+	/// Gets the `FileDescription` with language fallback, see [`query`](#method.query).
+	pub fn file_description(self) -> Option<String> {
+		self.query(&[], "FileDescription")
+	}
+	/// Gets the `FileVersion` string with language fallback, see [`query`](#method.query).
+	pub fn file_version(self) -> Option<String> {
+		self.query(&[], "FileVersion")
+	}
+	/// Gets the `ProductName` with language fallback, see [`query`](#method.query).
+	pub fn product_name(self) -> Option<String> {
+		self.query(&[], "ProductName")
+	}
+	/// Gets the `ProductVersion` string with language fallback, see [`query`](#method.query).
+	pub fn product_version(self) -> Option<String> {
+		self.query(&[], "ProductVersion")
+	}
 	/// Parse the version info into HashMaps.
 	pub fn file_info(self) -> FileInfo<'a> {
 		let mut file_info = FileInfo::default();
@@ -282,6 +338,19 @@ impl<'a> Visit<'a> for QueryTranslation<'a> {
 	}
 }
 
+struct QueryTables(Vec<Language>);
+impl<'a> Visit<'a> for QueryTables {
+	fn file_info(&mut self, key: &'a [u16]) -> bool {
+		key == strings::StringFileInfo
+	}
+	fn string_table(&mut self, lang: &'a [u16]) -> bool {
+		if let Ok(lang) = Language::parse(lang) {
+			self.0.push(lang);
+		}
+		false
+	}
+}
+
 struct QueryValue<'z> {
 	lang: Language,
 	key: &'z str,
@@ -326,6 +395,7 @@ impl<'a, F: FnMut(&str, &str)> Visit<'a> for QueryStrings<F> {
 impl<'a> Visit<'a> for String {
 	fn version_info(&mut self, _key: &'a [u16], fixed: Option<&'a VS_FIXEDFILEINFO>) -> bool {
 		if let Some(fixed) = fixed {
+			let (file_version, product_version) = (fixed.file_version(), fixed.product_version());
 			#[rustfmt::skip]
 			let _ = writeln!(self, "\
 1 VERSIONINFO
@@ -336,9 +406,9 @@ FILEFLAGS {:#x}
 FILEOS ({} << 16) | {}
 FILETYPE {}
 FILESUBTYPE {}",
-				fixed.dwFileVersion.Major, fixed.dwFileVersion.Minor, fixed.dwFileVersion.Patch, fixed.dwFileVersion.Build,
-				fixed.dwProductVersion.Major, fixed.dwProductVersion.Minor, fixed.dwProductVersion.Patch, fixed.dwProductVersion.Build,
-				fixed.dwFileFlagsMask, fixed.dwFileFlags, fixed.dwFileOS >> 16, fixed.dwFileOS & 0xffff, fixed.dwFileType, fixed.dwFileSubtype,
+				file_version.0, file_version.1, file_version.2, file_version.3,
+				product_version.0, product_version.1, product_version.2, product_version.3,
+				fixed.dwFileFlagsMask.le(), fixed.dwFileFlags.le(), fixed.dwFileOS.le() >> 16, fixed.dwFileOS.le() & 0xffff, fixed.dwFileType.le(), fixed.dwFileSubtype.le(),
 			);
 		}
 		true
@@ -681,6 +751,14 @@ fn test_parse_254() {
 	let fi = vi.file_info();
 	assert!(fi.fixed.is_some());
 
+	let fixed = vi.fixed().unwrap();
+	assert_eq!(fixed.file_version(), (22, 607, 2013, 25));
+	assert_eq!(fixed.dwProductVersion.to_string(), "22.607.2013.25");
+	assert_eq!(fixed.file_flags().to_string(), "0x0");
+	assert_eq!(fixed.file_os().to_str(), Some("VOS__WINDOWS32"));
+	assert_eq!(fixed.file_type().to_string(), "DLL");
+	assert_eq!(fixed.driver_subtype(), None);
+
 	let mut strings = HashMap::new();
 	strings.insert(Language { lang_id: 0, charset_id: 1200 }, {
 		let mut strings = HashMap::new();
@@ -698,5 +776,9 @@ fn test_parse_254() {
 
 	assert_eq!(fi.strings, strings);
 	assert_eq!(fi.langs, &[Language { lang_id: 0, charset_id: 1200 }]);
+
+	assert_eq!(vi.file_description().as_deref(), Some("BE.Essential"));
+	assert_eq!(vi.query(&[0x0407], "FileVersion").as_deref(), Some("22.607.2013.25"));
+	assert_eq!(vi.query(&[], "Comments"), None);
 	// panic!("{:#?}", fi);
 }
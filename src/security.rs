@@ -18,6 +18,9 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 	// The raw certificate data bytes
 	let certificate_data = security.certificate_data();
 
+	// Decode the Authenticode signature
+	let signed_data = security.signed_data()?;
+
 	Ok(())
 }
 ```
@@ -25,8 +28,14 @@ fn example(file: PeFile<'_>) -> pelite::Result<()> {
 
 use std::{fmt, mem};
 
-use crate::image::WIN_CERTIFICATE;
+use crate::authenticode::SignedData;
+#[cfg(feature = "verify")]
+use crate::authenticode::verify::{Verification, Verifier};
+#[cfg(feature = "verify")]
+use crate::timestamp::UtcDateTime;
+use crate::image::{WIN_CERTIFICATE, WIN_CERT_TYPE_PKCS_SIGNED_DATA};
 use crate::util::AlignTo;
+use crate::{Error, Result};
 
 /// Security Directory.
 ///
@@ -34,12 +43,15 @@ use crate::util::AlignTo;
 #[derive(Copy, Clone)]
 pub struct Security<'a> {
 	image: &'a [u8], // unsafe: MUST BE DWORD ALIGNED!
+	file: &'a [u8],
+	check_sum: usize,
+	datadir: usize,
 }
 impl<'a> Security<'a> {
-	pub(crate) unsafe fn new(image: &'a [u8]) -> Security<'a> {
+	pub(crate) unsafe fn new(image: &'a [u8], file: &'a [u8], check_sum: usize, datadir: usize) -> Security<'a> {
 		debug_assert!(image.as_ptr().aligned_to(mem::align_of::<WIN_CERTIFICATE>()));
 		debug_assert!(image.len() >= 8);
-		Security { image }
+		Security { image, file, check_sum, datadir }
 	}
 	/// Returns the underlying security directory image.
 	pub fn image(&self) -> &'a WIN_CERTIFICATE {
@@ -71,6 +83,33 @@ impl<'a> Security<'a> {
 		// Safety checked by new
 		unsafe { self.image.get_unchecked(8..) }
 	}
+	/// Decodes the certificate data as an Authenticode signature.
+	///
+	/// Returns [`Err(Invalid)`](../enum.Error.html#variant.Invalid) if the certificate type is not `WIN_CERT_TYPE_PKCS_SIGNED_DATA`.
+	pub fn signed_data(&self) -> Result<SignedData<'a>> {
+		if self.certificate_type() != WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+			return Err(Error::Invalid);
+		}
+		SignedData::parse(self.certificate_data())
+	}
+	/// Feeds the bytes covered by the Authenticode digest of the image to the callback.
+	///
+	/// The whole file is digested in order, skipping the `CheckSum` field, the certificate table data directory entry and the certificate table itself.
+	pub fn authenticode_digest<F: FnMut(&'a [u8])>(&self, mut update: F) {
+		let start = self.image.as_ptr() as usize - self.file.as_ptr() as usize;
+		let end = start + self.image.len();
+		update(&self.file[..self.check_sum]);
+		update(&self.file[self.check_sum + 4..self.datadir]);
+		update(&self.file[self.datadir + 8..start]);
+		update(&self.file[end..]);
+	}
+	/// Verifies the Authenticode signature against the trust anchors at the time of signing.
+	///
+	/// The time is used if the signature is not timestamped, see the [verify](../authenticode/verify/index.html) module for more information.
+	#[cfg(feature = "verify")]
+	pub fn verify<V: Verifier + ?Sized>(&self, verifier: &V, trust_anchors: &[&[u8]], time: UtcDateTime) -> Verification<'a> {
+		crate::authenticode::verify::verify(self, verifier, trust_anchors, time)
+	}
 }
 impl<'a> fmt::Debug for Security<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
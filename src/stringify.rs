@@ -2,7 +2,7 @@
 Stringify image constants.
 */
 
-use std::mem;
+use std::{fmt, mem, ops};
 use std::str::FromStr;
 
 use crate::image::*;
@@ -17,9 +17,23 @@ macro_rules! enum1 {
 		$($name:ident => $desc:expr,)*
 	) => {
 		$(#[$meta])*
-		#[derive(Copy, Clone)]
+		#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
 		pub struct $Item(pub $ty);
 		impl $Item {
+			/// Wraps the raw value, unknown values are preserved.
+			#[inline]
+			pub const fn from_raw(value: $ty) -> $Item {
+				$Item(value)
+			}
+			/// Returns the raw value.
+			#[inline]
+			pub const fn to_raw(self) -> $ty {
+				self.0
+			}
+			/// Returns true if the value is one of the known constants.
+			pub fn is_known(self) -> bool {
+				self.to_str().is_some()
+			}
 			/// Gets the code identifier name for the value.
 			pub fn to_str(self) -> Option<&'static str> {
 				match self.0 {
@@ -35,6 +49,12 @@ macro_rules! enum1 {
 				}
 			}
 		}
+		impl From<$ty> for $Item {
+			#[inline]
+			fn from(value: $ty) -> $Item {
+				$Item(value)
+			}
+		}
 		impl FromStr for $Item {
 			type Err = ();
 			fn from_str(s: &str) -> Result<$Item, ()> {
@@ -44,6 +64,22 @@ macro_rules! enum1 {
 				}
 			}
 		}
+		impl fmt::Display for $Item {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				match self.description() {
+					Some(desc) => f.write_str(desc),
+					None => write!(f, "{:#x}", self.0),
+				}
+			}
+		}
+		impl fmt::Debug for $Item {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				match self.to_str() {
+					Some(name) => write!(f, "{}({})", stringify!($Item), name),
+					None => write!(f, "{}({:#x})", stringify!($Item), self.0),
+				}
+			}
+		}
 	}
 }
 
@@ -54,9 +90,35 @@ macro_rules! flags {
 		$($index:expr, $name:expr => $desc:expr,)*
 	) => {
 		$(#[$meta])*
-		#[derive(Copy, Clone)]
+		#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
 		pub struct $Item(pub $ty);
 		impl $Item {
+			/// Wraps the raw bits, unknown flags are preserved.
+			#[inline]
+			pub const fn from_raw(bits: $ty) -> $Item {
+				$Item(bits)
+			}
+			/// Returns the raw bits.
+			#[inline]
+			pub const fn bits(self) -> $ty {
+				self.0
+			}
+			/// Returns true if all the given flags are set.
+			#[inline]
+			pub const fn contains(self, flags: $ty) -> bool {
+				self.0 & flags == flags
+			}
+			/// Returns true if any of the given flags are set.
+			#[inline]
+			pub const fn intersects(self, flags: $ty) -> bool {
+				self.0 & flags != 0
+			}
+			/// Returns the set bits which do not have a code identifier.
+			pub fn unknown_bits(self) -> $ty {
+				(0..mem::size_of::<$ty>() as u32 * 8)
+					.filter(|&i| Self::flag_str(i).is_none())
+					.fold(0, |acc, i| acc | (self.0 & (1 << i)))
+			}
 			/// Gets the code identifier for a flag value given the bit index.
 			pub fn flag_str(index: u32) -> Option<&'static str> {
 				match index {
@@ -71,6 +133,7 @@ macro_rules! flags {
 					_ => None,
 				}
 			}
+			/// Parses a code identifier into its flag value.
 			pub fn parse_flag(s: &str) -> Option<$ty> {
 				match s {
 					$(stringify!($name) => Some($name),)*
@@ -90,6 +153,52 @@ macro_rules! flags {
 					})
 			}
 		}
+		impl From<$ty> for $Item {
+			#[inline]
+			fn from(bits: $ty) -> $Item {
+				$Item(bits)
+			}
+		}
+		impl ops::BitOr<$ty> for $Item {
+			type Output = $Item;
+			#[inline]
+			fn bitor(self, rhs: $ty) -> $Item {
+				$Item(self.0 | rhs)
+			}
+		}
+		impl ops::BitAnd<$ty> for $Item {
+			type Output = $Item;
+			#[inline]
+			fn bitand(self, rhs: $ty) -> $Item {
+				$Item(self.0 & rhs)
+			}
+		}
+		/// Formats the set flags as their code identifiers separated by `|`, unknown bits are printed in hex.
+		impl fmt::Display for $Item {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				let mut first = true;
+				for name in self.to_strs() {
+					if !first {
+						f.write_str(" | ")?;
+					}
+					f.write_str(name)?;
+					first = false;
+				}
+				let unknown = self.unknown_bits();
+				if unknown != 0 || first {
+					if !first {
+						f.write_str(" | ")?;
+					}
+					write!(f, "{:#x}", unknown)?;
+				}
+				Ok(())
+			}
+		}
+		impl fmt::Debug for $Item {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				write!(f, "{}({})", stringify!($Item), self)
+			}
+		}
 	};
 }
 
@@ -98,9 +207,45 @@ macro_rules! flags {
 enum1! {
 	/// Stringifies the `IMAGE_FILE_MACHINE_*` constants for [`IMAGE_FILE_HEADER::Machine`](../image/struct.IMAGE_FILE_HEADER.html#Machine.v).
 	Machine(machine: u16),
+	IMAGE_FILE_MACHINE_UNKNOWN => "Unknown",
+	IMAGE_FILE_MACHINE_TARGET_HOST => "Target host",
 	IMAGE_FILE_MACHINE_I386 => "i386",
-	IMAGE_FILE_MACHINE_AMD64 => "AMD64",
+	IMAGE_FILE_MACHINE_R3000 => "MIPS R3000",
+	IMAGE_FILE_MACHINE_R4000 => "MIPS R4000",
+	IMAGE_FILE_MACHINE_R10000 => "MIPS R10000",
+	IMAGE_FILE_MACHINE_WCEMIPSV2 => "MIPS WCE v2",
+	IMAGE_FILE_MACHINE_ALPHA => "Alpha",
+	IMAGE_FILE_MACHINE_SH3 => "SH3",
+	IMAGE_FILE_MACHINE_SH3DSP => "SH3 DSP",
+	IMAGE_FILE_MACHINE_SH3E => "SH3E",
+	IMAGE_FILE_MACHINE_SH4 => "SH4",
+	IMAGE_FILE_MACHINE_SH5 => "SH5",
+	IMAGE_FILE_MACHINE_ARM => "ARM",
+	IMAGE_FILE_MACHINE_THUMB => "Thumb",
+	IMAGE_FILE_MACHINE_ARMNT => "ARM Thumb-2",
+	IMAGE_FILE_MACHINE_AM33 => "AM33",
+	IMAGE_FILE_MACHINE_POWERPC => "PowerPC",
+	IMAGE_FILE_MACHINE_POWERPCFP => "PowerPC FP",
 	IMAGE_FILE_MACHINE_IA64 => "IA64",
+	IMAGE_FILE_MACHINE_MIPS16 => "MIPS16",
+	IMAGE_FILE_MACHINE_ALPHA64 => "Alpha64",
+	IMAGE_FILE_MACHINE_MIPSFPU => "MIPS FPU",
+	IMAGE_FILE_MACHINE_MIPSFPU16 => "MIPS16 FPU",
+	IMAGE_FILE_MACHINE_TRICORE => "TriCore",
+	IMAGE_FILE_MACHINE_CEF => "CEF",
+	IMAGE_FILE_MACHINE_EBC => "EFI Byte Code",
+	IMAGE_FILE_MACHINE_CHPE_X86 => "CHPE x86",
+	IMAGE_FILE_MACHINE_RISCV32 => "RISC-V 32",
+	IMAGE_FILE_MACHINE_RISCV64 => "RISC-V 64",
+	IMAGE_FILE_MACHINE_RISCV128 => "RISC-V 128",
+	IMAGE_FILE_MACHINE_LOONGARCH32 => "LoongArch32",
+	IMAGE_FILE_MACHINE_LOONGARCH64 => "LoongArch64",
+	IMAGE_FILE_MACHINE_AMD64 => "AMD64",
+	IMAGE_FILE_MACHINE_M32R => "M32R",
+	IMAGE_FILE_MACHINE_ARM64EC => "ARM64EC",
+	IMAGE_FILE_MACHINE_ARM64X => "ARM64X",
+	IMAGE_FILE_MACHINE_ARM64 => "ARM64",
+	IMAGE_FILE_MACHINE_CEE => "CEE",
 }
 
 flags! {
@@ -149,6 +294,7 @@ enum1! {
 	IMAGE_SUBSYSTEM_EFI_ROM => "Windows EFI ROM",
 	IMAGE_SUBSYSTEM_XBOX => "XBOX",
 	IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION => "Windows Boot Application",
+	IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG => "XBOX Code Catalog",
 }
 
 flags! {
@@ -283,6 +429,76 @@ enum1! {
 	UWOP_PUSH_MACHFRAME => "push machframe",
 }
 
+flags! {
+	/// Stringifies the `VS_FF_*` flag indices for [`VS_FIXEDFILEINFO::dwFileFlags`](../image/struct.VS_FIXEDFILEINFO.html#dwFileFlags.v).
+	FileFlags(file_flags: u32),
+	/*0001*/ 0, VS_FF_DEBUG => "Debug build",
+	/*0002*/ 1, VS_FF_PRERELEASE => "Prerelease build",
+	/*0004*/ 2, VS_FF_PATCHED => "Patched",
+	/*0008*/ 3, VS_FF_PRIVATEBUILD => "Private build",
+	/*0010*/ 4, VS_FF_INFOINFERRED => "Version info created dynamically",
+	/*0020*/ 5, VS_FF_SPECIALBUILD => "Special build",
+}
+
+enum1! {
+	/// Stringifies the `VOS_*` constants for [`VS_FIXEDFILEINFO::dwFileOS`](../image/struct.VS_FIXEDFILEINFO.html#dwFileOS.v).
+	FileOS(file_os: u32),
+	VOS_UNKNOWN => "Unknown",
+	VOS__WINDOWS16 => "16-bit Windows",
+	VOS__PM16 => "16-bit Presentation Manager",
+	VOS__PM32 => "32-bit Presentation Manager",
+	VOS__WINDOWS32 => "32-bit Windows",
+	VOS_DOS => "MS-DOS",
+	VOS_OS216 => "16-bit OS/2",
+	VOS_OS232 => "32-bit OS/2",
+	VOS_NT => "Windows NT",
+	VOS_WINCE => "Windows CE",
+	VOS_DOS_WINDOWS16 => "16-bit Windows on MS-DOS",
+	VOS_DOS_WINDOWS32 => "32-bit Windows on MS-DOS",
+	VOS_OS216_PM16 => "16-bit Presentation Manager on 16-bit OS/2",
+	VOS_OS232_PM32 => "32-bit Presentation Manager on 32-bit OS/2",
+	VOS_NT_WINDOWS32 => "Windows NT",
+}
+
+enum1! {
+	/// Stringifies the `VFT_*` constants for [`VS_FIXEDFILEINFO::dwFileType`](../image/struct.VS_FIXEDFILEINFO.html#dwFileType.v).
+	FileType(file_type: u32),
+	VFT_UNKNOWN => "Unknown",
+	VFT_APP => "Application",
+	VFT_DLL => "DLL",
+	VFT_DRV => "Device driver",
+	VFT_FONT => "Font",
+	VFT_VXD => "Virtual device",
+	VFT_STATIC_LIB => "Static-link library",
+}
+
+enum1! {
+	/// Stringifies the `VFT2_DRV_*` constants for [`VS_FIXEDFILEINFO::dwFileSubtype`](../image/struct.VS_FIXEDFILEINFO.html#dwFileSubtype.v) of drivers.
+	DriverSubtype(driver_subtype: u32),
+	VFT2_UNKNOWN => "Unknown",
+	VFT2_DRV_PRINTER => "Printer driver",
+	VFT2_DRV_KEYBOARD => "Keyboard driver",
+	VFT2_DRV_LANGUAGE => "Language driver",
+	VFT2_DRV_DISPLAY => "Display driver",
+	VFT2_DRV_MOUSE => "Mouse driver",
+	VFT2_DRV_NETWORK => "Network driver",
+	VFT2_DRV_SYSTEM => "System driver",
+	VFT2_DRV_INSTALLABLE => "Installable driver",
+	VFT2_DRV_SOUND => "Sound driver",
+	VFT2_DRV_COMM => "Communications driver",
+	VFT2_DRV_INPUTMETHOD => "Input method driver",
+	VFT2_DRV_VERSIONED_PRINTER => "Versioned printer driver",
+}
+
+enum1! {
+	/// Stringifies the `VFT2_FONT_*` constants for [`VS_FIXEDFILEINFO::dwFileSubtype`](../image/struct.VS_FIXEDFILEINFO.html#dwFileSubtype.v) of fonts.
+	FontSubtype(font_subtype: u32),
+	VFT2_UNKNOWN => "Unknown",
+	VFT2_FONT_RASTER => "Raster font",
+	VFT2_FONT_VECTOR => "Vector font",
+	VFT2_FONT_TRUETYPE => "TrueType font",
+}
+
 enum1! {
 	/// Stringifies the `UNW_FLAG_*` constants for [`UNWIND_INFO` flags](..image/struct.UNWIND_INFO.html).
 	UnwindFlag(unwind_flag: u8),
@@ -313,4 +529,8 @@ enum1! {
 	IMAGE_DEBUG_TYPE_ILTCG => "ILTCG",
 	IMAGE_DEBUG_TYPE_MPX => "MPX",
 	IMAGE_DEBUG_TYPE_REPRO => "Repro",
+	IMAGE_DEBUG_TYPE_EMBEDDED_PORTABLE_PDB => "Embedded Portable PDB",
+	IMAGE_DEBUG_TYPE_SPGO => "SPGO",
+	IMAGE_DEBUG_TYPE_PDBCHECKSUM => "PDB Checksum",
+	IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS => "Extended DLL Characteristics",
 }
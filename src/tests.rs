@@ -27,10 +27,12 @@ fn pocs() {
 		println!("  exception...      {:?}", test!(image, exception));
 		println!("  exports...        {:?}", test!(image, exports));
 		println!("  imports...        {:?}", test!(image, imports));
+		println!("  delay_imports...  {:?}", test!(image, delay_imports));
 		println!("  debug...          {:?}", test!(image, debug));
 		println!("  load_config...    {:?}", test!(image, load_config));
 		println!("  security...       {:?}", test!(image, security));
 		println!("  tls...            {:?}", test!(image, tls));
+		println!("  clr...            {:?}", test!(image, clr));
 		println!("  resources...      {:?}", test!(image, resources));
 		println!("  scanner...        {:?}", test!(image, scanner));
 	}
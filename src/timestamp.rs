@@ -0,0 +1,185 @@
+/*!
+TimeDateStamp decoding.
+
+The `TimeDateStamp` field in the file header is traditionally the number of seconds since the unix epoch when the linker created the image.
+Deterministic builds (eg. `/Brepro`) replace this with a hash of the image contents, which decodes into nonsense dates.
+Such images are marked with an `IMAGE_DEBUG_TYPE_REPRO` debug directory entry.
+
+# Examples
+
+```
+# #![allow(unused_variables)]
+use pelite::pe64::{Pe, PeFile};
+use pelite::timestamp::TimeDateStamp;
+
+# #[allow(dead_code)]
+fn example(file: PeFile<'_>) {
+	match file.headers().time_date_stamp() {
+		TimeDateStamp::Time(_) => println!("linked at {}", file.headers().time_date_stamp()),
+		TimeDateStamp::ReproHash(hash) => println!("reproducible build {:08x}", hash),
+	}
+}
+```
+
+With the `chrono` or `time` feature enabled the timestamp converts into their date time types with
+[`to_chrono`](enum.TimeDateStamp.html#method.to_chrono) and [`to_time`](enum.TimeDateStamp.html#method.to_time).
+*/
+
+use std::fmt;
+
+/// Decoded `TimeDateStamp`.
+///
+/// For more information see the [module-level documentation](index.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TimeDateStamp {
+	/// Seconds since the unix epoch.
+	Time(u32),
+	/// Hash of the image contents stored by deterministic builds.
+	ReproHash(u32),
+}
+impl TimeDateStamp {
+	/// Decodes the raw value given whether the image is a reproducible build.
+	#[inline]
+	pub const fn new(value: u32, repro: bool) -> TimeDateStamp {
+		if repro { TimeDateStamp::ReproHash(value) } else { TimeDateStamp::Time(value) }
+	}
+	/// Returns the raw value.
+	#[inline]
+	pub const fn to_raw(self) -> u32 {
+		match self {
+			TimeDateStamp::Time(value) => value,
+			TimeDateStamp::ReproHash(value) => value,
+		}
+	}
+	/// Returns true if the value is a hash instead of a timestamp.
+	#[inline]
+	pub const fn is_repro(self) -> bool {
+		matches!(self, TimeDateStamp::ReproHash(_))
+	}
+	/// Gets the seconds since the unix epoch.
+	///
+	/// Returns `None` for reproducible builds and for the zero timestamp which is used to mean 'no timestamp'.
+	#[inline]
+	pub const fn unix_time(self) -> Option<u32> {
+		match self {
+			TimeDateStamp::Time(0) => None,
+			TimeDateStamp::Time(value) => Some(value),
+			TimeDateStamp::ReproHash(_) => None,
+		}
+	}
+	/// Decodes the timestamp into a calendar date and time in UTC.
+	pub fn utc(self) -> Option<UtcDateTime> {
+		self.unix_time().map(UtcDateTime::from_unix_time)
+	}
+	/// Converts the timestamp into a `SystemTime`.
+	#[cfg(feature = "std")]
+	pub fn system_time(self) -> Option<std::time::SystemTime> {
+		let secs = self.unix_time()?;
+		Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs as u64))
+	}
+	/// Converts the timestamp into a `chrono` date time in UTC.
+	///
+	/// Returns `None` for reproducible builds and for the zero timestamp.
+	#[cfg(feature = "chrono")]
+	pub fn to_chrono(self) -> Option<chrono::DateTime<chrono::Utc>> {
+		chrono::DateTime::from_timestamp(self.unix_time()? as i64, 0)
+	}
+	/// Converts the timestamp into a `time` date time in UTC.
+	///
+	/// Returns `None` for reproducible builds and for the zero timestamp.
+	#[cfg(feature = "time")]
+	pub fn to_time(self) -> Option<time::OffsetDateTime> {
+		time::OffsetDateTime::from_unix_timestamp(self.unix_time()? as i64).ok()
+	}
+}
+impl fmt::Display for TimeDateStamp {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			TimeDateStamp::Time(0) => f.write_str("none"),
+			TimeDateStamp::Time(value) => fmt::Display::fmt(&UtcDateTime::from_unix_time(value), f),
+			TimeDateStamp::ReproHash(value) => write!(f, "{:08x} (repro)", value),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Calendar date and time in UTC.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct UtcDateTime {
+	pub year: u16,
+	pub month: u8,
+	pub day: u8,
+	pub hour: u8,
+	pub minute: u8,
+	pub second: u8,
+}
+impl UtcDateTime {
+	/// Converts seconds since the unix epoch into a calendar date and time.
+	pub fn from_unix_time(secs: u32) -> UtcDateTime {
+		let days = secs / 86400;
+		let time = secs % 86400;
+		// Civil from days algorithm by Howard Hinnant, restricted to dates after the epoch
+		let z = days + 719468;
+		let era = z / 146097;
+		let doe = z - era * 146097;
+		let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+		let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+		let mp = (5 * doy + 2) / 153;
+		let day = doy - (153 * mp + 2) / 5 + 1;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 };
+		let year = yoe + era * 400 + (month <= 2) as u32;
+		UtcDateTime {
+			year: year as u16,
+			month: month as u8,
+			day: day as u8,
+			hour: (time / 3600) as u8,
+			minute: (time / 60 % 60) as u8,
+			second: (time % 60) as u8,
+		}
+	}
+}
+impl fmt::Display for UtcDateTime {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", self.year, self.month, self.day, self.hour, self.minute, self.second)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn utc() {
+		assert_eq!(UtcDateTime::from_unix_time(0).to_string(), "1970-01-01 00:00:00 UTC");
+		assert_eq!(UtcDateTime::from_unix_time(951782400).to_string(), "2000-02-29 00:00:00 UTC");
+		assert_eq!(UtcDateTime::from_unix_time(1599303245).to_string(), "2020-09-05 10:54:05 UTC");
+		assert_eq!(UtcDateTime::from_unix_time(u32::MAX).to_string(), "2106-02-07 06:28:15 UTC");
+	}
+
+	#[test]
+	fn repro() {
+		let stamp = TimeDateStamp::new(0xdeadbeef, true);
+		assert!(stamp.is_repro());
+		assert_eq!(stamp.utc(), None);
+		assert_eq!(stamp.to_string(), "deadbeef (repro)");
+		assert_eq!(TimeDateStamp::new(0, false).unix_time(), None);
+	}
+
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn chrono() {
+		assert_eq!(TimeDateStamp::new(1599303245, false).to_chrono().unwrap().to_string(), "2020-09-05 10:54:05 UTC");
+		assert_eq!(TimeDateStamp::new(0xdeadbeef, true).to_chrono(), None);
+	}
+
+	#[cfg(feature = "time")]
+	#[test]
+	fn time() {
+		let time = TimeDateStamp::new(u32::MAX, false).to_time().unwrap();
+		assert_eq!((time.year(), time.month() as u8, time.day(), time.hour(), time.minute(), time.second()), (2106, 2, 7, 6, 28, 15));
+		assert_eq!(TimeDateStamp::new(0xdeadbeef, true).to_time(), None);
+	}
+}
@@ -2,6 +2,8 @@
 Nul-terminated C string.
 */
 
+use std::prelude::v1::*;
+use std::borrow::Cow;
 use std::{cmp, fmt, mem, ops, str};
 
 use crate::util::{split_f, FromBytes};
@@ -53,9 +55,33 @@ impl CStr {
 		&self.bytes
 	}
 	/// Casts the C string to an UTF8 validated `str`.
+	///
+	/// The error converts into [`Error::Encoding`](../enum.Error.html#variant.Encoding) with the `?` operator.
 	pub fn to_str(&self) -> Result<&str, str::Utf8Error> {
 		str::from_utf8(self.as_ref())
 	}
+	/// Converts the C string to a `str`, replacing invalid UTF8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+	///
+	/// Borrows the C string if it is valid UTF8.
+	pub fn to_string_lossy(&self) -> Cow<'_, str> {
+		String::from_utf8_lossy(self.as_ref())
+	}
+	/// Compares the C string against the string ignoring ASCII case.
+	///
+	/// The loader matches the names of the DLLs imported from this way.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use pelite::util::CStr;
+	///
+	/// let dll_name = CStr::from_bytes(b"KERNEL32.dll\0").unwrap();
+	/// assert!(dll_name.eq_ignore_case("kernel32.DLL"));
+	/// assert!(!dll_name.eq_ignore_case("kernel32"));
+	/// ```
+	pub fn eq_ignore_case(&self, rhs: &str) -> bool {
+		self.as_ref().eq_ignore_ascii_case(rhs.as_bytes())
+	}
 }
 
 impl FromBytes for CStr {
@@ -136,7 +162,7 @@ impl fmt::Debug for CStr {
 					f.write_str(s)?;
 				},
 				_ => {
-					let (s, tail) = split_f(bytes, |&byte| byte >= 0x20 && byte < 0x80);
+					let (s, tail) = split_f(bytes, |&byte| byte >= 0x20 && byte < 0x7F);
 					bytes = tail;
 					for &byte in s {
 						write!(f, "\\x{:02X}", byte)?;
@@ -194,9 +220,21 @@ mod tests {
 		assert_eq!(CStr::from_bytes(b"length is eighteen\0").unwrap().len(), 18);
 	}
 
+	#[test]
+	fn conversions() {
+		let c_str = CStr::from_bytes(b"hello\xFFworld\0").unwrap();
+		assert!(c_str.to_str().is_err());
+		assert_eq!(c_str.to_string_lossy(), "hello\u{FFFD}world");
+		assert_eq!(CStr::from_bytes(b"KERNEL32.dll\0").unwrap().to_string_lossy(), "KERNEL32.dll");
+		assert!(CStr::from_bytes(b"KERNEL32.dll\0").unwrap().eq_ignore_case("kernel32.DLL"));
+		assert!(!c_str.eq_ignore_case("HELLO\u{FFFD}WORLD"));
+		assert!(CStr::empty().eq_ignore_case(""));
+	}
+
 	#[test]
 	fn fmt() {
 		assert_eq!(format!("{}", unsafe { CStr::from_bytes_unchecked(b"\tabc\n\xFFhello\x80world\0") }), "\tabc\n\\xFFhello\\x80world");
 		assert_eq!(format!("{:?}", unsafe { CStr::from_bytes_unchecked(b"\tabc\n\xFFhello\x80world\0") }), r#""\tabc\n\xFFhello\x80world""#);
+		assert_eq!(format!("{:?}", unsafe { CStr::from_bytes_unchecked(b"\x7F\x01del\0") }), r#""\x7F\x01del""#);
 	}
 }
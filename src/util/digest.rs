@@ -0,0 +1,43 @@
+/// Hash function fed with the bytes of an image.
+///
+/// Implement this for the hash function of your choice, eg. a SHA-256 implementation from another crate.
+pub trait Digest {
+	/// The resulting hash.
+	type Output;
+	/// Feeds more bytes to the hash function.
+	fn update(&mut self, bytes: &[u8]);
+	/// Finishes the hash.
+	fn finish(self) -> Self::Output;
+}
+
+/// The 64-bit FNV-1a hash function.
+///
+/// Fast and good enough to cluster similar images, not suitable to defend against tampering.
+///
+/// ```
+/// use pelite::util::{Digest, Fnv1a};
+///
+/// let mut fnv = Fnv1a::default();
+/// fnv.update(b"foobar");
+/// assert_eq!(fnv.finish(), 0x85944171f73967e8);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+	fn default() -> Fnv1a {
+		Fnv1a(0xcbf29ce484222325)
+	}
+}
+
+impl Digest for Fnv1a {
+	type Output = u64;
+	fn update(&mut self, bytes: &[u8]) {
+		for &byte in bytes {
+			self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+		}
+	}
+	fn finish(self) -> u64 {
+		self.0
+	}
+}
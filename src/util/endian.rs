@@ -0,0 +1,170 @@
+/*!
+Byte order conversion.
+*/
+
+use crate::image::*;
+
+/// Converts values read from the image to native byte order.
+///
+/// PE images store all their values in little endian byte order while the image structures are read by casting the raw bytes.
+/// On big endian targets the fields of these structures must be converted before they can be used.
+///
+/// This is a no-op on little endian targets.
+///
+/// The PE headers are converted when validating the headers and translating addresses,
+/// the directory wrappers convert the fields they read and their methods return values in native byte order.
+/// Structures and tables borrowed straight from the image are returned as stored, this includes the [`Pe`](../pe64/trait.Pe.html) header accessors,
+/// the `image()` of the directory wrappers and raw tables such as the export address table.
+/// The headers and directory descriptors implement this trait to convert them as a whole.
+///
+/// # Examples
+///
+/// ```
+/// use pelite::pe64::{Pe, PeFile};
+/// use pelite::util::Le;
+///
+/// # #[allow(dead_code)]
+/// fn size_of_image(file: PeFile<'_>) -> u32 {
+/// 	file.optional_header().SizeOfImage.le()
+/// }
+/// ```
+pub trait Le: Copy {
+	/// Converts from little endian to native byte order.
+	fn le(self) -> Self;
+}
+
+macro_rules! impl_le_int {
+	($($ty:ty),*) => {
+		$(impl Le for $ty {
+			#[inline]
+			fn le(self) -> $ty {
+				<$ty>::from_le(self)
+			}
+		})*
+	};
+}
+impl_le_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: Le, const N: usize> Le for [T; N] {
+	#[inline]
+	fn le(self) -> [T; N] {
+		self.map(Le::le)
+	}
+}
+
+impl<T: Le> Le for IMAGE_VERSION<T> {
+	#[inline]
+	fn le(self) -> IMAGE_VERSION<T> {
+		IMAGE_VERSION { Major: self.Major.le(), Minor: self.Minor.le() }
+	}
+}
+
+impl<V: Le> Le for IMAGE_TLS_DIRECTORY_T<V> {
+	#[inline]
+	fn le(self) -> IMAGE_TLS_DIRECTORY_T<V> {
+		IMAGE_TLS_DIRECTORY_T {
+			StartAddressOfRawData: self.StartAddressOfRawData.le(),
+			EndAddressOfRawData: self.EndAddressOfRawData.le(),
+			AddressOfIndex: self.AddressOfIndex.le(),
+			AddressOfCallBacks: self.AddressOfCallBacks.le(),
+			SizeOfZeroFill: self.SizeOfZeroFill.le(),
+			Characteristics: self.Characteristics.le(),
+		}
+	}
+}
+
+macro_rules! impl_le_struct {
+	($($ty:ident { $($field:ident),* })*) => {
+		$(impl Le for $ty {
+			#[inline]
+			fn le(self) -> $ty {
+				$ty { $($field: self.$field.le()),* }
+			}
+		})*
+	};
+}
+impl_le_struct! {
+	IMAGE_DOS_HEADER {
+		e_magic, e_cblp, e_cp, e_crlc, e_cparhdr, e_minalloc, e_maxalloc, e_ss, e_sp, e_csum, e_ip, e_cs,
+		e_lfarlc, e_ovno, e_res, e_oemid, e_oeminfo, e_res2, e_lfanew
+	}
+	IMAGE_FILE_HEADER {
+		Machine, NumberOfSections, TimeDateStamp, PointerToSymbolTable, NumberOfSymbols, SizeOfOptionalHeader, Characteristics
+	}
+	IMAGE_DATA_DIRECTORY {
+		VirtualAddress, Size
+	}
+	IMAGE_OPTIONAL_HEADER32 {
+		Magic, LinkerVersion, SizeOfCode, SizeOfInitializedData, SizeOfUninitializedData, AddressOfEntryPoint, BaseOfCode, BaseOfData,
+		ImageBase, SectionAlignment, FileAlignment, OperatingSystemVersion, ImageVersion, SubsystemVersion, Win32VersionValue,
+		SizeOfImage, SizeOfHeaders, CheckSum, Subsystem, DllCharacteristics, SizeOfStackReserve, SizeOfStackCommit,
+		SizeOfHeapReserve, SizeOfHeapCommit, LoaderFlags, NumberOfRvaAndSizes, DataDirectory
+	}
+	IMAGE_OPTIONAL_HEADER64 {
+		Magic, LinkerVersion, SizeOfCode, SizeOfInitializedData, SizeOfUninitializedData, AddressOfEntryPoint, BaseOfCode,
+		ImageBase, SectionAlignment, FileAlignment, OperatingSystemVersion, ImageVersion, SubsystemVersion, Win32VersionValue,
+		SizeOfImage, SizeOfHeaders, CheckSum, Subsystem, DllCharacteristics, SizeOfStackReserve, SizeOfStackCommit,
+		SizeOfHeapReserve, SizeOfHeapCommit, LoaderFlags, NumberOfRvaAndSizes, DataDirectory
+	}
+	IMAGE_NT_HEADERS32 {
+		Signature, FileHeader, OptionalHeader
+	}
+	IMAGE_NT_HEADERS64 {
+		Signature, FileHeader, OptionalHeader
+	}
+	IMAGE_SECTION_HEADER {
+		Name, VirtualSize, VirtualAddress, SizeOfRawData, PointerToRawData, PointerToRelocations, PointerToLinenumbers,
+		NumberOfRelocations, NumberOfLinenumbers, Characteristics
+	}
+	IMAGE_EXPORT_DIRECTORY {
+		Characteristics, TimeDateStamp, Version, Name, Base, NumberOfFunctions, NumberOfNames,
+		AddressOfFunctions, AddressOfNames, AddressOfNameOrdinals
+	}
+	IMAGE_IMPORT_DESCRIPTOR {
+		OriginalFirstThunk, TimeDateStamp, ForwarderChain, Name, FirstThunk
+	}
+	IMAGE_DELAYLOAD_DESCRIPTOR {
+		Attributes, DllNameRVA, ModuleHandleRVA, ImportAddressTableRVA, ImportNameTableRVA,
+		BoundImportAddressTableRVA, UnloadInformationTableRVA, TimeDateStamp
+	}
+	IMAGE_BOUND_IMPORT_DESCRIPTOR {
+		TimeDateStamp, OffsetModuleName, NumberOfModuleForwarderRefs
+	}
+	IMAGE_BOUND_FORWARDER_REF {
+		TimeDateStamp, OffsetModuleName, Reserved
+	}
+	IMAGE_RESOURCE_DIRECTORY {
+		Characteristics, TimeDateStamp, Version, NumberOfNamedEntries, NumberOfIdEntries
+	}
+	IMAGE_RESOURCE_DATA_ENTRY {
+		OffsetToData, Size, CodePage, Reserved
+	}
+	IMAGE_BASE_RELOCATION {
+		VirtualAddress, SizeOfBlock
+	}
+	RUNTIME_FUNCTION {
+		BeginAddress, EndAddress, UnwindData
+	}
+	IMAGE_ARM_RUNTIME_FUNCTION_ENTRY {
+		BeginAddress, UnwindData
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn le_bytes() {
+		assert_eq!(u32::from_ne_bytes([0x78, 0x56, 0x34, 0x12]).le(), 0x12345678);
+		assert_eq!(u16::from_ne_bytes([0x4d, 0x5a]).le(), IMAGE_DOS_SIGNATURE);
+		assert_eq!([u16::from_ne_bytes([0x01, 0x02]); 2].le(), [0x0201; 2]);
+
+		let data_dir = IMAGE_DATA_DIRECTORY {
+			VirtualAddress: u32::from_ne_bytes([0x00, 0x10, 0x00, 0x00]),
+			Size: u32::from_ne_bytes([0x20, 0x00, 0x00, 0x00]),
+		};
+		let data_dir = data_dir.le();
+		assert_eq!((data_dir.VirtualAddress, data_dir.Size), (0x1000, 0x20));
+	}
+}
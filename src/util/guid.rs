@@ -4,12 +4,13 @@
 use std::fmt;
 
 use crate::image::GUID;
+use crate::util::Le;
 
 #[inline(always)]
 fn group(guid: &GUID) -> (u32, u16, u16, u16, u64) {
-	let g1 = guid.Data1;
-	let g2 = guid.Data2;
-	let g3 = guid.Data3;
+	let g1 = guid.Data1.le();
+	let g2 = guid.Data2.le();
+	let g3 = guid.Data3.le();
 	// Mind the (little-) endianness
 	let g4 = (guid.Data4[0] as u16) << 8 | guid.Data4[1] as u16;
 	let g5 = (guid.Data4[2] as u64) << 8 * 5
@@ -6,7 +6,11 @@ use std::prelude::v1::*;
 
 mod align;
 mod c_str;
+mod digest;
+mod endian;
 mod guid;
+mod unaligned;
+mod w_str;
 #[allow(dead_code)]
 mod wide_str;
 
@@ -14,6 +18,10 @@ mod wide_str;
 pub(crate) mod serde_helper;
 
 pub use self::c_str::CStr;
+pub use self::digest::{Digest, Fnv1a};
+pub use self::endian::Le;
+pub use self::unaligned::{UnalignedIter, UnalignedSlice};
+pub use self::w_str::WStr;
 // pub use self::wide_str::WideStr;
 pub use self::align::*;
 pub(crate) use self::wide_str::FmtUtf16;
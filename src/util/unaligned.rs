@@ -0,0 +1,115 @@
+/*!
+Unaligned arrays.
+*/
+
+use std::{fmt, iter, marker, mem, ptr, slice};
+
+use crate::Pod;
+
+/// Array of pod `T` which may not be aligned.
+///
+/// Packed structures in the image aren't guaranteed to be aligned and references to them cannot be handed out.
+/// The elements are copied out on access instead, regardless of the `unsafe_alignment` feature.
+///
+/// # Examples
+///
+/// ```
+/// use pelite::pe64::{Pe, PeFile, Rva};
+///
+/// # #[allow(dead_code)]
+/// fn sum(file: PeFile<'_>, rva: Rva) -> pelite::Result<u32> {
+/// 	let table = file.derva_slice_unaligned::<u32>(rva, 16)?;
+/// 	Ok(table.iter().sum())
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct UnalignedSlice<'a, T> {
+	bytes: &'a [u8],
+	_marker: marker::PhantomData<&'a [T]>,
+}
+impl<'a, T: Copy + Pod> UnalignedSlice<'a, T> {
+	/// Wraps the bytes, any trailing bytes not making up a whole element are ignored.
+	pub fn new(bytes: &'a [u8]) -> UnalignedSlice<'a, T> {
+		let len = if mem::size_of::<T>() == 0 { 0 } else { bytes.len() / mem::size_of::<T>() * mem::size_of::<T>() };
+		UnalignedSlice { bytes: &bytes[..len], _marker: marker::PhantomData }
+	}
+	/// Returns the number of elements.
+	pub fn len(&self) -> usize {
+		if mem::size_of::<T>() == 0 { 0 } else { self.bytes.len() / mem::size_of::<T>() }
+	}
+	/// Returns true if there are no elements.
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+	/// Copies the element at the index.
+	pub fn get(&self, index: usize) -> Option<T> {
+		if index >= self.len() {
+			return None;
+		}
+		let start = index * mem::size_of::<T>();
+		let bytes = &self.bytes[start..start + mem::size_of::<T>()];
+		// This is safe as per Pod bound and the length of the bytes
+		Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+	}
+	/// Iterates over copies of the elements.
+	pub fn iter(&self) -> UnalignedIter<'a, T> {
+		UnalignedIter { chunks: self.bytes.chunks_exact(chunk_size::<T>()), _marker: marker::PhantomData }
+	}
+	/// Returns the elements as a slice if they happen to be aligned.
+	pub fn aligned(&self) -> Option<&'a [T]> {
+		if self.bytes.as_ptr() as usize % mem::align_of::<T>() == 0 {
+			// This is safe as per Pod bound, the length of the bytes and the alignment check
+			Some(unsafe { slice::from_raw_parts(self.bytes.as_ptr() as *const T, self.len()) })
+		}
+		else {
+			None
+		}
+	}
+	/// Returns the underlying bytes.
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.bytes
+	}
+}
+impl<'a, T: Copy + Pod> IntoIterator for UnalignedSlice<'a, T> {
+	type Item = T;
+	type IntoIter = UnalignedIter<'a, T>;
+	fn into_iter(self) -> UnalignedIter<'a, T> {
+		self.iter()
+	}
+}
+impl<'a, T: Copy + Pod + fmt::Debug> fmt::Debug for UnalignedSlice<'a, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+// The bytes of zero sized elements are empty, chunks_exact panics on a chunk size of zero
+fn chunk_size<T>() -> usize {
+	if mem::size_of::<T>() == 0 { 1 } else { mem::size_of::<T>() }
+}
+
+/// Iterator over the elements of an [`UnalignedSlice`](struct.UnalignedSlice.html).
+#[derive(Clone)]
+pub struct UnalignedIter<'a, T> {
+	chunks: slice::ChunksExact<'a, u8>,
+	_marker: marker::PhantomData<&'a [T]>,
+}
+impl<'a, T: Copy + Pod> Iterator for UnalignedIter<'a, T> {
+	type Item = T;
+	fn next(&mut self) -> Option<T> {
+		let bytes = self.chunks.next()?;
+		// This is safe as per Pod bound and the length of the chunk
+		Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.chunks.size_hint()
+	}
+}
+impl<'a, T: Copy + Pod> DoubleEndedIterator for UnalignedIter<'a, T> {
+	fn next_back(&mut self) -> Option<T> {
+		let bytes = self.chunks.next_back()?;
+		Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+	}
+}
+impl<'a, T: Copy + Pod> ExactSizeIterator for UnalignedIter<'a, T> {}
+impl<'a, T: Copy + Pod> iter::FusedIterator for UnalignedIter<'a, T> {}
@@ -0,0 +1,173 @@
+/*!
+Nul-terminated wide string.
+*/
+
+use std::prelude::v1::*;
+use std::{char, fmt, mem, ops, slice};
+
+use crate::util::{FmtUtf16, FromBytes};
+
+//----------------------------------------------------------------
+
+/// Nul-terminated UTF-16LE wide string.
+///
+/// The wide counterpart of [`CStr`](struct.CStr.html), read it from an image with `derva_string::<WStr>`.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WStr {
+	words: [u16],
+}
+
+impl WStr {
+	/// Returns the empty nul-terminated wide string.
+	pub fn empty() -> &'static WStr {
+		unsafe { WStr::from_words_unchecked(&[0]) }
+	}
+	/// Scans the word slice for a nul-terminated wide string.
+	///
+	/// Returns `None` if no nul word was found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use pelite::util::WStr;
+	///
+	/// let words = [b'H' as u16, b'i' as u16, 0, b'!' as u16];
+	/// let w_str = WStr::from_words(&words).unwrap();
+	/// assert_eq!(w_str.to_string_lossy(), "Hi");
+	/// assert_eq!(w_str.w_str(), &words[..3]);
+	/// assert_eq!(w_str.len(), 2);
+	///
+	/// let no_nul = WStr::from_words(&words[..2]);
+	/// assert_eq!(no_nul, None);
+	/// ```
+	pub fn from_words(words: &[u16]) -> Option<&WStr> {
+		let len = words.iter().position(|&word| word == 0)?;
+		Some(unsafe { WStr::from_words_unchecked(words.get_unchecked(..len + 1)) })
+	}
+	/// Interprets a word slice as a wide string.
+	///
+	/// # Safety
+	///
+	/// Ensure that the word slice ends with the only nul word.
+	pub unsafe fn from_words_unchecked(words: &[u16]) -> &WStr {
+		mem::transmute(words)
+	}
+	/// Gets the wide string as a nul terminated word slice.
+	pub fn w_str(&self) -> &[u16] {
+		&self.words
+	}
+	/// Returns an iterator over the decoded chars, invalid surrogates are replaced with `U+FFFD`.
+	pub fn chars(&self) -> impl '_ + Clone + Iterator<Item = char> {
+		char::decode_utf16(self.as_ref().iter().cloned()).map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+	}
+	/// Decodes the wide string as an UTF8 validated `String`.
+	pub fn to_utf8(&self) -> Result<String, char::DecodeUtf16Error> {
+		char::decode_utf16(self.as_ref().iter().cloned()).collect()
+	}
+	/// Decodes the wide string replacing invalid surrogates with `U+FFFD`.
+	pub fn to_string_lossy(&self) -> String {
+		self.chars().collect()
+	}
+}
+
+impl FromBytes for WStr {
+	const MIN_SIZE_OF: usize = 2;
+	const ALIGN_OF: usize = 2;
+	unsafe fn from_bytes(bytes: &[u8]) -> Option<&WStr> {
+		let words = slice::from_raw_parts(bytes.as_ptr() as *const u16, bytes.len() / 2);
+		WStr::from_words(words)
+	}
+}
+
+//----------------------------------------------------------------
+
+impl PartialEq<str> for WStr {
+	fn eq(&self, rhs: &str) -> bool {
+		let decoder = char::decode_utf16(self.as_ref().iter().cloned());
+		let chars = rhs.chars().map(Ok);
+		decoder.eq(chars)
+	}
+}
+impl<'a> PartialEq<&'a str> for WStr {
+	fn eq(&self, rhs: &&'a str) -> bool {
+		*self == **rhs
+	}
+}
+
+//----------------------------------------------------------------
+
+impl ops::Deref for WStr {
+	type Target = [u16];
+	fn deref(&self) -> &[u16] {
+		self.as_ref()
+	}
+}
+impl AsRef<[u16]> for WStr {
+	fn as_ref(&self) -> &[u16] {
+		// Strip the nul word
+		let len = self.words.len() - 1;
+		unsafe { self.words.get_unchecked(..len) }
+	}
+}
+
+//----------------------------------------------------------------
+// Formatting
+
+impl fmt::Display for WStr {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&FmtUtf16(self.as_ref()), f)
+	}
+}
+impl fmt::Debug for WStr {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&FmtUtf16(self.as_ref()), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WStr {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use std::{char, slice};
+
+	use crate::util::FromBytes;
+
+	use super::WStr;
+
+	static W_STR: [u16; 8] = [83, 84, 82, 73, 78, 71, 0, 88];
+	static INVALID_STR: [u16; 4] = [b'a' as u16, 0xd800, b'b' as u16, 0];
+
+	#[test]
+	fn units() {
+		let w_str = WStr::from_words(&W_STR).unwrap();
+		assert_eq!(w_str.to_utf8(), Ok(String::from("STRING")));
+		assert_eq!(w_str.len(), 6);
+		assert_eq!(w_str.w_str(), &W_STR[..7]);
+		assert!(w_str == "STRING");
+		assert_eq!(WStr::empty().len(), 0);
+	}
+
+	#[test]
+	fn from_bytes() {
+		let bytes = unsafe { slice::from_raw_parts(W_STR.as_ptr() as *const u8, W_STR.len() * 2) };
+		let w_str = unsafe { WStr::from_bytes(bytes).unwrap() };
+		assert_eq!(w_str, WStr::from_words(&W_STR).unwrap());
+		assert_eq!(unsafe { WStr::from_bytes(&bytes[..12]) }, None);
+	}
+
+	#[test]
+	fn lossy() {
+		let invalid_str = WStr::from_words(&INVALID_STR).unwrap();
+		assert!(invalid_str.to_utf8().is_err());
+		assert_eq!(invalid_str.to_string_lossy(), format!("a{}b", char::REPLACEMENT_CHARACTER));
+		assert_eq!(invalid_str.chars().count(), 3);
+		assert_eq!(format!("{:?}", invalid_str), "L\"a\\ud800b\"");
+	}
+}
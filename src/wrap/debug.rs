@@ -1,7 +1,9 @@
+use std::prelude::v1::{format, String};
+use std::borrow::Cow;
 use std::{fmt, str};
 
 use crate::image::*;
-use crate::util::CStr;
+use crate::util::{CStr, Le};
 use crate::*;
 
 use super::Wrap;
@@ -148,8 +150,8 @@ impl<'a> CodeView<'a> {
 	}
 	pub fn age(&self) -> u32 {
 		match self {
-			CodeView::Cv20 { image, .. } => image.Age,
-			CodeView::Cv70 { image, .. } => image.Age,
+			CodeView::Cv20 { image, .. } => image.Age.le(),
+			CodeView::Cv70 { image, .. } => image.Age.le(),
 		}
 	}
 	pub fn pdb_file_name(&self) -> &'a CStr {
@@ -158,6 +160,53 @@ impl<'a> CodeView<'a> {
 			CodeView::Cv70 { pdb_file_name, .. } => pdb_file_name,
 		}
 	}
+	/// Gets the file name of the PDB without its directories.
+	///
+	/// The path is written by the linker on the build machine and may contain either path separator or a drive prefix,
+	/// stripping everything up to the last `\\`, `/` or `:` keeps the name safe to use as a path component.
+	pub fn pdb_name(&self) -> Cow<'a, str> {
+		let path: &'a [u8] = self.pdb_file_name().as_ref();
+		let name = match path.iter().rposition(|&byte| byte == b'\\' || byte == b'/' || byte == b':') {
+			Some(pos) => &path[pos + 1..],
+			None => path,
+		};
+		let name = if name == b"." || name == b".." { &[][..] } else { name };
+		String::from_utf8_lossy(name)
+	}
+	/// Gets the symbol server key identifying this build of the PDB.
+	///
+	/// For CodeView 7.0 this is the signature GUID followed by the age, for CodeView 2.0 the timestamp followed by the age.
+	pub fn symbol_server_key(&self) -> String {
+		match self {
+			CodeView::Cv20 { image, .. } => format!("{:08X}{:X}", image.TimeDateStamp.le(), image.Age.le()),
+			CodeView::Cv70 { image, .. } => {
+				let guid = &image.Signature;
+				let mut key = format!("{:08X}{:04X}{:04X}", guid.Data1.le(), guid.Data2.le(), guid.Data3.le());
+				for byte in &guid.Data4 {
+					key.push_str(&format!("{:02X}", byte));
+				}
+				key.push_str(&format!("{:X}", image.Age.le()));
+				key
+			},
+		}
+	}
+	/// Gets the path of the PDB on a symbol server, `name/KEY/name`.
+	///
+	/// Returns `None` if the PDB has no file name.
+	pub fn symbol_server_path(&self) -> Option<String> {
+		let name = self.pdb_name();
+		if name.is_empty() {
+			return None;
+		}
+		Some(format!("{}/{}/{}", name, self.symbol_server_key(), name))
+	}
+	/// Gets the url of the PDB on the symbol server, eg. `https://msdl.microsoft.com/download/symbols`.
+	///
+	/// Returns `None` if the PDB has no file name.
+	pub fn symbol_server_url(&self, server: &str) -> Option<String> {
+		let path = self.symbol_server_path()?;
+		Some(format!("{}/{}", server.trim_end_matches('/'), path))
+	}
 }
 impl<'a> fmt::Debug for CodeView<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -166,12 +215,12 @@ impl<'a> fmt::Debug for CodeView<'a> {
 		stru.field("pdb_file_name", &self.pdb_file_name());
 		match self {
 			CodeView::Cv20 { image, .. } => {
-				stru.field("time_date_stamp", &image.TimeDateStamp);
-				stru.field("age", &image.Age);
+				stru.field("time_date_stamp", &image.TimeDateStamp.le());
+				stru.field("age", &image.Age.le());
 			},
 			CodeView::Cv70 { image, .. } => {
 				stru.field("signature", &image.Signature);
-				stru.field("age", &image.Age);
+				stru.field("age", &image.Age.le());
 			},
 		}
 		stru.finish()
@@ -262,6 +311,7 @@ pub struct PgoItem<'a> {
 #[cfg(feature = "serde")]
 mod serde2 {
 	use crate::util::serde_helper::*;
+	use crate::util::Le;
 
 	use super::{CodeView, Dbg, Pgo};
 
@@ -272,12 +322,12 @@ mod serde2 {
 			state.serialize_field("pdb_file_name", &self.pdb_file_name())?;
 			match self {
 				CodeView::Cv20 { image, .. } => {
-					state.serialize_field("time_date_stamp", &image.TimeDateStamp)?;
-					state.serialize_field("age", &image.Age)?;
+					state.serialize_field("time_date_stamp", &image.TimeDateStamp.le())?;
+					state.serialize_field("age", &image.Age.le())?;
 				},
 				CodeView::Cv70 { image, .. } => {
 					state.serialize_field("signature", &image.Signature)?;
-					state.serialize_field("age", &image.Age)?;
+					state.serialize_field("age", &image.Age.le())?;
 				},
 			}
 			state.end()
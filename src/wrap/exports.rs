@@ -1,4 +1,5 @@
 use crate::*;
+use crate::util::Le;
 
 use super::imports::Import;
 use super::Wrap;
@@ -17,10 +18,12 @@ pub enum Export<'a> {
 }
 impl<'a> Export<'a> {
 	/// Returns some if the symbol is exported.
+	///
+	/// The rva is converted to native byte order.
 	#[inline]
 	pub fn symbol(self) -> Option<u32> {
 		match self {
-			Export::Symbol(&rva) => Some(rva),
+			Export::Symbol(&rva) => Some(rva.le()),
 			_ => None,
 		}
 	}
@@ -260,7 +263,7 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<pe32::exports::By<'a, Pe32
 	/// Iterate over functions exported by name, returning their name and index in the functions table.
 	#[inline]
 	pub fn iter_name_indices<'s>(&'s self) -> impl 's + Clone + Iterator<Item = (Result<&'a util::CStr>, usize)> {
-		(0..self.names().len() as u32).map(move |hint| (self.name_of_hint(hint as usize), self.name_indices()[hint as usize] as usize))
+		(0..self.names().len() as u32).map(move |hint| (self.name_of_hint(hint as usize), self.name_indices()[hint as usize].le() as usize))
 	}
 }
 
@@ -14,4 +14,23 @@ impl<'a> PeFile<'a> {
 			Err(err) => Err(err),
 		}
 	}
+	/// Constructs a PeFile from byte slice with custom parse options.
+	pub fn from_bytes_with_options<T: AsRef<[u8]> + ?Sized>(image: &'a T, options: ParseOptions) -> Result<PeFile<'a>> {
+		match pe64::PeFile::from_bytes_with_options(image, options) {
+			Ok(file) => Ok(Wrap::T64(file)),
+			Err(Error::PeMagic) => Ok(Wrap::T32(pe32::PeFile::from_bytes_with_options(image, options)?)),
+			Err(err) => Err(err),
+		}
+	}
+	/// Constructs a PeFile from byte slice, tolerating header violations the Windows loader accepts.
+	pub fn from_bytes_lax<T: AsRef<[u8]> + ?Sized>(image: &'a T) -> Result<PeFile<'a>> {
+		PeFile::from_bytes_with_options(image, ParseOptions::LENIENT)
+	}
+	/// Returns the header violations tolerated by the lenient constructor.
+	pub fn violations(&self) -> Violations {
+		match self {
+			Wrap::T32(file) => file.violations(),
+			Wrap::T64(file) => file.violations(),
+		}
+	}
 }
@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+use crate::stringify::{DllChars, FileChars, Machine, Subsystem};
+use crate::timestamp::TimeDateStamp;
 use crate::*;
 
 use super::Wrap;
@@ -30,6 +32,46 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<pe32::headers::Headers<Pe3
 			Wrap::T64(headers) => headers.check_sum(),
 		}
 	}
+	/// Gets the typed machine from the file header.
+	#[inline]
+	pub fn machine(&self) -> Machine {
+		match self {
+			Wrap::T32(headers) => headers.machine(),
+			Wrap::T64(headers) => headers.machine(),
+		}
+	}
+	/// Gets the typed characteristics from the file header.
+	#[inline]
+	pub fn file_characteristics(&self) -> FileChars {
+		match self {
+			Wrap::T32(headers) => headers.file_characteristics(),
+			Wrap::T64(headers) => headers.file_characteristics(),
+		}
+	}
+	/// Decodes the file header's TimeDateStamp.
+	#[inline]
+	pub fn time_date_stamp(&self) -> TimeDateStamp {
+		match self {
+			Wrap::T32(headers) => headers.time_date_stamp(),
+			Wrap::T64(headers) => headers.time_date_stamp(),
+		}
+	}
+	/// Gets the typed subsystem from the optional header.
+	#[inline]
+	pub fn subsystem(&self) -> Subsystem {
+		match self {
+			Wrap::T32(headers) => headers.subsystem(),
+			Wrap::T64(headers) => headers.subsystem(),
+		}
+	}
+	/// Gets the typed dll characteristics from the optional header.
+	#[inline]
+	pub fn dll_characteristics(&self) -> DllChars {
+		match self {
+			Wrap::T32(headers) => headers.dll_characteristics(),
+			Wrap::T64(headers) => headers.dll_characteristics(),
+		}
+	}
 	/// Gets the code range from the optional header.
 	#[inline]
 	pub fn code_range(&self) -> Range<u32> {
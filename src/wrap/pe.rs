@@ -87,6 +87,20 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn optional_header_window(&self) -> &'a [u8] {
+		match self {
+			Wrap::T32(pe32) => pe32.optional_header_window(),
+			Wrap::T64(pe64) => pe64.optional_header_window(),
+		}
+	}
+	#[inline]
+	pub fn declared_data_directory(&self) -> &'a [image::IMAGE_DATA_DIRECTORY] {
+		match self {
+			Wrap::T32(pe32) => pe32.declared_data_directory(),
+			Wrap::T64(pe64) => pe64.declared_data_directory(),
+		}
+	}
+	#[inline]
 	pub fn data_directory(&self) -> &'a [image::IMAGE_DATA_DIRECTORY] {
 		match self {
 			Wrap::T32(pe32) => pe32.data_directory(),
@@ -153,6 +167,13 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn derva_slice_unaligned<T: Copy + Pod>(&self, rva: u32, len: usize) -> Result<util::UnalignedSlice<'a, T>> {
+		match self {
+			Wrap::T32(pe32) => pe32.derva_slice_unaligned(rva, len),
+			Wrap::T64(pe64) => pe64.derva_slice_unaligned(rva, len),
+		}
+	}
+	#[inline]
 	pub fn derva_slice_f<T: Pod, F: FnMut(&T) -> bool>(&self, rva: u32, f: F) -> Result<&'a [T]> {
 		match self {
 			Wrap::T32(pe32) => pe32.derva_slice_f(rva, f),
@@ -174,6 +195,13 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn derva_c_str_bounded(&self, rva: u32, max_len: usize) -> Result<&'a util::CStr> {
+		match self {
+			Wrap::T32(pe32) => pe32.derva_c_str_bounded(rva, max_len),
+			Wrap::T64(pe64) => pe64.derva_c_str_bounded(rva, max_len),
+		}
+	}
+	#[inline]
 	pub fn derva_string<T: ?Sized + util::FromBytes>(&self, rva: u32) -> Result<&'a T> {
 		match self {
 			Wrap::T32(pe32) => pe32.derva_string(rva),
@@ -205,6 +233,13 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn try_exports(&self) -> Result<Wrap<pe32::exports::Exports<'a, Pe32>, pe64::exports::Exports<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.try_exports().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.try_exports().map(Wrap::T64),
+		}
+	}
+	#[inline]
 	pub fn imports(&self) -> Result<Wrap<pe32::imports::Imports<'a, Pe32>, pe64::imports::Imports<'a, Pe64>>> {
 		match self {
 			Wrap::T32(pe32) => pe32.imports().map(Wrap::T32),
@@ -212,6 +247,13 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn try_imports(&self) -> Result<Wrap<pe32::imports::Imports<'a, Pe32>, pe64::imports::Imports<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.try_imports().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.try_imports().map(Wrap::T64),
+		}
+	}
+	#[inline]
 	pub fn iat(&self) -> Result<Wrap<pe32::imports::IAT<'a, Pe32>, pe64::imports::IAT<'a, Pe64>>> {
 		match self {
 			Wrap::T32(pe32) => pe32.iat().map(Wrap::T32),
@@ -219,6 +261,27 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn bound_imports(&self) -> Result<Wrap<pe32::bound_imports::BoundImports<'a, Pe32>, pe64::bound_imports::BoundImports<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.bound_imports().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.bound_imports().map(Wrap::T64),
+		}
+	}
+	#[inline]
+	pub fn delay_imports(&self) -> Result<Wrap<pe32::delay_imports::DelayImports<'a, Pe32>, pe64::delay_imports::DelayImports<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.delay_imports().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.delay_imports().map(Wrap::T64),
+		}
+	}
+	#[inline]
+	pub fn try_delay_imports(&self) -> Result<Wrap<pe32::delay_imports::DelayImports<'a, Pe32>, pe64::delay_imports::DelayImports<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.try_delay_imports().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.try_delay_imports().map(Wrap::T64),
+		}
+	}
+	#[inline]
 	pub fn base_relocs(&self) -> Result<crate::base_relocs::BaseRelocs<'a>> {
 		match self {
 			Wrap::T32(pe32) => pe32.base_relocs(),
@@ -254,6 +317,20 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<Pe32, Pe64> {
 		}
 	}
 	#[inline]
+	pub fn try_exception(&self) -> Result<Wrap<pe32::exception::Exception<'a, Pe32>, pe64::exception::Exception<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.try_exception().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.try_exception().map(Wrap::T64),
+		}
+	}
+	#[inline]
+	pub fn arm_exception(&self) -> Result<Wrap<pe32::exception::ArmException<'a, Pe32>, pe64::exception::ArmException<'a, Pe64>>> {
+		match self {
+			Wrap::T32(pe32) => pe32.arm_exception().map(Wrap::T32),
+			Wrap::T64(pe64) => pe64.arm_exception().map(Wrap::T64),
+		}
+	}
+	#[inline]
 	pub fn debug(&self) -> Result<Wrap<pe32::debug::Debug<'a, Pe32>, pe64::debug::Debug<'a, Pe64>>> {
 		match self {
 			Wrap::T32(pe32) => pe32.debug().map(Wrap::T32),
@@ -1,6 +1,7 @@
 use std::{fmt, mem, ops, slice};
 
 use crate::image::*;
+use crate::util::Le;
 use crate::Pod;
 
 //----------------------------------------------------------------
@@ -23,17 +24,22 @@ impl SectionHeader {
 	/// Returns the virtual range.
 	#[inline]
 	pub fn virtual_range(&self) -> std::ops::Range<u32> {
-		let start = self.0.VirtualAddress;
-		let end = u32::wrapping_add(self.0.VirtualAddress, self.0.VirtualSize);
+		let start = self.0.VirtualAddress.le();
+		let end = u32::wrapping_add(start, self.0.VirtualSize.le());
 		start..end
 	}
 	/// Returns the file range.
 	#[inline]
 	pub fn file_range(&self) -> std::ops::Range<u32> {
-		let start = self.0.PointerToRawData;
-		let end = u32::wrapping_add(self.0.PointerToRawData, self.0.SizeOfRawData);
+		let start = self.0.PointerToRawData.le();
+		let end = u32::wrapping_add(start, self.0.SizeOfRawData.le());
 		start..end
 	}
+	/// Returns the typed characteristics.
+	#[inline]
+	pub fn characteristics(&self) -> crate::stringify::SectionChars {
+		crate::stringify::SectionChars(self.0.Characteristics.le())
+	}
 }
 
 unsafe impl Pod for SectionHeader {}
@@ -56,11 +62,11 @@ impl fmt::Debug for SectionHeader {
 		};
 		f.debug_struct("SectionHeader")
 			.field("Name", name)
-			.field("VirtualAddress", &format_args!("{:#x}", self.0.VirtualAddress))
-			.field("VirtualSize", &format_args!("{:#x}", self.0.VirtualSize))
-			.field("PointerToRawData", &format_args!("{:#x}", self.0.PointerToRawData))
-			.field("SizeOfRawData", &format_args!("{:#x}", self.0.SizeOfRawData))
-			.field("Characteristics", &format_args!("{:#x}", self.0.Characteristics))
+			.field("VirtualAddress", &format_args!("{:#x}", self.0.VirtualAddress.le()))
+			.field("VirtualSize", &format_args!("{:#x}", self.0.VirtualSize.le()))
+			.field("PointerToRawData", &format_args!("{:#x}", self.0.PointerToRawData.le()))
+			.field("SizeOfRawData", &format_args!("{:#x}", self.0.SizeOfRawData.le()))
+			.field("Characteristics", &format_args!("{:#x}", self.0.Characteristics.le()))
 			.finish()
 	}
 }
@@ -115,7 +121,7 @@ impl SectionHeaders {
 	pub fn by_rva(&self, rva: u32) -> Option<&SectionHeader> {
 		for sect in self.iter() {
 			// FIXME! Should this round up the VirtualSize to the next virtual section alignment?
-			if rva >= sect.VirtualAddress && rva < u32::wrapping_add(sect.VirtualAddress, sect.VirtualSize) {
+			if sect.virtual_range().contains(&rva) {
 				return Some(sect);
 			}
 		}
@@ -36,9 +36,9 @@ impl<'a, Pe32: pe32::Pe<'a>, Pe64: pe64::Pe<'a>> Wrap<pe32::tls::Tls<'a, Pe32>,
 			Wrap::T64(tls) => tls.slot(),
 		}
 	}
-	/// Gets the TLS initialization callbacks.
+	/// Iterator over the TLS initialization callbacks.
 	#[inline]
-	pub fn callbacks(&self) -> Result<Wrap<&'a [u32], &'a [u64]>> {
+	pub fn callbacks(&self) -> Result<Wrap<pe32::tls::Callbacks<'a, Pe32>, pe64::tls::Callbacks<'a, Pe64>>> {
 		match self {
 			Wrap::T32(tls) => Wrap::T32(tls.callbacks()).transpose(),
 			Wrap::T64(tls) => Wrap::T64(tls.callbacks()).transpose(),
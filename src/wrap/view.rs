@@ -13,4 +13,11 @@ impl<'a> PeView<'a> {
 			Err(err) => Err(err),
 		}
 	}
+	pub fn from_bytes_with_options<T: AsRef<[u8]> + ?Sized>(image: &'a T, options: ParseOptions) -> Result<PeView<'a>> {
+		match pe64::PeView::from_bytes_with_options(image, options) {
+			Ok(file) => Ok(Wrap::T64(file)),
+			Err(Error::PeMagic) => Ok(Wrap::T32(pe32::PeView::from_bytes_with_options(image, options)?)),
+			Err(err) => Err(err),
+		}
+	}
 }
@@ -1,8 +1,8 @@
 use pelite::pe64::exports::{Export, GetProcAddress};
 use pelite::pe64::imports::Import;
-use pelite::pe64::{Pe, PeFile, Rva};
+use pelite::pe64::{Pe, PeFile, PeObject, Rva};
 use pelite::util::CStr;
-use pelite::{Error, FileMap};
+use pelite::{Error, FileMap, ParseOptions, Violation, Violations};
 
 const FILE_NAME: &str = "demo/Demo64.dll";
 
@@ -34,6 +34,1855 @@ fn slice_edges() {
 
 //----------------------------------------------------------------
 
+#[test]
+fn typed_headers() {
+	use pelite::image::*;
+	use pelite::stringify::{Machine, SectionChars};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let headers = file.headers();
+
+	assert_eq!(headers.machine(), Machine(IMAGE_FILE_MACHINE_AMD64));
+	assert_eq!(headers.machine().to_string(), "AMD64");
+	assert!(headers.file_characteristics().contains(IMAGE_FILE_DLL | IMAGE_FILE_EXECUTABLE_IMAGE));
+
+	let text = file.section_headers().by_name(".text").unwrap();
+	assert!(text.characteristics().contains(IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE));
+
+	let summary = headers.summary();
+	assert_eq!(summary.machine(), Machine(IMAGE_FILE_MACHINE_AMD64));
+	assert_eq!(summary.bitness, 64);
+	assert_eq!(summary.image_base, file.optional_header().ImageBase);
+	assert_eq!(summary.entry_point, file.optional_header().AddressOfEntryPoint);
+	assert_eq!(summary.number_of_sections as usize, file.section_headers().image().len());
+	assert!(summary.has_directory(IMAGE_DIRECTORY_ENTRY_EXPORT) && summary.has_directory(IMAGE_DIRECTORY_ENTRY_IMPORT));
+	assert!(!summary.has_directory(IMAGE_DIRECTORY_ENTRY_SECURITY));
+
+	// Unknown values are preserved
+	assert_eq!(Machine::from_raw(0x1234).to_raw(), 0x1234);
+	assert_eq!(Machine::from_raw(0x1234).to_string(), "0x1234");
+	assert_eq!(format!("{:?}", Machine::from_raw(IMAGE_FILE_MACHINE_I386)), "Machine(IMAGE_FILE_MACHINE_I386)");
+	assert_eq!(Machine::from_raw(IMAGE_FILE_MACHINE_ARM64EC).to_string(), "ARM64EC");
+	assert_eq!(SectionChars::from_raw(IMAGE_SCN_MEM_READ | IMAGE_SCN_MEM_WRITE).to_string(), "IMAGE_SCN_MEM_READ | IMAGE_SCN_MEM_WRITE");
+	assert_eq!(SectionChars::from_raw(0).to_string(), "0x0");
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn dyn_pe() {
+	use pelite::pe64::PeObject;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let images: Vec<Box<dyn PeObject + Send + Sync>> = vec![Box::new(file)];
+	for image in &images {
+		let pe: &(dyn PeObject + Send + Sync) = &**image;
+		assert_eq!(pe.exports().unwrap().dll_name().unwrap(), "Demo.dll");
+		assert_eq!(pe.derva_c_str(pe.exports().unwrap().image().Name).unwrap(), "Demo.dll");
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn owned_buf() {
+	use pelite::pe64::{PeFileBuf, PeObject};
+
+	fn load() -> PeFileBuf {
+		let bytes = std::fs::read(FILE_NAME).unwrap();
+		PeFileBuf::from_vec(bytes).unwrap()
+	}
+
+	let buf = load();
+	assert_eq!((&buf).exports().unwrap().dll_name().unwrap(), "Demo.dll");
+	assert_eq!(buf.as_file().image().len(), (&buf).image().len());
+
+	assert_eq!(PeFileBuf::from_vec(vec![0; 16]).err(), Some(Error::Bounds));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn section_index() {
+	use pelite::pe64::index::{Indexed, SectionIndex};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let index = SectionIndex::new(file);
+	let indexed = Indexed::new(file, &index);
+
+	for rva in (0..0x10000).step_by(0x80) {
+		assert_eq!(indexed.rva_to_file_offset(rva), file.rva_to_file_offset(rva));
+		assert_eq!(indexed.slice(rva, 0x10, 1), file.slice(rva, 0x10, 1));
+		let va = file.optional_header().ImageBase + rva as u64;
+		assert_eq!(indexed.read(va, 4, 4), file.read(va, 4, 4));
+	}
+	for file_offset in (0..file_map.as_ref().len() + 0x1000).step_by(0x80) {
+		assert_eq!(indexed.file_offset_to_rva(file_offset), file.file_offset_to_rva(file_offset));
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn parse_options() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let options = ParseOptions::default();
+	let file = PeFile::from_bytes_with_options(&file_map, options).unwrap();
+	assert_eq!(file.options(), ParseOptions::DEFAULT);
+	assert!(file.imports().is_ok());
+
+	let options = ParseOptions { max_sections: 1, ..ParseOptions::DEFAULT };
+	assert_eq!(PeFile::from_bytes_with_options(&file_map, options).err(), Some(Error::Insanity));
+
+	let options = ParseOptions { max_string_len: 2, max_import_descriptors: 1, ..ParseOptions::DEFAULT };
+	let file = PeFile::from_bytes_with_options(&file_map, options).unwrap();
+	assert_eq!(file.imports().err(), Some(Error::Insanity));
+	let exports = file.exports().unwrap();
+	assert_eq!(exports.dll_name().err(), Some(Error::Insanity));
+
+	let resources = file.resources().unwrap();
+	assert!(resources.fsck().is_ok());
+	assert_eq!(resources.set_max_depth(1).fsck(), Err(Error::Insanity));
+}
+
+#[test]
+fn c_str_bounded() {
+	use pelite::pe64::Ptr;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let dll_name = file.imports().unwrap().image()[0].Name;
+	assert_eq!(file.derva_c_str_bounded(dll_name, 12).unwrap(), "KERNEL32.dll");
+	assert_eq!(file.derva_c_str_bounded(dll_name, 0x1000).unwrap(), "KERNEL32.dll");
+	assert_eq!(file.derva_c_str_bounded(dll_name, 11), Err(Error::Insanity));
+	let ptr = Ptr::from(file.optional_header().ImageBase + dll_name as u64);
+	assert_eq!(file.deref_c_str_bounded(ptr, 12).unwrap(), "KERNEL32.dll");
+	assert_eq!(file.deref_c_str_bounded(ptr, 4), Err(Error::Insanity));
+
+	// The parse options still apply
+	let options = ParseOptions { max_string_len: 4, ..ParseOptions::DEFAULT };
+	let file = PeFile::from_bytes_with_options(&file_map, options).unwrap();
+	assert_eq!(file.derva_c_str_bounded(dll_name, 12), Err(Error::Insanity));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn lenient() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes_lax(&file_map).unwrap();
+	assert!(file.options().lenient);
+	assert_eq!(file.violations(), Violations::empty());
+
+	// Pretend the file has more sections than the strict constructor allows
+	let options = ParseOptions { max_sections: 1, ..ParseOptions::LENIENT };
+	let file = PeFile::from_bytes_with_options(&file_map, options).unwrap();
+	assert!(file.violations().contains(Violation::TooManySections));
+	assert_eq!(file.violations().iter().collect::<Vec<_>>(), [Violation::TooManySections]);
+
+	// Headers exceeding the image are tolerated
+	let mut bytes = file_map.as_ref().to_vec();
+	let headers_offset = file.dos_header().e_lfanew as usize + 0x54;
+	bytes[headers_offset..headers_offset + 4].copy_from_slice(&0x7fff_0000u32.to_le_bytes());
+	assert_eq!(PeFile::from_bytes(&bytes).err(), Some(Error::Bounds));
+	let file = PeFile::from_bytes_lax(&bytes).unwrap();
+	assert!(file.violations().contains(Violation::HeadersBounds));
+	assert!(file.violations().contains(Violation::HeadersExceedImage));
+	assert_eq!(file.headers().image().len(), bytes.len());
+	assert!(file.exports().is_ok());
+
+	// Only PE32 headers can be accessed in place when they're 4 byte aligned
+	let buf = misalign_nt_headers(&std::fs::read("demo/Demo.dll").unwrap());
+	let bytes = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len() * 8) };
+	let file = pelite::pe32::PeFile::from_bytes_lax(bytes).unwrap();
+	assert_eq!(file.violations().iter().collect::<Vec<_>>(), [Violation::LfanewAlignment]);
+	assert!(pelite::pe32::PeFile::from_bytes(bytes).is_ok());
+	if !cfg!(feature = "unsafe_alignment") {
+		let buf = misalign_nt_headers(file_map.as_ref());
+		let bytes = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len() * 8) };
+		assert_eq!(PeFile::from_bytes_lax(bytes).err(), Some(Error::Misaligned));
+	}
+}
+
+// Moves the NT and section headers up by 4 bytes into an 8 byte aligned buffer
+fn misalign_nt_headers(bytes: &[u8]) -> Vec<u64> {
+	let e_lfanew = u32::from_le_bytes([bytes[0x3c], bytes[0x3d], bytes[0x3e], bytes[0x3f]]) as usize;
+	let number_of_sections = u16::from_le_bytes([bytes[e_lfanew + 6], bytes[e_lfanew + 7]]) as usize;
+	let size_of_optional_header = u16::from_le_bytes([bytes[e_lfanew + 20], bytes[e_lfanew + 21]]) as usize;
+	let end = e_lfanew + 24 + size_of_optional_header + number_of_sections * 40;
+	let mut buf = vec![0u64; (bytes.len() + 7) / 8];
+	let dest = unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, bytes.len()) };
+	dest.copy_from_slice(bytes);
+	dest.copy_within(e_lfanew..end, e_lfanew + 4);
+	dest[0x3c..0x40].copy_from_slice(&(e_lfanew as u32 + 4).to_le_bytes());
+	buf
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn validate() {
+	use pelite::pe64::validate::{Finding, Severity};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let report = file.validate();
+	assert!(!report.has_errors(), "{}", report);
+
+	// Corrupt the first import descriptor's dll name
+	let mut bytes = file_map.as_ref().to_vec();
+	let desc = &file.imports().unwrap().image()[0];
+	let offset = file.rva_to_file_offset(desc.Name).unwrap();
+	bytes[offset] = 0;
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	let report = file.validate();
+	assert!(report.has_errors());
+	assert!(report.iter().any(|finding| *finding == Finding::ImportDllName { desc: 0, error: Error::Encoding }));
+	assert_eq!(report.max_severity(), Some(Severity::Error));
+	assert!(report.to_string().contains("error: import descriptor #0 dll name"));
+
+	// Move the second and third sections on top of the first, each overlapping section is reported once
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let mut bytes = file_map.as_ref().to_vec();
+	let sections = file.section_headers().image();
+	let offset = sections.as_ptr() as usize - file_map.as_ref().as_ptr() as usize;
+	for section in 1..3 {
+		let offset = offset + section * 40 + 12;
+		bytes[offset..offset + 4].copy_from_slice(&sections[0].VirtualAddress.to_le_bytes());
+	}
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	let overlaps: Vec<_> = file.validate().iter().filter(|finding| matches!(finding, Finding::SectionOverlap { .. })).cloned().collect();
+	assert_eq!(overlaps.len(), 2);
+	assert_eq!(overlaps[0], Finding::SectionOverlap { first: 0, second: 1 });
+}
+
+#[test]
+fn validate_exports() {
+	use pelite::pe64::validate::{Finding, Severity};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let exports = file.exports().unwrap();
+	assert!(!file.validate().iter().any(|finding| format!("{:?}", finding).starts_with("Export")));
+
+	// Duplicate the first name, garble the third name and clear the fourth function
+	let mut image = file_map.as_ref().to_vec();
+	let names = file.rva_to_file_offset(exports.image().AddressOfNames).unwrap();
+	image.copy_within(names..names + 4, names + 4);
+	let name = file.rva_to_file_offset(exports.by().unwrap().names()[2]).unwrap();
+	image[name] = 0xc3;
+	let functions = file.rva_to_file_offset(exports.image().AddressOfFunctions).unwrap();
+	image[functions + 12..functions + 16].copy_from_slice(&[0; 4]);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let report = file.validate();
+	assert!(report.iter().any(|finding| *finding == Finding::ExportNameDuplicate { index: 1, first: 0 }));
+	assert!(report.iter().any(|finding| *finding == Finding::ExportNameNonAscii { index: 2 }));
+	assert!(report.iter().any(|finding| *finding == Finding::ExportNamesUnsorted));
+	assert!(report.iter().any(|finding| *finding == Finding::ExportOrdinalGap { ordinal: 4, count: 1 }));
+	assert_eq!(Finding::ExportOrdinalGap { ordinal: 4, count: 1 }.severity(), Severity::Info);
+}
+
+#[test]
+fn validate_imports() {
+	use pelite::pe64::imports::Import;
+	use pelite::pe64::validate::{Finding, Severity};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert!(!file.validate().iter().any(|finding| format!("{:?}", finding).starts_with("Import")));
+
+	let imports = file.imports().unwrap();
+	let descs = imports.image();
+	let desc_offset = |desc: usize| file.rva_to_file_offset(file.data_directory()[pelite::image::IMAGE_DIRECTORY_ENTRY_IMPORT].VirtualAddress).unwrap() + desc * 20;
+	let names: Vec<_> = imports.iter().next().unwrap().int().unwrap()
+		.enumerate()
+		.filter_map(|(index, import)| match import.unwrap() {
+			Import::ByName { hint, name } => Some((name, hint, index)),
+			Import::ByOrdinal { .. } => None,
+		})
+		.collect();
+	let first = names.iter().min().unwrap();
+	let second = names.iter().filter(|name| *name > first).min().unwrap();
+
+	// Drop the import name table of the second descriptor, rename its dll and mess up the hint of the alphabetically first import
+	let mut image = file_map.as_ref().to_vec();
+	image[desc_offset(1)..desc_offset(1) + 4].copy_from_slice(&[0; 4]);
+	let name = file.rva_to_file_offset(descs[1].Name).unwrap();
+	image[name..name + 12].copy_from_slice(b"kernel32.DLL");
+	let hint_name = file.rva_to_file_offset(file.derva_copy::<u64>(descs[0].OriginalFirstThunk + first.2 as u32 * 8).unwrap() as u32).unwrap();
+	image[hint_name..hint_name + 2].copy_from_slice(&0xffffu16.to_le_bytes());
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let report = file.validate();
+	assert!(report.iter().any(|finding| *finding == Finding::ImportNullInt { desc: 1 }));
+	assert!(report.iter().any(|finding| *finding == Finding::ImportDllDuplicate { desc: 1, first: 0 }));
+	assert!(report.iter().any(|finding| *finding == Finding::ImportHint { desc: 0, index: second.2 }));
+	assert_eq!(Finding::ImportHint { desc: 0, index: 0 }.severity(), Severity::Warning);
+
+	// Move the dll name of the first descriptor into the DOS stub
+	let mut image = file_map.as_ref().to_vec();
+	let stub = image.windows(4).position(|window| window == b"This").unwrap() as u32;
+	image[desc_offset(0) + 12..desc_offset(0) + 16].copy_from_slice(&stub.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	let report = file.validate();
+	assert!(report.iter().any(|finding| *finding == Finding::ImportNameOutsideSection { desc: 0, index: None }));
+	assert!(report.to_string().contains("warning: import descriptor #0 dll name outside of the sections"));
+}
+
+#[test]
+fn validate_sections() {
+	use pelite::image::{IMAGE_SCN_MEM_EXECUTE, IMAGE_SCN_MEM_SHARED, IMAGE_SCN_MEM_WRITE};
+	use pelite::pe64::validate::{Finding, Severity};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert!(!file.validate().iter().any(|finding| format!("{:?}", finding).starts_with("Section")));
+
+	// Make .text writable, share .data and make .rdata executable
+	let mut image = file_map.as_ref().to_vec();
+	let headers = file.section_headers().image().as_ptr() as usize - file_map.as_ref().as_ptr() as usize;
+	let position = |name: &str| file.section_headers().iter().position(|it| it.name() == Ok(name)).unwrap();
+	let mut characteristics = |index: usize, flags: u32| {
+		let offset = headers + index * 40 + 36;
+		let value = file.section_headers().image()[index].Characteristics | flags;
+		image[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+	};
+	characteristics(position(".text"), IMAGE_SCN_MEM_WRITE);
+	characteristics(position(".data"), IMAGE_SCN_MEM_SHARED);
+	characteristics(position(".rdata"), IMAGE_SCN_MEM_EXECUTE);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let report = file.validate();
+	assert!(report.iter().any(|finding| *finding == Finding::SectionWritableExecutable { section: position(".text") }));
+	assert!(report.iter().any(|finding| *finding == Finding::SectionSharedWritable { section: position(".data") }));
+	assert!(report.iter().any(|finding| *finding == Finding::SectionExecutableData { section: position(".rdata") }));
+	assert_eq!(Finding::SectionWritableExecutable { section: 0 }.severity(), Severity::Warning);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn loader_raw_data() {
+	use pelite::image::IMAGE_DIRECTORY_ENTRY_EXPORT;
+	use pelite::pe64::index::{Indexed, SectionIndex};
+	use pelite::pe64::PeView;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let dll_name = file.exports().unwrap().dll_name().unwrap();
+
+	// Converting to a view copies the smaller of the virtual size and the size of raw data
+	let view = file.to_view();
+	let view = PeView::from_bytes(&view).unwrap();
+	assert_eq!(view.exports().unwrap().dll_name().unwrap(), dll_name);
+
+	// Misalign the PointerToRawData of the section containing the exports
+	let rva = file.data_directory()[IMAGE_DIRECTORY_ENTRY_EXPORT].VirtualAddress;
+	let index = file.section_headers().iter().position(|it| it.virtual_range().contains(&rva)).unwrap();
+	let offset = file.dos_header().e_lfanew as usize + 24 + file.file_header().SizeOfOptionalHeader as usize + index * 40 + 20;
+	let mut bytes = file_map.as_ref().to_vec();
+	let pointer_to_raw_data = file.section_headers().image()[index].PointerToRawData + 0x10;
+	bytes[offset..offset + 4].copy_from_slice(&pointer_to_raw_data.to_le_bytes());
+
+	// The strict parser takes the PointerToRawData literally
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	assert_ne!(file.exports().and_then(|exports| exports.dll_name()).ok(), Some(dll_name));
+
+	// The lenient parser truncates it like the Windows loader
+	let file = PeFile::from_bytes_lax(&bytes).unwrap();
+	assert_eq!(file.exports().unwrap().dll_name().unwrap(), dll_name);
+	assert_eq!(file.rva_to_file_offset(rva).unwrap(), PeFile::from_bytes(&file_map).unwrap().rva_to_file_offset(rva).unwrap());
+	let index = SectionIndex::new(file);
+	assert_eq!(Indexed::new(file, &index).exports().unwrap().dll_name().unwrap(), dll_name);
+	let view = file.to_view();
+	let view = PeView::from_bytes(&view).unwrap();
+	assert_eq!(view.exports().unwrap().dll_name().unwrap(), dll_name);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn pe_headers() {
+	use pelite::pe64::PeHeaders;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let image = file_map.as_ref();
+
+	// Only the section headers are required
+	let headers = PeHeaders::from_bytes(&image[..0x400]).unwrap();
+	let headers_len = headers.headers_len();
+	let headers = PeHeaders::from_bytes(&image[..headers_len]).unwrap();
+	assert_eq!(headers.violations(), Violations::empty());
+	assert_eq!(headers.machine().0, file.file_header().Machine);
+	assert_eq!(headers.section_headers().image().len(), file.section_headers().image().len());
+	assert_eq!(headers.image_size(), file.optional_header().SizeOfImage);
+	let file_size = std::fs::metadata(FILE_NAME).unwrap().len();
+	assert_eq!(headers.file_size(), file_size);
+	assert!(headers.is_complete(file_size));
+	assert!(!headers.is_complete(headers_len as u64));
+
+	// The strict options still accept truncated headers
+	assert!(PeHeaders::from_bytes_with_options(&image[..headers_len], ParseOptions::DEFAULT).is_ok());
+	assert_eq!(PeHeaders::from_bytes(&image[..headers_len - 1]).err(), Some(Error::Bounds));
+}
+
+#[test]
+fn reader() {
+	use pelite::image::{IMAGE_DIRECTORY_ENTRY_EXPORT, IMAGE_DIRECTORY_ENTRY_RESOURCE};
+	use pelite::pe64::PeReader;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let mut reader = PeReader::new(std::io::Cursor::new(file_map.as_ref())).unwrap();
+	assert!(reader.is_loaded(0) && !reader.is_loaded(0x1000));
+	assert_eq!(reader.view().section_headers().image().len(), file.section_headers().image().len());
+
+	// Only the pages of the export directory are loaded
+	reader.load_directory(IMAGE_DIRECTORY_ENTRY_EXPORT).unwrap();
+	let exports = reader.view().exports().unwrap();
+	assert_eq!(exports.dll_name(), file.exports().unwrap().dll_name());
+	assert_eq!(exports.by().unwrap().functions(), file.exports().unwrap().by().unwrap().functions());
+	assert!(!reader.is_loaded(0x1000) && !reader.is_loaded(0x8000));
+
+	// Referencing unloaded pages fails until they are loaded
+	assert_eq!(reader.view().derva_slice::<u8>(0x1000, 0x10).err(), Some(Error::ZeroFill));
+	assert_eq!(reader.view().pe().derva_slice::<u8>(0x1000, 0x10).unwrap(), [0; 0x10]);
+	// Slices end at the first page which is not loaded
+	assert!(file.section_headers().image()[0].VirtualSize > 0x1000);
+	reader.load(0x1000, 1).unwrap();
+	assert_eq!(reader.view().slice_bytes(0x1ff0).unwrap().len(), 0x10);
+	assert_eq!(reader.view().derva_slice::<u8>(0x1ff0, 0x20).err(), Some(Error::ZeroFill));
+	reader.load_section(0).unwrap();
+	assert_eq!(reader.view().derva_slice::<u8>(0x1000, 0x10).unwrap(), file.derva_slice::<u8>(0x1000, 0x10).unwrap());
+	reader.load_directory(IMAGE_DIRECTORY_ENTRY_RESOURCE).unwrap();
+	assert!(reader.view().resources().unwrap().version_info().is_ok());
+	assert_eq!(reader.load_directory(4).err().map(|err| err.to_string()), Some(Error::Null.to_string()));
+
+	// The size of the image is limited before allocating the buffer
+	let options = ParseOptions { max_image_size: file.optional_header().SizeOfImage as usize - 1, ..ParseOptions::DEFAULT };
+	let result = PeReader::with_options(std::io::Cursor::new(file_map.as_ref()), options);
+	assert!(matches!(result, Err(pelite::pe64::ReadError::Pe(Error::Insanity))));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn detour_point() {
+	use pelite::pe64::analysis::detour::detour_point;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	for function in file.exception().unwrap().functions() {
+		let image = function.image();
+		let len = (image.EndAddress - image.BeginAddress) as usize;
+		let prolog_len = function.unwind_info().unwrap().size_of_prolog();
+
+		// The end of the prolog is an instruction boundary
+		let detour = detour_point(file, image.BeginAddress, prolog_len).unwrap();
+		assert_eq!((detour.function, detour.end, detour.prolog_len), (image.BeginAddress, image.EndAddress, prolog_len));
+		assert_eq!(detour.len, prolog_len);
+		assert!(detour.in_prolog());
+
+		// Looking up the function from an address inside it
+		if len >= 5 {
+			let detour = detour_point(file, image.EndAddress - 1, 5).unwrap();
+			assert!(detour.len >= 5 && detour.len < 5 + 15);
+			assert_eq!(detour.rva, image.BeginAddress + detour.len as u32);
+		}
+		// Decoding the whole function ends exactly at its end
+		assert_eq!(detour_point(file, image.BeginAddress, len).unwrap().len, len);
+		assert_eq!(detour_point(file, image.BeginAddress, len + 1).err(), Some(Error::Bounds));
+	}
+	assert_eq!(detour_point(file, 0, 5).err(), Some(Error::Null));
+}
+
+#[test]
+fn coverage() {
+	use pelite::pe64::analysis::coverage::Coverage;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let functions = file.exception().unwrap().image();
+	let (first, second) = (functions[0], functions[1]);
+
+	// Hit the first function twice at the same address, the second function at two addresses, the headers and outside of the image
+	let hits = [second.BeginAddress + 1, first.BeginAddress, 0x10, second.BeginAddress, first.BeginAddress, 0xffff_0000];
+	let coverage = Coverage::from_rvas(file, &hits);
+	assert_eq!(coverage.functions.len(), 2);
+	assert_eq!((coverage.functions[0].begin, coverage.functions[0].end), (first.BeginAddress, first.EndAddress));
+	assert_eq!((coverage.functions[0].hits, coverage.functions[0].unique), (2, 1));
+	assert_eq!((coverage.functions[1].hits, coverage.functions[1].unique), (2, 2));
+	assert_eq!((coverage.unattributed, coverage.outside), (1, 1));
+	assert_eq!(coverage.sections.iter().sum::<u32>(), 4);
+	assert_eq!(coverage.hits(), hits.len() as u32);
+
+	// The same hits as file offsets
+	let file_offsets: Vec<usize> = hits.iter().map(|&rva| file.rva_to_file_offset(rva).unwrap_or(usize::MAX)).collect();
+	assert_eq!(Coverage::from_file_offsets(file, &file_offsets), coverage);
+}
+
+#[test]
+fn mitigations() {
+	use pelite::pe64::analysis::mitigations::{Mitigations, Status};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let mitigations = Mitigations::new(file);
+	assert_eq!(mitigations.aslr, Status::Enabled);
+	assert_eq!(mitigations.high_entropy_va, Status::Enabled);
+	assert_eq!(mitigations.dep, Status::Enabled);
+	assert_eq!(mitigations.cfg, Status::Disabled);
+	assert_eq!(mitigations.xfg, Status::Disabled);
+	assert_eq!(mitigations.safe_seh, Status::NotApplicable);
+	assert_eq!(mitigations.gs, Status::Enabled);
+	assert!(mitigations.to_string().starts_with("ASLR:             enabled\n"));
+	let load_config = file.load_config().unwrap().image();
+	let cookie = mitigations.security_cookie.unwrap();
+	assert_eq!(cookie.rva, file.va_to_rva(load_config.SecurityCookie).unwrap());
+	assert!(cookie.writable && cookie.initialized);
+
+	// Move the cookie past the IAT at the start of the read-only .rdata where nothing initializes it
+	let mut image = file_map.as_ref().to_vec();
+	let offset = &load_config.SecurityCookie as *const u64 as usize - file_map.as_ref().as_ptr() as usize;
+	let rdata = file.section_headers().by_name(".rdata").unwrap().VirtualAddress + 0x200;
+	image[offset..offset + 8].copy_from_slice(&file.rva_to_va(rdata).unwrap().to_le_bytes());
+	let cookie = Mitigations::new(PeFile::from_bytes(&image).unwrap()).security_cookie.unwrap();
+	assert_eq!(cookie.rva, rdata);
+	assert!(!cookie.writable && !cookie.initialized);
+
+	// The report is the same for the mapped image
+	let view = file.to_view();
+	let view = pelite::pe64::PeView::from_bytes(&view).unwrap();
+	assert_eq!(Mitigations::new(view), mitigations);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn toolchain() {
+	use pelite::pe64::analysis::toolchain::{detect_toolchain, Compiler, Evidence};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let toolchain = detect_toolchain(file);
+	assert_eq!(toolchain.compiler, Some(Compiler::Msvc));
+	assert_eq!(toolchain.version, Some("Visual Studio 2013"));
+	assert!(toolchain.evidence.contains(&(Compiler::Msvc, Evidence::RichHeader)));
+	assert!(toolchain.evidence.contains(&(Compiler::Msvc, Evidence::LinkerVersion(12, 0))));
+	assert_eq!(toolchain.score(Compiler::MinGW), 0);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn packer() {
+	use pelite::pe64::analysis::packer::{detect_packer, Indicator, Packer};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(detect_packer(file), Vec::new());
+
+	// Rename the first section to match the UPX signature
+	let offset = file.section_headers().image().as_ptr() as usize - file_map.as_ref().as_ptr() as usize;
+	let mut image = file_map.as_ref().to_vec();
+	image[offset..offset + 8].copy_from_slice(b"UPX1\0\0\0\0");
+	let file = PeFile::from_bytes(&image).unwrap();
+	let matches = detect_packer(file);
+	assert_eq!(matches.len(), 1);
+	assert_eq!(matches[0].packer, Packer::Upx);
+	assert_eq!(matches[0].indicators[0], Indicator::SectionName("UPX1"));
+	assert!(matches[0].confidence >= 50);
+}
+
+#[test]
+fn entry_point() {
+	use pelite::pe64::analysis::entry_point::{entry_point, Location};
+	use pelite::pe64::analysis::packer::{detect_packer, Indicator};
+	use pelite::pe64::validate::Finding;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let entry = entry_point(file);
+	assert_eq!(entry.location, Location::Section(0));
+	assert!(entry.executable && !entry.writable && !entry.last_section && !entry.section_start && !entry.in_iat);
+	assert!(!entry.is_suspicious());
+
+	// Point the entry point at the IAT
+	let mut image = file_map.as_ref().to_vec();
+	let offset = file.dos_header().e_lfanew as usize + 24 + 16;
+	image[offset..offset + 4].copy_from_slice(&0x3000u32.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	let entry = entry_point(file);
+	assert_eq!(entry.location, Location::Section(1));
+	assert!(!entry.executable && entry.section_start && entry.in_iat);
+	assert!(entry.is_suspicious());
+	let report = file.validate();
+	assert!(report.iter().any(|finding| *finding == Finding::EntryPointNotExecutable { section: 1 }));
+	assert!(report.iter().any(|finding| *finding == Finding::EntryPointInIat));
+
+	// The packer heuristics pick it up alongside a signature match
+	let section = file.section_headers().image()[1..].as_ptr() as usize - image.as_ptr() as usize;
+	let mut packed = image.clone();
+	packed[section..section + 8].copy_from_slice(b"UPX1\0\0\0\0");
+	let matches = detect_packer(PeFile::from_bytes(&packed).unwrap());
+	assert!(matches[0].indicators.contains(&Indicator::NonExecutableEntryPoint("UPX1")));
+
+	// Out of bounds
+	image[offset..offset + 4].copy_from_slice(&0xf000u32.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(entry_point(file).location, Location::OutOfBounds);
+	assert!(file.validate().iter().any(|finding| *finding == Finding::EntryPoint));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn overlay() {
+	use pelite::pe64::analysis::overlay::{overlay, OverlayKind};
+
+	let image = std::fs::read(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert!(overlay(file).is_none());
+
+	// The memory map is padded with zeroes to the page size
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let padding = overlay(file).unwrap();
+	assert_eq!((padding.offset(), padding.kind(), padding.data_len()), (image.len(), OverlayKind::Padding, 0));
+
+	let mut zip = image.clone();
+	zip.extend_from_slice(b"PK\x03\x04\x14\x00");
+	let file = PeFile::from_bytes(&zip).unwrap();
+	assert_eq!(overlay(file).unwrap().kind(), OverlayKind::Zip { offset: image.len() });
+
+	// The NSIS first header is found at the next 512 byte boundary
+	let mut nsis = image.clone();
+	nsis.resize((image.len() + 0x3ff) & !0x1ff, 0xcc);
+	nsis.extend_from_slice(b"\0\0\0\0\xEF\xBE\xAD\xDENullsoftInst");
+	let file = PeFile::from_bytes(&nsis).unwrap();
+	assert_eq!(overlay(file).unwrap().kind(), OverlayKind::Nsis { offset: (image.len() + 0x3ff) & !0x1ff });
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn footprint() {
+	use pelite::pe64::analysis::footprint::{footprint, Protection};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let footprint = footprint(file);
+	assert_eq!(footprint.headers, 0x1000);
+	assert_eq!((footprint.read_execute, footprint.read_write, footprint.read_only, footprint.read_write_execute), (0x2000, 0x2000, 0xA000, 0));
+	assert_eq!(footprint.virtual_size(), footprint.size_of_image as u64);
+	assert_eq!(footprint.pages(), 14);
+
+	let text = &footprint.sections[0];
+	assert_eq!((text.protection, text.virtual_size, text.aligned_size, text.slack(), text.pages()), (Protection::ReadExecute, 0x11BB, 0x2000, 0xE45, 2));
+	assert_eq!(footprint.slack(), footprint.sections.iter().map(|section| section.aligned_size - section.virtual_size as u64).sum());
+}
+
+#[test]
+fn slack() {
+	use pelite::pe64::analysis::slack::slack;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let slack = slack(file);
+	let section_headers = file.section_headers().image();
+	assert_eq!(slack.len(), section_headers.len());
+
+	let text = &slack[0];
+	let header = &section_headers[0];
+	assert_eq!(text.virtual_slack, header.VirtualAddress + 0x11BB..header.VirtualAddress + 0x2000);
+	assert_eq!(text.virtual_len(), 0xE45);
+	assert_eq!(text.physical_slack, header.PointerToRawData + 0x11BB..header.PointerToRawData + header.SizeOfRawData);
+	assert_eq!(text.physical_len(), header.SizeOfRawData - 0x11BB);
+	// The linker zero pads the raw data
+	for section in &slack {
+		assert!(section.physical_bytes(file).unwrap().iter().all(|&byte| byte == 0));
+	}
+
+	// Hide some data in the physical slack
+	let mut image = file_map.as_ref().to_vec();
+	image[text.physical_slack.start as usize] = 0xCC;
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(pelite::pe64::analysis::slack::slack(file)[0].physical_bytes(file).unwrap()[0], 0xCC);
+}
+
+#[test]
+fn hidden_data() {
+	use pelite::pe64::analysis::hidden::hidden_data;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(hidden_data(file), []);
+
+	// Hide data after the section table and between the first two sections
+	let section_headers = file.section_headers().image();
+	let table_end = section_headers.as_ptr_range().end as usize - file_map.as_ref().as_ptr() as usize;
+	let text = &section_headers[0];
+	let mut image = file_map.as_ref().to_vec();
+	image[table_end + 0x10] = 0x42;
+	let size_of_raw_data = (text as *const _ as usize - file_map.as_ref().as_ptr() as usize) + 16;
+	image[size_of_raw_data..size_of_raw_data + 4].copy_from_slice(&(text.SizeOfRawData - 0x200).to_le_bytes());
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let hidden = hidden_data(file);
+	assert_eq!(hidden.len(), 2);
+	assert_eq!(hidden[0].after, None);
+	assert_eq!(hidden[0].data, table_end as u32 + 0x10..table_end as u32 + 0x11);
+	assert_eq!(hidden[0].bytes(file), [0x42]);
+	assert_eq!(hidden[1].after, Some(0));
+	assert_eq!(hidden[1].gap, text.PointerToRawData + text.SizeOfRawData - 0x200..text.PointerToRawData + text.SizeOfRawData);
+}
+
+#[test]
+fn layout() {
+	use pelite::image::{IMAGE_DIRECTORY_ENTRY_EXPORT, IMAGE_DIRECTORY_ENTRY_RESOURCE};
+	use pelite::pe64::analysis::layout::{Region, RegionKind};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let layout = file.layout();
+
+	assert!(layout.is_clean());
+	assert_eq!(layout.overlay, Some(0x7200..0x8000));
+	assert_eq!(layout.regions[0], Region { rva: 0, size: 0x400, kind: RegionKind::Headers });
+	assert_eq!(layout.regions[1], Region { rva: 0x400, size: 0xc00, kind: RegionKind::Gap });
+	assert_eq!(layout.regions[2], Region { rva: 0x1000, size: 0x1200, kind: RegionKind::Section(0) });
+	assert_eq!(layout.regions[3], Region { rva: 0x2200, size: 0xe00, kind: RegionKind::Gap });
+	assert!(layout.regions.windows(2).all(|window| window[0].rva <= window[1].rva));
+	assert_eq!(layout.regions.iter().filter(|region| matches!(region.kind, RegionKind::Section(_))).count(), 7);
+	assert!(layout.regions.iter().any(|region| region.kind == RegionKind::Directory(IMAGE_DIRECTORY_ENTRY_EXPORT)));
+	let kinds: Vec<_> = layout.regions_at(0x8000).map(|region| region.kind).collect();
+	assert_eq!(kinds, [RegionKind::Section(5), RegionKind::Directory(IMAGE_DIRECTORY_ENTRY_RESOURCE)]);
+
+	// Overlapping sections are flagged
+	let mut image = file_map.as_ref().to_vec();
+	let offset = file.dos_header().e_lfanew as usize + 24 + file.file_header().SizeOfOptionalHeader as usize + 40 + 12;
+	image[offset..offset + 4].copy_from_slice(&0x2000u32.to_le_bytes());
+	let file = PeFile::from_bytes_with_options(&image, ParseOptions::LENIENT).unwrap();
+	let layout = file.layout();
+	assert_eq!(layout.overlaps.len(), 1);
+	let (first, second) = layout.overlaps[0];
+	assert_eq!((layout.regions[first].kind, layout.regions[second].kind), (RegionKind::Section(0), RegionKind::Section(1)));
+}
+
+#[test]
+fn what_is() {
+	use pelite::pe64::analysis::what_is::Item;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	assert_eq!(file.what_is(0x3c).items, [Item::HeaderField("e_lfanew")]);
+	assert_eq!(file.what_is(0x80).items, [Item::DosStub]);
+	assert_eq!(file.what_is(0x190).items, [Item::HeaderField("OptionalHeader.LoaderFlags")]);
+	assert_eq!(file.what_is(0x200).items, [Item::DataDirectoryEntry(13)]);
+
+	let what_is = file.what_is(0x3008);
+	assert_eq!((what_is.section, &*what_is.directories), (Some(1), &[12][..]));
+	match &what_is.items[..] {
+		[Item::ImportAddress { dll_name, import: Import::ByName { hint: 528, name } }] => assert_eq!((dll_name.to_str(), name.to_str()), (Ok("KERNEL32.dll"), Ok("GetCurrentProcessId"))),
+		items => panic!("{:?}", items),
+	}
+	match &file.what_is(0x3ca8).items[..] {
+		[Item::ImportDescriptor(dll_name)] => assert_eq!(*dll_name, "KERNEL32.dll"),
+		items => panic!("{:?}", items),
+	}
+	assert_eq!(file.what_is(0x3a58).items, [Item::ExportAddress(1)]);
+
+	match &file.what_is(0x10a8).items[..] {
+		[Item::Export(export)] => assert_eq!((export.rva, export.ordinal), (0x10a0, 15)),
+		items => panic!("{:?}", items),
+	}
+	assert_eq!(file.what_is(0x8400).items, [Item::Resource("/#ICON/#1/#2057".to_string())]);
+}
+
+#[test]
+fn rebase() {
+	use pelite::pe64::analysis::rebase::{Conflict, Range, Report};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	// Loading the same image twice forces the second copy to relocate
+	let demo = Range::new("Demo64.dll", file);
+	assert_eq!((demo.image_base, demo.size_of_image), (0x1_8000_0000, 0xE000));
+	let other = Range { name: "Other.dll", image_base: demo.image_base + 0x8000, size_of_image: 0x10000 };
+	let distinct = Range { name: "Distinct.dll", image_base: 0x1_9000_0000, size_of_image: 0x1000 };
+	let report = Report::new(vec![demo, Range::new("Copy.dll", file), other, distinct]);
+	assert!(!report.is_clean());
+	assert_eq!(report.conflicts, [
+		Conflict { first: 0, second: 1, overlap: 0xE000 },
+		Conflict { first: 0, second: 2, overlap: 0x6000 },
+		Conflict { first: 1, second: 2, overlap: 0x6000 },
+	]);
+	assert_eq!(report.relocated, [1, 2]);
+
+	let bases = report.suggest(0x1_8000_0001);
+	assert_eq!(bases, [0x1_8001_0000, 0x1_8002_0000, 0x1_8003_0000, 0x1_8004_0000]);
+	let ranges: Vec<_> = report.ranges.iter().zip(&bases).map(|(range, &image_base)| Range { image_base, ..*range }).collect();
+	assert!(Report::new(ranges).is_clean());
+}
+
+#[test]
+fn caves() {
+	use pelite::pe64::analysis::caves::{caves, CaveKind};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let caves = caves(file, 16);
+	assert!(caves.iter().any(|cave| cave.kind == CaveKind::Padding(0xCC)));
+	assert!(caves.iter().any(|cave| cave.kind == CaveKind::Slack));
+	for cave in &caves {
+		assert!(cave.size >= 16);
+		let section = &file.section_headers().image()[cave.section];
+		match cave.kind {
+			CaveKind::Padding(byte) => {
+				let bytes = file.derva_slice::<u8>(cave.rva, cave.size as usize).unwrap();
+				assert!(bytes.iter().all(|&b| b == byte));
+				assert!(cave.rva >= section.VirtualAddress && cave.rva + cave.size <= section.VirtualAddress + section.VirtualSize);
+			},
+			CaveKind::Slack => {
+				// Slack space is not backed by the file
+				assert!(file.rva_to_file_offset(cave.rva).is_err());
+			},
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn oep() {
+	use pelite::pe64::analysis::oep::{find_oep, Reason};
+	use pelite::pe64::PeView;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let entry_point = file.optional_header().AddressOfEntryPoint;
+
+	let candidates = find_oep(file);
+	assert_eq!(candidates[0].rva, entry_point);
+	assert_eq!(candidates[0].reasons, [Reason::EntryPoint, Reason::CrtSignature("MSVC DllMainCRTStartup")]);
+
+	// Simulate a dump where the entry point stub jumps into another executable section
+	let mut image = file.to_view();
+	let sections = file.section_headers().image();
+	let target = sections[1].VirtualAddress;
+	let stub = entry_point as usize;
+	image[stub] = 0xE9;
+	image[stub + 1..stub + 5].copy_from_slice(&(target.wrapping_sub(entry_point + 5)).to_le_bytes());
+	// Mark the second section executable, the characteristics are at offset 36 of the section header
+	let offset = sections.as_ptr() as usize - file_map.as_ref().as_ptr() as usize + 40 + 36;
+	image[offset + 3] |= 0x20;
+	let view = PeView::from_bytes(&image).unwrap();
+
+	let candidates = find_oep(view);
+	let candidate = candidates.iter().find(|candidate| candidate.rva == target).unwrap();
+	assert!(candidate.reasons.contains(&Reason::TailJump(entry_point)));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn imprec() {
+	use pelite::pe64::analysis::imprec::{ExportMap, Imports, Symbol};
+	use pelite::pe64::PeView;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let to_symbol = |import: Import<'_>| match import {
+		Import::ByName { name, .. } => Symbol::Name(name.to_str().unwrap().to_string()),
+		Import::ByOrdinal { ord } => Symbol::Ordinal(ord),
+	};
+
+	// Simulate a process where the imports are resolved to fake addresses
+	let mut export_map = ExportMap::new();
+	let mut image = file.to_view();
+	let mut expected = Vec::new();
+	let mut fake_va = 0x7ff8_0000_0000u64;
+	for desc in file.imports().unwrap() {
+		let dll_name = desc.dll_name().unwrap().to_str().unwrap();
+		let first_thunk = desc.image().FirstThunk as usize;
+		let mut symbols = Vec::new();
+		for (i, import) in desc.int().unwrap().enumerate() {
+			let symbol = to_symbol(import.unwrap());
+			export_map.add(dll_name, fake_va, symbol.clone());
+			image[first_thunk + i * 8..first_thunk + i * 8 + 8].copy_from_slice(&fake_va.to_le_bytes());
+			symbols.push(symbol);
+			fake_va += 0x10;
+		}
+		expected.push((dll_name, symbols));
+	}
+	let dump = PeView::from_bytes(&image).unwrap();
+
+	let iat = export_map.locate_iat(dump).unwrap();
+	let imports = Imports::resolve(dump, iat, &export_map).unwrap();
+	assert!(imports.unresolved.is_empty());
+	let descriptors: Vec<_> = imports.descriptors.iter().map(|desc| (&*desc.dll_name, desc.symbols.clone())).collect();
+	assert_eq!(descriptors, expected);
+
+	// Append the rebuilt import directory to the image and parse it back
+	let rva = image.len() as Rva;
+	let bytes = imports.build(rva);
+	image.resize(image.len() + bytes.len(), 0);
+	image[rva as usize..].copy_from_slice(&bytes);
+	let base = file_map.as_ref().as_ptr() as usize;
+	let optional_header = file.optional_header() as *const _ as usize - base;
+	let last_section = file.section_headers().image().last().unwrap();
+	let end = rva + bytes.len() as u32;
+	// Grow SizeOfImage and the last section's VirtualSize, and point the import directory at the appended bytes
+	image[optional_header + 56..optional_header + 60].copy_from_slice(&end.to_le_bytes());
+	let virtual_size = last_section as *const _ as usize - base + 8;
+	image[virtual_size..virtual_size + 4].copy_from_slice(&(end - last_section.VirtualAddress).to_le_bytes());
+	image[optional_header + 120..optional_header + 124].copy_from_slice(&rva.to_le_bytes());
+
+	let rebuilt = PeView::from_bytes(&image).unwrap();
+	for (desc, (dll_name, symbols)) in rebuilt.imports().unwrap().iter().zip(&expected) {
+		assert_eq!(desc.dll_name().unwrap(), dll_name);
+		let rebuilt_symbols: Vec<_> = desc.int().unwrap().map(|import| to_symbol(import.unwrap())).collect();
+		assert_eq!(&rebuilt_symbols, symbols);
+	}
+	assert_eq!(rebuilt.imports().unwrap().iter().count(), expected.len());
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn symbols() {
+	use pelite::pe64::symbols::{CoffSymbols, ExportSymbols, MapSymbols, Providers, SymbolProvider};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let symbols = ExportSymbols::new(file).unwrap();
+	assert_eq!(symbols.module(), "Demo");
+
+	let by = file.exports().unwrap().by().unwrap();
+	for &(rva, name) in symbols.symbols() {
+		assert_eq!(by.name(name).unwrap().symbol(), Some(rva));
+		let symbol = symbols.symbolize(rva).unwrap();
+		assert_eq!((symbol.rva, symbol.offset), (rva, 0));
+		assert_eq!(symbol.to_string(), format!("Demo!{}", symbol.name));
+	}
+
+	let &(rva, name) = symbols.symbols().first().unwrap();
+	let symbol = symbols.symbolize(rva + 0x2a).unwrap();
+	if symbol.rva == rva {
+		assert_eq!(symbol.to_string(), format!("Demo!{}+0x2a", name));
+	}
+	assert_eq!(symbols.symbolize(rva - 1), None);
+	assert_eq!(symbols.symbolize(file.optional_header().SizeOfImage), None);
+
+	// Append a COFF symbol table to the image
+	let mut image = std::fs::read(FILE_NAME).unwrap();
+	let file_header = file.file_header() as *const _ as usize - file_map.as_ref().as_ptr() as usize;
+	let offset = image.len() as u32;
+	image[file_header + 8..file_header + 12].copy_from_slice(&offset.to_le_bytes());
+	image[file_header + 12..file_header + 16].copy_from_slice(&4u32.to_le_bytes());
+	let mut symbol = |name: [u8; 8], value: u32, section: i16, ty: u16, class: u8, aux: u8| {
+		image.extend_from_slice(&name);
+		image.extend_from_slice(&value.to_le_bytes());
+		image.extend_from_slice(&section.to_le_bytes());
+		image.extend_from_slice(&ty.to_le_bytes());
+		image.extend_from_slice(&[class, aux]);
+	};
+	symbol(*b"short\0\0\0", 0x10, 1, 0x20, 2, 1);
+	symbol([0; 8], 0, 0, 0, 0, 0);
+	symbol(*b".text\0\0\0", 0, 1, 0, 3, 0);
+	symbol([0, 0, 0, 0, 4, 0, 0, 0], 0x20, 1, 0x20, 3, 0);
+	image.extend_from_slice(&17u32.to_le_bytes());
+	image.extend_from_slice(b"long_function\0");
+	let file = PeFile::from_bytes(&image).unwrap();
+	let coff = CoffSymbols::new(file).unwrap();
+	assert_eq!(coff.module(), "Demo");
+	assert_eq!(coff.symbols(), &[(0x1010, "short"), (0x1020, "long_function")]);
+	assert_eq!(coff.resolve("long_function"), Some(0x1020));
+	assert_eq!(coff.symbolize(0x1018).unwrap().to_string(), "Demo!short+0x8");
+	let view = file.to_view();
+	let view = pelite::pe64::PeView::from_bytes(&view).unwrap();
+	assert_eq!(CoffSymbols::new(view).map(|_| ()), Err(Error::Unmapped));
+
+	// Query in priority order
+	let map = MapSymbols::new(file, "Demo", vec![(0x1000, "text_start"), (0xffff_0000, "outside")]);
+	assert_eq!(map.symbols(), &[(0x1000, "text_start")]);
+	let mut providers = Providers::new();
+	providers.push(&coff);
+	providers.push(map);
+	providers.push(&symbols);
+	assert_eq!(providers.symbolize(0x1018).unwrap().name, "short");
+	assert_eq!(providers.symbolize(0x1008).unwrap().name, "text_start");
+	assert_eq!(providers.resolve(name), Some(rva));
+	assert_eq!(providers.resolve("missing"), None);
+}
+
+//----------------------------------------------------------------
+
+// Appends bytes to the last section of a mapped image, returns their address
+fn extend_view(view: &mut Vec<u8>, bytes: &[u8]) -> Rva {
+	let (optional_header, virtual_size, section_rva) = {
+		let pe = pelite::pe64::PeView::from_bytes(view).unwrap();
+		let base = view.as_ptr() as usize;
+		let last_section = pe.section_headers().image().last().unwrap();
+		(pe.optional_header() as *const _ as usize - base, last_section as *const _ as usize - base + 8, last_section.VirtualAddress)
+	};
+	let rva = view.len() as Rva;
+	view.extend_from_slice(bytes);
+	let end = view.len() as u32;
+	view[optional_header + 56..optional_header + 60].copy_from_slice(&end.to_le_bytes());
+	view[virtual_size..virtual_size + 4].copy_from_slice(&(end - section_rva).to_le_bytes());
+	rva
+}
+
+// Points a data directory of a mapped image at the given address
+fn set_data_directory(view: &mut Vec<u8>, index: usize, rva: Rva, size: u32) {
+	let offset = {
+		let pe = pelite::pe64::PeView::from_bytes(view).unwrap();
+		&pe.data_directory()[index] as *const _ as usize - view.as_ptr() as usize
+	};
+	view[offset..offset + 4].copy_from_slice(&rva.to_le_bytes());
+	view[offset + 4..offset + 8].copy_from_slice(&size.to_le_bytes());
+}
+
+#[test]
+fn clr() {
+	use pelite::image::*;
+	use pelite::pe64::PeView;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(file.clr().map(|_| ()), Err(Error::Null));
+
+	// Synthesize a precompiled assembly
+	let mut view = file.to_view();
+	let rva = view.len() as Rva;
+	let words = |words: &[u32]| words.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<u8>>();
+	let mut bytes = words(&[72, 0x0005_0002, rva + 72, 4, COMIMAGE_FLAGS_ILONLY, 0x0600_0001]);
+	bytes.extend(words(&[rva + 188, 12]));
+	bytes.extend(words(&[0; 8]));
+	bytes.extend(words(&[rva + 76, 52]));
+	bytes.extend(b"BSJB");
+	bytes.extend(words(&[READYTORUN_SIGNATURE, 0x0002_0009, READYTORUN_FLAG_COMPONENT, 3]));
+	bytes.extend(words(&[READYTORUN_SECTION_COMPILER_IDENTIFIER, rva + 128, 16]));
+	bytes.extend(words(&[READYTORUN_SECTION_IMPORT_SECTIONS, rva + 144, 20]));
+	bytes.extend(words(&[READYTORUN_SECTION_RUNTIME_FUNCTIONS, rva + 164, 24]));
+	bytes.extend(b"Crossgen2 8.0\0\0\0");
+	bytes.extend(words(&[0x1000, 8, 0x0801_0001, 0, 0]));
+	bytes.extend(words(&[0x1000, 0x1010, 0x3000, 0x1010, 0x1020, 0x3010]));
+	bytes.extend(words(&[5]));
+	bytes.extend(b"hello\0\0\0");
+	assert_eq!(extend_view(&mut view, &bytes), rva);
+	set_data_directory(&mut view, IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR, rva, 72);
+
+	let view = PeView::from_bytes(&view).unwrap();
+	let clr = view.clr().unwrap();
+	assert!(clr.is_il_only());
+	assert_eq!(clr.metadata().unwrap(), b"BSJB");
+	assert_eq!(clr.manifest_resource_data(0).unwrap(), b"hello");
+	assert_eq!(clr.manifest_resource_data(4), Err(Error::Bounds));
+	let r2r = clr.ready_to_run().unwrap();
+	assert_eq!(r2r.flags(), READYTORUN_FLAG_COMPONENT);
+	assert_eq!(r2r.sections().len(), 3);
+	assert_eq!(r2r.compiler_identifier().unwrap(), "Crossgen2 8.0");
+	let import_sections = r2r.import_sections().unwrap();
+	assert_eq!((import_sections.len(), import_sections[0].Type, import_sections[0].EntrySize), (1, 1, 8));
+	let runtime_functions = r2r.runtime_functions().unwrap();
+	assert_eq!(runtime_functions.len(), 2);
+	assert_eq!(runtime_functions[1].UnwindData, 0x3010);
+	assert_eq!(r2r.section(READYTORUN_SECTION_DEBUG_INFO), Err(Error::Null));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn strong_name() {
+	use pelite::image::*;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	// Place a CLR header and strong name signature in the raw data of the last section
+	let mut image = std::fs::read(FILE_NAME).unwrap();
+	let section = file.section_headers().image().last().unwrap();
+	let (rva, offset) = (section.VirtualAddress, section.PointerToRawData as usize);
+	let mut header = Vec::new();
+	for word in &[72, 0x0005_0002, 0, 0, COMIMAGE_FLAGS_ILONLY | COMIMAGE_FLAGS_STRONGNAMESIGNED, 0, 0, 0, rva + 72, 16] {
+		header.extend_from_slice(&u32::to_le_bytes(*word));
+	}
+	header.resize(72, 0);
+	header.extend_from_slice(&[0xaa; 16]);
+	image[offset..offset + header.len()].copy_from_slice(&header);
+	let base = file_map.as_ref().as_ptr() as usize;
+	let com_descriptor = &file.data_directory()[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR] as *const _ as usize - base;
+	image[com_descriptor..com_descriptor + 4].copy_from_slice(&rva.to_le_bytes());
+	image[com_descriptor + 4..com_descriptor + 8].copy_from_slice(&72u32.to_le_bytes());
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let clr = file.clr().unwrap();
+	assert!(clr.is_strong_name_signed());
+	assert_eq!(clr.strong_name_signature().unwrap(), &[0xaa; 16]);
+
+	let mut digest = Vec::new();
+	clr.strong_name_digest(|bytes| digest.extend_from_slice(bytes)).unwrap();
+
+	// The checksum, certificate table entry and signature are skipped
+	let checksum = file.optional_header() as *const _ as usize - image.as_ptr() as usize + 64;
+	let security = com_descriptor - (IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR - IMAGE_DIRECTORY_ENTRY_SECURITY) * 8;
+	let mut expected = Vec::new();
+	expected.extend_from_slice(&image[..checksum]);
+	expected.extend_from_slice(&image[checksum + 4..security]);
+	expected.extend_from_slice(&image[security + 8..file.optional_header().SizeOfHeaders as usize]);
+	for section in file.section_headers() {
+		let start = section.PointerToRawData as usize;
+		let end = start + section.SizeOfRawData as usize;
+		if start == offset {
+			expected.extend_from_slice(&image[start..offset + 72]);
+			expected.extend_from_slice(&image[offset + 88..end]);
+		}
+		else {
+			expected.extend_from_slice(&image[start..end]);
+		}
+	}
+	assert_eq!(digest, expected);
+
+	let view = file.to_view();
+	let view = pelite::pe64::PeView::from_bytes(&view).unwrap();
+	assert_eq!(view.clr().unwrap().strong_name_digest(|_| ()), Err(Error::Unmapped));
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn image_kind() {
+	use pelite::image::*;
+	use pelite::pe64::analysis::kind::{image_kind, is_dll, is_driver, ImageKind};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(image_kind(file), ImageKind::Dll);
+	assert!(is_dll(file) && !is_driver(file));
+
+	let optional_header = file.optional_header() as *const _ as usize - file_map.as_ref().as_ptr() as usize;
+	let kind = |subsystem: u16, dll_characteristics: u16| {
+		let mut image = std::fs::read(FILE_NAME).unwrap();
+		image[optional_header + 68..optional_header + 70].copy_from_slice(&subsystem.to_le_bytes());
+		image[optional_header + 70..optional_header + 72].copy_from_slice(&dll_characteristics.to_le_bytes());
+		image_kind(PeFile::from_bytes(&image).unwrap())
+	};
+	assert_eq!(kind(IMAGE_SUBSYSTEM_NATIVE, 0), ImageKind::Native);
+	assert_eq!(kind(IMAGE_SUBSYSTEM_NATIVE, IMAGE_DLLCHARACTERISTICS_WDM_DRIVER), ImageKind::Driver);
+	assert_eq!(kind(IMAGE_SUBSYSTEM_EFI_APPLICATION, 0), ImageKind::Efi);
+	assert_eq!(kind(IMAGE_SUBSYSTEM_WINDOWS_GUI, IMAGE_DLLCHARACTERISTICS_WDM_DRIVER).to_string(), "dll");
+}
+
+#[cfg(all(feature = "object", feature = "goblin"))]
+#[test]
+fn interop() {
+	use object::read::Object;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let object = file.to_object().unwrap();
+	assert_eq!(object.entry(), file.optional_header().ImageBase + file.optional_header().AddressOfEntryPoint as u64);
+	let file2 = PeFile::from_object(&object).unwrap();
+	assert_eq!(file2.image().as_ptr(), file.image().as_ptr());
+
+	let goblin = file.to_goblin().unwrap();
+	assert_eq!(goblin.name, Some("Demo.dll"));
+	assert!(PeFile::from_goblin(&goblin, file_map.as_ref()).is_ok());
+	assert_eq!(pelite::pe32::PeFile::from_goblin(&goblin, file_map.as_ref()).err(), Some(Error::PeMagic));
+}
+
+#[test]
+fn stubs() {
+	use pelite::pe64::stubs::Stubs;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let stubs = Stubs::new(file).unwrap();
+	assert_eq!(stubs.lib_name(), "Demo");
+	let ctor = &stubs.stubs()[0];
+	assert_eq!((ctor.name, ctor.ordinal, &*ctor.ident), (Some("??0Passwds@@QEAA@PEBD@Z"), 1, "__0Passwds__QEAA_PEBD_Z"));
+
+	let rust = stubs.to_rust();
+	assert!(rust.contains("#[link(name = \"Demo\", kind = \"raw-dylib\")]"));
+	assert!(rust.contains("\t#[link_name = \"??0Passwds@@QEAA@PEBD@Z\"]\n\tpub fn __0Passwds__QEAA_PEBD_Z();"));
+
+	let header = stubs.to_c_header();
+	assert!(header.contains("/* ordinal 1: ??0Passwds@@QEAA@PEBD@Z */"));
+}
+
+#[test]
+fn resource_stats() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let stats = file.resources().unwrap().stats().unwrap();
+	assert_eq!((stats.data_entries, stats.data_size), (7, 16287));
+	let version = stats.types.iter().find(|ty| ty.name == *"#VERSION").unwrap();
+	assert_eq!((version.count, version.size), (1, 696));
+	assert_eq!(stats.languages, [1033, 2057]);
+	assert!(stats.duplicates.is_empty());
+	assert_eq!((stats.padding, stats.invalid), (21, 0));
+}
+
+#[test]
+fn resources_straddling_sections() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	// Split the resources over the .rsrc and .reloc sections laid out back to back
+	let mut image = file_map.as_ref().to_vec();
+	let e_lfanew = file.dos_header().e_lfanew as usize;
+	let headers = e_lfanew + 24 + file.file_header().SizeOfOptionalHeader as usize;
+	let write_section = |image: &mut [u8], index: usize, fields: [u32; 4]| {
+		for (i, field) in fields.iter().enumerate() {
+			let offset = headers + index * 40 + 8 + i * 4;
+			image[offset..offset + 4].copy_from_slice(&field.to_le_bytes());
+		}
+	};
+	write_section(&mut image, 5, [0x2000, 0x8000, 0x2000, 0x2e00]);
+	write_section(&mut image, 6, [0x21d0, 0xa000, 0x2200, 0x4e00]);
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file.slice_bytes(0x8000).unwrap().len(), 0x2000);
+	let resources = file.resources().unwrap();
+	assert_eq!(resources.fsck(), Ok(()));
+	assert_eq!(resources.stats().unwrap().data_size, 16287);
+
+	// The rest of the resources isn't found right after the .rsrc section in the file
+	write_section(&mut image, 6, [0x21d0, 0xa000, 0x2000, 0x5000]);
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file.resources().unwrap().fsck(), Err(Error::ZeroFill));
+}
+
+#[test]
+fn resources_salvage() {
+	use pelite::resources::salvage::{Broken, Damage};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let salvage = file.resources().unwrap().salvage().unwrap();
+	assert!(salvage.is_intact());
+	assert_eq!(salvage.data.len(), 7);
+
+	// Loop the #ICON entry back to the root and point the #STRING entry out of bounds
+	let mut image = file_map.as_ref().to_vec();
+	let root = file.rva_to_file_offset(0x8000).unwrap() + 16;
+	image[root + 8 + 4..root + 8 + 8].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+	image[root + 16 + 4..root + 16 + 8].copy_from_slice(&0x8000_ff00u32.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	let resources = file.resources().unwrap();
+	assert!(resources.fsck().is_err());
+
+	let salvage = resources.salvage().unwrap();
+	assert_eq!(salvage.broken, [
+		Broken { path: String::from("/#ICON"), damage: Damage::Revisited },
+		Broken { path: String::from("/#STRING"), damage: Damage::Error(Error::Bounds) },
+	]);
+	let paths: Vec<_> = salvage.data.iter().map(|(path, _)| &path[..]).collect();
+	assert_eq!(paths, ["/IMPORTANT/#106/#2057", "/#ACCELERATOR/#101/#2057", "/#GROUP_ICON/#103/#2057", "/#VERSION/#1/#2057", "/#MANIFEST/#2/#1033"]);
+	assert!(resources.to_string().contains("#MANIFEST/"));
+}
+
+#[test]
+fn resources_build() {
+	use pelite::image::{IMAGE_DATA_DIRECTORY, IMAGE_DIRECTORY_ENTRY_RESOURCE};
+	use pelite::resources::build::{Builder, Layout};
+	use pelite::resources::{Name, Resources};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let resources = file.resources().unwrap();
+	let datadir = file.data_directory()[IMAGE_DIRECTORY_ENTRY_RESOURCE];
+	let original = file.derva_slice::<u8>(datadir.VirtualAddress, datadir.Size as usize).unwrap();
+	let contents = |bytes: &[u8], rva| {
+		let datadir = IMAGE_DATA_DIRECTORY { VirtualAddress: rva, Size: bytes.len() as u32 };
+		let resources = Resources::new(bytes, &datadir);
+		assert_eq!(resources.fsck(), Ok(()));
+		let salvage = resources.salvage().unwrap();
+		salvage.data.iter().map(|(path, data)| (path.clone(), data.bytes().unwrap().to_vec(), data.code_page())).collect::<Vec<_>>()
+	};
+	let manifest_path = [Name::MANIFEST, Name::Id(2), Name::Id(1033)];
+
+	// Unmodified resources round trip byte for byte
+	let mut builder = Builder::from_resources(resources).unwrap();
+	builder.set_layout(Layout::Preserve);
+	assert_eq!(builder.build(datadir.VirtualAddress), original);
+
+	// Replacing a resource in place only touches its data and data entry
+	let manifest = resources.find_resource_ex(&manifest_path).unwrap();
+	let manifest_offset = manifest.as_ptr() as usize - original.as_ptr() as usize;
+	let data_entry = resources.root().unwrap().get_dir(manifest_path[0]).unwrap().get_dir(manifest_path[1]).unwrap().get_data(manifest_path[2]).unwrap();
+	let data_entry_offset = data_entry.image() as *const _ as usize - original.as_ptr() as usize;
+	builder.insert(&manifest_path, &manifest[..16], 1200).unwrap();
+	let bytes = builder.build(datadir.VirtualAddress);
+	assert_eq!(bytes.len(), original.len());
+	let changed: Vec<_> = (0..bytes.len()).filter(|&i| bytes[i] != original[i]).collect();
+	assert!(changed.iter().all(|&i| (manifest_offset..manifest_offset + manifest.len()).contains(&i) || (data_entry_offset..data_entry_offset + 16).contains(&i)));
+	let mut expected = contents(original, datadir.VirtualAddress);
+	let index = expected.iter().position(|(path, _, _)| path == "/#MANIFEST/#2/#1033").unwrap();
+	expected[index] = (String::from("/#MANIFEST/#2/#1033"), manifest[..16].to_vec(), 1200);
+	assert_eq!(contents(&bytes, datadir.VirtualAddress), expected);
+
+	// Larger data is appended and the data entries follow the section to its new rva
+	let large = vec![0xcc; manifest.len() + 1];
+	builder.insert(&manifest_path, &large[..], 0).unwrap();
+	let bytes = builder.build(0x20000);
+	assert_eq!(&bytes[data_entry_offset + 16..manifest_offset], &original[data_entry_offset + 16..manifest_offset]);
+	expected[index] = (String::from("/#MANIFEST/#2/#1033"), large.clone(), 0);
+	assert_eq!(contents(&bytes, 0x20000), expected);
+
+	// Adding entries lays out the tree again in the original order
+	builder.insert(&[Name::Str("EXTRA"), Name::Id(1), Name::Id(1033)], &b"extra"[..], 0).unwrap();
+	let bytes = builder.build(datadir.VirtualAddress);
+	let preserved = contents(&bytes, datadir.VirtualAddress);
+	assert_eq!(preserved[0].0, "/IMPORTANT/#106/#2057");
+	assert_eq!(preserved[1].0, "/EXTRA/#1/#1033");
+
+	// The compact layout sorts the entries
+	builder.set_layout(Layout::Compact);
+	let bytes = builder.build(datadir.VirtualAddress);
+	let mut compact = contents(&bytes, datadir.VirtualAddress);
+	assert_eq!(compact[0].0, "/EXTRA/#1/#1033");
+	compact.sort();
+	let mut preserved = preserved;
+	preserved.sort();
+	assert_eq!(compact, preserved);
+
+	builder.remove(&[Name::Str("EXTRA")]).unwrap();
+	assert_eq!(builder.remove(&[Name::Str("EXTRA")]), Err(pelite::resources::FindError::NotFound));
+	assert_eq!(contents(&builder.build(0x1000), 0x1000).len(), expected.len());
+}
+
+#[test]
+fn unaligned_reads() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let functions = file.exports().unwrap().image().AddressOfFunctions;
+
+	let aligned = file.derva_slice::<u32>(functions, 20).unwrap();
+	let unaligned = file.derva_slice_unaligned::<u32>(functions, 20).unwrap();
+	assert_eq!(unaligned.len(), 20);
+	assert_eq!(unaligned.aligned(), Some(aligned));
+	assert!(unaligned.iter().eq(aligned.iter().copied()));
+
+	// Straddle two entries of the export address table
+	if !cfg!(feature = "unsafe_alignment") {
+		assert_eq!(file.derva_slice::<u32>(functions + 2, 19), Err(Error::Misaligned));
+	}
+	let unaligned = file.derva_slice_unaligned::<u32>(functions + 2, 19).unwrap();
+	assert_eq!(unaligned.aligned(), None);
+	assert_eq!(unaligned.get(0), Some(aligned[0] >> 16 | aligned[1] << 16));
+	assert_eq!(unaligned.get(19), None);
+	assert_eq!(unaligned.iter().rev().next(), unaligned.get(18));
+}
+
+#[test]
+fn pod_arrays() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let functions = file.exports().unwrap().image().AddressOfFunctions;
+	let aligned = file.derva_slice::<u32>(functions, 20).unwrap();
+
+	// Fixed-size arrays read the whole table at once
+	let table: &[u32; 20] = file.derva(functions).unwrap();
+	assert_eq!(&table[..], aligned);
+	assert_eq!(file.derva_copy::<[[u32; 4]; 5]>(functions).unwrap()[1], [aligned[4], aligned[5], aligned[6], aligned[7]]);
+	assert_eq!(file.derva::<[u32; 0x10000]>(functions), Err(Error::Bounds));
+}
+
+#[test]
+fn exports_builder() {
+	use pelite::pe64::exports::{Builder, Target};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let exports = file.exports().unwrap();
+	let builder = Builder::from_exports(&exports).unwrap();
+	assert_eq!(builder.len(), 20);
+
+	// Serialize the directory in place of the original
+	let datadir = file.data_directory()[pelite::image::IMAGE_DIRECTORY_ENTRY_EXPORT];
+	let bytes = builder.build(datadir.VirtualAddress);
+	assert_eq!(bytes.len(), datadir.Size as usize);
+	let mut image = file_map.as_ref().to_vec();
+	let offset = file.rva_to_file_offset(datadir.VirtualAddress).unwrap();
+	image[offset..offset + bytes.len()].copy_from_slice(&bytes);
+	let rebuilt = PeFile::from_bytes(&image).unwrap();
+	assert!(rebuilt.exports().unwrap().diff(&exports).unwrap().is_empty());
+	assert_eq!(rebuilt.exports().unwrap().by().unwrap().check_sorted(), Ok(true));
+
+	// Add a forwarder and an export out of order, overwriting the resources
+	let mut builder = builder;
+	builder.push("Forwarded", Target::Forward(String::from("KERNEL32.Sleep")));
+	builder.insert(30, Some("AAA"), Target::Symbol(0x1000));
+	let bytes = builder.build(0x8000);
+	let offset = file.rva_to_file_offset(0x8000).unwrap();
+	image[offset..offset + bytes.len()].copy_from_slice(&bytes);
+	let e_lfanew = file.dos_header().e_lfanew as usize;
+	let datadir_offset = e_lfanew + 24 + 112;
+	image[datadir_offset..datadir_offset + 4].copy_from_slice(&0x8000u32.to_le_bytes());
+	image[datadir_offset + 4..datadir_offset + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+	let rebuilt = PeFile::from_bytes(&image).unwrap();
+	let by = rebuilt.exports().unwrap().by().unwrap();
+	assert_eq!(by.check_sorted(), Ok(true));
+	assert_eq!(by.functions().len(), 30);
+	assert_eq!(by.name("Forwarded").unwrap().forward().unwrap(), "KERNEL32.Sleep");
+	assert_eq!(by.name("AAA").unwrap().symbol(), Some(0x1000));
+	assert_eq!(by.ordinal(30).unwrap().symbol(), Some(0x1000));
+	assert_eq!(by.ordinal(25), Err(Error::Null));
+}
+
+#[test]
+fn imports_builder() {
+	use pelite::image::{IMAGE_DIRECTORY_ENTRY_IAT, IMAGE_DIRECTORY_ENTRY_IMPORT};
+	use pelite::pe64::imports::{Builder, Symbol};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let mut builder = Builder::from_imports(&file.imports().unwrap()).unwrap();
+	assert_eq!(builder.len(), 2);
+	builder.add("kernel32.DLL", Symbol::Name { hint: 0, name: String::from("Sleep") });
+	builder.add("WS2_32.dll", Symbol::Ordinal(23));
+
+	// Serialize the directory over the resources and point the data directories at it
+	let bytes = builder.build(0x8000);
+	let mut image = file_map.as_ref().to_vec();
+	let offset = file.rva_to_file_offset(0x8000).unwrap();
+	image[offset..offset + bytes.len()].copy_from_slice(&bytes);
+	let datadir = file.dos_header().e_lfanew as usize + 24 + 112;
+	let iat = builder.iat(0x8000);
+	let entries = [(IMAGE_DIRECTORY_ENTRY_IMPORT, 0x8000, builder.descriptors_size() as u32), (IMAGE_DIRECTORY_ENTRY_IAT, iat.start, iat.end - iat.start)];
+	for &(index, rva, size) in &entries {
+		image[datadir + index * 8..datadir + index * 8 + 4].copy_from_slice(&rva.to_le_bytes());
+		image[datadir + index * 8 + 4..datadir + index * 8 + 8].copy_from_slice(&size.to_le_bytes());
+	}
+
+	let rebuilt = PeFile::from_bytes(&image).unwrap();
+	let imports = rebuilt.imports().unwrap();
+	assert_eq!(imports.iter().count(), 3);
+	for (desc, (dll_name, symbols)) in imports.iter().zip(builder.iter()) {
+		assert_eq!(desc.dll_name().unwrap(), dll_name);
+		assert_eq!(desc.int().unwrap().count(), symbols.len());
+		assert!(desc.iat().unwrap().eq(desc.pe().derva_slice_s::<u64>(desc.image().OriginalFirstThunk, 0).unwrap()));
+	}
+	for (desc, original) in imports.iter().zip(file.imports().unwrap()) {
+		assert!(desc.int().unwrap().take(original.int().unwrap().count()).eq(original.int().unwrap()));
+	}
+	let kernel32 = imports.iter().next().unwrap();
+	let sleep = builder.thunk(0x8000, "KERNEL32.dll", kernel32.int().unwrap().count() - 1).unwrap();
+	let thunk = rebuilt.derva_copy::<u64>(sleep).unwrap();
+	assert_eq!(rebuilt.derva_c_str(thunk as u32 + 2).unwrap(), "Sleep");
+	assert_eq!(imports.iter().nth(2).unwrap().int().unwrap().next(), Some(Ok(pelite::pe64::imports::Import::ByOrdinal { ord: 23 })));
+}
+
+#[test]
+fn exports_diff() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let exports = file.exports().unwrap();
+	assert!(exports.diff(&exports).unwrap().is_empty());
+
+	// Shift the ordinals and clear the first function
+	let mut image = file_map.as_ref().to_vec();
+	let offset = file.rva_to_file_offset(file.data_directory()[pelite::image::IMAGE_DIRECTORY_ENTRY_EXPORT].VirtualAddress).unwrap();
+	image[offset + 16] += 1;
+	let functions = file.rva_to_file_offset(exports.image().AddressOfFunctions).unwrap();
+	image[functions..functions + 4].copy_from_slice(&[0; 4]);
+	let file2 = PeFile::from_bytes(&image).unwrap();
+
+	let diff = exports.diff(&file2.exports().unwrap()).unwrap();
+	assert!(diff.added.is_empty());
+	assert_eq!(diff.removed.len(), 1);
+	assert_eq!(diff.removed[0].name.unwrap(), "??0Passwds@@QEAA@PEBD@Z");
+	assert_eq!(diff.changed.len(), exports.by().unwrap().names().len() - 1);
+	assert!(diff.changed.iter().all(|(old, new)| new.ordinal == old.ordinal + 1));
+	assert!(!diff.is_compatible());
+}
+
+#[test]
+fn exports_rva_order() {
+	use pelite::pe64::exports::ExportKind;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let rva_order = file.exports().unwrap().rva_order().unwrap();
+
+	assert_eq!(rva_order.as_slice().len(), 20);
+	assert!(rva_order.iter().zip(rva_order.iter().skip(1)).all(|(a, b)| a.rva <= b.rva));
+	assert_eq!(rva_order.iter().filter(|export| export.kind == ExportKind::Data).count(), 4);
+	let global_a = rva_order.iter().find(|export| export.rva == 0x5018).unwrap();
+	assert_eq!(global_a.name.unwrap(), "?GLOBAL_A@@3VA@@A");
+	assert_eq!(global_a.kind, ExportKind::Data);
+	assert!(rva_order.data().any(|export| export.rva == 0x5018));
+	assert_eq!(rva_order.code().count() + rva_order.data().count(), rva_order.as_slice().len());
+	let by = rva_order.by();
+	assert_eq!(by.kind(by.name("?GLOBAL_A@@3VA@@A").unwrap()), ExportKind::Data);
+	assert_eq!(by.kind(by.name("?Lock@Passwds@@QEAAXXZ").unwrap()), ExportKind::Code);
+
+	// Aliases prefer the lowest ordinal
+	let owner = rva_order.owner(0x10A8).unwrap();
+	assert_eq!((owner.rva, owner.ordinal), (0x10A0, 15));
+	assert_eq!(rva_order.owner(0x12D4).unwrap().name.unwrap(), "?Lock@Passwds@@QEAAXXZ");
+	assert_eq!(rva_order.owner(0x1000), None);
+}
+
+#[test]
+fn lazy_validation() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert!(file.try_exports().is_ok());
+	assert!(file.try_imports().is_ok());
+	assert!(file.try_exception().is_ok());
+	assert_eq!(file.try_delay_imports().err(), Some(Error::Null));
+
+	// Corrupt the last exported name, only the eager validation notices
+	let mut image = file_map.as_ref().to_vec();
+	let names = file.exports().unwrap().image().AddressOfNames + 19 * 4;
+	let offset = file.rva_to_file_offset(names).unwrap();
+	image[offset..offset + 4].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	let by = file.exports().unwrap().by().unwrap();
+	assert!(by.ordinal(1).is_ok());
+	assert!(file.try_exports().is_err());
+}
+
+#[test]
+fn delay_imports() {
+	use pelite::image::{IMAGE_DELAYLOAD_RVA_BASED, IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT};
+	use pelite::pe64::delay_imports::Binding;
+	use pelite::pe64::PeView;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(file.delay_imports().err(), Some(Error::Null));
+
+	// Describe the first imported module as delay loaded from the slack space at the end of .rdata
+	let mut image = file_map.as_ref().to_vec();
+	let desc = file.imports().unwrap().image()[0];
+	let e_lfanew = file.dos_header().e_lfanew as usize;
+	let rdata = &file.section_headers().image()[1];
+	let virtual_size = e_lfanew + 24 + file.file_header().SizeOfOptionalHeader as usize + 40 + 8;
+	image[virtual_size..virtual_size + 4].copy_from_slice(&rdata.SizeOfRawData.to_le_bytes());
+	let datadir = e_lfanew + 24 + 112 + IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT * 8;
+	image[datadir..datadir + 8].copy_from_slice(&[0x70, 0x41, 0, 0, 0x20, 0, 0, 0]);
+	let words = [IMAGE_DELAYLOAD_RVA_BASED, desc.Name, 0x41b0, desc.FirstThunk, desc.OriginalFirstThunk, 0, 0, 0];
+	let offset = (rdata.PointerToRawData + 0x4170 - rdata.VirtualAddress) as usize;
+	for (i, word) in words.iter().enumerate() {
+		image[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+	}
+
+	// Map the image and pretend the second import has been called
+	let file = PeFile::from_bytes(&image).unwrap();
+	let mut view = file.to_view();
+	let stub = file.optional_header().ImageBase + 0x1010;
+	for (i, va) in [stub, 0x7ff8_0000_1234].iter().enumerate() {
+		let slot = desc.FirstThunk as usize + i * 8;
+		view[slot..slot + 8].copy_from_slice(&va.to_le_bytes());
+	}
+	view[0x41b0..0x41b8].copy_from_slice(&0x7ff8_0000_0000u64.to_le_bytes());
+
+	let view = PeView::from_bytes(&view).unwrap();
+	let delay_imports = view.delay_imports().unwrap();
+	let desc = delay_imports.iter().next().unwrap();
+	assert_eq!(delay_imports.iter().len(), 1);
+	assert_eq!(desc.dll_name().unwrap(), view.imports().unwrap().iter().next().unwrap().dll_name().unwrap());
+	assert_eq!(desc.module_handle(), Ok(0x7ff8_0000_0000));
+
+	let bindings = desc.bindings().unwrap();
+	assert_eq!(bindings.len(), desc.int().unwrap().len());
+	assert_eq!((bindings[0].target, bindings[1].target), (Binding::Stub(0x1010), Binding::Resolved(0x7ff8_0000_1234)));
+	assert!(bindings[1].target.is_resolved() && bindings.iter().all(|binding| binding.import.is_ok()));
+	assert_eq!(delay_imports.find_iat(bindings[1].iat), Some(bindings[1].clone()));
+	assert_eq!(delay_imports.find_iat(bindings[1].iat + 1), None);
+}
+
+#[test]
+fn bound_imports() {
+	use pelite::pe64::PeFileBuf;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(file.bound_imports().err(), Some(Error::Null));
+
+	// Bind the first import descriptor in the slack space after the section headers
+	let base = file_map.as_ref().as_ptr() as usize;
+	let mut image = file_map.as_ref().to_vec();
+	let datadir = file.data_directory().as_ptr() as usize - base + 11 * 8;
+	image[datadir..datadir + 8].copy_from_slice(&[0x40, 0x03, 0, 0, 0x30, 0, 0, 0]);
+	image[0x340..0x350].copy_from_slice(&[0x34, 0x12, 0, 0, 0x18, 0, 1, 0, 0x78, 0x56, 0, 0, 0x25, 0, 0, 0]);
+	image[0x358..0x36f].copy_from_slice(b"KERNEL32.dll\0NTDLL.dll\0");
+	let desc = file.imports().unwrap().image()[0];
+	let desc_offset = &file.imports().unwrap().image()[0] as *const _ as usize - base;
+	image[desc_offset + 4..desc_offset + 8].copy_from_slice(&[0xff; 4]);
+	let iat = file.rva_to_file_offset(desc.FirstThunk).unwrap();
+	image[iat..iat + 8].copy_from_slice(&[0xcc; 8]);
+
+	let file2 = PeFile::from_bytes(&image).unwrap();
+	let bound_imports = file2.bound_imports().unwrap();
+	let descs: Vec<_> = bound_imports.iter().collect();
+	assert_eq!(descs.len(), 1);
+	assert_eq!(descs[0].dll_name().unwrap(), "KERNEL32.dll");
+	assert_eq!(descs[0].forwarders().map(|(name, _)| name.unwrap()).collect::<Vec<_>>(), [CStr::from_bytes(b"NTDLL.dll\0").unwrap()]);
+
+	let stale = bound_imports.stale(|name| if name == "NTDLL.dll" { Some(1) } else { Some(0x1234) });
+	assert_eq!(stale.len(), 1);
+	assert_eq!((stale[0].dll_name, stale[0].bound, stale[0].actual), (CStr::from_bytes(b"NTDLL.dll\0").unwrap(), 0x5678, 1));
+	assert_eq!(stale[0].forwarder.unwrap(), "KERNEL32.dll");
+
+	// Unbinding restores the original image
+	let mut buf = PeFileBuf::from_vec(image).unwrap();
+	buf.unbind_imports().unwrap();
+	assert!(buf.into_vec() == file_map.as_ref());
+}
+
+#[test]
+fn grow_data_directory() {
+	use pelite::image::IMAGE_DATA_DIRECTORY;
+	use pelite::pe64::PeFileBuf;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let base = file_map.as_ref().as_ptr() as usize;
+
+	// Shrink the data directory to 10 entries and move the section headers up
+	let mut image = file_map.as_ref().to_vec();
+	let opt = file.optional_header() as *const _ as usize - base;
+	let sections = file.section_headers().as_slice().as_ptr() as usize - base;
+	let sections_len = file.section_headers().as_slice().len() * 40;
+	image[opt + 108..opt + 112].copy_from_slice(&10u32.to_le_bytes());
+	image[opt - 4..opt - 2].copy_from_slice(&(112u16 + 80).to_le_bytes());
+	image.copy_within(sections..sections + sections_len, sections - 48);
+	for byte in &mut image[sections + sections_len - 48..sections + sections_len] {
+		*byte = 0;
+	}
+	let mut buf = PeFileBuf::from_vec(image).unwrap();
+	assert_eq!(buf.as_file().data_directory().len(), 10);
+
+	// Repoint an existing entry
+	let exception = IMAGE_DATA_DIRECTORY { VirtualAddress: 0x1234, Size: 0x10 };
+	buf.set_data_directory(3, exception).unwrap();
+	assert_eq!(buf.as_file().data_directory()[3].VirtualAddress, 0x1234);
+
+	// Growing the data directory moves the section headers back
+	let clr = IMAGE_DATA_DIRECTORY { VirtualAddress: 0x5000, Size: 0x48 };
+	buf.set_data_directory(14, clr).unwrap();
+	assert_eq!(buf.as_file().data_directory().len(), 15);
+	assert_eq!(buf.as_file().file_header().SizeOfOptionalHeader, 112 + 15 * 8);
+	buf.set_data_directory(15, IMAGE_DATA_DIRECTORY { VirtualAddress: 0, Size: 0 }).unwrap();
+	let file2 = buf.as_file();
+	assert_eq!(file2.data_directory().len(), 16);
+	assert_eq!((file2.data_directory()[14].VirtualAddress, file2.data_directory()[14].Size), (0x5000, 0x48));
+	assert_eq!((file2.data_directory()[10].VirtualAddress, file2.data_directory()[10].Size), (0, 0));
+	assert_eq!(file2.file_header().SizeOfOptionalHeader, 112 + 16 * 8);
+	assert_eq!(&file2.image()[sections..sections + sections_len], &file_map.as_ref()[sections..sections + sections_len]);
+	assert_eq!(file2.image()[sections + sections_len..0x400].iter().filter(|&&byte| byte != 0).count(), 0);
+
+	assert_eq!(buf.set_data_directory(16, clr), Err(Error::Bounds));
+	// No need to move the section headers if the optional header has room for the entries
+	let mut image = file_map.as_ref().to_vec();
+	image[opt + 108..opt + 112].copy_from_slice(&10u32.to_le_bytes());
+	let mut buf = PeFileBuf::from_vec(image).unwrap();
+	buf.set_data_directory(15, clr).unwrap();
+	assert_eq!(buf.as_file().data_directory().len(), 16);
+}
+
+#[test]
+fn hashes() {
+	use pelite::pe64::PeView;
+	use pelite::util::Fnv1a;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let section_hashes = file.section_hashes::<Fnv1a>();
+	assert_eq!(section_hashes.len(), file.section_headers().as_slice().len());
+	assert!(section_hashes.iter().all(|section_hash| section_hash.hash.is_ok()));
+	let image_hash = file.image_hash::<Fnv1a>().unwrap();
+
+	// Appending an overlay and changing the checksum leave the hashes alone
+	let mut image = file_map.as_ref().to_vec();
+	let checksum = file.optional_header() as *const _ as usize - file_map.as_ref().as_ptr() as usize + 64;
+	image[checksum] ^= 0xff;
+	image.extend_from_slice(b"overlay");
+	let file2 = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file2.section_hashes::<Fnv1a>(), section_hashes);
+	assert_eq!(file2.image_hash::<Fnv1a>(), Ok(image_hash));
+
+	// Patching the data section only changes its hash
+	let data = file.section_headers().by_name(".data").unwrap();
+	image[data.PointerToRawData as usize] ^= 0xff;
+	let file3 = PeFile::from_bytes(&image).unwrap();
+	let section_hashes3 = file3.section_hashes::<Fnv1a>();
+	let changed: Vec<_> = section_hashes.iter().zip(&section_hashes3).filter(|(a, b)| a != b).map(|(a, _)| a.section).collect();
+	assert_eq!(changed, [file.section_headers().iter().position(|section| std::ptr::eq(section, data)).unwrap()]);
+	assert_ne!(file3.image_hash::<Fnv1a>(), Ok(image_hash));
+
+	let view = file.to_view();
+	assert_eq!(PeView::from_bytes(&view).unwrap().image_hash::<Fnv1a>(), Err(Error::Unmapped));
+}
+
+#[test]
+fn function_hashes() {
+	use pelite::disasm_lite::{iter, Mode, RelKind};
+	use pelite::pe64::analysis::hashes::function_hashes;
+	use pelite::util::Fnv1a;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let function_hashes1 = function_hashes::<_, Fnv1a>(file).unwrap();
+	assert_eq!(function_hashes1.len(), file.exception().unwrap().image().len());
+
+	// Move the relocated addresses and the IAT references
+	let mut image = file_map.as_ref().to_vec();
+	for (rva, _) in file.base_relocs().unwrap().iter() {
+		let offset = file.rva_to_file_offset(rva).unwrap();
+		image[offset + 2] ^= 0x10;
+	}
+	let iat = file.data_directory()[pelite::image::IMAGE_DIRECTORY_ENTRY_IAT];
+	let mut iat_refs = 0;
+	for function in file.exception().unwrap().functions() {
+		let start = function.image().BeginAddress;
+		let bytes = function.bytes().unwrap();
+		for (offset, insn) in iter(bytes, Mode::X64) {
+			let rel = match insn.rel {
+				Some(rel) if rel.kind == RelKind::Memory => rel,
+				_ => continue,
+			};
+			let target = insn.target(start as u64 + offset as u64).unwrap();
+			if target.wrapping_sub(iat.VirtualAddress as u64) < iat.Size as u64 {
+				// Point it at the first IAT entry instead
+				let end = start + (offset + insn.len) as u32;
+				let offset = file.rva_to_file_offset(start + (offset + rel.offset) as u32).unwrap();
+				image[offset..offset + 4].copy_from_slice(&iat.VirtualAddress.wrapping_sub(end).to_le_bytes());
+				iat_refs += 1;
+			}
+		}
+	}
+	assert!(iat_refs > 0);
+	let file2 = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(function_hashes::<_, Fnv1a>(file2).unwrap(), function_hashes1);
+
+	// Changing the code changes the hash of the function
+	let function = &function_hashes1[1];
+	let offset = file.rva_to_file_offset(function.rva).unwrap();
+	image[offset] ^= 0xff;
+	let file3 = PeFile::from_bytes(&image).unwrap();
+	let changed: Vec<_> = function_hashes::<_, Fnv1a>(file3).unwrap().iter().zip(&function_hashes1).filter(|(a, b)| a != b).map(|(a, _)| a.function).collect();
+	assert_eq!(changed, [1]);
+}
+
+#[test]
+fn guard_eh_continuation_table() {
+	use pelite::image::{IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG, IMAGE_GUARD_EH_CONTINUATION_TABLE_PRESENT};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let load_config = file.load_config().unwrap();
+	assert_eq!(load_config.guard_flags(), None);
+	assert_eq!(load_config.guard_eh_continuation_table().err(), Some(Error::Null));
+
+	// Extend the load config directory over whatever follows it
+	let mut image = file_map.as_ref().to_vec();
+	let rva = file.data_directory()[IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG].VirtualAddress;
+	let offset = file.rva_to_file_offset(rva).unwrap();
+	let table = file.rva_to_va(rva + 0x140).unwrap();
+	image[offset..offset + 4].copy_from_slice(&0x118u32.to_le_bytes());
+	image[offset + 0x90..offset + 0x94].copy_from_slice(&(IMAGE_GUARD_EH_CONTINUATION_TABLE_PRESENT | 1 << 28).to_le_bytes());
+	image[offset + 0x108..offset + 0x110].copy_from_slice(&table.to_le_bytes());
+	image[offset + 0x110..offset + 0x118].copy_from_slice(&2u64.to_le_bytes());
+	image[offset + 0x140..offset + 0x14A].copy_from_slice(&[0x10, 0x12, 0, 0, 0, 0x34, 0x12, 0, 0, 1]);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let table = file.load_config().unwrap().guard_eh_continuation_table().unwrap();
+	assert_eq!((table.len(), table.stride()), (2, 5));
+	let entries: Vec<_> = table.iter().map(|entry| (entry.rva, entry.metadata)).collect();
+	assert_eq!(entries, [(0x1210, &[0][..]), (0x1234, &[1][..])]);
+}
+
+#[test]
+fn xfg_hashes() {
+	use pelite::image::*;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(file.load_config().unwrap().xfg_hashes().err(), Some(Error::Null));
+
+	// Extend the load config directory with a guard function table
+	let mut image = file_map.as_ref().to_vec();
+	let rva = file.data_directory()[IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG].VirtualAddress;
+	let offset = file.rva_to_file_offset(rva).unwrap();
+	let table = file.rva_to_va(rva + 0x140).unwrap();
+	image[offset..offset + 4].copy_from_slice(&0x94u32.to_le_bytes());
+	image[offset + 0x80..offset + 0x88].copy_from_slice(&table.to_le_bytes());
+	image[offset + 0x88..offset + 0x90].copy_from_slice(&2u64.to_le_bytes());
+	image[offset + 0x90..offset + 0x94].copy_from_slice(&(IMAGE_GUARD_CF_FUNCTION_TABLE_PRESENT | IMAGE_GUARD_XFG_ENABLED | 1 << 28).to_le_bytes());
+	image[offset + 0x140..offset + 0x14A].copy_from_slice(&[0x50, 0x10, 0, 0, IMAGE_GUARD_FLAG_FID_XFG, 0x70, 0x10, 0, 0, 0]);
+	// Functions are preceded by the hash of their prototype
+	let function = file.rva_to_file_offset(0x1050).unwrap();
+	image[function - 8..function].copy_from_slice(&0x0123_4567_89AB_CDEFu64.to_le_bytes());
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let load_config = file.load_config().unwrap();
+	assert_eq!(load_config.guard_cf_function_table().unwrap().len(), 2);
+	assert_eq!(load_config.xfg_hashes().unwrap().collect::<Vec<_>>(), [(0x1050, Ok(0x0123_4567_89AB_CDEF))]);
+}
+
+#[test]
+fn cfg_suppressed_exports() {
+	use pelite::image::*;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let by = file.exports().unwrap().by().unwrap();
+	assert_eq!(by.cfg_suppressed().err(), Some(Error::Null));
+
+	// Extend the load config directory with a guard function table suppressing the second export
+	let mut image = file_map.as_ref().to_vec();
+	let rva = file.data_directory()[IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG].VirtualAddress;
+	let offset = file.rva_to_file_offset(rva).unwrap();
+	let flags = IMAGE_GUARD_CF_FUNCTION_TABLE_PRESENT | IMAGE_GUARD_CF_EXPORT_SUPPRESSION_INFO_PRESENT | 1 << 28;
+	image[offset..offset + 4].copy_from_slice(&0xB0u32.to_le_bytes());
+	image[offset + 0x80..offset + 0x88].copy_from_slice(&file.rva_to_va(rva + 0x140).unwrap().to_le_bytes());
+	image[offset + 0x88..offset + 0x90].copy_from_slice(&2u64.to_le_bytes());
+	image[offset + 0x90..offset + 0x94].copy_from_slice(&flags.to_le_bytes());
+	image[offset + 0xA0..offset + 0xA8].copy_from_slice(&file.rva_to_va(rva + 0x150).unwrap().to_le_bytes());
+	image[offset + 0xA8..offset + 0xB0].copy_from_slice(&1u64.to_le_bytes());
+	let entries = [(by.functions()[0], 0), (by.functions()[1], IMAGE_GUARD_FLAG_EXPORT_SUPPRESSED)];
+	for (i, &(function, flags)) in entries.iter().enumerate() {
+		image[offset + 0x140 + i * 5..offset + 0x144 + i * 5].copy_from_slice(&function.to_le_bytes());
+		image[offset + 0x144 + i * 5] = flags;
+	}
+	image[offset + 0x150..offset + 0x155].copy_from_slice(&[0x00, 0x30, 0, 0, 0]);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let load_config = file.load_config().unwrap();
+	assert_eq!(load_config.suppressed_exports().unwrap(), [by.functions()[1]]);
+	let address_taken: Vec<_> = load_config.guard_address_taken_iat_entry_table().unwrap().iter().map(|entry| entry.rva).collect();
+	assert_eq!(address_taken, [0x3000]);
+	let by = file.exports().unwrap().by().unwrap();
+	assert_eq!(by.cfg_suppressed().unwrap(), [by.ordinal_base() + 1]);
+}
+
+//----------------------------------------------------------------
+
 #[test]
 fn rich_structure() {
 	let file_map = FileMap::open(FILE_NAME).unwrap();
@@ -91,6 +1940,16 @@ fn exports() {
 
 	assert_eq!(file.get_proc_address(bad_hint), file.rva_to_va(0x1230));
 	assert_eq!(file.get_proc_address(good_hint), file.rva_to_va(0x1230));
+
+	let name_index = exports_by.name_index();
+	assert_eq!(name_index.len(), 20);
+	assert_eq!(name_index.hint(s_export_name), Some(11));
+	assert_eq!(name_index.name(s_export_name), Ok(Export::Symbol(&0x1230)));
+	assert_eq!(name_index.name("ThrowException"), exports_by.name("ThrowException"));
+	assert_eq!(name_index.import(bad_hint), Ok(Export::Symbol(&0x1230)));
+	assert_eq!(name_index.import(good_hint), Ok(Export::Symbol(&0x1230)));
+	assert_eq!(name_index.hint_name(5, "ThrowException"), exports_by.name("ThrowException"));
+	assert_eq!(name_index.name("DoesNotExist"), Err(Error::Null));
 }
 
 //----------------------------------------------------------------
@@ -123,6 +1982,59 @@ fn imports() {
 	assert!(Iterator::eq(lhs, rhs));
 }
 
+#[test]
+fn optional_header_window() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	assert_eq!(file.optional_header_window().len(), 240);
+	assert_eq!(file.declared_data_directory().len(), 16);
+
+	let size_of_optional_header = file.dos_header().e_lfanew as usize + 20;
+	let sections = file.section_headers().image().as_ptr() as usize - file_map.as_ref().as_ptr() as usize;
+	let sections_len = file.section_headers().image().len() * 40;
+	for &size in &[112 + 4 * 8, 240 + 8] {
+		// Move the section headers to follow the declared optional header
+		let mut image = file_map.as_ref().to_vec();
+		let new_sections = sections + size - 240;
+		image.copy_within(sections..sections + sections_len, new_sections);
+		image[size_of_optional_header..size_of_optional_header + 2].copy_from_slice(&(size as u16).to_le_bytes());
+		let file = PeFile::from_bytes(&image).unwrap();
+		assert_eq!(file.optional_header_window().len(), size);
+		assert_eq!(file.declared_data_directory().len(), if size < 240 { 4 } else { 16 });
+		assert_eq!(file.data_directory().len(), 16);
+		assert_eq!(file.section_headers().image().as_ptr() as usize - image.as_ptr() as usize, new_sections);
+		assert!(file.section_headers().by_name(".text").is_some());
+		assert!(file.imports().is_ok());
+		let overlap = pelite::pe64::validate::Finding::DataDirectoryOverlap { count: 12 };
+		assert_eq!(file.validate().findings().contains(&overlap), size < 240);
+	}
+}
+
+#[test]
+fn imports_int_or_iat() {
+	use pelite::pe64::imports::ThunkSource;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let kernel32_dll = file.imports().unwrap().iter().next().unwrap();
+	let imports = kernel32_dll.int_or_iat().unwrap();
+	assert_eq!(imports.source(), ThunkSource::Int);
+	let expected: Vec<_> = imports.map(Result::unwrap).collect();
+
+	// Clear and corrupt the import name table of KERNEL32.dll
+	let offset = kernel32_dll.image() as *const _ as usize - file_map.as_ref().as_ptr() as usize;
+	for &original_first_thunk in &[0u32, 0xfffffff0] {
+		let mut image = file_map.as_ref().to_vec();
+		image[offset..offset + 4].copy_from_slice(&original_first_thunk.to_le_bytes());
+		let file = PeFile::from_bytes(&image).unwrap();
+		let kernel32_dll = file.imports().unwrap().iter().next().unwrap();
+		assert!(kernel32_dll.int().map_or(true, |mut int| int.any(|import| import.is_err())));
+		let imports = kernel32_dll.int_or_iat().unwrap();
+		assert_eq!(imports.source(), ThunkSource::Iat);
+		assert!(imports.map(Result::unwrap).eq(expected.iter().cloned()));
+	}
+}
+
 //----------------------------------------------------------------
 
 #[test]
@@ -161,6 +2073,28 @@ fn base_relocs() {
 	});
 	let result = pelite::base_relocs::build(&rvas, &types);
 	assert_eq!(result, base_relocs.image());
+
+	// Typed iterator and queries
+	use pelite::base_relocs::RelocType;
+	let relocs: Vec<_> = base_relocs.iter().collect();
+	assert_eq!(relocs.len(), rvas.len());
+	assert!(relocs.iter().all(|&(_, ty)| ty == RelocType::Dir64));
+	let (rva, _) = relocs[1];
+	assert!(base_relocs.contains(rva));
+	assert!(!base_relocs.contains(rva + 1));
+	assert_eq!(base_relocs.range(rva..rva + 8).collect::<Vec<_>>(), [(rva, RelocType::Dir64)]);
+	assert_eq!(base_relocs.range(0x5000..!0).count(), block2.iter().count());
+	assert_eq!(u8::from(RelocType::from(0xb)), 0xb);
+
+	// Rebuilding with the builder round trips
+	let mut builder = pelite::base_relocs::Builder::from_base_relocs(&base_relocs);
+	assert_eq!(builder.build(), base_relocs.image());
+	builder.remove_range(0x3000..0x4000);
+	builder.insert(0x3fff, RelocType::HighLow);
+	let image = builder.build();
+	let rebuilt = pelite::base_relocs::BaseRelocs::parse(&image).unwrap();
+	assert_eq!(rebuilt.iter_blocks().count(), 2);
+	assert_eq!(rebuilt.iter().next(), Some((0x3fff, RelocType::HighLow)));
 }
 
 //----------------------------------------------------------------
@@ -187,9 +2121,12 @@ fn tls() {
 	let file = PeFile::from_bytes(&file_map).unwrap();
 	let tls = file.tls().unwrap();
 
-	assert_eq!(tls.callbacks().unwrap(), &[0x1800014C0]);
+	assert_eq!(tls.callbacks().unwrap().as_slice(), &[0x1800014C0]);
+	assert_eq!(tls.callbacks().unwrap().collect::<Vec<_>>(), [Ok(0x14C0)]);
 	assert_eq!(tls.raw_data().unwrap(), &[0, 0]);
 	assert_eq!(tls.slot().unwrap(), &0);
+	assert_eq!(tls.index(), Ok(0));
+	assert_eq!(tls.template_size(), Ok(2 + tls.zero_fill_size() as usize));
 }
 
 //----------------------------------------------------------------
@@ -201,6 +2138,14 @@ fn debug() {
 	let debug = file.debug().unwrap();
 
 	assert_eq!(debug.pdb_file_name().unwrap(), r"D:\Projects\pelite\proto\Demo\x64\Release\Demo.pdb");
+
+	let code_view = debug.iter().find_map(|dir| dir.entry().ok()?.as_code_view()).unwrap();
+	assert_eq!(code_view.pdb_name(), "Demo.pdb");
+	assert_eq!(code_view.symbol_server_key(), "73D8E3B03631446AB9079C724C47A8651");
+	assert_eq!(code_view.symbol_server_path().unwrap(), "Demo.pdb/73D8E3B03631446AB9079C724C47A8651/Demo.pdb");
+	assert_eq!(
+		code_view.symbol_server_url("https://msdl.microsoft.com/download/symbols/").unwrap(),
+		"https://msdl.microsoft.com/download/symbols/Demo.pdb/73D8E3B03631446AB9079C724C47A8651/Demo.pdb");
 }
 
 //----------------------------------------------------------------
@@ -214,6 +2159,360 @@ fn security() {
 	assert!(matches!(security, Err(Error::Null)));
 }
 
+// Encodes a DER value
+fn der(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+	let len: usize = parts.iter().map(|part| part.len()).sum();
+	let mut bytes = vec![tag];
+	match len {
+		0..=0x7F => bytes.push(len as u8),
+		0x80..=0xFF => bytes.extend_from_slice(&[0x81, len as u8]),
+		_ => bytes.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+	}
+	for part in parts {
+		bytes.extend_from_slice(part);
+	}
+	bytes
+}
+
+// Toy cryptography where the private key is the public key
+fn toy_digest(parts: &[&[u8]]) -> Vec<u8> {
+	use pelite::util::{Digest, Fnv1a};
+	let mut fnv = Fnv1a::default();
+	for part in parts {
+		fnv.update(part);
+	}
+	fnv.finish().to_le_bytes().to_vec()
+}
+
+fn toy_name(cn: &str) -> Vec<u8> {
+	use pelite::authenticode::oid;
+	der(0x30, &[&der(0x31, &[&der(0x30, &[&der(0x06, &[oid::COMMON_NAME.as_bytes()]), &der(0x0C, &[cn.as_bytes()])])])])
+}
+fn toy_algorithm(oid: pelite::authenticode::Oid) -> Vec<u8> {
+	der(0x30, &[&der(0x06, &[oid.as_bytes()])])
+}
+fn toy_public_key(cn: &str) -> Vec<u8> {
+	der(0x30, &[&toy_algorithm(pelite::authenticode::oid::RSA_ENCRYPTION), &der(0x03, &[&[0], cn.as_bytes()])])
+}
+// Certificates are valid from 2020 until 2030, authorities may only sign certificates and the others may only sign data
+fn toy_certificate(serial: u8, issuer: &str, subject: &str, ca: bool) -> Vec<u8> {
+	use pelite::authenticode::oid;
+	let basic_constraints = der(0x30, &[&der(0x06, &[oid::BASIC_CONSTRAINTS.as_bytes()]), &der(0x04, &[&der(0x30, &[&der(0x01, &[&[0xFF]])])])]);
+	let key_usage = der(0x30, &[&der(0x06, &[oid::KEY_USAGE.as_bytes()]), &der(0x04, &[&der(0x03, &[&[0x01], &[if ca { 0x04 } else { 0x80 }]])])]);
+	let tbs = der(0x30, &[
+		&der(0xA0, &[&der(0x02, &[&[2]])]),
+		&der(0x02, &[&[serial]]),
+		&toy_algorithm(oid::RSA_ENCRYPTION),
+		&toy_name(issuer),
+		&der(0x30, &[&der(0x17, &[b"200101000000Z"]), &der(0x17, &[b"300101000000Z"])]),
+		&toy_name(subject),
+		&toy_public_key(subject),
+		&der(0xA3, &[&der(0x30, &[if ca { &basic_constraints } else { &[] }, &key_usage])]),
+	]);
+	let signature = toy_digest(&[&toy_public_key(issuer), &tbs]);
+	der(0x30, &[&tbs, &toy_algorithm(oid::RSA_ENCRYPTION), &der(0x03, &[&[0], &signature])])
+}
+
+// Signs the image with a certificate chain of `Leaf` issued by `CA` issued by the returned `Root` certificate
+//
+// A stale `CA` certificate which may not sign certificates is embedded before the valid one
+// The `TSA` certificate issued by `Root` is embedded for legacy counter-signatures in the unauthenticated attributes
+fn toy_sign(image: &mut Vec<u8>, unauthenticated_attributes: &[u8]) -> Vec<u8> {
+	use pelite::authenticode::oid;
+	use pelite::image::IMAGE_DIRECTORY_ENTRY_SECURITY;
+
+
+	// The certificate table is appended to the file
+	image.resize((image.len() + 7) & !7, 0);
+	let file = PeFile::from_bytes(&image[..]).unwrap();
+	let base = image.as_ptr() as usize;
+	let check_sum = file.optional_header() as *const _ as usize - base + 64;
+	let datadir = &file.data_directory()[IMAGE_DIRECTORY_ENTRY_SECURITY] as *const _ as usize - base;
+	let image_digest = toy_digest(&[&image[..check_sum], &image[check_sum + 4..datadir], &image[datadir + 8..]]);
+
+	let indirect_data = der(0x30, &[
+		&der(0x30, &[&der(0x06, &[&[0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x02, 0x01, 0x0F]])]),
+		&der(0x30, &[&toy_algorithm(oid::SHA256), &der(0x04, &[&image_digest])]),
+	]);
+	let authenticated_attributes = der(0xA0, &[
+		&der(0x30, &[&der(0x06, &[oid::CONTENT_TYPE.as_bytes()]), &der(0x31, &[&der(0x06, &[oid::SPC_INDIRECT_DATA.as_bytes()])])]),
+		&der(0x30, &[&der(0x06, &[oid::MESSAGE_DIGEST.as_bytes()]), &der(0x31, &[&der(0x04, &[&toy_digest(&[&indirect_data[2..]])])])]),
+	]);
+	let mut signed_attributes = authenticated_attributes.clone();
+	signed_attributes[0] = 0x31;
+	let signer_info = der(0x30, &[
+		&der(0x02, &[&[1]]),
+		&der(0x30, &[&toy_name("CA"), &der(0x02, &[&[3]])]),
+		&toy_algorithm(oid::SHA256),
+		&authenticated_attributes,
+		&toy_algorithm(oid::RSA_ENCRYPTION),
+		&der(0x04, &[&toy_digest(&[&toy_public_key("Leaf"), &signed_attributes])]),
+		unauthenticated_attributes,
+	]);
+	let signed_data = der(0x30, &[
+		&der(0x06, &[oid::SIGNED_DATA.as_bytes()]),
+		&der(0xA0, &[&der(0x30, &[
+			&der(0x02, &[&[1]]),
+			&der(0x31, &[&toy_algorithm(oid::SHA256)]),
+			&der(0x30, &[&der(0x06, &[oid::SPC_INDIRECT_DATA.as_bytes()]), &der(0xA0, &[&indirect_data])]),
+			&der(0xA0, &[
+				&toy_certificate(3, "CA", "Leaf", false),
+				&toy_certificate(4, "Root", "CA", false),
+				&toy_certificate(2, "Root", "CA", true),
+				&toy_certificate(9, "Root", "TSA", false),
+			]),
+			&der(0x31, &[&signer_info]),
+		])]),
+	]);
+
+	let offset = image.len() as u32;
+	let size = (8 + signed_data.len() as u32 + 7) & !7;
+	image.extend_from_slice(&size.to_le_bytes());
+	image.extend_from_slice(&[0x00, 0x02, 0x02, 0x00]);
+	image.extend_from_slice(&signed_data);
+	image.resize(offset as usize + size as usize, 0);
+	image[datadir..datadir + 4].copy_from_slice(&offset.to_le_bytes());
+	image[datadir + 4..datadir + 8].copy_from_slice(&size.to_le_bytes());
+
+	toy_certificate(1, "Root", "Root", true)
+}
+
+#[test]
+fn authenticode() {
+	use pelite::authenticode::{key_usage, oid};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let mut image = file_map.as_ref().to_vec();
+	toy_sign(&mut image, &[]);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let security = file.security().unwrap();
+	let signed_data = security.signed_data().unwrap();
+	assert_eq!(signed_data.content_type(), oid::SPC_INDIRECT_DATA);
+	let (digest_algorithm, digest) = signed_data.indirect_data().unwrap();
+	assert_eq!(digest_algorithm, oid::SHA256);
+	let mut message = Vec::new();
+	security.authenticode_digest(|bytes| message.push(bytes));
+	assert_eq!(digest, &toy_digest(&message)[..]);
+
+	assert_eq!(signed_data.certificates().len(), 4);
+	let signer = signed_data.signer_info().unwrap();
+	assert_eq!(signer.digest_algorithm(), oid::SHA256);
+	assert_eq!(signer.message_digest(), Some(&toy_digest(&[signed_data.content()])[..]));
+	let certificate = signed_data.signer_certificate().unwrap();
+	assert_eq!(certificate.subject().common_name().as_deref(), Some("Leaf"));
+	assert_eq!(certificate.issuer().to_string(), "CN=CA");
+	assert_eq!(certificate.serial_number(), [3]);
+	assert_eq!(certificate.not_before().to_string(), "2020-01-01 00:00:00 UTC");
+	assert_eq!(certificate.not_after().to_string(), "2030-01-01 00:00:00 UTC");
+	assert!(!certificate.is_ca());
+	assert_eq!(certificate.key_usage(), Some(key_usage::DIGITAL_SIGNATURE));
+	let authority = signed_data.certificates().iter().find(|certificate| certificate.serial_number() == [2]).unwrap();
+	assert!(authority.is_ca());
+	assert_eq!(authority.key_usage(), Some(key_usage::KEY_CERT_SIGN));
+	assert_eq!(signed_data.timestamp().unwrap_err(), Error::Null);
+
+	// A certificate table overlapping the headers is rejected
+	let datadir = file.optional_header() as *const _ as usize - image.as_ptr() as usize + 112 + 4 * 8;
+	image[datadir..datadir + 8].copy_from_slice(&[8, 0, 0, 0, 8, 0, 0, 0]);
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file.security().err(), Some(Error::Bounds));
+	assert_eq!(pelite::pe64::analysis::hashes::authenticode_hash::<_, pelite::util::Fnv1a>(file).err(), Some(Error::Bounds));
+}
+
+#[test]
+fn authenticode_timestamp() {
+	use pelite::authenticode::{oid, TimestampKind};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let signing_time = |tag: u8, time: &[u8]| der(0x30, &[&der(0x06, &[oid::SIGNING_TIME.as_bytes()]), &der(0x31, &[&der(tag, &[time])])]);
+
+	// Legacy Authenticode counter-signature
+	let counter_signer = der(0x30, &[
+		&der(0x02, &[&[1]]),
+		&der(0x30, &[&toy_name("Root"), &der(0x02, &[&[9]])]),
+		&toy_algorithm(oid::SHA256),
+		&der(0xA0, &[&signing_time(0x17, b"200905105405Z")]),
+		&toy_algorithm(oid::RSA_ENCRYPTION),
+		&der(0x04, &[b"signature"]),
+	]);
+	let attribute = der(0xA1, &[&der(0x30, &[&der(0x06, &[oid::COUNTER_SIGNATURE.as_bytes()]), &der(0x31, &[&counter_signer])])]);
+	let mut image = file_map.as_ref().to_vec();
+	toy_sign(&mut image, &attribute);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let timestamp = file.security().unwrap().signed_data().unwrap().timestamp().unwrap();
+	assert_eq!(timestamp.kind, TimestampKind::Authenticode);
+	assert_eq!(timestamp.signing_time.to_string(), "2020-09-05 10:54:05 UTC");
+	assert_eq!(timestamp.certificate.unwrap().subject().to_string(), "CN=TSA");
+
+	// RFC 3161 timestamp token
+	let tst_info = der(0x30, &[
+		&der(0x02, &[&[1]]),
+		&der(0x06, &[&[0x2A, 0x03, 0x04]]),
+		&der(0x30, &[&toy_algorithm(oid::SHA256), &der(0x04, &[b"imprint"])]),
+		&der(0x02, &[&[0x42]]),
+		&der(0x18, &[b"20210102030405.5Z"]),
+	]);
+	let token_signer = der(0x30, &[
+		&der(0x02, &[&[1]]),
+		&der(0x30, &[&toy_name("Root"), &der(0x02, &[&[7]])]),
+		&toy_algorithm(oid::SHA256),
+		&toy_algorithm(oid::RSA_ENCRYPTION),
+		&der(0x04, &[b"signature"]),
+	]);
+	let token = der(0x30, &[
+		&der(0x06, &[oid::SIGNED_DATA.as_bytes()]),
+		&der(0xA0, &[&der(0x30, &[
+			&der(0x02, &[&[3]]),
+			&der(0x31, &[&toy_algorithm(oid::SHA256)]),
+			&der(0x30, &[&der(0x06, &[oid::TST_INFO.as_bytes()]), &der(0xA0, &[&der(0x04, &[&tst_info])])]),
+			&der(0xA0, &[&toy_certificate(7, "Root", "Time Stamping Authority", false)]),
+			&der(0x31, &[&token_signer]),
+		])]),
+	]);
+	let attribute = der(0xA1, &[&der(0x30, &[&der(0x06, &[oid::SPC_RFC3161_TIMESTAMP.as_bytes()]), &der(0x31, &[&token])])]);
+	let mut image = file_map.as_ref().to_vec();
+	toy_sign(&mut image, &attribute);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let timestamp = file.security().unwrap().signed_data().unwrap().timestamp().unwrap();
+	assert_eq!(timestamp.kind, TimestampKind::Rfc3161);
+	assert_eq!(timestamp.signing_time.to_string(), "2021-01-02 03:04:05 UTC");
+	assert_eq!(timestamp.signer_info.serial_number(), [7]);
+	assert_eq!(timestamp.certificate.unwrap().subject().common_name().as_deref(), Some("Time Stamping Authority"));
+}
+
+#[test]
+fn catalog_signing() {
+	use pelite::image::IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY;
+	use pelite::pe64::analysis::catalog::{catalog_hashes, catalog_signing, Evidence, Signing};
+	use pelite::util::Fnv1a;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let mut image = file_map.as_ref().to_vec();
+	image.resize((image.len() + 7) & !7, 0);
+	let file = PeFile::from_bytes(&image).unwrap();
+	let signing = catalog_signing(file);
+	assert_eq!(signing.status, Signing::Unsigned);
+	assert_eq!(signing.evidence, []);
+
+	// Signing the image changes the flat hash but not the Authenticode hash
+	let hashes = catalog_hashes::<_, Fnv1a>(file).unwrap();
+	let mut signed = image.clone();
+	toy_sign(&mut signed, &[]);
+	let signed_file = PeFile::from_bytes(&signed).unwrap();
+	let signed_hashes = catalog_hashes::<_, Fnv1a>(signed_file).unwrap();
+	assert_ne!(hashes.flat, signed_hashes.flat);
+	assert_eq!(hashes.authenticode, signed_hashes.authenticode);
+	assert_eq!(catalog_signing(signed_file).status, Signing::Embedded);
+
+	// Images which require a signature to load but have none are catalog signed
+	let dll_characteristics = &file.optional_header().DllCharacteristics as *const u16 as usize - image.as_ptr() as usize;
+	let value = file.optional_header().DllCharacteristics | IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY;
+	image[dll_characteristics..dll_characteristics + 2].copy_from_slice(&value.to_le_bytes());
+	let file = PeFile::from_bytes(&image).unwrap();
+	let signing = catalog_signing(file);
+	assert_eq!(signing.status, Signing::MaybeCatalogSigned);
+	assert_eq!(signing.evidence, [Evidence::ForceIntegrity]);
+	assert_eq!(catalog_hashes::<_, Fnv1a>(file).unwrap().authenticode, file.authenticode_hash::<Fnv1a>().unwrap());
+}
+
+#[cfg(feature = "verify")]
+#[test]
+fn authenticode_verify() {
+	use pelite::authenticode::{oid, Oid};
+	use pelite::authenticode::verify::{Verdict, Verifier};
+	use pelite::timestamp::UtcDateTime;
+
+	struct Toy;
+	impl Verifier for Toy {
+		fn digest(&self, algorithm: Oid<'_>, message: &[&[u8]]) -> Option<Vec<u8>> {
+			if algorithm == oid::SHA256 { Some(toy_digest(message)) } else { None }
+		}
+		fn verify_signature(&self, _algorithm: Oid<'_>, _digest_algorithm: Option<Oid<'_>>, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+			toy_digest(&[public_key, message]) == signature
+		}
+	}
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let mut image = file_map.as_ref().to_vec();
+	let root = toy_sign(&mut image, &[]);
+	let now = UtcDateTime::from_unix_time(1_700_000_000);
+	let later = UtcDateTime::from_unix_time(2_000_000_000);
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let verification = file.security().unwrap().verify(&Toy, &[b"garbage", &root], now);
+	assert_eq!(verification.verdict, Verdict::Trusted);
+	assert_eq!(verification.anchor, Some(1));
+	assert_eq!(verification.timestamp, None);
+	assert_eq!(verification.timestamp_verdict, None);
+	let chain: Vec<_> = verification.chain.iter().map(|certificate| certificate.serial_number()).collect();
+	assert_eq!(chain, [[3], [2]]);
+
+	let verification = file.security().unwrap().verify(&Toy, &[], now);
+	assert_eq!(verification.verdict, Verdict::Untrusted);
+	assert_eq!(verification.chain.len(), 2);
+
+	// The certificates expire without a timestamp
+	let verification = file.security().unwrap().verify(&Toy, &[&root], later);
+	assert_eq!(verification.verdict, Verdict::Expired);
+	assert_eq!(verification.chain.len(), 1);
+
+	// Legacy counter-signature by the TSA over the signature of the signer
+	let signature = file.security().unwrap().signed_data().unwrap().signer_info().unwrap().signature().to_vec();
+	let counter_sign = |signature: &[u8], time: &[u8]| {
+		let authenticated_attributes = der(0xA0, &[
+			&der(0x30, &[&der(0x06, &[oid::SIGNING_TIME.as_bytes()]), &der(0x31, &[&der(0x17, &[time])])]),
+			&der(0x30, &[&der(0x06, &[oid::MESSAGE_DIGEST.as_bytes()]), &der(0x31, &[&der(0x04, &[&toy_digest(&[signature])])])]),
+		]);
+		let mut signed_attributes = authenticated_attributes.clone();
+		signed_attributes[0] = 0x31;
+		let counter_signer = der(0x30, &[
+			&der(0x02, &[&[1]]),
+			&der(0x30, &[&toy_name("Root"), &der(0x02, &[&[9]])]),
+			&toy_algorithm(oid::SHA256),
+			&authenticated_attributes,
+			&toy_algorithm(oid::RSA_ENCRYPTION),
+			&der(0x04, &[&toy_digest(&[&toy_public_key("TSA"), &signed_attributes])]),
+		]);
+		let attribute = der(0xA1, &[&der(0x30, &[&der(0x06, &[oid::COUNTER_SIGNATURE.as_bytes()]), &der(0x31, &[&counter_signer])])]);
+		let mut image = file_map.as_ref().to_vec();
+		toy_sign(&mut image, &attribute);
+		image
+	};
+
+	// The certificates are checked at the time of the timestamp
+	let timestamped = counter_sign(&signature, b"250101000000Z");
+	let file = PeFile::from_bytes(&timestamped).unwrap();
+	let verification = file.security().unwrap().verify(&Toy, &[&root], later);
+	assert_eq!(verification.verdict, Verdict::Trusted);
+	assert_eq!(verification.timestamp_verdict, Some(Verdict::Trusted));
+	assert_eq!(verification.timestamp.map(|time| time.to_string()), Some(String::from("2025-01-01 00:00:00 UTC")));
+
+	// Untrusted timestamps are reported separately and the certificates are checked at the given time
+	let timestamped = counter_sign(&signature, b"310101000000Z");
+	let file = PeFile::from_bytes(&timestamped).unwrap();
+	let verification = file.security().unwrap().verify(&Toy, &[&root], now);
+	assert_eq!(verification.verdict, Verdict::Trusted);
+	assert_eq!(verification.timestamp_verdict, Some(Verdict::Expired));
+	assert_eq!(verification.timestamp, None);
+
+	// The timestamp must counter-sign this signature
+	let timestamped = counter_sign(b"signature", b"250101000000Z");
+	let file = PeFile::from_bytes(&timestamped).unwrap();
+	let verification = file.security().unwrap().verify(&Toy, &[&root], later);
+	assert_eq!(verification.verdict, Verdict::Expired);
+	assert_eq!(verification.timestamp_verdict, Some(Verdict::DigestMismatch));
+
+	// Modify the signed image
+	let file = PeFile::from_bytes(&image).unwrap();
+	let offset = file.section_headers().by_name(".text").unwrap().PointerToRawData as usize;
+	image[offset] ^= 1;
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file.security().unwrap().verify(&Toy, &[&root], now).verdict, Verdict::DigestMismatch);
+}
+
 //----------------------------------------------------------------
 
 #[test]
@@ -223,6 +2522,134 @@ fn exception() {
 	let exception = file.exception().unwrap();
 
 	assert_eq!(exception.functions().len(), 38);
+	assert_eq!(file.arm_exception().err(), Some(Error::Invalid));
+}
+
+#[test]
+fn exception_frames() {
+	use pelite::pe64::exception::UnwindOp;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let exception = file.exception().unwrap();
+
+	for function in exception.functions() {
+		let unwind_info = function.unwind_info().unwrap();
+		let frame = function.frame().unwrap();
+		assert_eq!(frame.prolog_len, unwind_info.size_of_prolog());
+		assert!(frame.prolog_len <= function.bytes().unwrap().len());
+		for op in unwind_info.unwind_ops() {
+			let (offset, op) = op.unwrap();
+			assert!(offset as usize <= frame.prolog_len);
+			// The prolog ends with the stack allocation, check it against the code
+			if let (true, UnwindOp::Alloc(size @ 0..=0x7f)) = (offset as usize == frame.prolog_len, op) {
+				let bytes = function.bytes().unwrap();
+				assert_eq!(&bytes[frame.prolog_len - 4..frame.prolog_len], &[0x48, 0x83, 0xEC, size as u8]);
+			}
+		}
+		assert_eq!(frame.size() % 8, 0);
+	}
+	assert!(exception.functions().any(|function| function.frame().unwrap().stack_alloc != 0));
+}
+
+#[test]
+fn exception_lookup() {
+	use pelite::image::{RUNTIME_FUNCTION_INDIRECT, UNW_FLAG_CHAININFO};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let exception = file.exception().unwrap();
+
+	// Every function owns the addresses it spans and nothing else
+	for function in exception.functions() {
+		let image = function.image();
+		assert_eq!(exception.lookup(image.BeginAddress).unwrap().image().BeginAddress, image.BeginAddress);
+		assert_eq!(exception.lookup(image.EndAddress - 1).unwrap().image().BeginAddress, image.BeginAddress);
+	}
+	assert_eq!(exception.lookup(0).err(), Some(Error::Null));
+
+	// Write a function table with an indirect and a chained function record in the resources section
+	let mut image = file_map.as_ref().to_vec();
+	let rva = 0x8000;
+	let offset = file.rva_to_file_offset(rva).unwrap();
+	let unwind_data = exception.image()[0].UnwindData;
+	let table = [
+		[0x1000, 0x1010, unwind_data],
+		[0x1010, 0x1020, rva | RUNTIME_FUNCTION_INDIRECT],
+		[0x1020, 0x1030, rva + 0x40],
+	];
+	for (i, &value) in table.iter().flatten().enumerate() {
+		image[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+	}
+	image[offset + 0x40..offset + 0x44].copy_from_slice(&[1 | UNW_FLAG_CHAININFO << 3, 0, 0, 0]);
+	image.copy_within(offset..offset + 12, offset + 0x44);
+	let datadir = file.dos_header().e_lfanew as usize + 24 + 112 + pelite::image::IMAGE_DIRECTORY_ENTRY_EXCEPTION * 8;
+	image[datadir..datadir + 4].copy_from_slice(&rva.to_le_bytes());
+	image[datadir + 4..datadir + 8].copy_from_slice(&36u32.to_le_bytes());
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	let exception = file.exception().unwrap();
+	for &pc in &[0x1008, 0x1018, 0x1028] {
+		let function = exception.lookup(pc).unwrap();
+		assert_eq!((function.image().BeginAddress, function.image().EndAddress), (0x1000, 0x1010));
+		assert_eq!(function.image().UnwindData, unwind_data);
+		assert!(function.unwind_info().is_ok());
+	}
+	assert_eq!(exception.lookup(0x1030).err(), Some(Error::Null));
+}
+
+#[test]
+fn arm_exception() {
+	use pelite::image::{IMAGE_DIRECTORY_ENTRY_EXCEPTION, IMAGE_FILE_MACHINE_ARM64};
+	use pelite::pe64::exception::{ArmEpilogScope, ArmUnwindData};
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	// Pretend to be an ARM64 image with a packed and an .xdata function entry
+	let mut image = file_map.as_ref().to_vec();
+	let e_lfanew = file.dos_header().e_lfanew as usize;
+	image[e_lfanew + 4..e_lfanew + 6].copy_from_slice(&IMAGE_FILE_MACHINE_ARM64.to_le_bytes());
+	let datadir = &file.data_directory()[IMAGE_DIRECTORY_ENTRY_EXCEPTION];
+	let size_offset = e_lfanew + 24 + 112 + IMAGE_DIRECTORY_ENTRY_EXCEPTION * 8 + 4;
+	image[size_offset..size_offset + 4].copy_from_slice(&16u32.to_le_bytes());
+	let xdata = datadir.VirtualAddress + 0x20;
+	let words = [
+		0x1000, 1 | 0x10 << 2,
+		0x1100, xdata,
+		0, 0, 0, 0,
+		0x20 | 1 << 20 | 1 << 22 | 1 << 27,
+		0x1c | 2 << 22,
+		0xe4e4_e1c8,
+		0x1234,
+	];
+	let offset = file.rva_to_file_offset(datadir.VirtualAddress).unwrap();
+	for (i, word) in words.iter().enumerate() {
+		image[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&u32::to_le_bytes(*word));
+	}
+
+	let file = PeFile::from_bytes(&image).unwrap();
+	assert_eq!(file.exception().err(), Some(Error::Invalid));
+	let exception = file.arm_exception().unwrap();
+	assert!(exception.is_arm64() && exception.check_sorted());
+	assert_eq!(exception.functions().len(), 2);
+
+	let packed = exception.lookup_function_entry(0x1010).unwrap();
+	assert_eq!((packed.begin_address(), packed.end_address()), (0x1000, Ok(0x1040)));
+	assert_eq!(exception.index_of(0x1040), Err(1));
+	assert_eq!(exception.index_of(0x0fff), Err(0));
+
+	let function = exception.lookup_function_entry(0x1170).unwrap();
+	assert_eq!(function.bytes().unwrap().len(), 0x80);
+	let xdata = match function.unwind_data().unwrap() {
+		ArmUnwindData::Xdata(xdata) => xdata,
+		ArmUnwindData::Packed(_) => panic!("expected xdata"),
+	};
+	assert_eq!((xdata.epilog_count(), xdata.code_words(), xdata.single_epilog()), (1, 1, false));
+	assert_eq!(xdata.epilog_scopes().collect::<Vec<_>>(), [ArmEpilogScope { start_offset: 0x70, start_index: 2, condition: 0xe }]);
+	assert_eq!(xdata.unwind_codes(), &[0xc8, 0xe1, 0xe4, 0xe4]);
+	assert_eq!(xdata.exception_handler(), Some(0x1234));
+	assert_eq!(exception.index_of(0x1180), Err(2));
 }
 
 //----------------------------------------------------------------
@@ -248,6 +2675,99 @@ fn scanner() {
 	assert!(!file.scanner().finds(pat, 0x1490..0x149F, &mut save));
 }
 
+#[test]
+fn map_file() {
+	use pelite::map_file::MapFile;
+
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+	let section_headers = file.section_headers().image();
+
+	// Write the map file the linker would for the exports
+	let mut text = format!(" Demo64\n\n Timestamp is {:08x} (now)\n\n", file.file_header().TimeDateStamp);
+	text.push_str("  Address         Publics by Value              Rva+Base               Lib:Object\n\n");
+	let rva_order = file.exports().unwrap().rva_order().unwrap();
+	for export in rva_order.iter() {
+		let section = section_headers.iter().position(|it| it.VirtualAddress <= export.rva && export.rva < it.VirtualAddress + it.VirtualSize).unwrap();
+		let name = export.name.unwrap().to_str().unwrap();
+		let va = file.rva_to_va(export.rva).unwrap();
+		text.push_str(&format!(" {:04x}:{:08x}       {:<26} {:016x} f   Demo64.obj\n", section + 1, export.rva - section_headers[section].VirtualAddress, name, va));
+	}
+
+	let map_file = MapFile::parse(&text);
+	assert_eq!(map_file.time_date_stamp, Some(file.file_header().TimeDateStamp));
+	assert_eq!(map_file.symbols.len(), rva_order.as_slice().len());
+	let symbols = map_file.correlate(section_headers);
+	for export in rva_order.iter() {
+		let symbol = symbols.find(export.rva).unwrap();
+		assert_eq!(symbol.va, file.rva_to_va(export.rva).unwrap());
+		assert_eq!(symbols.lookup(export.rva + 1).unwrap().1, 1);
+	}
+}
+
+#[test]
+fn batch_address_translation() {
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	// Every 16th rva of the image and beyond, including the headers, the zero filled and the unmapped parts
+	let size_of_image = file.optional_header().SizeOfImage;
+	let mut rvas: Vec<u32> = (0..size_of_image + 0x1000).step_by(16).collect();
+	let offsets = file.rvas_to_file_offsets(&rvas);
+	assert_eq!(offsets, rvas.iter().map(|&rva| file.rva_to_file_offset(rva)).collect::<Vec<_>>());
+	assert!(offsets.iter().any(|offset| *offset == Err(Error::Bounds)));
+
+	let file_offsets: Vec<usize> = (0..file_map.as_ref().len() + 0x1000).step_by(16).collect();
+	let back = file.file_offsets_to_rvas(&file_offsets);
+	assert_eq!(back, file_offsets.iter().map(|&file_offset| file.file_offset_to_rva(file_offset)).collect::<Vec<_>>());
+
+	// Unsorted input is still translated correctly
+	rvas.reverse();
+	assert_eq!(file.rvas_to_file_offsets(&rvas), rvas.iter().map(|&rva| file.rva_to_file_offset(rva)).collect::<Vec<_>>());
+}
+
+#[test]
+fn scanner_context() {
+	use pelite::pe64::exports::ExportKind;
+
+	let mut save = [0; 8];
+	let file_map = FileMap::open(FILE_NAME).unwrap();
+	let file = PeFile::from_bytes(&file_map).unwrap();
+
+	let pat = pelite::pattern!("4C8B41'? 4C2BC2 ????????? 0FB60A 420FB60402 2BC8 75% 8B15${'} 85 C9");
+	let mut matches = file.scanner().matches_code(pat);
+	let context = matches.next_context(&mut save, 0x20).unwrap();
+	assert!(matches.next_context(&mut save, 0x20).is_none());
+
+	assert_eq!(context.rva, 0x12F0);
+	assert_eq!(context.section, file.section_headers().iter().position(|section| section.name() == Ok(".text")));
+	// Leaf functions have no runtime function
+	assert_eq!(context.function, None);
+	assert_eq!(context.window_rva, 0x12D0);
+	assert_eq!(context.window, file.derva_slice::<u8>(0x12D0, 0x40).unwrap());
+	let text = context.to_string();
+	assert!(text.starts_with("0x12f0 in section #0"));
+	assert!(text.contains("\n000012f0: 4c 8b 41 "));
+
+	// Matches in functions
+	let function = file.exception().unwrap().image()[0];
+	let context = file.scanner().context(function.BeginAddress + 1, 0);
+	assert_eq!(context.function, Some(function.BeginAddress..function.EndAddress));
+	assert!(context.window.is_empty());
+	assert_eq!(context.to_string(), format!("{:#x} in section #0 in function {:#x}+0x1\n", function.BeginAddress + 1, function.BeginAddress));
+
+	// Matches in exports
+	let rva_order = file.exports().unwrap().rva_order().unwrap();
+	let export = rva_order.iter().find(|export| export.kind == ExportKind::Code).unwrap();
+	let context = file.scanner().context(export.rva + 2, 0);
+	assert_eq!(context.export, Some(*export));
+	assert!(context.to_string().contains(&format!(" near {}+0x2", export.name.unwrap())));
+
+	// The window is clamped to the start of the section
+	let context = file.scanner().context(0x1000, 0x20);
+	assert_eq!((context.window_rva, context.window.len()), (0x1000, 0x20));
+}
+
 //----------------------------------------------------------------
 
 #[cfg(windows)]
@@ -0,0 +1,294 @@
+/*!
+Randomized robustness tests.
+
+The `Pe` accessors and directory parsers promise not to panic on arbitrary input.
+These tests mutate known good binaries and walk everything the library can parse, any panic is a bug.
+
+The mutations are deterministic, a failure can be reproduced by running the test again.
+Run with `RUSTFLAGS="-C overflow-checks=on"` to also catch arithmetic overflow.
+*/
+
+use rand::prng::XorShiftRng;
+use rand::{Rng, SeedableRng};
+
+use pelite::resources::{Directory, Entry};
+use pelite::{Error, ParseOptions};
+
+const ITERATIONS: usize = 500;
+
+/// Interesting values to write over header and directory fields.
+const VALUES: [u32; 10] = [0, 1, 0x7f, 0x1000, 0xffff, 0x7fff_ffff, 0x8000_0000, 0xffff_fff0, 0xffff_ffff, 0x0100_0000];
+
+fn mutate(rng: &mut XorShiftRng, image: &mut Vec<u8>) {
+	let len = image.len();
+	for _ in 0..rng.gen_range(1, 8) {
+		// Prefer the headers where most of the interesting fields are
+		let offset = if rng.gen() { rng.gen_range(0, std::cmp::min(len, 0x400)) } else { rng.gen_range(0, len) };
+		match rng.gen_range(0, 3) {
+			0 => image[offset] = rng.gen(),
+			1 if offset + 4 <= len => {
+				let value = VALUES[rng.gen_range(0, VALUES.len())];
+				image[offset & !3..(offset & !3) + 4].copy_from_slice(&value.to_le_bytes());
+			},
+			_ => image[offset] ^= 1 << rng.gen_range(0, 8),
+		}
+	}
+	if rng.gen_range(0, 8) == 0 {
+		let new_len = rng.gen_range(0, len);
+		image.truncate(new_len);
+	}
+}
+
+// Corrupt resources may reference the same directory many times, limit the number of visited entries
+fn walk_resources(dir: Directory<'_>, budget: &mut u32) {
+	let _ = format!("{:?}", dir);
+	for entry in dir.entries() {
+		if *budget == 0 {
+			return;
+		}
+		*budget -= 1;
+		let _ = format!("{:?}", entry.name());
+		match entry.entry() {
+			Ok(Entry::DataEntry(data)) => {
+				let _ = data.bytes();
+			},
+			Ok(Entry::Directory(dir)) => walk_resources(dir, budget),
+			_ => (),
+		}
+	}
+}
+
+macro_rules! walk {
+	($pe:ident, $rng:expr, $file:expr) => {{
+		use pelite::$pe::{Pe, PeObject};
+		let file = $file;
+		let rng: &mut XorShiftRng = $rng;
+
+		// Address translation and raw access with arbitrary addresses
+		let image_base = file.image_base();
+		for _ in 0..32 {
+			let rva = if rng.gen() { rng.gen::<u32>() } else { VALUES[rng.gen_range(0, VALUES.len())] };
+			let size = [0, 1, 4, 0x1000, usize::MAX][rng.gen_range(0, 5)];
+			let _ = file.rva_to_file_offset(rva);
+			let _ = file.file_offset_to_rva(rva as usize);
+			let _ = file.rva_to_va(rva);
+			let _ = file.va_to_rva(image_base.wrapping_add(rva as _));
+			let _ = file.slice(rva, size, 1);
+			let _ = file.slice_bytes(rva);
+			let _ = file.read(image_base.wrapping_add(rva as _), size, 1);
+			let _ = file.derva::<u64>(rva);
+			let _ = file.derva_c_str(rva);
+			let _ = file.derva_slice::<u32>(rva, size);
+			let _ = file.derva_slice_f::<u16, _>(rva, |&word| word == 0);
+		}
+
+		// Batch address translation agrees with translating one by one
+		let mut rvas: Vec<u32> = (0..32).map(|_| if rng.gen() { rng.gen::<u32>() } else { VALUES[rng.gen_range(0, VALUES.len())] }).collect();
+		rvas.sort();
+		assert_eq!(file.rvas_to_file_offsets(&rvas), rvas.iter().map(|&rva| file.rva_to_file_offset(rva)).collect::<Vec<_>>());
+		let file_offsets: Vec<usize> = rvas.iter().map(|&rva| rva as usize).collect();
+		assert_eq!(file.file_offsets_to_rvas(&file_offsets), file_offsets.iter().map(|&file_offset| file.file_offset_to_rva(file_offset)).collect::<Vec<_>>());
+
+		let _ = file.headers().image();
+		let _ = format!("{}", file.validate());
+		let index = pelite::$pe::index::SectionIndex::new(file);
+		let indexed = pelite::$pe::index::Indexed::new(file, &index);
+		let _ = indexed.derva_c_str(file.optional_header().AddressOfEntryPoint);
+		let _ = format!("{}", pelite::$pe::analysis::mitigations::Mitigations::new(file));
+		let _ = pelite::$pe::analysis::toolchain::detect_toolchain(file);
+		let _ = pelite::$pe::analysis::packer::detect_packer(file);
+		let _ = pelite::$pe::analysis::caves::caves(file, 16);
+		let _ = pelite::$pe::analysis::footprint::footprint(file).pages();
+		let _ = pelite::$pe::analysis::entry_point::entry_point(file).is_suspicious();
+		let _ = file.what_is(file.optional_header().AddressOfEntryPoint);
+		let _ = file.layout().regions_at(file.optional_header().AddressOfEntryPoint).count();
+		let _ = pelite::$pe::analysis::kind::image_kind(file);
+		let _ = pelite::$pe::analysis::oep::find_oep(file);
+		let _ = (file.section_hashes::<pelite::util::Fnv1a>(), file.image_hash::<pelite::util::Fnv1a>());
+		let _ = pelite::$pe::analysis::hashes::function_hashes::<_, pelite::util::Fnv1a>(file);
+		let mut export_map = pelite::$pe::analysis::imprec::ExportMap::new();
+		let _ = export_map.add_module("self.dll", image_base, file);
+		if let Some(iat) = export_map.locate_iat(file) {
+			let _ = pelite::$pe::analysis::imprec::Imports::resolve(file, iat, &export_map).map(|imports| imports.build(!0));
+		}
+		let _ = pelite::$pe::analysis::overlay::overlay(file).map(|overlay| overlay.kind());
+		if let Ok(symbols) = pelite::$pe::symbols::ExportSymbols::new(file) {
+			use pelite::$pe::symbols::SymbolProvider;
+			let _ = symbols.symbolize(file.optional_header().AddressOfEntryPoint).map(|symbol| symbol.to_string());
+			let _ = symbols.resolve("main");
+		}
+		let _ = pelite::$pe::stubs::Stubs::new(file).map(|stubs| (stubs.to_rust(), stubs.to_c_header()));
+		if let Ok(symbols) = pelite::$pe::symbols::CoffSymbols::new(file) {
+			use pelite::$pe::symbols::SymbolProvider;
+			let _ = symbols.symbolize(file.optional_header().AddressOfEntryPoint);
+		}
+
+		let _ = (file.try_exports().is_ok(), file.try_imports().is_ok(), file.try_delay_imports().is_ok(), file.try_exception().is_ok());
+		if let Ok(exports) = file.exports() {
+			let _ = exports.dll_name();
+			let _ = exports.diff(&exports);
+			if let Ok(by) = exports.by() {
+				let _ = by.check_sorted();
+				for (name, export) in by.iter_names() {
+					let _ = (name, export);
+				}
+				let _ = by.name("main");
+				let _ = by.ordinal(by.ordinal_base().wrapping_add(1));
+				let _ = by.cfg_suppressed();
+			}
+		}
+		if let Ok(imports) = file.imports() {
+			for desc in imports {
+				let _ = desc.dll_name();
+				if let Ok(int) = desc.int() {
+					for _ in int {}
+				}
+				if let Ok(iat) = desc.iat() {
+					for _ in iat {}
+				}
+			}
+		}
+		if let Ok(delay_imports) = file.delay_imports() {
+			for desc in delay_imports {
+				let _ = (desc.dll_name(), desc.module_handle(), desc.bindings());
+			}
+			let _ = delay_imports.find_iat(file.optional_header().AddressOfEntryPoint);
+		}
+		if let Ok(bound_imports) = file.bound_imports() {
+			for desc in bound_imports {
+				let _ = desc.dll_name();
+				for _ in desc.forwarders() {}
+			}
+			let _ = bound_imports.stale(|_| Some(0));
+		}
+		if let Ok(iat) = file.iat() {
+			for _ in iat.iter() {}
+		}
+		if let Ok(base_relocs) = file.base_relocs() {
+			base_relocs.for_each(|_, _| ());
+			let _ = (base_relocs.iter().count(), base_relocs.contains(file.optional_header().AddressOfEntryPoint), base_relocs.range(0x1000..0x2000).count());
+		}
+		if let Ok(exception) = file.exception() {
+			let _ = exception.check_sorted();
+			for function in exception.functions().take(0x1000) {
+				let _ = function.bytes();
+				let _ = function.unwind_info().map(|unwind_info| format!("{:?}", unwind_info));
+			}
+		}
+		if let Ok(exception) = file.arm_exception() {
+			let _ = exception.check_sorted();
+			for function in exception.functions().take(0x1000) {
+				let _ = function.bytes();
+				let _ = function.unwind_data().map(|unwind_data| format!("{:?}", unwind_data));
+			}
+			let _ = exception.lookup_function_entry(file.optional_header().AddressOfEntryPoint);
+		}
+		if let Ok(debug) = file.debug() {
+			for dir in debug {
+				let _ = dir.data();
+				let _ = dir.entry().map(|entry| format!("{:?}", entry));
+			}
+		}
+		if let Ok(clr) = file.clr() {
+			let _ = format!("{:?}", clr);
+			let _ = clr.strong_name_signature();
+			let _ = clr.strong_name_digest(|_| ());
+			if let Ok(metadata) = clr.metadata().and_then(pelite::metadata::Metadata::new) {
+				let _ = format!("{:?}", metadata);
+				if let Ok(tables) = metadata.tables() {
+					let _ = tables.public_key();
+					for resource in tables.manifest_resources().take(0x100).flatten() {
+						let _ = clr.manifest_resource_data(resource.offset);
+					}
+				}
+			}
+			if let Ok(r2r) = clr.ready_to_run() {
+				let _ = r2r.import_sections();
+				let _ = r2r.runtime_functions();
+			}
+		}
+		if let Ok(load_config) = file.load_config() {
+			let _ = format!("{:?}", load_config);
+			let _ = load_config.se_handler_table();
+			let _ = load_config.guard_eh_continuation_table().map(|table| table.iter().count());
+			let _ = load_config.xfg_hashes().map(|xfg_hashes| xfg_hashes.count());
+			let _ = load_config.guard_address_taken_iat_entry_table().map(|table| table.iter().count());
+		}
+		if let Ok(tls) = file.tls() {
+			let _ = tls.raw_data();
+			let _ = tls.callbacks().map(|callbacks| callbacks.count());
+		}
+		if let Ok(security) = file.security() {
+			let _ = security.certificate_data();
+			security.authenticode_digest(|_| ());
+		}
+		let _ = pelite::$pe::analysis::hashes::authenticode_hash::<_, pelite::util::Fnv1a>(file);
+		if let Ok(rich_structure) = file.rich_structure() {
+			for _ in rich_structure.records() {}
+		}
+		if let Ok(resources) = file.resources() {
+			if let Ok(root) = resources.root() {
+				walk_resources(root, &mut 0x1000);
+			}
+			if let Ok(stats) = resources.stats() {
+				let _ = (stats.duplicate_size(), stats.to_string());
+			}
+			if let Ok(salvage) = resources.salvage() {
+				let _ = salvage.is_intact();
+			}
+			let _ = resources.to_string();
+		}
+	}};
+}
+
+macro_rules! fuzz_file {
+	($pe:ident, $rng:expr, $image:expr, $options:expr) => {{
+		use pelite::$pe::Pe;
+		if let Ok(file) = pelite::$pe::PeFile::from_bytes_with_options($image, $options) {
+			walk!($pe, $rng, file);
+			// Walk the mapped image as well, unless it's ridiculously large
+			if file.optional_header().SizeOfImage < 0x0100_0000 {
+				let view = file.to_view();
+				if let Ok(view) = pelite::$pe::PeView::from_bytes_with_options(&view, $options) {
+					walk!($pe, $rng, view);
+				}
+			}
+		}
+	}};
+}
+
+fn fuzz(file_name: &str, seed: u8) {
+	let original = std::fs::read(file_name).unwrap();
+	let mut rng = XorShiftRng::from_seed([seed.wrapping_add(1); 16]);
+	for _ in 0..ITERATIONS {
+		let mut image = original.clone();
+		mutate(&mut rng, &mut image);
+		for &options in &[ParseOptions::DEFAULT, ParseOptions::LENIENT] {
+			match pelite::pe64::PeFile::from_bytes_with_options(&image, options) {
+				Err(Error::PeMagic) => fuzz_file!(pe32, &mut rng, &image, options),
+				_ => fuzz_file!(pe64, &mut rng, &image, options),
+			}
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn demo64() {
+	fuzz("demo/Demo64.dll", 64);
+}
+
+#[test]
+fn demo32() {
+	fuzz("demo/Demo.dll", 32);
+}
+
+#[test]
+fn tiny() {
+	let mut paths: Vec<_> = std::fs::read_dir("tests/tiny").unwrap().map(|entry| entry.unwrap().path()).collect();
+	paths.sort();
+	for (i, path) in paths.iter().enumerate() {
+		fuzz(path.to_str().unwrap(), i as u8);
+	}
+}
@@ -48,6 +48,30 @@ fn tiny_c_1024() {
 	assert_eq!(sections[0].Characteristics, 0x60000020);
 }
 
+/*
+Images without sections have all their content in the headers
+*/
+
+#[test]
+fn tiny_c_1024_no_sections() {
+	let file_map = FileMap::open("tests/tiny/tiny.c.1024").unwrap();
+	let mut bytes = file_map.as_ref()[..0x400].to_vec();
+	let e_lfanew = PeFile::from_bytes(&bytes).unwrap().dos_header().e_lfanew as usize;
+	// NumberOfSections = 0, SizeOfHeaders = 0x400
+	bytes[e_lfanew + 6..e_lfanew + 8].copy_from_slice(&0u16.to_le_bytes());
+	bytes[e_lfanew + 24 + 60..e_lfanew + 24 + 64].copy_from_slice(&0x400u32.to_le_bytes());
+
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	assert_eq!(file.section_headers().as_slice().len(), 0);
+	assert_eq!(file.slice_bytes(0x200).unwrap(), &bytes[0x200..]);
+	assert_eq!(file.derva_copy::<u32>(0x3FC), Ok(u32::from_le_bytes([bytes[0x3FC], bytes[0x3FD], bytes[0x3FE], bytes[0x3FF]])));
+	assert_eq!(file.slice(0x3FC, 8, 1), Err(pelite::Error::Bounds));
+	assert_eq!(file.slice_bytes(0x1000), Err(pelite::Error::Bounds));
+	assert_eq!(file.rva_to_file_offset(0x200), Ok(0x200));
+	assert_eq!(file.file_offset_to_rva(0x200), Ok(0x200));
+	assert_eq!(&file.to_view()[..0x400], &bytes[..]);
+}
+
 /*
 Barebones C program with no CRT and decrease alignment
 */
@@ -82,6 +106,32 @@ fn tiny_c_468() {
 	assert_eq!(sections[0].Characteristics, 0x60000020);
 }
 
+/*
+Low alignment images are mapped as is, the section headers do not matter
+*/
+
+#[test]
+fn tiny_c_468_low_alignment() {
+	let file_map = FileMap::open("tests/tiny/tiny.c.468").unwrap();
+	let mut bytes = file_map.as_ref().to_vec();
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	let code = file.slice_bytes(0x1D0).unwrap().to_vec();
+	assert_eq!(code, &file_map.as_ref()[0x1D0..0x1D4]);
+
+	// Point the section's raw data somewhere else
+	let offset = file.dos_header().e_lfanew as usize + 24 + 0xE0 + 20;
+	bytes[offset..offset + 4].copy_from_slice(&0x100u32.to_le_bytes());
+	let file = PeFile::from_bytes(&bytes).unwrap();
+	assert_eq!(file.section_headers().as_slice()[0].PointerToRawData, 0x100);
+
+	assert_eq!(file.slice_bytes(0x1D0).unwrap(), &code[..]);
+	assert_eq!(file.rva_to_file_offset(0x1D2), Ok(0x1D2));
+	assert_eq!(file.file_offset_to_rva(0x1D2), Ok(0x1D2));
+	assert_eq!(file.rva_to_file_offset(0x1D4), Err(pelite::Error::Bounds));
+	assert_eq!(file.slice(0x1D0, 8, 1), Err(pelite::Error::Bounds));
+	assert_eq!(&file.to_view()[..], &bytes[..0x1D4]);
+}
+
 /*
 Removing the DOS stub
 */
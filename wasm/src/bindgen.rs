@@ -0,0 +1,75 @@
+/*!
+Bindings generated with wasm-bindgen.
+
+Build with `wasm-pack build --target web -- --features bindgen` and use the generated module:
+
+```js
+import init, { PeFile } from './pkg/pelite_wasm.js';
+await init();
+let pefile = new PeFile(new Uint8Array(arrayBuffer));
+console.log(pefile.is64, JSON.parse(pefile.headers()));
+pefile.free();
+```
+
+Structured data is returned as JSON strings, directories which are not present are returned as `undefined`.
+*/
+
+use pelite::Error::Null;
+use wasm_bindgen::prelude::*;
+
+fn json<T: serde::Serialize>(value: pelite::Result<T>) -> Result<Option<String>, JsError> {
+	match value {
+		Ok(value) => Ok(Some(serde_json::to_string(&value)?)),
+		Err(Null) => Ok(None),
+		Err(err) => Err(err.into()),
+	}
+}
+
+/// PE file parsed from a copy of a byte buffer.
+#[wasm_bindgen]
+pub struct PeFile {
+	image: Box<[u8]>,
+}
+
+#[wasm_bindgen]
+impl PeFile {
+	fn as_ref(&self) -> pelite::PeFile<'_> {
+		// Checked by the constructor
+		pelite::PeFile::from_bytes(&self.image).unwrap()
+	}
+	/// Parses the PE32 or PE32+ file.
+	#[wasm_bindgen(constructor)]
+	pub fn new(bytes: &[u8]) -> Result<PeFile, JsError> {
+		pelite::PeFile::from_bytes(bytes)?;
+		Ok(PeFile { image: bytes.into() })
+	}
+	/// Returns true if the file is PE32+.
+	#[wasm_bindgen(getter)]
+	pub fn is64(&self) -> bool {
+		matches!(self.as_ref(), pelite::Wrap::T64(_))
+	}
+	/// Gets the headers as JSON.
+	pub fn headers(&self) -> Result<String, JsError> {
+		Ok(serde_json::to_string(&self.as_ref().headers())?)
+	}
+	/// Gets the export directory as JSON.
+	pub fn exports(&self) -> Result<Option<String>, JsError> {
+		json(self.as_ref().exports())
+	}
+	/// Gets the import directory as JSON.
+	pub fn imports(&self) -> Result<Option<String>, JsError> {
+		json(self.as_ref().imports())
+	}
+	/// Gets the resources as JSON.
+	pub fn resources(&self) -> Result<Option<String>, JsError> {
+		json(self.as_ref().resources())
+	}
+	/// Gets the debug directory as JSON.
+	pub fn debug(&self) -> Result<Option<String>, JsError> {
+		json(self.as_ref().debug())
+	}
+	/// Copies the bytes at the rva.
+	pub fn slice(&self, rva: u32, len: usize) -> Result<Vec<u8>, JsError> {
+		Ok(self.as_ref().slice(rva, len, 1)?[..len].to_vec())
+	}
+}
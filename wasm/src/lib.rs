@@ -7,6 +7,8 @@ use pelite::Error::Null;
 use crate::wasm::*;
 
 mod base_relocs;
+#[cfg(feature = "bindgen")]
+pub mod bindgen;
 mod headers;
 mod resources;
 mod scanner;